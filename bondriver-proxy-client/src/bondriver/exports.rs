@@ -21,13 +21,16 @@ use crate::client::buffer::TS_PACKET_SIZE;
 use crate::client::{Connection, ConnectionConfig, ConnectionState};
 use crate::file_log;
 
+/// Value returned by GetCurSpace/GetCurChannel before a channel is set.
+const NO_CHANNEL: DWORD = 0xFFFFFFFF;
+
 /// Global state for the BonDriver instance.
 struct BonDriverState {
     /// Connection to the proxy server.
     connection: Arc<Connection>,
-    /// Current tuning space.
+    /// Tuning space of the last successful channel change.
     cur_space: u32,
-    /// Current channel.
+    /// Channel of the last successful channel change.
     cur_channel: u32,
     /// Cached tuner name.
     tuner_name: Option<Vec<u16>>,
@@ -44,8 +47,8 @@ impl BonDriverState {
     fn new(config: ConnectionConfig) -> Self {
         Self {
             connection: Connection::new(config),
-            cur_space: 0xFFFFFFFF,
-            cur_channel: 0xFFFFFFFF,
+            cur_space: NO_CHANNEL,
+            cur_channel: NO_CHANNEL,
             tuner_name: None,
             space_names: Vec::new(),
             channel_names: Vec::new(),
@@ -77,7 +80,8 @@ fn get_instance() -> &'static Mutex<BonDriverState> {
         file_log!(info, "get_instance: Loading configuration...");
         let config = load_config();
         info!("BonDriver_NetworkProxy initialized");
-        file_log!(info, "get_instance: Server address: {}", config.server_addr);
+        file_log!(info, "get_instance: Server address: {}, fallback: {:?}", config.server_addr, config.fallback_servers);
+        file_log!(info, "get_instance: TS buffer size: {} bytes", config.buffer_size);
         debug!("Server: {}", config.server_addr);
 
         file_log!(info, "get_instance: Creating BonDriverState...");
@@ -114,7 +118,7 @@ pub unsafe extern "system" fn open_tuner(_this: *mut c_void) -> BOOL {
             error!("Failed to connect to server");
             return 0;
         }
-        file_log!(info, "OpenTuner: Connected to server");
+        file_log!(info, "OpenTuner: Connected to server {}", state.connection.active_server());
     }
 
     // Open tuner
@@ -126,6 +130,18 @@ pub unsafe extern "system" fn open_tuner(_this: *mut c_void) -> BOOL {
     } else {
         file_log!(error, "OpenTuner: Failed to open tuner");
         error!("Failed to open tuner");
+        // Name what the server does offer, to help fix the configured tuner
+        if let Some(entries) = state.connection.list_tuners() {
+            for entry in entries {
+                file_log!(
+                    info,
+                    "OpenTuner: Server offers {:?} {} ({} free)",
+                    entry.kind,
+                    entry.name,
+                    entry.available_instances
+                );
+            }
+        }
         0
     }
 }
@@ -134,8 +150,10 @@ pub unsafe extern "system" fn open_tuner(_this: *mut c_void) -> BOOL {
 pub unsafe extern "system" fn close_tuner(_this: *mut c_void) {
     file_log!(info, "CloseTuner called");
     debug!("CloseTuner called");
-    let state = get_instance().lock();
+    let mut state = get_instance().lock();
     state.connection.close_tuner();
+    state.cur_space = NO_CHANNEL;
+    state.cur_channel = NO_CHANNEL;
     file_log!(info, "CloseTuner: Tuner closed");
     info!("Tuner closed");
 }
@@ -520,7 +538,7 @@ pub unsafe extern "system" fn is_tuner_opening(_this: *mut c_void) -> BOOL {
     trace!("IsTunerOpening called");
     let state = get_instance().lock();
     match state.connection.state() {
-        ConnectionState::TunerOpen | ConnectionState::Streaming => 1,
+        ConnectionState::TunerOpen | ConnectionState::Streaming | ConnectionState::Reconnecting => 1,
         _ => 0,
     }
 }
@@ -657,14 +675,14 @@ pub unsafe extern "system" fn set_channel2(
     }
 }
 
-/// Get current tuning space.
+/// Get current tuning space (`NO_CHANNEL` until a channel is set).
 pub unsafe extern "system" fn get_cur_space(_this: *mut c_void) -> DWORD {
     trace!("GetCurSpace called");
     let state = get_instance().lock();
     state.cur_space
 }
 
-/// Get current channel.
+/// Get current channel (`NO_CHANNEL` until a channel is set).
 pub unsafe extern "system" fn get_cur_channel(_this: *mut c_void) -> DWORD {
     trace!("GetCurChannel called");
     let state = get_instance().lock();
@@ -676,18 +694,42 @@ pub unsafe extern "system" fn get_cur_channel(_this: *mut c_void) -> DWORD {
 // =============================================================================
 
 /// Get total device count.
+///
+/// With the tuner open this is the `max_instances` of the server-side
+/// driver(s) behind it; otherwise 1.
 pub unsafe extern "system" fn get_total_device_num(_this: *mut c_void) -> DWORD {
     debug!("GetTotalDeviceNum called");
-    // Return 1 as we only support one device through the proxy
-    1
+    let state = get_instance().lock();
+    match state.connection.state() {
+        ConnectionState::TunerOpen | ConnectionState::Streaming => {
+            match state.connection.get_tuner_capacity() {
+                Some(drivers) if !drivers.is_empty() => {
+                    drivers.iter().map(|d| DWORD::from(d.max_instances)).sum()
+                }
+                _ => 1,
+            }
+        }
+        _ => 1,
+    }
 }
 
 /// Get active device count.
+///
+/// With the tuner open this counts every running instance of the
+/// server-side driver(s), including those of other clients.
 pub unsafe extern "system" fn get_active_device_num(_this: *mut c_void) -> DWORD {
     debug!("GetActiveDeviceNum called");
     let state = get_instance().lock();
     match state.connection.state() {
-        ConnectionState::TunerOpen | ConnectionState::Streaming => 1,
+        ConnectionState::TunerOpen | ConnectionState::Streaming => {
+            match state.connection.get_tuner_capacity() {
+                Some(drivers) if !drivers.is_empty() => {
+                    drivers.iter().map(|d| DWORD::from(d.running)).sum::<DWORD>().max(1)
+                }
+                _ => 1,
+            }
+        }
+        ConnectionState::Reconnecting => 1,
         _ => 0,
     }
 }
@@ -1,6 +1,6 @@
 //! Lock-free ring buffer for TS data.
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Condvar, Mutex};
 use std::ptr;
 use std::time::Duration;
@@ -8,8 +8,28 @@ use std::time::Duration;
 /// TS packet size.
 pub const TS_PACKET_SIZE: usize = 188;
 
-/// Size of the ring buffer (100 MB).
-pub const RING_BUFFER_SIZE: usize = TS_PACKET_SIZE * 1024 * 100;
+/// Default size of the ring buffer (32768 TS packets, about 6 MB).
+///
+/// About 1.6 s of a 30 Mbps stream, enough to ride out host stalls of a few
+/// hundred milliseconds. The buffer is allocated up front, so every extra MB
+/// costs resident memory per loaded BonDriver instance.
+pub const DEFAULT_RING_BUFFER_SIZE: usize = TS_PACKET_SIZE * 32 * 1024;
+
+/// Smallest accepted ring buffer size (64 TS packets).
+pub const MIN_RING_BUFFER_SIZE: usize = TS_PACKET_SIZE * 64;
+
+/// Snapshot of ring buffer health.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferStats {
+    /// Capacity in bytes.
+    pub capacity: usize,
+    /// Bytes currently waiting to be read.
+    pub available: usize,
+    /// Bytes discarded because the reader fell behind.
+    pub dropped_bytes: u64,
+    /// Number of writes that had to discard data.
+    pub overflow_events: u64,
+}
 
 /// A lock-free ring buffer for TS data.
 ///
@@ -22,6 +42,8 @@ pub const RING_BUFFER_SIZE: usize = TS_PACKET_SIZE * 1024 * 100;
 pub struct TsRingBuffer {
     /// The underlying buffer (heap-allocated).
     buffer: Box<[u8]>,
+    /// Buffer length in bytes (a multiple of the TS packet size).
+    capacity: usize,
     /// Write position (updated by receiver).
     write_pos: AtomicUsize,
     /// Read position (updated by GetTsStream).
@@ -31,20 +53,51 @@ pub struct TsRingBuffer {
     data_available: Condvar,
     /// Mutex paired with data_available (holds no meaningful state).
     data_mutex: Mutex<()>,
+    /// Bytes discarded on overflow.
+    dropped_bytes: AtomicU64,
+    /// Writes that discarded data on overflow.
+    overflow_events: AtomicU64,
 }
 
 #[allow(dead_code)]
 impl TsRingBuffer {
-    /// Create a new ring buffer.
+    /// Create a new ring buffer with the default size.
     pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_RING_BUFFER_SIZE)
+    }
+
+    /// Create a ring buffer of `capacity` bytes.
+    ///
+    /// The size is rounded down to a whole number of TS packets and raised to
+    /// at least `MIN_RING_BUFFER_SIZE`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = (capacity / TS_PACKET_SIZE * TS_PACKET_SIZE).max(MIN_RING_BUFFER_SIZE);
         // Allocate directly on heap to avoid stack overflow
-        let buffer = vec![0u8; RING_BUFFER_SIZE].into_boxed_slice();
+        let buffer = vec![0u8; capacity].into_boxed_slice();
         Self {
             buffer,
+            capacity,
             write_pos: AtomicUsize::new(0),
             read_pos: AtomicUsize::new(0),
             data_available: Condvar::new(),
             data_mutex: Mutex::new(()),
+            dropped_bytes: AtomicU64::new(0),
+            overflow_events: AtomicU64::new(0),
+        }
+    }
+
+    /// Buffer capacity in bytes.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Current fill level and overflow counters.
+    pub fn stats(&self) -> BufferStats {
+        BufferStats {
+            capacity: self.capacity,
+            available: self.available(),
+            dropped_bytes: self.dropped_bytes.load(Ordering::Relaxed),
+            overflow_events: self.overflow_events.load(Ordering::Relaxed),
         }
     }
 
@@ -55,13 +108,13 @@ impl TsRingBuffer {
         if write >= read {
             write - read
         } else {
-            RING_BUFFER_SIZE - read + write
+            self.capacity - read + write
         }
     }
 
     /// Get the number of bytes of free space for writing.
     pub fn free_space(&self) -> usize {
-        RING_BUFFER_SIZE - self.available() - 1 // -1 to distinguish full from empty
+        self.capacity - self.available() - 1 // -1 to distinguish full from empty
     }
 
     /// Write data to the buffer.
@@ -73,23 +126,26 @@ impl TsRingBuffer {
     /// when multi-hop proxy chains produced bursts faster than the consumer
     /// could drain.
     ///
+    /// Discarded bytes are counted in `stats()`.
+    ///
     /// Returns the number of bytes written (always == data.len() unless
-    /// data.len() >= RING_BUFFER_SIZE - 1, in which case it is capped).
+    /// data.len() >= capacity - 1, in which case it is capped).
     pub fn write(&self, data: &[u8]) -> usize {
         let write = self.write_pos.load(Ordering::Acquire);
         let read = self.read_pos.load(Ordering::Acquire);
 
         let free = if write >= read {
-            RING_BUFFER_SIZE - write + read - 1
+            self.capacity - write + read - 1
         } else {
             read - write - 1
         };
 
         // Cap to maximum writable size (buffer size - 1).
-        let to_write = data.len().min(RING_BUFFER_SIZE - 1);
+        let to_write = data.len().min(self.capacity - 1);
         if to_write == 0 {
             return 0;
         }
+        let mut dropped = (data.len() - to_write) as u64;
 
         // If not enough space, advance the read pointer to discard oldest data.
         // Round up to a TS packet boundary (188 bytes) so the consumer never
@@ -97,12 +153,17 @@ impl TsRingBuffer {
         if to_write > free {
             let advance = to_write - free;
             let advance = ((advance + TS_PACKET_SIZE - 1) / TS_PACKET_SIZE) * TS_PACKET_SIZE;
-            let new_read = (read + advance) % RING_BUFFER_SIZE;
+            let new_read = (read + advance) % self.capacity;
             self.read_pos.store(new_read, Ordering::Release);
+            dropped += advance as u64;
+        }
+        if dropped > 0 {
+            self.dropped_bytes.fetch_add(dropped, Ordering::Relaxed);
+            self.overflow_events.fetch_add(1, Ordering::Relaxed);
         }
 
         let dst = self.buffer.as_ptr() as *mut u8; // 生ポインタ（&mut を作らない）
-        let first_chunk = to_write.min(RING_BUFFER_SIZE - write);
+        let first_chunk = to_write.min(self.capacity - write);
 
         unsafe {
             ptr::copy_nonoverlapping(data.as_ptr(), dst.add(write), first_chunk);
@@ -112,7 +173,7 @@ impl TsRingBuffer {
             }
         }
 
-        let new_write = (write + to_write) % RING_BUFFER_SIZE;
+        let new_write = (write + to_write) % self.capacity;
         self.write_pos.store(new_write, Ordering::Release);
 
         // Notify any thread blocked in wait_data().
@@ -176,7 +237,7 @@ impl TsRingBuffer {
         let available = if write >= read {
             write - read
         } else {
-            RING_BUFFER_SIZE - read
+            self.capacity - read
         };
 
         let to_read = max_len.min(available);
@@ -200,7 +261,7 @@ impl TsRingBuffer {
         let available = if write >= read {
             write - read
         } else {
-            RING_BUFFER_SIZE - read + write
+            self.capacity - read + write
         };
 
         let to_read = dest.len().min(available);
@@ -211,7 +272,7 @@ impl TsRingBuffer {
 
 
         // Copy data, handling wrap-around
-        let first_chunk = to_read.min(RING_BUFFER_SIZE - read);
+        let first_chunk = to_read.min(self.capacity - read);
         dest[..first_chunk].copy_from_slice(&self.buffer[read..read + first_chunk]);
 
         if first_chunk < to_read {
@@ -226,7 +287,7 @@ impl TsRingBuffer {
     /// Consume bytes from the read position.
     pub fn consume(&self, count: usize) {
         let read = self.read_pos.load(Ordering::Acquire);
-        let new_read = (read + count) % RING_BUFFER_SIZE;
+        let new_read = (read + count) % self.capacity;
         self.read_pos.store(new_read, Ordering::Release);
     }
 
@@ -278,7 +339,7 @@ mod tests {
 
         // Fill most of the buffer in chunks to avoid stack issues
         let chunk_size = 64 * 1024; // 64KB chunks
-        let total_to_write = RING_BUFFER_SIZE - 100;
+        let total_to_write = buffer.capacity() - 100;
         let chunk = vec![0xFFu8; chunk_size];
 
         let mut written_total = 0;
@@ -288,7 +349,7 @@ mod tests {
             written_total += to_write;
         }
 
-        buffer.consume(RING_BUFFER_SIZE - 200);
+        buffer.consume(buffer.capacity() - 200);
 
         // Write data that wraps around
         let wrap_data = vec![0x47u8; 300];
@@ -296,6 +357,29 @@ mod tests {
         assert!(written > 0);
     }
 
+    #[test]
+    fn test_with_capacity_rounds_to_packets() {
+        assert_eq!(TsRingBuffer::with_capacity(188 * 100 + 50).capacity(), 188 * 100);
+        assert_eq!(TsRingBuffer::with_capacity(0).capacity(), MIN_RING_BUFFER_SIZE);
+    }
+
+    #[test]
+    fn test_overflow_stats() {
+        let buffer = TsRingBuffer::with_capacity(MIN_RING_BUFFER_SIZE);
+        let packets = vec![0x47u8; TS_PACKET_SIZE * 48];
+
+        buffer.write(&packets);
+        assert_eq!(buffer.stats().dropped_bytes, 0);
+
+        // The reader fell behind: the oldest whole packets are discarded
+        buffer.write(&packets);
+        let stats = buffer.stats();
+        assert_eq!(stats.overflow_events, 1);
+        assert_eq!(stats.dropped_bytes % TS_PACKET_SIZE as u64, 0);
+        assert!(stats.dropped_bytes >= (TS_PACKET_SIZE * 32) as u64);
+        assert_eq!(stats.available + stats.dropped_bytes as usize, TS_PACKET_SIZE * 96);
+    }
+
     #[test]
     fn test_clear() {
         let buffer = TsRingBuffer::new();
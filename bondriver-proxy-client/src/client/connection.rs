@@ -1,9 +1,10 @@
 //! TCP connection management for the BonDriver client.
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use log::{debug, error, info, trace, warn};
 use parking_lot::Mutex;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
@@ -11,11 +12,14 @@ use tokio::net::TcpStream;
 use tokio::sync::mpsc;
 
 use recisdb_protocol::{
-    decode_header, decode_server_message, encode_client_message, ClientMessage,
-    MessageType, ServerMessage, HEADER_SIZE, PROTOCOL_VERSION,
+    decode_compressed_ts_payload, decode_header, decode_server_message_with,
+    encode_client_message_with, strip_frame_crc, supported_features, ClientMessage,
+    ErrorCode, FrameOptions, MessageType, ProtocolError, ServerMessage, TunerCapacity, TunerListEntry,
+    FEATURE_FRAME_CRC32, FEATURE_SERVER_PING, FEATURE_TS_COMPRESSION, FEATURE_TUNER_STATUS, HEADER_SIZE,
+    PROTOCOL_VERSION,
 };
 
-use crate::client::buffer::TsRingBuffer;
+use crate::client::buffer::{TsRingBuffer, DEFAULT_RING_BUFFER_SIZE};
 use crate::file_log;
 
 #[cfg(feature = "tls")]
@@ -37,6 +41,8 @@ pub enum ConnectionState {
     Connected,
     TunerOpen,
     Streaming,
+    /// The link dropped; re-establishing it and replaying the session.
+    Reconnecting,
     Error,
 }
 
@@ -61,6 +67,29 @@ pub struct ConnectionConfig {
     /// When true, the server sends only the selected service's TS packets
     /// instead of the entire transport stream.
     pub single_service: bool,
+    /// Ask the server for zstd-compressed TS frames (useful over WAN links).
+    pub compression: bool,
+    /// Shared secret sent in Hello when the server requires authentication.
+    pub auth_token: Option<String>,
+    /// Reconnect and resume the session when the link drops.
+    pub reconnect: bool,
+    /// Upper bound of the exponential reconnect backoff.
+    pub reconnect_max_delay: Duration,
+    /// Backup servers tried in order after `server_addr`.
+    pub fallback_servers: Vec<String>,
+    /// TS ring buffer size in bytes.
+    ///
+    /// Allocated up front for each loaded driver. A larger buffer survives
+    /// longer host stalls before the oldest packets are dropped.
+    pub buffer_size: usize,
+}
+
+impl ConnectionConfig {
+    /// All configured servers, primary first.
+    pub fn servers(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.server_addr.as_str())
+            .chain(self.fallback_servers.iter().map(String::as_str))
+    }
 }
 
 impl Default for ConnectionConfig {
@@ -77,6 +106,44 @@ impl Default for ConnectionConfig {
             #[cfg(feature = "tls")]
             tls_ca_cert: None,
             single_service: false,
+            compression: false,
+            auth_token: None,
+            reconnect: true,
+            reconnect_max_delay: Duration::from_secs(30),
+            fallback_servers: Vec::new(),
+            buffer_size: DEFAULT_RING_BUFFER_SIZE,
+        }
+    }
+}
+
+/// First reconnect delay; doubled on every failed attempt.
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// Delay before reconnect attempt `attempt` (0-based), capped at `max`.
+fn reconnect_delay(attempt: u32, max: Duration) -> Duration {
+    RECONNECT_INITIAL_DELAY
+        .saturating_mul(1u32 << attempt.min(16))
+        .min(max)
+}
+
+/// Session setup replayed on a new link after a disconnect.
+#[derive(Debug, Clone, Default)]
+struct SessionReplay {
+    tuner_open: bool,
+    /// Last successful SetChannel / SetChannelSpace request.
+    channel: Option<ClientMessage>,
+    streaming: bool,
+}
+
+impl SessionReplay {
+    /// State the connection is in once this session has been replayed.
+    fn resumed_state(&self) -> ConnectionState {
+        if self.streaming {
+            ConnectionState::Streaming
+        } else if self.tuner_open {
+            ConnectionState::TunerOpen
+        } else {
+            ConnectionState::Connected
         }
     }
 }
@@ -103,23 +170,64 @@ pub struct Connection {
     /// Cached signal level and the time it was last fetched.
     /// TTL = 2 s — avoids a network round-trip on every TVTest poll.
     signal_level: Mutex<(f32, Option<std::time::Instant>)>,
+    /// TsData frames arrive in the compressed format (set from HelloAck).
+    ts_compression: AtomicBool,
+    /// Frames carry a CRC32 trailer (set from HelloAck).
+    frame_crc32: AtomicBool,
+    /// Session setup to replay after a reconnect.
+    replay: Mutex<SessionReplay>,
+    /// Set by `disconnect()` so the connection task does not reconnect.
+    closing: AtomicBool,
+    /// Address of the server currently serving this connection.
+    active_server: Mutex<String>,
 }
 
 impl Connection {
     /// Create a new connection.
     pub fn new(config: ConnectionConfig) -> Arc<Self> {
+        let buffer = Arc::new(TsRingBuffer::with_capacity(config.buffer_size));
         Arc::new(Self {
             config,
             state: Mutex::new(ConnectionState::Disconnected),
-            buffer: Arc::new(TsRingBuffer::new()),
+            buffer,
             request_tx: Mutex::new(None),
             response_rx: Mutex::new(None),
             runtime: Mutex::new(None),
             bondriver_version: Mutex::new(0),
             signal_level: Mutex::new((0.0, None)),
+            ts_compression: AtomicBool::new(false),
+            frame_crc32: AtomicBool::new(false),
+            replay: Mutex::new(SessionReplay::default()),
+            closing: AtomicBool::new(false),
+            active_server: Mutex::new(String::new()),
         })
     }
 
+    /// Feature bits to request in Hello.
+    fn requested_features(&self) -> u32 {
+        let mut features = FEATURE_FRAME_CRC32 | FEATURE_TUNER_STATUS | FEATURE_SERVER_PING;
+        if self.config.compression {
+            features |= FEATURE_TS_COMPRESSION;
+        }
+        features & supported_features()
+    }
+
+    /// Hello for a new link.
+    fn hello_message(&self) -> ClientMessage {
+        ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+            features: self.requested_features(),
+            auth_token: self.config.auth_token.clone(),
+        }
+    }
+
+    /// Framing options currently in effect.
+    fn frame_options(&self) -> FrameOptions {
+        FrameOptions {
+            crc32: self.frame_crc32.load(Ordering::Acquire),
+        }
+    }
+
     /// Get the current state.
     pub fn state(&self) -> ConnectionState {
         *self.state.lock()
@@ -147,12 +255,17 @@ impl Connection {
         self.config.client_exclusive
     }
 
+    /// Address of the server in use (empty before the first connect).
+    pub fn active_server(&self) -> String {
+        self.active_server.lock().clone()
+    }
+
     /// Get a reference to the ring buffer.
     pub fn buffer(&self) -> &Arc<TsRingBuffer> {
         &self.buffer
     }
 
-    /// Connect to the server.
+    /// Connect to the first configured server that accepts the handshake.
     pub fn connect(self: &Arc<Self>) -> bool {
         file_log!(info, "Connection::connect() called");
 
@@ -164,7 +277,31 @@ impl Connection {
         }
         *state = ConnectionState::Connecting;
         drop(state);
+        self.closing.store(false, Ordering::Release);
+
+        let servers: Vec<String> = self.config.servers().map(str::to_string).collect();
+        for (i, addr) in servers.iter().enumerate() {
+            if self.connect_to(addr) {
+                file_log!(info, "connect: Connected successfully, active server {}", addr);
+                info!("Active server: {}", addr);
+                *self.state.lock() = ConnectionState::Connected;
+                return true;
+            }
+
+            self.shutdown_link();
+            if i + 1 < servers.len() {
+                file_log!(warn, "connect: Server {} unavailable, trying {}", addr, servers[i + 1]);
+                warn!("Server {} unavailable, trying {}", addr, servers[i + 1]);
+            }
+            *self.state.lock() = ConnectionState::Connecting;
+        }
 
+        *self.state.lock() = ConnectionState::Error;
+        false
+    }
+
+    /// Connect to one server and perform the handshake.
+    fn connect_to(self: &Arc<Self>, addr: &str) -> bool {
         // Create runtime
         file_log!(info, "connect: Creating tokio runtime...");
         let runtime = match tokio::runtime::Builder::new_multi_thread()
@@ -179,7 +316,6 @@ impl Connection {
             Err(e) => {
                 file_log!(error, "connect: Failed to create runtime: {}", e);
                 error!("Failed to create runtime: {}", e);
-                *self.state.lock() = ConnectionState::Error;
                 return false;
             }
         };
@@ -192,12 +328,13 @@ impl Connection {
 
         *self.request_tx.lock() = Some(req_tx);
         *self.response_rx.lock() = Some(resp_rx);
+        *self.active_server.lock() = addr.to_string();
 
         let conn = Arc::clone(self);
         let config = self.config.clone();
         let buffer = Arc::clone(&self.buffer);
 
-        file_log!(info, "connect: Spawning connection task to {}", config.server_addr);
+        file_log!(info, "connect: Spawning connection task to {}", addr);
         runtime.spawn(async move {
             file_log!(info, "connect: Connection task started");
             if let Err(e) = connection_task(conn, config, req_rx, resp_tx, buffer).await {
@@ -218,9 +355,8 @@ impl Connection {
         // Perform handshake with timeout
         file_log!(info, "connect: Sending hello...");
         if !self.send_hello() {
-            file_log!(error, "connect: Handshake failed");
-            error!("Handshake failed");
-            *self.state.lock() = ConnectionState::Error;
+            file_log!(error, "connect: Handshake with {} failed", addr);
+            error!("Handshake with {} failed", addr);
             return false;
         }
 
@@ -239,13 +375,27 @@ impl Connection {
             }
         }
 
-        file_log!(info, "connect: Connected successfully");
-        *self.state.lock() = ConnectionState::Connected;
         true
     }
 
     /// Disconnect from the server.
     pub fn disconnect(&self) {
+        self.closing.store(true, Ordering::Release);
+        *self.replay.lock() = SessionReplay::default();
+
+        self.shutdown_link();
+
+        let stats = self.buffer.stats();
+        if stats.dropped_bytes > 0 {
+            crate::file_log!(warn, "TS buffer dropped {} bytes in {} overflows (capacity {} bytes)",
+                   stats.dropped_bytes, stats.overflow_events, stats.capacity);
+        }
+        self.buffer.clear();
+        *self.state.lock() = ConnectionState::Disconnected;
+    }
+
+    /// Stop the connection task and drop the request/response channels.
+    fn shutdown_link(&self) {
         // Drop the request channel to signal shutdown
         *self.request_tx.lock() = None;
         *self.response_rx.lock() = None;
@@ -254,9 +404,6 @@ impl Connection {
         if let Some(rt) = self.runtime.lock().take() {
             rt.shutdown_timeout(Duration::from_secs(1));
         }
-
-        self.buffer.clear();
-        *self.state.lock() = ConnectionState::Disconnected;
     }
 
     /// Send a message and wait for response with timeout.
@@ -265,6 +412,13 @@ impl Connection {
     /// wait — no spin loop, no sleep().  This mirrors the per-command
     /// `WaitForMultipleObjects` + auto-reset event pattern in BonDriverProxy(Ex).
     fn send_request_with_timeout(&self, msg: ClientMessage, timeout: Duration) -> Option<ServerMessage> {
+        // Nothing is read from the request channel until the link is back;
+        // fail fast instead of blocking the host for the full timeout.
+        if self.state() == ConnectionState::Reconnecting {
+            debug!("[Connection] Reconnecting, request dropped: {:?}", std::mem::discriminant(&msg));
+            return None;
+        }
+
         // Send the request first (briefly holds request_tx lock).
         {
             let tx = self.request_tx.lock();
@@ -310,23 +464,26 @@ impl Connection {
     /// Send hello message with timeout (for connection setup).
     #[allow(dead_code)]
     fn send_hello_with_timeout(&self, timeout: Duration) -> bool {
-        let resp = self.send_request_with_timeout(
-            ClientMessage::Hello {
-                version: PROTOCOL_VERSION,
-            },
-            timeout,
-        );
+        let resp = self.send_request_with_timeout(self.hello_message(), timeout);
 
         match resp {
-            Some(ServerMessage::HelloAck { version, success }) => {
+            Some(ServerMessage::HelloAck { version, success, features }) => {
                 if success {
-                    info!("Connected to server, protocol version {}", version);
+                    info!("Connected to server, protocol version {}, features {:#x}", version, features);
                     true
                 } else {
                     error!("Server rejected hello, version mismatch");
                     false
                 }
             }
+            Some(ServerMessage::AuthRequired) => {
+                error!("Server requires authentication; set AuthToken in the INI file");
+                false
+            }
+            Some(ServerMessage::AuthFailed) => {
+                error!("Server rejected the auth token");
+                false
+            }
             _ => {
                 // No response yet or invalid response
                 false
@@ -337,21 +494,26 @@ impl Connection {
     /// Send hello message.
     fn send_hello(&self) -> bool {
         // Use connect_timeout (not read_timeout) for the initial handshake.
-        let resp = self.send_request_with_timeout(
-            ClientMessage::Hello { version: PROTOCOL_VERSION },
-            self.config.connect_timeout,
-        );
+        let resp = self.send_request_with_timeout(self.hello_message(), self.config.connect_timeout);
 
         match resp {
-            Some(ServerMessage::HelloAck { version, success }) => {
+            Some(ServerMessage::HelloAck { version, success, features }) => {
                 if success {
-                    info!("Connected to server, protocol version {}", version);
+                    info!("Connected to server, protocol version {}, features {:#x}", version, features);
                     true
                 } else {
                     error!("Server rejected hello, version mismatch");
                     false
                 }
             }
+            Some(ServerMessage::AuthRequired) => {
+                error!("Server requires authentication; set AuthToken in the INI file");
+                false
+            }
+            Some(ServerMessage::AuthFailed) => {
+                error!("Server rejected the auth token");
+                false
+            }
             _ => {
                 error!("Invalid hello response");
                 false
@@ -378,6 +540,7 @@ impl Connection {
             }) => {
                 if success {
                     *self.bondriver_version.lock() = bondriver_version;
+                    self.replay.lock().tuner_open = true;
                     *self.state.lock() = ConnectionState::TunerOpen;
                     info!("Tuner opened, BonDriver version {}", bondriver_version);
                     true
@@ -395,30 +558,55 @@ impl Connection {
             self.stop_stream();
         }
 
+        *self.replay.lock() = SessionReplay::default();
         let _ = self.send_request(ClientMessage::CloseTuner);
-        *self.state.lock() = ConnectionState::Connected;
+        let mut state = self.state.lock();
+        if *state != ConnectionState::Reconnecting {
+            *state = ConnectionState::Connected;
+        }
     }
 
     /// Set channel (IBonDriver v1).
     pub fn set_channel(&self, channel: u8, _force: bool) -> bool {
-        let resp = self.send_request(ClientMessage::SetChannel {
+        let msg = ClientMessage::SetChannel {
             channel,
             priority: self.config.client_priority,
             exclusive: self.config.client_exclusive,
-        });
+        };
+        let resp = self.send_request(msg.clone());
 
         match resp {
-            Some(ServerMessage::SetChannelAck { success, .. }) => success,
+            Some(ServerMessage::SetChannelAck { success: true, .. }) => {
+                self.replay.lock().channel = Some(msg);
+                true
+            }
+            Some(ServerMessage::SetChannelAck { error_code, .. }) => {
+                warn!("SetChannel({}) failed: {}", channel, ErrorCode::from(error_code).description());
+                false
+            }
             _ => false,
         }
     }
 
     /// Set channel by space (IBonDriver v2).
     pub fn set_channel_space(&self, space: u32, channel: u32, priority: i32, exclusive: bool) -> bool {
-        let resp = self.send_request(ClientMessage::SetChannelSpace { space, channel, priority, exclusive });
+        let msg = ClientMessage::SetChannelSpace { space, channel, priority, exclusive };
+        let resp = self.send_request(msg.clone());
 
         match resp {
-            Some(ServerMessage::SetChannelSpaceAck { success, .. }) => success,
+            Some(ServerMessage::SetChannelSpaceAck { success: true, .. }) => {
+                self.replay.lock().channel = Some(msg);
+                true
+            }
+            Some(ServerMessage::SetChannelSpaceAck { error_code, .. }) => {
+                warn!(
+                    "SetChannel(space {}, ch {}) failed: {}",
+                    space,
+                    channel,
+                    ErrorCode::from(error_code).description()
+                );
+                false
+            }
             _ => false,
         }
     }
@@ -464,6 +652,7 @@ impl Connection {
         match resp {
             Some(ServerMessage::StartStreamAck { success, .. }) => {
                 if success {
+                    self.replay.lock().streaming = true;
                     *self.state.lock() = ConnectionState::Streaming;
                 }
                 success
@@ -474,6 +663,7 @@ impl Connection {
 
     /// Stop streaming.
     pub fn stop_stream(&self) {
+        self.replay.lock().streaming = false;
         if self.state() != ConnectionState::Streaming {
             return;
         }
@@ -508,6 +698,26 @@ impl Connection {
         }
     }
 
+    /// Running/max instances of the server-side driver(s) backing this tuner.
+    pub fn get_tuner_capacity(&self) -> Option<Vec<TunerCapacity>> {
+        let resp = self.send_request(ClientMessage::GetTunerCapacity);
+
+        match resp {
+            Some(ServerMessage::GetTunerCapacityAck { drivers }) => Some(drivers),
+            _ => None,
+        }
+    }
+
+    /// Groups and drivers the server can open, with their free instances.
+    pub fn list_tuners(&self) -> Option<Vec<TunerListEntry>> {
+        let resp = self.send_request(ClientMessage::ListTuners);
+
+        match resp {
+            Some(ServerMessage::TunerList { entries }) => Some(entries),
+            _ => None,
+        }
+    }
+
     /// Set LNB power.
     pub fn set_lnb_power(&self, enable: bool) -> bool {
         let resp = self.send_request(ClientMessage::SetLnbPower { enable });
@@ -519,21 +729,81 @@ impl Connection {
     }
 }
 
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+type BoxReader = Box<dyn AsyncRead + Unpin + Send>;
+type BoxWriter = Box<dyn AsyncWrite + Unpin + Send>;
+
+/// Shared request receiver; kept across reconnects so the sync side keeps
+/// its sender.
+type SharedRequestRx = Arc<tokio::sync::Mutex<mpsc::Receiver<ClientMessage>>>;
+
 /// Background task for handling the connection.
+///
+/// When the link drops after `connect()` has completed, the task reconnects
+/// with exponential backoff and replays the session before resuming the
+/// read loop. TS data already in the ring buffer stays readable meanwhile.
 async fn connection_task(
     conn: Arc<Connection>,
     config: ConnectionConfig,
     req_rx: mpsc::Receiver<ClientMessage>,
     resp_tx: std::sync::mpsc::Sender<ServerMessage>,
     buffer: Arc<TsRingBuffer>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    file_log!(info, "connection_task: Starting, connecting to {}...", config.server_addr);
-    info!("Connecting to {}...", config.server_addr);
+) -> Result<(), BoxError> {
+    let req_rx: SharedRequestRx = Arc::new(tokio::sync::Mutex::new(req_rx));
+
+    let (reader, writer) = open_transport(&config, &conn.active_server()).await?;
+    let mut result = connection_loop(
+        Arc::clone(&conn),
+        Arc::clone(&req_rx),
+        resp_tx.clone(),
+        Arc::clone(&buffer),
+        reader,
+        writer,
+    )
+    .await;
+
+    loop {
+        let established = matches!(
+            conn.state(),
+            ConnectionState::Connected | ConnectionState::TunerOpen | ConnectionState::Streaming
+        );
+        if !config.reconnect || !established || conn.closing.load(Ordering::Acquire) {
+            if result.is_ok() {
+                *conn.state.lock() = ConnectionState::Disconnected;
+            }
+            return result;
+        }
+
+        match &result {
+            Ok(()) => file_log!(warn, "connection_task: Server closed the connection, reconnecting"),
+            Err(e) => file_log!(warn, "connection_task: Connection lost ({}), reconnecting", e),
+        }
+        warn!("Connection to {} lost, reconnecting", conn.active_server());
+
+        let Some((reader, writer)) = reconnect(&conn, &config).await else {
+            return result;
+        };
+        result = connection_loop(
+            Arc::clone(&conn),
+            Arc::clone(&req_rx),
+            resp_tx.clone(),
+            Arc::clone(&buffer),
+            reader,
+            writer,
+        )
+        .await;
+    }
+}
+
+/// Connect to the server (TCP, then TLS if enabled).
+async fn open_transport(config: &ConnectionConfig, addr: &str) -> Result<(BoxReader, BoxWriter), BoxError> {
+    file_log!(info, "connection_task: Starting, connecting to {}...", addr);
+    info!("Connecting to {}...", addr);
 
     file_log!(debug, "connection_task: Attempting TCP connect with timeout {:?}", config.connect_timeout);
     let stream = match tokio::time::timeout(
         config.connect_timeout,
-        TcpStream::connect(&config.server_addr),
+        TcpStream::connect(addr),
     )
     .await {
         Ok(Ok(s)) => {
@@ -551,8 +821,8 @@ async fn connection_task(
     };
 
     stream.set_nodelay(true)?;
-    file_log!(info, "connection_task: Connected to {}", config.server_addr);
-    info!("Connected to {}", config.server_addr);
+    file_log!(info, "connection_task: Connected to {}", addr);
+    info!("Connected to {}", addr);
 
     // Handle TLS if enabled
     #[cfg(feature = "tls")]
@@ -561,19 +831,190 @@ async fn connection_task(
             info!("Establishing TLS connection...");
             let tls_config = build_tls_config(config.tls_ca_cert.as_deref())?;
             let connector = TlsConnector::from(Arc::new(tls_config));
-            let server_name = extract_server_name(&config.server_addr);
+            let server_name = extract_server_name(addr);
 
             let tls_stream = connector.connect(server_name, stream).await?;
             info!("TLS connection established");
 
             let (reader, writer) = tokio::io::split(tls_stream);
-            return connection_loop(conn, req_rx, resp_tx, buffer, reader, writer).await;
+            return Ok((Box::new(reader), Box::new(writer)));
         }
     }
 
     // Plain TCP connection
     let (reader, writer) = stream.into_split();
-    connection_loop(conn, req_rx, resp_tx, buffer, reader, writer).await
+    Ok((Box::new(reader), Box::new(writer)))
+}
+
+/// Reconnect with exponential backoff and replay the session.
+///
+/// Returns `None` once `disconnect()` has been called.
+async fn reconnect(conn: &Arc<Connection>, config: &ConnectionConfig) -> Option<(BoxReader, BoxWriter)> {
+    *conn.state.lock() = ConnectionState::Reconnecting;
+
+    let mut attempt = 0u32;
+    loop {
+        let delay = reconnect_delay(attempt, config.reconnect_max_delay);
+        attempt = attempt.saturating_add(1);
+        tokio::time::sleep(delay).await;
+        if conn.closing.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let replay = conn.replay.lock().clone();
+        for addr in failover_order(config, &conn.active_server()) {
+            let resumed = async {
+                let (mut reader, mut writer) = open_transport(config, &addr).await?;
+                replay_session(conn, config, &replay, &mut reader, &mut writer).await?;
+                Ok::<_, BoxError>((reader, writer))
+            }
+            .await;
+
+            match resumed {
+                Ok(link) => {
+                    let previous = std::mem::replace(&mut *conn.active_server.lock(), addr.clone());
+                    if previous != addr {
+                        file_log!(warn, "reconnect: Failed over from {} to {}", previous, addr);
+                        warn!("Failed over from {} to {}", previous, addr);
+                    }
+                    file_log!(info, "reconnect: Session resumed on {} after {} attempt(s)", addr, attempt);
+                    info!("Reconnected, active server: {}", addr);
+                    *conn.state.lock() = replay.resumed_state();
+                    return Some(link);
+                }
+                Err(e) => {
+                    file_log!(warn, "reconnect: Attempt {} to {} failed: {}", attempt, addr, e);
+                    warn!("Reconnect attempt {} to {} failed: {}", attempt, addr, e);
+                }
+            }
+        }
+    }
+}
+
+/// Servers to try when reconnecting: the active one first, then the rest in
+/// configured order.
+fn failover_order(config: &ConnectionConfig, active: &str) -> Vec<String> {
+    let mut servers: Vec<String> = config.servers().map(str::to_string).collect();
+    if let Some(pos) = servers.iter().position(|s| s == active) {
+        servers.rotate_left(pos);
+    }
+    servers
+}
+
+/// Replay Hello / OpenTuner / SetChannel / StartStream on a fresh link.
+async fn replay_session<R, W>(
+    conn: &Connection,
+    config: &ConnectionConfig,
+    replay: &SessionReplay,
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<(), BoxError>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    // A new link starts with plain framing until HelloAck
+    conn.ts_compression.store(false, Ordering::Release);
+    conn.frame_crc32.store(false, Ordering::Release);
+
+    let hello = conn.hello_message();
+    match exchange(conn, config, reader, writer, &hello).await? {
+        ServerMessage::HelloAck { success: true, features, .. } => {
+            conn.ts_compression
+                .store(features & FEATURE_TS_COMPRESSION != 0, Ordering::Release);
+            conn.frame_crc32
+                .store(features & FEATURE_FRAME_CRC32 != 0, Ordering::Release);
+        }
+        other => return Err(format!("unexpected Hello response: {:?}", other).into()),
+    }
+
+    if config.single_service {
+        let filter = ClientMessage::SetServiceFilter { single_service: true };
+        exchange(conn, config, reader, writer, &filter).await?;
+    }
+
+    if !replay.tuner_open {
+        return Ok(());
+    }
+    let open = ClientMessage::OpenTuner {
+        tuner_path: config.tuner_path.clone(),
+    };
+    match exchange(conn, config, reader, writer, &open).await? {
+        ServerMessage::OpenTunerAck { success: true, .. } => {}
+        other => return Err(format!("OpenTuner failed: {:?}", other).into()),
+    }
+
+    if let Some(channel) = &replay.channel {
+        match exchange(conn, config, reader, writer, channel).await? {
+            ServerMessage::SetChannelAck { success: true, .. }
+            | ServerMessage::SetChannelSpaceAck { success: true, .. } => {}
+            other => return Err(format!("channel selection failed: {:?}", other).into()),
+        }
+    }
+
+    if replay.streaming {
+        match exchange(conn, config, reader, writer, &ClientMessage::StartStream).await? {
+            ServerMessage::StartStreamAck { success: true, .. } => {}
+            other => return Err(format!("StartStream failed: {:?}", other).into()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Send one request and read frames until its response arrives.
+///
+/// Frames are read exactly, so nothing is left over for the read loop.
+async fn exchange<R, W>(
+    conn: &Connection,
+    config: &ConnectionConfig,
+    reader: &mut R,
+    writer: &mut W,
+    msg: &ClientMessage,
+) -> Result<ServerMessage, BoxError>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let encoded = encode_client_message_with(msg, conn.frame_options())?;
+    writer.write_all(&encoded).await?;
+    writer.flush().await?;
+
+    let read_response = async {
+        loop {
+            let mut header_buf = [0u8; HEADER_SIZE];
+            reader.read_exact(&mut header_buf).await?;
+            let header = decode_header(&header_buf)?.ok_or("incomplete frame header")?;
+            let mut payload = vec![0u8; header.payload_len as usize];
+            reader.read_exact(&mut payload).await?;
+
+            // The stream may already be running server-side; the read loop
+            // picks TS data up once the session is resumed.
+            if header.message_type == MessageType::TsData {
+                continue;
+            }
+            let msg = decode_server_message_with(
+                header.message_type,
+                Bytes::from(payload),
+                conn.frame_options(),
+            )?;
+            if let ServerMessage::TunerStatus { state, reason } = &msg {
+                crate::file_log!(warn, "Server tuner status {:?}: {}", state, reason);
+                continue;
+            }
+            if let ServerMessage::Ping { seq } = msg {
+                let pong = encode_client_message_with(&ClientMessage::Pong { seq }, conn.frame_options())?;
+                writer.write_all(&pong).await?;
+                writer.flush().await?;
+                continue;
+            }
+            return Ok::<_, BoxError>(msg);
+        }
+    };
+
+    tokio::time::timeout(config.read_timeout, read_response)
+        .await
+        .map_err(|_| "timed out waiting for response")?
 }
 
 /// Main connection loop handling reads and writes.
@@ -584,12 +1025,12 @@ async fn connection_task(
 /// hop adds latency and the downstream TCP send buffer can fill up.
 async fn connection_loop<R, W>(
     conn: Arc<Connection>,
-    mut req_rx: mpsc::Receiver<ClientMessage>,
+    req_rx: SharedRequestRx,
     resp_tx: std::sync::mpsc::Sender<ServerMessage>,
     buffer: Arc<TsRingBuffer>,
     mut reader: R,
     mut writer: W,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+) -> Result<(), BoxError>
 where
     R: AsyncRead + Unpin + Send + 'static,
     W: AsyncWrite + Unpin + Send + 'static,
@@ -597,10 +1038,12 @@ where
     // --- Writer task (independent) ---
     // Runs in its own tokio task so that write_all() blocking on TCP
     // backpressure does not stall the reader.
+    let writer_conn = Arc::clone(&conn);
     let writer_handle = tokio::spawn(async move {
+        let mut req_rx = req_rx.lock().await;
         while let Some(msg) = req_rx.recv().await {
             trace!("Sending request: {:?}", msg);
-            let encoded = match encode_client_message(&msg) {
+            let encoded = match encode_client_message_with(&msg, writer_conn.frame_options()) {
                 Ok(e) => e,
                 Err(e) => {
                     error!("Failed to encode client message: {}", e);
@@ -626,13 +1069,14 @@ where
 
     static TS_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
     static TS_BYTES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    // Dropped-byte count already reported in the log
+    let mut dropped_reported = buffer.stats().dropped_bytes;
 
     let result: Result<(), Box<dyn std::error::Error + Send + Sync>> = async {
         loop {
             let n = reader.read_buf(&mut read_buf).await?;
             if n == 0 {
                 info!("Connection closed by server");
-                *conn.state.lock() = ConnectionState::Disconnected;
                 break;
             }
 
@@ -655,7 +1099,21 @@ where
                         // the decoder).  The payload is written straight
                         // from read_buf into the ring buffer (single copy).
                         if header.message_type == MessageType::TsData {
-                            let ts_payload = read_buf.split_to(header.payload_len as usize);
+                            let ts_payload = read_buf.split_to(header.payload_len as usize).freeze();
+                            let ts_payload = match strip_frame_crc(ts_payload, conn.frame_options()) {
+                                Ok(payload) => payload,
+                                Err(e @ ProtocolError::ChecksumMismatch { .. }) => {
+                                    crate::file_log!(warn, "Dropping corrupted TsData frame: {}", e);
+                                    continue;
+                                }
+                                Err(e) => return Err(e.into()),
+                            };
+                            // Compressed frames cost one extra copy for decompression
+                            let ts_payload = if conn.ts_compression.load(Ordering::Acquire) {
+                                Bytes::from(decode_compressed_ts_payload(&ts_payload)?)
+                            } else {
+                                ts_payload
+                            };
 
                             let count = TS_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                             TS_BYTES.fetch_add(ts_payload.len() as u64, std::sync::atomic::Ordering::Relaxed);
@@ -664,12 +1122,17 @@ where
 
                             if count % 100 == 0 {
                                 let total_bytes = TS_BYTES.load(std::sync::atomic::Ordering::Relaxed);
-                                crate::file_log!(info, "TsData #{}: {} bytes, written={}, buffer={}, total={}",
-                                       count, ts_payload.len(), written, buffer.available(), total_bytes);
-                            }
-
-                            if written < ts_payload.len() {
-                                crate::file_log!(warn, "Buffer full, dropped {} bytes", ts_payload.len() - written);
+                                let stats = buffer.stats();
+                                crate::file_log!(info, "TsData #{}: {} bytes, written={}, buffer={}/{}, total={}, dropped={}",
+                                       count, ts_payload.len(), written, stats.available, stats.capacity,
+                                       total_bytes, stats.dropped_bytes);
+
+                                // Report new overflows at most once per 100 frames
+                                if stats.dropped_bytes > dropped_reported {
+                                    crate::file_log!(warn, "TS buffer overflow: dropped {} bytes since last report ({} bytes in {} overflows total)",
+                                           stats.dropped_bytes - dropped_reported, stats.dropped_bytes, stats.overflow_events);
+                                    dropped_reported = stats.dropped_bytes;
+                                }
                             }
 
                             continue;
@@ -678,7 +1141,42 @@ where
                         // --- Non-TS messages ---
                         // freeze() is zero-copy (BytesMut → Bytes without cloning).
                         let payload = read_buf.split_to(header.payload_len as usize).freeze();
-                        let msg = decode_server_message(header.message_type, payload)?;
+                        let msg = match decode_server_message_with(
+                            header.message_type,
+                            payload,
+                            conn.frame_options(),
+                        ) {
+                            Ok(msg) => msg,
+                            Err(e @ ProtocolError::ChecksumMismatch { .. }) => {
+                                // The length was intact, so the next frame starts cleanly
+                                warn!("Dropping corrupted {:?} frame: {}", header.message_type, e);
+                                continue;
+                            }
+                            Err(e) => return Err(e.into()),
+                        };
+                        // Switch the framing here, before any other frame can follow the ack
+                        if let ServerMessage::HelloAck { features, .. } = &msg {
+                            conn.ts_compression
+                                .store(features & FEATURE_TS_COMPRESSION != 0, Ordering::Release);
+                            conn.frame_crc32
+                                .store(features & FEATURE_FRAME_CRC32 != 0, Ordering::Release);
+                        }
+
+                        // Unsolicited, so it must not be taken as a command reply
+                        if let ServerMessage::TunerStatus { state, reason } = &msg {
+                            crate::file_log!(warn, "Server tuner status {:?}: {}", state, reason);
+                            continue;
+                        }
+                        // The writer task owns the socket, so the Pong goes through the request queue
+                        if let ServerMessage::Ping { seq } = msg {
+                            let tx = conn.request_tx.lock().clone();
+                            if let Some(tx) = tx {
+                                if tx.try_send(ClientMessage::Pong { seq }).is_err() {
+                                    debug!("Request queue full, Ping {} unanswered", seq);
+                                }
+                            }
+                            continue;
+                        }
 
                         // std::sync::mpsc::Sender::send() is non-blocking.
                         if resp_tx.send(msg).is_err() {
@@ -779,3 +1277,137 @@ fn extract_server_name(addr: &str) -> ServerName<'static> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use recisdb_protocol::{decode_client_message, encode_server_message};
+
+    #[test]
+    fn test_failover_order_starts_at_active_server() {
+        let config = ConnectionConfig {
+            server_addr: "10.0.0.1:40070".to_string(),
+            fallback_servers: vec!["10.0.0.2:40070".to_string(), "10.0.0.3:40070".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            failover_order(&config, "10.0.0.2:40070"),
+            vec!["10.0.0.2:40070", "10.0.0.3:40070", "10.0.0.1:40070"]
+        );
+        assert_eq!(failover_order(&config, "unknown:1")[0], "10.0.0.1:40070");
+    }
+
+    #[test]
+    fn test_reconnect_delay_is_capped() {
+        let max = Duration::from_secs(30);
+        assert_eq!(reconnect_delay(0, max), Duration::from_millis(500));
+        assert_eq!(reconnect_delay(1, max), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(3, max), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(10, max), max);
+        assert_eq!(reconnect_delay(u32::MAX, max), max);
+    }
+
+    /// Read one client frame, or `None` once the client hung up.
+    async fn read_client_message<R: AsyncRead + Unpin>(reader: &mut R) -> Option<ClientMessage> {
+        let mut header_buf = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut header_buf).await.ok()?;
+        let header = decode_header(&header_buf).unwrap().unwrap();
+        let mut payload = vec![0u8; header.payload_len as usize];
+        reader.read_exact(&mut payload).await.unwrap();
+        Some(decode_client_message(header.message_type, Bytes::from(payload)).unwrap())
+    }
+
+    /// Minimal server answering each request with a successful ack.
+    async fn fake_server(stream: tokio::io::DuplexStream) -> Vec<ClientMessage> {
+        let (mut reader, mut writer) = tokio::io::split(stream);
+        let mut received = Vec::new();
+        while let Some(msg) = read_client_message(&mut reader).await {
+
+            let ack = match &msg {
+                ClientMessage::Hello { version, .. } => {
+                    ServerMessage::HelloAck { version: *version, success: true, features: 0 }
+                }
+                ClientMessage::OpenTuner { .. } => {
+                    ServerMessage::OpenTunerAck { success: true, error_code: 0, bondriver_version: 2 }
+                }
+                ClientMessage::SetChannelSpace { .. } => {
+                    ServerMessage::SetChannelSpaceAck { success: true, error_code: 0 }
+                }
+                ClientMessage::StartStream => {
+                    // TS data racing ahead of the ack must be skipped
+                    let ts = encode_server_message(&ServerMessage::TsData { data: vec![0x47; 188] }).unwrap();
+                    writer.write_all(&ts).await.unwrap();
+                    ServerMessage::StartStreamAck { success: true, error_code: 0 }
+                }
+                other => panic!("unexpected request {:?}", other),
+            };
+            received.push(msg);
+            writer.write_all(&encode_server_message(&ack).unwrap()).await.unwrap();
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_hello_requests_server_ping() {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let (mut reader, mut writer) = tokio::io::split(server);
+            let hello = read_client_message(&mut reader).await.unwrap();
+            let ClientMessage::Hello { version, features, .. } = hello else {
+                panic!("expected Hello, got {:?}", hello);
+            };
+            assert_ne!(features & FEATURE_SERVER_PING, 0);
+
+            // A Ping arriving before the HelloAck must be answered, not returned
+            let ping = encode_server_message(&ServerMessage::Ping { seq: 7 }).unwrap();
+            writer.write_all(&ping).await.unwrap();
+            let pong = read_client_message(&mut reader).await.unwrap();
+            let ack = ServerMessage::HelloAck { version, success: true, features: 0 };
+            writer.write_all(&encode_server_message(&ack).unwrap()).await.unwrap();
+            pong
+        });
+
+        let config = ConnectionConfig::default();
+        let conn = Connection::new(config.clone());
+        let (mut reader, mut writer) = tokio::io::split(client);
+        let resp = exchange(&conn, &config, &mut reader, &mut writer, &conn.hello_message())
+            .await
+            .unwrap();
+        assert!(matches!(resp, ServerMessage::HelloAck { success: true, .. }));
+        assert_eq!(server.await.unwrap(), ClientMessage::Pong { seq: 7 });
+    }
+
+    #[tokio::test]
+    async fn test_replay_session_restores_streaming() {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(fake_server(server));
+
+        let config = ConnectionConfig {
+            tuner_path: "BonDriver_PX-MLT1.dll".to_string(),
+            ..Default::default()
+        };
+        let conn = Connection::new(config.clone());
+        let replay = SessionReplay {
+            tuner_open: true,
+            channel: Some(ClientMessage::SetChannelSpace { space: 0, channel: 13, priority: 0, exclusive: false }),
+            streaming: true,
+        };
+
+        let (mut reader, mut writer) = tokio::io::split(client);
+        replay_session(&conn, &config, &replay, &mut reader, &mut writer)
+            .await
+            .unwrap();
+        assert_eq!(replay.resumed_state(), ConnectionState::Streaming);
+        drop((reader, writer));
+
+        let received = server.await.unwrap();
+        assert!(matches!(received[0], ClientMessage::Hello { .. }));
+        assert_eq!(
+            received[1],
+            ClientMessage::OpenTuner { tuner_path: "BonDriver_PX-MLT1.dll".to_string() }
+        );
+        assert_eq!(received[2], replay.channel.clone().unwrap());
+        assert_eq!(received[3], ClientMessage::StartStream);
+        assert_eq!(received.len(), 4);
+    }
+}
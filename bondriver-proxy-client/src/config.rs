@@ -11,6 +11,7 @@ use std::time::Duration;
 
 use log::{debug, error, info, warn};
 
+use crate::client::buffer::DEFAULT_RING_BUFFER_SIZE;
 use crate::client::ConnectionConfig;
 
 /// Load log level from INI file or environment.
@@ -175,11 +176,13 @@ fn load_from_ini(path: &PathBuf) -> Option<ConnectionConfig> {
     let sections = parse_ini(&content);
     let section = sections.get("Server")?;
 
-    let server_addr = section
-        .get("Address")
+    // `Servers` lists the failover order; `Address` may also hold a list
+    let (server_addr, fallback_servers) = section
+        .get("Servers")
+        .or_else(|| section.get("Address"))
         .or_else(|| section.get("Server"))
-        .cloned()
-        .unwrap_or_else(|| "127.0.0.1:40070".to_string());
+        .map(|s| split_server_list(s))
+        .unwrap_or_else(|| ("127.0.0.1:40070".to_string(), Vec::new()));
 
     let tuner_path = section
         .get("Tuner")
@@ -231,7 +234,36 @@ fn load_from_ini(path: &PathBuf) -> Option<ConnectionConfig> {
         .map(|s| s.to_lowercase() == "single")
         .unwrap_or(false);
 
-    debug!("Configuration loaded: server={}, tuner={}", server_addr, tuner_path);
+    let compression = section
+        .get("Compression")
+        .map(|s| parse_compression(s))
+        .unwrap_or(false);
+
+    let auth_token = section
+        .get("AuthToken")
+        .filter(|s| !s.is_empty())
+        .cloned();
+
+    let reconnect = section
+        .get("Reconnect")
+        .map(|s| parse_bool(s))
+        .unwrap_or(true);
+
+    let reconnect_max_delay = section
+        .get("ReconnectMaxDelay")
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(Duration::from_secs(30));
+
+    let buffer_size = section
+        .get("BufferSize")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_RING_BUFFER_SIZE);
+
+    debug!(
+        "Configuration loaded: server={}, fallback={:?}, tuner={}",
+        server_addr, fallback_servers, tuner_path
+    );
 
     Some(ConnectionConfig {
         server_addr,
@@ -245,13 +277,20 @@ fn load_from_ini(path: &PathBuf) -> Option<ConnectionConfig> {
         #[cfg(feature = "tls")]
         tls_ca_cert,
         single_service,
+        compression,
+        auth_token,
+        reconnect,
+        reconnect_max_delay,
+        fallback_servers,
+        buffer_size,
     })
 }
 
 /// Load configuration from environment variables.
 fn load_from_env() -> ConnectionConfig {
-    let server_addr = std::env::var("BONDRIVER_PROXY_SERVER")
-        .unwrap_or_else(|_| "127.0.0.1:40070".to_string());
+    let (server_addr, fallback_servers) = std::env::var("BONDRIVER_PROXY_SERVER")
+        .map(|s| split_server_list(&s))
+        .unwrap_or_else(|_| ("127.0.0.1:40070".to_string(), Vec::new()));
 
     let tuner_path = std::env::var("BONDRIVER_PROXY_TUNER")
         .unwrap_or_default();
@@ -298,9 +337,57 @@ fn load_from_env() -> ConnectionConfig {
         single_service: std::env::var("BONDRIVER_PROXY_SERVICE_FILTER")
             .map(|s| s.to_lowercase() == "single")
             .unwrap_or(false),
+        compression: std::env::var("BONDRIVER_PROXY_COMPRESSION")
+            .map(|s| parse_compression(&s))
+            .unwrap_or(false),
+        auth_token: std::env::var("BONDRIVER_PROXY_AUTH_TOKEN")
+            .ok()
+            .filter(|s| !s.is_empty()),
+        reconnect: std::env::var("BONDRIVER_PROXY_RECONNECT")
+            .map(|s| parse_bool(&s))
+            .unwrap_or(true),
+        reconnect_max_delay: std::env::var("BONDRIVER_PROXY_RECONNECT_MAX_DELAY")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(Duration::from_secs(30)),
+        fallback_servers,
+        buffer_size: std::env::var("BONDRIVER_PROXY_BUFFER_SIZE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_RING_BUFFER_SIZE),
     }
 }
 
+/// Parse a server list into the primary address and the fallbacks.
+///
+/// Accepts `a:1, b:2` as well as `["a:1", "b:2"]`. An empty list yields the
+/// default address.
+fn split_server_list(value: &str) -> (String, Vec<String>) {
+    let mut servers = value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|s| s.trim().trim_matches('"').trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let primary = servers.next().unwrap_or_else(|| "127.0.0.1:40070".to_string());
+    (primary, servers.collect())
+}
+
+/// Parse a boolean setting (`1`/`true`/`yes`/`on`).
+fn parse_bool(value: &str) -> bool {
+    let lower = value.trim().to_lowercase();
+    lower == "1" || lower == "true" || lower == "yes" || lower == "on"
+}
+
+/// Parse the `Compression` setting (`zstd`, or a boolean for the default codec).
+fn parse_compression(value: &str) -> bool {
+    let lower = value.trim().to_lowercase();
+    lower == "zstd" || lower == "1" || lower == "true" || lower == "yes" || lower == "on"
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -311,6 +398,20 @@ mod tests {
         assert!(!config.server_addr.is_empty());
     }
 
+    #[test]
+    fn test_split_server_list() {
+        assert_eq!(split_server_list("10.0.0.1:12345"), ("10.0.0.1:12345".to_string(), vec![]));
+        assert_eq!(
+            split_server_list(r#"["10.0.0.1:12345", "10.0.0.2:12345"]"#),
+            ("10.0.0.1:12345".to_string(), vec!["10.0.0.2:12345".to_string()])
+        );
+        assert_eq!(
+            split_server_list("10.0.0.1:12345, 10.0.0.2:12345,"),
+            ("10.0.0.1:12345".to_string(), vec!["10.0.0.2:12345".to_string()])
+        );
+        assert_eq!(split_server_list(" "), ("127.0.0.1:40070".to_string(), vec![]));
+    }
+
     #[test]
     fn test_parse_ini() {
         let content = r#"
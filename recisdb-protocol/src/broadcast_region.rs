@@ -13,6 +13,9 @@
 //! - 地域識別 (region_id): 1-62
 //! - 地域事業者識別 (broadcaster_id): 0-15
 //! - 県複フラグ: 0 (normal) or 1 (prefecture-specific)
+//!
+//! Cable (CATV) networks do not encode a region; their NIDs name the
+//! delivery scheme instead (see [`CATV_NETWORKS`]).
 
 use serde::{Deserialize, Serialize};
 
@@ -256,6 +259,27 @@ impl TerrestrialRegion {
             Self::Unknown(_) => "Unknown",
         }
     }
+
+    /// Returns the conventional north-to-south ordering of this region.
+    ///
+    /// Hokkaido sorts first and Okinawa last; unknown regions sort after
+    /// every known region, ordered by their raw value.
+    pub fn sort_order(&self) -> u32 {
+        match self {
+            Self::Hokkaido => 1,
+            Self::Tohoku => 2,
+            Self::Kanto => 3,
+            Self::Koshinetsu => 4,
+            Self::Hokuriku => 5,
+            Self::Tokai => 6,
+            Self::Kinki => 7,
+            Self::Chugoku => 8,
+            Self::Shikoku => 9,
+            Self::Kyushu => 10,
+            Self::Okinawa => 11,
+            Self::Unknown(raw) => 0x1_0000 + *raw as u32,
+        }
+    }
 }
 
 /// Classify broadcast type and region from Network ID.
@@ -271,6 +295,7 @@ impl TerrestrialRegion {
 /// - BS: NID = 4
 /// - CS: NID = 6, 7, 10
 /// - Terrestrial: NID = 0x7F00-0x7FFF (varies by region)
+/// - CATV: NID = 0xFFF0-0xFFFF (see [`CATV_NETWORKS`])
 ///
 /// # Example
 /// ```
@@ -298,12 +323,62 @@ pub fn classify_nid(nid: u16) -> (BroadcastType, Option<TerrestrialRegion>) {
         // 10: SKY PerfecTV! Premium Service
         6 | 7 | 10 => (BroadcastType::CS, None),
 
+        // Cable television (remux / transmodulation / 自主放送)
+        nid if is_catv_nid(nid) => (BroadcastType::Catv, None),
+
         // Terrestrial digital broadcasting
         // NID ranges based on ARIB TR-B14
         nid => classify_terrestrial_nid(nid),
     }
 }
 
+/// Documented cable television network IDs (ARIB STD-B10 第2部 付録N).
+///
+/// Terrestrial and satellite services carried over cable keep their own NID
+/// (パススルー), so only the cable-specific delivery schemes appear here.
+pub const CATV_NETWORKS: &[(u16, &str)] = &[
+    (0xFFFE, "デジタル放送リマックス"),
+    (0xFFFD, "JC-HITS トランスモジュレーション"),
+    (0xFFFA, "デジタル放送高度リマックス"),
+    (0xFFF9, "高度JC-HITS トランスモジュレーション"),
+    (0xFFF7, "高度ケーブル自主放送"),
+];
+
+/// NID block the cable allocations above are taken from.
+const CATV_NID_RANGE: std::ops::RangeInclusive<u16> = 0xFFF0..=0xFFFF;
+
+/// Whether `nid` belongs to a cable television network.
+///
+/// Besides the documented IDs in [`CATV_NETWORKS`], any other NID in
+/// 0xFFF0-0xFFFF is treated as cable so newer allocations still classify.
+pub fn is_catv_nid(nid: u16) -> bool {
+    CATV_NID_RANGE.contains(&nid)
+}
+
+/// Get the cable network (delivery scheme) name for a CATV NID.
+///
+/// Returns "ケーブルテレビ" for CATV NIDs without a documented name and
+/// None for non-cable NIDs.
+///
+/// # Example
+/// ```
+/// use recisdb_protocol::broadcast_region::get_catv_network_name;
+///
+/// assert_eq!(get_catv_network_name(0xFFFE), Some("デジタル放送リマックス"));
+/// assert_eq!(get_catv_network_name(0xFFF1), Some("ケーブルテレビ"));
+/// assert_eq!(get_catv_network_name(4), None);
+/// ```
+pub fn get_catv_network_name(nid: u16) -> Option<&'static str> {
+    if !is_catv_nid(nid) {
+        return None;
+    }
+    let name = CATV_NETWORKS
+        .iter()
+        .find(|(catv_nid, _)| *catv_nid == nid)
+        .map(|(_, name)| *name);
+    Some(name.unwrap_or("ケーブルテレビ"))
+}
+
 /// Classify terrestrial NID to region using calculation-based approach.
 ///
 /// Uses `get_region_id_from_nid` and `get_terrestrial_region_from_id` for classification.
@@ -323,6 +398,7 @@ pub fn broadcast_type_name(btype: BroadcastType) -> &'static str {
         BroadcastType::Terrestrial => "地デジ",
         BroadcastType::BS => "BS",
         BroadcastType::CS => "CS",
+        BroadcastType::Catv => "CATV",
     }
 }
 
@@ -332,6 +408,7 @@ pub fn broadcast_type_name_en(btype: BroadcastType) -> &'static str {
         BroadcastType::Terrestrial => "Terrestrial",
         BroadcastType::BS => "BS",
         BroadcastType::CS => "CS",
+        BroadcastType::Catv => "CATV",
     }
 }
 
@@ -352,6 +429,7 @@ pub fn generate_space_name(btype: BroadcastType, region: Option<TerrestrialRegio
     match btype {
         BroadcastType::BS => "BS".to_string(),
         BroadcastType::CS => "CS".to_string(),
+        BroadcastType::Catv => "CATV".to_string(),
         BroadcastType::Terrestrial => {
             if let Some(r) = region {
                 format!("地デジ ({})", r.display_name())
@@ -454,6 +532,21 @@ mod tests {
         assert_eq!(region, Some(TerrestrialRegion::Okinawa));
     }
 
+    #[test]
+    fn test_catv_classification() {
+        for &(nid, name) in CATV_NETWORKS {
+            assert_eq!(classify_nid(nid), (BroadcastType::Catv, None));
+            assert_eq!(get_catv_network_name(nid), Some(name));
+        }
+        // Undocumented IDs in the cable block fall back to a generic name
+        assert_eq!(classify_nid(0xFFF0), (BroadcastType::Catv, None));
+        assert_eq!(get_catv_network_name(0xFFF0), Some("ケーブルテレビ"));
+        // Terrestrial services passed through keep their regional NID
+        assert_eq!(classify_nid(0x7FE8).0, BroadcastType::Terrestrial);
+        assert_eq!(get_catv_network_name(0x7FE8), None);
+        assert_eq!(generate_space_name(BroadcastType::Catv, None), "CATV");
+    }
+
     #[test]
     fn test_unknown_nid() {
         // Unknown NID returns Terrestrial with Unknown region
@@ -483,6 +576,30 @@ mod tests {
         assert_eq!(TerrestrialRegion::Unknown(0x7FFF).display_name(), "その他");
     }
 
+    #[test]
+    fn test_region_sort_order_north_to_south() {
+        let mut regions = vec![
+            TerrestrialRegion::Unknown(0x1234),
+            TerrestrialRegion::Okinawa,
+            TerrestrialRegion::Kinki,
+            TerrestrialRegion::Hokkaido,
+            TerrestrialRegion::Kanto,
+            TerrestrialRegion::Tohoku,
+        ];
+        regions.sort_by_key(|r| r.sort_order());
+        assert_eq!(
+            regions,
+            vec![
+                TerrestrialRegion::Hokkaido,
+                TerrestrialRegion::Tohoku,
+                TerrestrialRegion::Kanto,
+                TerrestrialRegion::Kinki,
+                TerrestrialRegion::Okinawa,
+                TerrestrialRegion::Unknown(0x1234),
+            ]
+        );
+    }
+
     #[test]
     fn test_prefecture_names() {
         // Test prefectural NIDs (new ranges based on ARIB TR-B14)
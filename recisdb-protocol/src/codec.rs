@@ -8,6 +8,17 @@
 //! +--------+--------+--------+------------------+
 //! | 4 bytes| 4 bytes| 2 bytes|  Length bytes    |
 //! ```
+//!
+//! When both sides negotiated [`FEATURE_FRAME_CRC32`], every frame after
+//! Hello/HelloAck ends with a CRC32 (IEEE) of the payload; the 4 bytes are
+//! counted in Length, so the header layout is unchanged.
+//!
+//! When both sides negotiated [`FEATURE_TS_COMPRESSION`], TsData payloads
+//! start with a flag byte; with [`TS_FLAG_ZSTD`] set the rest is a zstd frame.
+//!
+//! [`FEATURE_TUNER_STATUS`] does not change the framing; it only tells the
+//! server that the client can decode unsolicited TunerStatus messages.
+//! [`FEATURE_SERVER_PING`] likewise only allows the server to send Pings.
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
@@ -17,17 +28,68 @@ use crate::types::*;
 /// Frame header size: 4 (magic) + 4 (length) + 2 (type) = 10 bytes.
 pub const HEADER_SIZE: usize = 10;
 
+/// Size of the trailing CRC32 when [`FEATURE_FRAME_CRC32`] is negotiated.
+pub const CRC_SIZE: usize = 4;
+
+/// Per-connection framing options, derived from the negotiated features.
+///
+/// Hello and HelloAck are always exchanged with the default (plain) options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameOptions {
+    /// Frames end with a CRC32 of the payload.
+    pub crc32: bool,
+}
+
+impl FrameOptions {
+    /// Options for the feature bits agreed in HelloAck.
+    pub fn from_features(features: u32) -> Self {
+        Self {
+            crc32: features & FEATURE_FRAME_CRC32 != 0,
+        }
+    }
+}
+
+/// CRC32 (IEEE) of a frame payload.
+pub fn frame_crc32(payload: &[u8]) -> u32 {
+    crc32fast::hash(payload)
+}
+
+/// TsData flag (compression negotiated): the payload is zstd-compressed.
+pub const TS_FLAG_ZSTD: u8 = 0x01;
+
+/// zstd level for TsData; low levels already shrink null-padded TS well.
+#[cfg(feature = "compression")]
+const TS_ZSTD_LEVEL: i32 = 1;
+
 /// Encode a client message into bytes.
 pub fn encode_client_message(msg: &ClientMessage) -> Result<Bytes, ProtocolError> {
+    encode_client_message_with(msg, FrameOptions::default())
+}
+
+/// Encode a client message with the connection's framing options.
+pub fn encode_client_message_with(
+    msg: &ClientMessage,
+    options: FrameOptions,
+) -> Result<Bytes, ProtocolError> {
     let mut payload = BytesMut::new();
 
     match msg {
-        ClientMessage::Hello { version } => {
+        ClientMessage::Hello { version, features, auth_token } => {
             payload.put_u16_le(*version);
+            payload.put_u32_le(*features);
+            // Appended only when set so servers without auth see the old layout
+            if let Some(token) = auth_token {
+                let token_bytes = token.as_bytes();
+                payload.put_u16_le(token_bytes.len() as u16);
+                payload.put_slice(token_bytes);
+            }
         }
         ClientMessage::Ping => {
             // Empty payload
         }
+        ClientMessage::Pong { seq } => {
+            payload.put_u32_le(*seq);
+        }
         ClientMessage::OpenTuner { tuner_path } => {
             let path_bytes = tuner_path.as_bytes();
             payload.put_u16_le(path_bytes.len() as u16);
@@ -61,9 +123,24 @@ pub fn encode_client_message(msg: &ClientMessage) -> Result<Bytes, ProtocolError
             payload.put_i32_le(*priority);
             payload.put_u8(if *exclusive { 1 } else { 0 });
         }
+        ClientMessage::SetKeepAlive { seconds } => {
+            payload.put_u32_le(*seconds);
+        }
+        ClientMessage::SetDecode { enable } => {
+            payload.put_u8(if *enable { 1 } else { 0 });
+        }
         ClientMessage::GetSignalLevel => {
             // Empty payload
         }
+        ClientMessage::GetStreamStats => {
+            // Empty payload
+        }
+        ClientMessage::GetTunerCapacity => {
+            // Empty payload
+        }
+        ClientMessage::ListTuners => {
+            // Empty payload
+        }
         ClientMessage::EnumTuningSpace { space } => {
             payload.put_u32_le(*space);
         }
@@ -83,7 +160,7 @@ pub fn encode_client_message(msg: &ClientMessage) -> Result<Bytes, ProtocolError
         ClientMessage::SetLnbPower { enable } => {
             payload.put_u8(if *enable { 1 } else { 0 });
         }
-        ClientMessage::SelectLogicalChannel { nid, tsid, sid } => {
+        ClientMessage::SelectLogicalChannel { nid, tsid, sid, priority, exclusive } => {
             payload.put_u16_le(*nid);
             payload.put_u16_le(*tsid);
             match sid {
@@ -95,6 +172,24 @@ pub fn encode_client_message(msg: &ClientMessage) -> Result<Bytes, ProtocolError
                     payload.put_u8(0); // no sid
                 }
             }
+            // Optional controls; omitted entirely so older servers see the
+            // original layout when the client does not use them
+            if priority.is_some() || exclusive.is_some() {
+                match priority {
+                    Some(p) => {
+                        payload.put_u8(1); // has priority
+                        payload.put_i32_le(*p);
+                    }
+                    None => payload.put_u8(0),
+                }
+                match exclusive {
+                    Some(e) => {
+                        payload.put_u8(1); // has exclusive
+                        payload.put_u8(if *e { 1 } else { 0 });
+                    }
+                    None => payload.put_u8(0),
+                }
+            }
         }
         ClientMessage::GetChannelList { filter } => {
             match filter {
@@ -110,23 +205,55 @@ pub fn encode_client_message(msg: &ClientMessage) -> Result<Bytes, ProtocolError
         ClientMessage::SetServiceFilter { single_service } => {
             payload.put_u8(if *single_service { 1 } else { 0 });
         }
+        ClientMessage::SelectByEvent { nid, sid, event_id } => {
+            payload.put_u16_le(*nid);
+            payload.put_u16_le(*sid);
+            payload.put_u16_le(*event_id);
+        }
+        ClientMessage::SetChannelByRemoteKey { remote_key, space_hint } => {
+            payload.put_u8(*remote_key);
+            match space_hint {
+                Some(space) => {
+                    payload.put_u8(1); // has space hint
+                    payload.put_u32_le(*space);
+                }
+                None => {
+                    payload.put_u8(0); // no space hint
+                }
+            }
+        }
     }
 
-    encode_frame(msg.message_type(), payload.freeze())
+    encode_frame(msg.message_type(), payload.freeze(), options)
 }
 
 /// Encode a server message into bytes.
 pub fn encode_server_message(msg: &ServerMessage) -> Result<Bytes, ProtocolError> {
+    encode_server_message_with(msg, FrameOptions::default())
+}
+
+/// Encode a server message with the connection's framing options.
+pub fn encode_server_message_with(
+    msg: &ServerMessage,
+    options: FrameOptions,
+) -> Result<Bytes, ProtocolError> {
     let mut payload = BytesMut::new();
 
     match msg {
-        ServerMessage::HelloAck { version, success } => {
+        ServerMessage::HelloAck { version, success, features } => {
             payload.put_u16_le(*version);
             payload.put_u8(if *success { 1 } else { 0 });
+            payload.put_u32_le(*features);
+        }
+        ServerMessage::AuthRequired | ServerMessage::AuthFailed => {
+            // Empty payload
         }
         ServerMessage::Pong => {
             // Empty payload
         }
+        ServerMessage::Ping { seq } => {
+            payload.put_u32_le(*seq);
+        }
         ServerMessage::OpenTunerAck {
             success,
             error_code,
@@ -147,9 +274,47 @@ pub fn encode_server_message(msg: &ServerMessage) -> Result<Bytes, ProtocolError
             payload.put_u8(if *success { 1 } else { 0 });
             payload.put_u16_le(*error_code);
         }
+        ServerMessage::SetKeepAliveAck { success } => {
+            payload.put_u8(if *success { 1 } else { 0 });
+        }
+        ServerMessage::SetDecodeAck { success, status } => {
+            payload.put_u8(if *success { 1 } else { 0 });
+            payload.put_u8(*status as u8);
+        }
         ServerMessage::GetSignalLevelAck { signal_level } => {
             payload.put_f32_le(*signal_level);
         }
+        ServerMessage::StreamStats {
+            signal_level,
+            drop_rate,
+            scramble_rate,
+            error_rate,
+            bitrate_mbps,
+            decode_status,
+        } => {
+            payload.put_f32_le(*signal_level);
+            payload.put_f32_le(*drop_rate);
+            payload.put_f32_le(*scramble_rate);
+            payload.put_f32_le(*error_rate);
+            payload.put_f32_le(*bitrate_mbps);
+            payload.put_u8(*decode_status as u8);
+        }
+        ServerMessage::GetTunerCapacityAck { drivers } => {
+            payload.put_u32_le(drivers.len() as u32);
+            for d in drivers {
+                encode_string(&mut payload, &d.tuner_path);
+                payload.put_u16_le(d.running);
+                payload.put_u16_le(d.max_instances);
+            }
+        }
+        ServerMessage::TunerList { entries } => {
+            payload.put_u32_le(entries.len() as u32);
+            for e in entries {
+                encode_string(&mut payload, &e.name);
+                payload.put_u8(e.kind as u8);
+                payload.put_u16_le(e.available_instances);
+            }
+        }
         ServerMessage::EnumTuningSpaceAck { name } => {
             encode_optional_string(&mut payload, name);
         }
@@ -202,27 +367,121 @@ pub fn encode_server_message(msg: &ServerMessage) -> Result<Bytes, ProtocolError
         ServerMessage::SetServiceFilterAck { success } => {
             payload.put_u8(if *success { 1 } else { 0 });
         }
+        ServerMessage::TunerStatus { state, reason } => {
+            payload.put_u8(*state as u8);
+            encode_string(&mut payload, reason);
+        }
+    }
+
+    encode_frame(msg.message_type(), payload.freeze(), options)
+}
+
+/// Feature bits this build can negotiate.
+pub fn supported_features() -> u32 {
+    let features = FEATURE_FRAME_CRC32 | FEATURE_TUNER_STATUS | FEATURE_SERVER_PING;
+    if cfg!(feature = "compression") {
+        features | FEATURE_TS_COMPRESSION
+    } else {
+        features
+    }
+}
+
+/// Encode a TsData frame for a connection that negotiated
+/// [`FEATURE_TS_COMPRESSION`].
+///
+/// The data is zstd-compressed only when that makes it smaller, so the
+/// per-frame flag tells the receiver which form it got.
+pub fn encode_compressed_ts_frame(
+    data: &[u8],
+    options: FrameOptions,
+) -> Result<Bytes, ProtocolError> {
+    #[cfg(feature = "compression")]
+    {
+        let compressed = zstd::bulk::compress(data, TS_ZSTD_LEVEL)
+            .map_err(|e| ProtocolError::EncodeError(e.to_string()))?;
+        if compressed.len() < data.len() {
+            let mut payload = BytesMut::with_capacity(1 + compressed.len());
+            payload.put_u8(TS_FLAG_ZSTD);
+            payload.put_slice(&compressed);
+            return encode_frame(MessageType::TsData, payload.freeze(), options);
+        }
+    }
+
+    let mut payload = BytesMut::with_capacity(1 + data.len());
+    payload.put_u8(0);
+    payload.put_slice(data);
+    encode_frame(MessageType::TsData, payload.freeze(), options)
+}
+
+/// Decode the payload of a TsData frame sent with [`FEATURE_TS_COMPRESSION`].
+pub fn decode_compressed_ts_payload(payload: &[u8]) -> Result<Vec<u8>, ProtocolError> {
+    let (&flags, data) = payload.split_first().ok_or(ProtocolError::IncompleteFrame {
+        expected: 1,
+        actual: 0,
+    })?;
+    if flags & TS_FLAG_ZSTD == 0 {
+        return Ok(data.to_vec());
     }
 
-    encode_frame(msg.message_type(), payload.freeze())
+    #[cfg(feature = "compression")]
+    {
+        zstd::bulk::decompress(data, MAX_FRAME_SIZE as usize)
+            .map_err(|e| ProtocolError::DecodeError(e.to_string()))
+    }
+    #[cfg(not(feature = "compression"))]
+    {
+        Err(ProtocolError::DecodeError(
+            "compressed TsData received but compression support is not built in".to_string(),
+        ))
+    }
 }
 
-/// Encode a frame with magic, length, type, and payload.
-fn encode_frame(msg_type: MessageType, payload: Bytes) -> Result<Bytes, ProtocolError> {
-    let payload_len = payload.len() as u32;
+/// Encode a frame with magic, length, type, payload and the optional CRC32.
+fn encode_frame(
+    msg_type: MessageType,
+    payload: Bytes,
+    options: FrameOptions,
+) -> Result<Bytes, ProtocolError> {
+    let crc_len = if options.crc32 { CRC_SIZE } else { 0 };
+    let payload_len = (payload.len() + crc_len) as u32;
     if payload_len > MAX_FRAME_SIZE {
         return Err(ProtocolError::FrameTooLarge(payload_len, MAX_FRAME_SIZE));
     }
 
-    let mut frame = BytesMut::with_capacity(HEADER_SIZE + payload.len());
+    let mut frame = BytesMut::with_capacity(HEADER_SIZE + payload_len as usize);
     frame.put_slice(&MAGIC);
     frame.put_u32_le(payload_len);
     frame.put_u16_le(msg_type.into());
     frame.put_slice(&payload);
+    if options.crc32 {
+        frame.put_u32_le(frame_crc32(&payload));
+    }
 
     Ok(frame.freeze())
 }
 
+/// Verify and remove the trailing CRC32 of a frame payload.
+///
+/// Returns the payload unchanged when CRC32 is not negotiated.
+pub fn strip_frame_crc(mut payload: Bytes, options: FrameOptions) -> Result<Bytes, ProtocolError> {
+    if !options.crc32 {
+        return Ok(payload);
+    }
+    if payload.len() < CRC_SIZE {
+        return Err(ProtocolError::IncompleteFrame {
+            expected: CRC_SIZE,
+            actual: payload.len(),
+        });
+    }
+    let mut trailer = payload.split_off(payload.len() - CRC_SIZE);
+    let expected = trailer.get_u32_le();
+    let actual = frame_crc32(&payload);
+    if expected != actual {
+        return Err(ProtocolError::ChecksumMismatch { expected, actual });
+    }
+    Ok(payload)
+}
+
 fn encode_optional_string(buf: &mut BytesMut, s: &Option<String>) {
     match s {
         Some(s) => {
@@ -368,6 +627,7 @@ fn encode_channel_filter(buf: &mut BytesMut, filter: &ChannelFilter) {
                 BroadcastType::Terrestrial => 0,
                 BroadcastType::BS => 1,
                 BroadcastType::CS => 2,
+                BroadcastType::Catv => 3,
             });
         }
         None => {
@@ -395,6 +655,7 @@ fn decode_channel_filter(buf: &mut Bytes) -> Result<ChannelFilter, ProtocolError
         Some(match buf.get_u8() {
             0 => BroadcastType::Terrestrial,
             1 => BroadcastType::BS,
+            3 => BroadcastType::Catv,
             _ => BroadcastType::CS,
         })
     } else {
@@ -478,6 +739,16 @@ fn encode_string(buf: &mut BytesMut, s: &str) {
     buf.put_slice(bytes);
 }
 
+fn decode_decode_status(value: u8) -> Result<DecodeStatus, ProtocolError> {
+    DecodeStatus::try_from(value)
+        .map_err(|v| ProtocolError::DecodeError(format!("unknown decode status {}", v)))
+}
+
+fn decode_tuner_entry_kind(value: u8) -> Result<TunerEntryKind, ProtocolError> {
+    TunerEntryKind::try_from(value)
+        .map_err(|v| ProtocolError::DecodeError(format!("unknown tuner entry kind {}", v)))
+}
+
 fn decode_string(buf: &mut Bytes) -> Result<String, ProtocolError> {
     if buf.remaining() < 2 {
         return Err(ProtocolError::IncompleteFrame {
@@ -538,8 +809,18 @@ pub fn decode_header(buf: &[u8]) -> Result<Option<FrameHeader>, ProtocolError> {
 /// The buffer should start at the payload (after the header).
 pub fn decode_client_message(
     msg_type: MessageType,
-    mut payload: Bytes,
+    payload: Bytes,
 ) -> Result<ClientMessage, ProtocolError> {
+    decode_client_message_with(msg_type, payload, FrameOptions::default())
+}
+
+/// Decode a client message, checking the CRC32 if negotiated.
+pub fn decode_client_message_with(
+    msg_type: MessageType,
+    payload: Bytes,
+    options: FrameOptions,
+) -> Result<ClientMessage, ProtocolError> {
+    let mut payload = strip_frame_crc(payload, options)?;
     match msg_type {
         MessageType::Hello => {
             if payload.remaining() < 2 {
@@ -549,9 +830,36 @@ pub fn decode_client_message(
                 });
             }
             let version = payload.get_u16_le();
-            Ok(ClientMessage::Hello { version })
+            // Older clients send no feature bits
+            let features = if payload.remaining() >= 4 { payload.get_u32_le() } else { 0 };
+            let auth_token = if payload.remaining() >= 2 {
+                let token_len = payload.get_u16_le() as usize;
+                if payload.remaining() < token_len {
+                    return Err(ProtocolError::IncompleteFrame {
+                        expected: token_len,
+                        actual: payload.remaining(),
+                    });
+                }
+                let token_bytes = payload.copy_to_bytes(token_len);
+                Some(
+                    String::from_utf8(token_bytes.to_vec())
+                        .map_err(|e| ProtocolError::DecodeError(e.to_string()))?,
+                )
+            } else {
+                None
+            };
+            Ok(ClientMessage::Hello { version, features, auth_token })
         }
         MessageType::Ping => Ok(ClientMessage::Ping),
+        MessageType::Pong => {
+            if payload.remaining() < 4 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 4,
+                    actual: payload.remaining(),
+                });
+            }
+            Ok(ClientMessage::Pong { seq: payload.get_u32_le() })
+        }
         MessageType::OpenTuner => {
             if payload.remaining() < 2 {
                 return Err(ProtocolError::IncompleteFrame {
@@ -604,7 +912,30 @@ pub fn decode_client_message(
             let exclusive = payload.get_u8() != 0;
             Ok(ClientMessage::SetChannelSpace { space, channel, priority, exclusive })
         }
+        MessageType::SetKeepAlive => {
+            if payload.remaining() < 4 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 4,
+                    actual: payload.remaining(),
+                });
+            }
+            let seconds = payload.get_u32_le();
+            Ok(ClientMessage::SetKeepAlive { seconds })
+        }
+        MessageType::SetDecode => {
+            if payload.remaining() < 1 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 1,
+                    actual: payload.remaining(),
+                });
+            }
+            let enable = payload.get_u8() != 0;
+            Ok(ClientMessage::SetDecode { enable })
+        }
         MessageType::GetSignalLevel => Ok(ClientMessage::GetSignalLevel),
+        MessageType::GetStreamStats => Ok(ClientMessage::GetStreamStats),
+        MessageType::GetTunerCapacity => Ok(ClientMessage::GetTunerCapacity),
+        MessageType::ListTuners => Ok(ClientMessage::ListTuners),
         MessageType::EnumTuningSpace => {
             if payload.remaining() < 4 {
                 return Err(ProtocolError::IncompleteFrame {
@@ -660,7 +991,29 @@ pub fn decode_client_message(
             } else {
                 None
             };
-            Ok(ClientMessage::SelectLogicalChannel { nid, tsid, sid })
+            let priority = if payload.remaining() >= 1 && payload.get_u8() != 0 {
+                if payload.remaining() < 4 {
+                    return Err(ProtocolError::IncompleteFrame {
+                        expected: 4,
+                        actual: payload.remaining(),
+                    });
+                }
+                Some(payload.get_i32_le())
+            } else {
+                None
+            };
+            let exclusive = if payload.remaining() >= 1 && payload.get_u8() != 0 {
+                if payload.remaining() < 1 {
+                    return Err(ProtocolError::IncompleteFrame {
+                        expected: 1,
+                        actual: payload.remaining(),
+                    });
+                }
+                Some(payload.get_u8() != 0)
+            } else {
+                None
+            };
+            Ok(ClientMessage::SelectLogicalChannel { nid, tsid, sid, priority, exclusive })
         }
         MessageType::GetChannelList => {
             if payload.remaining() < 1 {
@@ -687,6 +1040,40 @@ pub fn decode_client_message(
             let single_service = payload.get_u8() != 0;
             Ok(ClientMessage::SetServiceFilter { single_service })
         }
+        MessageType::SelectByEvent => {
+            if payload.remaining() < 6 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 6,
+                    actual: payload.remaining(),
+                });
+            }
+            let nid = payload.get_u16_le();
+            let sid = payload.get_u16_le();
+            let event_id = payload.get_u16_le();
+            Ok(ClientMessage::SelectByEvent { nid, sid, event_id })
+        }
+        MessageType::SetChannelByRemoteKey => {
+            if payload.remaining() < 2 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 2,
+                    actual: payload.remaining(),
+                });
+            }
+            let remote_key = payload.get_u8();
+            let has_space_hint = payload.get_u8() != 0;
+            let space_hint = if has_space_hint {
+                if payload.remaining() < 4 {
+                    return Err(ProtocolError::IncompleteFrame {
+                        expected: 4,
+                        actual: payload.remaining(),
+                    });
+                }
+                Some(payload.get_u32_le())
+            } else {
+                None
+            };
+            Ok(ClientMessage::SetChannelByRemoteKey { remote_key, space_hint })
+        }
         _ => Err(ProtocolError::UnknownMessageType(msg_type as u16)),
     }
 }
@@ -695,8 +1082,18 @@ pub fn decode_client_message(
 /// The buffer should start at the payload (after the header).
 pub fn decode_server_message(
     msg_type: MessageType,
-    mut payload: Bytes,
+    payload: Bytes,
+) -> Result<ServerMessage, ProtocolError> {
+    decode_server_message_with(msg_type, payload, FrameOptions::default())
+}
+
+/// Decode a server message, checking the CRC32 if negotiated.
+pub fn decode_server_message_with(
+    msg_type: MessageType,
+    payload: Bytes,
+    options: FrameOptions,
 ) -> Result<ServerMessage, ProtocolError> {
+    let mut payload = strip_frame_crc(payload, options)?;
     match msg_type {
         MessageType::HelloAck => {
             if payload.remaining() < 3 {
@@ -707,9 +1104,22 @@ pub fn decode_server_message(
             }
             let version = payload.get_u16_le();
             let success = payload.get_u8() != 0;
-            Ok(ServerMessage::HelloAck { version, success })
+            // Older servers send no feature bits
+            let features = if payload.remaining() >= 4 { payload.get_u32_le() } else { 0 };
+            Ok(ServerMessage::HelloAck { version, success, features })
         }
+        MessageType::AuthRequired => Ok(ServerMessage::AuthRequired),
+        MessageType::AuthFailed => Ok(ServerMessage::AuthFailed),
         MessageType::Pong => Ok(ServerMessage::Pong),
+        MessageType::Ping => {
+            if payload.remaining() < 4 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 4,
+                    actual: payload.remaining(),
+                });
+            }
+            Ok(ServerMessage::Ping { seq: payload.get_u32_le() })
+        }
         MessageType::OpenTunerAck => {
             if payload.remaining() < 4 {
                 return Err(ProtocolError::IncompleteFrame {
@@ -758,6 +1168,27 @@ pub fn decode_server_message(
             let error_code = payload.get_u16_le();
             Ok(ServerMessage::SetChannelSpaceAck { success, error_code })
         }
+        MessageType::SetKeepAliveAck => {
+            if payload.remaining() < 1 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 1,
+                    actual: payload.remaining(),
+                });
+            }
+            let success = payload.get_u8() != 0;
+            Ok(ServerMessage::SetKeepAliveAck { success })
+        }
+        MessageType::SetDecodeAck => {
+            if payload.remaining() < 2 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 2,
+                    actual: payload.remaining(),
+                });
+            }
+            let success = payload.get_u8() != 0;
+            let status = decode_decode_status(payload.get_u8())?;
+            Ok(ServerMessage::SetDecodeAck { success, status })
+        }
         MessageType::GetSignalLevelAck => {
             if payload.remaining() < 4 {
                 return Err(ProtocolError::IncompleteFrame {
@@ -768,6 +1199,72 @@ pub fn decode_server_message(
             let signal_level = payload.get_f32_le();
             Ok(ServerMessage::GetSignalLevelAck { signal_level })
         }
+        MessageType::StreamStats => {
+            if payload.remaining() < 21 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 21,
+                    actual: payload.remaining(),
+                });
+            }
+            Ok(ServerMessage::StreamStats {
+                signal_level: payload.get_f32_le(),
+                drop_rate: payload.get_f32_le(),
+                scramble_rate: payload.get_f32_le(),
+                error_rate: payload.get_f32_le(),
+                bitrate_mbps: payload.get_f32_le(),
+                decode_status: decode_decode_status(payload.get_u8())?,
+            })
+        }
+        MessageType::GetTunerCapacityAck => {
+            if payload.remaining() < 4 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 4,
+                    actual: payload.remaining(),
+                });
+            }
+            let count = payload.get_u32_le() as usize;
+            let mut drivers = Vec::with_capacity(count.min(payload.remaining() / 8));
+            for _ in 0..count {
+                let tuner_path = decode_string(&mut payload)?;
+                if payload.remaining() < 4 {
+                    return Err(ProtocolError::IncompleteFrame {
+                        expected: 4,
+                        actual: payload.remaining(),
+                    });
+                }
+                drivers.push(TunerCapacity {
+                    tuner_path,
+                    running: payload.get_u16_le(),
+                    max_instances: payload.get_u16_le(),
+                });
+            }
+            Ok(ServerMessage::GetTunerCapacityAck { drivers })
+        }
+        MessageType::TunerList => {
+            if payload.remaining() < 4 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 4,
+                    actual: payload.remaining(),
+                });
+            }
+            let count = payload.get_u32_le() as usize;
+            let mut entries = Vec::with_capacity(count.min(payload.remaining() / 5));
+            for _ in 0..count {
+                let name = decode_string(&mut payload)?;
+                if payload.remaining() < 3 {
+                    return Err(ProtocolError::IncompleteFrame {
+                        expected: 3,
+                        actual: payload.remaining(),
+                    });
+                }
+                entries.push(TunerListEntry {
+                    name,
+                    kind: decode_tuner_entry_kind(payload.get_u8())?,
+                    available_instances: payload.get_u16_le(),
+                });
+            }
+            Ok(ServerMessage::TunerList { entries })
+        }
         MessageType::EnumTuningSpaceAck => {
             let name = decode_optional_string(&mut payload)?;
             Ok(ServerMessage::EnumTuningSpaceAck { name })
@@ -867,6 +1364,18 @@ pub fn decode_server_message(
             let success = payload.get_u8() != 0;
             Ok(ServerMessage::SetServiceFilterAck { success })
         }
+        MessageType::TunerStatus => {
+            if payload.remaining() < 1 {
+                return Err(ProtocolError::IncompleteFrame {
+                    expected: 1,
+                    actual: payload.remaining(),
+                });
+            }
+            let state = TunerState::try_from(payload.get_u8())
+                .map_err(|v| ProtocolError::DecodeError(format!("unknown tuner state {}", v)))?;
+            let reason = decode_string(&mut payload)?;
+            Ok(ServerMessage::TunerStatus { state, reason })
+        }
         MessageType::Error => {
             if payload.remaining() < 4 {
                 return Err(ProtocolError::IncompleteFrame {
@@ -897,7 +1406,7 @@ mod tests {
 
     #[test]
     fn test_encode_decode_hello() {
-        let msg = ClientMessage::Hello { version: 1 };
+        let msg = ClientMessage::Hello { version: 1, features: FEATURE_TS_COMPRESSION, auth_token: None };
         let encoded = encode_client_message(&msg).unwrap();
 
         // Verify header
@@ -939,6 +1448,82 @@ mod tests {
         assert_eq!(decoded, msg);
     }
 
+    #[test]
+    fn test_hello_auth_token_roundtrip() {
+        let msg = ClientMessage::Hello { version: 1, features: 0, auth_token: Some("s3cret".to_string()) };
+        let encoded = encode_client_message(&msg).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(decode_client_message(header.message_type, payload).unwrap(), msg);
+
+        for msg in [ServerMessage::AuthRequired, ServerMessage::AuthFailed] {
+            let encoded = encode_server_message(&msg).unwrap();
+            let header = decode_header(&encoded).unwrap().unwrap();
+            assert_eq!(header.payload_len, 0);
+            let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+            assert_eq!(decode_server_message(header.message_type, payload).unwrap(), msg);
+        }
+    }
+
+    #[test]
+    fn test_decode_legacy_hello() {
+        // Hello/HelloAck without the feature bitfield
+        let hello = decode_client_message(MessageType::Hello, Bytes::from_static(&[1, 0])).unwrap();
+        assert_eq!(hello, ClientMessage::Hello { version: 1, features: 0, auth_token: None });
+
+        let ack = decode_server_message(MessageType::HelloAck, Bytes::from_static(&[1, 0, 1])).unwrap();
+        assert_eq!(ack, ServerMessage::HelloAck { version: 1, success: true, features: 0 });
+    }
+
+    #[test]
+    fn test_frame_crc32() {
+        let options = FrameOptions::from_features(FEATURE_FRAME_CRC32);
+        let msg = ServerMessage::SetChannelAck { success: true, error_code: 0 };
+        let encoded = encode_server_message_with(&msg, options).unwrap();
+
+        // The CRC is counted in the length; the header layout is unchanged
+        let plain = encode_server_message(&msg).unwrap();
+        assert_eq!(encoded.len(), plain.len() + CRC_SIZE);
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.payload_len as usize, plain.len() - HEADER_SIZE + CRC_SIZE);
+
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        let decoded = decode_server_message_with(header.message_type, payload, options).unwrap();
+        assert_eq!(decoded, msg);
+
+        let mut corrupted = encoded[HEADER_SIZE..].to_vec();
+        corrupted[0] ^= 0xFF;
+        let err = decode_server_message_with(header.message_type, Bytes::from(corrupted), options)
+            .unwrap_err();
+        assert!(matches!(err, ProtocolError::ChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn test_compressed_ts_frame_roundtrip() {
+        // Null packets compress well; a single short packet of noise does not
+        let mut null_packets = Vec::new();
+        for _ in 0..100 {
+            null_packets.extend_from_slice(&[0x47, 0x1F, 0xFF, 0x10]);
+            null_packets.extend_from_slice(&[0xFF; 184]);
+        }
+        let noise: Vec<u8> = (0..=255u8).collect();
+
+        for data in [null_packets, noise] {
+            let encoded = encode_compressed_ts_frame(&data, FrameOptions::default()).unwrap();
+            let header = decode_header(&encoded).unwrap().unwrap();
+            assert_eq!(header.message_type, MessageType::TsData);
+
+            let payload = &encoded[HEADER_SIZE..];
+            if cfg!(feature = "compression") && data.len() > 256 {
+                assert_eq!(payload[0] & TS_FLAG_ZSTD, TS_FLAG_ZSTD);
+                assert!(payload.len() < data.len() / 10);
+            } else {
+                assert_eq!(payload[0], 0);
+            }
+            assert_eq!(decode_compressed_ts_payload(payload).unwrap(), data);
+        }
+    }
+
     #[test]
     fn test_encode_decode_signal_level() {
         let msg = ServerMessage::GetSignalLevelAck { signal_level: 23.5 };
@@ -950,6 +1535,173 @@ mod tests {
         assert_eq!(decoded, msg);
     }
 
+    #[test]
+    fn test_encode_decode_set_keep_alive() {
+        let msg = ClientMessage::SetKeepAlive { seconds: 120 };
+        let encoded = encode_client_message(&msg).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::SetKeepAlive);
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(decode_client_message(header.message_type, payload).unwrap(), msg);
+
+        let msg = ServerMessage::SetKeepAliveAck { success: true };
+        let encoded = encode_server_message(&msg).unwrap();
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(decode_server_message(MessageType::SetKeepAliveAck, payload).unwrap(), msg);
+    }
+
+    #[test]
+    fn test_encode_decode_stream_stats() {
+        let encoded = encode_client_message(&ClientMessage::GetStreamStats).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::GetStreamStats);
+
+        let msg = ServerMessage::StreamStats {
+            signal_level: 18.2,
+            drop_rate: 0.5,
+            scramble_rate: 0.0,
+            error_rate: 0.25,
+            bitrate_mbps: 16.8,
+            decode_status: DecodeStatus::Active,
+        };
+        let encoded = encode_server_message(&msg).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::StreamStats);
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(decode_server_message(header.message_type, payload.clone()).unwrap(), msg);
+        assert!(decode_server_message(MessageType::StreamStats, payload.slice(..20)).is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_tuner_capacity() {
+        let encoded = encode_client_message(&ClientMessage::GetTunerCapacity).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::GetTunerCapacity);
+
+        let msg = ServerMessage::GetTunerCapacityAck {
+            drivers: vec![
+                TunerCapacity {
+                    tuner_path: "BonDriver_PX4-T0.dll".to_string(),
+                    running: 1,
+                    max_instances: 1,
+                },
+                TunerCapacity {
+                    tuner_path: "BonDriver_PX4-T1.dll".to_string(),
+                    running: 0,
+                    max_instances: 1,
+                },
+            ],
+        };
+        let encoded = encode_server_message(&msg).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::GetTunerCapacityAck);
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(decode_server_message(header.message_type, payload.clone()).unwrap(), msg);
+        assert!(decode_server_message(MessageType::GetTunerCapacityAck, payload.slice(..payload.len() - 2)).is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_server_ping() {
+        let encoded = encode_server_message(&ServerMessage::Ping { seq: 42 }).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::Ping);
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(
+            decode_server_message(header.message_type, payload).unwrap(),
+            ServerMessage::Ping { seq: 42 }
+        );
+
+        let encoded = encode_client_message(&ClientMessage::Pong { seq: 42 }).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::Pong);
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(
+            decode_client_message(header.message_type, payload.clone()).unwrap(),
+            ClientMessage::Pong { seq: 42 }
+        );
+        assert!(decode_client_message(MessageType::Pong, payload.slice(..2)).is_err());
+
+        // Keep-alive pings keep their empty payloads
+        assert_eq!(
+            decode_client_message(MessageType::Ping, Bytes::new()).unwrap(),
+            ClientMessage::Ping
+        );
+        assert_eq!(decode_server_message(MessageType::Pong, Bytes::new()).unwrap(), ServerMessage::Pong);
+    }
+
+    #[test]
+    fn test_encode_decode_tuner_list() {
+        let encoded = encode_client_message(&ClientMessage::ListTuners).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::ListTuners);
+
+        let msg = ServerMessage::TunerList {
+            entries: vec![
+                TunerListEntry {
+                    name: "PX4".to_string(),
+                    kind: TunerEntryKind::Group,
+                    available_instances: 3,
+                },
+                TunerListEntry {
+                    name: "BonDriver_PX4-T0.dll".to_string(),
+                    kind: TunerEntryKind::Driver,
+                    available_instances: 0,
+                },
+            ],
+        };
+        let encoded = encode_server_message(&msg).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::TunerList);
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(decode_server_message(header.message_type, payload.clone()).unwrap(), msg);
+        assert!(decode_server_message(MessageType::TunerList, payload.slice(..payload.len() - 1)).is_err());
+
+        // Unknown entry kinds are rejected
+        let mut bad = payload.to_vec();
+        let kind_offset = 4 + 2 + "PX4".len();
+        bad[kind_offset] = 9;
+        assert!(decode_server_message(MessageType::TunerList, Bytes::from(bad)).is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_set_decode() {
+        let msg = ClientMessage::SetDecode { enable: false };
+        let encoded = encode_client_message(&msg).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::SetDecode);
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(decode_client_message(header.message_type, payload).unwrap(), msg);
+
+        let msg = ServerMessage::SetDecodeAck { success: false, status: DecodeStatus::NoCard };
+        let encoded = encode_server_message(&msg).unwrap();
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        assert_eq!(decode_server_message(MessageType::SetDecodeAck, payload).unwrap(), msg);
+
+        let bad = Bytes::from_static(&[1, 9]);
+        assert!(decode_server_message(MessageType::SetDecodeAck, bad).is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_tuner_status() {
+        let msg = ServerMessage::TunerStatus {
+            state: TunerState::Preempted,
+            reason: "preempted by higher priority session".to_string(),
+        };
+        let encoded = encode_server_message(&msg).unwrap();
+
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::TunerStatus);
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        let decoded = decode_server_message(header.message_type, payload).unwrap();
+        assert_eq!(decoded, msg);
+
+        // Unknown states are rejected rather than misreported
+        let mut payload = BytesMut::new();
+        payload.put_u8(0x7F);
+        encode_string(&mut payload, "");
+        assert!(decode_server_message(MessageType::TunerStatus, payload.freeze()).is_err());
+    }
+
     #[test]
     fn test_invalid_magic() {
         let bad_frame = b"BADPxxxx\x00\x00";
@@ -957,6 +1709,24 @@ mod tests {
         assert!(matches!(result, Err(ProtocolError::InvalidMagic(_))));
     }
 
+    #[test]
+    fn test_frame_too_large() {
+        let mut header = BytesMut::new();
+        header.put_slice(&MAGIC);
+        header.put_u32_le(MAX_FRAME_SIZE + 1);
+        header.put_u16_le(MessageType::TsData.into());
+        let result = decode_header(&header);
+        assert_eq!(result.unwrap_err(), ProtocolError::FrameTooLarge(MAX_FRAME_SIZE + 1, MAX_FRAME_SIZE));
+
+        // Exactly the limit is still accepted
+        header[4..8].copy_from_slice(&MAX_FRAME_SIZE.to_le_bytes());
+        assert_eq!(decode_header(&header).unwrap().unwrap().payload_len, MAX_FRAME_SIZE);
+
+        let oversized = Bytes::from(vec![0u8; MAX_FRAME_SIZE as usize + 1]);
+        let result = encode_frame(MessageType::TsData, oversized, FrameOptions::default());
+        assert!(matches!(result, Err(ProtocolError::FrameTooLarge(..))));
+    }
+
     #[test]
     fn test_incomplete_header() {
         let partial = b"BNDP\x00";
@@ -971,6 +1741,8 @@ mod tests {
             nid: 0x7FE8,
             tsid: 32736,
             sid: Some(1024),
+            priority: None,
+            exclusive: None,
         };
         let encoded = encode_client_message(&msg).unwrap();
         let header = decode_header(&encoded).unwrap().unwrap();
@@ -983,12 +1755,32 @@ mod tests {
             nid: 0x7FE8,
             tsid: 32736,
             sid: None,
+            priority: None,
+            exclusive: None,
         };
         let encoded = encode_client_message(&msg).unwrap();
         let header = decode_header(&encoded).unwrap().unwrap();
         let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
         let decoded = decode_client_message(header.message_type, payload).unwrap();
         assert_eq!(decoded, msg);
+        // Without controls the payload keeps the original layout
+        assert_eq!(encoded.len() - HEADER_SIZE, 5);
+
+        // With priority/exclusive
+        for (priority, exclusive) in [(Some(-5), Some(true)), (None, Some(false)), (Some(10), None)] {
+            let msg = ClientMessage::SelectLogicalChannel {
+                nid: 0x0004,
+                tsid: 0x4010,
+                sid: Some(101),
+                priority,
+                exclusive,
+            };
+            let encoded = encode_client_message(&msg).unwrap();
+            let header = decode_header(&encoded).unwrap().unwrap();
+            let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+            let decoded = decode_client_message(header.message_type, payload).unwrap();
+            assert_eq!(decoded, msg);
+        }
     }
 
     #[test]
@@ -1021,6 +1813,37 @@ mod tests {
         assert_eq!(decoded, msg);
     }
 
+    #[test]
+    fn test_encode_decode_select_by_event() {
+        let msg = ClientMessage::SelectByEvent {
+            nid: 0x7FE8,
+            sid: 1024,
+            event_id: 0x1234,
+        };
+        let encoded = encode_client_message(&msg).unwrap();
+        let header = decode_header(&encoded).unwrap().unwrap();
+        assert_eq!(header.message_type, MessageType::SelectByEvent);
+        let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+        let decoded = decode_client_message(header.message_type, payload).unwrap();
+        assert_eq!(decoded, msg);
+    }
+
+    #[test]
+    fn test_encode_decode_set_channel_by_remote_key() {
+        for space_hint in [None, Some(2)] {
+            let msg = ClientMessage::SetChannelByRemoteKey {
+                remote_key: 4,
+                space_hint,
+            };
+            let encoded = encode_client_message(&msg).unwrap();
+            let header = decode_header(&encoded).unwrap().unwrap();
+            assert_eq!(header.message_type, MessageType::SetChannelByRemoteKey);
+            let payload = Bytes::copy_from_slice(&encoded[HEADER_SIZE..]);
+            let decoded = decode_client_message(header.message_type, payload).unwrap();
+            assert_eq!(decoded, msg);
+        }
+    }
+
     #[test]
     fn test_encode_decode_get_channel_list() {
         // Without filter
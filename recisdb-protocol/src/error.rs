@@ -29,6 +29,10 @@ pub enum ProtocolError {
     #[error("Failed to encode message: {0}")]
     EncodeError(String),
 
+    /// Frame payload does not match its CRC32 trailer.
+    #[error("Frame checksum mismatch: expected {expected:#010x}, got {actual:#010x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+
     /// Protocol version mismatch.
     #[error("Protocol version mismatch: client={client}, server={server}")]
     VersionMismatch { client: u16, server: u16 },
@@ -104,6 +108,18 @@ pub enum ErrorCode {
     InvalidParameter = 0x0007,
     /// Protocol error.
     ProtocolError = 0x0008,
+    /// Connection refused by a per-client connection limit.
+    TooManyConnections = 0x0009,
+    /// The client's identity is not allowed to use the requested tuner.
+    PermissionDenied = 0x000A,
+    /// The channel locked but the signal is missing or too weak.
+    NoSignal = 0x000B,
+    /// No enabled channel matches the request.
+    ChannelNotFound = 0x000C,
+    /// The BonDriver could not be loaded or opened.
+    DriverOpenFailed = 0x000D,
+    /// The tuner was taken over by another session while switching.
+    Preempted = 0x000E,
 }
 
 impl From<u16> for ErrorCode {
@@ -117,6 +133,12 @@ impl From<u16> for ErrorCode {
             0x0006 => ErrorCode::InvalidState,
             0x0007 => ErrorCode::InvalidParameter,
             0x0008 => ErrorCode::ProtocolError,
+            0x0009 => ErrorCode::TooManyConnections,
+            0x000A => ErrorCode::PermissionDenied,
+            0x000B => ErrorCode::NoSignal,
+            0x000C => ErrorCode::ChannelNotFound,
+            0x000D => ErrorCode::DriverOpenFailed,
+            0x000E => ErrorCode::Preempted,
             _ => ErrorCode::Unknown,
         }
     }
@@ -133,4 +155,68 @@ impl ErrorCode {
     pub fn is_success(self) -> bool {
         self == ErrorCode::Success
     }
+
+    /// Short human-readable description, for logs and UIs.
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::Success => "success",
+            ErrorCode::Unknown => "unknown error",
+            ErrorCode::TunerOpenFailed => "tuner could not be opened",
+            ErrorCode::ChannelSetFailed => "channel could not be set",
+            ErrorCode::TunerBusy => "all tuners are busy",
+            ErrorCode::NotAuthenticated => "not authenticated",
+            ErrorCode::InvalidState => "invalid session state",
+            ErrorCode::InvalidParameter => "invalid parameter",
+            ErrorCode::ProtocolError => "protocol error",
+            ErrorCode::TooManyConnections => "too many connections",
+            ErrorCode::PermissionDenied => "permission denied",
+            ErrorCode::NoSignal => "no signal or signal too weak",
+            ErrorCode::ChannelNotFound => "channel not found",
+            ErrorCode::DriverOpenFailed => "BonDriver could not be opened",
+            ErrorCode::Preempted => "tuner was preempted by another session",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_CODES: [ErrorCode; 15] = [
+        ErrorCode::Success,
+        ErrorCode::Unknown,
+        ErrorCode::TunerOpenFailed,
+        ErrorCode::ChannelSetFailed,
+        ErrorCode::TunerBusy,
+        ErrorCode::NotAuthenticated,
+        ErrorCode::InvalidState,
+        ErrorCode::InvalidParameter,
+        ErrorCode::ProtocolError,
+        ErrorCode::TooManyConnections,
+        ErrorCode::PermissionDenied,
+        ErrorCode::NoSignal,
+        ErrorCode::ChannelNotFound,
+        ErrorCode::DriverOpenFailed,
+        ErrorCode::Preempted,
+    ];
+
+    #[test]
+    fn test_error_code_round_trip() {
+        for code in ALL_CODES {
+            assert_eq!(ErrorCode::from(u16::from(code)), code);
+        }
+    }
+
+    #[test]
+    fn test_error_code_values_are_stable() {
+        assert_eq!(u16::from(ErrorCode::ChannelSetFailed), 0x0003);
+        assert_eq!(u16::from(ErrorCode::TunerBusy), 0x0004);
+        assert_eq!(u16::from(ErrorCode::PermissionDenied), 0x000A);
+        assert_eq!(u16::from(ErrorCode::NoSignal), 0x000B);
+        assert_eq!(u16::from(ErrorCode::ChannelNotFound), 0x000C);
+        assert_eq!(u16::from(ErrorCode::DriverOpenFailed), 0x000D);
+        assert_eq!(u16::from(ErrorCode::Preempted), 0x000E);
+        // Codes from newer servers decode as Unknown
+        assert_eq!(ErrorCode::from(0x00FF), ErrorCode::Unknown);
+    }
 }
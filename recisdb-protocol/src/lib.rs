@@ -20,7 +20,7 @@
 //! use bytes::Bytes;
 //!
 //! // Encode a message
-//! let msg = ClientMessage::Hello { version: 1 };
+//! let msg = ClientMessage::Hello { version: 1, features: 0, auth_token: None };
 //! let encoded = encode_client_message(&msg).unwrap();
 //!
 //! // Decode the header
@@ -64,14 +64,19 @@ pub mod error;
 pub mod types;
 
 pub use codec::{
-    decode_client_message, decode_header, decode_server_message, encode_client_message,
-    encode_server_message, FrameHeader, HEADER_SIZE,
+    decode_client_message, decode_client_message_with, decode_compressed_ts_payload,
+    decode_header, decode_server_message, decode_server_message_with, encode_client_message,
+    encode_client_message_with, encode_compressed_ts_frame, encode_server_message,
+    encode_server_message_with, frame_crc32, strip_frame_crc, supported_features, FrameHeader,
+    FrameOptions, CRC_SIZE, HEADER_SIZE, TS_FLAG_ZSTD,
 };
 pub use error::{ClientError, ErrorCode, ProtocolError, ServerError};
 pub use types::{
     // Existing types
     ChannelSpec, ClientMessage, MessageType, ServerMessage, MAGIC, MAX_FRAME_SIZE, MAX_TS_CHUNK_SIZE,
-    PROTOCOL_VERSION, BandType,
+    PROTOCOL_VERSION, FEATURE_FRAME_CRC32, FEATURE_TS_COMPRESSION, FEATURE_TUNER_STATUS,
+    FEATURE_SERVER_PING, BandType,
+    TunerState, DecodeStatus, TunerCapacity, TunerEntryKind, TunerListEntry,
     // New channel management types
     BroadcastType, ChannelFilter, ChannelInfo, ChannelKey, ChannelListMessage, ChannelSelector,
     ClientChannelInfo,
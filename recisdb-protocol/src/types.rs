@@ -5,6 +5,22 @@ use serde::{Deserialize, Serialize};
 /// Protocol version.
 pub const PROTOCOL_VERSION: u16 = 1;
 
+/// Feature bit: TsData payloads carry a compressed flag and may be zstd frames.
+///
+/// Exchanged in the `features` field of Hello/HelloAck; the server replies
+/// with the subset of the client's bits it agrees to.
+pub const FEATURE_TS_COMPRESSION: u32 = 0x0000_0001;
+
+/// Feature bit: every frame after the handshake carries a trailing CRC32.
+pub const FEATURE_FRAME_CRC32: u32 = 0x0000_0002;
+
+/// Feature bit: the client understands unsolicited TunerStatus messages.
+pub const FEATURE_TUNER_STATUS: u32 = 0x0000_0004;
+
+/// Feature bit: the client answers server Pings with a Pong, so the server
+/// can measure the round-trip time.
+pub const FEATURE_SERVER_PING: u32 = 0x0000_0008;
+
 /// Magic bytes for frame header: "BNDP" (BonDriver Network Protocol).
 pub const MAGIC: [u8; 4] = *b"BNDP";
 
@@ -51,7 +67,7 @@ impl BandType {
     /// - CS (110度): 0x0006, 0x0007
     /// - BS4K: 0x000B (高度BS), 0x000C (高度110度CS)
     /// - SKY (124/128度CS): 0x000A (SPHD), 0x0001, 0x0003
-    /// - CATV: 0xFFFE, 0xFFFA, 0xFFFD, 0xFFF9, 0xFFF7
+    /// - CATV: 0xFFF0-0xFFFF (0xFFFE, 0xFFFA, 0xFFFD, 0xFFF9, 0xFFF7 documented)
     ///
     /// ref: https://www.arib.or.jp/english/html/overview/doc/6-STD-B10v5_13-E1.pdf
     pub fn from_nid(nid: u16) -> Self {
@@ -76,12 +92,8 @@ impl BandType {
             0x000A | 0x0001 | 0x0003 => BandType::SKY,
 
             // Cable television (ケーブルテレビ)
-            // デジタル放送リマックス: 0xFFFE
-            // デジタル放送高度リマックス: 0xFFFA
-            // JC-HITSトランスモジュレーション: 0xFFFD
-            // 高度JC-HITSトランスモジュレーション: 0xFFF9
-            // 高度ケーブル自主放送: 0xFFF7
-            0xFFFE | 0xFFFA | 0xFFFD | 0xFFF9 | 0xFFF7 => BandType::CATV,
+            // See broadcast_region::CATV_NETWORKS for the documented IDs
+            nid if crate::broadcast_region::is_catv_nid(nid) => BandType::CATV,
 
             // Terrestrial digital broadcasting (地上デジタル放送)
             // 県複フラグ=0: 0x7C10 〜 0x7FEF
@@ -129,6 +141,10 @@ pub enum MessageType {
     Hello = 0x0001,
     /// Server hello response.
     HelloAck = 0x0002,
+    /// Server requires an auth token the client did not send.
+    AuthRequired = 0x0003,
+    /// Client sent a wrong auth token.
+    AuthFailed = 0x0004,
 
     // Tuner control (0x01xx)
     /// Open tuner request.
@@ -147,6 +163,14 @@ pub enum MessageType {
     SetChannelSpace = 0x0106,
     /// Set channel by space response.
     SetChannelSpaceAck = 0x0107,
+    /// Override the idle-close delay for this session's tuners.
+    SetKeepAlive = 0x0108,
+    /// Set keep-alive response.
+    SetKeepAliveAck = 0x0109,
+    /// Turn B25 decoding on or off for this session.
+    SetDecode = 0x010A,
+    /// Set decode response.
+    SetDecodeAck = 0x010B,
 
     // Tuner info (0x02xx)
     /// Get signal level request.
@@ -161,6 +185,20 @@ pub enum MessageType {
     EnumChannelName = 0x0204,
     /// Enumerate channel name response.
     EnumChannelNameAck = 0x0205,
+    /// Unsolicited tuner status change (server to client).
+    TunerStatus = 0x0206,
+    /// Get stream quality statistics request.
+    GetStreamStats = 0x0207,
+    /// Stream quality statistics response.
+    StreamStats = 0x0208,
+    /// Get per-driver instance capacity request.
+    GetTunerCapacity = 0x0209,
+    /// Per-driver instance capacity response.
+    GetTunerCapacityAck = 0x020A,
+    /// List selectable drivers and groups request.
+    ListTuners = 0x020B,
+    /// Selectable drivers and groups response.
+    TunerList = 0x020C,
 
     // Streaming (0x03xx)
     /// Start TS stream request.
@@ -197,6 +235,10 @@ pub enum MessageType {
     SetServiceFilter = 0x0504,
     /// Set service filter mode response.
     SetServiceFilterAck = 0x0505,
+    /// Select channel by EPG event (by NID/SID/event_id).
+    SelectByEvent = 0x0506,
+    /// Select channel by remote control key (1-12).
+    SetChannelByRemoteKey = 0x0507,
 
     // Misc (0xFFxx)
     /// Error response.
@@ -214,6 +256,8 @@ impl TryFrom<u16> for MessageType {
         match value {
             0x0001 => Ok(MessageType::Hello),
             0x0002 => Ok(MessageType::HelloAck),
+            0x0003 => Ok(MessageType::AuthRequired),
+            0x0004 => Ok(MessageType::AuthFailed),
             0x0100 => Ok(MessageType::OpenTuner),
             0x0101 => Ok(MessageType::OpenTunerAck),
             0x0102 => Ok(MessageType::CloseTuner),
@@ -222,12 +266,23 @@ impl TryFrom<u16> for MessageType {
             0x0105 => Ok(MessageType::SetChannelAck),
             0x0106 => Ok(MessageType::SetChannelSpace),
             0x0107 => Ok(MessageType::SetChannelSpaceAck),
+            0x0108 => Ok(MessageType::SetKeepAlive),
+            0x0109 => Ok(MessageType::SetKeepAliveAck),
+            0x010A => Ok(MessageType::SetDecode),
+            0x010B => Ok(MessageType::SetDecodeAck),
             0x0200 => Ok(MessageType::GetSignalLevel),
             0x0201 => Ok(MessageType::GetSignalLevelAck),
             0x0202 => Ok(MessageType::EnumTuningSpace),
             0x0203 => Ok(MessageType::EnumTuningSpaceAck),
             0x0204 => Ok(MessageType::EnumChannelName),
             0x0205 => Ok(MessageType::EnumChannelNameAck),
+            0x0206 => Ok(MessageType::TunerStatus),
+            0x0207 => Ok(MessageType::GetStreamStats),
+            0x0208 => Ok(MessageType::StreamStats),
+            0x0209 => Ok(MessageType::GetTunerCapacity),
+            0x020A => Ok(MessageType::GetTunerCapacityAck),
+            0x020B => Ok(MessageType::ListTuners),
+            0x020C => Ok(MessageType::TunerList),
             0x0300 => Ok(MessageType::StartStream),
             0x0301 => Ok(MessageType::StartStreamAck),
             0x0302 => Ok(MessageType::StopStream),
@@ -243,6 +298,8 @@ impl TryFrom<u16> for MessageType {
             0x0503 => Ok(MessageType::GetChannelListAck),
             0x0504 => Ok(MessageType::SetServiceFilter),
             0x0505 => Ok(MessageType::SetServiceFilterAck),
+            0x0506 => Ok(MessageType::SelectByEvent),
+            0x0507 => Ok(MessageType::SetChannelByRemoteKey),
             0xFF00 => Ok(MessageType::Error),
             0xFF01 => Ok(MessageType::Ping),
             0xFF02 => Ok(MessageType::Pong),
@@ -257,6 +314,111 @@ impl From<MessageType> for u16 {
     }
 }
 
+/// Tuner state reported in [`ServerMessage::TunerStatus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum TunerState {
+    /// The tuner was taken over for a higher-priority session; the stream
+    /// ends and the connection is closed.
+    Preempted = 1,
+    /// The driver stopped delivering TS and its reader was restarted.
+    Stalled = 2,
+    /// The tuner was retuned to another channel underneath the session.
+    ChannelChanged = 3,
+}
+
+impl TryFrom<u8> for TunerState {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            1 => Ok(TunerState::Preempted),
+            2 => Ok(TunerState::Stalled),
+            3 => Ok(TunerState::ChannelChanged),
+            _ => Err(value),
+        }
+    }
+}
+
+/// B25 decode state of a session's stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum DecodeStatus {
+    /// The stream is descrambled by the server.
+    Active = 1,
+    /// The client asked for the raw (scrambled) TS.
+    Disabled = 2,
+    /// No card reader / decoder is available; the TS is passed through.
+    NoCard = 3,
+    /// The decoder failed on this stream; the TS is passed through.
+    Error = 4,
+}
+
+impl TryFrom<u8> for DecodeStatus {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            1 => Ok(DecodeStatus::Active),
+            2 => Ok(DecodeStatus::Disabled),
+            3 => Ok(DecodeStatus::NoCard),
+            4 => Ok(DecodeStatus::Error),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Instance usage of one BonDriver, as reported by `GetTunerCapacityAck`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TunerCapacity {
+    /// BonDriver DLL path.
+    pub tuner_path: String,
+    /// Instances currently running on this driver.
+    pub running: u16,
+    /// Maximum concurrent instances (`max_instances`).
+    pub max_instances: u16,
+}
+
+impl TunerCapacity {
+    /// Whether a new channel on this driver would need to evict another one.
+    pub fn is_full(&self) -> bool {
+        self.running >= self.max_instances
+    }
+}
+
+/// What a `TunerList` entry names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum TunerEntryKind {
+    /// A BonDriver path, opened with `OpenTuner`.
+    Driver = 0,
+    /// A group name, opened with `OpenTunerWithGroup`.
+    Group = 1,
+}
+
+impl TryFrom<u8> for TunerEntryKind {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0 => Ok(TunerEntryKind::Driver),
+            1 => Ok(TunerEntryKind::Group),
+            _ => Err(value),
+        }
+    }
+}
+
+/// A driver or group a client can open, as reported by `TunerList`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TunerListEntry {
+    /// BonDriver path or group name.
+    pub name: String,
+    pub kind: TunerEntryKind,
+    /// Instances that can start without evicting another channel
+    /// (summed over the members for a group).
+    pub available_instances: u16,
+}
+
 /// Channel specification for tuning.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChannelSpec {
@@ -269,10 +431,13 @@ pub enum ChannelSpec {
 /// Messages sent from client to server.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ClientMessage {
-    /// Client hello with protocol version.
-    Hello { version: u16 },
+    /// Client hello with protocol version, requested feature bits and the
+    /// shared-secret token (when the server is configured with one).
+    Hello { version: u16, features: u32, auth_token: Option<String> },
     /// Ping for keep-alive.
     Ping,
+    /// Answer to a server `Ping`, echoing its sequence number.
+    Pong { seq: u32 },
     /// Open a tuner by path.
     OpenTuner { tuner_path: String },
     /// Open a tuner by group name (auto-select driver from group).
@@ -285,8 +450,20 @@ pub enum ClientMessage {
     SetChannelSpace { space: u32, channel: u32, priority: i32, exclusive: bool },
     /// Set channel by space within a group (auto-select driver).
     SetChannelSpaceInGroup { group_name: String, space_idx: u32, channel: u32, priority: i32, exclusive: bool },
+    /// Keep tuners this session releases open for `seconds` instead of the
+    /// server's keep-alive (0 = release immediately).
+    SetKeepAlive { seconds: u32 },
+    /// Receive the descrambled (true, default) or raw scrambled TS.
+    SetDecode { enable: bool },
     /// Get signal level.
     GetSignalLevel,
+    /// Get signal level and TS quality statistics in one request.
+    GetStreamStats,
+    /// Get running/max instances for the session's driver, or for every
+    /// driver of its group.
+    GetTunerCapacity,
+    /// List the drivers and groups this server can open.
+    ListTuners,
     /// Enumerate tuning space.
     EnumTuningSpace { space: u32 },
     /// Enumerate channel name.
@@ -305,6 +482,10 @@ pub enum ClientMessage {
         tsid: u16,
         /// Optional SID filter
         sid: Option<u16>,
+        /// Channel priority, as in `SetChannelSpace` (None = channel default).
+        priority: Option<i32>,
+        /// Exclusive access request (None = not exclusive).
+        exclusive: Option<bool>,
     },
     /// Get channel list from server.
     GetChannelList {
@@ -316,15 +497,39 @@ pub enum ClientMessage {
     SetServiceFilter {
         single_service: bool,
     },
+    /// Select the channel airing an EPG event (resolved from stored EIT).
+    /// Answered with `SelectLogicalChannelAck`.
+    SelectByEvent {
+        nid: u16,
+        sid: u16,
+        event_id: u16,
+    },
+    /// Select a channel by its remote control key, as a TV remote would.
+    /// `space_hint` is a tuning space index from `EnumTuningSpace`; without
+    /// it the first space with a matching service is used.
+    /// Answered with `SelectLogicalChannelAck`.
+    SetChannelByRemoteKey {
+        remote_key: u8,
+        space_hint: Option<u32>,
+    },
 }
 
 /// Messages sent from server to client.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ServerMessage {
-    /// Server hello response.
-    HelloAck { version: u16, success: bool },
+    /// Server hello response with the accepted feature bits.
+    HelloAck { version: u16, success: bool, features: u32 },
+    /// The server requires an auth token; sent instead of HelloAck before
+    /// closing the connection.
+    AuthRequired,
+    /// The auth token was wrong; sent instead of HelloAck before closing the
+    /// connection.
+    AuthFailed,
     /// Pong response to ping.
     Pong,
+    /// Latency probe, only sent to clients that negotiated
+    /// [`FEATURE_SERVER_PING`].
+    Ping { seq: u32 },
     /// Open tuner response.
     OpenTunerAck {
         success: bool,
@@ -337,8 +542,27 @@ pub enum ServerMessage {
     SetChannelAck { success: bool, error_code: u16 },
     /// Set channel by space response.
     SetChannelSpaceAck { success: bool, error_code: u16 },
+    /// Set keep-alive response.
+    SetKeepAliveAck { success: bool },
+    /// Set decode response; `success` is false when decoding was requested
+    /// but is not available, in which case the raw TS is streamed.
+    SetDecodeAck { success: bool, status: DecodeStatus },
     /// Signal level response.
     GetSignalLevelAck { signal_level: f32 },
+    /// Stream quality response. Rates are percentages of the packets analyzed
+    /// over the session; the bitrate covers the last second.
+    StreamStats {
+        signal_level: f32,
+        drop_rate: f32,
+        scramble_rate: f32,
+        error_rate: f32,
+        bitrate_mbps: f32,
+        decode_status: DecodeStatus,
+    },
+    /// Tuner capacity response, one entry per driver.
+    GetTunerCapacityAck { drivers: Vec<TunerCapacity> },
+    /// Drivers and groups response, groups first.
+    TunerList { entries: Vec<TunerListEntry> },
     /// Enumerate tuning space response.
     EnumTuningSpaceAck { name: Option<String> },
     /// Enumerate channel name response.
@@ -371,6 +595,9 @@ pub enum ServerMessage {
     },
     /// Set service filter mode response.
     SetServiceFilterAck { success: bool },
+    /// Unsolicited tuner status change, only sent to clients that negotiated
+    /// [`FEATURE_TUNER_STATUS`].
+    TunerStatus { state: TunerState, reason: String },
     /// Error response.
     Error { error_code: u16, message: String },
 }
@@ -381,13 +608,19 @@ impl ClientMessage {
         match self {
             ClientMessage::Hello { .. } => MessageType::Hello,
             ClientMessage::Ping => MessageType::Ping,
+            ClientMessage::Pong { .. } => MessageType::Pong,
             ClientMessage::OpenTuner { .. } => MessageType::OpenTuner,
             ClientMessage::OpenTunerWithGroup { .. } => MessageType::OpenTuner,
             ClientMessage::CloseTuner => MessageType::CloseTuner,
             ClientMessage::SetChannel { .. } => MessageType::SetChannel,
             ClientMessage::SetChannelSpace { .. } => MessageType::SetChannelSpace,
             ClientMessage::SetChannelSpaceInGroup { .. } => MessageType::SetChannelSpace,
+            ClientMessage::SetKeepAlive { .. } => MessageType::SetKeepAlive,
+            ClientMessage::SetDecode { .. } => MessageType::SetDecode,
             ClientMessage::GetSignalLevel => MessageType::GetSignalLevel,
+            ClientMessage::GetStreamStats => MessageType::GetStreamStats,
+            ClientMessage::GetTunerCapacity => MessageType::GetTunerCapacity,
+            ClientMessage::ListTuners => MessageType::ListTuners,
             ClientMessage::EnumTuningSpace { .. } => MessageType::EnumTuningSpace,
             ClientMessage::EnumChannelName { .. } => MessageType::EnumChannelName,
             ClientMessage::StartStream => MessageType::StartStream,
@@ -397,6 +630,8 @@ impl ClientMessage {
             ClientMessage::SelectLogicalChannel { .. } => MessageType::SelectLogicalChannel,
             ClientMessage::GetChannelList { .. } => MessageType::GetChannelList,
             ClientMessage::SetServiceFilter { .. } => MessageType::SetServiceFilter,
+            ClientMessage::SelectByEvent { .. } => MessageType::SelectByEvent,
+            ClientMessage::SetChannelByRemoteKey { .. } => MessageType::SetChannelByRemoteKey,
         }
     }
 }
@@ -406,12 +641,20 @@ impl ServerMessage {
     pub fn message_type(&self) -> MessageType {
         match self {
             ServerMessage::HelloAck { .. } => MessageType::HelloAck,
+            ServerMessage::AuthRequired => MessageType::AuthRequired,
+            ServerMessage::AuthFailed => MessageType::AuthFailed,
             ServerMessage::Pong => MessageType::Pong,
+            ServerMessage::Ping { .. } => MessageType::Ping,
             ServerMessage::OpenTunerAck { .. } => MessageType::OpenTunerAck,
             ServerMessage::CloseTunerAck { .. } => MessageType::CloseTunerAck,
             ServerMessage::SetChannelAck { .. } => MessageType::SetChannelAck,
             ServerMessage::SetChannelSpaceAck { .. } => MessageType::SetChannelSpaceAck,
+            ServerMessage::SetKeepAliveAck { .. } => MessageType::SetKeepAliveAck,
+            ServerMessage::SetDecodeAck { .. } => MessageType::SetDecodeAck,
             ServerMessage::GetSignalLevelAck { .. } => MessageType::GetSignalLevelAck,
+            ServerMessage::StreamStats { .. } => MessageType::StreamStats,
+            ServerMessage::GetTunerCapacityAck { .. } => MessageType::GetTunerCapacityAck,
+            ServerMessage::TunerList { .. } => MessageType::TunerList,
             ServerMessage::EnumTuningSpaceAck { .. } => MessageType::EnumTuningSpaceAck,
             ServerMessage::EnumChannelNameAck { .. } => MessageType::EnumChannelNameAck,
             ServerMessage::StartStreamAck { .. } => MessageType::StartStreamAck,
@@ -422,6 +665,7 @@ impl ServerMessage {
             ServerMessage::SelectLogicalChannelAck { .. } => MessageType::SelectLogicalChannelAck,
             ServerMessage::GetChannelListAck { .. } => MessageType::GetChannelListAck,
             ServerMessage::SetServiceFilterAck { .. } => MessageType::SetServiceFilterAck,
+            ServerMessage::TunerStatus { .. } => MessageType::TunerStatus,
             ServerMessage::Error { .. } => MessageType::Error,
         }
     }
@@ -448,6 +692,10 @@ pub struct ChannelInfo {
     pub raw_name: Option<String>,
     /// Normalized channel name
     pub channel_name: Option<String>,
+    /// `channel_name` was derived by the scanner (no usable service name in
+    /// the SDT) rather than provided by the broadcaster
+    #[serde(default)]
+    pub name_is_synthetic: bool,
     /// Physical channel number (from NIT)
     pub physical_ch: Option<u8>,
     /// Remote control key ID (from NIT)
@@ -456,6 +704,9 @@ pub struct ChannelInfo {
     pub service_type: Option<u8>,
     /// Network name (from NIT)
     pub network_name: Option<String>,
+    /// TS name (from NIT TS information descriptor, e.g. "ＮＨＫ総合・東京")
+    #[serde(default)]
+    pub ts_name: Option<String>,
 
     /// BonDriver Space number (recorded during scan)
     pub bon_space: Option<u32>,
@@ -466,6 +717,9 @@ pub struct ChannelInfo {
     pub band_type: Option<u8>,
     /// Terrestrial region name (e.g., "福島", "宮城") - for Terrestrial only
     pub terrestrial_region: Option<String>,
+    /// 1seg (partial reception) service flag (from NIT partial reception descriptor)
+    #[serde(default)]
+    pub is_one_seg: bool,
 }
 
 impl ChannelInfo {
@@ -478,14 +732,17 @@ impl ChannelInfo {
             manual_sheet: None,
             raw_name: None,
             channel_name: None,
+            name_is_synthetic: false,
             physical_ch: None,
             remote_control_key: None,
             service_type: None,
             network_name: None,
+            ts_name: None,
             bon_space: None,
             bon_channel: None,
             band_type: None,
             terrestrial_region: None,
+            is_one_seg: false,
         }
     }
 
@@ -595,6 +852,8 @@ pub enum BroadcastType {
     BS,
     /// CS digital (CS1, CS2)
     CS,
+    /// Cable television (ケーブルテレビ: リマックス, トランスモジュレーション, 自主放送)
+    Catv,
 }
 
 /// Channel key for identifying removed channels in updates.
@@ -668,6 +927,8 @@ mod tests {
         let types = [
             MessageType::Hello,
             MessageType::HelloAck,
+            MessageType::AuthRequired,
+            MessageType::AuthFailed,
             MessageType::OpenTuner,
             MessageType::TsData,
             MessageType::Error,
@@ -8,27 +8,91 @@ use tokio::time::interval;
 
 use crate::database::AlertRuleRecord;
 use crate::server::listener::DatabaseHandle;
-use crate::web::SessionRegistry;
+use crate::web::{SessionEvent, SessionRegistry};
 
 #[cfg(feature = "webhook")]
 use reqwest::Client;
 
+#[cfg(feature = "email")]
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+/// Connection security for the SMTP server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (port 587).
+    #[default]
+    StartTls,
+    /// Implicit TLS (port 465).
+    Tls,
+    /// No encryption (local relays only).
+    None,
+}
+
+impl SmtpSecurity {
+    /// Parse a config value (`starttls`, `tls` or `none`).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "starttls" => Some(Self::StartTls),
+            "tls" | "ssl" => Some(Self::Tls),
+            "none" | "plain" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// SMTP settings for `email` alert rules.
+#[derive(Debug, Clone, Default)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// Defaults to the standard port for `security`.
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Sender address.
+    pub from: String,
+    pub security: SmtpSecurity,
+}
+
 /// Alert manager task.
 pub struct AlertManager {
     database: DatabaseHandle,
     session_registry: Arc<SessionRegistry>,
     #[cfg(feature = "webhook")]
     webhook_sender: WebhookSender,
+    #[cfg(feature = "email")]
+    email_sender: Option<EmailSender>,
 }
 
 impl AlertManager {
     /// Create a new alert manager.
-    pub fn new(database: DatabaseHandle, session_registry: Arc<SessionRegistry>) -> Self {
+    ///
+    /// `smtp` is only needed for rules whose `webhook_format` is `email`.
+    pub fn new(
+        database: DatabaseHandle,
+        session_registry: Arc<SessionRegistry>,
+        smtp: Option<SmtpConfig>,
+    ) -> Self {
+        #[cfg(not(feature = "email"))]
+        if smtp.is_some() {
+            warn!("SMTP is configured but this build lacks the `email` feature; email alerts are disabled");
+        }
         Self {
             database,
             session_registry,
             #[cfg(feature = "webhook")]
             webhook_sender: WebhookSender::new(),
+            #[cfg(feature = "email")]
+            email_sender: smtp.and_then(|config| match EmailSender::new(&config) {
+                Ok(sender) => Some(sender),
+                Err(e) => {
+                    warn!("Email alerts disabled: {}", e);
+                    None
+                }
+            }),
         }
     }
 
@@ -59,6 +123,18 @@ impl AlertManager {
                 let active = db.get_active_alert_for_rule_session(rule.id, Some(session.id as i64))?;
 
                 if triggered && active.is_none() {
+                    // A metric hovering at the threshold resolves and re-fires
+                    // every cycle; keep quiet until the cooldown has passed.
+                    let now = chrono::Utc::now().timestamp();
+                    if rule.cooldown_secs > 0 {
+                        if let Some(last) = db.get_last_alert_triggered_at(rule.id, Some(session.id as i64))? {
+                            if now - last < rule.cooldown_secs {
+                                debug!("Alert suppressed by cooldown: rule={} session={}", rule.name, session.id);
+                                continue;
+                            }
+                        }
+                    }
+
                     let message = format!(
                         "{} {} {} (value={:.2})",
                         rule.metric, rule.condition, rule.threshold, value
@@ -66,24 +142,47 @@ impl AlertManager {
                     let alert_id = db.insert_alert_history(
                         rule.id,
                         Some(session.id as i64),
-                        chrono::Utc::now().timestamp(),
+                        now,
                         Some(value),
                         Some(&message),
                     )?;
 
                     info!("Alert triggered: rule={} session={} id={}", rule.name, session.id, alert_id);
+                    self.session_registry.publish(SessionEvent::Alert {
+                        alert_id,
+                        rule_name: rule.name.clone(),
+                        session_id: session.id,
+                        severity: rule.severity.clone(),
+                        message: message.clone(),
+                        resolved: false,
+                    });
 
-                    #[cfg(feature = "webhook")]
-                    if let Some(url) = rule.webhook_url.as_deref() {
-                        let format = rule.webhook_format.as_deref().unwrap_or("generic");
-                        if let Err(e) = self.webhook_sender.send_alert(url, format, &rule, session.id, value, &message).await {
-                            warn!("Webhook send failed: {}", e);
+                    self.notify(rule, session, value, &message, false).await;
+                    // Critical rules may also act on the offending session or driver
+                    if rule.severity == "critical" {
+                        match rule.critical_action.as_deref() {
+                            Some("disconnect") => {
+                                warn!("Critical alert '{}': disconnecting session {}", rule.name, session.id);
+                                self.session_registry.request_shutdown(session.id).await;
+                            }
+                            Some("disable_auto_scan") => disable_auto_scan(&db, rule, session),
+                            _ => {}
                         }
                     }
                 } else if !triggered {
                     if let Some(active_alert) = active {
                         db.resolve_alert_history(active_alert.id, chrono::Utc::now().timestamp())?;
                         debug!("Alert resolved: rule={} session={}", rule.name, session.id);
+                        let message = active_alert.message.unwrap_or_default();
+                        self.session_registry.publish(SessionEvent::Alert {
+                            alert_id: active_alert.id,
+                            rule_name: rule.name.clone(),
+                            session_id: session.id,
+                            severity: rule.severity.clone(),
+                            message: message.clone(),
+                            resolved: true,
+                        });
+                        self.notify(rule, session, value, &message, true).await;
                     }
                 }
             }
@@ -91,6 +190,121 @@ impl AlertManager {
 
         Ok(())
     }
+
+    /// Deliver a fired or resolved alert to the rule's webhook or mailbox.
+    async fn notify(
+        &self,
+        rule: &AlertRuleRecord,
+        session: &crate::web::SessionInfo,
+        value: f64,
+        message: &str,
+        resolved: bool,
+    ) {
+        let format = rule.webhook_format.as_deref().unwrap_or("generic");
+        if format == "email" {
+            self.send_email_alert(rule, session, value, resolved);
+            return;
+        }
+        #[cfg(feature = "webhook")]
+        if let Some(url) = rule.webhook_url.as_deref() {
+            if let Err(e) = self.webhook_sender.send_alert(url, format, rule, session.id, value, message, resolved).await {
+                warn!("Webhook send failed: {}", e);
+            }
+        }
+        #[cfg(not(feature = "webhook"))]
+        let _ = message;
+    }
+
+    /// Queue an email for an `email` rule.
+    ///
+    /// The SMTP exchange runs on its own task so a slow mail server never
+    /// delays rule evaluation.
+    #[cfg(feature = "email")]
+    fn send_email_alert(&self, rule: &AlertRuleRecord, session: &crate::web::SessionInfo, value: f64, resolved: bool) {
+        let Some(sender) = &self.email_sender else {
+            warn!("Email alert for rule '{}' skipped: [alert] smtp_host is not configured", rule.name);
+            return;
+        };
+        let recipients = parse_mailto(rule.webhook_url.as_deref().unwrap_or_default());
+        if recipients.is_empty() {
+            warn!("Email alert for rule '{}' skipped: no recipients in webhook_url", rule.name);
+            return;
+        }
+        let message = match sender.build_message(&recipients, rule, session, value, resolved) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Email alert for rule '{}' skipped: {}", rule.name, e);
+                return;
+            }
+        };
+        let transport = sender.transport.clone();
+        let rule_name = rule.name.clone();
+        tokio::spawn(async move {
+            match transport.send(message).await {
+                Ok(_) => debug!("Alert email sent: rule={}", rule_name),
+                Err(e) => warn!("Alert email send failed: rule={}: {}", rule_name, e),
+            }
+        });
+    }
+
+    #[cfg(not(feature = "email"))]
+    fn send_email_alert(&self, rule: &AlertRuleRecord, _session: &crate::web::SessionInfo, _value: f64, _resolved: bool) {
+        warn!("Email alert for rule '{}' skipped: built without the `email` feature", rule.name);
+    }
+}
+
+/// Turn off auto-scan for the driver a session is using.
+fn disable_auto_scan(db: &crate::database::Database, rule: &AlertRuleRecord, session: &crate::web::SessionInfo) {
+    let Some(path) = session.tuner_path.as_deref() else {
+        return;
+    };
+    match db.get_bon_driver_by_path(path) {
+        Ok(Some(driver)) if driver.auto_scan_enabled => {
+            warn!("Critical alert '{}': disabling auto-scan for {}", rule.name, path);
+            if let Err(e) = db.update_scan_config(driver.id, Some(false), None, None, None) {
+                warn!("Failed to disable auto-scan for {}: {}", path, e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to look up driver {}: {}", path, e),
+    }
+}
+
+/// Split an email rule's destination (`mailto:a@example.com,b@example.com`)
+/// into addresses.
+#[cfg_attr(not(feature = "email"), allow(dead_code))]
+fn parse_mailto(url: &str) -> Vec<String> {
+    let url = url.trim();
+    let addresses = url
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map_or(url, |_| &url[7..]);
+    // Drop any `?subject=...` style parameters
+    let addresses = addresses.split('?').next().unwrap_or_default();
+    addresses
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Plain-text body of an alert email.
+#[cfg_attr(not(feature = "email"), allow(dead_code))]
+fn format_email_body(rule: &AlertRuleRecord, session: &crate::web::SessionInfo, value: f64, resolved: bool) -> String {
+    let mut body = format!(
+        "{}: {}\nSeverity: {}\nMetric: {}\nCondition: {} {}\nValue: {:.2}\n\nSession: {}\nClient: {}\n",
+        if resolved { "Resolved" } else { "Alert" },
+        rule.name, rule.severity, rule.metric, rule.condition, rule.threshold, value, session.id, session.addr
+    );
+    if let Some(path) = &session.tuner_path {
+        body.push_str(&format!("Driver: {}\n", path));
+    }
+    if let Some(channel) = session.channel_name.as_ref().or(session.channel_info.as_ref()) {
+        body.push_str(&format!("Channel: {}\n", channel));
+    }
+    body.push_str(&format!("Time: {}\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")));
+    body
 }
 
 fn metric_value(rule: &AlertRuleRecord, session: &crate::web::SessionInfo) -> Option<f64> {
@@ -133,6 +347,7 @@ impl WebhookSender {
         Self { client: Client::new() }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn send_alert(
         &self,
         url: &str,
@@ -141,12 +356,14 @@ impl WebhookSender {
         session_id: u64,
         metric_value: f64,
         message: &str,
+        resolved: bool,
     ) -> crate::database::Result<()> {
+        let title = format!("{}: {}", if resolved { "Resolved" } else { "Alert" }, rule.name);
         let payload = match format {
-            "discord" => self.format_discord_payload(rule, session_id, metric_value, message),
-            "slack" => self.format_slack_payload(rule, session_id, metric_value, message),
-            "line" => self.format_line_payload(rule, session_id, metric_value, message),
-            _ => self.format_generic_payload(rule, session_id, metric_value, message),
+            "discord" => self.format_discord_payload(&title, rule, session_id, metric_value, message, resolved),
+            "slack" => self.format_slack_payload(&title, rule, session_id, metric_value, message),
+            "line" => self.format_line_payload(&title, rule, session_id, metric_value, message),
+            _ => self.format_generic_payload(rule, session_id, metric_value, message, resolved),
         };
 
         self.client.post(url).json(&payload).send().await.map_err(|e| {
@@ -157,17 +374,20 @@ impl WebhookSender {
 
     fn format_discord_payload(
         &self,
+        title: &str,
         rule: &AlertRuleRecord,
         session_id: u64,
         metric_value: f64,
         message: &str,
+        resolved: bool,
     ) -> serde_json::Value {
         serde_json::json!({
             "embeds": [{
-                "title": format!("Alert: {}", rule.name),
+                "title": title,
                 "description": message,
-                "color": 15158332,
+                "color": if resolved { 3066993 } else { severity_color(&rule.severity) },
                 "fields": [
+                    {"name": "Severity", "value": rule.severity, "inline": true},
                     {"name": "Session", "value": session_id.to_string(), "inline": true},
                     {"name": "Metric", "value": rule.metric, "inline": true},
                     {"name": "Value", "value": format!("{:.2}", metric_value), "inline": true}
@@ -178,6 +398,7 @@ impl WebhookSender {
 
     fn format_slack_payload(
         &self,
+        title: &str,
         rule: &AlertRuleRecord,
         session_id: u64,
         metric_value: f64,
@@ -187,11 +408,12 @@ impl WebhookSender {
             "blocks": [
                 {
                     "type": "section",
-                    "text": {"type": "mrkdwn", "text": format!("*Alert:* {}", rule.name)}
+                    "text": {"type": "mrkdwn", "text": format!("*{}*", title)}
                 },
                 {
                     "type": "section",
                     "fields": [
+                        {"type": "mrkdwn", "text": format!("*Severity:* {}", rule.severity)},
                         {"type": "mrkdwn", "text": format!("*Session:* {}", session_id)},
                         {"type": "mrkdwn", "text": format!("*Metric:* {}", rule.metric)},
                         {"type": "mrkdwn", "text": format!("*Value:* {:.2}", metric_value)}
@@ -207,13 +429,14 @@ impl WebhookSender {
 
     fn format_line_payload(
         &self,
+        title: &str,
         rule: &AlertRuleRecord,
         session_id: u64,
         metric_value: f64,
         message: &str,
     ) -> serde_json::Value {
         serde_json::json!({
-            "message": format!("[Alert] {}\nSession: {}\nMetric: {}\nValue: {:.2}\n{}", rule.name, session_id, rule.metric, metric_value, message)
+            "message": format!("[{}]\nSeverity: {}\nSession: {}\nMetric: {}\nValue: {:.2}\n{}", title, rule.severity, session_id, rule.metric, metric_value, message)
         })
     }
 
@@ -223,6 +446,7 @@ impl WebhookSender {
         session_id: u64,
         metric_value: f64,
         message: &str,
+        resolved: bool,
     ) -> serde_json::Value {
         serde_json::json!({
             "alert_name": rule.name,
@@ -231,6 +455,96 @@ impl WebhookSender {
             "value": metric_value,
             "message": message,
             "severity": rule.severity,
+            "resolved": resolved,
         })
     }
 }
+
+/// Discord embed color for a fired alert.
+#[cfg(feature = "webhook")]
+fn severity_color(severity: &str) -> u32 {
+    match severity {
+        "critical" => 15158332,
+        "info" => 3447003,
+        _ => 15105570,
+    }
+}
+
+#[cfg(feature = "email")]
+struct EmailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+#[cfg(feature = "email")]
+impl EmailSender {
+    fn new(config: &SmtpConfig) -> Result<Self, String> {
+        let from = config
+            .from
+            .parse::<Mailbox>()
+            .map_err(|e| format!("invalid smtp_from '{}': {}", config.from, e))?;
+        let mut builder = match config.security {
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+            SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)),
+        }
+        .map_err(|e| format!("SMTP setup failed for {}: {}", config.host, e))?
+        .timeout(Some(Duration::from_secs(30)));
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        if let Some(username) = &config.username {
+            builder = builder.credentials(Credentials::new(
+                username.clone(),
+                config.password.clone().unwrap_or_default(),
+            ));
+        }
+        Ok(Self { transport: builder.build(), from })
+    }
+
+    fn build_message(
+        &self,
+        recipients: &[String],
+        rule: &AlertRuleRecord,
+        session: &crate::web::SessionInfo,
+        metric_value: f64,
+        resolved: bool,
+    ) -> Result<Message, String> {
+        let subject = if resolved {
+            format!("[recisdb-proxy] resolved: {}", rule.name)
+        } else {
+            format!("[recisdb-proxy] {} alert: {}", rule.severity, rule.name)
+        };
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN);
+        for recipient in recipients {
+            let mailbox = recipient
+                .parse::<Mailbox>()
+                .map_err(|e| format!("invalid recipient '{}': {}", recipient, e))?;
+            builder = builder.to(mailbox);
+        }
+        builder
+            .body(format_email_body(rule, session, metric_value, resolved))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mailto() {
+        assert_eq!(
+            parse_mailto("mailto:a@example.com, b@example.com?subject=x"),
+            vec!["a@example.com", "b@example.com"]
+        );
+        assert_eq!(parse_mailto("MAILTO:a@example.com;b@example.com"), vec!["a@example.com", "b@example.com"]);
+        assert_eq!(parse_mailto("a@example.com"), vec!["a@example.com"]);
+        assert!(parse_mailto("mailto:").is_empty());
+        assert_eq!(SmtpSecurity::parse("STARTTLS"), Some(SmtpSecurity::StartTls));
+        assert_eq!(SmtpSecurity::parse("bogus"), None);
+    }
+}
@@ -36,3 +36,40 @@ pub fn decode_arib_b24(bytes: &[u8]) -> String {
 pub fn decode_arib_string(bytes: &[u8]) -> String {
     decode_arib_b24(bytes)
 }
+
+/// 表示名として使えるよう文字列を正規化する。
+///
+/// 有効な UTF-8 はそのまま (制御文字を除去してトリム) 採用し、UTF-8 として
+/// 不正なバイト列は ARIB STD-B24 として再デコードを試みる。
+/// 置換文字 (U+FFFD) を含む、または空になる場合は文字化けとみなし None を返す。
+pub fn sanitize_display_name(raw: &[u8]) -> Option<String> {
+    let decoded = match std::str::from_utf8(raw) {
+        Ok(s) => s.to_string(),
+        Err(_) => decode_arib_b24(raw),
+    };
+
+    let cleaned: String = decoded.chars().filter(|c| !c.is_control()).collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() || cleaned.contains('\u{FFFD}') {
+        return None;
+    }
+    Some(cleaned.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_display_name_keeps_valid_utf8() {
+        assert_eq!(sanitize_display_name("地デジ (関東)".as_bytes()), Some("地デジ (関東)".to_string()));
+        assert_eq!(sanitize_display_name(b" BS\0\r\n"), Some("BS".to_string()));
+    }
+
+    #[test]
+    fn test_sanitize_display_name_rejects_garbage() {
+        assert_eq!(sanitize_display_name(b""), None);
+        assert_eq!(sanitize_display_name(b"\x1b\x0e"), None);
+        assert_eq!(sanitize_display_name("BS\u{FFFD}".as_bytes()), None);
+    }
+}
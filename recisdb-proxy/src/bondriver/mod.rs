@@ -1,7 +1,8 @@
 //! BonDriver wrapper module.
 //!
 //! On Windows: wraps BonDriver DLLs via FFI.
-//! On Linux: wraps character devices (/dev/px4video*, etc.) via ioctl.
+//! On Linux: wraps character devices (/dev/px4video*, etc.) via ioctl, or
+//! DVB frontends (/dev/dvb/adapterN/frontendM) via the DVBv5 API.
 
 #[cfg(target_os = "windows")]
 mod windows;
@@ -38,6 +39,13 @@ mod stub {
             ))
         }
 
+        pub fn set_lnb_power(&self, _enable: bool) -> Result<(), io::Error> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "BonDriver/chardev tuner is only supported on Windows and Linux",
+            ))
+        }
+
         pub fn get_signal_level(&self) -> f32 {
             0.0
         }
@@ -1,4 +1,4 @@
-//! Unix character device implementation of BonDriverTuner.
+//! Character device tuner backend.
 //!
 //! Supports physical tuners at /dev/px4video*, /dev/pt3video*, etc.
 //! Uses ioctl interface compatible with px4-drv and pt3-drv kernel drivers.
@@ -6,6 +6,7 @@
 use std::fs::{File, OpenOptions};
 use std::io;
 use std::os::unix::io::AsRawFd;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
 
 use log::{debug, warn};
@@ -88,11 +89,8 @@ fn space_channel_to_ioctl_freq(space: u32, channel: u32) -> Result<IoctlFreq, io
     }
 }
 
-/// BonDriver-compatible wrapper for Unix character device tuners.
-///
-/// Provides the same interface as the Windows BonDriverTuner to allow
-/// transparent usage in recisdb-proxy on Unix systems.
-pub struct BonDriverTuner {
+/// Tuner backed by a px4-drv/pt3-drv character device.
+pub struct ChardevTuner {
     /// File handle for TS data reading.
     file: File,
     /// Duplicated fd for ioctl operations (avoids borrowing conflicts with reader).
@@ -103,19 +101,20 @@ pub struct BonDriverTuner {
     recording: AtomicBool,
     /// Current tuning space (0=GR, 1=BS, 2=CS) for signal level conversion.
     current_space: AtomicI32,
+    /// LNB power explicitly requested via set_lnb_power (15V).
+    lnb_power: AtomicBool,
 }
 
-impl BonDriverTuner {
-    pub fn new(path: &str) -> Result<Self, io::Error> {
-        // Canonicalize to resolve symlinks (e.g. /dev/px4video0 → real device node)
-        let path = std::fs::canonicalize(path)?;
-        let file = OpenOptions::new().read(true).open(&path)?;
+impl ChardevTuner {
+    pub fn new(path: &Path) -> Result<Self, io::Error> {
+        let file = OpenOptions::new().read(true).open(path)?;
         let ioctl_file = file.try_clone()?;
         Ok(Self {
             file,
             ioctl_file,
             recording: AtomicBool::new(false),
             current_space: AtomicI32::new(0),
+            lnb_power: AtomicBool::new(false),
         })
     }
 
@@ -147,11 +146,14 @@ impl BonDriverTuner {
         // If LNB is not yet powered when set_ch starts that wait, the dish has no
         // power and the lock fails with EAGAIN.  Powering LNB first gives the
         // satellite LNB time to stabilize before the driver starts scanning.
+        let lnb_requested = self.lnb_power.load(Ordering::Acquire);
         match space {
             1 | 2 => {
-                // BS/CS: enable LNB voltage (11V) before set_ch
-                let _ = unsafe { ptx_enable_lnb(self.ioctl_file.as_raw_fd(), 1) };
+                // BS/CS: enable LNB voltage before set_ch (11V unless 15V was requested)
+                let voltage = if lnb_requested { 2 } else { 1 };
+                let _ = unsafe { ptx_enable_lnb(self.ioctl_file.as_raw_fd(), voltage) };
             }
+            _ if lnb_requested => {}
             _ => {
                 // Terrestrial: disable LNB
                 let _ = unsafe { ptx_disable_lnb(self.ioctl_file.as_raw_fd()) };
@@ -229,20 +231,7 @@ impl BonDriverTuner {
 
     /// Poll for available TS data with a timeout.
     pub fn wait_ts_stream(&self, timeout_ms: u32) -> bool {
-        use nix::poll::{poll, PollFd, PollFlags};
-        let fd = self.file.as_raw_fd();
-        // SAFETY: fd is valid for the lifetime of self.
-        let mut fds = [PollFd::new(
-            unsafe { std::os::unix::io::BorrowedFd::borrow_raw(fd) },
-            PollFlags::POLLIN,
-        )];
-        match poll(&mut fds, timeout_ms.min(u16::MAX as u32) as u16) {
-            Ok(n) if n > 0 => fds[0]
-                .revents()
-                .map(|r| r.contains(PollFlags::POLLIN))
-                .unwrap_or(false),
-            _ => false,
-        }
+        super::poll_readable(self.file.as_raw_fd(), timeout_ms)
     }
 
     /// Read TS data from the device. Returns (bytes_read, remaining=0).
@@ -254,88 +243,41 @@ impl BonDriverTuner {
 
     /// Discard buffered TS data (best-effort).
     pub fn purge_ts_stream(&self) {
-        use nix::poll::{poll, PollFd, PollFlags};
-        let fd = self.file.as_raw_fd();
-        let mut discard_buf = vec![0u8; 65536];
-        for _ in 0..16 {
-            let mut fds = [PollFd::new(
-                unsafe { std::os::unix::io::BorrowedFd::borrow_raw(fd) },
-                PollFlags::POLLIN,
-            )];
-            match poll(&mut fds, 0u16) {
-                Ok(n) if n > 0 => {
-                    let has_data = fds[0]
-                        .revents()
-                        .map(|r| r.contains(PollFlags::POLLIN))
-                        .unwrap_or(false);
-                    if !has_data {
-                        break;
-                    }
-                    match nix::unistd::read(fd, &mut discard_buf) {
-                        Ok(0) | Err(_) => break,
-                        Ok(_) => continue,
-                    }
-                }
-                _ => break,
-            }
-        }
-    }
-
-    /// Enumerate tuning space names.
-    pub fn enum_tuning_space(&self, space: u32) -> Option<String> {
-        match space {
-            0 => Some("GR".to_string()),
-            1 => Some("BS".to_string()),
-            2 => Some("CS".to_string()),
-            _ => None,
-        }
+        super::drain_readable(self.file.as_raw_fd());
     }
 
-    /// Enumerate channel names within a tuning space.
-    pub fn enum_channel_name(&self, space: u32, channel: u32) -> Option<String> {
-        match space {
-            0 => {
-                // GR: UHF ch 13-62 (50 channels)
-                let uhf_ch = channel + 13;
-                if uhf_ch <= 62 {
-                    Some(format!("GR{}", uhf_ch))
-                } else {
-                    None
-                }
-            }
-            1 => {
-                // BS: BS1, BS3, ..., BS23 (12 channels)
-                if channel <= 11 {
-                    Some(format!("BS{}", channel * 2 + 1))
-                } else {
-                    None
-                }
-            }
-            2 => {
-                // CS: CS2, CS4, ..., CS24 (12 channels)
-                if channel <= 11 {
-                    Some(format!("CS{}", channel * 2 + 2))
-                } else {
-                    None
-                }
+    /// Turn the LNB power supply on (15V) or off.
+    ///
+    /// Returns `Unsupported` when the device driver has no LNB ioctl.
+    pub fn set_lnb_power(&self, enable: bool) -> Result<(), io::Error> {
+        let fd = self.ioctl_file.as_raw_fd();
+        let result = if enable {
+            unsafe { ptx_enable_lnb(fd, 2) }
+        } else {
+            unsafe { ptx_disable_lnb(fd) }
+        };
+        match result {
+            Ok(_) => {
+                self.lnb_power.store(enable, Ordering::Release);
+                debug!("UnixChardevTuner: LNB power {}", if enable { "on" } else { "off" });
+                Ok(())
             }
-            _ => None,
+            Err(nix::errno::Errno::ENOTTY) | Err(nix::errno::Errno::EINVAL) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "LNB power control is not supported by this device",
+            )),
+            Err(e) => Err(io::Error::from(e)),
         }
     }
-
-    /// BonDriver interface version (IBonDriver2: supports EnumTuningSpace/EnumChannelName).
-    pub fn version(&self) -> u8 {
-        2
-    }
 }
 
-impl Drop for BonDriverTuner {
+impl Drop for ChardevTuner {
     fn drop(&mut self) {
         if self.recording.load(Ordering::Acquire) {
             // Disable LNB first (matches recisdb-rs PowerOffHandle drop order),
             // then stop recording.
             let space = self.current_space.load(Ordering::Relaxed);
-            if space == 1 || space == 2 {
+            if space == 1 || space == 2 || self.lnb_power.load(Ordering::Acquire) {
                 let _ = unsafe { ptx_disable_lnb(self.ioctl_file.as_raw_fd()) };
             }
             let _ = unsafe { stop_rec(self.ioctl_file.as_raw_fd()) };
@@ -0,0 +1,469 @@
+//! Linux DVBv5 tuner backend.
+//!
+//! Drives `/dev/dvb/adapterN/frontendM` through the DVBv5 property API,
+//! routes the full TS to the DVR device with a demux PES filter on PID 0x2000,
+//! and reads TS packets from `/dev/dvb/adapterN/dvrM`.
+//! Tuning parameters match `tuner/linux/dvbv5.rs` in recisdb-rs.
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::time::{Duration, Instant};
+
+use log::{debug, warn};
+
+// linux/dvb/frontend.h
+const DTV_TUNE: u32 = 1;
+const DTV_CLEAR: u32 = 2;
+const DTV_FREQUENCY: u32 = 3;
+const DTV_BANDWIDTH_HZ: u32 = 5;
+const DTV_VOLTAGE: u32 = 10;
+const DTV_DELIVERY_SYSTEM: u32 = 17;
+const DTV_ISDBT_PARTIAL_RECEPTION: u32 = 18;
+const DTV_ISDBT_SOUND_BROADCASTING: u32 = 19;
+const DTV_ISDBT_LAYER_ENABLED: u32 = 41;
+const DTV_STREAM_ID: u32 = 42;
+const DTV_STAT_CNR: u32 = 63;
+
+const SYS_ISDBT: u32 = 8;
+const SYS_ISDBS: u32 = 9;
+
+const SEC_VOLTAGE_13: u32 = 0;
+const SEC_VOLTAGE_18: u32 = 1;
+const SEC_VOLTAGE_OFF: u32 = 2;
+
+const FE_HAS_LOCK: u32 = 0x10;
+const FE_SCALE_DECIBEL: u8 = 1;
+const NO_STREAM_ID_FILTER: u32 = !0;
+const MAX_DTV_STATS: usize = 4;
+
+// linux/dvb/dmx.h
+const DMX_IN_FRONTEND: u32 = 0;
+const DMX_OUT_TS_TAP: u32 = 2;
+const DMX_PES_OTHER: u32 = 20;
+const DMX_IMMEDIATE_START: u32 = 4;
+
+/// Pseudo PID that makes the demux pass every packet of the TS.
+const FULL_TS_PID: u16 = 0x2000;
+
+/// How long to wait for FE_HAS_LOCK after DTV_TUNE.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Kernel ring buffer size for the DVR device.
+const DVR_BUFFER_SIZE: i32 = 4 * 1024 * 1024;
+
+/// struct dtv_stats (packed).
+#[allow(dead_code)] // mirrors the kernel layout
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+struct DtvStats {
+    scale: u8,
+    value: i64,
+}
+
+/// struct dtv_fe_stats (packed).
+#[allow(dead_code)]
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+struct DtvFeStats {
+    len: u8,
+    stat: [DtvStats; MAX_DTV_STATS],
+}
+
+/// Buffer member of the dtv_property union (only here for its size).
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct DtvBuffer {
+    data: [u8; 32],
+    len: u32,
+    reserved1: [u32; 3],
+    reserved2: *mut std::ffi::c_void,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+union DtvPropertyValue {
+    data: u32,
+    st: DtvFeStats,
+    buffer: DtvBuffer,
+}
+
+/// struct dtv_property (packed).
+#[allow(dead_code)]
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+struct DtvProperty {
+    cmd: u32,
+    reserved: [u32; 3],
+    u: DtvPropertyValue,
+    result: i32,
+}
+
+impl DtvProperty {
+    fn new(cmd: u32, data: u32) -> Self {
+        // SAFETY: every field is plain data for which all-zero is valid.
+        let mut prop: Self = unsafe { std::mem::zeroed() };
+        prop.cmd = cmd;
+        prop.u.data = data;
+        prop
+    }
+}
+
+/// struct dtv_properties.
+#[repr(C)]
+struct DtvProperties {
+    num: u32,
+    props: *mut DtvProperty,
+}
+
+/// struct dmx_pes_filter_params.
+#[allow(dead_code)]
+#[repr(C)]
+struct DmxPesFilterParams {
+    pid: u16,
+    input: u32,
+    output: u32,
+    pes_type: u32,
+    flags: u32,
+}
+
+nix::ioctl_read!(fe_read_status, b'o', 69, u32);
+nix::ioctl_write_ptr!(fe_set_property, b'o', 82, DtvProperties);
+nix::ioctl_read!(fe_get_property, b'o', 83, DtvProperties);
+nix::ioctl_write_int_bad!(fe_set_voltage, nix::request_code_none!(b'o', 67));
+nix::ioctl_none!(dmx_stop, b'o', 42);
+nix::ioctl_write_ptr!(dmx_set_pes_filter, b'o', 44, DmxPesFilterParams);
+nix::ioctl_write_int_bad!(dmx_set_buffer_size, nix::request_code_none!(b'o', 45));
+
+/// Delivery system and frequency for one BonDriver (space, channel).
+#[derive(Debug, PartialEq, Eq)]
+struct DvbTuneParams {
+    delivery_system: u32,
+    /// Hz for ISDB-T, kHz for ISDB-S (DVBv5 convention).
+    frequency: u32,
+}
+
+/// Converts BonDriver (space, channel) indices to DVBv5 tuning parameters.
+///
+/// Uses the same channel layout as the character device backend:
+/// - space=0 (GR): channel 0..49 → UHF ch 13..62
+/// - space=1 (BS): channel 0..11 → BS1, BS3, ..., BS23
+/// - space=2 (CS): channel 0..11 → CS2, CS4, ..., CS24
+///
+/// Frequencies match `DvbFreq::from(ChannelType)` in recisdb-rs.
+fn space_channel_to_dvb_params(space: u32, channel: u32) -> Result<DvbTuneParams, io::Error> {
+    let out_of_range = |name: &str, max: u32| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} channel {} out of range (0-{})", name, channel, max),
+        )
+    };
+    match space {
+        0 if channel <= 49 => Ok(DvbTuneParams {
+            delivery_system: SYS_ISDBT,
+            frequency: 473_142_857 + channel * 6_000_000,
+        }),
+        0 => Err(out_of_range("GR", 49)),
+        1 if channel <= 11 => Ok(DvbTuneParams {
+            delivery_system: SYS_ISDBS,
+            frequency: 1_049_480 + channel * 38_360,
+        }),
+        1 => Err(out_of_range("BS", 11)),
+        2 if channel <= 11 => Ok(DvbTuneParams {
+            delivery_system: SYS_ISDBS,
+            frequency: 1_613_000 + channel * 40_000,
+        }),
+        2 => Err(out_of_range("CS", 11)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unknown tuning space: {}", space),
+        )),
+    }
+}
+
+/// Tuner backed by a Linux DVB frontend/demux/dvr triple.
+pub struct DvbTuner {
+    frontend: File,
+    /// Demux handle; the PES filter lives as long as this fd stays open.
+    demux: File,
+    /// DVR device delivering the filtered TS.
+    dvr: File,
+    /// Whether the demux filter has been started.
+    streaming: AtomicBool,
+    /// Current tuning space (0=GR, 1=BS, 2=CS).
+    current_space: AtomicI32,
+    /// LNB power explicitly requested via set_lnb_power (15V).
+    lnb_power: AtomicBool,
+}
+
+impl DvbTuner {
+    pub fn new(adapter: u32, frontend: u32) -> Result<Self, io::Error> {
+        let base = format!("/dev/dvb/adapter{}", adapter);
+        let open_rw = |name: &str| {
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(format!("{}/{}{}", base, name, frontend))
+        };
+        let fe = open_rw("frontend")?;
+        let demux = open_rw("demux")?;
+        let dvr = OpenOptions::new()
+            .read(true)
+            .open(format!("{}/dvr{}", base, frontend))?;
+
+        if let Err(e) = unsafe { dmx_set_buffer_size(dvr.as_raw_fd(), DVR_BUFFER_SIZE) } {
+            debug!(
+                "DvbTuner: DMX_SET_BUFFER_SIZE failed, using driver default: {}",
+                e
+            );
+        }
+
+        Ok(Self {
+            frontend: fe,
+            demux,
+            dvr,
+            streaming: AtomicBool::new(false),
+            current_space: AtomicI32::new(0),
+            lnb_power: AtomicBool::new(false),
+        })
+    }
+
+    pub fn set_channel(&self, space: u32, channel: u32) -> Result<(), io::Error> {
+        let params = space_channel_to_dvb_params(space, channel)?;
+
+        // Stop the filter before re-tuning if already active
+        if self.streaming.swap(false, Ordering::AcqRel) {
+            let _ = unsafe { dmx_stop(self.demux.as_raw_fd()) };
+        }
+
+        let mut props = vec![
+            DtvProperty::new(DTV_CLEAR, 0),
+            DtvProperty::new(DTV_DELIVERY_SYSTEM, params.delivery_system),
+            DtvProperty::new(DTV_FREQUENCY, params.frequency),
+        ];
+        if params.delivery_system == SYS_ISDBT {
+            props.extend([
+                DtvProperty::new(DTV_BANDWIDTH_HZ, 6_000_000),
+                DtvProperty::new(DTV_ISDBT_PARTIAL_RECEPTION, 0),
+                DtvProperty::new(DTV_ISDBT_SOUND_BROADCASTING, 0),
+                DtvProperty::new(DTV_ISDBT_LAYER_ENABLED, 0x07),
+            ]);
+        } else {
+            // BS/CS: pass every TS on the transponder and power the LNB
+            // (11V unless 15V was requested, as with the chardev backend).
+            let voltage = if self.lnb_power.load(Ordering::Acquire) {
+                SEC_VOLTAGE_18
+            } else {
+                SEC_VOLTAGE_13
+            };
+            props.extend([
+                DtvProperty::new(DTV_STREAM_ID, NO_STREAM_ID_FILTER),
+                DtvProperty::new(DTV_VOLTAGE, voltage),
+            ]);
+        }
+        props.push(DtvProperty::new(DTV_TUNE, 0));
+
+        let cmd = DtvProperties {
+            num: props.len() as u32,
+            props: props.as_mut_ptr(),
+        };
+        unsafe {
+            fe_set_property(self.frontend.as_raw_fd(), &cmd).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "FE_SET_PROPERTY failed (space={}, ch={}): {}",
+                        space, channel, e
+                    ),
+                )
+            })?;
+        }
+
+        self.wait_for_lock().map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("No lock on space={}, ch={}: {}", space, channel, e),
+            )
+        })?;
+
+        let filter = DmxPesFilterParams {
+            pid: FULL_TS_PID,
+            input: DMX_IN_FRONTEND,
+            output: DMX_OUT_TS_TAP,
+            pes_type: DMX_PES_OTHER,
+            flags: DMX_IMMEDIATE_START,
+        };
+        unsafe {
+            dmx_set_pes_filter(self.demux.as_raw_fd(), &filter).map_err(io::Error::from)?;
+        }
+
+        self.streaming.store(true, Ordering::Release);
+        self.current_space.store(space as i32, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Poll FE_READ_STATUS until the frontend reports FE_HAS_LOCK.
+    fn wait_for_lock(&self) -> Result<(), io::Error> {
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+        loop {
+            let mut status: u32 = 0;
+            unsafe {
+                fe_read_status(self.frontend.as_raw_fd(), &mut status).map_err(io::Error::from)?;
+            }
+            if status & FE_HAS_LOCK != 0 {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("frontend status 0x{:02x}", status),
+                ));
+            }
+            std::thread::sleep(LOCK_POLL_INTERVAL);
+        }
+    }
+
+    /// C/N in dB from the DVBv5 DTV_STAT_CNR statistic.
+    pub fn get_signal_level(&self) -> f32 {
+        let mut prop = [DtvProperty::new(DTV_STAT_CNR, 0)];
+        let mut cmd = DtvProperties {
+            num: 1,
+            props: prop.as_mut_ptr(),
+        };
+        if let Err(e) = unsafe { fe_get_property(self.frontend.as_raw_fd(), &mut cmd) } {
+            warn!("FE_GET_PROPERTY(DTV_STAT_CNR) failed: {:?}", e);
+            return 0.0;
+        }
+
+        // SAFETY: the kernel fills the `st` member for DTV_STAT_* commands.
+        let st = unsafe { prop[0].u.st };
+        let stats = st.stat;
+        let first = stats[0];
+        if st.len == 0 || first.scale != FE_SCALE_DECIBEL {
+            return 0.0;
+        }
+        let value = first.value;
+        // Scale is 0.001 dB
+        value as f32 / 1000.0
+    }
+
+    /// Poll for available TS data with a timeout.
+    pub fn wait_ts_stream(&self, timeout_ms: u32) -> bool {
+        super::poll_readable(self.dvr.as_raw_fd(), timeout_ms)
+    }
+
+    /// Read TS data from the DVR device. Returns (bytes_read, remaining=0).
+    pub fn get_ts_stream(&self, buf: &mut [u8]) -> Result<(usize, usize), io::Error> {
+        match nix::unistd::read(self.dvr.as_raw_fd(), buf) {
+            Ok(n) => Ok((n, 0)),
+            // The kernel ring buffer overflowed; the next read resumes with fresh data.
+            Err(nix::errno::Errno::EOVERFLOW) => {
+                warn!("DvbTuner: DVR buffer overflow, TS data dropped");
+                Ok((0, 0))
+            }
+            Err(e) => Err(io::Error::from(e)),
+        }
+    }
+
+    /// Discard buffered TS data (best-effort).
+    pub fn purge_ts_stream(&self) {
+        super::drain_readable(self.dvr.as_raw_fd());
+    }
+
+    /// Set the LNB voltage to 15V (18V SEC level) or turn it off.
+    ///
+    /// Returns `Unsupported` when the frontend has no voltage control.
+    pub fn set_lnb_power(&self, enable: bool) -> Result<(), io::Error> {
+        let voltage = if enable {
+            SEC_VOLTAGE_18
+        } else {
+            SEC_VOLTAGE_OFF
+        };
+        match unsafe { fe_set_voltage(self.frontend.as_raw_fd(), voltage as i32) } {
+            Ok(_) => {
+                self.lnb_power.store(enable, Ordering::Release);
+                debug!("DvbTuner: LNB power {}", if enable { "on" } else { "off" });
+                Ok(())
+            }
+            Err(nix::errno::Errno::ENOTTY)
+            | Err(nix::errno::Errno::EINVAL)
+            | Err(nix::errno::Errno::EOPNOTSUPP) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "LNB power control is not supported by this device",
+            )),
+            Err(e) => Err(io::Error::from(e)),
+        }
+    }
+}
+
+impl Drop for DvbTuner {
+    fn drop(&mut self) {
+        if self.streaming.load(Ordering::Acquire) {
+            let _ = unsafe { dmx_stop(self.demux.as_raw_fd()) };
+            debug!("DvbTuner: demux stopped on drop");
+        }
+        let space = self.current_space.load(Ordering::Relaxed);
+        if space == 1 || space == 2 || self.lnb_power.load(Ordering::Acquire) {
+            let _ = unsafe { fe_set_voltage(self.frontend.as_raw_fd(), SEC_VOLTAGE_OFF as i32) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_uapi_struct_sizes() {
+        // Sizes are encoded in the ioctl request numbers, so they must match the kernel.
+        assert_eq!(std::mem::size_of::<DtvProperty>(), 76);
+        assert_eq!(std::mem::size_of::<DtvProperties>(), 16);
+        assert_eq!(std::mem::size_of::<DmxPesFilterParams>(), 20);
+    }
+
+    #[test]
+    fn test_space_channel_to_dvb_params() {
+        // GR13 / GR18
+        assert_eq!(
+            space_channel_to_dvb_params(0, 0).unwrap(),
+            DvbTuneParams {
+                delivery_system: SYS_ISDBT,
+                frequency: 473_142_857
+            }
+        );
+        assert_eq!(
+            space_channel_to_dvb_params(0, 5).unwrap().frequency,
+            503_142_857
+        );
+        // BS1 / BS15
+        assert_eq!(
+            space_channel_to_dvb_params(1, 0).unwrap(),
+            DvbTuneParams {
+                delivery_system: SYS_ISDBS,
+                frequency: 1_049_480
+            }
+        );
+        assert_eq!(
+            space_channel_to_dvb_params(1, 7).unwrap().frequency,
+            1_318_000
+        );
+        // CS2 / CS24
+        assert_eq!(
+            space_channel_to_dvb_params(2, 0).unwrap().frequency,
+            1_613_000
+        );
+        assert_eq!(
+            space_channel_to_dvb_params(2, 11).unwrap().frequency,
+            2_053_000
+        );
+
+        assert!(space_channel_to_dvb_params(0, 50).is_err());
+        assert!(space_channel_to_dvb_params(1, 12).is_err());
+        assert!(space_channel_to_dvb_params(3, 0).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Unix implementation of BonDriverTuner.
+//!
+//! The configured tuner path selects the backend:
+//! - `/dev/dvb/adapterN/frontendM`: Linux DVBv5 frontend (see [`dvb`])
+//! - anything else: px4-drv/pt3-drv character device (see [`chardev`])
+//!
+//! Both backends expose BonDriver-style (space, channel) tuning with the same
+//! GR/BS/CS channel layout, so the rest of the proxy treats them alike.
+
+mod chardev;
+mod dvb;
+
+use std::io;
+use std::os::unix::io::{BorrowedFd, RawFd};
+
+use log::info;
+
+pub use chardev::ChardevTuner;
+pub use dvb::DvbTuner;
+
+/// BonDriver-compatible wrapper for Unix tuner devices.
+///
+/// Provides the same interface as the Windows BonDriverTuner to allow
+/// transparent usage in recisdb-proxy on Unix systems.
+pub enum BonDriverTuner {
+    Chardev(ChardevTuner),
+    Dvb(DvbTuner),
+}
+
+impl BonDriverTuner {
+    pub fn new(path: &str) -> Result<Self, io::Error> {
+        // "N|M" shorthand names a DVB frontend without touching the filesystem
+        if let Some((adapter, frontend)) = parse_dvb_frontend_path(path) {
+            return Self::open_dvb(adapter, frontend);
+        }
+
+        // Canonicalize to resolve symlinks (e.g. /dev/px4video0 → real device node)
+        let path = std::fs::canonicalize(path)?;
+        match path.to_str().and_then(parse_dvb_frontend_path) {
+            Some((adapter, frontend)) => Self::open_dvb(adapter, frontend),
+            None => Ok(Self::Chardev(ChardevTuner::new(&path)?)),
+        }
+    }
+
+    fn open_dvb(adapter: u32, frontend: u32) -> Result<Self, io::Error> {
+        info!(
+            "Opening DVB frontend adapter{} frontend{}",
+            adapter, frontend
+        );
+        Ok(Self::Dvb(DvbTuner::new(adapter, frontend)?))
+    }
+
+    pub fn set_channel(&self, space: u32, channel: u32) -> Result<(), io::Error> {
+        match self {
+            Self::Chardev(t) => t.set_channel(space, channel),
+            Self::Dvb(t) => t.set_channel(space, channel),
+        }
+    }
+
+    pub fn get_signal_level(&self) -> f32 {
+        match self {
+            Self::Chardev(t) => t.get_signal_level(),
+            Self::Dvb(t) => t.get_signal_level(),
+        }
+    }
+
+    /// Poll for available TS data with a timeout.
+    pub fn wait_ts_stream(&self, timeout_ms: u32) -> bool {
+        match self {
+            Self::Chardev(t) => t.wait_ts_stream(timeout_ms),
+            Self::Dvb(t) => t.wait_ts_stream(timeout_ms),
+        }
+    }
+
+    /// Read TS data from the device. Returns (bytes_read, remaining=0).
+    pub fn get_ts_stream(&self, buf: &mut [u8]) -> Result<(usize, usize), io::Error> {
+        match self {
+            Self::Chardev(t) => t.get_ts_stream(buf),
+            Self::Dvb(t) => t.get_ts_stream(buf),
+        }
+    }
+
+    /// Discard buffered TS data (best-effort).
+    pub fn purge_ts_stream(&self) {
+        match self {
+            Self::Chardev(t) => t.purge_ts_stream(),
+            Self::Dvb(t) => t.purge_ts_stream(),
+        }
+    }
+
+    /// Enumerate tuning space names.
+    pub fn enum_tuning_space(&self, space: u32) -> Option<String> {
+        match space {
+            0 => Some("GR".to_string()),
+            1 => Some("BS".to_string()),
+            2 => Some("CS".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Enumerate channel names within a tuning space.
+    pub fn enum_channel_name(&self, space: u32, channel: u32) -> Option<String> {
+        match space {
+            0 => {
+                // GR: UHF ch 13-62 (50 channels)
+                let uhf_ch = channel + 13;
+                if uhf_ch <= 62 {
+                    Some(format!("GR{}", uhf_ch))
+                } else {
+                    None
+                }
+            }
+            1 => {
+                // BS: BS1, BS3, ..., BS23 (12 channels)
+                if channel <= 11 {
+                    Some(format!("BS{}", channel * 2 + 1))
+                } else {
+                    None
+                }
+            }
+            2 => {
+                // CS: CS2, CS4, ..., CS24 (12 channels)
+                if channel <= 11 {
+                    Some(format!("CS{}", channel * 2 + 2))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Turn the LNB power supply on (15V) or off.
+    ///
+    /// Returns `Unsupported` when the device driver has no LNB control.
+    pub fn set_lnb_power(&self, enable: bool) -> Result<(), io::Error> {
+        match self {
+            Self::Chardev(t) => t.set_lnb_power(enable),
+            Self::Dvb(t) => t.set_lnb_power(enable),
+        }
+    }
+
+    /// BonDriver interface version (IBonDriver2: supports EnumTuningSpace/EnumChannelName).
+    pub fn version(&self) -> u8 {
+        2
+    }
+}
+
+/// Poll `fd` for readability with a timeout.
+fn poll_readable(fd: RawFd, timeout_ms: u32) -> bool {
+    use nix::poll::{poll, PollFd, PollFlags};
+    // SAFETY: callers pass an fd owned by a live tuner.
+    let mut fds = [PollFd::new(
+        unsafe { BorrowedFd::borrow_raw(fd) },
+        PollFlags::POLLIN,
+    )];
+    match poll(&mut fds, timeout_ms.min(u16::MAX as u32) as u16) {
+        Ok(n) if n > 0 => fds[0]
+            .revents()
+            .map(|r| r.contains(PollFlags::POLLIN))
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Read and discard whatever is immediately available on `fd` (best-effort).
+fn drain_readable(fd: RawFd) {
+    let mut discard_buf = vec![0u8; 65536];
+    for _ in 0..16 {
+        if !poll_readable(fd, 0) {
+            break;
+        }
+        match nix::unistd::read(fd, &mut discard_buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => continue,
+        }
+    }
+}
+
+/// Extract (adapter, frontend) numbers from a `/dev/dvb/adapterN/frontendM`
+/// path or its `N|M` shorthand.
+///
+/// Returns `None` for any other path, which is then treated as a character device.
+fn parse_dvb_frontend_path(path: &str) -> Option<(u32, u32)> {
+    let (adapter, frontend) = match path.strip_prefix("/dev/dvb/adapter") {
+        Some(rest) => rest.split_once("/frontend")?,
+        None => path.split_once('|')?,
+    };
+    Some((adapter.trim().parse().ok()?, frontend.trim().parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dvb_frontend_path() {
+        assert_eq!(
+            parse_dvb_frontend_path("/dev/dvb/adapter0/frontend0"),
+            Some((0, 0))
+        );
+        assert_eq!(
+            parse_dvb_frontend_path("/dev/dvb/adapter12/frontend1"),
+            Some((12, 1))
+        );
+        assert_eq!(parse_dvb_frontend_path("0|0"), Some((0, 0)));
+        assert_eq!(parse_dvb_frontend_path("2|1"), Some((2, 1)));
+        assert_eq!(parse_dvb_frontend_path("/dev/dvb/adapter0/dvr0"), None);
+        assert_eq!(parse_dvb_frontend_path("/dev/dvb/adapter0"), None);
+        assert_eq!(parse_dvb_frontend_path("/dev/px4video0"), None);
+        assert_eq!(parse_dvb_frontend_path("/dev/pt3video2"), None);
+        assert_eq!(parse_dvb_frontend_path("a|b"), None);
+    }
+}
@@ -181,6 +181,21 @@ impl IBon {
         }
     }
 
+    fn set_lnb_power(&self, enable: bool) -> Result<(), io::Error> {
+        let iface = self.ibon3.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Unsupported, "IBonDriver3 not supported by this driver")
+        })?;
+        unsafe {
+            if ib3::C_SetLnbPower(iface.as_ptr(), enable as BOOL) != 0 {
+                Ok(())
+            } else {
+                let msg = format!("SetLnbPower({}) failed", enable);
+                debug!("[BonDriver] {}", msg);
+                Err(io::Error::new(io::ErrorKind::Other, msg))
+            }
+        }
+    }
+
     fn get_signal_level(&self) -> f32 {
         unsafe { ib1::C_GetSignalLevel(self.ibon1.as_ptr()) }
     }
@@ -348,6 +363,12 @@ impl BonDriverTuner {
         self.ibon.set_channel_by_space(space, channel)
     }
 
+    /// Turn the LNB power supply on or off (IBonDriver3 only).
+    pub fn set_lnb_power(&self, enable: bool) -> Result<(), io::Error> {
+        debug!("[BonDriver] SetLnbPower: {}", enable);
+        self.ibon.set_lnb_power(enable)
+    }
+
     /// Get the current signal level.
     pub fn get_signal_level(&self) -> f32 {
         self.ibon.get_signal_level()
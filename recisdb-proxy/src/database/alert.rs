@@ -4,11 +4,17 @@ use rusqlite::params;
 
 use super::{AlertHistoryRecord, AlertRuleRecord, Database, Result};
 
+/// Valid `severity` values.
+pub const ALERT_SEVERITIES: &[&str] = &["info", "warning", "critical"];
+
+/// Valid `critical_action` values.
+pub const ALERT_CRITICAL_ACTIONS: &[&str] = &["disconnect", "disable_auto_scan"];
+
 impl Database {
     /// Get all alert rules.
     pub fn get_alert_rules(&self) -> Result<Vec<AlertRuleRecord>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, metric, condition, threshold, severity, is_enabled, webhook_url, webhook_format, created_at FROM alert_rules ORDER BY id DESC",
+            "SELECT id, name, metric, condition, threshold, severity, is_enabled, webhook_url, webhook_format, cooldown_secs, critical_action, created_at FROM alert_rules ORDER BY id DESC",
         )?;
 
         let rules = stmt
@@ -23,7 +29,9 @@ impl Database {
                     is_enabled: row.get::<_, i32>(6)? != 0,
                     webhook_url: row.get(7)?,
                     webhook_format: row.get(8)?,
-                    created_at: row.get(9)?,
+                    cooldown_secs: row.get::<_, Option<i64>>(9)?.unwrap_or(0),
+                    critical_action: row.get(10)?,
+                    created_at: row.get(11)?,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -34,7 +42,7 @@ impl Database {
     /// Get enabled alert rules.
     pub fn get_enabled_alert_rules(&self) -> Result<Vec<AlertRuleRecord>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, metric, condition, threshold, severity, is_enabled, webhook_url, webhook_format, created_at FROM alert_rules WHERE is_enabled = 1 ORDER BY id DESC",
+            "SELECT id, name, metric, condition, threshold, severity, is_enabled, webhook_url, webhook_format, cooldown_secs, critical_action, created_at FROM alert_rules WHERE is_enabled = 1 ORDER BY id DESC",
         )?;
 
         let rules = stmt
@@ -49,7 +57,9 @@ impl Database {
                     is_enabled: row.get::<_, i32>(6)? != 0,
                     webhook_url: row.get(7)?,
                     webhook_format: row.get(8)?,
-                    created_at: row.get(9)?,
+                    cooldown_secs: row.get::<_, Option<i64>>(9)?.unwrap_or(0),
+                    critical_action: row.get(10)?,
+                    created_at: row.get(11)?,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -68,9 +78,11 @@ impl Database {
         is_enabled: bool,
         webhook_url: Option<&str>,
         webhook_format: Option<&str>,
+        cooldown_secs: i64,
+        critical_action: Option<&str>,
     ) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO alert_rules (name, metric, condition, threshold, severity, is_enabled, webhook_url, webhook_format) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO alert_rules (name, metric, condition, threshold, severity, is_enabled, webhook_url, webhook_format, cooldown_secs, critical_action) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 name,
                 metric,
@@ -80,6 +92,8 @@ impl Database {
                 is_enabled as i32,
                 webhook_url,
                 webhook_format,
+                cooldown_secs,
+                critical_action,
             ],
         )?;
         Ok(self.conn.last_insert_rowid())
@@ -129,7 +143,9 @@ impl Database {
     /// Get active alerts (resolved_at is NULL).
     pub fn get_active_alerts(&self) -> Result<Vec<AlertHistoryRecord>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, rule_id, session_id, triggered_at, resolved_at, metric_value, message, acknowledged FROM alert_history WHERE resolved_at IS NULL ORDER BY triggered_at DESC",
+            "SELECT h.id, h.rule_id, h.session_id, h.triggered_at, h.resolved_at, h.metric_value, h.message, h.acknowledged, r.severity
+             FROM alert_history h LEFT JOIN alert_rules r ON r.id = h.rule_id
+             WHERE h.resolved_at IS NULL ORDER BY h.triggered_at DESC",
         )?;
 
         let records = stmt
@@ -143,6 +159,7 @@ impl Database {
                     metric_value: row.get(5)?,
                     message: row.get(6)?,
                     acknowledged: row.get::<_, i32>(7)? != 0,
+                    severity: row.get(8)?,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -150,6 +167,20 @@ impl Database {
         Ok(records)
     }
 
+    /// When the rule last fired for the session, resolved or not.
+    pub fn get_last_alert_triggered_at(
+        &self,
+        rule_id: i64,
+        session_id: Option<i64>,
+    ) -> Result<Option<i64>> {
+        let last = self.conn.query_row(
+            "SELECT MAX(triggered_at) FROM alert_history WHERE rule_id = ?1 AND session_id IS ?2",
+            params![rule_id, session_id],
+            |row| row.get(0),
+        )?;
+        Ok(last)
+    }
+
     /// Get active alert history by rule and session.
     pub fn get_active_alert_for_rule_session(
         &self,
@@ -157,7 +188,9 @@ impl Database {
         session_id: Option<i64>,
     ) -> Result<Option<AlertHistoryRecord>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, rule_id, session_id, triggered_at, resolved_at, metric_value, message, acknowledged FROM alert_history WHERE rule_id = ?1 AND session_id IS ?2 AND resolved_at IS NULL ORDER BY triggered_at DESC LIMIT 1",
+            "SELECT h.id, h.rule_id, h.session_id, h.triggered_at, h.resolved_at, h.metric_value, h.message, h.acknowledged, r.severity
+             FROM alert_history h LEFT JOIN alert_rules r ON r.id = h.rule_id
+             WHERE h.rule_id = ?1 AND h.session_id IS ?2 AND h.resolved_at IS NULL ORDER BY h.triggered_at DESC LIMIT 1",
         )?;
 
         let result = stmt.query_row(params![rule_id, session_id], |row| {
@@ -170,6 +203,7 @@ impl Database {
                 metric_value: row.get(5)?,
                 message: row.get(6)?,
                 acknowledged: row.get::<_, i32>(7)? != 0,
+                severity: row.get(8)?,
             })
         });
 
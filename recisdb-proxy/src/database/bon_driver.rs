@@ -1,6 +1,6 @@
 //! BonDriver CRUD operations.
 
-use super::{BonDriverRecord, Database, NewBonDriver, Result};
+use super::{BonDriverRecord, Database, DatabaseError, GroupSummary, NewBonDriver, Result, TunerResolution};
 use rusqlite::params;
 
 impl Database {
@@ -144,6 +144,21 @@ impl Database {
         }
     }
 
+    /// Get whether a BonDriver can receive 4K/HEVC transports, by DLL path.
+    pub fn get_supports_4k_for_path(&self, dll_path: &str) -> Result<bool> {
+        let mut stmt = self.conn.prepare(
+            "SELECT supports_4k FROM bon_drivers WHERE dll_path = ?1",
+        )?;
+
+        let result = stmt.query_row([dll_path], |row| row.get::<_, Option<bool>>(0));
+
+        match result {
+            Ok(supports_4k) => Ok(supports_4k.unwrap_or(false)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(false), // Unknown drivers are not 4K capable
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Get all BonDrivers.
     pub fn get_all_bon_drivers(&self) -> Result<Vec<BonDriverRecord>> {
         let mut stmt = self.conn.prepare(
@@ -187,7 +202,7 @@ impl Database {
                     max_instances, created_at, updated_at
              FROM bon_drivers
              WHERE auto_scan_enabled = 1
-               AND scan_interval_hours > 0
+               AND (scan_interval_hours > 0 OR scan_cron IS NOT NULL)
                AND (next_scan_at IS NULL OR next_scan_at <= ?1)
              ORDER BY scan_priority DESC, next_scan_at ASC",
         )?;
@@ -270,6 +285,62 @@ impl Database {
         Ok(())
     }
 
+    /// Get the cron scan schedule of a BonDriver by ID.
+    pub fn get_scan_cron(&self, id: i64) -> Result<Option<String>> {
+        let result = self.conn.query_row(
+            "SELECT scan_cron FROM bon_drivers WHERE id = ?1",
+            [id],
+            |row| row.get::<_, Option<String>>(0),
+        );
+
+        match result {
+            Ok(cron) => Ok(cron),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Set or clear the cron scan schedule of a BonDriver.
+    ///
+    /// `next_scan_at` reschedules the pending scan so a new schedule takes
+    /// effect immediately instead of after the previously planned scan.
+    pub fn update_bon_driver_scan_cron(
+        &self,
+        id: i64,
+        cron: Option<&str>,
+        next_scan_at: Option<i64>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE bon_drivers SET scan_cron = ?1, next_scan_at = COALESCE(?2, next_scan_at) WHERE id = ?3",
+            params![cron, next_scan_at, id],
+        )?;
+        Ok(())
+    }
+
+    /// Get the scan scope of a BonDriver by ID (None = scan everything).
+    pub fn get_scan_scope(&self, id: i64) -> Result<Option<String>> {
+        let result = self.conn.query_row(
+            "SELECT scan_scope FROM bon_drivers WHERE id = ?1",
+            [id],
+            |row| row.get::<_, Option<String>>(0),
+        );
+
+        match result {
+            Ok(scope) => Ok(scope),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Set or clear the scan scope of a BonDriver.
+    pub fn update_bon_driver_scan_scope(&self, id: i64, scope: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE bon_drivers SET scan_scope = ?1 WHERE id = ?2",
+            params![scope, id],
+        )?;
+        Ok(())
+    }
+
     /// Enable scanning for a BonDriver and schedule immediate scan.
     /// This sets auto_scan_enabled = 1, scan_interval_hours = 24, and next_scan_at = 0.
     pub fn enable_immediate_scan(&self, id: i64) -> Result<()> {
@@ -305,6 +376,143 @@ impl Database {
         Ok(())
     }
 
+    /// Update 4K/HEVC capability flag for a BonDriver by ID.
+    pub fn update_bon_driver_supports_4k(&self, id: i64, supports_4k: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE bon_drivers SET supports_4k = ?1 WHERE id = ?2",
+            params![supports_4k, id],
+        )?;
+        Ok(())
+    }
+
+    /// Get the broadcast buffer capacity and lag policy of a BonDriver, by
+    /// DLL path (None = default).
+    pub fn get_broadcast_config_for_path(&self, dll_path: &str) -> Result<(Option<i64>, Option<String>)> {
+        let result = self.conn.query_row(
+            "SELECT broadcast_capacity, lag_policy FROM bon_drivers WHERE dll_path = ?1",
+            [dll_path],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        );
+
+        match result {
+            Ok(config) => Ok(config),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok((None, None)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Broadcast buffer settings of every BonDriver that overrides them, as
+    /// (DLL path, capacity, lag policy).
+    pub fn get_broadcast_configs(&self) -> Result<Vec<(String, Option<i64>, Option<String>)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT dll_path, broadcast_capacity, lag_policy FROM bon_drivers
+             WHERE broadcast_capacity IS NOT NULL OR lag_policy IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Update the broadcast buffer capacity of a BonDriver (None = default).
+    pub fn update_bon_driver_broadcast_capacity(&self, id: i64, capacity: Option<i64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE bon_drivers SET broadcast_capacity = ?1 WHERE id = ?2",
+            params![capacity, id],
+        )?;
+        Ok(())
+    }
+
+    /// Update the lag policy of a BonDriver (None = default).
+    pub fn update_bon_driver_lag_policy(&self, id: i64, lag_policy: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE bon_drivers SET lag_policy = ?1 WHERE id = ?2",
+            params![lag_policy, id],
+        )?;
+        Ok(())
+    }
+
+    /// Get the scan reception check of a BonDriver, by DLL path, as
+    /// (minimum signal level in dB, auto-disable failing channels).
+    pub fn get_reception_config_for_path(&self, dll_path: &str) -> Result<(Option<f64>, bool)> {
+        let result = self.conn.query_row(
+            "SELECT reception_min_signal, auto_disable_no_reception FROM bon_drivers WHERE dll_path = ?1",
+            [dll_path],
+            |row| Ok((row.get(0)?, row.get::<_, Option<bool>>(1)?.unwrap_or(false))),
+        );
+
+        match result {
+            Ok(config) => Ok(config),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok((None, false)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Update the minimum signal level a scan requires (None = scanner default).
+    pub fn update_bon_driver_reception_min_signal(&self, id: i64, min_signal: Option<f64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE bon_drivers SET reception_min_signal = ?1 WHERE id = ?2",
+            params![min_signal, id],
+        )?;
+        Ok(())
+    }
+
+    /// Update whether scans disable channels that fail the reception check.
+    pub fn update_bon_driver_auto_disable_no_reception(&self, id: i64, enabled: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE bon_drivers SET auto_disable_no_reception = ?1 WHERE id = ?2",
+            params![enabled, id],
+        )?;
+        Ok(())
+    }
+
+    /// Get the group selection strategy stored on a BonDriver, by DLL path.
+    pub fn get_group_strategy_for_path(&self, dll_path: &str) -> Result<Option<String>> {
+        let result = self.conn.query_row(
+            "SELECT group_strategy FROM bon_drivers WHERE dll_path = ?1",
+            [dll_path],
+            |row| row.get::<_, Option<String>>(0),
+        );
+
+        match result {
+            Ok(strategy) => Ok(strategy),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get the driver selection strategy of a group.
+    ///
+    /// Drivers added to the group later may not carry the setting yet, so the
+    /// first driver (by DLL path) that has one decides.
+    pub fn get_group_strategy(&self, group_name: &str) -> Result<Option<String>> {
+        let result = self.conn.query_row(
+            "SELECT group_strategy FROM bon_drivers
+             WHERE group_name = ?1 AND group_strategy IS NOT NULL
+             ORDER BY dll_path LIMIT 1",
+            [group_name],
+            |row| row.get::<_, String>(0),
+        );
+
+        match result {
+            Ok(strategy) => Ok(Some(strategy)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Update the group selection strategy of a BonDriver by ID.
+    ///
+    /// The strategy applies to the whole group, so every driver sharing the
+    /// driver's group_name is updated as well.
+    pub fn update_bon_driver_group_strategy(&self, id: i64, strategy: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE bon_drivers SET group_strategy = ?1
+             WHERE id = ?2
+                OR group_name = (SELECT group_name FROM bon_drivers WHERE id = ?2)",
+            params![strategy, id],
+        )?;
+        Ok(())
+    }
+
     /// Update display name for a BonDriver by ID.
     pub fn update_bon_driver_display_name(&self, id: i64, display_name: &str) -> Result<()> {
         self.conn.execute(
@@ -357,7 +565,18 @@ impl Database {
     }
 
     /// Set group_name for a BonDriver by ID.
+    ///
+    /// A group name equal to a driver's DLL path is rejected: such a group
+    /// could never be opened by name, since the path match always wins.
     pub fn set_group_name(&self, id: i64, group_name: Option<&str>) -> Result<()> {
+        if let Some(group) = group_name {
+            if self.get_bon_driver_by_path(group)?.is_some() {
+                return Err(DatabaseError::InvalidGroupName(format!(
+                    "'{}' is already used as a BonDriver path",
+                    group
+                )));
+            }
+        }
         self.conn.execute(
             "UPDATE bon_drivers SET group_name = ?1, updated_at = strftime('%s', 'now') WHERE id = ?2",
             params![group_name, id],
@@ -365,6 +584,128 @@ impl Database {
         Ok(())
     }
 
+    /// All groups with their member drivers, by group name.
+    pub fn get_groups(&self) -> Result<Vec<GroupSummary>> {
+        let names: Vec<String> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT DISTINCT group_name FROM bon_drivers WHERE group_name IS NOT NULL ORDER BY group_name",
+            )?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect::<std::result::Result<_, _>>()?
+        };
+
+        names
+            .into_iter()
+            .map(|name| {
+                let drivers = self.get_group_drivers(&name)?;
+                Ok(GroupSummary {
+                    strategy: self.get_group_strategy(&name)?,
+                    total_instances: drivers.iter().map(|d| d.max_instances as i64).sum(),
+                    drivers,
+                    name,
+                })
+            })
+            .collect()
+    }
+
+    /// Rename a group. Returns the number of member drivers.
+    ///
+    /// The new name must pass the same checks as [`set_group_name`](Self::set_group_name)
+    /// and must not be another existing group, so two groups are never
+    /// merged by accident.
+    pub fn rename_group(&self, old_name: &str, new_name: &str) -> Result<usize> {
+        if old_name == new_name {
+            return Ok(self.get_group_drivers(old_name)?.len());
+        }
+        if self.get_bon_driver_by_path(new_name)?.is_some() {
+            return Err(DatabaseError::InvalidGroupName(format!(
+                "'{}' is already used as a BonDriver path",
+                new_name
+            )));
+        }
+        if !self.get_group_drivers(new_name)?.is_empty() {
+            return Err(DatabaseError::InvalidGroupName(format!("group '{}' already exists", new_name)));
+        }
+        let renamed = self.conn.execute(
+            "UPDATE bon_drivers SET group_name = ?1, updated_at = strftime('%s', 'now') WHERE group_name = ?2",
+            params![new_name, old_name],
+        )?;
+        Ok(renamed)
+    }
+
+    /// Set the driver selection strategy of every driver in a group.
+    pub fn set_group_strategy(&self, group_name: &str, strategy: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE bon_drivers SET group_strategy = ?1 WHERE group_name = ?2",
+            params![strategy, group_name],
+        )?;
+        Ok(())
+    }
+
+    /// Add a driver to a group, taking over the group's strategy.
+    pub fn add_group_member(&self, group_name: &str, id: i64) -> Result<()> {
+        let strategy = self.get_group_strategy(group_name)?;
+        self.set_group_name(id, Some(group_name))?;
+        self.conn.execute(
+            "UPDATE bon_drivers SET group_strategy = ?1 WHERE id = ?2",
+            params![strategy, id],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a driver from a group. Returns false if it was not a member.
+    ///
+    /// The strategy only has a meaning within the group, so it is cleared.
+    pub fn remove_group_member(&self, group_name: &str, id: i64) -> Result<bool> {
+        let removed = self.conn.execute(
+            "UPDATE bon_drivers SET group_name = NULL, group_strategy = NULL,
+                                    updated_at = strftime('%s', 'now')
+             WHERE id = ?1 AND group_name = ?2",
+            params![id, group_name],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Resolve a client-supplied tuner name.
+    ///
+    /// The name is matched in order as a DLL path, a group name, then a
+    /// driver display name. A name that matches both a DLL path and a group,
+    /// or a group and the display name of a driver outside that group, is
+    /// reported as ambiguous rather than silently picking one of them.
+    pub fn resolve_tuner_name(&self, name: &str) -> Result<TunerResolution> {
+        let by_path = self.get_bon_driver_by_path(name)?;
+        let group = self.get_group_drivers(name)?;
+
+        if let Some(driver) = by_path {
+            if !group.is_empty() {
+                return Err(DatabaseError::AmbiguousTunerName(format!(
+                    "'{}' is both a BonDriver path and a group name",
+                    name
+                )));
+            }
+            return Ok(TunerResolution::Driver(driver.dll_path));
+        }
+
+        let by_display_name = self.get_bon_driver_by_display_name(name)?;
+
+        if !group.is_empty() {
+            if let Some(driver) = by_display_name {
+                if driver.group_name.as_deref() != Some(name) {
+                    return Err(DatabaseError::AmbiguousTunerName(format!(
+                        "'{}' is a group name and the display name of {} outside that group",
+                        name, driver.dll_path
+                    )));
+                }
+            }
+            return Ok(TunerResolution::Group(name.to_string()));
+        }
+
+        Ok(match by_display_name {
+            Some(driver) => TunerResolution::Driver(driver.dll_path),
+            None => TunerResolution::NotFound,
+        })
+    }
+
     /// Infer group_name from DLL filename.
     /// Examples:
     ///   "BonDriver_MLT1.dll" -> "PX-MLT"
@@ -458,4 +799,201 @@ mod tests {
         let all = db.get_all_bon_drivers().unwrap();
         assert_eq!(all.len(), 2);
     }
+
+    #[test]
+    fn test_supports_4k_flag() {
+        let db = Database::open_in_memory().unwrap();
+
+        let id = db.insert_bon_driver(&NewBonDriver::new("BonDriver_4K.dll")).unwrap();
+        assert!(!db.get_supports_4k_for_path("BonDriver_4K.dll").unwrap());
+
+        db.update_bon_driver_supports_4k(id, true).unwrap();
+        assert!(db.get_supports_4k_for_path("BonDriver_4K.dll").unwrap());
+
+        // Unknown drivers are treated as not capable
+        assert!(!db.get_supports_4k_for_path("BonDriver_Missing.dll").unwrap());
+    }
+
+    #[test]
+    fn test_broadcast_config() {
+        let db = Database::open_in_memory().unwrap();
+
+        let id = db.insert_bon_driver(&NewBonDriver::new("BonDriver_A.dll")).unwrap();
+        db.insert_bon_driver(&NewBonDriver::new("BonDriver_B.dll")).unwrap();
+        assert_eq!(db.get_broadcast_config_for_path("BonDriver_A.dll").unwrap(), (None, None));
+        assert!(db.get_broadcast_configs().unwrap().is_empty());
+
+        db.update_bon_driver_broadcast_capacity(id, Some(1024)).unwrap();
+        db.update_bon_driver_lag_policy(id, Some("slow_producer")).unwrap();
+        assert_eq!(
+            db.get_broadcast_config_for_path("BonDriver_A.dll").unwrap(),
+            (Some(1024), Some("slow_producer".to_string()))
+        );
+        // Only drivers that override a setting are listed
+        let configs = db.get_broadcast_configs().unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].0, "BonDriver_A.dll");
+
+        db.update_bon_driver_broadcast_capacity(id, None).unwrap();
+        db.update_bon_driver_lag_policy(id, None).unwrap();
+        assert!(db.get_broadcast_configs().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scan_cron() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db.insert_bon_driver(&NewBonDriver::new("BonDriver_S.dll")).unwrap();
+        db.update_scan_config(id, Some(true), Some(0), None, None).unwrap();
+        db.update_next_scan(id, 1).unwrap();
+        assert_eq!(db.get_scan_cron(id).unwrap(), None);
+        // Interval 0 without a cron never scans
+        assert!(db.get_due_bon_drivers().unwrap().is_empty());
+
+        db.update_bon_driver_scan_cron(id, Some("0 4 * * *"), Some(2)).unwrap();
+        assert_eq!(db.get_scan_cron(id).unwrap().as_deref(), Some("0 4 * * *"));
+        let due = db.get_due_bon_drivers().unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].next_scan_at, Some(2));
+
+        // Clearing keeps the pending scan time
+        db.update_bon_driver_scan_cron(id, None, None).unwrap();
+        assert_eq!(db.get_scan_cron(id).unwrap(), None);
+        assert_eq!(db.get_bon_driver(id).unwrap().unwrap().next_scan_at, Some(2));
+    }
+
+    #[test]
+    fn test_group_strategy() {
+        let db = Database::open_in_memory().unwrap();
+
+        let a = db.insert_bon_driver(&NewBonDriver::new("BonDriver_MLT1.dll")).unwrap();
+        let b = db.insert_bon_driver(&NewBonDriver::new("BonDriver_MLT2.dll")).unwrap();
+        let other = db.insert_bon_driver(&NewBonDriver::new("BonDriver_Q1.dll")).unwrap();
+        db.set_group_name(a, Some("PX-MLT")).unwrap();
+        db.set_group_name(b, Some("PX-MLT")).unwrap();
+        assert_eq!(db.get_group_strategy("PX-MLT").unwrap(), None);
+
+        // Setting it on one driver applies to the whole group
+        db.update_bon_driver_group_strategy(b, Some("least_loaded")).unwrap();
+        assert_eq!(db.get_group_strategy("PX-MLT").unwrap().as_deref(), Some("least_loaded"));
+        assert_eq!(
+            db.get_group_strategy_for_path("BonDriver_MLT1.dll").unwrap().as_deref(),
+            Some("least_loaded")
+        );
+        assert_eq!(db.get_group_strategy_for_path("BonDriver_Q1.dll").unwrap(), None);
+
+        // Ungrouped drivers only update themselves
+        db.update_bon_driver_group_strategy(other, Some("round_robin")).unwrap();
+        assert_eq!(db.get_group_strategy("PX-MLT").unwrap().as_deref(), Some("least_loaded"));
+
+        db.update_bon_driver_group_strategy(a, None).unwrap();
+        assert_eq!(db.get_group_strategy("PX-MLT").unwrap(), None);
+    }
+
+    #[test]
+    fn test_group_management() {
+        let db = Database::open_in_memory().unwrap();
+
+        let a = db.insert_bon_driver(&NewBonDriver::new("BonDriver_MLT1.dll")).unwrap();
+        let b = db.insert_bon_driver(&NewBonDriver::new("BonDriver_MLT2.dll").with_max_instances(2)).unwrap();
+        let c = db.insert_bon_driver(&NewBonDriver::new("BonDriver_Q1.dll")).unwrap();
+        db.set_group_name(a, Some("PX-MLT")).unwrap();
+        db.set_group_strategy("PX-MLT", Some("least_loaded")).unwrap();
+
+        // New members take over the group strategy
+        db.add_group_member("PX-MLT", b).unwrap();
+        assert_eq!(
+            db.get_group_strategy_for_path("BonDriver_MLT2.dll").unwrap().as_deref(),
+            Some("least_loaded")
+        );
+        db.add_group_member("PX-Q", c).unwrap();
+
+        let groups = db.get_groups().unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "PX-MLT");
+        assert_eq!(groups[0].drivers.len(), 2);
+        assert_eq!(groups[0].total_instances, 3);
+        assert_eq!(groups[1].strategy, None);
+
+        // Renaming onto an existing group or a driver path is refused
+        assert!(matches!(db.rename_group("PX-MLT", "PX-Q"), Err(DatabaseError::InvalidGroupName(_))));
+        assert!(matches!(
+            db.rename_group("PX-MLT", "BonDriver_Q1.dll"),
+            Err(DatabaseError::InvalidGroupName(_))
+        ));
+        assert_eq!(db.rename_group("PX-MLT", "MLT").unwrap(), 2);
+        assert_eq!(db.get_group_strategy("MLT").unwrap().as_deref(), Some("least_loaded"));
+
+        assert!(db.remove_group_member("MLT", a).unwrap());
+        assert!(!db.remove_group_member("MLT", a).unwrap());
+        let removed = db.get_bon_driver(a).unwrap().unwrap();
+        assert_eq!(removed.group_name, None);
+        assert_eq!(db.get_group_strategy_for_path("BonDriver_MLT1.dll").unwrap(), None);
+    }
+
+    #[test]
+    fn test_resolve_tuner_name() {
+        let db = Database::open_in_memory().unwrap();
+
+        let a = db
+            .insert_bon_driver(&NewBonDriver::new("BonDriver_A.dll").with_name("Tuner A"))
+            .unwrap();
+        let b = db
+            .insert_bon_driver(&NewBonDriver::new("BonDriver_B.dll").with_name("PX-Q"))
+            .unwrap();
+        db.set_group_name(a, Some("PX-MLT")).unwrap();
+
+        assert_eq!(
+            db.resolve_tuner_name("BonDriver_A.dll").unwrap(),
+            TunerResolution::Driver("BonDriver_A.dll".to_string())
+        );
+        assert_eq!(
+            db.resolve_tuner_name("PX-MLT").unwrap(),
+            TunerResolution::Group("PX-MLT".to_string())
+        );
+        assert_eq!(
+            db.resolve_tuner_name("Tuner A").unwrap(),
+            TunerResolution::Driver("BonDriver_A.dll".to_string())
+        );
+        assert_eq!(db.resolve_tuner_name("Missing").unwrap(), TunerResolution::NotFound);
+
+        // Display name equal to its own group is fine
+        db.set_group_name(b, Some("PX-Q")).unwrap();
+        assert_eq!(
+            db.resolve_tuner_name("PX-Q").unwrap(),
+            TunerResolution::Group("PX-Q".to_string())
+        );
+
+        // Display name of a driver outside the group is ambiguous
+        db.set_group_name(b, Some("PX-MLT")).unwrap();
+        let c = db
+            .insert_bon_driver(&NewBonDriver::new("BonDriver_C.dll").with_name("PX-MLT"))
+            .unwrap();
+        assert!(matches!(
+            db.resolve_tuner_name("PX-MLT"),
+            Err(DatabaseError::AmbiguousTunerName(_))
+        ));
+        db.set_group_name(c, Some("PX-MLT")).unwrap();
+        assert_eq!(
+            db.resolve_tuner_name("PX-MLT").unwrap(),
+            TunerResolution::Group("PX-MLT".to_string())
+        );
+    }
+
+    #[test]
+    fn test_group_name_cannot_be_driver_path() {
+        let db = Database::open_in_memory().unwrap();
+
+        let a = db.insert_bon_driver(&NewBonDriver::new("BonDriver_A.dll")).unwrap();
+        db.insert_bon_driver(&NewBonDriver::new("BonDriver_B.dll")).unwrap();
+
+        assert!(matches!(
+            db.set_group_name(a, Some("BonDriver_B.dll")),
+            Err(DatabaseError::InvalidGroupName(_))
+        ));
+        assert!(matches!(
+            db.set_group_name(a, Some("BonDriver_A.dll")),
+            Err(DatabaseError::InvalidGroupName(_))
+        ));
+        assert!(db.get_bon_driver(a).unwrap().unwrap().group_name.is_none());
+    }
 }
@@ -1,15 +1,18 @@
 //! Channel CRUD operations.
 
 use super::{
-    BonDriverRecord, ChannelRecord, ChannelWithDriver, ClientChannelRecord, Database, MergeResult,
-    Result, ScanHistoryRecord,
+    BonDriverRecord, ChannelReception, ChannelRecord, ChannelSearchFilter, ChannelWithDriver,
+    ClientChannelRecord, Database, LogicalChannel, LogicalChannelMapping, MergeResult,
+    ReceptionProbe, ReceptionUpdate, Result, ScanHistoryRecord,
 };
+use crate::aribb24::sanitize_display_name;
 use recisdb_protocol::{
     broadcast_region::{get_prefecture_name, get_region_id_from_nid},
     ChannelInfo,
 };
 use rusqlite::params;
-use std::collections::HashSet;
+use rusqlite::types::ValueRef;
+use std::collections::{HashMap, HashSet};
 
 impl Database {
     /// Insert a new channel.
@@ -28,10 +31,10 @@ impl Database {
                 bon_driver_id, nid, sid, tsid, manual_sheet,
                 raw_name, channel_name, physical_ch, remote_control_key,
                 service_type, network_name, bon_space, bon_channel,
-                band_type, region_id, terrestrial_region,
+                band_type, region_id, terrestrial_region, is_one_seg, ts_name, name_is_synthetic,
                 scan_time, last_seen
             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
-                      ?14, ?15, ?16, strftime('%s', 'now'), strftime('%s', 'now'))",
+                      ?14, ?15, ?16, ?17, ?18, ?19, strftime('%s', 'now'), strftime('%s', 'now'))",
             params![
                 bon_driver_id,
                 info.nid as i32,
@@ -49,6 +52,9 @@ impl Database {
                 bt as i32,
                 region_id.map(|v| v as i32),
                 terrestrial_region,
+                info.is_one_seg as i32,
+                info.ts_name,
+                info.name_is_synthetic as i32,
             ],
         )?;
         Ok(self.conn.last_insert_rowid())
@@ -82,6 +88,28 @@ impl Database {
         Ok(records)
     }
 
+    /// Channels not confirmed by a scan or the passive scanner since `cutoff`.
+    ///
+    /// Rows that were never seen come first, then the oldest `last_seen`.
+    /// Each row carries its BonDriver path.
+    pub fn get_stale_channels(&self, cutoff: i64) -> Result<Vec<(ChannelRecord, Option<String>)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT c.*, bd.dll_path
+             FROM channels c
+             LEFT JOIN bon_drivers bd ON c.bon_driver_id = bd.id
+             WHERE c.last_seen IS NULL OR c.last_seen < ?1
+             ORDER BY c.last_seen IS NOT NULL, c.last_seen, c.nid, c.tsid, c.sid",
+        )?;
+        let records = stmt
+            .query_map([cutoff], |row| {
+                let ch = Self::row_to_channel_record(row)?;
+                let dll: Option<String> = row.get("dll_path")?;
+                Ok((ch, dll))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
     /// Get channel by unique key (bon_driver_id, nid, sid, tsid, manual_sheet).
     pub fn get_channel_by_key(
         &self,
@@ -144,6 +172,40 @@ impl Database {
         Ok(records)
     }
 
+    /// Search channels by case-insensitive substring over the service name,
+    /// TS name and network name.
+    ///
+    /// Matching is done by SQLite `LIKE` on the stored UTF-8 text, so Japanese
+    /// names match as-is (case folding applies to ASCII only). `%` and `_` in
+    /// `query` are matched literally.
+    pub fn search_channels(&self, query: &str, filter: &ChannelSearchFilter) -> Result<Vec<ChannelRecord>> {
+        let pattern = format!(
+            "%{}%",
+            query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+        );
+        let mut conditions = vec![
+            "(channel_name LIKE ? ESCAPE '\\' OR ts_name LIKE ? ESCAPE '\\' OR network_name LIKE ? ESCAPE '\\')",
+        ];
+        let mut values: Vec<rusqlite::types::Value> = vec![pattern.clone().into(), pattern.clone().into(), pattern.into()];
+        if let Some(bon_driver_id) = filter.bon_driver_id {
+            conditions.push("bon_driver_id = ?");
+            values.push(bon_driver_id.into());
+        }
+        if filter.enabled_only {
+            conditions.push("is_enabled = 1");
+        }
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT * FROM channels WHERE {} ORDER BY priority DESC, nid, tsid, sid",
+            conditions.join(" AND ")
+        ))?;
+        let records = stmt
+            .query_map(rusqlite::params_from_iter(values), Self::row_to_channel_record)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
     /// Get enabled channels by NID/TSID with priority ordering.
     pub fn get_channels_by_nid_tsid_ordered(
         &self,
@@ -188,6 +250,39 @@ impl Database {
         Ok(records)
     }
 
+    /// Get the channel carrying a transport stream on each BonDriver.
+    ///
+    /// Returns one `(dll_path, bon_space, bon_channel)` per driver, for enabled
+    /// channels only. A channel placed from the NIT (`physical_ch` set) wins
+    /// over one that was only seen on the tuned channel.
+    pub fn get_physical_channels(&self, nid: u16, tsid: u16) -> Result<Vec<(String, u32, u32)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT bd.dll_path, c.bon_space, c.bon_channel
+             FROM channels c
+             JOIN bon_drivers bd ON c.bon_driver_id = bd.id
+             WHERE c.nid = ?1 AND c.tsid = ?2 AND c.is_enabled = 1
+               AND c.bon_space IS NOT NULL AND c.bon_channel IS NOT NULL
+             ORDER BY bd.id, c.physical_ch IS NULL, c.priority DESC",
+        )?;
+        let rows = stmt.query_map(params![nid as i32, tsid as i32], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)? as u32,
+                row.get::<_, i64>(2)? as u32,
+            ))
+        })?;
+
+        let mut seen = HashSet::new();
+        let mut channels = Vec::new();
+        for row in rows {
+            let row = row?;
+            if seen.insert(row.0.clone()) {
+                channels.push(row);
+            }
+        }
+        Ok(channels)
+    }
+
     /// Get all distinct SIDs for a given NID+TSID combination.
     pub fn get_sids_for_nid_tsid(&self, nid: u16, tsid: u16) -> Result<Vec<u16>> {
         let mut stmt = self.conn.prepare(
@@ -229,21 +324,27 @@ impl Database {
     }
 
     /// Get all channels with their BonDriver information (for channel list queries).
+    ///
+    /// Rows are in lineup order: user-defined `display_order` first, then
+    /// remote control key, then BonDriver channel number. Unset values sort last.
     pub fn get_all_channels_with_drivers(
         &self,
     ) -> Result<Vec<(ClientChannelRecord, Option<BonDriverRecord>)>> {
         let mut stmt = self.conn.prepare(
             "SELECT c.id, c.bon_driver_id, c.nid, c.sid, c.tsid,
-                    c.channel_name, c.network_name, c.service_type,
+                    c.channel_name, c.name_is_synthetic,
+                    COALESCE(c.ts_name, c.network_name) AS ts_name, c.service_type,
                     c.remote_control_key, c.bon_space, c.bon_channel,
-                    c.is_enabled, c.priority,
+                    c.is_enabled, c.priority, c.display_order,
                     bd.id as bd_id, bd.dll_path, bd.driver_name, bd.version,
                     bd.auto_scan_enabled, bd.scan_interval_hours, bd.scan_priority,
                     bd.last_scan, bd.next_scan_at, bd.passive_scan_enabled,
                     bd.created_at as bd_created_at, bd.updated_at as bd_updated_at
              FROM channels c
              LEFT JOIN bon_drivers bd ON c.bon_driver_id = bd.id
-             ORDER BY c.priority DESC, c.nid, c.tsid, c.sid",
+             ORDER BY c.display_order IS NULL, c.display_order,
+                      c.remote_control_key IS NULL, c.remote_control_key,
+                      c.bon_channel, c.priority DESC, c.nid, c.tsid, c.sid",
         )?;
 
         let rows = stmt.query_map([], |row| {
@@ -254,13 +355,15 @@ impl Database {
                 sid: row.get("sid")?,
                 tsid: row.get("tsid")?,
                 service_name: row.get("channel_name")?,
-                ts_name: row.get("network_name")?,
+                name_is_synthetic: row.get::<_, Option<i32>>("name_is_synthetic")?.unwrap_or(0) != 0,
+                ts_name: row.get("ts_name")?,
                 service_type: row.get("service_type")?,
                 remote_control_key: row.get("remote_control_key")?,
                 space: row.get::<_, Option<i32>>("bon_space")?.unwrap_or(0) as u32,
                 channel: row.get::<_, Option<i32>>("bon_channel")?.unwrap_or(0) as u32,
                 is_enabled: row.get::<_, i32>("is_enabled")? != 0,
                 priority: row.get("priority")?,
+                display_order: row.get("display_order")?,
             };
 
             let bon_driver: Option<BonDriverRecord> = row.get::<_, Option<i64>>("bd_id")?.map(|id| {
@@ -288,6 +391,63 @@ impl Database {
         rows.collect::<std::result::Result<Vec<_>, _>>().map_err(|e| e.into())
     }
 
+    /// Merge the channel rows of every BonDriver into logical channels keyed
+    /// by NID/TSID/SID, ordered by NID, TSID, SID.
+    ///
+    /// Each logical channel lists all (driver, space, channel) locations, highest
+    /// priority first, so callers can fall back across drivers.
+    pub fn merge_logical_channels(&self, enabled_only: bool) -> Result<Vec<LogicalChannel>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT c.*, bd.dll_path AS bd_dll_path, bd.driver_name AS bd_driver_name
+             FROM channels c
+             JOIN bon_drivers bd ON c.bon_driver_id = bd.id
+             WHERE ?1 = 0 OR c.is_enabled = 1
+             ORDER BY c.nid, c.tsid, c.sid, c.priority DESC, bd.scan_priority DESC, c.id",
+        )?;
+        let rows = stmt
+            .query_map([enabled_only as i32], |row| {
+                Ok((
+                    Self::row_to_channel_record(row)?,
+                    row.get::<_, String>("bd_dll_path")?,
+                    row.get::<_, Option<String>>("bd_driver_name")?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut merged: Vec<LogicalChannel> = Vec::new();
+        for (record, dll_path, driver_name) in rows {
+            let mapping = LogicalChannelMapping {
+                channel_id: record.id,
+                bon_driver_id: record.bon_driver_id,
+                dll_path,
+                driver_name,
+                bon_space: record.bon_space,
+                bon_channel: record.bon_channel,
+                is_enabled: record.is_enabled,
+            };
+            match merged.last_mut() {
+                Some(last)
+                    if (last.channel.nid, last.channel.tsid, last.channel.sid)
+                        == (record.nid, record.tsid, record.sid) =>
+                {
+                    let channel = &mut last.channel;
+                    channel.priority = channel.priority.max(record.priority);
+                    channel.display_order = match (channel.display_order, record.display_order) {
+                        (Some(a), Some(b)) => Some(a.min(b)),
+                        (a, b) => a.or(b),
+                    };
+                    last.mappings.push(mapping);
+                }
+                _ => merged.push(LogicalChannel {
+                    channel: record,
+                    mappings: vec![mapping],
+                }),
+            }
+        }
+
+        Ok(merged)
+    }
+
     /// Update channel information.
     pub fn update_channel(&self, bon_driver_id: i64, info: &ChannelInfo) -> Result<()> {
         // Auto-detect band_type, region_id, and terrestrial_region if not provided
@@ -301,17 +461,17 @@ impl Database {
 
         let sql = if info.manual_sheet.is_some() {
             "UPDATE channels SET
-                raw_name = ?5, channel_name = ?6, physical_ch = ?7, remote_control_key = ?8,
+                raw_name = ?5, channel_name = ?6, physical_ch = COALESCE(?7, physical_ch), remote_control_key = COALESCE(?8, remote_control_key),
                 service_type = ?9, network_name = ?10, bon_space = ?11, bon_channel = ?12,
-                band_type = ?14, region_id = ?15, terrestrial_region = ?16,
+                band_type = ?14, region_id = ?15, terrestrial_region = ?16, is_one_seg = ?17, ts_name = COALESCE(?18, ts_name), name_is_synthetic = ?19,
                 scan_time = strftime('%s', 'now'), last_seen = strftime('%s', 'now'),
                 is_enabled = 1
              WHERE bon_driver_id = ?1 AND nid = ?2 AND sid = ?3 AND tsid = ?4 AND manual_sheet = ?13"
         } else {
             "UPDATE channels SET
-                raw_name = ?5, channel_name = ?6, physical_ch = ?7, remote_control_key = ?8,
+                raw_name = ?5, channel_name = ?6, physical_ch = COALESCE(?7, physical_ch), remote_control_key = COALESCE(?8, remote_control_key),
                 service_type = ?9, network_name = ?10, bon_space = ?11, bon_channel = ?12,
-                band_type = ?13, region_id = ?14, terrestrial_region = ?15,
+                band_type = ?13, region_id = ?14, terrestrial_region = ?15, is_one_seg = ?16, ts_name = COALESCE(?17, ts_name), name_is_synthetic = ?18,
                 scan_time = strftime('%s', 'now'), last_seen = strftime('%s', 'now'),
                 is_enabled = 1
              WHERE bon_driver_id = ?1 AND nid = ?2 AND sid = ?3 AND tsid = ?4 AND manual_sheet IS NULL"
@@ -337,6 +497,9 @@ impl Database {
                     band_type as i32,
                     region_id.map(|v| v as i32),
                     terrestrial_region,
+                    info.is_one_seg as i32,
+                    info.ts_name,
+                    info.name_is_synthetic as i32,
                 ],
             )?;
         } else {
@@ -358,6 +521,9 @@ impl Database {
                     band_type as i32,
                     region_id.map(|v| v as i32),
                     terrestrial_region,
+                    info.is_one_seg as i32,
+                    info.ts_name,
+                    info.name_is_synthetic as i32,
                 ],
             )?;
         }
@@ -408,6 +574,70 @@ impl Database {
         Ok(())
     }
 
+    /// Store the signal a scan measured on each probed channel of a BonDriver.
+    ///
+    /// Channels on a probed (space, channel) are flagged `reception_ok` when
+    /// the stream locked at `min_signal` dB or more. With `auto_disable`,
+    /// failing channels are disabled and marked `auto_disabled`; such
+    /// channels are enabled again once a later scan receives them. Channels
+    /// the user disabled are never re-enabled.
+    pub fn apply_scan_reception(
+        &self,
+        bon_driver_id: i64,
+        probes: &[ReceptionProbe],
+        min_signal: f32,
+        auto_disable: bool,
+    ) -> Result<ReceptionUpdate> {
+        let mut update = ReceptionUpdate::default();
+        let tx = self.conn.unchecked_transaction()?;
+        for probe in probes {
+            let ok = probe.locked && probe.signal_level >= min_signal;
+            let (space, channel) = (probe.space as i32, probe.channel as i32);
+            let changed = tx.execute(
+                "UPDATE channels SET signal_level = ?4, reception_ok = ?5
+                 WHERE bon_driver_id = ?1 AND bon_space = ?2 AND bon_channel = ?3",
+                params![bon_driver_id, space, channel, probe.signal_level as f64, ok],
+            )?;
+            if ok {
+                update.restored += tx.execute(
+                    "UPDATE channels SET is_enabled = 1, auto_disabled = 0
+                     WHERE bon_driver_id = ?1 AND bon_space = ?2 AND bon_channel = ?3 AND auto_disabled = 1",
+                    params![bon_driver_id, space, channel],
+                )?;
+                continue;
+            }
+            update.failed += changed;
+            if auto_disable {
+                update.disabled += tx.execute(
+                    "UPDATE channels SET is_enabled = 0, auto_disabled = 1
+                     WHERE bon_driver_id = ?1 AND bon_space = ?2 AND bon_channel = ?3 AND is_enabled = 1",
+                    params![bon_driver_id, space, channel],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(update)
+    }
+
+    /// Reception state of every channel checked by a scan, by channel id.
+    pub fn get_channel_reception(&self) -> Result<HashMap<i64, ChannelReception>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, signal_level, reception_ok, auto_disabled FROM channels
+             WHERE reception_ok IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                ChannelReception {
+                    signal_level: row.get(1)?,
+                    reception_ok: row.get::<_, i32>(2)? != 0,
+                    auto_disabled: row.get::<_, Option<i32>>(3)?.unwrap_or(0) != 0,
+                },
+            ))
+        })?;
+        Ok(rows.collect::<std::result::Result<HashMap<_, _>, _>>()?)
+    }
+
     /// Update channel fields (name, priority, enabled).
     pub fn update_channel_fields(
         &self,
@@ -438,7 +668,8 @@ impl Database {
         let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
         if let Some(name) = channel_name {
-            updates.push("channel_name = ?");
+            // A name set by hand is no longer a guess
+            updates.push("channel_name = ?, name_is_synthetic = 0");
             values.push(Box::new(name.to_string()));
         }
         if let Some(p) = priority {
@@ -489,6 +720,33 @@ impl Database {
         Ok(())
     }
 
+    /// Set the lineup order from an ordered list of channel IDs.
+    ///
+    /// Channels are numbered in list order. The position applies to every
+    /// BonDriver's row for the same NID-SID-TSID, so the lineup is shared
+    /// across tuners. Channels not in the list lose their position and fall
+    /// back to remote control key ordering. Returns the number of rows positioned.
+    pub fn reorder_channels(&mut self, channel_ids: &[i64]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        tx.execute("UPDATE channels SET display_order = NULL WHERE display_order IS NOT NULL", [])?;
+        let mut positioned = 0;
+        {
+            let mut stmt = tx.prepare_cached(
+                "UPDATE channels SET display_order = ?2, updated_at = strftime('%s', 'now')
+                 WHERE EXISTS (
+                     SELECT 1 FROM channels o
+                     WHERE o.id = ?1 AND o.nid = channels.nid AND o.sid = channels.sid
+                       AND o.tsid = channels.tsid
+                 )",
+            )?;
+            for (order, id) in channel_ids.iter().enumerate() {
+                positioned += stmt.execute(params![id, order as i32])?;
+            }
+        }
+        tx.commit()?;
+        Ok(positioned)
+    }
+
     /// Delete a channel.
     pub fn delete_channel(&self, channel_id: i64) -> Result<()> {
         self.conn.execute(
@@ -543,17 +801,17 @@ impl Database {
                 // Update existing
                 let sql = if info.manual_sheet.is_some() {
                     "UPDATE channels SET
-                        raw_name = ?5, channel_name = ?6, physical_ch = ?7, remote_control_key = ?8,
+                        raw_name = ?5, channel_name = ?6, physical_ch = COALESCE(?7, physical_ch), remote_control_key = COALESCE(?8, remote_control_key),
                         service_type = ?9, network_name = ?10, bon_space = ?11, bon_channel = ?12,
-                        band_type = ?14, region_id = ?15, terrestrial_region = ?16,
+                        band_type = ?14, region_id = ?15, terrestrial_region = ?16, is_one_seg = ?17, ts_name = COALESCE(?18, ts_name), name_is_synthetic = ?19,
                         scan_time = strftime('%s', 'now'), last_seen = strftime('%s', 'now'),
                         is_enabled = 1
                      WHERE bon_driver_id = ?1 AND nid = ?2 AND sid = ?3 AND tsid = ?4 AND manual_sheet = ?13"
                 } else {
                     "UPDATE channels SET
-                        raw_name = ?5, channel_name = ?6, physical_ch = ?7, remote_control_key = ?8,
+                        raw_name = ?5, channel_name = ?6, physical_ch = COALESCE(?7, physical_ch), remote_control_key = COALESCE(?8, remote_control_key),
                         service_type = ?9, network_name = ?10, bon_space = ?11, bon_channel = ?12,
-                        band_type = ?13, region_id = ?14, terrestrial_region = ?15,
+                        band_type = ?13, region_id = ?14, terrestrial_region = ?15, is_one_seg = ?16, ts_name = COALESCE(?17, ts_name), name_is_synthetic = ?18,
                         scan_time = strftime('%s', 'now'), last_seen = strftime('%s', 'now'),
                         is_enabled = 1
                      WHERE bon_driver_id = ?1 AND nid = ?2 AND sid = ?3 AND tsid = ?4 AND manual_sheet IS NULL"
@@ -579,6 +837,9 @@ impl Database {
                             band_type as i32,
                             region_id.map(|v| v as i32),
                             terrestrial_region,
+                            info.is_one_seg as i32,
+                            info.ts_name,
+                            info.name_is_synthetic as i32,
                         ],
                     )?;
                 } else {
@@ -600,6 +861,9 @@ impl Database {
                             band_type as i32,
                             region_id.map(|v| v as i32),
                             terrestrial_region,
+                            info.is_one_seg as i32,
+                            info.ts_name,
+                            info.name_is_synthetic as i32,
                         ],
                     )?;
                 }
@@ -611,10 +875,10 @@ impl Database {
                         bon_driver_id, nid, sid, tsid, manual_sheet,
                         raw_name, channel_name, physical_ch, remote_control_key,
                         service_type, network_name, bon_space, bon_channel,
-                        band_type, region_id, terrestrial_region,
+                        band_type, region_id, terrestrial_region, is_one_seg, ts_name, name_is_synthetic,
                         scan_time, last_seen
                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
-                              ?14, ?15, ?16, strftime('%s', 'now'), strftime('%s', 'now'))",
+                              ?14, ?15, ?16, ?17, ?18, ?19, strftime('%s', 'now'), strftime('%s', 'now'))",
                     params![
                         bon_driver_id,
                         info.nid as i32,
@@ -632,6 +896,9 @@ impl Database {
                         band_type as i32,
                         region_id.map(|v| v as i32),
                         terrestrial_region,
+                        info.is_one_seg as i32,
+                        info.ts_name,
+                        info.name_is_synthetic as i32,
                     ],
                 )?;
                 result.inserted += 1;
@@ -680,9 +947,13 @@ impl Database {
                         params![now, existing.id],
                     )?;
 
-                    // Full update if channel name or service type changed
+                    // Full update if channel name, service type or TS information changed
                     if existing.channel_name != info.channel_name
                         || existing.service_type != info.service_type
+                        || existing.is_one_seg != info.is_one_seg
+                        || (info.ts_name.is_some() && existing.ts_name != info.ts_name)
+                        || (info.remote_control_key.is_some()
+                            && existing.remote_control_key != info.remote_control_key)
                     {
                         self.update_channel(bon_driver_id, info)?;
                         updated += 1;
@@ -745,22 +1016,20 @@ impl Database {
     }
 
     /// Get distinct tuning spaces for a BonDriver.
-    /// Returns space numbers and their names (derived from band_type and terrestrial_region).
+    /// Returns space numbers and their names: the TS name when every channel
+    /// of the space shares one, otherwise derived from band_type and terrestrial_region.
     pub fn get_tuning_spaces(&self, bon_driver_id: i64) -> Result<Vec<(u32, String)>> {
         let mut stmt = self.conn.prepare(
-            "SELECT DISTINCT bon_space, band_type, terrestrial_region
+            "SELECT bon_space, MIN(band_type), MIN(terrestrial_region),
+                    CASE WHEN COUNT(DISTINCT ts_name) = 1 AND COUNT(ts_name) = COUNT(*)
+                         THEN MIN(ts_name) END
              FROM channels
              WHERE bon_driver_id = ?1 AND bon_space IS NOT NULL AND is_enabled = 1
+             GROUP BY bon_space
              ORDER BY bon_space",
         )?;
 
-        let rows = stmt.query_map([bon_driver_id], |row| {
-            let space: i32 = row.get(0)?;
-            let band_type: Option<i32> = row.get(1)?;
-            let terrestrial_region: Option<String> = row.get(2)?;
-            let space_name = Self::generate_space_name(band_type, terrestrial_region, space);
-            Ok((space as u32, space_name))
-        })?;
+        let rows = stmt.query_map([bon_driver_id], Self::row_to_tuning_space)?;
 
         rows.collect::<std::result::Result<Vec<_>, _>>()
             .map_err(|e| e.into())
@@ -768,24 +1037,47 @@ impl Database {
 
     /// Get tuning space name by space number.
     pub fn get_tuning_space_name(&self, bon_driver_id: i64, space: u32) -> Result<Option<String>> {
-        let result: std::result::Result<(Option<i32>, Option<String>), _> = self.conn.query_row(
-            "SELECT band_type, terrestrial_region
+        let result = self.conn.query_row(
+            "SELECT bon_space, MIN(band_type), MIN(terrestrial_region),
+                    CASE WHEN COUNT(DISTINCT ts_name) = 1 AND COUNT(ts_name) = COUNT(*)
+                         THEN MIN(ts_name) END
              FROM channels
              WHERE bon_driver_id = ?1 AND bon_space = ?2 AND is_enabled = 1
-             LIMIT 1",
+             GROUP BY bon_space",
             params![bon_driver_id, space as i32],
-            |row| Ok((row.get(0)?, row.get(1)?)),
+            Self::row_to_tuning_space,
         );
 
         match result {
-            Ok((band_type, terrestrial_region)) => {
-                Ok(Some(Self::generate_space_name(band_type, terrestrial_region, space as i32)))
-            }
+            Ok((_, name)) => Ok(Some(name)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e.into()),
         }
     }
 
+    /// Map a (space, band_type, terrestrial_region, shared ts_name) row to a named space.
+    fn row_to_tuning_space(row: &rusqlite::Row<'_>) -> rusqlite::Result<(u32, String)> {
+        let space: i32 = row.get(0)?;
+        let band_type: Option<i32> = row.get(1)?;
+        let terrestrial_region = Self::read_display_text(row, 2)?;
+        let space_name = match Self::read_display_text(row, 3)? {
+            Some(ts_name) => ts_name,
+            None => Self::generate_space_name(band_type, terrestrial_region, space),
+        };
+        Ok((space as u32, space_name))
+    }
+
+    /// Read a display string column without failing on invalid UTF-8.
+    ///
+    /// Values stored as raw bytes (mis-decoded ARIB text, BLOBs) are re-decoded
+    /// via [`sanitize_display_name`]; unusable values read as None.
+    fn read_display_text(row: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<Option<String>> {
+        Ok(match row.get_ref(idx)? {
+            ValueRef::Text(bytes) | ValueRef::Blob(bytes) => sanitize_display_name(bytes),
+            _ => None,
+        })
+    }
+
     /// Generate a space name from band_type and terrestrial_region.
     fn generate_space_name(band_type: Option<i32>, terrestrial_region: Option<String>, space: i32) -> String {
         match band_type {
@@ -885,16 +1177,20 @@ impl Database {
             remote_control_key: row.get::<_, Option<i32>>("remote_control_key")?.map(|v| v as u8),
             service_type: row.get::<_, Option<i32>>("service_type")?.map(|v| v as u8),
             network_name: row.get("network_name")?,
+            ts_name: row.get("ts_name")?,
             bon_space: row.get::<_, Option<i32>>("bon_space")?.map(|v| v as u32),
             bon_channel: row.get::<_, Option<i32>>("bon_channel")?.map(|v| v as u32),
             band_type: row.get::<_, Option<i32>>("band_type")?.map(|v| v as u8),
             region_id: row.get::<_, Option<i32>>("region_id")?.map(|v| v as u8),
             terrestrial_region: row.get("terrestrial_region")?,
+            is_one_seg: row.get::<_, Option<i32>>("is_one_seg")?.unwrap_or(0) != 0,
+            name_is_synthetic: row.get::<_, Option<i32>>("name_is_synthetic")?.unwrap_or(0) != 0,
             is_enabled: row.get::<_, i32>("is_enabled")? != 0,
             scan_time: row.get("scan_time")?,
             last_seen: row.get("last_seen")?,
             failure_count: row.get("failure_count")?,
             priority: row.get("priority")?,
+            display_order: row.get("display_order")?,
             created_at: row.get("created_at")?,
             updated_at: row.get("updated_at")?,
         })
@@ -952,6 +1248,227 @@ mod tests {
         assert!(!disabled.is_enabled);
     }
 
+    #[test]
+    fn test_stale_channels() {
+        let db = Database::open_in_memory().unwrap();
+        let bon_driver_id = db.get_or_create_bon_driver("Test.dll").unwrap();
+        let fresh = db.insert_channel(bon_driver_id, &create_test_channel(0x7FE8, 1024, 32736)).unwrap();
+        let old = db.insert_channel(bon_driver_id, &create_test_channel(0x7FE8, 1032, 32736)).unwrap();
+        let never = db.insert_channel(bon_driver_id, &create_test_channel(0x7FE8, 1040, 32736)).unwrap();
+        let now = chrono::Utc::now().timestamp();
+        db.connection()
+            .execute("UPDATE channels SET last_seen = ?1 WHERE id = ?2", params![now - 40 * 86400, old])
+            .unwrap();
+        db.connection()
+            .execute("UPDATE channels SET last_seen = NULL WHERE id = ?1", [never])
+            .unwrap();
+
+        let stale = db.get_stale_channels(now - 30 * 86400).unwrap();
+        let ids: Vec<i64> = stale.iter().map(|(ch, _)| ch.id).collect();
+        assert_eq!(ids, vec![never, old]);
+        assert_eq!(stale[0].1.as_deref(), Some("Test.dll"));
+
+        // A passive scan confirmation makes the channel fresh again
+        db.passive_update_channels(bon_driver_id, &[create_test_channel(0x7FE8, 1032, 32736)])
+            .unwrap();
+        let stale = db.get_stale_channels(now - 30 * 86400).unwrap();
+        assert!(stale.iter().all(|(ch, _)| ch.id != old && ch.id != fresh));
+    }
+
+    #[test]
+    fn test_passive_update_one_seg_flag() {
+        let db = Database::open_in_memory().unwrap();
+        let bon_driver_id = db.get_or_create_bon_driver("Test.dll").unwrap();
+
+        let info = create_test_channel(0x7FE8, 0x0588, 32736);
+        db.insert_channel(bon_driver_id, &info).unwrap();
+        let record = db
+            .get_channel_by_key(bon_driver_id, 0x7FE8, 0x0588, 32736, None)
+            .unwrap()
+            .unwrap();
+        assert!(!record.is_one_seg);
+
+        // Passive scan learns the service is 1seg
+        let mut one_seg = info.clone();
+        one_seg.is_one_seg = true;
+        assert_eq!(db.passive_update_channels(bon_driver_id, &[one_seg]).unwrap(), 1);
+
+        let record = db
+            .get_channel_by_key(bon_driver_id, 0x7FE8, 0x0588, 32736, None)
+            .unwrap()
+            .unwrap();
+        assert!(record.is_one_seg);
+        assert!(record.to_channel_info().is_one_seg);
+    }
+
+    #[test]
+    fn test_ts_name_names_tuning_space() {
+        let db = Database::open_in_memory().unwrap();
+        let bon_driver_id = db.get_or_create_bon_driver("Test.dll").unwrap();
+
+        let mut info = create_test_channel(0x7FE8, 1024, 32736);
+        info.bon_space = Some(0);
+        db.insert_channel(bon_driver_id, &info).unwrap();
+        let generated = db.get_tuning_space_name(bon_driver_id, 0).unwrap().unwrap();
+
+        // Passive scan learns the TS name and remote key from the NIT
+        let mut named = info.clone();
+        named.ts_name = Some("NHK総合・東京".to_string());
+        named.remote_control_key = Some(1);
+        assert_eq!(db.passive_update_channels(bon_driver_id, &[named]).unwrap(), 1);
+        assert_eq!(
+            db.get_tuning_space_name(bon_driver_id, 0).unwrap().as_deref(),
+            Some("NHK総合・東京")
+        );
+
+        // A later capture without the descriptor keeps the stored values
+        let mut renamed = info.clone();
+        renamed.channel_name = Some("NHK1".to_string());
+        assert_eq!(db.passive_update_channels(bon_driver_id, &[renamed]).unwrap(), 1);
+        let record = db
+            .get_channel_by_key(bon_driver_id, 0x7FE8, 1024, 32736, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.ts_name.as_deref(), Some("NHK総合・東京"));
+        assert_eq!(record.remote_control_key, Some(1));
+
+        // Another TS in the same space falls back to the generated name
+        let mut other = create_test_channel(0x7FE8, 1032, 32737);
+        other.bon_space = Some(0);
+        other.ts_name = Some("日テレ".to_string());
+        db.insert_channel(bon_driver_id, &other).unwrap();
+        assert_eq!(db.get_tuning_spaces(bon_driver_id).unwrap(), vec![(0, generated)]);
+    }
+
+    #[test]
+    fn test_merge_logical_channels() {
+        let db = Database::open_in_memory().unwrap();
+        let driver_a = db.get_or_create_bon_driver("A.dll").unwrap();
+        let driver_b = db.get_or_create_bon_driver("B.dll").unwrap();
+        let driver_c = db.get_or_create_bon_driver("C.dll").unwrap();
+
+        // The same BS service scanned by three tuners
+        let bs = create_test_channel(4, 101, 0x4010);
+        let id_a = db.insert_channel(driver_a, &bs).unwrap();
+        let id_b = db.insert_channel(driver_b, &bs).unwrap();
+        let id_c = db.insert_channel(driver_c, &bs).unwrap();
+        db.insert_channel(driver_a, &create_test_channel(4, 103, 0x4011)).unwrap();
+        db.update_channel_fields(id_b, None, Some(5), None).unwrap();
+        db.disable_channel(id_c).unwrap();
+
+        let merged = db.merge_logical_channels(false).unwrap();
+        assert_eq!(merged.len(), 2);
+        let bs1 = &merged[0];
+        assert_eq!(bs1.channel.sid, 101);
+        assert_eq!(bs1.channel.priority, 5);
+        assert_eq!(bs1.tuner_count(), 3);
+        // Highest priority location first
+        let ids: Vec<i64> = bs1.mappings.iter().map(|m| m.channel_id).collect();
+        assert_eq!(ids, vec![id_b, id_a, id_c]);
+        assert_eq!(bs1.mappings[0].dll_path, "B.dll");
+
+        let merged = db.merge_logical_channels(true).unwrap();
+        assert_eq!(merged[0].tuner_count(), 2);
+    }
+
+    #[test]
+    fn test_search_channels() {
+        let db = Database::open_in_memory().unwrap();
+        let driver_a = db.get_or_create_bon_driver("A.dll").unwrap();
+        let driver_b = db.get_or_create_bon_driver("B.dll").unwrap();
+
+        let mut nhk = create_test_channel(0x7FE8, 1024, 32736);
+        nhk.channel_name = Some("ＮＨＫ総合１・東京".to_string());
+        nhk.ts_name = Some("NHK総合".to_string());
+        let mut ntv = create_test_channel(0x7FE8, 1040, 32737);
+        ntv.channel_name = Some("日テレ1".to_string());
+        ntv.network_name = Some("Kanto 100%".to_string());
+        db.insert_channel(driver_a, &nhk).unwrap();
+        let ntv_a = db.insert_channel(driver_a, &ntv).unwrap();
+        db.insert_channel(driver_b, &ntv).unwrap();
+
+        let all = ChannelSearchFilter::default();
+        let sids = |records: Vec<ChannelRecord>| records.into_iter().map(|c| c.sid).collect::<Vec<_>>();
+
+        // Japanese substring on the service name, case-insensitive ASCII on the TS name
+        assert_eq!(sids(db.search_channels("総合１", &all).unwrap()), vec![1024]);
+        assert_eq!(sids(db.search_channels("nhk", &all).unwrap()), vec![1024]);
+        assert_eq!(sids(db.search_channels("テレ", &all).unwrap()), vec![1040, 1040]);
+
+        // LIKE wildcards are literal
+        assert_eq!(sids(db.search_channels("100%", &all).unwrap()), vec![1040, 1040]);
+        assert!(db.search_channels("_", &all).unwrap().is_empty());
+
+        let driver_only = ChannelSearchFilter { bon_driver_id: Some(driver_b), ..Default::default() };
+        assert_eq!(sids(db.search_channels("テレ", &driver_only).unwrap()), vec![1040]);
+
+        db.disable_channel(ntv_a).unwrap();
+        let enabled_only = ChannelSearchFilter { enabled_only: true, ..Default::default() };
+        assert_eq!(db.search_channels("テレ", &enabled_only).unwrap()[0].bon_driver_id, driver_b);
+    }
+
+    #[test]
+    fn test_reorder_channels() {
+        let mut db = Database::open_in_memory().unwrap();
+        let driver_a = db.get_or_create_bon_driver("A.dll").unwrap();
+        let driver_b = db.get_or_create_bon_driver("B.dll").unwrap();
+
+        let mut nhk = create_test_channel(0x7FE8, 1024, 32736);
+        nhk.remote_control_key = Some(1);
+        let mut ntv = create_test_channel(0x7FE8, 1040, 32737);
+        ntv.remote_control_key = Some(4);
+        let nhk_a = db.insert_channel(driver_a, &nhk).unwrap();
+        let ntv_a = db.insert_channel(driver_a, &ntv).unwrap();
+        db.insert_channel(driver_b, &ntv).unwrap();
+
+        let order = |db: &Database| -> Vec<i32> {
+            db.get_all_channels_with_drivers()
+                .unwrap()
+                .into_iter()
+                .map(|(c, _)| c.sid)
+                .collect()
+        };
+
+        // Without a lineup, channels follow the remote control key
+        assert_eq!(order(&db), vec![1024, 1040, 1040]);
+
+        // Positions apply to every driver's row for the same service
+        assert_eq!(db.reorder_channels(&[ntv_a, nhk_a]).unwrap(), 3);
+        assert_eq!(order(&db), vec![1040, 1040, 1024]);
+
+        // Channels left out of the list lose their position
+        assert_eq!(db.reorder_channels(&[nhk_a]).unwrap(), 1);
+        let ntv_record = db.get_channel_by_id(ntv_a).unwrap().unwrap();
+        assert_eq!(ntv_record.display_order, None);
+        assert_eq!(order(&db), vec![1024, 1040, 1040]);
+    }
+
+    #[test]
+    fn test_get_physical_channels() {
+        let db = Database::open_in_memory().unwrap();
+        let driver_a = db.get_or_create_bon_driver("A.dll").unwrap();
+        let driver_b = db.get_or_create_bon_driver("B.dll").unwrap();
+
+        let satellite = |sid: u16, channel: u32, physical_ch: Option<u8>| {
+            let mut info = create_test_channel(0x0004, sid, 0x4011);
+            info.bon_space = Some(0);
+            info.bon_channel = Some(channel);
+            info.physical_ch = physical_ch;
+            info
+        };
+        // A stale row left on the wrong transponder, and one placed from the NIT
+        db.insert_channel(driver_a, &satellite(0x0097, 2, None)).unwrap();
+        db.insert_channel(driver_a, &satellite(0x0098, 1, Some(1))).unwrap();
+        db.insert_channel(driver_b, &satellite(0x0097, 5, None)).unwrap();
+
+        let channels = db.get_physical_channels(0x0004, 0x4011).unwrap();
+        assert_eq!(
+            channels,
+            vec![("A.dll".to_string(), 0, 1), ("B.dll".to_string(), 0, 5)]
+        );
+        assert!(db.get_physical_channels(0x0004, 0x4010).unwrap().is_empty());
+    }
+
     #[test]
     fn test_merge_scan_results() {
         let mut db = Database::open_in_memory().unwrap();
@@ -1011,6 +1528,49 @@ mod tests {
         assert_eq!(record.failure_count, 0);
     }
 
+    #[test]
+    fn test_apply_scan_reception() {
+        let db = Database::open_in_memory().unwrap();
+        let bon_driver_id = db.get_or_create_bon_driver("Test.dll").unwrap();
+        let mut strong = create_test_channel(0x7FE8, 1024, 32736);
+        strong.bon_space = Some(0);
+        strong.bon_channel = Some(13);
+        let strong = db.insert_channel(bon_driver_id, &strong).unwrap();
+        let mut weak = create_test_channel(0x7FE9, 1032, 32737);
+        weak.bon_space = Some(0);
+        weak.bon_channel = Some(14);
+        let weak = db.insert_channel(bon_driver_id, &weak).unwrap();
+        let probe = |channel, signal_level, locked| ReceptionProbe { space: 0, channel, signal_level, locked };
+
+        // Flag only
+        let update = db
+            .apply_scan_reception(bon_driver_id, &[probe(13, 25.0, true), probe(14, 1.5, false)], 3.0, false)
+            .unwrap();
+        assert_eq!(update, ReceptionUpdate { failed: 1, disabled: 0, restored: 0 });
+        let reception = db.get_channel_reception().unwrap();
+        assert!(reception[&strong].reception_ok);
+        assert!(!reception[&weak].reception_ok);
+        assert_eq!(reception[&weak].signal_level, Some(1.5));
+        assert!(db.get_channel_by_id(weak).unwrap().unwrap().is_enabled);
+
+        // A locked channel below the driver's threshold fails too
+        let update = db
+            .apply_scan_reception(bon_driver_id, &[probe(13, 25.0, true), probe(14, 8.0, true)], 10.0, true)
+            .unwrap();
+        assert_eq!(update, ReceptionUpdate { failed: 1, disabled: 1, restored: 0 });
+        assert!(!db.get_channel_by_id(weak).unwrap().unwrap().is_enabled);
+        assert!(db.get_channel_reception().unwrap()[&weak].auto_disabled);
+
+        // Recovered reception re-enables only auto-disabled channels
+        db.disable_channel(strong).unwrap();
+        let update = db
+            .apply_scan_reception(bon_driver_id, &[probe(13, 25.0, true), probe(14, 12.0, true)], 10.0, true)
+            .unwrap();
+        assert_eq!(update, ReceptionUpdate { failed: 0, disabled: 0, restored: 1 });
+        assert!(db.get_channel_by_id(weak).unwrap().unwrap().is_enabled);
+        assert!(!db.get_channel_by_id(strong).unwrap().unwrap().is_enabled);
+    }
+
     #[test]
     fn test_scan_history() {
         let db = Database::open_in_memory().unwrap();
@@ -0,0 +1,142 @@
+//! Channel usage statistics.
+//!
+//! Usage drained from [`ChannelUsageTracker`](crate::metrics::ChannelUsageTracker)
+//! is folded into hourly buckets per (NID, TSID, SID), so the table stays
+//! small while still answering "most watched over the last N hours".
+
+use rusqlite::params;
+
+use super::{ChannelUsageRecord, Database, Result};
+use crate::metrics::ChannelUsage;
+
+/// Width of one `channel_usage_stats` bucket in seconds.
+const CHANNEL_USAGE_BUCKET_SECS: i64 = 3600;
+
+impl Database {
+    /// Add usage observed at unix time `at` to its hourly bucket.
+    ///
+    /// Bytes and packets are summed; the peak viewer count keeps the maximum.
+    pub fn record_channel_usage(&self, at: i64, usage: &[ChannelUsage]) -> Result<()> {
+        if usage.is_empty() {
+            return Ok(());
+        }
+        let bucket_start = at - at.rem_euclid(CHANNEL_USAGE_BUCKET_SECS);
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO channel_usage_stats
+                     (nid, tsid, sid, bucket_start, bytes_served, packets_served, peak_viewers)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT(nid, tsid, sid, bucket_start) DO UPDATE SET
+                     bytes_served = bytes_served + excluded.bytes_served,
+                     packets_served = packets_served + excluded.packets_served,
+                     peak_viewers = MAX(peak_viewers, excluded.peak_viewers)",
+            )?;
+            for u in usage {
+                stmt.execute(params![
+                    u.nid,
+                    u.tsid,
+                    u.sid,
+                    bucket_start,
+                    u.bytes as i64,
+                    u.packets as i64,
+                    u.peak_viewers,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The `limit` most-served channels since unix time `since`.
+    ///
+    /// The window is widened to the start of the hour containing `since`.
+    pub fn get_top_channel_usage(&self, since: i64, limit: u32) -> Result<Vec<ChannelUsageRecord>> {
+        let since = since - since.rem_euclid(CHANNEL_USAGE_BUCKET_SECS);
+        let mut stmt = self.conn.prepare(
+            "SELECT u.nid, u.tsid, u.sid,
+                    (SELECT COALESCE(c.channel_name, c.raw_name) FROM channels c
+                     WHERE c.nid = u.nid AND c.tsid = u.tsid AND c.sid = u.sid
+                     ORDER BY c.is_enabled DESC, c.priority DESC LIMIT 1),
+                    SUM(u.bytes_served), SUM(u.packets_served), MAX(u.peak_viewers)
+             FROM channel_usage_stats u
+             WHERE u.bucket_start >= ?1
+             GROUP BY u.nid, u.tsid, u.sid
+             ORDER BY SUM(u.bytes_served) DESC, MAX(u.peak_viewers) DESC
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![since, limit], |row| {
+            Ok(ChannelUsageRecord {
+                nid: row.get(0)?,
+                tsid: row.get(1)?,
+                sid: row.get(2)?,
+                channel_name: row.get(3)?,
+                bytes_served: row.get(4)?,
+                packets_served: row.get(5)?,
+                peak_viewers: row.get(6)?,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::NewBonDriver;
+
+    fn usage(sid: u16, bytes: u64, peak_viewers: u32) -> ChannelUsage {
+        ChannelUsage {
+            nid: 0x7FE0,
+            tsid: 0x7FE0,
+            sid,
+            bytes,
+            packets: bytes / 188,
+            peak_viewers,
+        }
+    }
+
+    #[test]
+    fn test_channel_usage_buckets_and_ranking() {
+        let db = Database::open_in_memory().unwrap();
+        let driver = db
+            .insert_bon_driver(&NewBonDriver::new("BonDriver_A.dll"))
+            .unwrap();
+        db.connection()
+            .execute(
+                "INSERT INTO channels (bon_driver_id, nid, sid, tsid, channel_name)
+                 VALUES (?1, 32736, 1024, 32736, 'NHK総合')",
+                [driver],
+            )
+            .unwrap();
+
+        let hour = 1_700_000_000 - 1_700_000_000 % CHANNEL_USAGE_BUCKET_SECS;
+        // Two flushes in the same hour merge; a later hour gets its own bucket
+        db.record_channel_usage(hour + 60, &[usage(0x0400, 1880, 2), usage(0x0408, 188, 1)])
+            .unwrap();
+        db.record_channel_usage(hour + 120, &[usage(0x0400, 1880, 1)])
+            .unwrap();
+        db.record_channel_usage(hour + 3700, &[usage(0x0408, 18800, 3)])
+            .unwrap();
+
+        let top = db.get_top_channel_usage(hour, 10).unwrap();
+        assert_eq!(top.len(), 2);
+        assert_eq!(
+            (top[0].sid, top[0].bytes_served, top[0].peak_viewers),
+            (0x0408, 18988, 3)
+        );
+        assert_eq!(top[0].channel_name, None);
+        assert_eq!(
+            (top[1].sid, top[1].bytes_served, top[1].packets_served),
+            (0x0400, 3760, 20)
+        );
+        assert_eq!(top[1].peak_viewers, 2);
+        assert_eq!(top[1].channel_name.as_deref(), Some("NHK総合"));
+
+        // A window starting within the later hour covers only that bucket
+        let top = db.get_top_channel_usage(hour + 3650, 10).unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].bytes_served, 18800);
+        assert_eq!(db.get_top_channel_usage(hour, 1).unwrap().len(), 1);
+    }
+}
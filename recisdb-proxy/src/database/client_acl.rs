@@ -0,0 +1,121 @@
+//! Client ACL database operations.
+//!
+//! Entries grant a TLS client identity access to a BonDriver or to every
+//! driver in a group. Identities without any entry are unrestricted, so
+//! ACLs only need to be written for the clients that should be confined.
+
+use std::collections::HashSet;
+
+use rusqlite::{params, OptionalExtension, Row};
+
+use super::{ClientAclRecord, Database, DatabaseError, Result};
+
+impl Database {
+    /// Grant `identity` a BonDriver and/or a group. Returns the entry ID.
+    pub fn insert_client_acl(
+        &self,
+        identity: &str,
+        bon_driver_id: Option<i64>,
+        group_name: Option<&str>,
+    ) -> Result<i64> {
+        if identity.is_empty() {
+            return Err(DatabaseError::InvalidClientAcl("identity must not be empty".into()));
+        }
+        if bon_driver_id.is_none() && group_name.is_none() {
+            return Err(DatabaseError::InvalidClientAcl(
+                "an entry needs a bon_driver_id or a group_name".into(),
+            ));
+        }
+        self.conn.execute(
+            "INSERT INTO client_acl (identity, bon_driver_id, group_name) VALUES (?1, ?2, ?3)",
+            params![identity, bon_driver_id, group_name],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Get all ACL entries, grouped by identity.
+    pub fn get_client_acls(&self) -> Result<Vec<ClientAclRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, identity, bon_driver_id, group_name, created_at
+             FROM client_acl ORDER BY identity, id",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_client_acl)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Delete an ACL entry. Returns false when it did not exist.
+    pub fn delete_client_acl(&self, id: i64) -> Result<bool> {
+        Ok(self.conn.execute("DELETE FROM client_acl WHERE id = ?1", [id])? > 0)
+    }
+
+    /// BonDriver IDs `identity` may use, or `None` when it is unrestricted.
+    pub fn get_client_allowed_drivers(&self, identity: &str) -> Result<Option<HashSet<i64>>> {
+        let restricted = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM client_acl WHERE identity = ?1 LIMIT 1",
+                [identity],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !restricted {
+            return Ok(None);
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT b.id FROM bon_drivers b
+             JOIN client_acl a ON a.identity = ?1
+                AND (a.bon_driver_id = b.id
+                     OR (a.group_name IS NOT NULL AND a.group_name = b.group_name))",
+        )?;
+        let ids = stmt.query_map([identity], |row| row.get(0))?;
+        Ok(Some(ids.collect::<std::result::Result<_, _>>()?))
+    }
+
+    fn row_to_client_acl(row: &Row) -> rusqlite::Result<ClientAclRecord> {
+        Ok(ClientAclRecord {
+            id: row.get(0)?,
+            identity: row.get(1)?,
+            bon_driver_id: row.get(2)?,
+            group_name: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::NewBonDriver;
+
+    #[test]
+    fn test_client_allowed_drivers() {
+        let db = Database::open_in_memory().unwrap();
+        let a = db.insert_bon_driver(&NewBonDriver::new("BonDriver_A.dll")).unwrap();
+        let b = db.insert_bon_driver(&NewBonDriver::new("BonDriver_B.dll")).unwrap();
+        let c = db.insert_bon_driver(&NewBonDriver::new("BonDriver_C.dll")).unwrap();
+        db.set_group_name(b, Some("PX-Q")).unwrap();
+        db.set_group_name(c, Some("PX-Q")).unwrap();
+
+        // No entries: unrestricted
+        assert_eq!(db.get_client_allowed_drivers("house-1").unwrap(), None);
+
+        let entry = db.insert_client_acl("house-1", Some(a), None).unwrap();
+        db.insert_client_acl("house-2", None, Some("PX-Q")).unwrap();
+        assert!(db.insert_client_acl("house-3", None, None).is_err());
+
+        assert_eq!(db.get_client_allowed_drivers("house-1").unwrap(), Some(HashSet::from([a])));
+        assert_eq!(db.get_client_allowed_drivers("house-2").unwrap(), Some(HashSet::from([b, c])));
+        assert_eq!(db.get_client_acls().unwrap().len(), 2);
+
+        db.insert_client_acl("house-1", Some(b), None).unwrap();
+        assert!(db.delete_client_acl(entry).unwrap());
+        assert!(!db.delete_client_acl(entry).unwrap());
+        assert_eq!(db.get_client_allowed_drivers("house-1").unwrap(), Some(HashSet::from([b])));
+
+        // An entry for a deleted driver grants nothing but keeps the restriction
+        db.delete_bon_driver(b).unwrap();
+        assert_eq!(db.get_client_allowed_drivers("house-1").unwrap(), Some(HashSet::new()));
+    }
+}
@@ -0,0 +1,438 @@
+//! JSON export/import of BonDriver and channel configuration.
+//!
+//! Used to move a setup between machines without copying the SQLite file.
+//! Drivers are matched by DLL path and channels by NID/SID/TSID (plus
+//! manual sheet) within their driver, so importing the same document twice
+//! leaves the database unchanged.
+
+use recisdb_protocol::ChannelInfo;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::{ChannelRecord, ConfigImportSummary, Database, DatabaseError, NewBonDriver, Result};
+
+/// Format version written by `export_config`.
+const CONFIG_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigDocument {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    bon_drivers: Vec<BonDriverConfig>,
+}
+
+/// Exported BonDriver settings. Omitted fields are left unchanged on import.
+#[derive(Debug, Serialize, Deserialize)]
+struct BonDriverConfig {
+    dll_path: String,
+    display_name: Option<String>,
+    group_name: Option<String>,
+    max_instances: Option<i32>,
+    supports_4k: Option<bool>,
+    group_strategy: Option<String>,
+    auto_scan_enabled: Option<bool>,
+    scan_interval_hours: Option<i32>,
+    #[serde(default)]
+    scan_cron: Option<String>,
+    #[serde(default)]
+    scan_scope: Option<String>,
+    #[serde(default)]
+    broadcast_capacity: Option<i64>,
+    #[serde(default)]
+    lag_policy: Option<String>,
+    #[serde(default)]
+    reception_min_signal: Option<f64>,
+    #[serde(default)]
+    auto_disable_no_reception: Option<bool>,
+    scan_priority: Option<i32>,
+    passive_scan_enabled: Option<bool>,
+    #[serde(default)]
+    channels: Vec<ChannelConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChannelConfig {
+    #[serde(flatten)]
+    info: ChannelInfo,
+    priority: Option<i32>,
+    is_enabled: Option<bool>,
+}
+
+enum ImportOutcome {
+    Added,
+    Updated,
+    Skipped,
+}
+
+/// Whether an imported optional value would change the stored one.
+fn differs<T: PartialEq>(new: &Option<T>, old: &Option<T>) -> bool {
+    new.is_some() && new != old
+}
+
+impl Database {
+    /// Export all BonDrivers and their channels as a JSON document.
+    pub fn export_config(&self) -> Result<serde_json::Value> {
+        let mut bon_drivers = Vec::new();
+        for driver in self.get_all_bon_drivers()? {
+            let (broadcast_capacity, lag_policy) = self.get_broadcast_config_for_path(&driver.dll_path)?;
+            let (reception_min_signal, auto_disable) = self.get_reception_config_for_path(&driver.dll_path)?;
+            let channels = self
+                .get_channels_by_bon_driver(driver.id)?
+                .iter()
+                .map(|ch| ChannelConfig {
+                    info: ch.to_channel_info(),
+                    priority: Some(ch.priority),
+                    is_enabled: Some(ch.is_enabled),
+                })
+                .collect();
+
+            bon_drivers.push(BonDriverConfig {
+                supports_4k: Some(self.get_supports_4k_for_path(&driver.dll_path)?),
+                group_strategy: self.get_group_strategy_for_path(&driver.dll_path)?,
+                scan_cron: self.get_scan_cron(driver.id)?,
+                scan_scope: self.get_scan_scope(driver.id)?,
+                broadcast_capacity,
+                lag_policy,
+                reception_min_signal,
+                auto_disable_no_reception: Some(auto_disable),
+                dll_path: driver.dll_path,
+                display_name: driver.driver_name,
+                group_name: driver.group_name,
+                max_instances: Some(driver.max_instances),
+                auto_scan_enabled: Some(driver.auto_scan_enabled),
+                scan_interval_hours: Some(driver.scan_interval_hours),
+                scan_priority: Some(driver.scan_priority),
+                passive_scan_enabled: Some(driver.passive_scan_enabled),
+                channels,
+            });
+        }
+
+        let document = ConfigDocument {
+            version: CONFIG_FORMAT_VERSION,
+            bon_drivers,
+        };
+        serde_json::to_value(document).map_err(|e| DatabaseError::InvalidConfig(e.to_string()))
+    }
+
+    /// Import a document produced by `export_config`.
+    ///
+    /// Existing drivers and channels are updated in place; entries identical
+    /// to the database are counted as skipped. The import is all-or-nothing.
+    pub fn import_config(&self, value: serde_json::Value) -> Result<ConfigImportSummary> {
+        let document: ConfigDocument =
+            serde_json::from_value(value).map_err(|e| DatabaseError::InvalidConfig(e.to_string()))?;
+        if document.version > CONFIG_FORMAT_VERSION {
+            return Err(DatabaseError::InvalidConfig(format!(
+                "unsupported format version {}",
+                document.version
+            )));
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut summary = ConfigImportSummary::default();
+
+        for driver in &document.bon_drivers {
+            let dll_path = driver.dll_path.trim();
+            if dll_path.is_empty() {
+                summary.drivers_skipped += 1;
+                continue;
+            }
+
+            let (bon_driver_id, outcome) = self.import_bon_driver(dll_path, driver)?;
+            match outcome {
+                ImportOutcome::Added => summary.drivers_added += 1,
+                ImportOutcome::Updated => summary.drivers_updated += 1,
+                ImportOutcome::Skipped => summary.drivers_skipped += 1,
+            }
+
+            for channel in &driver.channels {
+                match self.import_channel(bon_driver_id, channel)? {
+                    ImportOutcome::Added => summary.channels_added += 1,
+                    ImportOutcome::Updated => summary.channels_updated += 1,
+                    ImportOutcome::Skipped => summary.channels_skipped += 1,
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(summary)
+    }
+
+    fn import_bon_driver(&self, dll_path: &str, config: &BonDriverConfig) -> Result<(i64, ImportOutcome)> {
+        let (id, existing) = match self.get_bon_driver_by_path(dll_path)? {
+            Some(record) => (record.id, Some(record)),
+            None => {
+                let id = self.insert_bon_driver(&NewBonDriver {
+                    dll_path: dll_path.to_string(),
+                    driver_name: config.display_name.clone(),
+                    version: None,
+                    max_instances: config.max_instances,
+                })?;
+                (id, None)
+            }
+        };
+
+        let mut changed = false;
+        if let Some(record) = &existing {
+            if let Some(name) = config.display_name.as_deref().filter(|n| record.driver_name.as_deref() != Some(*n)) {
+                self.update_bon_driver_display_name(id, name)?;
+                changed = true;
+            }
+            if let Some(max_instances) = config.max_instances.filter(|v| *v != record.max_instances) {
+                self.update_bon_driver_max_instances(id, max_instances)?;
+                changed = true;
+            }
+        }
+
+        let current_group = existing.as_ref().and_then(|r| r.group_name.clone());
+        if differs(&config.group_name, &current_group) {
+            self.set_group_name(id, config.group_name.as_deref())?;
+            changed = true;
+        }
+
+        let supports_4k = self.get_supports_4k_for_path(dll_path)?;
+        if let Some(value) = config.supports_4k.filter(|v| *v != supports_4k) {
+            self.update_bon_driver_supports_4k(id, value)?;
+            changed = true;
+        }
+
+        let group_strategy = self.get_group_strategy_for_path(dll_path)?;
+        if differs(&config.group_strategy, &group_strategy) {
+            self.update_bon_driver_group_strategy(id, config.group_strategy.as_deref())?;
+            changed = true;
+        }
+
+        if differs(&config.scan_cron, &self.get_scan_cron(id)?) {
+            self.update_bon_driver_scan_cron(id, config.scan_cron.as_deref(), None)?;
+            changed = true;
+        }
+
+        if differs(&config.scan_scope, &self.get_scan_scope(id)?) {
+            self.update_bon_driver_scan_scope(id, config.scan_scope.as_deref())?;
+            changed = true;
+        }
+
+        let (broadcast_capacity, lag_policy) = self.get_broadcast_config_for_path(dll_path)?;
+        if differs(&config.broadcast_capacity, &broadcast_capacity) {
+            self.update_bon_driver_broadcast_capacity(id, config.broadcast_capacity)?;
+            changed = true;
+        }
+        if differs(&config.lag_policy, &lag_policy) {
+            self.update_bon_driver_lag_policy(id, config.lag_policy.as_deref())?;
+            changed = true;
+        }
+
+        let (reception_min_signal, auto_disable) = self.get_reception_config_for_path(dll_path)?;
+        if differs(&config.reception_min_signal, &reception_min_signal) {
+            self.update_bon_driver_reception_min_signal(id, config.reception_min_signal)?;
+            changed = true;
+        }
+        if let Some(value) = config.auto_disable_no_reception.filter(|v| *v != auto_disable) {
+            self.update_bon_driver_auto_disable_no_reception(id, value)?;
+            changed = true;
+        }
+
+        let scan_changed = match &existing {
+            Some(record) => {
+                differs(&config.auto_scan_enabled, &Some(record.auto_scan_enabled))
+                    || differs(&config.scan_interval_hours, &Some(record.scan_interval_hours))
+                    || differs(&config.scan_priority, &Some(record.scan_priority))
+                    || differs(&config.passive_scan_enabled, &Some(record.passive_scan_enabled))
+            }
+            None => true,
+        };
+        if scan_changed {
+            self.update_scan_config(
+                id,
+                config.auto_scan_enabled,
+                config.scan_interval_hours,
+                config.scan_priority,
+                config.passive_scan_enabled,
+            )?;
+            changed = true;
+        }
+
+        let outcome = match (existing, changed) {
+            (None, _) => ImportOutcome::Added,
+            (Some(_), true) => ImportOutcome::Updated,
+            (Some(_), false) => ImportOutcome::Skipped,
+        };
+        Ok((id, outcome))
+    }
+
+    fn import_channel(&self, bon_driver_id: i64, config: &ChannelConfig) -> Result<ImportOutcome> {
+        let info = &config.info;
+        let Some(existing) =
+            self.get_channel_by_key(bon_driver_id, info.nid, info.sid, info.tsid, info.manual_sheet)?
+        else {
+            let id = self.insert_channel(bon_driver_id, info)?;
+            self.update_channel_fields(id, None, config.priority, config.is_enabled)?;
+            return Ok(ImportOutcome::Added);
+        };
+
+        if !Self::channel_import_differs(&existing, config) {
+            return Ok(ImportOutcome::Skipped);
+        }
+
+        self.conn.execute(
+            "UPDATE channels SET
+                raw_name = COALESCE(?2, raw_name),
+                channel_name = COALESCE(?3, channel_name),
+                physical_ch = COALESCE(?4, physical_ch),
+                remote_control_key = COALESCE(?5, remote_control_key),
+                service_type = COALESCE(?6, service_type),
+                network_name = COALESCE(?7, network_name),
+                bon_space = COALESCE(?8, bon_space),
+                bon_channel = COALESCE(?9, bon_channel),
+                band_type = COALESCE(?10, band_type),
+                terrestrial_region = COALESCE(?11, terrestrial_region),
+                is_one_seg = ?12,
+                priority = COALESCE(?13, priority),
+                is_enabled = COALESCE(?14, is_enabled),
+                ts_name = COALESCE(?15, ts_name),
+                updated_at = strftime('%s', 'now')
+             WHERE id = ?1",
+            params![
+                existing.id,
+                info.raw_name,
+                info.channel_name,
+                info.physical_ch.map(|v| v as i32),
+                info.remote_control_key.map(|v| v as i32),
+                info.service_type.map(|v| v as i32),
+                info.network_name,
+                info.bon_space.map(|v| v as i32),
+                info.bon_channel.map(|v| v as i32),
+                info.band_type.map(|v| v as i32),
+                info.terrestrial_region,
+                info.is_one_seg as i32,
+                config.priority,
+                config.is_enabled.map(|v| v as i32),
+                info.ts_name,
+            ],
+        )?;
+        Ok(ImportOutcome::Updated)
+    }
+
+    fn channel_import_differs(existing: &ChannelRecord, config: &ChannelConfig) -> bool {
+        let info = &config.info;
+        differs(&info.raw_name, &existing.raw_name)
+            || differs(&info.channel_name, &existing.channel_name)
+            || differs(&info.physical_ch, &existing.physical_ch)
+            || differs(&info.remote_control_key, &existing.remote_control_key)
+            || differs(&info.service_type, &existing.service_type)
+            || differs(&info.network_name, &existing.network_name)
+            || differs(&info.ts_name, &existing.ts_name)
+            || differs(&info.bon_space, &existing.bon_space)
+            || differs(&info.bon_channel, &existing.bon_channel)
+            || differs(&info.band_type, &existing.band_type)
+            || differs(&info.terrestrial_region, &existing.terrestrial_region)
+            || info.is_one_seg != existing.is_one_seg
+            || differs(&config.priority, &Some(existing.priority))
+            || differs(&config.is_enabled, &Some(existing.is_enabled))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn populated_db() -> Database {
+        let db = Database::open_in_memory().unwrap();
+        let id = db.insert_bon_driver(&NewBonDriver::new("BonDriver_PX-MLT1.dll")).unwrap();
+        db.update_bon_driver_display_name(id, "PX-MLT 1").unwrap();
+        db.set_group_name(id, Some("PX-MLT")).unwrap();
+        db.update_bon_driver_max_instances(id, 2).unwrap();
+        db.update_scan_config(id, Some(false), Some(12), Some(5), None).unwrap();
+
+        let mut info = ChannelInfo::new(0x7FE8, 1024, 32736);
+        info.channel_name = Some("ＮＨＫ総合１・東京".to_string());
+        info.bon_space = Some(0);
+        info.bon_channel = Some(13);
+        let ch = db.insert_channel(id, &info).unwrap();
+        db.update_channel_fields(ch, None, Some(3), Some(false)).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let exported = populated_db().export_config().unwrap();
+
+        let target = Database::open_in_memory().unwrap();
+        let summary = target.import_config(exported.clone()).unwrap();
+        assert_eq!(summary.drivers_added, 1);
+        assert_eq!(summary.channels_added, 1);
+
+        let driver = target.get_bon_driver_by_path("BonDriver_PX-MLT1.dll").unwrap().unwrap();
+        assert_eq!(driver.driver_name.as_deref(), Some("PX-MLT 1"));
+        assert_eq!(driver.group_name.as_deref(), Some("PX-MLT"));
+        assert_eq!(driver.max_instances, 2);
+        assert!(!driver.auto_scan_enabled);
+        assert_eq!(driver.scan_interval_hours, 12);
+        assert_eq!(driver.scan_priority, 5);
+
+        let channel = target
+            .get_channel_by_key(driver.id, 0x7FE8, 1024, 32736, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(channel.channel_name.as_deref(), Some("ＮＨＫ総合１・東京"));
+        assert_eq!(channel.bon_channel, Some(13));
+        assert_eq!(channel.priority, 3);
+        assert!(!channel.is_enabled);
+
+        // A second import changes nothing
+        let summary = target.import_config(exported).unwrap();
+        assert_eq!(summary.drivers_skipped, 1);
+        assert_eq!(summary.channels_skipped, 1);
+        assert_eq!(summary.drivers_added + summary.drivers_updated, 0);
+        assert_eq!(summary.channels_added + summary.channels_updated, 0);
+    }
+
+    #[test]
+    fn test_import_updates_existing_entries() {
+        let db = populated_db();
+        let summary = db
+            .import_config(serde_json::json!({
+                "version": 1,
+                "bon_drivers": [{
+                    "dll_path": "BonDriver_PX-MLT1.dll",
+                    "max_instances": 4,
+                    "channels": [
+                        { "nid": 0x7FE8, "sid": 1024, "tsid": 32736, "channel_name": "NHK総合", "priority": 3 },
+                        { "nid": 0x7FE8, "sid": 1032, "tsid": 32736, "channel_name": "NHK Eテレ" }
+                    ]
+                }, {
+                    "dll_path": "  "
+                }]
+            }))
+            .unwrap();
+        assert_eq!(summary.drivers_updated, 1);
+        assert_eq!(summary.drivers_skipped, 1);
+        assert_eq!(summary.channels_updated, 1);
+        assert_eq!(summary.channels_added, 1);
+
+        let driver = db.get_bon_driver_by_path("BonDriver_PX-MLT1.dll").unwrap().unwrap();
+        assert_eq!(driver.max_instances, 4);
+        // Omitted fields are left alone
+        assert_eq!(driver.group_name.as_deref(), Some("PX-MLT"));
+
+        let channel = db.get_channel_by_key(driver.id, 0x7FE8, 1024, 32736, None).unwrap().unwrap();
+        assert_eq!(channel.channel_name.as_deref(), Some("NHK総合"));
+        assert_eq!(channel.bon_channel, Some(13));
+        assert!(!channel.is_enabled);
+        assert_eq!(db.get_channels_by_bon_driver(driver.id).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_document() {
+        let db = Database::open_in_memory().unwrap();
+        assert!(matches!(
+            db.import_config(serde_json::json!({ "bon_drivers": "nope" })),
+            Err(DatabaseError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            db.import_config(serde_json::json!({ "version": 99 })),
+            Err(DatabaseError::InvalidConfig(_))
+        ));
+    }
+}
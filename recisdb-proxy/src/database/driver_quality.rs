@@ -3,12 +3,19 @@
 use rusqlite::params;
 
 use super::{BonDriverRecord, Database, DriverQualityStats, Result};
+use crate::tuner::quality_scorer::QualityScorer;
+
+/// Driver ranking row:
+/// (driver, quality_score, recent_drop_rate, total_sessions, stall_count)
+pub type DriverRankingRow = (BonDriverRecord, f64, f64, i64, i64);
 
 impl Database {
     /// Get driver quality stats by BonDriver ID.
+    ///
+    /// The stats are returned as of `last_updated`, without decay applied.
     pub fn get_driver_quality_stats(&self, bon_driver_id: i64) -> Result<Option<DriverQualityStats>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, bon_driver_id, total_packets, dropped_packets, scrambled_packets, error_packets, total_sessions, quality_score, recent_drop_rate, recent_error_rate, last_updated FROM driver_quality_stats WHERE bon_driver_id = ?1",
+            "SELECT id, bon_driver_id, total_packets, dropped_packets, scrambled_packets, error_packets, total_sessions, quality_score, recent_drop_rate, recent_error_rate, last_updated, COALESCE(stall_count, 0), COALESCE(weighted_packets, total_packets, 0), COALESCE(weighted_dropped, dropped_packets, 0), COALESCE(weighted_scrambled, scrambled_packets, 0), COALESCE(weighted_errors, error_packets, 0), COALESCE(weighted_sessions, total_sessions, 0), COALESCE(weighted_stalls, stall_count, 0) FROM driver_quality_stats WHERE bon_driver_id = ?1",
         )?;
 
         let result = stmt.query_row([bon_driver_id], |row| {
@@ -24,6 +31,13 @@ impl Database {
                 recent_drop_rate: row.get(8)?,
                 recent_error_rate: row.get(9)?,
                 last_updated: row.get(10)?,
+                stall_count: row.get(11)?,
+                weighted_packets: row.get(12)?,
+                weighted_dropped: row.get(13)?,
+                weighted_scrambled: row.get(14)?,
+                weighted_errors: row.get(15)?,
+                weighted_sessions: row.get(16)?,
+                weighted_stalls: row.get(17)?,
             })
         });
 
@@ -35,61 +49,74 @@ impl Database {
     }
 
     /// Upsert driver quality stats.
-    #[allow(clippy::too_many_arguments)]
-    pub fn upsert_driver_quality_stats(
-        &self,
-        bon_driver_id: i64,
-        total_packets: i64,
-        dropped_packets: i64,
-        scrambled_packets: i64,
-        error_packets: i64,
-        total_sessions: i64,
-        quality_score: f64,
-        recent_drop_rate: f64,
-        recent_error_rate: f64,
-        last_updated: i64,
-    ) -> Result<()> {
+    pub fn upsert_driver_quality_stats(&self, stats: &DriverQualityStats) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO driver_quality_stats (bon_driver_id, total_packets, dropped_packets, scrambled_packets, error_packets, total_sessions, quality_score, recent_drop_rate, recent_error_rate, last_updated) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10) ON CONFLICT(bon_driver_id) DO UPDATE SET total_packets = excluded.total_packets, dropped_packets = excluded.dropped_packets, scrambled_packets = excluded.scrambled_packets, error_packets = excluded.error_packets, total_sessions = excluded.total_sessions, quality_score = excluded.quality_score, recent_drop_rate = excluded.recent_drop_rate, recent_error_rate = excluded.recent_error_rate, last_updated = excluded.last_updated",
+            "INSERT INTO driver_quality_stats (bon_driver_id, total_packets, dropped_packets, scrambled_packets, error_packets, total_sessions, quality_score, recent_drop_rate, recent_error_rate, stall_count, weighted_packets, weighted_dropped, weighted_scrambled, weighted_errors, weighted_sessions, weighted_stalls, last_updated) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17) ON CONFLICT(bon_driver_id) DO UPDATE SET total_packets = excluded.total_packets, dropped_packets = excluded.dropped_packets, scrambled_packets = excluded.scrambled_packets, error_packets = excluded.error_packets, total_sessions = excluded.total_sessions, quality_score = excluded.quality_score, recent_drop_rate = excluded.recent_drop_rate, recent_error_rate = excluded.recent_error_rate, stall_count = excluded.stall_count, weighted_packets = excluded.weighted_packets, weighted_dropped = excluded.weighted_dropped, weighted_scrambled = excluded.weighted_scrambled, weighted_errors = excluded.weighted_errors, weighted_sessions = excluded.weighted_sessions, weighted_stalls = excluded.weighted_stalls, last_updated = excluded.last_updated",
             params![
-                bon_driver_id,
-                total_packets,
-                dropped_packets,
-                scrambled_packets,
-                error_packets,
-                total_sessions,
-                quality_score,
-                recent_drop_rate,
-                recent_error_rate,
-                last_updated,
+                stats.bon_driver_id,
+                stats.total_packets,
+                stats.dropped_packets,
+                stats.scrambled_packets,
+                stats.error_packets,
+                stats.total_sessions,
+                stats.quality_score,
+                stats.recent_drop_rate,
+                stats.recent_error_rate,
+                stats.stall_count,
+                stats.weighted_packets,
+                stats.weighted_dropped,
+                stats.weighted_scrambled,
+                stats.weighted_errors,
+                stats.weighted_sessions,
+                stats.weighted_stalls,
+                stats.last_updated,
             ],
         )?;
         Ok(())
     }
 
-    /// Get driver quality score by DLL path.
+    /// Get driver quality score by DLL path, decayed to the current time.
     pub fn get_driver_quality_score_by_path(&self, dll_path: &str) -> Result<f64> {
         let mut stmt = self.conn.prepare(
-            "SELECT COALESCE(dqs.quality_score, 1.0) FROM bon_drivers bd LEFT JOIN driver_quality_stats dqs ON bd.id = dqs.bon_driver_id WHERE bd.dll_path = ?1",
+            "SELECT dqs.quality_score, dqs.last_updated FROM bon_drivers bd LEFT JOIN driver_quality_stats dqs ON bd.id = dqs.bon_driver_id WHERE bd.dll_path = ?1",
         )?;
 
-        let result = stmt.query_row([dll_path], |row| row.get(0));
+        let result = stmt.query_row([dll_path], |row| {
+            Ok((row.get::<_, Option<f64>>(0)?, row.get::<_, Option<i64>>(1)?))
+        });
 
         match result {
-            Ok(score) => Ok(score),
+            Ok((Some(score), Some(last_updated))) => Ok(QualityScorer::decayed_score(
+                score,
+                last_updated,
+                chrono::Utc::now().timestamp(),
+                QualityScorer::half_life_hours(self),
+            )),
+            Ok((score, _)) => Ok(score.unwrap_or(1.0)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(1.0),
             Err(e) => Err(e.into()),
         }
     }
 
     /// Get BonDriver ranking by quality score.
-    pub fn get_bondrivers_ranking(&self) -> Result<Vec<(BonDriverRecord, f64, f64, i64)>> {
+    ///
+    /// Rows are `(driver, quality_score, recent_drop_rate, total_sessions, stall_count)`.
+    /// Scores are decayed to the current time, so a driver whose problems are
+    /// long past climbs back up.
+    pub fn get_bondrivers_ranking(&self) -> Result<Vec<DriverRankingRow>> {
         let mut stmt = self.conn.prepare(
-            "SELECT bd.id, bd.dll_path, bd.driver_name, bd.version, bd.group_name, bd.auto_scan_enabled, bd.scan_interval_hours, bd.scan_priority, bd.last_scan, bd.next_scan_at, bd.passive_scan_enabled, bd.max_instances, bd.created_at, bd.updated_at, COALESCE(dqs.quality_score, 1.0) as quality_score, COALESCE(dqs.recent_drop_rate, 0.0) as recent_drop_rate, COALESCE(dqs.total_sessions, 0) as total_sessions FROM bon_drivers bd LEFT JOIN driver_quality_stats dqs ON bd.id = dqs.bon_driver_id ORDER BY quality_score DESC, total_sessions DESC, bd.dll_path ASC",
+            "SELECT bd.id, bd.dll_path, bd.driver_name, bd.version, bd.group_name, bd.auto_scan_enabled, bd.scan_interval_hours, bd.scan_priority, bd.last_scan, bd.next_scan_at, bd.passive_scan_enabled, bd.max_instances, bd.created_at, bd.updated_at, COALESCE(dqs.quality_score, 1.0) as quality_score, COALESCE(dqs.recent_drop_rate, 0.0) as recent_drop_rate, COALESCE(dqs.total_sessions, 0) as total_sessions, COALESCE(dqs.stall_count, 0) as stall_count, dqs.last_updated FROM bon_drivers bd LEFT JOIN driver_quality_stats dqs ON bd.id = dqs.bon_driver_id",
         )?;
 
-        let rows = stmt
+        let now = chrono::Utc::now().timestamp();
+        let half_life_hours = QualityScorer::half_life_hours(self);
+        let mut rows = stmt
             .query_map([], |row| {
+                let score: f64 = row.get(14)?;
+                let score = match row.get::<_, Option<i64>>(18)? {
+                    Some(last_updated) => QualityScorer::decayed_score(score, last_updated, now, half_life_hours),
+                    None => score,
+                };
                 Ok((
                     BonDriverRecord {
                         id: row.get(0)?,
@@ -107,12 +134,20 @@ impl Database {
                         created_at: row.get(12)?,
                         updated_at: row.get(13)?,
                     },
-                    row.get(14)?,
+                    score,
                     row.get(15)?,
                     row.get(16)?,
+                    row.get(17)?,
                 ))
             })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+            .collect::<std::result::Result<Vec<DriverRankingRow>, _>>()?;
+
+        rows.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.3.cmp(&a.3))
+                .then_with(|| a.0.dll_path.cmp(&b.0.dll_path))
+        });
 
         Ok(rows)
     }
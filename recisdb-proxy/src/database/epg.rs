@@ -0,0 +1,308 @@
+//! EPG event database operations.
+
+use rusqlite::{params, Row};
+
+use super::{Database, EpgEventRecord, NewEpgEvent, NowNextEvent, NowNextRecord, Result};
+
+const UPSERT_EPG_EVENT_SQL: &str =
+    "INSERT INTO epg_events (nid, tsid, sid, event_id, start_time, duration_secs, title, description, updated_at)
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, strftime('%s', 'now'))
+     ON CONFLICT(nid, tsid, sid, event_id) DO UPDATE SET
+        start_time = excluded.start_time,
+        duration_secs = excluded.duration_secs,
+        title = COALESCE(excluded.title, epg_events.title),
+        description = COALESCE(excluded.description, epg_events.description),
+        updated_at = excluded.updated_at";
+
+impl Database {
+    /// Insert or update a batch of EPG events in one transaction.
+    ///
+    /// Returns the number of events written.
+    pub fn insert_events(&mut self, events: &[NewEpgEvent]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(UPSERT_EPG_EVENT_SQL)?;
+            for event in events {
+                stmt.execute(params![
+                    event.nid as i32,
+                    event.tsid as i32,
+                    event.sid as i32,
+                    event.event_id as i32,
+                    event.start_time,
+                    event.duration_secs,
+                    event.title,
+                    event.description
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(events.len())
+    }
+
+    /// Get an EPG event by NID/SID/event_id.
+    ///
+    /// If the same event was seen on more than one TS, the most recently
+    /// updated one is returned.
+    pub fn get_epg_event(&self, nid: u16, sid: u16, event_id: u16) -> Result<Option<EpgEventRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM epg_events WHERE nid = ?1 AND sid = ?2 AND event_id = ?3
+             ORDER BY updated_at DESC, id DESC LIMIT 1",
+        )?;
+
+        let result = stmt.query_row(
+            params![nid as i32, sid as i32, event_id as i32],
+            Self::row_to_epg_event_record,
+        );
+
+        match result {
+            Ok(record) => Ok(Some(record)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get events of a service overlapping the `[from, to)` unix time range,
+    /// ordered by start time. Events with an undefined start time are skipped.
+    pub fn get_events_for_service(
+        &self,
+        nid: u16,
+        tsid: u16,
+        sid: u16,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<EpgEventRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM epg_events
+             WHERE nid = ?1 AND tsid = ?2 AND sid = ?3
+               AND start_time IS NOT NULL
+               AND start_time < ?5
+               AND start_time + COALESCE(duration_secs, 0) > ?4
+             ORDER BY start_time, event_id",
+        )?;
+
+        let rows = stmt.query_map(
+            params![nid as i32, tsid as i32, sid as i32, from, to],
+            Self::row_to_epg_event_record,
+        )?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Get the programme airing at `now` and the one after it for every
+    /// enabled service, in channel display order.
+    ///
+    /// One query covers all services: each service's first two events that
+    /// have not ended by `now` are joined on. Services without EPG data are
+    /// listed with both events None.
+    pub fn get_now_next(&self, now: i64) -> Result<Vec<NowNextRecord>> {
+        let mut stmt = self.conn.prepare(
+            "WITH services AS (
+                SELECT nid, tsid, sid, MAX(channel_name) AS service_name,
+                       MIN(display_order) AS display_order, MIN(remote_control_key) AS remote_control_key
+                FROM channels WHERE is_enabled = 1
+                GROUP BY nid, tsid, sid
+             ),
+             upcoming AS (
+                SELECT nid, tsid, sid, event_id, title, start_time, duration_secs,
+                       ROW_NUMBER() OVER (PARTITION BY nid, tsid, sid ORDER BY start_time, event_id) AS rn
+                FROM epg_events
+                WHERE start_time IS NOT NULL AND start_time + COALESCE(duration_secs, 0) > ?1
+             )
+             SELECT s.nid, s.tsid, s.sid, s.service_name,
+                    u.event_id, u.title, u.start_time, u.duration_secs
+             FROM services s
+             LEFT JOIN upcoming u ON u.nid = s.nid AND u.tsid = s.tsid AND u.sid = s.sid AND u.rn <= 2
+             ORDER BY s.display_order IS NULL, s.display_order,
+                      s.remote_control_key IS NULL, s.remote_control_key,
+                      s.nid, s.tsid, s.sid, u.rn",
+        )?;
+
+        let rows = stmt.query_map([now], |row| {
+            let event = match row.get::<_, Option<i32>>(4)? {
+                Some(event_id) => Some(NowNextEvent {
+                    event_id: event_id as u16,
+                    title: row.get(5)?,
+                    start_time: row.get(6)?,
+                    duration_secs: row.get(7)?,
+                }),
+                None => None,
+            };
+            Ok((
+                row.get::<_, i32>(0)? as u16,
+                row.get::<_, i32>(1)? as u16,
+                row.get::<_, i32>(2)? as u16,
+                row.get::<_, Option<String>>(3)?,
+                event,
+            ))
+        })?;
+
+        let mut records: Vec<NowNextRecord> = Vec::new();
+        for row in rows {
+            let (nid, tsid, sid, service_name, event) = row?;
+            let record = match records.last_mut() {
+                Some(last) if (last.nid, last.tsid, last.sid) == (nid, tsid, sid) => last,
+                _ => {
+                    records.push(NowNextRecord { nid, tsid, sid, service_name, now: None, next: None });
+                    records.last_mut().unwrap()
+                }
+            };
+            let Some(event) = event else { continue };
+            // The first unfinished event is only "now" once it has started
+            if record.now.is_none() && record.next.is_none() && event.start_time <= now {
+                record.now = Some(event);
+            } else if record.next.is_none() {
+                record.next = Some(event);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Delete events that ended before `before` (unix time).
+    ///
+    /// Returns the number of deleted events.
+    pub fn prune_expired_events(&self, before: i64) -> Result<usize> {
+        let deleted = self.conn.execute(
+            "DELETE FROM epg_events
+             WHERE start_time IS NOT NULL
+               AND start_time + COALESCE(duration_secs, 0) < ?1",
+            [before],
+        )?;
+        Ok(deleted)
+    }
+
+    fn row_to_epg_event_record(row: &Row) -> rusqlite::Result<EpgEventRecord> {
+        Ok(EpgEventRecord {
+            id: row.get("id")?,
+            nid: row.get::<_, i32>("nid")? as u16,
+            tsid: row.get::<_, i32>("tsid")? as u16,
+            sid: row.get::<_, i32>("sid")? as u16,
+            event_id: row.get::<_, i32>("event_id")? as u16,
+            start_time: row.get("start_time")?,
+            duration_secs: row.get("duration_secs")?,
+            title: row.get("title")?,
+            description: row.get("description")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_id: u16, start_time: i64, duration_secs: i64, title: &str) -> NewEpgEvent {
+        NewEpgEvent {
+            nid: 0x7FE8,
+            tsid: 32736,
+            sid: 1024,
+            event_id,
+            start_time: Some(start_time),
+            duration_secs: Some(duration_secs),
+            title: Some(title.to_string()),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_insert_and_get_epg_event() {
+        let mut db = Database::open_in_memory().unwrap();
+
+        assert!(db.get_epg_event(0x7FE8, 1024, 0x1234).unwrap().is_none());
+
+        db.insert_events(&[event(0x1234, 1_700_000_000, 1800, "ニュース")])
+            .unwrap();
+        let stored = db.get_epg_event(0x7FE8, 1024, 0x1234).unwrap().unwrap();
+        assert_eq!(stored.tsid, 32736);
+        assert_eq!(stored.start_time, Some(1_700_000_000));
+        assert_eq!(stored.title.as_deref(), Some("ニュース"));
+
+        // Update keeps the existing title when the new one is unknown
+        let mut update = event(0x1234, 1_700_000_600, 1200, "");
+        update.title = None;
+        update.description = Some("概要".to_string());
+        db.insert_events(&[update]).unwrap();
+        let stored = db.get_epg_event(0x7FE8, 1024, 0x1234).unwrap().unwrap();
+        assert_eq!(stored.start_time, Some(1_700_000_600));
+        assert_eq!(stored.duration_secs, Some(1200));
+        assert_eq!(stored.title.as_deref(), Some("ニュース"));
+        assert_eq!(stored.description.as_deref(), Some("概要"));
+    }
+
+    #[test]
+    fn test_insert_query_and_prune_events() {
+        let mut db = Database::open_in_memory().unwrap();
+        let base = 1_700_000_000;
+
+        let written = db
+            .insert_events(&[
+                event(1, base, 1800, "朝"),
+                event(2, base + 1800, 1800, "昼"),
+                event(3, base + 3600, 1800, "夜"),
+            ])
+            .unwrap();
+        assert_eq!(written, 3);
+
+        // Re-inserting the same key updates in place
+        db.insert_events(&[event(2, base + 1800, 3600, "昼（延長）")]).unwrap();
+        let count: i64 = db
+            .connection()
+            .query_row("SELECT COUNT(*) FROM epg_events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 3);
+
+        // Same event_id on another TS is a separate event
+        let mut other_ts = event(2, base, 60, "別TS");
+        other_ts.tsid = 32737;
+        db.insert_events(&[other_ts]).unwrap();
+
+        // Range overlaps the running event and the next one
+        let events = db
+            .get_events_for_service(0x7FE8, 32736, 1024, base + 1000, base + 2000)
+            .unwrap();
+        let ids: Vec<u16> = events.iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(events[1].duration_secs, Some(3600));
+        assert_eq!(events[1].title.as_deref(), Some("昼（延長）"));
+
+        // Only the first event has ended by base + 1800
+        assert_eq!(db.prune_expired_events(base + 1800 + 1).unwrap(), 2);
+        assert!(db.get_epg_event(0x7FE8, 1024, 1).unwrap().is_none());
+        assert!(db.get_epg_event(0x7FE8, 1024, 2).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_now_next() {
+        let mut db = Database::open_in_memory().unwrap();
+        let base = 1_700_000_000;
+        let driver_id = db.get_or_create_bon_driver("BonDriver_T.dll").unwrap();
+        let mut info = recisdb_protocol::ChannelInfo::new(0x7FE8, 1024, 32736);
+        info.channel_name = Some("NHK総合".to_string());
+        db.insert_channel(driver_id, &info).unwrap();
+        // No EPG for this service
+        db.insert_channel(driver_id, &recisdb_protocol::ChannelInfo::new(0x7FE8, 1025, 32736)).unwrap();
+
+        db.insert_events(&[
+            event(1, base, 1800, "朝"),
+            event(2, base + 1800, 1800, "昼"),
+            event(3, base + 3600, 1800, "夜"),
+        ])
+        .unwrap();
+
+        let listing = db.get_now_next(base + 1000).unwrap();
+        assert_eq!(listing.len(), 2);
+        assert_eq!(listing[0].service_name.as_deref(), Some("NHK総合"));
+        assert_eq!(listing[0].now.as_ref().map(|e| e.event_id), Some(1));
+        assert_eq!(listing[0].next.as_ref().map(|e| e.event_id), Some(2));
+        assert_eq!(listing[1].sid, 1025);
+        assert!(listing[1].now.is_none() && listing[1].next.is_none());
+
+        // Between the listed events nothing is airing
+        let listing = db.get_now_next(base - 60).unwrap();
+        assert!(listing[0].now.is_none());
+        assert_eq!(listing[0].next.as_ref().map(|e| e.event_id), Some(1));
+
+        // The last event has no successor
+        let listing = db.get_now_next(base + 4000).unwrap();
+        assert_eq!(listing[0].now.as_ref().map(|e| e.event_id), Some(3));
+        assert!(listing[0].next.is_none());
+    }
+}
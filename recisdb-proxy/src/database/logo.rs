@@ -0,0 +1,116 @@
+//! Station logo database operations.
+
+use rusqlite::{params, Row};
+
+use super::{ChannelLogoRecord, Database, Result};
+
+impl Database {
+    /// Insert or update a station logo.
+    ///
+    /// An older `logo_version` never replaces a newer one.
+    pub fn upsert_channel_logo(
+        &self,
+        nid: u16,
+        logo_id: u16,
+        logo_type: u8,
+        logo_version: u16,
+        png: &[u8],
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO channel_logos (nid, logo_id, logo_type, logo_version, png, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, strftime('%s', 'now'))
+             ON CONFLICT(nid, logo_id, logo_type) DO UPDATE SET
+                logo_version = excluded.logo_version,
+                png = excluded.png,
+                updated_at = excluded.updated_at
+             WHERE excluded.logo_version >= channel_logos.logo_version",
+            params![nid as i32, logo_id as i32, logo_type as i32, logo_version as i32, png],
+        )?;
+        Ok(())
+    }
+
+    /// Record the logo ID announced for each service of a network.
+    ///
+    /// Returns the number of channel rows updated.
+    pub fn set_service_logo_ids(&mut self, nid: u16, logo_ids: &[(u16, u16)]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut updated = 0;
+        {
+            let mut stmt = tx.prepare_cached(
+                "UPDATE channels SET logo_id = ?3
+                 WHERE nid = ?1 AND sid = ?2 AND logo_id IS NOT ?3",
+            )?;
+            for (sid, logo_id) in logo_ids {
+                updated += stmt.execute(params![nid as i32, *sid as i32, *logo_id as i32])?;
+            }
+        }
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// Get the logo of a channel, preferring the largest HD image.
+    ///
+    /// Returns None when the channel has no logo ID or the logo has not been
+    /// received yet.
+    pub fn get_channel_logo(&self, channel_id: i64) -> Result<Option<ChannelLogoRecord>> {
+        // Types 5 (64x36) and 2 (48x27) are the HD logos
+        let mut stmt = self.conn.prepare(
+            "SELECT l.* FROM channels c
+             JOIN channel_logos l ON l.nid = c.nid AND l.logo_id = c.logo_id
+             WHERE c.id = ?1
+             ORDER BY CASE l.logo_type WHEN 5 THEN 0 WHEN 2 THEN 1 ELSE 2 END, l.logo_type DESC
+             LIMIT 1",
+        )?;
+        match stmt.query_row([channel_id], Self::row_to_channel_logo) {
+            Ok(record) => Ok(Some(record)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn row_to_channel_logo(row: &Row) -> rusqlite::Result<ChannelLogoRecord> {
+        Ok(ChannelLogoRecord {
+            nid: row.get::<_, i32>("nid")? as u16,
+            logo_id: row.get::<_, i32>("logo_id")? as u16,
+            logo_type: row.get::<_, i32>("logo_type")? as u8,
+            logo_version: row.get::<_, i32>("logo_version")? as u16,
+            png: row.get("png")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use recisdb_protocol::ChannelInfo;
+
+    #[test]
+    fn test_channel_logo_lookup() {
+        let mut db = Database::open_in_memory().unwrap();
+        let driver_id = db.get_or_create_bon_driver("BonDriver_T.dll").unwrap();
+        let channel_id = db
+            .insert_channel(driver_id, &ChannelInfo::new(0x7FE8, 1024, 32736))
+            .unwrap();
+
+        // No logo ID announced yet
+        db.upsert_channel_logo(0x7FE8, 0x10, 5, 1, b"hd-large").unwrap();
+        assert!(db.get_channel_logo(channel_id).unwrap().is_none());
+
+        assert_eq!(db.set_service_logo_ids(0x7FE8, &[(1024, 0x10), (1025, 0x11)]).unwrap(), 1);
+        // Unchanged logo IDs are not rewritten
+        assert_eq!(db.set_service_logo_ids(0x7FE8, &[(1024, 0x10)]).unwrap(), 0);
+
+        db.upsert_channel_logo(0x7FE8, 0x10, 0, 1, b"sd-small").unwrap();
+        let logo = db.get_channel_logo(channel_id).unwrap().unwrap();
+        assert_eq!(logo.logo_type, 5);
+        assert_eq!(logo.png, b"hd-large");
+
+        // Older versions are ignored, newer ones replace the image
+        db.upsert_channel_logo(0x7FE8, 0x10, 5, 0, b"old").unwrap();
+        assert_eq!(db.get_channel_logo(channel_id).unwrap().unwrap().png, b"hd-large");
+        db.upsert_channel_logo(0x7FE8, 0x10, 5, 2, b"new").unwrap();
+        let logo = db.get_channel_logo(channel_id).unwrap().unwrap();
+        assert_eq!((logo.logo_version, logo.png.as_slice()), (2, b"new".as_slice()));
+    }
+}
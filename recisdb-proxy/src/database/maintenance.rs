@@ -0,0 +1,232 @@
+//! Database maintenance (history pruning, VACUUM, ANALYZE, channel re-linking).
+
+use rusqlite::{params, OptionalExtension};
+
+use super::{Database, DatabaseError, MaintenanceReport, ReconcileReport, Result};
+
+/// Default retention for session, alert and channel usage history.
+pub const DEFAULT_HISTORY_RETENTION_DAYS: u64 = 90;
+
+impl Database {
+    /// Prune old history, then `VACUUM` and `ANALYZE` the database.
+    ///
+    /// Finished sessions, resolved alerts and channel usage buckets older than
+    /// `retention_days` are deleted; running sessions and active alerts are
+    /// always kept. `VACUUM` cannot run inside a transaction, so this fails if
+    /// one is open on the connection.
+    pub fn maintenance(&self, retention_days: u64) -> Result<MaintenanceReport> {
+        if !self.conn.is_autocommit() {
+            return Err(DatabaseError::MaintenanceFailed(
+                "a transaction is open on the connection".to_string(),
+            ));
+        }
+
+        let size_before = self.file_size_bytes()?;
+        let cutoff = chrono::Utc::now().timestamp() - (retention_days as i64).saturating_mul(86_400);
+
+        let tx = self.conn.unchecked_transaction()?;
+        let sessions_pruned = tx.execute(
+            "DELETE FROM session_history WHERE ended_at IS NOT NULL AND created_at < ?1",
+            params![cutoff],
+        )?;
+        let alerts_pruned = tx.execute(
+            "DELETE FROM alert_history WHERE resolved_at IS NOT NULL AND triggered_at < ?1",
+            params![cutoff],
+        )?;
+        tx.execute(
+            "DELETE FROM channel_usage_stats WHERE bucket_start < ?1",
+            params![cutoff],
+        )?;
+        tx.commit()?;
+
+        self.conn.execute_batch("VACUUM; ANALYZE;")?;
+        let size_after = self.file_size_bytes()?;
+
+        log::info!(
+            "Database maintenance: pruned {} sessions and {} alerts, {} -> {} bytes",
+            sessions_pruned,
+            alerts_pruned,
+            size_before,
+            size_after
+        );
+
+        Ok(MaintenanceReport {
+            retention_days,
+            sessions_pruned,
+            alerts_pruned,
+            size_before,
+            size_after,
+        })
+    }
+
+    /// Re-link channels whose BonDriver no longer exists.
+    ///
+    /// Such channels are left behind when a driver row is deleted with
+    /// foreign keys off (e.g. from an external SQLite tool) and the driver is
+    /// registered again under a new ID. Each one is moved to the driver that
+    /// now has its remembered `driver_dll_path`. Channels without such a
+    /// driver, or whose service the driver already has, are disabled and
+    /// reported as orphaned.
+    pub fn reconcile_channels(&self) -> Result<ReconcileReport> {
+        let tx = self.conn.unchecked_transaction()?;
+        let orphans: Vec<(i64, Option<String>, i64, i64, i64, Option<i64>)> = {
+            let mut stmt = tx.prepare(
+                "SELECT c.id, c.driver_dll_path, c.nid, c.sid, c.tsid, c.manual_sheet
+                 FROM channels c
+                 WHERE NOT EXISTS (SELECT 1 FROM bon_drivers d WHERE d.id = c.bon_driver_id)
+                 ORDER BY c.id",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+            })?;
+            rows.collect::<std::result::Result<_, _>>()?
+        };
+
+        let mut report = ReconcileReport::default();
+        for (id, dll_path, nid, sid, tsid, manual_sheet) in orphans {
+            let driver_id: Option<i64> = match &dll_path {
+                Some(path) => tx
+                    .query_row(
+                        "SELECT id FROM bon_drivers WHERE dll_path = ?1 COLLATE NOCASE ORDER BY id LIMIT 1",
+                        [path],
+                        |row| row.get(0),
+                    )
+                    .optional()?,
+                None => None,
+            };
+            let duplicate = match driver_id {
+                Some(driver_id) => tx
+                    .query_row(
+                        "SELECT 1 FROM channels
+                         WHERE bon_driver_id = ?1 AND nid = ?2 AND sid = ?3 AND tsid = ?4 AND manual_sheet IS ?5",
+                        params![driver_id, nid, sid, tsid, manual_sheet],
+                        |_| Ok(()),
+                    )
+                    .optional()?
+                    .is_some(),
+                None => false,
+            };
+
+            match driver_id {
+                Some(driver_id) if !duplicate => {
+                    tx.execute("UPDATE channels SET bon_driver_id = ?1 WHERE id = ?2", params![driver_id, id])?;
+                    report.relinked += 1;
+                }
+                _ => {
+                    log::warn!(
+                        "Channel {} (NID={} SID={} TSID={}) references a missing driver{}; disabling it",
+                        id,
+                        nid,
+                        sid,
+                        tsid,
+                        dll_path.map(|p| format!(" ({})", p)).unwrap_or_default()
+                    );
+                    tx.execute("UPDATE channels SET is_enabled = 0 WHERE id = ?1", [id])?;
+                    report.orphaned += 1;
+                    report.orphaned_channel_ids.push(id);
+                }
+            }
+        }
+        tx.commit()?;
+
+        log::info!(
+            "Channel reconcile: {} relinked, {} orphaned",
+            report.relinked,
+            report.orphaned
+        );
+        Ok(report)
+    }
+
+    /// Size of the database in bytes (page_count * page_size).
+    fn file_size_bytes(&self) -> Result<u64> {
+        let page_count: i64 = self.conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: i64 = self.conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok((page_count * page_size) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::NewBonDriver;
+
+    #[test]
+    fn test_maintenance_prunes_old_history() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let old = chrono::Utc::now().timestamp() - 100 * 86_400;
+
+        // Old finished, old still running, and recent finished sessions
+        conn.execute_batch(&format!(
+            "INSERT INTO session_history (session_id, client_address, started_at, ended_at, created_at)
+                 VALUES (1, '127.0.0.1', {old}, {old}, {old});
+             INSERT INTO session_history (session_id, client_address, started_at, created_at)
+                 VALUES (2, '127.0.0.1', {old}, {old});
+             INSERT INTO session_history (session_id, client_address, started_at, ended_at)
+                 VALUES (3, '127.0.0.1', {old}, {old});
+             INSERT INTO alert_rules (name, metric, condition, threshold) VALUES ('drop', 'drop_rate', 'gt', 1.0);
+             INSERT INTO alert_history (rule_id, triggered_at, resolved_at) VALUES (1, {old}, {old});
+             INSERT INTO alert_history (rule_id, triggered_at) VALUES (1, {old});"
+        ))
+        .unwrap();
+
+        let report = db.maintenance(DEFAULT_HISTORY_RETENTION_DAYS).unwrap();
+        assert_eq!(report.sessions_pruned, 1);
+        assert_eq!(report.alerts_pruned, 1);
+        assert!(report.size_before > 0);
+        assert!(report.size_after > 0);
+
+        assert_eq!(db.get_total_session_count().unwrap(), 2);
+        assert_eq!(db.get_active_alerts().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_reconcile_channels_relinks_by_path() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let old = db.insert_bon_driver(&NewBonDriver::new("BonDriver_PX4-T.dll")).unwrap();
+        let gone = db.insert_bon_driver(&NewBonDriver::new("BonDriver_Old.dll")).unwrap();
+        conn.execute_batch(&format!(
+            "INSERT INTO channels (bon_driver_id, nid, sid, tsid) VALUES ({old}, 32736, 1024, 32736);
+             INSERT INTO channels (bon_driver_id, nid, sid, tsid) VALUES ({old}, 32736, 1032, 32736);
+             INSERT INTO channels (bon_driver_id, nid, sid, tsid) VALUES ({gone}, 32737, 1040, 32737);"
+        ))
+        .unwrap();
+
+        // Delete both drivers without cascading, then re-register one of them
+        conn.execute_batch("PRAGMA foreign_keys = OFF; DELETE FROM bon_drivers; PRAGMA foreign_keys = ON;")
+            .unwrap();
+        let new = db.insert_bon_driver(&NewBonDriver::new("BonDriver_PX4-T.dll")).unwrap();
+        assert_ne!(new, old);
+        // The re-registered driver was rescanned and already has one service
+        conn.execute(
+            "INSERT INTO channels (bon_driver_id, nid, sid, tsid) VALUES (?1, 32736, 1032, 32736)",
+            [new],
+        )
+        .unwrap();
+
+        let report = db.reconcile_channels().unwrap();
+        assert_eq!((report.relinked, report.orphaned), (1, 2));
+        let relinked: i64 = conn
+            .query_row("SELECT COUNT(*) FROM channels WHERE bon_driver_id = ?1", [new], |row| row.get(0))
+            .unwrap();
+        assert_eq!(relinked, 2);
+        for id in &report.orphaned_channel_ids {
+            let enabled: bool = conn
+                .query_row("SELECT is_enabled FROM channels WHERE id = ?1", [id], |row| row.get(0))
+                .unwrap();
+            assert!(!enabled);
+        }
+
+        // Nothing left to re-link on a second run
+        assert_eq!(db.reconcile_channels().unwrap().relinked, 0);
+    }
+
+    #[test]
+    fn test_maintenance_rejects_open_transaction() {
+        let db = Database::open_in_memory().unwrap();
+        db.connection().execute_batch("BEGIN").unwrap();
+        assert!(matches!(db.maintenance(30), Err(DatabaseError::MaintenanceFailed(_))));
+        db.connection().execute_batch("ROLLBACK").unwrap();
+    }
+}
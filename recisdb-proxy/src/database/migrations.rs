@@ -0,0 +1,632 @@
+//! Ordered schema migrations.
+//!
+//! Each migration runs once, inside its own transaction, and is recorded in
+//! `schema_migrations`. Databases created before that table existed already
+//! carry some of these changes; such migrations are detected through
+//! `already_applied` and only recorded, not re-run.
+
+use std::collections::HashSet;
+
+use rusqlite::{params, Connection};
+
+use super::{DatabaseError, Result};
+
+/// A single schema migration.
+pub(crate) struct Migration {
+    /// Unique, increasing migration number.
+    pub id: u32,
+    pub name: &'static str,
+    pub up: fn(&Connection) -> Result<()>,
+    /// Detects databases that already have this change from before
+    /// `schema_migrations` was introduced. New migrations leave this `None`.
+    pub already_applied: Option<fn(&Connection) -> Result<bool>>,
+}
+
+/// All migrations, in the order they are applied.
+pub(crate) fn migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            id: 1,
+            name: "channel band/region columns",
+            up: |conn| add_columns(conn, M001_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M001_COLUMNS)),
+        },
+        Migration {
+            id: 2,
+            name: "fill channel band_type and terrestrial_region",
+            up: |conn| Ok(conn.execute_batch(M002_FILL_BAND_TYPE_SQL)?),
+            already_applied: Some(|conn| {
+                let unfilled: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM channels WHERE band_type IS NULL",
+                    [],
+                    |row| row.get(0),
+                )?;
+                Ok(unfilled == 0)
+            }),
+        },
+        Migration {
+            id: 3,
+            name: "alert webhook columns",
+            up: |conn| add_columns(conn, M003_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M003_COLUMNS)),
+        },
+        Migration {
+            id: 4,
+            name: "scan timing config",
+            up: |conn| add_columns(conn, M004_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M004_COLUMNS)),
+        },
+        Migration {
+            id: 5,
+            name: "tuner startup timing config",
+            up: |conn| add_columns(conn, M005_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M005_COLUMNS)),
+        },
+        Migration {
+            id: 6,
+            name: "bon_drivers 4K flag",
+            up: |conn| add_columns(conn, M006_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M006_COLUMNS)),
+        },
+        Migration {
+            id: 7,
+            name: "driver affinity toggle",
+            up: |conn| add_columns(conn, M007_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M007_COLUMNS)),
+        },
+        Migration {
+            id: 8,
+            name: "TS output alignment toggle",
+            up: |conn| add_columns(conn, M008_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M008_COLUMNS)),
+        },
+        Migration {
+            id: 9,
+            name: "descramble re-key threshold",
+            up: |conn| add_columns(conn, M009_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M009_COLUMNS)),
+        },
+        Migration {
+            id: 10,
+            name: "preferred region space cap",
+            up: |conn| add_columns(conn, M010_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M010_COLUMNS)),
+        },
+        Migration {
+            id: 11,
+            name: "channel 1seg flag",
+            up: |conn| add_columns(conn, M011_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M011_COLUMNS)),
+        },
+        Migration {
+            id: 12,
+            name: "session idle timeout",
+            up: |conn| add_columns(conn, M012_COLUMNS),
+            already_applied: Some(|conn| columns_exist(conn, M012_COLUMNS)),
+        },
+        Migration {
+            id: 13,
+            name: "key epg_events by (nid, tsid, sid, event_id)",
+            up: rebuild_epg_events_unique_key,
+            already_applied: Some(|conn| Ok(!epg_events_has_legacy_key(conn)?)),
+        },
+        Migration {
+            id: 14,
+            name: "bon_drivers group selection strategy",
+            up: |conn| add_columns(conn, M014_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 15,
+            name: "reader stall watchdog",
+            up: |conn| add_columns(conn, M015_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 16,
+            name: "session bitrate cap",
+            up: |conn| add_columns(conn, M016_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 17,
+            name: "alert rule cooldown",
+            up: |conn| add_columns(conn, M017_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 18,
+            name: "alert critical action",
+            up: |conn| add_columns(conn, M018_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 19,
+            name: "cron scan schedule",
+            up: |conn| add_columns(conn, M019_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 20,
+            name: "scan scope",
+            up: |conn| add_columns(conn, M020_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 21,
+            name: "channel logo ID",
+            up: |conn| add_columns(conn, M021_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 22,
+            name: "channel TS name",
+            up: |conn| add_columns(conn, M022_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 23,
+            name: "channel display order",
+            up: |conn| add_columns(conn, M023_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 24,
+            name: "driver quality decay",
+            up: |conn| add_columns(conn, M024_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 25,
+            name: "TS coalescing",
+            up: |conn| add_columns(conn, M025_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 26,
+            name: "session client identity",
+            up: |conn| add_columns(conn, M026_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 27,
+            name: "synthetic channel name flag",
+            up: |conn| add_columns(conn, M027_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 28,
+            name: "group and startup prewarm",
+            up: |conn| add_columns(conn, M028_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 29,
+            name: "minimum signal level",
+            up: |conn| add_columns(conn, M029_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 30,
+            name: "channel driver path",
+            up: |conn| {
+                add_columns(conn, M030_COLUMNS)?;
+                Ok(conn.execute_batch(M030_DRIVER_PATH_SQL)?)
+            },
+            already_applied: None,
+        },
+        Migration {
+            id: 31,
+            name: "broadcast buffer settings",
+            up: |conn| add_columns(conn, M031_COLUMNS),
+            already_applied: None,
+        },
+        Migration {
+            id: 32,
+            name: "scan reception check",
+            up: |conn| add_columns(conn, M032_COLUMNS),
+            already_applied: None,
+        },
+    ]
+}
+
+/// Apply every migration not yet recorded in `schema_migrations`, in order.
+pub(crate) fn run(conn: &Connection, migrations: &[Migration]) -> Result<()> {
+    let applied: HashSet<u32> = {
+        let mut stmt = conn.prepare("SELECT id FROM schema_migrations")?;
+        let ids = stmt.query_map([], |row| row.get(0))?;
+        ids.collect::<std::result::Result<_, _>>()?
+    };
+
+    for migration in migrations.iter().filter(|m| !applied.contains(&m.id)) {
+        let fail = |e: DatabaseError| {
+            DatabaseError::MigrationFailed(format!("{:03} ({}): {}", migration.id, migration.name, e))
+        };
+
+        let tx = conn.unchecked_transaction()?;
+        let backfilled = match migration.already_applied {
+            Some(check) => check(&tx).map_err(fail)?,
+            None => false,
+        };
+        if !backfilled {
+            (migration.up)(&tx).map_err(fail)?;
+        }
+        tx.execute(
+            "INSERT INTO schema_migrations (id, name) VALUES (?1, ?2)",
+            params![migration.id, migration.name],
+        )?;
+        tx.commit()?;
+
+        if backfilled {
+            log::debug!("Migration {:03} ({}) already present, recorded", migration.id, migration.name);
+        } else {
+            log::info!("Migration {:03} ({}) applied", migration.id, migration.name);
+        }
+    }
+
+    Ok(())
+}
+
+/// `(table, column, type)` added by a column migration.
+type ColumnDef = (&'static str, &'static str, &'static str);
+
+const M001_COLUMNS: &[ColumnDef] = &[
+    ("channels", "band_type", "INTEGER"),
+    ("channels", "region_id", "INTEGER"),
+    ("channels", "terrestrial_region", "TEXT"),
+];
+const M003_COLUMNS: &[ColumnDef] = &[
+    ("alert_rules", "webhook_url", "TEXT"),
+    ("alert_rules", "webhook_format", "TEXT DEFAULT 'generic'"),
+];
+const M004_COLUMNS: &[ColumnDef] = &[
+    ("scan_scheduler_config", "signal_lock_wait_ms", "INTEGER DEFAULT 500"),
+    ("scan_scheduler_config", "ts_read_timeout_ms", "INTEGER DEFAULT 300000"),
+];
+const M005_COLUMNS: &[ColumnDef] = &[
+    ("tuner_config", "set_channel_retry_interval_ms", "INTEGER DEFAULT 500"),
+    ("tuner_config", "set_channel_retry_timeout_ms", "INTEGER DEFAULT 10000"),
+    ("tuner_config", "signal_poll_interval_ms", "INTEGER DEFAULT 500"),
+    ("tuner_config", "signal_wait_timeout_ms", "INTEGER DEFAULT 10000"),
+];
+const M006_COLUMNS: &[ColumnDef] = &[("bon_drivers", "supports_4k", "INTEGER DEFAULT 0")];
+const M007_COLUMNS: &[ColumnDef] = &[("tuner_config", "driver_affinity_enabled", "INTEGER DEFAULT 1")];
+const M008_COLUMNS: &[ColumnDef] = &[("tuner_config", "align_ts_output", "INTEGER DEFAULT 0")];
+const M009_COLUMNS: &[ColumnDef] = &[
+    ("tuner_config", "descramble_rekey_percent", "INTEGER DEFAULT 90"),
+    ("tuner_config", "descramble_rekey_window_secs", "INTEGER DEFAULT 30"),
+];
+const M010_COLUMNS: &[ColumnDef] = &[
+    ("server_config", "preferred_region", "TEXT"),
+    ("server_config", "limit_spaces_to_preferred_region", "INTEGER DEFAULT 0"),
+];
+const M011_COLUMNS: &[ColumnDef] = &[("channels", "is_one_seg", "INTEGER DEFAULT 0")];
+const M012_COLUMNS: &[ColumnDef] = &[("server_config", "session_idle_timeout_secs", "INTEGER")];
+const M014_COLUMNS: &[ColumnDef] = &[("bon_drivers", "group_strategy", "TEXT")];
+const M015_COLUMNS: &[ColumnDef] = &[
+    ("tuner_config", "stall_timeout_ms", "INTEGER DEFAULT 15000"),
+    ("driver_quality_stats", "stall_count", "INTEGER DEFAULT 0"),
+];
+const M016_COLUMNS: &[ColumnDef] = &[("server_config", "max_bitrate_mbps", "REAL")];
+const M017_COLUMNS: &[ColumnDef] = &[("alert_rules", "cooldown_secs", "INTEGER DEFAULT 0")];
+const M018_COLUMNS: &[ColumnDef] = &[("alert_rules", "critical_action", "TEXT")];
+const M019_COLUMNS: &[ColumnDef] = &[("bon_drivers", "scan_cron", "TEXT")];
+const M020_COLUMNS: &[ColumnDef] = &[("bon_drivers", "scan_scope", "TEXT")];
+const M021_COLUMNS: &[ColumnDef] = &[("channels", "logo_id", "INTEGER")];
+const M022_COLUMNS: &[ColumnDef] = &[("channels", "ts_name", "TEXT")];
+const M023_COLUMNS: &[ColumnDef] = &[("channels", "display_order", "INTEGER")];
+const M024_COLUMNS: &[ColumnDef] = &[
+    ("server_config", "quality_half_life_hours", "REAL"),
+    ("driver_quality_stats", "weighted_packets", "REAL"),
+    ("driver_quality_stats", "weighted_dropped", "REAL"),
+    ("driver_quality_stats", "weighted_scrambled", "REAL"),
+    ("driver_quality_stats", "weighted_errors", "REAL"),
+    ("driver_quality_stats", "weighted_sessions", "REAL"),
+    ("driver_quality_stats", "weighted_stalls", "REAL"),
+];
+const M025_COLUMNS: &[ColumnDef] = &[
+    ("tuner_config", "ts_coalesce_bytes", "INTEGER DEFAULT 0"),
+    ("tuner_config", "ts_coalesce_flush_ms", "INTEGER DEFAULT 20"),
+];
+const M026_COLUMNS: &[ColumnDef] = &[("session_history", "client_identity", "TEXT")];
+const M027_COLUMNS: &[ColumnDef] = &[("channels", "name_is_synthetic", "INTEGER DEFAULT 0")];
+const M028_COLUMNS: &[ColumnDef] = &[
+    ("tuner_config", "prewarm_group", "INTEGER DEFAULT 0"),
+    ("tuner_config", "prewarm_on_startup", "INTEGER DEFAULT 0"),
+];
+const M029_COLUMNS: &[ColumnDef] = &[
+    ("tuner_config", "min_signal_level_terrestrial", "REAL DEFAULT 0"),
+    ("tuner_config", "min_signal_level_satellite", "REAL DEFAULT 0"),
+];
+const M030_COLUMNS: &[ColumnDef] = &[("channels", "driver_dll_path", "TEXT")];
+const M031_COLUMNS: &[ColumnDef] = &[
+    ("bon_drivers", "broadcast_capacity", "INTEGER"),
+    ("bon_drivers", "lag_policy", "TEXT"),
+];
+const M032_COLUMNS: &[ColumnDef] = &[
+    ("channels", "signal_level", "REAL"),
+    ("channels", "reception_ok", "INTEGER"),
+    ("channels", "auto_disabled", "INTEGER DEFAULT 0"),
+    ("bon_drivers", "reception_min_signal", "REAL"),
+    ("bon_drivers", "auto_disable_no_reception", "INTEGER DEFAULT 0"),
+];
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+    Ok(exists)
+}
+
+fn columns_exist(conn: &Connection, columns: &[ColumnDef]) -> Result<bool> {
+    for (table, column, _) in columns {
+        if !column_exists(conn, table, column)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn add_columns(conn: &Connection, columns: &[ColumnDef]) -> Result<()> {
+    for (table, column, column_type) in columns {
+        add_column_if_not_exists(conn, table, column, column_type)?;
+    }
+    Ok(())
+}
+
+/// Add a column to a table if it doesn't exist.
+///
+/// SQLite doesn't support IF NOT EXISTS for ALTER TABLE, so the column list
+/// is checked first.
+pub(crate) fn add_column_if_not_exists(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    column_type: &str,
+) -> Result<()> {
+    if !column_exists(conn, table, column)? {
+        let sql = format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, column_type);
+        conn.execute(&sql, [])?;
+        log::info!("Migration: Added column {} to table {}", column, table);
+    }
+    Ok(())
+}
+
+fn epg_events_has_legacy_key(conn: &Connection) -> Result<bool> {
+    let sql: String = conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'epg_events'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(sql.contains("UNIQUE(nid, sid, event_id)"))
+}
+
+/// Rebuild epg_events if it still uses the old (nid, sid, event_id) key.
+///
+/// SQLite cannot alter a UNIQUE constraint in place, so the table is
+/// copied into a new one with the current schema.
+fn rebuild_epg_events_unique_key(conn: &Connection) -> Result<()> {
+    if !epg_events_has_legacy_key(conn)? {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE epg_events_new (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nid INTEGER NOT NULL,
+            tsid INTEGER NOT NULL,
+            sid INTEGER NOT NULL,
+            event_id INTEGER NOT NULL,
+            start_time INTEGER,
+            duration_secs INTEGER,
+            title TEXT,
+            description TEXT,
+            updated_at INTEGER DEFAULT (strftime('%s', 'now')),
+            UNIQUE(nid, tsid, sid, event_id)
+        );
+        INSERT OR IGNORE INTO epg_events_new
+            (id, nid, tsid, sid, event_id, start_time, duration_secs, title, description, updated_at)
+            SELECT id, nid, tsid, sid, event_id, start_time, duration_secs, title, description, updated_at
+            FROM epg_events;
+        DROP TABLE epg_events;
+        ALTER TABLE epg_events_new RENAME TO epg_events;
+        CREATE INDEX IF NOT EXISTS idx_epg_events_service_start ON epg_events(nid, tsid, sid, start_time);
+        "#,
+    )?;
+    log::info!("Migration: Rebuilt epg_events with (nid, tsid, sid, event_id) key");
+    Ok(())
+}
+
+/// Fill band_type and terrestrial_region for channels stored before those
+/// columns were derived on insert.
+const M002_FILL_BAND_TYPE_SQL: &str = r#"
+UPDATE channels
+SET band_type = CASE
+    WHEN nid = 4 OR nid = 5 OR (nid >= 0x4001 AND nid <= 0x400F) THEN 1
+    WHEN nid IN (6, 7, 10) OR (nid >= 0x6001 AND nid <= 0x600F) THEN 2
+    WHEN nid >= 0x7C00 AND nid <= 0x7CFF THEN 3
+    WHEN nid >= 0x7F00 AND nid <= 0x7FFF THEN 0
+    ELSE 4
+END
+WHERE band_type IS NULL;
+
+UPDATE channels
+SET terrestrial_region = CASE
+    WHEN nid IN (0x7F01, 0x7FE0, 0x7FF0) THEN '北海道'
+    WHEN nid = 0x7F08 THEN '青森'
+    WHEN nid = 0x7F09 THEN '岩手'
+    WHEN nid = 0x7F0A THEN '宮城'
+    WHEN nid = 0x7F0B THEN '秋田'
+    WHEN nid = 0x7F0C THEN '山形'
+    WHEN nid = 0x7F0D THEN '福島'
+    WHEN nid = 0x7F0E THEN '茨城'
+    WHEN nid = 0x7F0F THEN '栃木'
+    WHEN nid = 0x7F10 THEN '群馬'
+    WHEN nid = 0x7F11 THEN '埼玉'
+    WHEN nid = 0x7F12 THEN '千葉'
+    WHEN nid = 0x7F13 THEN '東京'
+    WHEN nid = 0x7F14 THEN '神奈川'
+    WHEN nid = 0x7F15 THEN '新潟'
+    WHEN nid = 0x7F16 THEN '長野'
+    WHEN nid = 0x7F17 THEN '山梨'
+    WHEN nid = 0x7F18 THEN '富山'
+    WHEN nid = 0x7F19 THEN '石川'
+    WHEN nid = 0x7F1A THEN '福井'
+    WHEN nid = 0x7F1B THEN '静岡'
+    WHEN nid = 0x7F1C THEN '愛知'
+    WHEN nid = 0x7F1D THEN '岐阜'
+    WHEN nid = 0x7F1E THEN '三重'
+    WHEN nid = 0x7F1F THEN '滋賀'
+    WHEN nid = 0x7F20 THEN '京都'
+    WHEN nid = 0x7F21 THEN '大阪'
+    WHEN nid = 0x7F22 THEN '兵庫'
+    WHEN nid = 0x7F23 THEN '奈良'
+    WHEN nid = 0x7F24 THEN '和歌山'
+    WHEN nid = 0x7F25 THEN '鳥取'
+    WHEN nid = 0x7F26 THEN '島根'
+    WHEN nid = 0x7F27 THEN '岡山'
+    WHEN nid = 0x7F28 THEN '広島'
+    WHEN nid = 0x7F29 THEN '山口'
+    WHEN nid = 0x7F2A THEN '徳島'
+    WHEN nid = 0x7F2B THEN '香川'
+    WHEN nid = 0x7F2C THEN '愛媛'
+    WHEN nid = 0x7F2D THEN '高知'
+    WHEN nid = 0x7F2E THEN '福岡'
+    WHEN nid = 0x7F2F THEN '佐賀'
+    WHEN nid = 0x7F30 THEN '長崎'
+    WHEN nid = 0x7F31 THEN '熊本'
+    WHEN nid = 0x7F32 THEN '大分'
+    WHEN nid = 0x7F33 THEN '宮崎'
+    WHEN nid = 0x7F34 THEN '鹿児島'
+    WHEN nid = 0x7F35 THEN '沖縄'
+    WHEN nid >= 0x7FE0 AND nid <= 0x7FE7 THEN '北海道'
+    WHEN nid = 0x7FE8 THEN '東京'
+    WHEN nid = 0x7FE9 THEN '大阪'
+    WHEN nid = 0x7FEA THEN '愛知'
+    WHEN nid = 0x7FEB THEN '岡山'
+    WHEN nid = 0x7FEC THEN '島根'
+    WHEN nid >= 0x7FF0 AND nid <= 0x7FF7 THEN '北海道'
+    ELSE '不明'
+END
+WHERE band_type = 0 AND terrestrial_region IS NULL;
+"#;
+
+/// Remember each channel's driver path so channels left behind by a deleted
+/// driver (foreign keys off, e.g. an external SQLite tool) can be re-linked.
+const M030_DRIVER_PATH_SQL: &str = r#"
+UPDATE channels
+SET driver_dll_path = (SELECT dll_path FROM bon_drivers WHERE id = channels.bon_driver_id)
+WHERE driver_dll_path IS NULL;
+
+CREATE TRIGGER IF NOT EXISTS channels_driver_path_insert
+AFTER INSERT ON channels
+BEGIN
+    UPDATE channels SET driver_dll_path = (SELECT dll_path FROM bon_drivers WHERE id = NEW.bon_driver_id)
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS channels_driver_path_relink
+AFTER UPDATE OF bon_driver_id ON channels
+BEGIN
+    UPDATE channels SET driver_dll_path = (SELECT dll_path FROM bon_drivers WHERE id = NEW.bon_driver_id)
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS bon_drivers_path_rename
+AFTER UPDATE OF dll_path ON bon_drivers
+BEGIN
+    UPDATE channels SET driver_dll_path = NEW.dll_path WHERE bon_driver_id = NEW.id;
+END;
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    fn applied_ids(conn: &Connection) -> Vec<u32> {
+        let mut stmt = conn.prepare("SELECT id FROM schema_migrations ORDER BY id").unwrap();
+        let ids = stmt.query_map([], |row| row.get(0)).unwrap();
+        ids.collect::<std::result::Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn test_fresh_database_records_all_migrations() {
+        let db = Database::open_in_memory().unwrap();
+        let expected: Vec<u32> = migrations().iter().map(|m| m.id).collect();
+        assert_eq!(applied_ids(db.connection()), expected);
+    }
+
+    #[test]
+    fn test_migration_ids_are_increasing() {
+        let ids: Vec<u32> = migrations().iter().map(|m| m.id).collect();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_legacy_database_is_backfilled() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+
+        // A database from before schema_migrations: changes present, nothing recorded
+        conn.execute("DELETE FROM schema_migrations", []).unwrap();
+        conn.execute("INSERT INTO bon_drivers (dll_path) VALUES ('BonDriver_A.dll')", []).unwrap();
+        conn.execute(
+            "INSERT INTO channels (bon_driver_id, nid, sid, tsid, band_type, terrestrial_region)
+             VALUES (1, 4, 101, 16400, 1, NULL)",
+            [],
+        )
+        .unwrap();
+
+        run(conn, &migrations()).unwrap();
+        assert_eq!(applied_ids(conn).len(), migrations().len());
+    }
+
+    #[test]
+    fn test_only_unapplied_migrations_run() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let new_migrations = [
+            Migration {
+                id: 1,
+                name: "already recorded",
+                up: |_| panic!("must not run"),
+                already_applied: None,
+            },
+            Migration {
+                id: 1000,
+                name: "create test table",
+                up: |conn| Ok(conn.execute_batch("CREATE TABLE migration_test (id INTEGER)")?),
+                already_applied: None,
+            },
+        ];
+
+        run(conn, &new_migrations).unwrap();
+        assert!(applied_ids(conn).contains(&1000));
+
+        // Running again is a no-op
+        run(conn, &new_migrations).unwrap();
+    }
+
+    #[test]
+    fn test_failed_migration_is_rolled_back() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let failing = [Migration {
+            id: 1000,
+            name: "broken",
+            up: |conn| {
+                conn.execute_batch("CREATE TABLE migration_test (id INTEGER)")?;
+                Ok(conn.execute_batch("SELECT * FROM no_such_table")?)
+            },
+            already_applied: None,
+        }];
+
+        assert!(matches!(run(conn, &failing), Err(DatabaseError::MigrationFailed(_))));
+        assert!(!applied_ids(conn).contains(&1000));
+        assert!(!column_exists(conn, "migration_test", "id").unwrap());
+    }
+}
@@ -4,15 +4,31 @@
 //! - BonDriver registration and scan configuration
 //! - Channel information (NID/SID/TSID-based identification)
 //! - Scan history and statistics
+//! - Per-channel usage statistics
+//! - EPG events (EIT)
+//! - Station logos (CDT)
+//! - JSON export/import of driver and channel configuration
+//! - Maintenance (history pruning, VACUUM)
 
 mod bon_driver;
 mod channel;
+mod channel_usage;
+mod client_acl;
+mod config_transfer;
 mod driver_quality;
+mod epg;
+mod logo;
+mod maintenance;
 mod alert;
+mod recording;
 mod session_history;
 mod models;
 mod schema;
+mod migrations;
 
+pub use alert::{ALERT_CRITICAL_ACTIONS, ALERT_SEVERITIES};
+pub use driver_quality::DriverRankingRow;
+pub use maintenance::DEFAULT_HISTORY_RETENTION_DAYS;
 pub use models::*;
 
 use rusqlite::{Connection, Result as SqliteResult};
@@ -41,6 +57,21 @@ pub enum DatabaseError {
 
     #[error("Migration failed: {0}")]
     MigrationFailed(String),
+
+    #[error("Invalid group name: {0}")]
+    InvalidGroupName(String),
+
+    #[error("Ambiguous tuner name: {0}")]
+    AmbiguousTunerName(String),
+
+    #[error("Invalid configuration document: {0}")]
+    InvalidConfig(String),
+
+    #[error("Maintenance failed: {0}")]
+    MaintenanceFailed(String),
+
+    #[error("Invalid client ACL: {0}")]
+    InvalidClientAcl(String),
 }
 
 pub type Result<T> = std::result::Result<T, DatabaseError>;
@@ -89,120 +120,18 @@ impl Database {
         column: &str,
         column_type: &str,
     ) -> Result<()> {
-        // Check if column exists using PRAGMA table_info
-        let mut stmt = self.conn.prepare(&format!("PRAGMA table_info({})", table))?;
-        let column_exists = stmt
-            .query_map([], |row| row.get::<_, String>(1))?
-            .filter_map(|r| r.ok())
-            .any(|name| name == column);
-
-        if !column_exists {
-            let sql = format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, column_type);
-            self.conn.execute(&sql, [])?;
-            log::info!("Migration: Added column {} to table {}", column, table);
-        }
-
-        Ok(())
+        migrations::add_column_if_not_exists(&self.conn, table, column, column_type)
     }
 
-    /// Apply pending migrations.
+    /// Apply pending migrations recorded in `schema_migrations`.
     fn apply_migrations(&self) -> Result<()> {
-        // Migration 001: Add band_type, region_id, and terrestrial_region columns if they don't exist
-        // SQLite doesn't support IF NOT EXISTS for ALTER TABLE, so we check and add individually
-        self.add_column_if_not_exists("channels", "band_type", "INTEGER")?;
-        self.add_column_if_not_exists("channels", "region_id", "INTEGER")?;
-        self.add_column_if_not_exists("channels", "terrestrial_region", "TEXT")?;
-
-        // Migration 003: Add webhook columns to alert_rules if they don't exist
-        self.add_column_if_not_exists("alert_rules", "webhook_url", "TEXT")?;
-        self.add_column_if_not_exists("alert_rules", "webhook_format", "TEXT DEFAULT 'generic'")?;
-
-        // Migration 004: Add global scan timing config columns if they don't exist
-        self.add_column_if_not_exists("scan_scheduler_config", "signal_lock_wait_ms", "INTEGER DEFAULT 500")?;
-        self.add_column_if_not_exists("scan_scheduler_config", "ts_read_timeout_ms", "INTEGER DEFAULT 300000")?;
-
-        // Migration 005: Add tuner startup timing config columns if they don't exist
-        self.add_column_if_not_exists("tuner_config", "set_channel_retry_interval_ms", "INTEGER DEFAULT 500")?;
-        self.add_column_if_not_exists("tuner_config", "set_channel_retry_timeout_ms", "INTEGER DEFAULT 10000")?;
-        self.add_column_if_not_exists("tuner_config", "signal_poll_interval_ms", "INTEGER DEFAULT 500")?;
-        self.add_column_if_not_exists("tuner_config", "signal_wait_timeout_ms", "INTEGER DEFAULT 10000")?;
-
-        // Migration 002: Fill band_type and terrestrial_region for existing channels
-        // This updates all NULL values in these columns based on NID
-        self.conn.execute_batch(
-            r#"
-            UPDATE channels
-            SET band_type = CASE
-                WHEN nid = 4 OR nid = 5 OR (nid >= 0x4001 AND nid <= 0x400F) THEN 1
-                WHEN nid IN (6, 7, 10) OR (nid >= 0x6001 AND nid <= 0x600F) THEN 2
-                WHEN nid >= 0x7C00 AND nid <= 0x7CFF THEN 3
-                WHEN nid >= 0x7F00 AND nid <= 0x7FFF THEN 0
-                ELSE 4
-            END
-            WHERE band_type IS NULL;
-
-            UPDATE channels
-            SET terrestrial_region = CASE
-                WHEN nid IN (0x7F01, 0x7FE0, 0x7FF0) THEN '北海道'
-                WHEN nid = 0x7F08 THEN '青森'
-                WHEN nid = 0x7F09 THEN '岩手'
-                WHEN nid = 0x7F0A THEN '宮城'
-                WHEN nid = 0x7F0B THEN '秋田'
-                WHEN nid = 0x7F0C THEN '山形'
-                WHEN nid = 0x7F0D THEN '福島'
-                WHEN nid = 0x7F0E THEN '茨城'
-                WHEN nid = 0x7F0F THEN '栃木'
-                WHEN nid = 0x7F10 THEN '群馬'
-                WHEN nid = 0x7F11 THEN '埼玉'
-                WHEN nid = 0x7F12 THEN '千葉'
-                WHEN nid = 0x7F13 THEN '東京'
-                WHEN nid = 0x7F14 THEN '神奈川'
-                WHEN nid = 0x7F15 THEN '新潟'
-                WHEN nid = 0x7F16 THEN '長野'
-                WHEN nid = 0x7F17 THEN '山梨'
-                WHEN nid = 0x7F18 THEN '富山'
-                WHEN nid = 0x7F19 THEN '石川'
-                WHEN nid = 0x7F1A THEN '福井'
-                WHEN nid = 0x7F1B THEN '静岡'
-                WHEN nid = 0x7F1C THEN '愛知'
-                WHEN nid = 0x7F1D THEN '岐阜'
-                WHEN nid = 0x7F1E THEN '三重'
-                WHEN nid = 0x7F1F THEN '滋賀'
-                WHEN nid = 0x7F20 THEN '京都'
-                WHEN nid = 0x7F21 THEN '大阪'
-                WHEN nid = 0x7F22 THEN '兵庫'
-                WHEN nid = 0x7F23 THEN '奈良'
-                WHEN nid = 0x7F24 THEN '和歌山'
-                WHEN nid = 0x7F25 THEN '鳥取'
-                WHEN nid = 0x7F26 THEN '島根'
-                WHEN nid = 0x7F27 THEN '岡山'
-                WHEN nid = 0x7F28 THEN '広島'
-                WHEN nid = 0x7F29 THEN '山口'
-                WHEN nid = 0x7F2A THEN '徳島'
-                WHEN nid = 0x7F2B THEN '香川'
-                WHEN nid = 0x7F2C THEN '愛媛'
-                WHEN nid = 0x7F2D THEN '高知'
-                WHEN nid = 0x7F2E THEN '福岡'
-                WHEN nid = 0x7F2F THEN '佐賀'
-                WHEN nid = 0x7F30 THEN '長崎'
-                WHEN nid = 0x7F31 THEN '熊本'
-                WHEN nid = 0x7F32 THEN '大分'
-                WHEN nid = 0x7F33 THEN '宮崎'
-                WHEN nid = 0x7F34 THEN '鹿児島'
-                WHEN nid = 0x7F35 THEN '沖縄'
-                WHEN nid >= 0x7FE0 AND nid <= 0x7FE7 THEN '北海道'
-                WHEN nid = 0x7FE8 THEN '東京'
-                WHEN nid = 0x7FE9 THEN '大阪'
-                WHEN nid = 0x7FEA THEN '愛知'
-                WHEN nid = 0x7FEB THEN '岡山'
-                WHEN nid = 0x7FEC THEN '島根'
-                WHEN nid >= 0x7FF0 AND nid <= 0x7FF7 THEN '北海道'
-                ELSE '不明'
-            END
-            WHERE band_type = 0 AND terrestrial_region IS NULL;
-            "#
-        )?;
+        migrations::run(&self.conn, &migrations::migrations())
+    }
 
+    /// Check that the database file can still be read.
+    pub fn check_health(&self) -> Result<()> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))?;
         Ok(())
     }
 
@@ -283,14 +212,30 @@ impl Database {
     }
 }
 
+/// Tuner optimization configuration row:
+/// (keep_alive_secs, prewarm_enabled, prewarm_timeout_secs,
+///  set_channel_retry_interval_ms, set_channel_retry_timeout_ms,
+///  signal_poll_interval_ms, signal_wait_timeout_ms, driver_affinity_enabled,
+///  align_ts_output, descramble_rekey_percent, descramble_rekey_window_secs,
+///  stall_timeout_ms, ts_coalesce_bytes, ts_coalesce_flush_ms, prewarm_group,
+///  prewarm_on_startup, min_signal_level_terrestrial, min_signal_level_satellite)
+pub type TunerConfigRow = (
+    u64, bool, u64, u64, u64, u64, u64, bool, bool, u64, u64, u64, u64, u64, bool, bool, f64, f64,
+);
+
 /// Tuner optimization configuration storage.
 impl Database {
     /// Get tuner optimization configuration from database.
-    pub fn get_tuner_config(&self) -> Result<(u64, bool, u64, u64, u64, u64, u64)> {
+    pub fn get_tuner_config(&self) -> Result<TunerConfigRow> {
         let mut stmt = self.conn.prepare(
             "SELECT keep_alive_secs, prewarm_enabled, prewarm_timeout_secs,
                     set_channel_retry_interval_ms, set_channel_retry_timeout_ms,
-                    signal_poll_interval_ms, signal_wait_timeout_ms
+                    signal_poll_interval_ms, signal_wait_timeout_ms,
+                    driver_affinity_enabled, align_ts_output,
+                    descramble_rekey_percent, descramble_rekey_window_secs,
+                    stall_timeout_ms, ts_coalesce_bytes, ts_coalesce_flush_ms,
+                    prewarm_group, prewarm_on_startup,
+                    min_signal_level_terrestrial, min_signal_level_satellite
              FROM tuner_config WHERE id = 1"
         )?;
 
@@ -303,6 +248,17 @@ impl Database {
                 row.get::<_, u64>(4)?,
                 row.get::<_, u64>(5)?,
                 row.get::<_, u64>(6)?,
+                row.get::<_, Option<i64>>(7)?.unwrap_or(1) != 0,
+                row.get::<_, Option<i64>>(8)?.unwrap_or(0) != 0,
+                row.get::<_, Option<u64>>(9)?.unwrap_or(90),
+                row.get::<_, Option<u64>>(10)?.unwrap_or(30),
+                row.get::<_, Option<u64>>(11)?.unwrap_or(15_000),
+                row.get::<_, Option<u64>>(12)?.unwrap_or(0),
+                row.get::<_, Option<u64>>(13)?.unwrap_or(20),
+                row.get::<_, Option<i64>>(14)?.unwrap_or(0) != 0,
+                row.get::<_, Option<i64>>(15)?.unwrap_or(0) != 0,
+                row.get::<_, Option<f64>>(16)?.unwrap_or(0.0),
+                row.get::<_, Option<f64>>(17)?.unwrap_or(0.0),
             ))
         });
 
@@ -315,6 +271,17 @@ impl Database {
                 set_channel_retry_timeout_ms,
                 signal_poll_interval_ms,
                 signal_wait_timeout_ms,
+                driver_affinity_enabled,
+                align_ts_output,
+                descramble_rekey_percent,
+                descramble_rekey_window_secs,
+                stall_timeout_ms,
+                ts_coalesce_bytes,
+                ts_coalesce_flush_ms,
+                prewarm_group,
+                prewarm_on_startup,
+                min_signal_level_terrestrial,
+                min_signal_level_satellite,
             )) => {
                 Ok((
                     keep_alive,
@@ -324,6 +291,17 @@ impl Database {
                     set_channel_retry_timeout_ms,
                     signal_poll_interval_ms,
                     signal_wait_timeout_ms,
+                    driver_affinity_enabled,
+                    align_ts_output,
+                    descramble_rekey_percent,
+                    descramble_rekey_window_secs,
+                    stall_timeout_ms,
+                    ts_coalesce_bytes,
+                    ts_coalesce_flush_ms,
+                    prewarm_group,
+                    prewarm_on_startup,
+                    min_signal_level_terrestrial,
+                    min_signal_level_satellite,
                 ))
             }
             Err(rusqlite::Error::QueryReturnedNoRows) => {
@@ -331,11 +309,18 @@ impl Database {
                     "INSERT OR IGNORE INTO tuner_config
                      (id, keep_alive_secs, prewarm_enabled, prewarm_timeout_secs,
                       set_channel_retry_interval_ms, set_channel_retry_timeout_ms,
-                      signal_poll_interval_ms, signal_wait_timeout_ms)
-                     VALUES (1, 60, 1, 30, 500, 10000, 500, 10000)",
+                      signal_poll_interval_ms, signal_wait_timeout_ms,
+                      driver_affinity_enabled, align_ts_output,
+                      descramble_rekey_percent, descramble_rekey_window_secs, stall_timeout_ms,
+                      ts_coalesce_bytes, ts_coalesce_flush_ms, prewarm_group, prewarm_on_startup,
+                      min_signal_level_terrestrial, min_signal_level_satellite)
+                     VALUES (1, 60, 1, 30, 500, 10000, 500, 10000, 1, 0, 90, 30, 15000, 0, 20, 0, 0, 0, 0)",
                     [],
                 )?;
-                Ok((60, true, 30, 500, 10000, 500, 10000))
+                Ok((
+                    60, true, 30, 500, 10000, 500, 10000, true, false, 90, 30, 15000, 0, 20, false,
+                    false, 0.0, 0.0,
+                ))
             }
             Err(e) => Err(DatabaseError::Sqlite(e)),
         }
@@ -351,14 +336,34 @@ impl Database {
         set_channel_retry_timeout_ms: u64,
         signal_poll_interval_ms: u64,
         signal_wait_timeout_ms: u64,
+        driver_affinity_enabled: bool,
+        align_ts_output: bool,
+        descramble_rekey_percent: u64,
+        descramble_rekey_window_secs: u64,
+        stall_timeout_ms: u64,
+        ts_coalesce_bytes: u64,
+        ts_coalesce_flush_ms: u64,
+        prewarm_group: bool,
+        prewarm_on_startup: bool,
+        min_signal_level_terrestrial: f64,
+        min_signal_level_satellite: f64,
     ) -> Result<()> {
         let prewarm_enabled = if prewarm_enabled { 1 } else { 0 };
+        let prewarm_group = if prewarm_group { 1 } else { 0 };
+        let prewarm_on_startup = if prewarm_on_startup { 1 } else { 0 };
+        let driver_affinity_enabled = if driver_affinity_enabled { 1 } else { 0 };
+        let align_ts_output = if align_ts_output { 1 } else { 0 };
         self.conn.execute(
             "INSERT OR REPLACE INTO tuner_config
              (id, keep_alive_secs, prewarm_enabled, prewarm_timeout_secs,
               set_channel_retry_interval_ms, set_channel_retry_timeout_ms,
-              signal_poll_interval_ms, signal_wait_timeout_ms, updated_at)
-             VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, strftime('%s', 'now'))",
+              signal_poll_interval_ms, signal_wait_timeout_ms,
+              driver_affinity_enabled, align_ts_output,
+              descramble_rekey_percent, descramble_rekey_window_secs, stall_timeout_ms,
+              ts_coalesce_bytes, ts_coalesce_flush_ms, prewarm_group, prewarm_on_startup,
+              min_signal_level_terrestrial, min_signal_level_satellite, updated_at)
+             VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                     ?17, ?18, strftime('%s', 'now'))",
             rusqlite::params![
                 keep_alive_secs,
                 prewarm_enabled,
@@ -366,13 +371,177 @@ impl Database {
                 set_channel_retry_interval_ms,
                 set_channel_retry_timeout_ms,
                 signal_poll_interval_ms,
-                signal_wait_timeout_ms
+                signal_wait_timeout_ms,
+                driver_affinity_enabled,
+                align_ts_output,
+                descramble_rekey_percent,
+                descramble_rekey_window_secs,
+                stall_timeout_ms,
+                ts_coalesce_bytes,
+                ts_coalesce_flush_ms,
+                prewarm_group,
+                prewarm_on_startup,
+                min_signal_level_terrestrial,
+                min_signal_level_satellite
             ],
         )?;
         Ok(())
     }
 }
 
+/// Server runtime configuration storage.
+impl Database {
+    /// Get the persisted max_connections override (None if never set).
+    pub fn get_server_max_connections(&self) -> Result<Option<usize>> {
+        let result = self.conn.query_row(
+            "SELECT max_connections FROM server_config WHERE id = 1",
+            [],
+            |row| row.get::<_, Option<i64>>(0),
+        );
+
+        match result {
+            Ok(value) => Ok(value.filter(|v| *v > 0).map(|v| v as usize)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DatabaseError::Sqlite(e)),
+        }
+    }
+
+    /// Persist the max_connections override.
+    pub fn set_server_max_connections(&self, max_connections: usize) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO server_config (id, max_connections, updated_at)
+             VALUES (1, ?1, strftime('%s', 'now'))
+             ON CONFLICT(id) DO UPDATE SET
+                max_connections = excluded.max_connections,
+                updated_at = excluded.updated_at",
+            rusqlite::params![max_connections as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Get the persisted session idle timeout override (None if never set).
+    ///
+    /// A stored value of 0 means idle sessions are never reaped.
+    pub fn get_server_session_idle_timeout(&self) -> Result<Option<u64>> {
+        let result = self.conn.query_row(
+            "SELECT session_idle_timeout_secs FROM server_config WHERE id = 1",
+            [],
+            |row| row.get::<_, Option<i64>>(0),
+        );
+
+        match result {
+            Ok(value) => Ok(value.filter(|v| *v >= 0).map(|v| v as u64)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DatabaseError::Sqlite(e)),
+        }
+    }
+
+    /// Persist the session idle timeout override.
+    pub fn set_server_session_idle_timeout(&self, secs: u64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO server_config (id, session_idle_timeout_secs, updated_at)
+             VALUES (1, ?1, strftime('%s', 'now'))
+             ON CONFLICT(id) DO UPDATE SET
+                session_idle_timeout_secs = excluded.session_idle_timeout_secs,
+                updated_at = excluded.updated_at",
+            rusqlite::params![secs as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Get the default per-session TS bitrate cap in Mbps (None = unlimited).
+    pub fn get_server_max_bitrate(&self) -> Result<Option<f64>> {
+        let result = self.conn.query_row(
+            "SELECT max_bitrate_mbps FROM server_config WHERE id = 1",
+            [],
+            |row| row.get::<_, Option<f64>>(0),
+        );
+
+        match result {
+            Ok(value) => Ok(value.filter(|v| *v > 0.0)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DatabaseError::Sqlite(e)),
+        }
+    }
+
+    /// Persist the default per-session TS bitrate cap; 0 means unlimited.
+    pub fn set_server_max_bitrate(&self, mbps: f64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO server_config (id, max_bitrate_mbps, updated_at)
+             VALUES (1, ?1, strftime('%s', 'now'))
+             ON CONFLICT(id) DO UPDATE SET
+                max_bitrate_mbps = excluded.max_bitrate_mbps,
+                updated_at = excluded.updated_at",
+            rusqlite::params![mbps],
+        )?;
+        Ok(())
+    }
+
+    /// Get the persisted half-life of driver quality stats in hours (None if never set).
+    ///
+    /// A stored value of 0 means the stats never decay.
+    pub fn get_quality_half_life_hours(&self) -> Result<Option<f64>> {
+        let result = self.conn.query_row(
+            "SELECT quality_half_life_hours FROM server_config WHERE id = 1",
+            [],
+            |row| row.get::<_, Option<f64>>(0),
+        );
+
+        match result {
+            Ok(value) => Ok(value.filter(|v| *v >= 0.0)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DatabaseError::Sqlite(e)),
+        }
+    }
+
+    /// Persist the half-life of driver quality stats; 0 disables decay.
+    pub fn set_quality_half_life_hours(&self, hours: f64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO server_config (id, quality_half_life_hours, updated_at)
+             VALUES (1, ?1, strftime('%s', 'now'))
+             ON CONFLICT(id) DO UPDATE SET
+                quality_half_life_hours = excluded.quality_half_life_hours,
+                updated_at = excluded.updated_at",
+            rusqlite::params![hours],
+        )?;
+        Ok(())
+    }
+
+    /// Get the virtual space cap settings: (preferred_region, limit_spaces_to_preferred_region).
+    pub fn get_space_cap_config(&self) -> Result<(Option<String>, bool)> {
+        let result = self.conn.query_row(
+            "SELECT preferred_region, limit_spaces_to_preferred_region FROM server_config WHERE id = 1",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, Option<i64>>(1)?.unwrap_or(0) != 0,
+                ))
+            },
+        );
+
+        match result {
+            Ok((region, limit)) => Ok((region.filter(|r| !r.is_empty()), limit)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok((None, false)),
+            Err(e) => Err(DatabaseError::Sqlite(e)),
+        }
+    }
+
+    /// Persist the virtual space cap settings.
+    pub fn set_space_cap_config(&self, preferred_region: Option<&str>, limit: bool) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO server_config (id, preferred_region, limit_spaces_to_preferred_region, updated_at)
+             VALUES (1, ?1, ?2, strftime('%s', 'now'))
+             ON CONFLICT(id) DO UPDATE SET
+                preferred_region = excluded.preferred_region,
+                limit_spaces_to_preferred_region = excluded.limit_spaces_to_preferred_region,
+                updated_at = excluded.updated_at",
+            rusqlite::params![preferred_region, limit],
+        )?;
+        Ok(())
+    }
+}
+
 /// tsreplace configuration storage.
 impl Database {
     fn ensure_tsreplace_config_compat(&self) -> Result<()> {
@@ -535,4 +704,120 @@ mod tests {
 
         assert_eq!(count, 8);
     }
+
+    #[test]
+    fn test_server_max_connections() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.get_server_max_connections().unwrap(), None);
+
+        db.set_server_max_connections(16).unwrap();
+        assert_eq!(db.get_server_max_connections().unwrap(), Some(16));
+    }
+
+    #[test]
+    fn test_space_cap_config_preserves_max_connections() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.get_space_cap_config().unwrap(), (None, false));
+
+        db.set_server_max_connections(8).unwrap();
+        db.set_space_cap_config(Some("関東"), true).unwrap();
+        assert_eq!(db.get_space_cap_config().unwrap(), (Some("関東".to_string()), true));
+        assert_eq!(db.get_server_max_connections().unwrap(), Some(8));
+
+        db.set_server_max_connections(4).unwrap();
+        assert_eq!(db.get_space_cap_config().unwrap(), (Some("関東".to_string()), true));
+    }
+
+    #[test]
+    fn test_migrate_epg_events_unique_key() {
+        let mut db = Database::open_in_memory().unwrap();
+        db.conn
+            .execute_batch(
+                "DROP TABLE epg_events;
+                 CREATE TABLE epg_events (
+                     id INTEGER PRIMARY KEY AUTOINCREMENT,
+                     nid INTEGER NOT NULL,
+                     tsid INTEGER NOT NULL,
+                     sid INTEGER NOT NULL,
+                     event_id INTEGER NOT NULL,
+                     start_time INTEGER,
+                     duration_secs INTEGER,
+                     title TEXT,
+                     description TEXT,
+                     updated_at INTEGER DEFAULT (strftime('%s', 'now')),
+                     UNIQUE(nid, sid, event_id)
+                 );
+                 INSERT INTO epg_events (nid, tsid, sid, event_id, title) VALUES (1, 2, 3, 4, 'old');",
+            )
+            .unwrap();
+        db.conn.execute("DELETE FROM schema_migrations WHERE id = 13", []).unwrap();
+
+        db.apply_migrations().unwrap();
+
+        // The old row survives and the same event on another TS is now distinct
+        assert_eq!(db.get_epg_event(1, 3, 4).unwrap().unwrap().title.as_deref(), Some("old"));
+        db.insert_events(&[crate::database::NewEpgEvent {
+            nid: 1,
+            tsid: 5,
+            sid: 3,
+            event_id: 4,
+            start_time: None,
+            duration_secs: None,
+            title: Some("other".to_string()),
+            description: None,
+        }])
+        .unwrap();
+        let count: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM epg_events WHERE nid = 1 AND sid = 3 AND event_id = 4", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_server_session_idle_timeout() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.get_server_session_idle_timeout().unwrap(), None);
+
+        db.set_server_max_connections(8).unwrap();
+        assert_eq!(db.get_server_session_idle_timeout().unwrap(), None);
+
+        db.set_server_session_idle_timeout(0).unwrap();
+        assert_eq!(db.get_server_session_idle_timeout().unwrap(), Some(0));
+
+        db.set_server_session_idle_timeout(120).unwrap();
+        assert_eq!(db.get_server_session_idle_timeout().unwrap(), Some(120));
+        assert_eq!(db.get_server_max_connections().unwrap(), Some(8));
+    }
+
+    #[test]
+    fn test_server_max_bitrate() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.get_server_max_bitrate().unwrap(), None);
+
+        db.set_server_max_bitrate(12.5).unwrap();
+        assert_eq!(db.get_server_max_bitrate().unwrap(), Some(12.5));
+
+        db.set_server_max_bitrate(0.0).unwrap();
+        assert_eq!(db.get_server_max_bitrate().unwrap(), None);
+    }
+
+    #[test]
+    fn test_last_alert_triggered_at() {
+        let db = Database::open_in_memory().unwrap();
+        let rule_id = db
+            .create_alert_rule("drops", "drop_rate", "gt", 1.0, "warning", true, None, None, 300, None)
+            .unwrap();
+        assert_eq!(db.get_enabled_alert_rules().unwrap()[0].cooldown_secs, 300);
+        assert_eq!(db.get_last_alert_triggered_at(rule_id, Some(1)).unwrap(), None);
+
+        let first = db.insert_alert_history(rule_id, Some(1), 1000, Some(2.0), None).unwrap();
+        db.resolve_alert_history(first, 1010).unwrap();
+        db.insert_alert_history(rule_id, Some(1), 1200, Some(3.0), None).unwrap();
+        db.insert_alert_history(rule_id, Some(2), 1500, Some(3.0), None).unwrap();
+
+        assert_eq!(db.get_last_alert_triggered_at(rule_id, Some(1)).unwrap(), Some(1200));
+        assert_eq!(db.get_last_alert_triggered_at(rule_id, None).unwrap(), None);
+        assert!(db.get_active_alerts().unwrap().iter().all(|a| a.severity.as_deref() == Some("warning")));
+    }
 }
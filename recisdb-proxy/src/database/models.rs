@@ -26,6 +26,20 @@ pub struct BonDriverRecord {
     pub updated_at: i64,
 }
 
+/// Result of resolving a client-supplied tuner name.
+///
+/// Resolution is a single, non-recursive pass: a group name never
+/// resolves to another group, so group definitions cannot loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TunerResolution {
+    /// A single BonDriver, by DLL path.
+    Driver(String),
+    /// A group of BonDrivers, by group name.
+    Group(String),
+    /// Nothing matched the name.
+    NotFound,
+}
+
 /// Channel record from database.
 #[derive(Debug, Clone, Serialize)]
 pub struct ChannelRecord {
@@ -39,10 +53,12 @@ pub struct ChannelRecord {
     // Channel info
     pub raw_name: Option<String>,
     pub channel_name: Option<String>,
+    pub name_is_synthetic: bool,
     pub physical_ch: Option<u8>,
     pub remote_control_key: Option<u8>,
     pub service_type: Option<u8>,
     pub network_name: Option<String>,
+    pub ts_name: Option<String>,
     // BonDriver specific
     pub bon_space: Option<u32>,
     pub bon_channel: Option<u32>,
@@ -50,12 +66,14 @@ pub struct ChannelRecord {
     pub band_type: Option<u8>,
     pub region_id: Option<u8>,
     pub terrestrial_region: Option<String>,
+    pub is_one_seg: bool,
     // State
     pub is_enabled: bool,
     pub scan_time: Option<i64>,
     pub last_seen: Option<i64>,
     pub failure_count: i32,
     pub priority: i32,
+    pub display_order: Option<i32>,
     // Metadata
     pub created_at: i64,
     pub updated_at: i64,
@@ -71,18 +89,53 @@ impl ChannelRecord {
             manual_sheet: self.manual_sheet,
             raw_name: self.raw_name.clone(),
             channel_name: self.channel_name.clone(),
+            name_is_synthetic: self.name_is_synthetic,
             physical_ch: self.physical_ch,
             remote_control_key: self.remote_control_key,
             service_type: self.service_type,
             network_name: self.network_name.clone(),
+            ts_name: self.ts_name.clone(),
             bon_space: self.bon_space,
             bon_channel: self.bon_channel,
             band_type: self.band_type,
             terrestrial_region: self.terrestrial_region.clone(),
+            is_one_seg: self.is_one_seg,
         }
     }
 }
 
+/// One physical location of a logical channel.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalChannelMapping {
+    pub channel_id: i64,
+    pub bon_driver_id: i64,
+    pub dll_path: String,
+    pub driver_name: Option<String>,
+    pub bon_space: Option<u32>,
+    pub bon_channel: Option<u32>,
+    pub is_enabled: bool,
+}
+
+/// Channel rows of every BonDriver merged by NID/TSID/SID.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalChannel {
+    /// Representative row (highest priority), with the highest priority and
+    /// earliest display order across all rows.
+    pub channel: ChannelRecord,
+    /// Every (driver, space, channel) carrying this service.
+    pub mappings: Vec<LogicalChannelMapping>,
+}
+
+impl LogicalChannel {
+    /// Number of distinct BonDrivers carrying this service.
+    pub fn tuner_count(&self) -> usize {
+        let mut ids: Vec<i64> = self.mappings.iter().map(|m| m.bon_driver_id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.len()
+    }
+}
+
 /// Channel record with BonDriver path (for joined queries).
 #[derive(Debug, Clone)]
 pub struct ChannelWithDriver {
@@ -100,6 +153,7 @@ pub struct ClientChannelRecord {
     pub sid: i32,
     pub tsid: i32,
     pub service_name: Option<String>,
+    pub name_is_synthetic: bool,
     pub ts_name: Option<String>,
     pub service_type: Option<i32>,
     pub remote_control_key: Option<i32>,
@@ -107,6 +161,7 @@ pub struct ClientChannelRecord {
     pub channel: u32,
     pub is_enabled: bool,
     pub priority: i32,
+    pub display_order: Option<i32>,
 }
 
 /// Scan history record.
@@ -120,6 +175,26 @@ pub struct ScanHistoryRecord {
     pub error_message: Option<String>,
 }
 
+/// Filter for `Database::search_channels`.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelSearchFilter {
+    /// Only channels of this BonDriver.
+    pub bon_driver_id: Option<i64>,
+    /// Only enabled channels.
+    pub enabled_only: bool,
+}
+
+/// Filter for `Database::get_session_history_paged`.
+#[derive(Debug, Clone, Default)]
+pub struct SessionHistoryFilter {
+    /// Substring match on the client address.
+    pub client_address: Option<String>,
+    /// Inclusive lower bound on `started_at` (unix time).
+    pub from: Option<i64>,
+    /// Exclusive upper bound on `started_at` (unix time).
+    pub to: Option<i64>,
+}
+
 /// Session history record.
 #[derive(Debug, Clone, Serialize)]
 pub struct SessionHistoryRecord {
@@ -140,6 +215,7 @@ pub struct SessionHistoryRecord {
     pub average_bitrate_mbps: Option<f64>,
     pub average_signal_level: Option<f64>,
     pub disconnect_reason: Option<String>,
+    pub client_identity: Option<String>,
     pub created_at: i64,
 }
 
@@ -155,6 +231,10 @@ pub struct AlertRuleRecord {
     pub is_enabled: bool,
     pub webhook_url: Option<String>,
     pub webhook_format: Option<String>,
+    /// Seconds after firing during which the rule stays silent for the same session.
+    pub cooldown_secs: i64,
+    /// What to do when a `critical` rule fires: `disconnect` or `disable_auto_scan`.
+    pub critical_action: Option<String>,
     pub created_at: i64,
 }
 
@@ -169,6 +249,8 @@ pub struct AlertHistoryRecord {
     pub metric_value: Option<f64>,
     pub message: Option<String>,
     pub acknowledged: bool,
+    /// Severity of the rule that raised the alert.
+    pub severity: Option<String>,
 }
 
 /// Driver quality stats record.
@@ -184,9 +266,180 @@ pub struct DriverQualityStats {
     pub quality_score: f64,
     pub recent_drop_rate: f64,
     pub recent_error_rate: f64,
+    /// Reader restarts after the driver stopped delivering TS.
+    pub stall_count: i64,
+    /// Time-weighted counterparts of the counts above, decayed as of
+    /// `last_updated`. The quality score is calculated from these.
+    pub weighted_packets: f64,
+    pub weighted_dropped: f64,
+    pub weighted_scrambled: f64,
+    pub weighted_errors: f64,
+    pub weighted_sessions: f64,
+    pub weighted_stalls: f64,
     pub last_updated: i64,
 }
 
+/// EPG event record (from EIT).
+#[derive(Debug, Clone, Serialize)]
+pub struct EpgEventRecord {
+    pub id: i64,
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    pub event_id: u16,
+    pub start_time: Option<i64>,
+    pub duration_secs: Option<i64>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub updated_at: i64,
+}
+
+/// Programme in a now/next listing.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NowNextEvent {
+    pub event_id: u16,
+    pub title: Option<String>,
+    pub start_time: i64,
+    pub duration_secs: Option<i64>,
+}
+
+/// Currently airing and next programme of an enabled service.
+///
+/// Either is None when the EPG has no such event.
+#[derive(Debug, Clone, Serialize)]
+pub struct NowNextRecord {
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    pub service_name: Option<String>,
+    pub now: Option<NowNextEvent>,
+    pub next: Option<NowNextEvent>,
+}
+
+/// Station logo record (from CDT), keyed by (nid, logo_id, logo_type).
+#[derive(Debug, Clone)]
+pub struct ChannelLogoRecord {
+    pub nid: u16,
+    pub logo_id: u16,
+    /// ARIB logo type (0x00-0x05), selects the image size.
+    pub logo_type: u8,
+    pub logo_version: u16,
+    /// Standard PNG bytes.
+    pub png: Vec<u8>,
+    pub updated_at: i64,
+}
+
+/// Recording reservation status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingStatus {
+    /// Waiting for its start time.
+    Pending,
+    /// Being recorded.
+    Recording,
+    /// Recorded to the end.
+    Done,
+    /// Could not be recorded, or recording stopped with an error.
+    Failed,
+    /// No tuner is left for it once higher-priority reservations are placed.
+    Conflict,
+}
+
+impl RecordingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Recording => "recording",
+            Self::Done => "done",
+            Self::Failed => "failed",
+            Self::Conflict => "conflict",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pending" => Some(Self::Pending),
+            "recording" => Some(Self::Recording),
+            "done" => Some(Self::Done),
+            "failed" => Some(Self::Failed),
+            "conflict" => Some(Self::Conflict),
+            _ => None,
+        }
+    }
+}
+
+/// Client ACL entry: one driver or group a TLS client identity may use.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientAclRecord {
+    pub id: i64,
+    pub identity: String,
+    pub bon_driver_id: Option<i64>,
+    pub group_name: Option<String>,
+    pub created_at: i64,
+}
+
+/// Usage of one service summed over a time window (see `channel_usage_stats`).
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelUsageRecord {
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    /// Channel name from the scanned channel list, if the service is known.
+    pub channel_name: Option<String>,
+    pub bytes_served: i64,
+    pub packets_served: i64,
+    /// Highest hourly peak of concurrent viewers in the window.
+    pub peak_viewers: u32,
+}
+
+/// Recording reservation record.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReservationRecord {
+    pub id: i64,
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    /// EPG event whose time changes the reservation follows.
+    pub event_id: Option<u16>,
+    pub title: Option<String>,
+    /// Unix timestamp, without the scheduler's margins.
+    pub start_time: i64,
+    /// Unix timestamp, without the scheduler's margins.
+    pub end_time: i64,
+    pub output_path: String,
+    pub status: RecordingStatus,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Recording reservation to insert or update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewReservation {
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    pub event_id: Option<u16>,
+    pub title: Option<String>,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub output_path: String,
+}
+
+/// EPG event to insert or update (from EIT).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewEpgEvent {
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    pub event_id: u16,
+    /// Unix timestamp (None = undefined).
+    pub start_time: Option<i64>,
+    pub duration_secs: Option<i64>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
 /// Result of merging scan results into database.
 #[derive(Debug, Default, Clone)]
 pub struct MergeResult {
@@ -201,6 +454,79 @@ impl MergeResult {
     }
 }
 
+/// Signal measured by a scan on one BonDriver channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReceptionProbe {
+    pub space: u32,
+    pub channel: u32,
+    /// Signal level read after tuning (dB).
+    pub signal_level: f32,
+    /// A TSID was read from the stream.
+    pub locked: bool,
+}
+
+/// Reception state of a channel as of its last scan.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ChannelReception {
+    pub signal_level: Option<f64>,
+    pub reception_ok: bool,
+    /// Disabled by a scan rather than by the user.
+    pub auto_disabled: bool,
+}
+
+/// Channels changed by `Database::apply_scan_reception`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReceptionUpdate {
+    /// Channels flagged `reception_ok = false`.
+    pub failed: usize,
+    /// Channels disabled because of it.
+    pub disabled: usize,
+    /// Previously auto-disabled channels enabled again.
+    pub restored: usize,
+}
+
+/// Counts reported by a configuration import.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ConfigImportSummary {
+    pub drivers_added: usize,
+    pub drivers_updated: usize,
+    pub drivers_skipped: usize,
+    pub channels_added: usize,
+    pub channels_updated: usize,
+    pub channels_skipped: usize,
+}
+
+/// Result of `Database::maintenance`.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct MaintenanceReport {
+    pub retention_days: u64,
+    pub sessions_pruned: usize,
+    pub alerts_pruned: usize,
+    /// Database size in bytes before pruning and VACUUM.
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+/// A BonDriver group with its members, from `Database::get_groups`.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupSummary {
+    pub name: String,
+    pub strategy: Option<String>,
+    pub drivers: Vec<BonDriverRecord>,
+    /// Sum of the members' max_instances.
+    pub total_instances: i64,
+}
+
+/// Result of `Database::reconcile_channels`.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ReconcileReport {
+    /// Channels moved to the current driver with their old driver's path.
+    pub relinked: usize,
+    /// Channels that could not be re-linked; they are disabled.
+    pub orphaned: usize,
+    pub orphaned_channel_ids: Vec<i64>,
+}
+
 /// New BonDriver to insert.
 #[derive(Debug, Clone, Default)]
 pub struct NewBonDriver {
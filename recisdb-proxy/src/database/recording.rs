@@ -0,0 +1,207 @@
+//! Recording reservation database operations.
+
+use rusqlite::{params, Row};
+
+use super::{Database, NewReservation, RecordingStatus, ReservationRecord, Result};
+
+const RESERVATION_COLUMNS: &str =
+    "id, nid, tsid, sid, event_id, title, start_time, end_time, output_path, status, error, created_at, updated_at";
+
+impl Database {
+    /// Insert a recording reservation (status `pending`).
+    pub fn insert_reservation(&self, reservation: &NewReservation) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO recordings (nid, tsid, sid, event_id, title, start_time, end_time, output_path)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                reservation.nid as i32,
+                reservation.tsid as i32,
+                reservation.sid as i32,
+                reservation.event_id.map(|id| id as i32),
+                reservation.title,
+                reservation.start_time,
+                reservation.end_time,
+                reservation.output_path,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Get a recording reservation by ID.
+    pub fn get_reservation(&self, id: i64) -> Result<Option<ReservationRecord>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM recordings WHERE id = ?1",
+            RESERVATION_COLUMNS
+        ))?;
+        match stmt.query_row([id], Self::row_to_reservation) {
+            Ok(record) => Ok(Some(record)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get all recording reservations, ordered by start time.
+    pub fn get_reservations(&self) -> Result<Vec<ReservationRecord>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM recordings ORDER BY start_time, id",
+            RESERVATION_COLUMNS
+        ))?;
+        let rows = stmt.query_map([], Self::row_to_reservation)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Get reservations that still need the scheduler's attention
+    /// (pending, conflict or recording), ordered by start time.
+    pub fn get_open_reservations(&self) -> Result<Vec<ReservationRecord>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM recordings WHERE status IN ('pending', 'conflict', 'recording')
+             ORDER BY start_time, id",
+            RESERVATION_COLUMNS
+        ))?;
+        let rows = stmt.query_map([], Self::row_to_reservation)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Replace a reservation's settings and put it back to `pending`.
+    ///
+    /// Returns false if no reservation has that ID.
+    pub fn update_reservation(&self, id: i64, reservation: &NewReservation) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE recordings SET nid = ?2, tsid = ?3, sid = ?4, event_id = ?5, title = ?6,
+                 start_time = ?7, end_time = ?8, output_path = ?9, status = 'pending', error = NULL,
+                 updated_at = strftime('%s', 'now')
+             WHERE id = ?1",
+            params![
+                id,
+                reservation.nid as i32,
+                reservation.tsid as i32,
+                reservation.sid as i32,
+                reservation.event_id.map(|id| id as i32),
+                reservation.title,
+                reservation.start_time,
+                reservation.end_time,
+                reservation.output_path,
+            ],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Move a reservation to follow a changed EPG event time.
+    pub fn update_reservation_times(&self, id: i64, start_time: i64, end_time: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET start_time = ?2, end_time = ?3, updated_at = strftime('%s', 'now') WHERE id = ?1",
+            params![id, start_time, end_time],
+        )?;
+        Ok(())
+    }
+
+    /// Set a reservation's status and error message.
+    pub fn set_reservation_status(&self, id: i64, status: RecordingStatus, error: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET status = ?2, error = ?3, updated_at = strftime('%s', 'now') WHERE id = ?1",
+            params![id, status.as_str(), error],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a reservation. Returns false if no reservation has that ID.
+    pub fn delete_reservation(&self, id: i64) -> Result<bool> {
+        let deleted = self.conn.execute("DELETE FROM recordings WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
+    }
+
+    /// Mark reservations left in `recording` by a previous run as failed.
+    ///
+    /// Returns the number of reservations updated.
+    pub fn fail_interrupted_reservations(&self) -> Result<usize> {
+        let updated = self.conn.execute(
+            "UPDATE recordings SET status = 'failed', error = 'Interrupted by server restart',
+                 updated_at = strftime('%s', 'now')
+             WHERE status = 'recording'",
+            [],
+        )?;
+        Ok(updated)
+    }
+
+    fn row_to_reservation(row: &Row) -> rusqlite::Result<ReservationRecord> {
+        let status: String = row.get(9)?;
+        Ok(ReservationRecord {
+            id: row.get(0)?,
+            nid: row.get::<_, i32>(1)? as u16,
+            tsid: row.get::<_, i32>(2)? as u16,
+            sid: row.get::<_, i32>(3)? as u16,
+            event_id: row.get::<_, Option<i32>>(4)?.map(|id| id as u16),
+            title: row.get(5)?,
+            start_time: row.get(6)?,
+            end_time: row.get(7)?,
+            output_path: row.get(8)?,
+            // Unknown values only come from manual edits; treat them as failed
+            status: RecordingStatus::from_name(&status).unwrap_or(RecordingStatus::Failed),
+            error: row.get(10)?,
+            created_at: row.get(11)?,
+            updated_at: row.get(12)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reservation(start_time: i64) -> NewReservation {
+        NewReservation {
+            nid: 0x7FE0,
+            tsid: 0x7FE0,
+            sid: 1024,
+            event_id: Some(100),
+            title: Some("News".to_string()),
+            start_time,
+            end_time: start_time + 1800,
+            output_path: "news.ts".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_reservation_crud() {
+        let db = Database::open_in_memory().unwrap();
+        let late = db.insert_reservation(&reservation(2_000)).unwrap();
+        let early = db.insert_reservation(&reservation(1_000)).unwrap();
+
+        let all: Vec<i64> = db.get_reservations().unwrap().iter().map(|r| r.id).collect();
+        assert_eq!(all, [early, late]);
+
+        let record = db.get_reservation(early).unwrap().unwrap();
+        assert_eq!(record.status, RecordingStatus::Pending);
+        assert_eq!(record.event_id, Some(100));
+        assert_eq!(record.end_time, 2_800);
+
+        db.set_reservation_status(early, RecordingStatus::Done, None).unwrap();
+        db.set_reservation_status(late, RecordingStatus::Conflict, Some("no tuner")).unwrap();
+        let open: Vec<i64> = db.get_open_reservations().unwrap().iter().map(|r| r.id).collect();
+        assert_eq!(open, [late]);
+
+        // Editing resets the status
+        assert!(db.update_reservation(late, &reservation(3_000)).unwrap());
+        let record = db.get_reservation(late).unwrap().unwrap();
+        assert_eq!((record.status, record.error, record.start_time), (RecordingStatus::Pending, None, 3_000));
+
+        db.update_reservation_times(late, 3_060, 4_860).unwrap();
+        assert_eq!(db.get_reservation(late).unwrap().unwrap().end_time, 4_860);
+
+        assert!(db.delete_reservation(early).unwrap());
+        assert!(!db.delete_reservation(early).unwrap());
+        assert!(db.get_reservation(early).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fail_interrupted_reservations() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db.insert_reservation(&reservation(1_000)).unwrap();
+        db.set_reservation_status(id, RecordingStatus::Recording, None).unwrap();
+
+        assert_eq!(db.fail_interrupted_reservations().unwrap(), 1);
+        let record = db.get_reservation(id).unwrap().unwrap();
+        assert_eq!(record.status, RecordingStatus::Failed);
+        assert!(record.error.is_some());
+    }
+}
@@ -2,6 +2,13 @@
 
 /// SQL schema for the channel database.
 pub const SCHEMA_SQL: &str = r#"
+-- Applied schema migrations (see migrations.rs)
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    applied_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
 -- BonDriver management table
 CREATE TABLE IF NOT EXISTS bon_drivers (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -10,9 +17,12 @@ CREATE TABLE IF NOT EXISTS bon_drivers (
     version TEXT,
     -- Group management for multi-tuner selection
     group_name TEXT,                       -- Unified group name (e.g., "PX-MLT", "PX-Q1UD")
+    group_strategy TEXT,                   -- Driver selection within the group (quality/round_robin/least_loaded)
     -- Scan configuration (per-tuner)
     auto_scan_enabled INTEGER DEFAULT 1,     -- Auto scan enabled/disabled
     scan_interval_hours INTEGER DEFAULT 24,  -- Scan interval in hours (0 = disabled)
+    scan_cron TEXT,                          -- Cron schedule (local time); overrides the interval
+    scan_scope TEXT,                         -- Bands/channels to scan (NULL = all; see scheduler::scan_scope)
     scan_priority INTEGER DEFAULT 0,         -- Scan priority (higher = scanned first)
     last_scan INTEGER,                       -- Last scan timestamp
     next_scan_at INTEGER,                    -- Next scheduled scan timestamp
@@ -20,6 +30,14 @@ CREATE TABLE IF NOT EXISTS bon_drivers (
     passive_scan_enabled INTEGER DEFAULT 1,  -- Real-time update during streaming
     -- Concurrent usage control
     max_instances INTEGER DEFAULT 1,         -- Maximum concurrent instances (1 for exclusive)
+    -- Codec capability
+    supports_4k INTEGER DEFAULT 0,           -- Can receive 4K/HEVC transports (BS4K/CS4K)
+    -- Broadcast buffer
+    broadcast_capacity INTEGER,              -- TS chunks buffered per tuner (NULL = default)
+    lag_policy TEXT,                         -- drop_oldest/slow_producer (NULL = drop_oldest)
+    -- Scan reception check
+    reception_min_signal REAL,               -- Signal level (dB) a channel needs to count as received (NULL = scanner default)
+    auto_disable_no_reception INTEGER DEFAULT 0, -- Disable channels that fail the reception check
     -- Metadata
     created_at INTEGER DEFAULT (strftime('%s', 'now')),
     updated_at INTEGER DEFAULT (strftime('%s', 'now'))
@@ -29,6 +47,7 @@ CREATE TABLE IF NOT EXISTS bon_drivers (
 CREATE TABLE IF NOT EXISTS channels (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     bon_driver_id INTEGER NOT NULL,
+    driver_dll_path TEXT,                -- dll_path of the linked BonDriver, kept by triggers (see reconcile_channels)
     -- Unique identification key (NID-SID-TSID-manual_sheet)
     nid INTEGER NOT NULL,                -- Network ID (from SDT)
     sid INTEGER NOT NULL,                -- Service ID
@@ -41,6 +60,8 @@ CREATE TABLE IF NOT EXISTS channels (
     remote_control_key INTEGER,          -- Remote control key ID (from NIT)
     service_type INTEGER,                -- Service type (0x01=TV, 0x02=Radio, etc.)
     network_name TEXT,                   -- Network name (from NIT)
+    ts_name TEXT,                        -- TS name (from NIT TS information descriptor)
+    name_is_synthetic INTEGER DEFAULT 0, -- channel_name was derived by the scanner, not sent by the broadcaster
     -- BonDriver-specific information
     bon_space INTEGER,                   -- BonDriver Space number
     bon_channel INTEGER,                 -- BonDriver Channel number
@@ -48,13 +69,19 @@ CREATE TABLE IF NOT EXISTS channels (
     band_type INTEGER,                   -- BandType enum (0=Terrestrial, 1=BS, 2=CS, 3=4K, 4=Other, 5=CATV, 6=SKY)
     region_id INTEGER,                   -- ARIB region ID (1-62 for terrestrial, NULL for others)
     terrestrial_region TEXT,             -- Prefecture name for Terrestrial (e.g., "福島", "宮城")
+    is_one_seg INTEGER DEFAULT 0,        -- 1seg (partial reception) service flag (from NIT)
+    logo_id INTEGER,                     -- CDT logo ID (from SDT logo transmission descriptor)
     -- State management
     is_enabled INTEGER DEFAULT 1,        -- Enabled/disabled flag
     scan_time INTEGER,                   -- Last scan timestamp
     last_seen INTEGER,                   -- Last detected timestamp (for auto-update)
     failure_count INTEGER DEFAULT 0,     -- Consecutive tuning failure count
+    signal_level REAL,                   -- Signal level measured by the last scan (dB)
+    reception_ok INTEGER,                -- Last scan locked above the driver's threshold (NULL = not scanned)
+    auto_disabled INTEGER DEFAULT 0,     -- Disabled by a scan for poor reception (re-enabled when it recovers)
     -- Selection priority
     priority INTEGER DEFAULT 0,          -- Channel selection priority (for logical mode)
+    display_order INTEGER,               -- User-defined lineup position (NULL = unordered)
     -- Metadata
     created_at INTEGER DEFAULT (strftime('%s', 'now')),
     updated_at INTEGER DEFAULT (strftime('%s', 'now')),
@@ -94,6 +121,35 @@ CREATE TABLE IF NOT EXISTS tuner_config (
     set_channel_retry_timeout_ms INTEGER DEFAULT 10000,
     signal_poll_interval_ms INTEGER DEFAULT 500,
     signal_wait_timeout_ms INTEGER DEFAULT 10000,
+    driver_affinity_enabled INTEGER DEFAULT 1,
+    align_ts_output INTEGER DEFAULT 0,
+    descramble_rekey_percent INTEGER DEFAULT 90,
+    descramble_rekey_window_secs INTEGER DEFAULT 30,
+    stall_timeout_ms INTEGER DEFAULT 15000,
+    ts_coalesce_bytes INTEGER DEFAULT 0,
+    ts_coalesce_flush_ms INTEGER DEFAULT 20,
+    prewarm_group INTEGER DEFAULT 0,
+    prewarm_on_startup INTEGER DEFAULT 0,
+    min_signal_level_terrestrial REAL DEFAULT 0,
+    min_signal_level_satellite REAL DEFAULT 0,
+    updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Server runtime configuration table
+-- max_connections: NULL = use config file / command line value
+-- preferred_region: terrestrial region key (e.g. "関東") shown to clients
+-- limit_spaces_to_preferred_region: hide other terrestrial regions from EnumTuningSpace
+-- session_idle_timeout_secs: NULL = use config file / command line value, 0 = never reap
+-- max_bitrate_mbps: default per-session TS bitrate cap, NULL or 0 = unlimited
+-- quality_half_life_hours: half-life of driver quality stats, NULL = 168, 0 = no decay
+CREATE TABLE IF NOT EXISTS server_config (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    max_connections INTEGER,
+    preferred_region TEXT,
+    limit_spaces_to_preferred_region INTEGER DEFAULT 0,
+    session_idle_timeout_secs INTEGER,
+    max_bitrate_mbps REAL,
+    quality_half_life_hours REAL,
     updated_at INTEGER DEFAULT (strftime('%s', 'now'))
 );
 
@@ -127,6 +183,7 @@ CREATE TABLE IF NOT EXISTS session_history (
     average_bitrate_mbps REAL,
     average_signal_level REAL,
     disconnect_reason TEXT,
+    client_identity TEXT,                -- TLS client certificate CN/SAN
     created_at INTEGER DEFAULT (strftime('%s', 'now'))
 );
 
@@ -141,6 +198,8 @@ CREATE TABLE IF NOT EXISTS alert_rules (
     is_enabled INTEGER DEFAULT 1,
     webhook_url TEXT,
     webhook_format TEXT DEFAULT 'generic',
+    cooldown_secs INTEGER DEFAULT 0,
+    critical_action TEXT,           -- 'disconnect', 'disable_auto_scan' (critical rules only)
     created_at INTEGER DEFAULT (strftime('%s', 'now'))
 );
 
@@ -172,12 +231,89 @@ CREATE TABLE IF NOT EXISTS driver_quality_stats (
     -- Recent stats (last 24h)
     recent_drop_rate REAL DEFAULT 0.0,
     recent_error_rate REAL DEFAULT 0.0,
+    -- Reader restarts after the driver stopped delivering TS
+    stall_count INTEGER DEFAULT 0,
+    -- Time-weighted counts, halved every quality_half_life_hours
+    -- (NULL = not decayed yet, falls back to the cumulative counts)
+    weighted_packets REAL,
+    weighted_dropped REAL,
+    weighted_scrambled REAL,
+    weighted_errors REAL,
+    weighted_sessions REAL,
+    weighted_stalls REAL,
     -- Timestamp
     last_updated INTEGER DEFAULT (strftime('%s', 'now')),
     UNIQUE(bon_driver_id),
     FOREIGN KEY(bon_driver_id) REFERENCES bon_drivers(id) ON DELETE CASCADE
 );
 
+-- EPG event table (from EIT)
+CREATE TABLE IF NOT EXISTS epg_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    nid INTEGER NOT NULL,                -- original_network_id
+    tsid INTEGER NOT NULL,               -- transport_stream_id
+    sid INTEGER NOT NULL,                -- service_id
+    event_id INTEGER NOT NULL,
+    start_time INTEGER,                  -- Unix timestamp (NULL = undefined)
+    duration_secs INTEGER,               -- NULL = undefined
+    title TEXT,
+    description TEXT,
+    updated_at INTEGER DEFAULT (strftime('%s', 'now')),
+    UNIQUE(nid, tsid, sid, event_id)
+);
+
+-- Recording reservations table
+CREATE TABLE IF NOT EXISTS recordings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    nid INTEGER NOT NULL,
+    tsid INTEGER NOT NULL,
+    sid INTEGER NOT NULL,
+    event_id INTEGER,                    -- EPG event to follow (NULL = fixed time range)
+    title TEXT,
+    start_time INTEGER NOT NULL,         -- Unix timestamp, without margins
+    end_time INTEGER NOT NULL,           -- Unix timestamp, without margins
+    output_path TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending', -- 'pending', 'recording', 'done', 'failed', 'conflict'
+    error TEXT,
+    created_at INTEGER DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Station logos (from CDT), shared by every service announcing the logo_id
+CREATE TABLE IF NOT EXISTS channel_logos (
+    nid INTEGER NOT NULL,                -- original_network_id
+    logo_id INTEGER NOT NULL,
+    logo_type INTEGER NOT NULL,          -- 0x00-0x05 (image size)
+    logo_version INTEGER NOT NULL,
+    png BLOB NOT NULL,                   -- Standard PNG (ARIB palette injected)
+    updated_at INTEGER DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY(nid, logo_id, logo_type)
+);
+
+-- Tuners a TLS client identity may use. An identity without rows is unrestricted.
+-- bon_driver_id has no foreign key on purpose: deleting a driver must not
+-- remove an identity's last entry and so lift its restriction.
+CREATE TABLE IF NOT EXISTS client_acl (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    identity TEXT NOT NULL,              -- Client certificate CN (or first SAN)
+    bon_driver_id INTEGER,
+    group_name TEXT,                     -- Grants every driver in the group
+    created_at INTEGER DEFAULT (strftime('%s', 'now')),
+    CHECK (bon_driver_id IS NOT NULL OR group_name IS NOT NULL)
+);
+
+-- Bytes served and peak concurrent viewers per service, in hourly buckets
+CREATE TABLE IF NOT EXISTS channel_usage_stats (
+    nid INTEGER NOT NULL,
+    tsid INTEGER NOT NULL,
+    sid INTEGER NOT NULL,
+    bucket_start INTEGER NOT NULL,       -- Unix timestamp of the hour
+    bytes_served INTEGER NOT NULL DEFAULT 0,
+    packets_served INTEGER NOT NULL DEFAULT 0,
+    peak_viewers INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY(nid, tsid, sid, bucket_start)
+);
+
 -- Indexes for efficient queries
 CREATE INDEX IF NOT EXISTS idx_bon_drivers_group_name ON bon_drivers(group_name);
 CREATE INDEX IF NOT EXISTS idx_channels_bon_driver ON channels(bon_driver_id);
@@ -188,9 +324,14 @@ CREATE INDEX IF NOT EXISTS idx_scan_history_bon_driver ON scan_history(bon_drive
 CREATE INDEX IF NOT EXISTS idx_channels_band_type ON channels(band_type, is_enabled);
 CREATE INDEX IF NOT EXISTS idx_session_history_session_id ON session_history(session_id);
 CREATE INDEX IF NOT EXISTS idx_session_history_created_at ON session_history(created_at);
+CREATE INDEX IF NOT EXISTS idx_session_history_started_at ON session_history(started_at);
 CREATE INDEX IF NOT EXISTS idx_alert_rules_enabled ON alert_rules(is_enabled);
 CREATE INDEX IF NOT EXISTS idx_alert_history_rule ON alert_history(rule_id);
 CREATE INDEX IF NOT EXISTS idx_driver_quality_stats_driver ON driver_quality_stats(bon_driver_id);
+CREATE INDEX IF NOT EXISTS idx_epg_events_service_start ON epg_events(nid, tsid, sid, start_time);
+CREATE INDEX IF NOT EXISTS idx_recordings_status_start ON recordings(status, start_time);
+CREATE INDEX IF NOT EXISTS idx_client_acl_identity ON client_acl(identity);
+CREATE INDEX IF NOT EXISTS idx_channel_usage_stats_bucket ON channel_usage_stats(bucket_start);
 
 -- Trigger to update updated_at on bon_drivers
 CREATE TRIGGER IF NOT EXISTS bon_drivers_updated_at
@@ -234,5 +375,10 @@ mod tests {
         assert!(tables.contains(&"alert_history".to_string()));
         assert!(tables.contains(&"driver_quality_stats".to_string()));
         assert!(tables.contains(&"tuner_config".to_string()));
+        assert!(tables.contains(&"server_config".to_string()));
+        assert!(tables.contains(&"epg_events".to_string()));
+        assert!(tables.contains(&"channel_logos".to_string()));
+        assert!(tables.contains(&"client_acl".to_string()));
+        assert!(tables.contains(&"channel_usage_stats".to_string()));
     }
 }
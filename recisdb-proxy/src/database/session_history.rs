@@ -2,7 +2,7 @@
 
 use rusqlite::params;
 
-use super::{Database, Result, SessionHistoryRecord};
+use super::{Database, Result, SessionHistoryFilter, SessionHistoryRecord};
 
 impl Database {
     /// Insert session start record.
@@ -14,10 +14,11 @@ impl Database {
         channel_info: Option<&str>,
         channel_name: Option<&str>,
         started_at: i64,
+        client_identity: Option<&str>,
     ) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO session_history (session_id, client_address, tuner_path, channel_info, channel_name, started_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![session_id as i64, client_address, tuner_path, channel_info, channel_name, started_at],
+            "INSERT INTO session_history (session_id, client_address, tuner_path, channel_info, channel_name, started_at, client_identity) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![session_id as i64, client_address, tuner_path, channel_info, channel_name, started_at, client_identity],
         )?;
         Ok(self.conn.last_insert_rowid())
     }
@@ -117,35 +118,61 @@ impl Database {
         per_page: u32,
         client_address: Option<&str>,
     ) -> Result<(Vec<SessionHistoryRecord>, u32)> {
-        let offset = (page.saturating_sub(1) * per_page) as i64;
-        let limit = per_page as i64;
-
-        let (count_sql, list_sql, params_vec): (String, String, Vec<rusqlite::types::Value>) =
-            if let Some(addr) = client_address {
-                let like = format!("%{}%", addr);
-                (
-                    "SELECT COUNT(*) FROM session_history WHERE client_address LIKE ?1".to_string(),
-                    "SELECT id, session_id, client_address, tuner_path, channel_info, channel_name, started_at, ended_at, duration_secs, packets_sent, packets_dropped, packets_scrambled, packets_error, bytes_sent, average_bitrate_mbps, average_signal_level, disconnect_reason, created_at FROM session_history WHERE client_address LIKE ?1 ORDER BY started_at DESC LIMIT ?2 OFFSET ?3".to_string(),
-                    vec![like.into(), limit.into(), offset.into()],
-                )
-            } else {
-                (
-                    "SELECT COUNT(*) FROM session_history".to_string(),
-                    "SELECT id, session_id, client_address, tuner_path, channel_info, channel_name, started_at, ended_at, duration_secs, packets_sent, packets_dropped, packets_scrambled, packets_error, bytes_sent, average_bitrate_mbps, average_signal_level, disconnect_reason, created_at FROM session_history ORDER BY started_at DESC LIMIT ?1 OFFSET ?2".to_string(),
-                    vec![limit.into(), offset.into()],
-                )
-            };
-
-        let total: u32 = if let Some(addr) = client_address {
-            self.conn
-                .query_row(&count_sql, params![format!("%{}%", addr)], |row| row.get(0))?
+        let filter = SessionHistoryFilter {
+            client_address: client_address.map(str::to_string),
+            ..Default::default()
+        };
+        let offset = page.saturating_sub(1).saturating_mul(per_page);
+        let (rows, total) = self.get_session_history_paged(&filter, per_page, offset)?;
+        Ok((rows, total.min(u32::MAX as u64) as u32))
+    }
+
+    /// Get one page of session history, newest first.
+    ///
+    /// Returns the page and the number of sessions matching `filter`.
+    /// `from`/`to` select sessions whose `started_at` is in `[from, to)`.
+    pub fn get_session_history_paged(
+        &self,
+        filter: &SessionHistoryFilter,
+        limit: u32,
+        offset: u32,
+    ) -> Result<(Vec<SessionHistoryRecord>, u64)> {
+        let mut conditions: Vec<&str> = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        if let Some(addr) = filter.client_address.as_deref().filter(|a| !a.is_empty()) {
+            conditions.push("client_address LIKE ?");
+            values.push(format!("%{}%", addr).into());
+        }
+        if let Some(from) = filter.from {
+            conditions.push("started_at >= ?");
+            values.push(from.into());
+        }
+        if let Some(to) = filter.to {
+            conditions.push("started_at < ?");
+            values.push(to.into());
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
         } else {
-            self.conn.query_row(&count_sql, [], |row| row.get(0))?
+            format!(" WHERE {}", conditions.join(" AND "))
         };
 
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM session_history{}", where_clause),
+            rusqlite::params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        let list_sql = format!(
+            "SELECT id, session_id, client_address, tuner_path, channel_info, channel_name, started_at, ended_at, duration_secs, packets_sent, packets_dropped, packets_scrambled, packets_error, bytes_sent, average_bitrate_mbps, average_signal_level, disconnect_reason, client_identity, created_at FROM session_history{} ORDER BY started_at DESC, id DESC LIMIT ? OFFSET ?",
+            where_clause
+        );
+        values.push((limit as i64).into());
+        values.push((offset as i64).into());
+
         let mut stmt = self.conn.prepare(&list_sql)?;
         let rows = stmt
-            .query_map(rusqlite::params_from_iter(params_vec), |row| {
+            .query_map(rusqlite::params_from_iter(values), |row| {
                 Ok(SessionHistoryRecord {
                     id: row.get(0)?,
                     session_id: row.get(1)?,
@@ -164,11 +191,55 @@ impl Database {
                     average_bitrate_mbps: row.get(14)?,
                     average_signal_level: row.get(15)?,
                     disconnect_reason: row.get(16)?,
-                    created_at: row.get(17)?,
+                    client_identity: row.get(17)?,
+                    created_at: row.get(18)?,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok((rows, total))
+        Ok((rows, total as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_session_history_paged() {
+        let db = Database::open_in_memory().unwrap();
+        for i in 0..5 {
+            let addr = if i % 2 == 0 { "192.168.1.10:50000" } else { "10.0.0.5:40000" };
+            let identity = (i == 0).then_some("living-room");
+            db.insert_session_start(i, addr, None, None, None, 1_000 + i as i64 * 100, identity).unwrap();
+        }
+
+        // Newest first, with the total across all pages
+        let (page, total) = db
+            .get_session_history_paged(&SessionHistoryFilter::default(), 2, 0)
+            .unwrap();
+        assert_eq!(total, 5);
+        assert_eq!(page.iter().map(|r| r.started_at).collect::<Vec<_>>(), vec![1_400, 1_300]);
+        assert_eq!(page[0].client_identity, None);
+
+        let (page, _) = db
+            .get_session_history_paged(&SessionHistoryFilter::default(), 2, 4)
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].client_identity.as_deref(), Some("living-room"));
+
+        let filter = SessionHistoryFilter {
+            client_address: Some("192.168.".to_string()),
+            from: Some(1_100),
+            to: Some(1_400),
+        };
+        let (page, total) = db.get_session_history_paged(&filter, 10, 0).unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(page[0].started_at, 1_200);
+
+        // Legacy page-based accessor agrees
+        let (page, total) = db.get_session_history(2, 2, None).unwrap();
+        assert_eq!(total, 5);
+        assert_eq!(page[0].started_at, 1_200);
     }
 }
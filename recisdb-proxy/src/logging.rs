@@ -4,12 +4,85 @@
 //! Log files are automatically rotated based on time, keeping only logs from
 //! the last N days.
 
+use std::collections::BTreeMap;
 use std::io;
+use std::net::SocketAddr;
 use std::path::Path;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing_subscriber::{fmt, prelude::*, reload, EnvFilter, Registry};
 use chrono::Local;
 use std::fs;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+/// Output format for console and file logs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines (default).
+    #[default]
+    Text,
+    /// One JSON object per line, for Loki/Elasticsearch ingestion.
+    Json,
+}
+
+/// Whether logs are emitted as JSON (set once by `init_logging`).
+static JSON_FORMAT: AtomicBool = AtomicBool::new(false);
+
+/// Span covering a client session's log lines.
+///
+/// With JSON output every event inside it carries `session_id`, `client`,
+/// `identity`, `driver` and `channel` as structured fields; `driver` and
+/// `channel` are recorded by the session as they change. Text output already
+/// names the session on each line, so no span is created for it.
+pub fn session_span(session_id: u64, addr: SocketAddr, identity: Option<&str>) -> tracing::Span {
+    if !JSON_FORMAT.load(Ordering::Relaxed) {
+        return tracing::Span::none();
+    }
+    tracing::info_span!(
+        "session",
+        session_id,
+        client = %addr,
+        identity,
+        driver = tracing::field::Empty,
+        channel = tracing::field::Empty,
+    )
+}
+
+/// Runtime handle for the global log filter.
+///
+/// Swapping the filter takes effect for every subsequent log call, so the
+/// level can be raised while diagnosing a tuner without restarting the server.
+pub struct LogFilterHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+    /// Directive string of the active filter.
+    current: Mutex<String>,
+}
+
+impl LogFilterHandle {
+    /// The active filter directives (e.g. "info,recisdb_proxy::tuner=debug").
+    pub fn current(&self) -> String {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Replace the active filter. Invalid directives leave it unchanged.
+    pub fn set(&self, directives: &str) -> Result<(), String> {
+        let filter = EnvFilter::try_new(directives).map_err(|e| e.to_string())?;
+        self.handle.reload(filter).map_err(|e| e.to_string())?;
+        *self.current.lock().unwrap() = directives.to_string();
+        Ok(())
+    }
+}
+
+/// Build filter directives from a default level and per-target levels.
+///
+/// `("warn", {"recisdb_proxy::tuner": "debug"})` becomes
+/// `"warn,recisdb_proxy::tuner=debug"`.
+pub fn build_filter_directives(level: &str, targets: &BTreeMap<String, String>) -> String {
+    std::iter::once(level.to_string())
+        .chain(targets.iter().map(|(target, level)| format!("{}={}", target, level)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
 
 /// Initialize the logging system with both console and file output.
 ///
@@ -18,12 +91,18 @@ use std::sync::Arc;
 /// * `retention_days` - Number of days to keep log files
 /// * `verbose` - Whether to enable debug-level logging
 /// * `level` - Log level override from config file (e.g. "warn", "info", "error")
+/// * `targets` - Per-target levels from config file (e.g. "recisdb_proxy::tuner" → "debug")
+/// * `format` - Text or JSON output for both console and file
+///
+/// Returns the handle used to change the filter at runtime.
 pub fn init_logging(
     log_dir: &Path,
     retention_days: u64,
     verbose: bool,
     level: Option<&str>,
-) -> Result<(), Box<dyn std::error::Error>> {
+    targets: &BTreeMap<String, String>,
+    format: LogFormat,
+) -> Result<Arc<LogFilterHandle>, Box<dyn std::error::Error>> {
     // Create logs directory if it doesn't exist
     fs::create_dir_all(log_dir)?;
 
@@ -43,14 +122,24 @@ pub fn init_logging(
     } else {
         level.unwrap_or("info")
     };
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(default_level));
+    let directives = std::env::var(EnvFilter::DEFAULT_ENV)
+        .unwrap_or_else(|_| build_filter_directives(default_level, targets));
+    let (env_filter, directives) = match EnvFilter::try_new(&directives) {
+        Ok(filter) => (filter, directives),
+        Err(e) => {
+            eprintln!("Invalid log filter {:?}: {}; falling back to {:?}", directives, e, default_level);
+            (EnvFilter::new(default_level), default_level.to_string())
+        }
+    };
+    let (env_filter, reload_handle) = reload::Layer::new(env_filter);
 
     // Build the subscriber with both console and file output
     // Use tracing_log to bridge log:: macros to tracing
+    let json = format == LogFormat::Json;
+    JSON_FORMAT.store(json, Ordering::Relaxed);
     let subscriber = tracing_subscriber::registry()
         .with(env_filter)
-        .with(
+        .with((!json).then(|| {
             fmt::layer()
                 .with_writer(io::stdout)
                 .with_target(true)
@@ -59,10 +148,10 @@ pub fn init_logging(
                 .with_file(false)
                 .with_line_number(false)
                 .with_timer(LocalTimeTimer)
-        )
-        .with(
+        }))
+        .with((!json).then(|| {
             fmt::layer()
-                .with_writer(non_blocking)
+                .with_writer(non_blocking.clone())
                 .with_target(true)
                 .with_level(true)
                 .with_thread_ids(true)
@@ -70,7 +159,32 @@ pub fn init_logging(
                 .with_line_number(true)
                 .with_ansi(false)
                 .with_timer(LocalTimeTimer)
-        );
+        }))
+        // JSON: message and event fields at the top level, session fields
+        // under "span"
+        .with(json.then(|| {
+            fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .with_span_list(false)
+                .with_writer(io::stdout)
+                .with_target(true)
+                .with_timer(LocalTimeTimer)
+        }))
+        .with(json.then(|| {
+            fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .with_span_list(false)
+                .with_writer(non_blocking)
+                .with_target(true)
+                .with_thread_ids(true)
+                .with_file(true)
+                .with_line_number(true)
+                .with_timer(LocalTimeTimer)
+        }));
 
     // Initialize with tracing and tracing-log to bridge log:: macros
     tracing::subscriber::set_global_default(subscriber)
@@ -80,7 +194,10 @@ pub fn init_logging(
     tracing_log::LogTracer::init()
         .map_err(|e| format!("Failed to initialize LogTracer: {}", e))?;
 
-    Ok(())
+    Ok(Arc::new(LogFilterHandle {
+        handle: reload_handle,
+        current: Mutex::new(directives),
+    }))
 }
 
 /// Clean up log files older than the specified number of days.
@@ -137,3 +254,21 @@ impl fmt::time::FormatTime for LocalTimeTimer {
         write!(w, "{}", now.format("%Y-%m-%dT%H:%M:%S%.6f"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_filter_directives() {
+        assert_eq!(build_filter_directives("info", &BTreeMap::new()), "info");
+
+        let targets = BTreeMap::from([
+            ("recisdb_proxy::tuner".to_string(), "debug".to_string()),
+            ("recisdb_proxy::server".to_string(), "warn".to_string()),
+        ]);
+        let directives = build_filter_directives("info", &targets);
+        assert_eq!(directives, "info,recisdb_proxy::server=warn,recisdb_proxy::tuner=debug");
+        assert!(EnvFilter::try_new(&directives).is_ok());
+    }
+}
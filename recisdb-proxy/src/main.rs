@@ -18,18 +18,18 @@ use recisdb_proxy::server;
 use recisdb_proxy::tuner;
 use recisdb_proxy::web;
 
-use scheduler::{ScanScheduler, scan_scheduler::ScanSchedulerConfig};
+use scheduler::{RecordingScheduler, RecordingSchedulerConfig, ScanScheduler, scan_scheduler::ScanSchedulerConfig};
 
-use server::{Server, ServerConfig};
+use server::{ListenAddr, Server, ServerConfig};
 use tuner::TunerPoolConfig;
 
 /// recisdb-proxy - Network proxy server for BonDriver
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Address to listen on
+    /// Address to listen on (repeatable; IPv4, IPv6 or unix:/path/to.sock)
     #[arg(short, long, default_value = "0.0.0.0:40070")]
-    listen: SocketAddr,
+    listen: Vec<ListenAddr>,
 
     /// Address for web dashboard to listen on
     #[arg(long, default_value = "0.0.0.0:40080")]
@@ -47,6 +47,18 @@ struct Args {
     #[arg(short = 'c', long, default_value = "64")]
     max_connections: usize,
 
+    /// Drop sessions that send nothing for this many seconds (0 = never)
+    #[arg(long, default_value = "0")]
+    session_idle_timeout: u64,
+
+    /// Maximum concurrent connections from one client IP (0 = unlimited)
+    #[arg(long, default_value = "0")]
+    max_sessions_per_ip: usize,
+
+    /// New connections one client IP may open per minute (0 = unlimited)
+    #[arg(long, default_value = "0")]
+    connection_rate_per_ip: u32,
+
     /// Configuration file path
     #[arg(short = 'f', long)]
     config: Option<PathBuf>,
@@ -71,6 +83,18 @@ struct Args {
     #[arg(long, default_value = "1")]
     max_concurrent_scans: usize,
 
+    /// Start scheduled recordings this many seconds early
+    #[arg(long, default_value = "30")]
+    record_start_margin: u64,
+
+    /// Keep scheduled recordings running this many seconds past the end
+    #[arg(long, default_value = "30")]
+    record_end_margin: u64,
+
+    /// Directory server-side recordings are written under
+    #[arg(long, default_value = "recordings")]
+    recording_dir: PathBuf,
+
     /// Directory where log files are stored
     #[arg(long, default_value = "logs")]
     log_dir: PathBuf,
@@ -109,17 +133,107 @@ struct ConfigFile {
     database: DatabaseSection,
     #[serde(default)]
     logging: LoggingSection,
+    #[serde(default)]
+    web: WebSection,
+    #[serde(default)]
+    alert: AlertSection,
     #[cfg(feature = "tls")]
     #[serde(default)]
     tls: TlsSection,
 }
 
+/// One listen address or a list of them.
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum ListenSetting {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl ListenSetting {
+    fn addrs(&self) -> Vec<&str> {
+        match self {
+            Self::One(addr) => vec![addr.as_str()],
+            Self::Many(addrs) => addrs.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
 #[derive(Debug, serde::Deserialize, Default)]
 struct ServerSection {
-    listen: Option<String>,
+    listen: Option<ListenSetting>,
     web_listen: Option<String>,
     tuner: Option<String>,
     max_connections: Option<usize>,
+    session_idle_timeout_secs: Option<u64>,
+    auth_token: Option<String>,
+    /// Whether Unix-socket clients must present auth_token (default: true).
+    unix_socket_auth: Option<bool>,
+    max_sessions_per_ip: Option<usize>,
+    connection_rate_per_ip: Option<u32>,
+}
+
+#[derive(Debug, serde::Deserialize, Default)]
+struct AlertSection {
+    smtp_host: Option<String>,
+    smtp_port: Option<u16>,
+    smtp_username: Option<String>,
+    smtp_password: Option<String>,
+    smtp_from: Option<String>,
+    smtp_security: Option<String>,
+}
+
+impl AlertSection {
+    /// SMTP settings for email alerts, if a host is configured.
+    fn smtp_config(&self) -> Option<alert::SmtpConfig> {
+        let host = self.smtp_host.clone().filter(|h| !h.is_empty())?;
+        let security = match self.smtp_security.as_deref() {
+            None => alert::SmtpSecurity::default(),
+            Some(value) => alert::SmtpSecurity::parse(value).unwrap_or_else(|| {
+                warn!("Unknown smtp_security '{}', using starttls", value);
+                alert::SmtpSecurity::default()
+            }),
+        };
+        let username = self.smtp_username.clone().filter(|u| !u.is_empty());
+        Some(alert::SmtpConfig {
+            from: self
+                .smtp_from
+                .clone()
+                .or_else(|| username.clone())
+                .unwrap_or_else(|| format!("recisdb-proxy@{}", host)),
+            host,
+            port: self.smtp_port,
+            username,
+            password: self.smtp_password.clone(),
+            security,
+        })
+    }
+}
+
+#[derive(Debug, serde::Deserialize, Default)]
+struct WebSection {
+    auth_token: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    /// Names shown instead of reverse DNS, e.g. `"192.168.1.20" = "Living Room TV"`.
+    #[serde(default)]
+    client_labels: std::collections::HashMap<String, String>,
+}
+
+impl WebSection {
+    /// Client labels keyed by IP; entries with an invalid IP are skipped.
+    fn client_labels(&self) -> std::collections::HashMap<std::net::IpAddr, String> {
+        self.client_labels
+            .iter()
+            .filter_map(|(ip, label)| match ip.parse() {
+                Ok(ip) => Some((ip, label.clone())),
+                Err(_) => {
+                    warn!("Ignoring client label for invalid IP address '{}'", ip);
+                    None
+                }
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, serde::Deserialize, Default)]
@@ -127,6 +241,13 @@ struct LoggingSection {
     log_dir: Option<String>,
     retention_days: Option<u64>,
     level: Option<String>,
+    /// Output format ("text" or "json").
+    #[serde(default)]
+    format: logging::LogFormat,
+    /// Per-target levels, e.g. `"recisdb_proxy::tuner" = "debug"`.
+    #[serde(default)]
+    targets: std::collections::BTreeMap<String, String>,
+    sync_loss_log_interval_secs: Option<u64>,
 }
 
 #[derive(Debug, serde::Deserialize, Default)]
@@ -150,6 +271,42 @@ fn load_config(path: &PathBuf) -> Result<ConfigFile, Box<dyn std::error::Error>>
     Ok(config)
 }
 
+/// Resolve when the process is asked to stop (Ctrl-C, SIGTERM, or the
+/// console window being closed on Windows).
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(e) => {
+                warn!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(windows)]
+    {
+        match tokio::signal::windows::ctrl_close() {
+            Ok(mut close) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = close.recv() => {}
+                }
+            }
+            Err(e) => {
+                warn!("Failed to install console close handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Parse command line arguments
@@ -194,17 +351,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Initialize logging with file output and rotation
     let log_level = file_config.logging.level.as_deref();
-    logging::init_logging(&log_dir, log_retention_days, args.verbose, log_level)
-        .expect("Failed to initialize logging");
+    let log_filter = logging::init_logging(
+        &log_dir,
+        log_retention_days,
+        args.verbose,
+        log_level,
+        &file_config.logging.targets,
+        file_config.logging.format,
+    )
+    .expect("Failed to initialize logging");
+
+    // Throttle interval for TS sync-loss / resync log lines
+    let sync_loss_log_interval_secs = file_config
+        .logging
+        .sync_loss_log_interval_secs
+        .unwrap_or(tuner::ts_analyzer::DEFAULT_SYNC_LOSS_LOG_INTERVAL_SECS);
+    tuner::ts_analyzer::set_sync_loss_log_interval(std::time::Duration::from_secs(
+        sync_loss_log_interval_secs,
+    ));
 
     // Use log macros which are now bridged to tracing
     use log::{error, info};
 
     // Get database path and other settings from config
-    let listen_addr = if let Some(addr_str) = &file_config.server.listen {
-        addr_str.parse::<SocketAddr>().unwrap_or(args.listen)
-    } else {
-        args.listen
+    let listen_addrs = match &file_config.server.listen {
+        Some(setting) => {
+            let addrs: Vec<ListenAddr> = setting
+                .addrs()
+                .into_iter()
+                .filter_map(|addr| {
+                    addr.parse()
+                        .map_err(|e| warn!("Ignoring listen address: {}", e))
+                        .ok()
+                })
+                .collect();
+            if addrs.is_empty() { args.listen.clone() } else { addrs }
+        }
+        None => args.listen.clone(),
     };
     let web_listen_addr = if let Some(addr_str) = &file_config.server.web_listen {
         addr_str.parse::<SocketAddr>().unwrap_or(args.web_listen)
@@ -216,6 +399,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .server
         .max_connections
         .unwrap_or(args.max_connections);
+    let session_idle_timeout_secs = file_config
+        .server
+        .session_idle_timeout_secs
+        .unwrap_or(args.session_idle_timeout);
+    let max_sessions_per_ip = file_config
+        .server
+        .max_sessions_per_ip
+        .unwrap_or(args.max_sessions_per_ip);
+    let connection_rate_per_ip = file_config
+        .server
+        .connection_rate_per_ip
+        .unwrap_or(args.connection_rate_per_ip);
     let db_path = file_config
         .database
         .path
@@ -303,16 +498,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 set_channel_retry_timeout_ms,
                 signal_poll_interval_ms,
                 signal_wait_timeout_ms,
+                driver_affinity_enabled,
+                align_ts_output,
+                descramble_rekey_percent,
+                descramble_rekey_window_secs,
+                stall_timeout_ms,
+                ts_coalesce_bytes,
+                ts_coalesce_flush_ms,
+                prewarm_group,
+                prewarm_on_startup,
+                min_signal_level_terrestrial,
+                min_signal_level_satellite,
             )) => {
                 info!(
-                    "Loaded tuner config from database: keep_alive={}s, prewarm_enabled={}, prewarm_timeout={}s, set_retry_interval={}ms, set_retry_timeout={}ms, signal_poll={}ms, signal_wait_timeout={}ms",
+                    "Loaded tuner config from database: keep_alive={}s, prewarm_enabled={}, prewarm_timeout={}s, set_retry_interval={}ms, set_retry_timeout={}ms, signal_poll={}ms, signal_wait_timeout={}ms, driver_affinity={}, align_ts_output={}, descramble_rekey={}%/{}s, stall_timeout={}ms, ts_coalesce={}B/{}ms, prewarm_group={}, prewarm_on_startup={}, min_signal={}/{}dB",
                     keep_alive_secs,
                     prewarm_enabled,
                     prewarm_timeout_secs,
                     set_channel_retry_interval_ms,
                     set_channel_retry_timeout_ms,
                     signal_poll_interval_ms,
-                    signal_wait_timeout_ms
+                    signal_wait_timeout_ms,
+                    driver_affinity_enabled,
+                    align_ts_output,
+                    descramble_rekey_percent,
+                    descramble_rekey_window_secs,
+                    stall_timeout_ms,
+                    ts_coalesce_bytes,
+                    ts_coalesce_flush_ms,
+                    prewarm_group,
+                    prewarm_on_startup,
+                    min_signal_level_terrestrial,
+                    min_signal_level_satellite
                 );
                 TunerPoolConfig {
                     keep_alive_secs,
@@ -322,6 +539,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     set_channel_retry_timeout_ms,
                     signal_poll_interval_ms,
                     signal_wait_timeout_ms,
+                    driver_affinity_enabled,
+                    align_ts_output,
+                    descramble_rekey_percent,
+                    descramble_rekey_window_secs,
+                    stall_timeout_ms,
+                    ts_coalesce_bytes: ts_coalesce_bytes as usize,
+                    ts_coalesce_flush_ms,
+                    prewarm_group,
+                    prewarm_on_startup,
+                    min_signal_level_terrestrial,
+                    min_signal_level_satellite,
                 }
             }
             Err(e) => {
@@ -331,20 +559,58 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    // Runtime max_connections set via Web API overrides the config file value
+    let max_connections = {
+        let db_lock = db.lock().await;
+        match db_lock.get_server_max_connections() {
+            Ok(Some(value)) => {
+                info!("Loaded max_connections from database: {} (overrides {})", value, max_connections);
+                value
+            }
+            Ok(None) => max_connections,
+            Err(e) => {
+                warn!("Failed to load server config from database: {}", e);
+                max_connections
+            }
+        }
+    };
+
     // Build server config
     let config = ServerConfig {
-        listen_addr,
+        listen_addrs,
         max_connections,
         default_tuner: default_tuner.clone(),
         database: db.clone(),
         tuner_config: tuner_config.clone(),
+        session_idle_timeout_secs,
+        auth_token: file_config.server.auth_token.clone().filter(|t| !t.is_empty()),
+        unix_socket_auth: file_config.server.unix_socket_auth.unwrap_or(true),
+        max_sessions_per_ip,
+        connection_rate_per_ip,
         #[cfg(feature = "tls")]
         tls_config,
     };
 
     info!("recisdb-proxy starting...");
-    info!("  Listen address: {}", config.listen_addr);
+    for addr in &config.listen_addrs {
+        info!("  Listen address: {}", addr);
+    }
     info!("  Max connections: {}", config.max_connections);
+    if config.session_idle_timeout_secs > 0 {
+        info!("  Session idle timeout: {}s", config.session_idle_timeout_secs);
+    }
+    if config.auth_token.is_some() {
+        info!("  Client authentication: enabled");
+        if !config.unix_socket_auth {
+            info!("  Client authentication on Unix sockets: disabled");
+        }
+    }
+    if config.max_sessions_per_ip > 0 {
+        info!("  Max sessions per IP: {}", config.max_sessions_per_ip);
+    }
+    if config.connection_rate_per_ip > 0 {
+        info!("  Connection rate per IP: {}/min", config.connection_rate_per_ip);
+    }
     info!("  Database: {:?}", db_path);
     if let Some(tuner) = &config.default_tuner {
         info!("  Default tuner: {}", tuner);
@@ -373,18 +639,62 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Create session registry for tracking active sessions
-    let session_registry = Arc::new(web::SessionRegistry::new());
+    let session_registry = Arc::new(
+        web::SessionRegistry::new().with_host_labels(file_config.web.client_labels()),
+    );
 
     // Start alert manager
     let alert_db = db.clone();
     let alert_registry = Arc::clone(&session_registry);
+    let smtp_config = file_config.alert.smtp_config();
+    if let Some(smtp) = &smtp_config {
+        info!("  Alert email via {} ({:?})", smtp.host, smtp.security);
+    }
     tokio::spawn(async move {
-        let manager = alert::AlertManager::new(alert_db, alert_registry);
+        let manager = alert::AlertManager::new(alert_db, alert_registry, smtp_config);
         manager.run().await;
     });
 
+    // Persist per-channel usage (bytes served, peak viewers) once a minute
+    let usage_db = db.clone();
+    let usage_registry = Arc::clone(&session_registry);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let usage = usage_registry.channel_usage().take();
+            let now = chrono::Utc::now().timestamp();
+            if let Err(e) = usage_db.lock().await.record_channel_usage(now, &usage) {
+                warn!("Failed to record channel usage: {}", e);
+            }
+        }
+    });
+
     // Create server
     let server = Server::new(config, Arc::clone(&session_registry));
+    server.tuner_pool().start_stall_watchdog(db.clone());
+    if let Err(e) = server.tuner_pool().load_broadcast_settings(&*db.lock().await) {
+        warn!("Failed to load broadcast buffer settings: {}", e);
+    }
+
+    // Open the default tuner now so the first client skips the BonDriver
+    // open delay; the first session tuning it takes it over.
+    if tuner_config.prewarm_on_startup {
+        match &default_tuner {
+            Some(_) if tuner_config.keep_alive_secs == 0 => {
+                warn!("prewarm_on_startup ignored: keep-alive is disabled");
+            }
+            Some(path) => {
+                info!("  Prewarming default tuner: {}", path);
+                server
+                    .tuner_pool()
+                    .set_startup_warm(tuner::WarmTunerHandle::spawn(path.clone(), 0))
+                    .await;
+            }
+            None => warn!("prewarm_on_startup ignored: no default tuner configured"),
+        }
+    }
 
     // Prepare scan configuration to share with web server
     let scan_config_for_web = if args.enable_scan {
@@ -407,20 +717,59 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         set_channel_retry_timeout_ms: tuner_config.set_channel_retry_timeout_ms,
         signal_poll_interval_ms: tuner_config.signal_poll_interval_ms,
         signal_wait_timeout_ms: tuner_config.signal_wait_timeout_ms,
+        driver_affinity_enabled: tuner_config.driver_affinity_enabled,
+        align_ts_output: tuner_config.align_ts_output,
+        descramble_rekey_percent: tuner_config.descramble_rekey_percent,
+        descramble_rekey_window_secs: tuner_config.descramble_rekey_window_secs,
+        stall_timeout_ms: tuner_config.stall_timeout_ms,
+        ts_coalesce_bytes: tuner_config.ts_coalesce_bytes as u64,
+        ts_coalesce_flush_ms: tuner_config.ts_coalesce_flush_ms,
+        prewarm_group: tuner_config.prewarm_group,
+        prewarm_on_startup: tuner_config.prewarm_on_startup,
+        min_signal_level_terrestrial: tuner_config.min_signal_level_terrestrial,
+        min_signal_level_satellite: tuner_config.min_signal_level_satellite,
     });
 
+    // Start recording scheduler
+    let recording_scheduler = Arc::new(RecordingScheduler::new(
+        db.clone(),
+        Arc::clone(server.tuner_pool()),
+        Arc::new(tuner::RecordingRegistry::new(args.recording_dir.clone())),
+        RecordingSchedulerConfig {
+            start_margin_secs: args.record_start_margin,
+            end_margin_secs: args.record_end_margin,
+            ..Default::default()
+        },
+    ));
+    let _recording_scheduler_handle = Arc::clone(&recording_scheduler).start();
+
     // Start web dashboard server
+    let web_auth = web::auth::WebAuthConfig {
+        token: file_config.web.auth_token.clone().filter(|t| !t.is_empty()),
+        basic: match (&file_config.web.username, &file_config.web.password) {
+            (Some(user), Some(pass)) if !user.is_empty() => Some((user.clone(), pass.clone())),
+            _ => None,
+        },
+    };
+    if web_auth.is_enabled() {
+        info!("Web dashboard authentication enabled");
+    }
     let web_db = db.clone();
     let web_tuner_pool = Arc::clone(server.tuner_pool());
     let web_session_registry = Arc::clone(&session_registry);
+    let web_connection_limiter = Arc::clone(server.connection_limiter());
     tokio::spawn(async move {
         match web::start_web_server(
             web_listen_addr,
             web_db,
             web_tuner_pool,
             web_session_registry,
+            web_connection_limiter,
+            recording_scheduler,
             scan_config_for_web,
             tuner_config_for_web,
+            web_auth,
+            Some(log_filter),
         ).await {
             Ok(_) => info!("Web dashboard server stopped"),
             Err(e) => error!("Web dashboard error: {}", e),
@@ -461,11 +810,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             ts_read_timeout_ms: db_ts_read_timeout_ms,
         };
 
-        let scheduler = Arc::new(ScanScheduler::new(
-            db.clone(),
-            Arc::clone(server.tuner_pool()),
-            scan_config,
-        ));
+        let scheduler = Arc::new(
+            ScanScheduler::new(db.clone(), Arc::clone(server.tuner_pool()), scan_config)
+                .with_events(Arc::clone(&session_registry)),
+        );
 
         info!("Starting channel scan scheduler (interval: {}s, max concurrent: {})", 
               db_check_interval, db_max_concurrent);
@@ -485,8 +833,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    // Run server
-    server.run().await?;
+    // Run server until a shutdown signal arrives
+    tokio::select! {
+        result = server.run() => result?,
+        _ = shutdown_signal() => {
+            info!("Shutdown signal received");
+            server.shutdown().await;
+        }
+    }
 
     Ok(())
 }
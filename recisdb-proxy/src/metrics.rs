@@ -5,11 +5,12 @@
 //! - Error occurrence and recovery
 //! - Tuner switching and allocation statistics
 //! - Signal quality metrics
+//! - Per-channel usage (bytes served, peak concurrent viewers)
 
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 use log::info;
 
@@ -295,6 +296,108 @@ impl Default for SystemMetrics {
     }
 }
 
+/// Usage of one channel accumulated since the last [`ChannelUsageTracker::take`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelUsage {
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    /// TS bytes forwarded to clients watching the channel.
+    pub bytes: u64,
+    /// TS packets forwarded to clients watching the channel.
+    pub packets: u64,
+    /// Highest number of sessions tuned to the channel at the same time.
+    pub peak_viewers: u32,
+}
+
+#[derive(Debug, Default)]
+struct ChannelCounter {
+    bytes: u64,
+    viewers: u32,
+    peak_viewers: u32,
+}
+
+/// Rolling per-channel counters keyed by (NID, TSID, SID).
+///
+/// Sessions report their channel and forwarded bytes through the session
+/// registry; a background task periodically drains the counters into the
+/// `channel_usage_stats` table.
+#[derive(Debug, Default)]
+pub struct ChannelUsageTracker {
+    channels: std::sync::Mutex<HashMap<(u16, u16, u16), ChannelCounter>>,
+}
+
+impl ChannelUsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record TS bytes forwarded to a viewer of `key`.
+    pub fn record_bytes(&self, key: (u16, u16, u16), bytes: u64) {
+        if bytes == 0 {
+            return;
+        }
+        if let Ok(mut channels) = self.channels.lock() {
+            channels.entry(key).or_default().bytes += bytes;
+        }
+    }
+
+    /// A session tuned to `key`.
+    pub fn viewer_joined(&self, key: (u16, u16, u16)) {
+        if let Ok(mut channels) = self.channels.lock() {
+            let counter = channels.entry(key).or_default();
+            counter.viewers += 1;
+            counter.peak_viewers = counter.peak_viewers.max(counter.viewers);
+        }
+    }
+
+    /// A session tuned away from `key` or disconnected.
+    pub fn viewer_left(&self, key: (u16, u16, u16)) {
+        if let Ok(mut channels) = self.channels.lock() {
+            if let Some(counter) = channels.get_mut(&key) {
+                counter.viewers = counter.viewers.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Current number of sessions tuned to `key`.
+    pub fn viewers(&self, key: (u16, u16, u16)) -> u32 {
+        self.channels
+            .lock()
+            .ok()
+            .and_then(|channels| channels.get(&key).map(|c| c.viewers))
+            .unwrap_or(0)
+    }
+
+    /// Drain the usage accumulated since the previous call.
+    ///
+    /// Counters restart from zero bytes with the current viewer count as
+    /// the new peak; channels nobody is watching are forgotten.
+    pub fn take(&self) -> Vec<ChannelUsage> {
+        let Ok(mut channels) = self.channels.lock() else {
+            return Vec::new();
+        };
+        let usage = channels
+            .iter()
+            .filter(|(_, c)| c.bytes > 0 || c.peak_viewers > 0)
+            .map(|(&(nid, tsid, sid), c)| ChannelUsage {
+                nid,
+                tsid,
+                sid,
+                bytes: c.bytes,
+                packets: c.bytes / 188,
+                peak_viewers: c.peak_viewers,
+            })
+            .collect();
+        channels.retain(|_, c| c.viewers > 0);
+        for counter in channels.values_mut() {
+            counter.bytes = 0;
+            counter.peak_viewers = counter.viewers;
+        }
+        usage
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -339,5 +442,34 @@ mod tests {
         metrics.add_bytes_transferred(5000);
         assert_eq!(metrics.total_bytes_transferred(), 5000);
     }
-}
 
+    #[test]
+    fn test_channel_usage_tracker() {
+        let tracker = ChannelUsageTracker::new();
+        let nhk = (0x7FE0, 0x7FE0, 0x0400);
+        let bs = (4, 16625, 101);
+
+        tracker.viewer_joined(nhk);
+        tracker.viewer_joined(nhk);
+        tracker.viewer_joined(bs);
+        tracker.viewer_left(nhk);
+        tracker.record_bytes(nhk, 188 * 100);
+        tracker.record_bytes(bs, 188 * 10);
+        tracker.viewer_left(bs);
+        assert_eq!(tracker.viewers(nhk), 1);
+
+        let mut usage = tracker.take();
+        usage.sort_by_key(|u| u.bytes);
+        assert_eq!(usage.len(), 2);
+        assert_eq!((usage[1].bytes, usage[1].packets, usage[1].peak_viewers), (188 * 100, 100, 2));
+        assert_eq!((usage[0].bytes, usage[0].peak_viewers), (188 * 10, 1));
+
+        // The next window starts from the viewers still watching
+        let usage = tracker.take();
+        assert_eq!(usage.len(), 1);
+        assert_eq!((usage[0].sid, usage[0].bytes, usage[0].peak_viewers), (0x0400, 0, 1));
+        tracker.viewer_left(nhk);
+        tracker.take();
+        assert!(tracker.take().is_empty());
+    }
+}
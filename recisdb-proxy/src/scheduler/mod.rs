@@ -2,7 +2,13 @@
 //!
 //! This module provides:
 //! - [`ScanScheduler`]: Periodic channel scanning scheduler
+//! - [`RecordingScheduler`]: EPG-driven recording reservations
+//! - [`ScanScope`]: Per-driver band/channel restriction for scans
 
+pub mod recording_scheduler;
 pub mod scan_scheduler;
+pub mod scan_scope;
 
+pub use recording_scheduler::{RecordingScheduler, RecordingSchedulerConfig};
 pub use scan_scheduler::ScanScheduler;
+pub use scan_scope::ScanScope;
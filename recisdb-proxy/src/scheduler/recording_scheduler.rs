@@ -0,0 +1,497 @@
+//! EPG-driven recording scheduler.
+//!
+//! Reservations are stored in the `recordings` table and recorded with the
+//! server-side record sink ([`RecordingRegistry`]).
+//!
+//! # How It Works
+//!
+//! 1. A reservation names a service and either an EPG event or a fixed time
+//!    range. Event-based reservations follow start/end changes in the EPG
+//!    until they start recording.
+//! 2. On every check, the open reservations are placed on the BonDrivers that
+//!    carry their service, highest channel priority first. Reservations on
+//!    the same channel share a tuner; the rest are limited by each driver's
+//!    `max_instances`. Those that do not fit are marked `conflict`.
+//! 3. Once a reservation is within `start_margin_secs` of its start time, its
+//!    tuner is opened and recorded until `end_margin_secs` after its end.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{debug, error, info, warn};
+use tokio::time::interval;
+
+use crate::database::{
+    BonDriverRecord, ClientChannelRecord, Database, RecordingStatus, ReservationRecord, Result,
+};
+use crate::server::listener::DatabaseHandle;
+use crate::tuner::{ChannelKey, RecordingId, RecordingRegistry, RecordingRequest, TunerPool};
+use crate::web::stream::{open_first_available, service_candidates, StreamCandidate};
+
+/// Recording scheduler configuration.
+#[derive(Debug, Clone)]
+pub struct RecordingSchedulerConfig {
+    /// Interval between scheduler checks (seconds).
+    pub check_interval_secs: u64,
+    /// Start recording this long before the reservation's start time (seconds).
+    pub start_margin_secs: u64,
+    /// Keep recording this long after the reservation's end time (seconds).
+    pub end_margin_secs: u64,
+}
+
+impl Default for RecordingSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_secs: 5,
+            start_margin_secs: 30,
+            end_margin_secs: 30,
+        }
+    }
+}
+
+/// A reservation's claim on the tuners, for conflict planning.
+#[derive(Debug, Clone)]
+struct PlanEntry {
+    id: i64,
+    /// Padded start time.
+    start: i64,
+    /// Padded end time.
+    end: i64,
+    /// Channel priority of the service.
+    priority: i32,
+    candidates: Vec<StreamCandidate>,
+    /// Already recording; placed before everything else and never a conflict.
+    recording: bool,
+}
+
+/// IDs of the reservations that get no tuner.
+///
+/// Reservations are placed in priority order (recording ones first, then by
+/// channel priority and start time). A driver can take a reservation while
+/// the distinct channels of the overlapping reservations already on it stay
+/// within its capacity.
+fn plan_conflicts(entries: &[PlanEntry], capacity: &HashMap<String, usize>) -> HashSet<i64> {
+    let mut order: Vec<&PlanEntry> = entries.iter().collect();
+    order.sort_by(|a, b| {
+        b.recording
+            .cmp(&a.recording)
+            .then(b.priority.cmp(&a.priority))
+            .then(a.start.cmp(&b.start))
+            .then(a.id.cmp(&b.id))
+    });
+
+    let mut placed: Vec<(&PlanEntry, &StreamCandidate)> = Vec::new();
+    let mut conflicts = HashSet::new();
+    for entry in order {
+        let fits = |candidate: &StreamCandidate| {
+            let mut channels: HashSet<(u32, u32)> = placed
+                .iter()
+                .filter(|(other, c)| {
+                    c.dll_path == candidate.dll_path && other.start < entry.end && entry.start < other.end
+                })
+                .map(|(_, c)| (c.space, c.channel))
+                .collect();
+            channels.insert((candidate.space, candidate.channel));
+            channels.len() <= capacity.get(&candidate.dll_path).copied().unwrap_or(1)
+        };
+        match entry.candidates.iter().find(|c| fits(c)) {
+            Some(candidate) => placed.push((entry, candidate)),
+            // A service without candidates fails at start time instead
+            None if !entry.recording && !entry.candidates.is_empty() => {
+                conflicts.insert(entry.id);
+            }
+            None => {}
+        }
+    }
+    conflicts
+}
+
+/// Highest priority among the enabled channel rows of a service.
+fn channel_priority(
+    rows: &[(ClientChannelRecord, Option<BonDriverRecord>)],
+    reservation: &ReservationRecord,
+) -> i32 {
+    rows.iter()
+        .filter(|(ch, _)| {
+            ch.is_enabled
+                && ch.nid as u16 == reservation.nid
+                && ch.tsid as u16 == reservation.tsid
+                && ch.sid as u16 == reservation.sid
+        })
+        .map(|(ch, _)| ch.priority)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Move an event-based reservation to its current EPG time.
+fn follow_epg(db: &Database, reservation: &mut ReservationRecord) -> Result<()> {
+    let Some(event_id) = reservation.event_id else { return Ok(()) };
+    let Some(event) = db.get_epg_event(reservation.nid, reservation.sid, event_id)? else {
+        return Ok(());
+    };
+    let (Some(start), Some(duration)) = (event.start_time, event.duration_secs) else {
+        return Ok(());
+    };
+    if (start, start + duration) == (reservation.start_time, reservation.end_time) {
+        return Ok(());
+    }
+    info!(
+        "RecordingScheduler: Reservation {} follows EPG event {}: {}-{} -> {}-{}",
+        reservation.id, event_id, reservation.start_time, reservation.end_time, start, start + duration
+    );
+    db.update_reservation_times(reservation.id, start, start + duration)?;
+    reservation.start_time = start;
+    reservation.end_time = start + duration;
+    Ok(())
+}
+
+/// EPG-driven recording scheduler.
+pub struct RecordingScheduler {
+    /// Database handle.
+    database: DatabaseHandle,
+    /// Tuner pool reference.
+    tuner_pool: Arc<TunerPool>,
+    /// Recordings in progress (shared with ad-hoc recordings).
+    recordings: Arc<RecordingRegistry>,
+    /// Configuration.
+    config: RecordingSchedulerConfig,
+    /// Reservation ID -> recording in progress.
+    active: Mutex<HashMap<i64, RecordingId>>,
+}
+
+impl RecordingScheduler {
+    /// Create a new recording scheduler.
+    pub fn new(
+        database: DatabaseHandle,
+        tuner_pool: Arc<TunerPool>,
+        recordings: Arc<RecordingRegistry>,
+        config: RecordingSchedulerConfig,
+    ) -> Self {
+        Self {
+            database,
+            tuner_pool,
+            recordings,
+            config,
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registry of recordings in progress.
+    pub fn recordings(&self) -> &Arc<RecordingRegistry> {
+        &self.recordings
+    }
+
+    /// Scheduler configuration.
+    pub fn config(&self) -> &RecordingSchedulerConfig {
+        &self.config
+    }
+
+    /// Whether the reservation is being recorded right now.
+    pub fn is_recording(&self, reservation_id: i64) -> bool {
+        self.active.lock().unwrap().contains_key(&reservation_id)
+    }
+
+    /// Stop the recording of a reservation, if it is being recorded.
+    pub fn cancel_reservation(&self, reservation_id: i64) -> bool {
+        let recording_id = self.active.lock().unwrap().get(&reservation_id).copied();
+        recording_id.is_some_and(|id| self.recordings.cancel(id))
+    }
+
+    /// Start the scheduler background task.
+    pub fn start(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            self.run().await;
+        })
+    }
+
+    /// Run the scheduler loop.
+    async fn run(self: Arc<Self>) {
+        info!(
+            "RecordingScheduler: Starting (check interval {}s, margins -{}s/+{}s)",
+            self.config.check_interval_secs, self.config.start_margin_secs, self.config.end_margin_secs
+        );
+        match self.database.lock().await.fail_interrupted_reservations() {
+            Ok(0) => {}
+            Ok(n) => warn!("RecordingScheduler: {} recordings were interrupted by a restart", n),
+            Err(e) => error!("RecordingScheduler: Failed to reset interrupted recordings: {}", e),
+        }
+
+        let mut check_interval = interval(Duration::from_secs(self.config.check_interval_secs.max(1)));
+        loop {
+            check_interval.tick().await;
+            if let Err(e) = self.check_reservations().await {
+                error!("RecordingScheduler: Error during reservation check: {}", e);
+            }
+        }
+    }
+
+    /// Update reservation statuses and start the recordings that are due.
+    async fn check_reservations(self: &Arc<Self>) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let start_margin = self.config.start_margin_secs as i64;
+        let end_margin = self.config.end_margin_secs as i64;
+
+        let (reservations, rows, capacity, quality_scores) = {
+            let db = self.database.lock().await;
+            let mut reservations = db.get_open_reservations()?;
+            for reservation in reservations
+                .iter_mut()
+                .filter(|r| r.status != RecordingStatus::Recording)
+            {
+                follow_epg(&db, reservation)?;
+            }
+            let rows = db.get_all_channels_with_drivers()?;
+            let capacity: HashMap<String, usize> = rows
+                .iter()
+                .filter_map(|(_, driver)| driver.as_ref())
+                .map(|d| (d.dll_path.clone(), d.max_instances.max(1) as usize))
+                .collect();
+            let quality_scores: HashMap<String, f64> = db
+                .get_bondrivers_ranking()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(driver, score, _, _, _)| (driver.dll_path, score))
+                .collect();
+            (reservations, rows, capacity, quality_scores)
+        };
+
+        let mut entries: Vec<PlanEntry> = reservations
+            .iter()
+            .filter(|r| r.status == RecordingStatus::Recording || r.end_time + end_margin > now)
+            .map(|r| PlanEntry {
+                id: r.id,
+                start: r.start_time - start_margin,
+                end: r.end_time + end_margin,
+                priority: channel_priority(&rows, r),
+                candidates: service_candidates(&rows, &quality_scores, r.nid, Some(r.tsid), r.sid),
+                recording: r.status == RecordingStatus::Recording,
+            })
+            .collect();
+        let conflicts = plan_conflicts(&entries, &capacity);
+        // Start due reservations in priority order
+        entries.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.start.cmp(&b.start)));
+
+        for reservation in &reservations {
+            if reservation.status == RecordingStatus::Recording {
+                continue;
+            }
+            if reservation.end_time + end_margin <= now {
+                // A conflict that was never resolved stays a conflict
+                if reservation.status == RecordingStatus::Pending {
+                    let error = reservation.error.as_deref().unwrap_or("Start time passed without recording");
+                    warn!("RecordingScheduler: Reservation {} missed: {}", reservation.id, error);
+                    self.set_status(reservation.id, RecordingStatus::Failed, Some(error)).await;
+                }
+                continue;
+            }
+            if conflicts.contains(&reservation.id) {
+                if reservation.status != RecordingStatus::Conflict {
+                    warn!("RecordingScheduler: Reservation {} conflicts with higher-priority reservations", reservation.id);
+                    self.set_status(reservation.id, RecordingStatus::Conflict, Some("No tuner left for this time slot"))
+                        .await;
+                }
+            } else if reservation.status == RecordingStatus::Conflict {
+                info!("RecordingScheduler: Reservation {} no longer conflicts", reservation.id);
+                self.set_status(reservation.id, RecordingStatus::Pending, None).await;
+            }
+        }
+
+        for entry in &entries {
+            if entry.recording || conflicts.contains(&entry.id) || entry.start > now || entry.end <= now {
+                continue;
+            }
+            if let Some(reservation) = reservations.iter().find(|r| r.id == entry.id) {
+                self.start_recording(reservation, &entry.candidates, &capacity, now).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Candidates whose driver can take another recording right now.
+    async fn available_candidates(
+        &self,
+        candidates: &[StreamCandidate],
+        capacity: &HashMap<String, usize>,
+    ) -> Vec<StreamCandidate> {
+        let keys = self.tuner_pool.keys().await;
+        let mut available = Vec::new();
+        for candidate in candidates {
+            let key = ChannelKey::space_channel(&candidate.dll_path, candidate.space, candidate.channel);
+            // Joining a running tuner takes no extra instance
+            if self.tuner_pool.get(&key).await.is_some_and(|t| t.is_running()) {
+                available.push(candidate.clone());
+                continue;
+            }
+            let mut running = 0;
+            for k in keys.iter().filter(|k| k.tuner_path == candidate.dll_path) {
+                if self.tuner_pool.get(k).await.is_some_and(|t| t.is_running()) {
+                    running += 1;
+                }
+            }
+            if running < capacity.get(&candidate.dll_path).copied().unwrap_or(1) {
+                available.push(candidate.clone());
+            }
+        }
+        available
+    }
+
+    /// Open a tuner for a due reservation and start recording it.
+    async fn start_recording(
+        self: &Arc<Self>,
+        reservation: &ReservationRecord,
+        candidates: &[StreamCandidate],
+        capacity: &HashMap<String, usize>,
+        now: i64,
+    ) {
+        let id = reservation.id;
+        if candidates.is_empty() {
+            error!("RecordingScheduler: Reservation {}: service not found in the channel list", id);
+            self.set_status(id, RecordingStatus::Failed, Some("Channel not found")).await;
+            return;
+        }
+        // Confined again at start: rows stored by older versions were never checked
+        let output_path = match self.recordings.output_path(&reservation.output_path) {
+            Ok(output_path) => output_path,
+            Err(e) => {
+                error!("RecordingScheduler: Reservation {}: {}", id, e);
+                self.set_status(id, RecordingStatus::Failed, Some(&e)).await;
+                return;
+            }
+        };
+
+        let label = format!("reservation {}", id);
+        let available = self.available_candidates(candidates, capacity).await;
+        let tuner = match open_first_available(&self.tuner_pool, &available, &label).await {
+            Some(tuner) => tuner,
+            None => {
+                // Retried on the next check until the end time passes
+                debug!("RecordingScheduler: No tuner available for reservation {} yet", id);
+                if reservation.error.as_deref() != Some("No tuner available") {
+                    self.set_status(id, RecordingStatus::Pending, Some("No tuner available")).await;
+                }
+                return;
+            }
+        };
+
+        let end = reservation.end_time + self.config.end_margin_secs as i64;
+        let request = RecordingRequest {
+            nid: reservation.nid,
+            tsid: reservation.tsid,
+            sid: reservation.sid,
+            duration: Duration::from_secs((end - now).max(1) as u64),
+            output_path,
+            service_only: true,
+            segment: None,
+            lossless: false,
+        };
+        let (info, finished) = match self.recordings.start_watched(&self.tuner_pool, Arc::clone(&tuner), request).await {
+            Ok(started) => started,
+            Err(e) => {
+                error!("RecordingScheduler: Reservation {} failed to start: {}", id, e);
+                if !tuner.has_subscribers() {
+                    self.tuner_pool.schedule_idle_close(tuner.key.clone(), tuner).await;
+                }
+                self.set_status(id, RecordingStatus::Failed, Some(&e.to_string())).await;
+                return;
+            }
+        };
+
+        info!(
+            "RecordingScheduler: Reservation {} ({}) recording as {} until {}",
+            id,
+            reservation.title.as_deref().unwrap_or("-"),
+            info.id,
+            end
+        );
+        self.active.lock().unwrap().insert(id, info.id);
+        self.set_status(id, RecordingStatus::Recording, None).await;
+
+        let scheduler = Arc::clone(self);
+        tokio::spawn(async move {
+            let result = finished.await;
+            scheduler.active.lock().unwrap().remove(&id);
+            let (status, error) = match result {
+                Ok(status) if status.error.is_some() => (RecordingStatus::Failed, status.error),
+                Ok(status) if status.bytes_written == 0 => {
+                    (RecordingStatus::Failed, Some("No data received".to_string()))
+                }
+                Ok(_) => (RecordingStatus::Done, None),
+                Err(_) => (RecordingStatus::Failed, Some("Recording ended unexpectedly".to_string())),
+            };
+            info!("RecordingScheduler: Reservation {} {}", id, status.as_str());
+            scheduler.set_status(id, status, error.as_deref()).await;
+        });
+    }
+
+    async fn set_status(&self, id: i64, status: RecordingStatus, error: Option<&str>) {
+        let db = self.database.lock().await;
+        if let Err(e) = db.set_reservation_status(id, status, error) {
+            error!("RecordingScheduler: Failed to update reservation {}: {}", id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(dll_path: &str, channel: u32) -> StreamCandidate {
+        StreamCandidate {
+            dll_path: dll_path.to_string(),
+            space: 0,
+            channel,
+            max_instances: 1,
+        }
+    }
+
+    fn entry(id: i64, start: i64, end: i64, priority: i32, candidates: Vec<StreamCandidate>) -> PlanEntry {
+        PlanEntry {
+            id,
+            start,
+            end,
+            priority,
+            candidates,
+            recording: false,
+        }
+    }
+
+    #[test]
+    fn test_plan_conflicts_by_priority() {
+        let capacity: HashMap<String, usize> = [("T1.dll".to_string(), 1)].into_iter().collect();
+        let entries = vec![
+            entry(1, 0, 100, 0, vec![candidate("T1.dll", 13)]),
+            entry(2, 50, 150, 10, vec![candidate("T1.dll", 14)]),
+            // Same channel as 2: shares its tuner
+            entry(3, 60, 120, 0, vec![candidate("T1.dll", 14)]),
+            // After 2 ends
+            entry(4, 150, 200, 0, vec![candidate("T1.dll", 13)]),
+        ];
+        assert_eq!(plan_conflicts(&entries, &capacity), HashSet::from([1]));
+    }
+
+    #[test]
+    fn test_plan_conflicts_uses_capacity_and_fallback() {
+        let capacity: HashMap<String, usize> =
+            [("T1.dll".to_string(), 2), ("T2.dll".to_string(), 1)].into_iter().collect();
+        let both = |ch| vec![candidate("T1.dll", ch), candidate("T2.dll", ch)];
+        let entries = vec![
+            entry(1, 0, 100, 0, both(13)),
+            entry(2, 0, 100, 0, both(14)),
+            entry(3, 0, 100, 0, both(15)),
+            entry(4, 0, 100, 0, both(16)),
+            // Unknown service: not a conflict, fails when due
+            entry(5, 0, 100, 0, Vec::new()),
+        ];
+        assert_eq!(plan_conflicts(&entries, &capacity), HashSet::from([4]));
+    }
+
+    #[test]
+    fn test_plan_conflicts_keeps_running_recordings() {
+        let capacity: HashMap<String, usize> = [("T1.dll".to_string(), 1)].into_iter().collect();
+        let mut running = entry(1, 0, 100, 0, vec![candidate("T1.dll", 13)]);
+        running.recording = true;
+        let entries = vec![running, entry(2, 50, 150, 10, vec![candidate("T1.dll", 14)])];
+        assert_eq!(plan_conflicts(&entries, &capacity), HashSet::from([2]));
+    }
+}
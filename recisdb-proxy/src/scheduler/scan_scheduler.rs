@@ -15,20 +15,31 @@
 //! Each BonDriver can be configured with:
 //! - `auto_scan_enabled`: Whether automatic scanning is enabled
 //! - `scan_interval_hours`: How often to scan (0 = disabled)
+//! - `scan_cron`: Cron schedule in local time (e.g. `0 4 * * *`); takes
+//!   precedence over the interval when set
 //! - `scan_priority`: Priority order for scanning
+//! - `scan_scope`: Restrict scans to one band or a list of physical
+//!   channels (see [`ScanScope`])
+//! - `reception_min_signal` / `auto_disable_no_reception`: Signal level a
+//!   channel must lock at to count as received, and whether channels that
+//!   fail are disabled
 
 use std::sync::Arc;
 use std::time::Duration;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 
 use log::{debug, error, info, warn};
 use tokio::sync::Mutex;
 use tokio::time::interval;
 
 use crate::bondriver::BonDriverTuner;
-use crate::database::BonDriverRecord;
+use crate::database::{BonDriverRecord, ReceptionProbe};
+use crate::scheduler::ScanScope;
+use crate::tuner::bs_cs_mapping::{SatelliteChannelMap, SatelliteTs};
 use crate::server::listener::DatabaseHandle;
 use crate::tuner::TunerPool;
+use crate::web::{SessionEvent, SessionRegistry};
 use recisdb_protocol::BandType;
 
 /// Scan scheduler configuration.
@@ -81,6 +92,8 @@ pub struct ScanScheduler {
     state: Arc<Mutex<SchedulerState>>,
     /// Number of active scans.
     active_scans: Arc<std::sync::atomic::AtomicUsize>,
+    /// Where per-channel scan progress is published, if anywhere.
+    events: Option<Arc<SessionRegistry>>,
 }
 
 impl ScanScheduler {
@@ -96,9 +109,16 @@ impl ScanScheduler {
             config,
             state: Arc::new(Mutex::new(SchedulerState::Running)),
             active_scans: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            events: None,
         }
     }
 
+    /// Publish scan progress as [`SessionEvent`]s on `registry`.
+    pub fn with_events(mut self, registry: Arc<SessionRegistry>) -> Self {
+        self.events = Some(registry);
+        self
+    }
+
     /// Start the scheduler background task.
     pub fn start(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
@@ -181,6 +201,7 @@ impl ScanScheduler {
         let database = self.database.clone();
         let tuner_pool = self.tuner_pool.clone();
         let active_scans = self.active_scans.clone();
+        let events = self.events.clone();
 
         // Read timing config fresh from DB each time so that changes made
         // through the web dashboard take effect without restarting the process.
@@ -211,12 +232,27 @@ impl ScanScheduler {
                     &driver,
                     database.clone(),
                     tuner_pool,
+                    events.clone(),
                     signal_lock_wait_ms,
                     ts_read_timeout_ms,
                 ),
             )
             .await;
 
+            if let Some(events) = &events {
+                let (success, channels_found, error) = match &scan_result {
+                    Ok(Ok(count)) => (true, *count, None),
+                    Ok(Err(e)) => (false, 0, Some(e.to_string())),
+                    Err(_) => (false, 0, Some("Scan timed out".to_string())),
+                };
+                events.publish(SessionEvent::ScanFinished {
+                    bondriver_id: driver.id,
+                    success,
+                    channels_found,
+                    error,
+                });
+            }
+
             match scan_result {
                 Ok(Ok(channel_count)) => {
                     info!(
@@ -225,10 +261,16 @@ impl ScanScheduler {
                     );
 
                     // Update next scan time
-                    let next_scan = chrono::Utc::now().timestamp()
-                        + (driver.scan_interval_hours as i64 * 3600);
-
                     let db = database.lock().await;
+                    let scan_cron = db.get_scan_cron(driver.id).unwrap_or_else(|e| {
+                        warn!("ScanScheduler: Failed to load scan_cron for {}: {}", driver.dll_path, e);
+                        None
+                    });
+                    let next_scan = next_scan_time(
+                        scan_cron.as_deref(),
+                        driver.scan_interval_hours,
+                        chrono::Local::now(),
+                    );
                     if let Err(e) = db.update_next_scan(driver.id, next_scan) {
                         warn!("ScanScheduler: Failed to update next scan time: {}", e);
                     }
@@ -316,6 +358,7 @@ impl ScanScheduler {
 }
 
 /// Minimum signal level to consider a channel as having signal.
+/// Also the reception check threshold of drivers that set none.
 const MIN_SIGNAL_LEVEL: f32 = 3.0;
 
 /// TS パケット長
@@ -364,6 +407,19 @@ struct ServiceInfo {
     service_name: Option<String>,
     /// Service type (0x01=TV, etc.)
     service_type: Option<u8>,
+    /// 1seg (partial reception) service
+    is_one_seg: bool,
+    /// CDT logo ID (from the SDT logo transmission descriptor)
+    logo_id: Option<u16>,
+    /// TS name (from the NIT TS information descriptor)
+    ts_name: Option<String>,
+    /// Network name (from the NIT network name descriptor)
+    network_name: Option<String>,
+    /// Remote control key ID (from the NIT TS information descriptor or a
+    /// remote control key descriptor in the NIT/SDT)
+    remote_control_key: Option<u8>,
+    /// Transponder frequency in kHz (from the NIT satellite delivery descriptor)
+    satellite_frequency: Option<u32>,
 }
 
 use crate::ts_analyzer::{TsAnalyzer, AnalyzerConfig};
@@ -425,12 +481,20 @@ fn enumerate_spaces_and_channels_blocking(
 
 /// Scan channels in a space by enumerating BonDriver's channel list.
 /// This runs in a blocking thread to avoid Send/Sync issues with raw pointers.
+///
+/// When `events` is set, a [`SessionEvent::ScanProgress`] is published for
+/// every channel once it has been probed, locked or not. The signal measured
+/// on each tuned channel is appended to `probes`.
+#[allow(clippy::too_many_arguments)]
 fn scan_space_blocking(
     dll_path: &str,
+    bondriver_id: i64,
     space: u32,
     channels: &[(u32, String)],
+    events: Option<&SessionRegistry>,
     signal_lock_wait_ms: u64,
     ts_read_timeout_ms: u64,
+    probes: &mut Vec<ReceptionProbe>,
 ) -> Result<Vec<ScanChannelResult>, Box<dyn std::error::Error + Send + Sync>> {
     info!("scan_space_blocking: Loading BonDriver {}", dll_path);
     let tuner = BonDriverTuner::new(dll_path)?;
@@ -452,7 +516,24 @@ fn scan_space_blocking(
     // the satellite link is fully unavailable and skip remaining channels.
     const MAX_CONSECUTIVE_EAGAIN: u32 = 3;
 
-    for (channel, channel_name) in channels {
+    let total = channels.len();
+    let report = |index: usize, channel: u32, channel_name: &str, signal_level: f32, services_found: Option<usize>| {
+        if let Some(events) = events {
+            events.publish(SessionEvent::ScanProgress {
+                bondriver_id,
+                space,
+                channel,
+                channel_name: channel_name.to_string(),
+                index: index + 1,
+                total,
+                locked: services_found.is_some(),
+                signal_level,
+                services_found: services_found.unwrap_or(0),
+            });
+        }
+    };
+
+    for (index, (channel, channel_name)) in channels.iter().enumerate() {
         let channel = *channel;
 
         debug!("scan_space_blocking: Trying space={}, channel={} ({})", space, channel, channel_name);
@@ -463,6 +544,7 @@ fn scan_space_blocking(
                 "scan_space_blocking: SetChannel(space={}, ch={} \"{}\") failed: {} (os error: {:?})",
                 space, channel, channel_name, e, e.raw_os_error()
             );
+            report(index, channel, channel_name, 0.0, None);
 
             // EINVAL (InvalidInput) means the tuner hardware does not support this
             // band at all (e.g. a satellite-only tuner receiving a terrestrial request).
@@ -482,6 +564,7 @@ fn scan_space_blocking(
             // If it occurs consecutively, all channels in this space will fail
             // the same way — skip the rest to avoid 5s × N channel delays.
             if e.kind() == std::io::ErrorKind::WouldBlock {
+                probes.push(ReceptionProbe { space, channel, signal_level: 0.0, locked: false });
                 consecutive_eagain += 1;
                 if consecutive_eagain >= MAX_CONSECUTIVE_EAGAIN {
                     warn!(
@@ -533,6 +616,8 @@ fn scan_space_blocking(
 
         if signal_level < MIN_SIGNAL_LEVEL {
             debug!("scan_space_blocking: Signal too weak ({:.2} < {:.2})", signal_level, MIN_SIGNAL_LEVEL);
+            report(index, channel, channel_name, signal_level, None);
+            probes.push(ReceptionProbe { space, channel, signal_level, locked: false });
             continue;
         }
 
@@ -621,6 +706,14 @@ fn scan_space_blocking(
             }
         };
 
+        report(index, channel, channel_name, signal_level, Some(services.len()));
+        probes.push(ReceptionProbe {
+            space,
+            channel,
+            signal_level,
+            locked: transport_stream_id.is_some(),
+        });
+
         results.push(ScanChannelResult {
             space,
             channel,
@@ -646,6 +739,8 @@ fn analyze_ts_stream(
         parse_nit: true,
         parse_sdt: true,
         parse_all_pmts: false,
+        parse_eit: false,
+        measure_bitrate: false,
         max_packets: 200_000,
     };
 
@@ -750,6 +845,13 @@ fn analyze_ts_stream(
     );
 
     // services 抽出（元コード踏襲）
+    let logo_ids = result.service_logo_ids();
+    let nit_ts = result
+        .transport_stream_id
+        .and_then(|tsid| result.nit.as_ref()?.find_transport_stream(tsid));
+    let ts_name = nit_ts.and_then(|ts| ts.ts_name()).map(|s| s.to_string());
+    let satellite_frequency = nit_ts.and_then(|ts| ts.satellite_frequency());
+    let network_name = result.nit.as_ref().and_then(|nit| nit.network_name.clone());
     let services: Vec<ServiceInfo> = if let Some(ref pat) = result.pat {
         pat.get_all_program_numbers()
             .into_iter()
@@ -765,8 +867,20 @@ fn analyze_ts_stream(
                         (name, stype)
                     })
                     .unwrap_or((None, None));
-
-                ServiceInfo { service_id: sid, service_name, service_type }
+                // Services missing from the SDT still have a type in the NIT service list
+                let service_type = service_type.or_else(|| nit_ts.and_then(|ts| ts.service_type(sid)));
+
+                ServiceInfo {
+                    service_id: sid,
+                    service_name,
+                    service_type,
+                    is_one_seg: result.is_one_seg_service(sid),
+                    logo_id: logo_ids.get(&sid).copied(),
+                    ts_name: ts_name.clone(),
+                    network_name: network_name.clone(),
+                    remote_control_key: result.get_remote_control_key(sid),
+                    satellite_frequency,
+                }
             })
             .collect()
     } else {
@@ -776,16 +890,130 @@ fn analyze_ts_stream(
     Ok((result.network_id, result.transport_stream_id, services))
 }
 
+/// Outcome of a one-off test tune (see [`test_tune_blocking`]).
+#[derive(Debug, Clone, Default)]
+pub struct TestTuneReport {
+    /// Time SetChannel took to return.
+    pub set_channel_ms: u64,
+    /// Time from SetChannel until the first TS bytes arrived; `None` when
+    /// no data arrived before the deadline.
+    pub first_ts_ms: Option<u64>,
+    /// Signal level read once data arrived (or at the deadline).
+    pub signal_level: f32,
+    /// Network ID (from NIT)
+    pub network_id: Option<u16>,
+    /// Transport Stream ID (from PAT)
+    pub transport_stream_id: Option<u16>,
+    /// Network name (from the NIT network name descriptor)
+    pub network_name: Option<String>,
+    /// TS name (from the NIT TS information descriptor)
+    pub ts_name: Option<String>,
+    /// Services found in the PAT, with their SDT names
+    pub services: Vec<(u16, Option<String>)>,
+    /// SetChannel or TS analysis failure
+    pub error: Option<String>,
+}
+
+/// Open the driver, tune once and report lock time, signal and the
+/// decoded NIT/SDT, then close it.
+///
+/// Nothing is streamed to clients; the whole run is bounded by `timeout`.
+pub fn test_tune_blocking(
+    dll_path: &str,
+    space: u32,
+    channel: u32,
+    timeout: Duration,
+) -> Result<TestTuneReport, Box<dyn std::error::Error + Send + Sync>> {
+    let tuner = BonDriverTuner::new(dll_path)?;
+    let mut report = TestTuneReport::default();
+
+    let start = std::time::Instant::now();
+    let deadline = start + timeout;
+    if let Err(e) = tuner.set_channel(space, channel) {
+        report.set_channel_ms = start.elapsed().as_millis() as u64;
+        report.error = Some(format!("SetChannel failed: {}", e));
+        return Ok(report);
+    }
+    report.set_channel_ms = start.elapsed().as_millis() as u64;
+    tuner.purge_ts_stream();
+
+    // Time to first TS: the bytes read here are discarded, the analyzer
+    // below starts on fresh data
+    let mut buffer = vec![0u8; TS_BUFFER_SIZE];
+    while std::time::Instant::now() < deadline {
+        tuner.wait_ts_stream(TS_WAIT_MS);
+        match tuner.get_ts_stream(&mut buffer) {
+            Ok((size, _)) if size > 0 => {
+                report.first_ts_ms = Some(start.elapsed().as_millis() as u64);
+                break;
+            }
+            Ok(_) => std::thread::sleep(Duration::from_millis(10)),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(10))
+            }
+            Err(e) => {
+                report.error = Some(format!("GetTsStream failed: {}", e));
+                break;
+            }
+        }
+    }
+    report.signal_level = tuner.get_signal_level();
+    if report.first_ts_ms.is_none() {
+        return Ok(report);
+    }
+
+    let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+    let analysis = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        analyze_ts_stream(&tuner, remaining.as_millis() as u64)
+    }))
+    .unwrap_or_else(|_| Err("panic in analyze_ts_stream".into()));
+    match analysis {
+        Ok((nid, tsid, services)) => {
+            report.network_id = nid;
+            report.transport_stream_id = tsid;
+            report.network_name = services.iter().find_map(|s| s.network_name.clone());
+            report.ts_name = services.iter().find_map(|s| s.ts_name.clone());
+            report.services = services
+                .into_iter()
+                .map(|s| (s.service_id, s.service_name))
+                .collect();
+        }
+        Err(e) => report.error = Some(format!("TS analysis failed: {}", e)),
+    }
+
+    Ok(report)
+}
+
 /// Convert scan results to ChannelInfo for database storage.
 /// Each ScanChannelResult may contain multiple services (SIDs).
+///
+/// BS/CS streams are stored on the channel the NIT places them on (see
+/// [`SatelliteChannelMap`]) rather than the channel that was tuned.
 fn scan_results_to_channel_infos(
     results: &[ScanChannelResult],
+    satellite_map: &SatelliteChannelMap,
 ) -> Vec<recisdb_protocol::ChannelInfo> {
     let mut channel_infos = Vec::new();
 
     for r in results {
         let nid = r.network_id.unwrap_or(0);
         let tsid = r.transport_stream_id.unwrap_or(0);
+        let satellite = r
+            .services
+            .iter()
+            .find_map(|svc| svc.satellite_frequency)
+            .and_then(|freq| SatelliteTs::from_nit(nid, tsid, freq));
+        let (space, channel) = match satellite.and_then(|ts| satellite_map.locate(&ts)) {
+            Some(located) if located != (r.space, r.channel) => {
+                warn!(
+                    "scan_results_to_channel_infos: TSID 0x{:04X} read on space={}, channel={} belongs to space={}, channel={} per NIT",
+                    tsid, r.space, r.channel, located.0, located.1
+                );
+                located
+            }
+            Some(located) => located,
+            None => (r.space, r.channel),
+        };
 
         if r.services.is_empty() {
             // No services found, create entry with minimal info
@@ -794,6 +1022,7 @@ fn scan_results_to_channel_infos(
                   r.space, r.channel);
             let mut info = recisdb_protocol::ChannelInfo::new(nid, 0, tsid);
             info.channel_name = Some(r.channel_name.clone());
+            info.name_is_synthetic = true;
             info.bon_space = Some(r.space);
             info.bon_channel = Some(r.channel);
             channel_infos.push(info);
@@ -801,10 +1030,26 @@ fn scan_results_to_channel_infos(
             // Create a ChannelInfo entry for each service
             for svc in &r.services {
                 let mut info = recisdb_protocol::ChannelInfo::new(nid, svc.service_id, tsid);
-                info.channel_name = svc.service_name.clone().or_else(|| Some(r.channel_name.clone()));
+                match svc.service_name.as_deref().map(str::trim).filter(|name| !name.is_empty()) {
+                    Some(name) => info.channel_name = Some(name.to_string()),
+                    None => {
+                        // No usable SDT name: guess one rather than keep the BonDriver's "CH24"
+                        info.channel_name = synthesize_service_name(
+                            svc.network_name.as_deref(),
+                            svc.remote_control_key,
+                        )
+                        .or_else(|| Some(r.channel_name.clone()));
+                        info.name_is_synthetic = true;
+                    }
+                }
+                info.network_name = svc.network_name.clone();
                 info.service_type = svc.service_type;
-                info.bon_space = Some(r.space);
-                info.bon_channel = Some(r.channel);
+                info.is_one_seg = svc.is_one_seg;
+                info.ts_name = svc.ts_name.clone();
+                info.remote_control_key = svc.remote_control_key;
+                info.physical_ch = satellite.map(|ts| ts.transponder);
+                info.bon_space = Some(space);
+                info.bon_channel = Some(channel);
                 channel_infos.push(info);
             }
         }
@@ -813,31 +1058,124 @@ fn scan_results_to_channel_infos(
     channel_infos
 }
 
+/// Build a channel name from the NIT when the SDT carries none.
+///
+/// Uses the network name plus the remote control key (e.g. "ＮＨＫ総合 1"),
+/// or the network name alone when no key is announced.
+fn synthesize_service_name(network_name: Option<&str>, remote_control_key: Option<u8>) -> Option<String> {
+    let network_name = network_name.map(str::trim).filter(|name| !name.is_empty())?;
+    Some(match remote_control_key {
+        Some(key) => format!("{} {}", network_name, key),
+        None => network_name.to_string(),
+    })
+}
+
+/// Collect `(sid, logo_id)` pairs per network from scan results.
+fn scan_results_logo_ids(results: &[ScanChannelResult]) -> HashMap<u16, Vec<(u16, u16)>> {
+    let mut logo_ids: HashMap<u16, Vec<(u16, u16)>> = HashMap::new();
+    for r in results {
+        let Some(nid) = r.network_id else {
+            continue;
+        };
+        for svc in &r.services {
+            if let Some(logo_id) = svc.logo_id {
+                logo_ids.entry(nid).or_default().push((svc.service_id, logo_id));
+            }
+        }
+    }
+    logo_ids
+}
+
+/// Parse a `scan_cron` expression.
+///
+/// Accepts the usual five fields (`min hour day month weekday`) as well as
+/// the `cron` crate's six/seven-field form with seconds.
+pub fn parse_scan_cron(expr: &str) -> Result<cron::Schedule, String> {
+    let expr = expr.trim();
+    let normalized = if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+    cron::Schedule::from_str(&normalized)
+        .map_err(|e| format!("Invalid cron expression '{}': {}", expr, e))
+}
+
+/// Unix time of the next scan after `now`.
+///
+/// Uses the cron schedule when one is set (evaluated in local time) and
+/// falls back to `interval_hours` otherwise.
+pub fn next_scan_time<Tz: chrono::TimeZone>(
+    scan_cron: Option<&str>,
+    interval_hours: i32,
+    now: chrono::DateTime<Tz>,
+) -> i64 {
+    if let Some(expr) = scan_cron {
+        match parse_scan_cron(expr) {
+            Ok(schedule) => {
+                if let Some(next) = schedule.after(&now).next() {
+                    return next.timestamp();
+                }
+            }
+            Err(e) => warn!("ScanScheduler: {}; using scan_interval_hours", e),
+        }
+    }
+    now.timestamp() + interval_hours as i64 * 3600
+}
+
 /// Perform a channel scan for a BonDriver.
 async fn perform_scan(
     driver: &BonDriverRecord,
     database: DatabaseHandle,
-    _tuner_pool: Arc<TunerPool>,
+    tuner_pool: Arc<TunerPool>,
+    events: Option<Arc<SessionRegistry>>,
     signal_lock_wait_ms: u64,
     ts_read_timeout_ms: u64,
 ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
     debug!("perform_scan: Starting scan for {}", driver.dll_path);
 
+    // Let a test tune in progress close the driver, then keep new ones off
+    // it until the scan is done
+    let _scanning = {
+        let _init = tuner_pool.acquire_dll_init_lock(&driver.dll_path).await;
+        tuner_pool.begin_scan(&driver.dll_path)
+    };
+
+    // The scan opens the driver directly, so it must not hold it open twice
+    tuner_pool.release_startup_warm(&driver.dll_path).await;
+
     let dll_path = driver.dll_path.clone();
     let driver_id = driver.id;
     let is_initial_scan = driver.next_scan_at.is_none();
 
     // Get existing channel spaces from database to know what to scan
-    let scan_ranges = if is_initial_scan {
-        info!(
-            "perform_scan: Initial scan for {} detected; treating DB spaces as empty",
-            driver.dll_path
-        );
-        Vec::new()
-    } else {
+    let (scan_ranges, scope) = {
         let db = database.lock().await;
-        db.get_tuning_spaces(driver_id).unwrap_or_default()
+        let scan_ranges = if is_initial_scan {
+            info!(
+                "perform_scan: Initial scan for {} detected; treating DB spaces as empty",
+                driver.dll_path
+            );
+            Vec::new()
+        } else {
+            db.get_tuning_spaces(driver_id).unwrap_or_default()
+        };
+        let scope = match db.get_scan_scope(driver_id) {
+            Ok(Some(value)) => ScanScope::parse(&value).unwrap_or_else(|e| {
+                warn!("perform_scan: {}; scanning everything", e);
+                ScanScope::All
+            }),
+            Ok(None) => ScanScope::All,
+            Err(e) => {
+                warn!("perform_scan: Failed to load scan_scope: {}", e);
+                ScanScope::All
+            }
+        };
+        (scan_ranges, scope)
     };
+    if scope != ScanScope::All {
+        info!("perform_scan: Scan scope for {}: {}", driver.dll_path, scope);
+    }
 
     // Channel scan always uses BonDriver enumeration results directly.
     // DB spaces are not used as a runtime filter.
@@ -851,25 +1189,36 @@ async fn perform_scan(
 
     // Collect all scan results
     let dll = dll_path.clone();
-    let all_results = tokio::task::spawn_blocking(move || {
+    let (all_results, satellite_map, probes) = tokio::task::spawn_blocking(move || {
         let mut results = Vec::new();
+        let mut probes = Vec::new();
 
         // 1) Open tuner and enumerate spaces/channels first
         let plans = match enumerate_spaces_and_channels_blocking(&dll) {
             Ok(p) => p,
             Err(e) => {
                 warn!("perform_scan: Failed to enumerate spaces/channels: {}", e);
-                return Ok::<_, Box<dyn std::error::Error + Send + Sync>>(results);
+                return Ok::<_, Box<dyn std::error::Error + Send + Sync>>((results, SatelliteChannelMap::default(), probes));
             }
         };
 
         if plans.is_empty() {
             warn!("perform_scan: BonDriver reported no tuning spaces");
-            return Ok::<_, Box<dyn std::error::Error + Send + Sync>>(results);
+            return Ok::<_, Box<dyn std::error::Error + Send + Sync>>((results, SatelliteChannelMap::default(), probes));
         }
 
-        // 2) Use all (space, channel list) from enumeration to run scans
-        for (space, space_name, channels) in plans {
+        // BS/CS channels by transponder, from the whole enumerated list
+        let satellite_map = SatelliteChannelMap::new(plans.iter().flat_map(|(space, _, channels)| {
+            channels.iter().map(move |(channel, name)| (*space, *channel, name.as_str()))
+        }));
+
+        // 2) Use the (space, channel list) pairs from enumeration that fall in the scope
+        for (space, space_name, mut channels) in plans {
+            if !scope.includes_space(&space_name) {
+                info!("perform_scan: Skipping space {} ({}) outside scan scope", space, space_name);
+                continue;
+            }
+            channels.retain(|(_, name)| scope.includes_channel(name));
             if channels.is_empty() {
                 warn!(
                     "perform_scan: Space {} ({}) has no channels from BonDriver enumeration",
@@ -886,18 +1235,27 @@ async fn perform_scan(
                 channels.len()
             );
 
-            match scan_space_blocking(&dll, space, &channels, signal_lock_wait_ms, ts_read_timeout_ms) {
+            match scan_space_blocking(
+                &dll,
+                driver_id,
+                space,
+                &channels,
+                events.as_deref(),
+                signal_lock_wait_ms,
+                ts_read_timeout_ms,
+                &mut probes,
+            ) {
                 Ok(r) => results.extend(r),
                 Err(e) => warn!("perform_scan: Space {} scan failed: {}", space, e),
             }
         }
 
-        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(results)
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>((results, satellite_map, probes))
     })
     .await??;
 
     // Convert results to ChannelInfo
-    let channel_infos = scan_results_to_channel_infos(&all_results);
+    let channel_infos = scan_results_to_channel_infos(&all_results, &satellite_map);
     let total = channel_infos.len();
 
     // Log detailed scan results
@@ -915,6 +1273,12 @@ async fn perform_scan(
             }
         }
 
+        for (nid, logo_ids) in scan_results_logo_ids(&all_results) {
+            if let Err(e) = db.set_service_logo_ids(nid, &logo_ids) {
+                warn!("perform_scan: Failed to store logo IDs for NID {}: {}", nid, e);
+            }
+        }
+
         // Record successful scan in history
         let _ = db.insert_scan_history(
             driver_id,
@@ -924,6 +1288,25 @@ async fn perform_scan(
         );
     }
 
+    // Flag (and optionally disable) channels that did not come in, including
+    // ones this scan found nothing on
+    if !probes.is_empty() {
+        let db = database.lock().await;
+        let (min_signal, auto_disable) = db.get_reception_config_for_path(&dll_path).unwrap_or_else(|e| {
+            warn!("perform_scan: Failed to load reception settings: {}", e);
+            (None, false)
+        });
+        let min_signal = min_signal.map(|v| v as f32).unwrap_or(MIN_SIGNAL_LEVEL);
+        match db.apply_scan_reception(driver_id, &probes, min_signal, auto_disable) {
+            Ok(update) if update.failed > 0 || update.restored > 0 => info!(
+                "perform_scan: Reception check (< {:.1} dB) for {}: {} channels failed, {} disabled, {} re-enabled",
+                min_signal, driver.dll_path, update.failed, update.disabled, update.restored
+            ),
+            Ok(_) => {}
+            Err(e) => warn!("perform_scan: Failed to store reception results: {}", e),
+        }
+    }
+
     info!(
         "perform_scan: Completed scan for {}: {} channels found",
         driver.dll_path, total
@@ -945,6 +1328,105 @@ mod tests {
         assert_eq!(config.signal_lock_wait_ms, 500);
         assert_eq!(config.ts_read_timeout_ms, 300000);
     }
+
+    #[test]
+    fn test_scan_results_relocated_by_nit() {
+        let service = |sid: u16, freq: Option<u32>| ServiceInfo {
+            service_id: sid,
+            service_name: None,
+            service_type: Some(0x01),
+            is_one_seg: false,
+            logo_id: None,
+            ts_name: None,
+            network_name: None,
+            remote_control_key: None,
+            satellite_frequency: freq,
+        };
+        // Tuned BS03/TS0 but the tuner was still delivering BS01/TS1
+        let results = vec![ScanChannelResult {
+            space: 0,
+            channel: 2,
+            channel_name: "BS03/TS0".to_string(),
+            signal_level: 10.0,
+            network_id: Some(0x0004),
+            transport_stream_id: Some(0x4011),
+            services: vec![service(0x0097, Some(11_727_480))],
+        }];
+        let names = [(0, 0, "BS01/TS0"), (0, 1, "BS01/TS1"), (0, 2, "BS03/TS0")];
+        let map = SatelliteChannelMap::new(names.iter().map(|(s, c, n)| (*s, *c, *n)));
+
+        let infos = scan_results_to_channel_infos(&results, &map);
+        assert_eq!(infos[0].bon_channel, Some(1));
+        assert_eq!(infos[0].physical_ch, Some(1));
+
+        // Without a satellite delivery descriptor the tuned channel is kept
+        let mut results = results;
+        results[0].services = vec![service(0x0097, None)];
+        let infos = scan_results_to_channel_infos(&results, &map);
+        assert_eq!((infos[0].bon_channel, infos[0].physical_ch), (Some(2), None));
+    }
+
+    #[test]
+    fn test_scan_results_synthesize_missing_names() {
+        let service = |sid: u16, name: Option<&str>, key: Option<u8>| ServiceInfo {
+            service_id: sid,
+            service_name: name.map(|n| n.to_string()),
+            service_type: Some(0x01),
+            is_one_seg: false,
+            logo_id: None,
+            ts_name: None,
+            network_name: Some("ＮＨＫ総合".to_string()),
+            remote_control_key: key,
+            satellite_frequency: None,
+        };
+        let results = vec![ScanChannelResult {
+            space: 0,
+            channel: 27,
+            channel_name: "CH24".to_string(),
+            signal_level: 30.0,
+            network_id: Some(0x7FE0),
+            transport_stream_id: Some(0x7FE0),
+            services: vec![
+                service(0x0400, Some("ＮＨＫ総合１・東京"), Some(1)),
+                service(0x0401, Some(""), Some(1)),
+                service(0x0402, None, None),
+            ],
+        }];
+        let map = SatelliteChannelMap::new(std::iter::empty());
+
+        let infos = scan_results_to_channel_infos(&results, &map);
+        let names: Vec<(Option<&str>, bool)> = infos
+            .iter()
+            .map(|i| (i.channel_name.as_deref(), i.name_is_synthetic))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (Some("ＮＨＫ総合１・東京"), false),
+                (Some("ＮＨＫ総合 1"), true),
+                (Some("ＮＨＫ総合"), true),
+            ]
+        );
+        assert_eq!(synthesize_service_name(None, Some(1)), None);
+        assert_eq!(synthesize_service_name(Some(" "), None), None);
+    }
+
+    #[test]
+    fn test_next_scan_time() {
+        use chrono::TimeZone;
+        let now = chrono::Utc.with_ymd_and_hms(2024, 5, 1, 21, 30, 0).unwrap();
+
+        // Five-field cron: next 04:00
+        let next = next_scan_time(Some("0 4 * * *"), 24, now);
+        assert_eq!(next, chrono::Utc.with_ymd_and_hms(2024, 5, 2, 4, 0, 0).unwrap().timestamp());
+
+        // No cron, or an invalid one, falls back to the interval
+        assert_eq!(next_scan_time(None, 24, now), now.timestamp() + 24 * 3600);
+        assert_eq!(next_scan_time(Some("not a cron"), 6, now), now.timestamp() + 6 * 3600);
+
+        assert!(parse_scan_cron("0 0 4 * * Sun").is_ok());
+        assert!(parse_scan_cron("61 4 * * *").is_err());
+    }
 }
 
 /// Log detailed scan results with regional and band-type information.
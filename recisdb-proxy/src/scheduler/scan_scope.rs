@@ -0,0 +1,188 @@
+//! Per-driver restriction of which tuning spaces and channels get scanned.
+//!
+//! Stored as text in `bon_drivers.scan_scope`:
+//!
+//! | Value                  | Scanned                                          |
+//! |------------------------|--------------------------------------------------|
+//! | *(NULL)* / `all`       | every space the BonDriver reports                |
+//! | `terrestrial`          | 地デジ spaces only                               |
+//! | `bs` / `cs`            | BS / CS spaces only                              |
+//! | `channels:13,16,20-27` | those physical channels in 地デジ spaces only    |
+
+use std::fmt;
+
+/// Broadcast band of a BonDriver tuning space, guessed from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpaceBand {
+    Terrestrial,
+    Bs,
+    Cs,
+}
+
+impl SpaceBand {
+    /// Classify a tuning space name such as `地デジ`, `UHF`, `BS` or `CS110`.
+    pub fn from_space_name(name: &str) -> Option<Self> {
+        let upper = name.to_uppercase();
+        if upper.contains("BS") {
+            Some(Self::Bs)
+        } else if upper.contains("CS") || upper.contains("110") {
+            Some(Self::Cs)
+        } else if ["地デジ", "地上", "UHF", "VHF", "CATV", "GR", "TERRESTRIAL"]
+            .iter()
+            .any(|key| upper.contains(key))
+        {
+            Some(Self::Terrestrial)
+        } else {
+            None
+        }
+    }
+}
+
+/// Which part of a driver's channel list a scan covers.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ScanScope {
+    #[default]
+    All,
+    Band(SpaceBand),
+    /// Terrestrial physical channels as inclusive ranges.
+    Channels(Vec<(u32, u32)>),
+}
+
+impl ScanScope {
+    /// Parse a stored or submitted scope. Empty means [`ScanScope::All`].
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        match value.to_ascii_lowercase().as_str() {
+            "" | "all" => return Ok(Self::All),
+            "terrestrial" => return Ok(Self::Band(SpaceBand::Terrestrial)),
+            "bs" => return Ok(Self::Band(SpaceBand::Bs)),
+            "cs" => return Ok(Self::Band(SpaceBand::Cs)),
+            _ => {}
+        }
+
+        let invalid = || {
+            format!(
+                "Invalid scan_scope '{}' (all, terrestrial, bs, cs or channels:13,16,20-27)",
+                value
+            )
+        };
+        let list = value
+            .get(..9)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("channels:"))
+            .map(|_| &value[9..])
+            .ok_or_else(invalid)?;
+
+        let mut ranges = Vec::new();
+        for item in list.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let (start, end) = item.split_once('-').unwrap_or((item, item));
+            let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+            let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+            if start > end {
+                return Err(invalid());
+            }
+            ranges.push((start, end));
+        }
+        if ranges.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self::Channels(ranges))
+    }
+
+    /// Whether a tuning space is scanned at all.
+    pub fn includes_space(&self, space_name: &str) -> bool {
+        let band = SpaceBand::from_space_name(space_name);
+        match self {
+            Self::All => true,
+            Self::Band(wanted) => band == Some(*wanted),
+            Self::Channels(_) => band == Some(SpaceBand::Terrestrial),
+        }
+    }
+
+    /// Whether a channel of an included space is scanned.
+    pub fn includes_channel(&self, channel_name: &str) -> bool {
+        match self {
+            Self::Channels(ranges) => physical_channel(channel_name)
+                .is_some_and(|ch| ranges.iter().any(|(start, end)| (*start..=*end).contains(&ch))),
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for ScanScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::Band(SpaceBand::Terrestrial) => write!(f, "terrestrial"),
+            Self::Band(SpaceBand::Bs) => write!(f, "bs"),
+            Self::Band(SpaceBand::Cs) => write!(f, "cs"),
+            Self::Channels(ranges) => {
+                let list: Vec<String> = ranges
+                    .iter()
+                    .map(|(start, end)| {
+                        if start == end {
+                            start.to_string()
+                        } else {
+                            format!("{}-{}", start, end)
+                        }
+                    })
+                    .collect();
+                write!(f, "channels:{}", list.join(","))
+            }
+        }
+    }
+}
+
+/// Physical channel number in a BonDriver channel name (`13ch`, `UHF 27`, `１３ｃｈ`).
+fn physical_channel(name: &str) -> Option<u32> {
+    let digits: String = name
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            _ => c,
+        })
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trip() {
+        assert_eq!(ScanScope::parse("").unwrap(), ScanScope::All);
+        assert_eq!(ScanScope::parse("BS").unwrap(), ScanScope::Band(SpaceBand::Bs));
+
+        let scope = ScanScope::parse("channels: 13, 20-27").unwrap();
+        assert_eq!(scope, ScanScope::Channels(vec![(13, 13), (20, 27)]));
+        assert_eq!(scope.to_string(), "channels:13,20-27");
+
+        assert!(ScanScope::parse("channels:").is_err());
+        assert!(ScanScope::parse("channels:27-20").is_err());
+        assert!(ScanScope::parse("satellite").is_err());
+    }
+
+    #[test]
+    fn test_scope_filters() {
+        let terrestrial = ScanScope::parse("terrestrial").unwrap();
+        assert!(terrestrial.includes_space("地デジ"));
+        assert!(terrestrial.includes_space("UHF"));
+        assert!(!terrestrial.includes_space("BS"));
+        assert!(!terrestrial.includes_space("CS110"));
+
+        let cs = ScanScope::parse("cs").unwrap();
+        assert!(cs.includes_space("CS110"));
+        assert!(!cs.includes_space("地デジ"));
+
+        let channels = ScanScope::parse("channels:13,20-27").unwrap();
+        assert!(channels.includes_space("地デジ"));
+        assert!(!channels.includes_space("BS"));
+        assert!(channels.includes_channel("13ch"));
+        assert!(channels.includes_channel("UHF 27"));
+        assert!(channels.includes_channel("２１ｃｈ"));
+        assert!(!channels.includes_channel("14ch"));
+        assert!(!channels.includes_channel("no number"));
+    }
+}
@@ -0,0 +1,133 @@
+//! Coalescing of small TS chunks into larger TsData frames.
+
+use std::time::{Duration, Instant};
+
+use bytes::{Bytes, BytesMut};
+use recisdb_protocol::MAX_TS_CHUNK_SIZE;
+
+/// Size of one TS packet.
+const TS_PACKET_SIZE: usize = 188;
+
+/// Collects aligned TS chunks until a target size is reached or the oldest
+/// buffered byte has waited for the flush interval.
+///
+/// Frames never exceed `MAX_TS_CHUNK_SIZE` unless a single incoming chunk is
+/// already larger; such chunks are passed through untouched.
+#[derive(Debug)]
+pub(crate) struct TsCoalescer {
+    /// Frame size that triggers an immediate flush (whole packets).
+    target: usize,
+    flush_after: Duration,
+    buf: BytesMut,
+    /// When the buffered data must be sent (None while empty).
+    deadline: Option<Instant>,
+}
+
+impl TsCoalescer {
+    /// Create a coalescer, or None when coalescing is disabled.
+    ///
+    /// `target_bytes` is rounded down to whole packets and capped at
+    /// `MAX_TS_CHUNK_SIZE`; less than two packets or a zero flush interval
+    /// disables coalescing.
+    pub(crate) fn new(target_bytes: usize, flush_after: Duration) -> Option<Self> {
+        let target = target_bytes.min(MAX_TS_CHUNK_SIZE) / TS_PACKET_SIZE * TS_PACKET_SIZE;
+        if target < TS_PACKET_SIZE * 2 || flush_after.is_zero() {
+            return None;
+        }
+        Some(Self {
+            target,
+            flush_after,
+            buf: BytesMut::with_capacity(target),
+            deadline: None,
+        })
+    }
+
+    /// Add a chunk and return the frames that are ready to send.
+    pub(crate) fn push(&mut self, data: Bytes, now: Instant) -> Vec<Bytes> {
+        let mut ready = Vec::new();
+        if !self.buf.is_empty() && self.buf.len() + data.len() > MAX_TS_CHUNK_SIZE {
+            ready.extend(self.flush());
+        }
+        if self.buf.is_empty() && data.len() >= self.target {
+            ready.push(data);
+            return ready;
+        }
+
+        self.buf.extend_from_slice(&data);
+        if self.buf.len() >= self.target {
+            ready.extend(self.flush());
+        } else if self.deadline.is_none() {
+            self.deadline = Some(now + self.flush_after);
+        }
+        ready
+    }
+
+    /// Time by which the buffered data must be flushed.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Take the buffered data, if any.
+    pub(crate) fn flush(&mut self) -> Option<Bytes> {
+        self.deadline = None;
+        (!self.buf.is_empty()).then(|| self.buf.split().freeze())
+    }
+
+    /// Discard the buffered data.
+    pub(crate) fn clear(&mut self) {
+        self.buf.clear();
+        self.deadline = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packets(n: usize) -> Bytes {
+        Bytes::from(vec![0x47; TS_PACKET_SIZE * n])
+    }
+
+    #[test]
+    fn test_coalescer_flushes_on_size_and_deadline() {
+        let flush_after = Duration::from_millis(20);
+        let mut coalescer = TsCoalescer::new(TS_PACKET_SIZE * 10 + 100, flush_after).unwrap();
+        let now = Instant::now();
+
+        assert!(coalescer.push(packets(4), now).is_empty());
+        assert_eq!(coalescer.deadline(), Some(now + flush_after));
+        // A later chunk does not push the deadline back
+        assert!(coalescer.push(packets(4), now + Duration::from_millis(5)).is_empty());
+        assert_eq!(coalescer.deadline(), Some(now + flush_after));
+
+        let ready = coalescer.push(packets(4), now);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].len(), TS_PACKET_SIZE * 12);
+        assert_eq!(coalescer.deadline(), None);
+
+        // Low-bitrate streams are sent by the timer
+        coalescer.push(packets(1), now);
+        assert_eq!(coalescer.flush().unwrap().len(), TS_PACKET_SIZE);
+        assert!(coalescer.flush().is_none());
+
+        // Large chunks bypass the buffer
+        let ready = coalescer.push(packets(20), now);
+        assert_eq!(ready[0].len(), TS_PACKET_SIZE * 20);
+    }
+
+    #[test]
+    fn test_coalescer_respects_max_chunk_size() {
+        let mut coalescer = TsCoalescer::new(usize::MAX, Duration::from_millis(20)).unwrap();
+        let now = Instant::now();
+        let max_packets = MAX_TS_CHUNK_SIZE / TS_PACKET_SIZE;
+
+        assert!(coalescer.push(packets(max_packets - 1), now).is_empty());
+        let ready = coalescer.push(packets(2), now);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].len(), TS_PACKET_SIZE * (max_packets - 1));
+        assert!(coalescer.flush().is_some());
+
+        assert!(TsCoalescer::new(TS_PACKET_SIZE, Duration::from_millis(20)).is_none());
+        assert!(TsCoalescer::new(TS_PACKET_SIZE * 10, Duration::ZERO).is_none());
+    }
+}
@@ -1,27 +1,140 @@
-//! TCP listener for accepting client connections.
+//! TCP / Unix-socket listeners for accepting client connections.
 
-use std::net::SocketAddr;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
 use log::{error, info, warn};
-use tokio::io::{AsyncWriteExt};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch, Notify};
+use tracing::Instrument;
 use bytes::Bytes;
+use recisdb_protocol::{encode_server_message, ErrorCode, ServerMessage};
 
 use crate::database::Database;
-use crate::server::session::Session;
+use crate::server::session::{Session, SocketReader};
+use crate::server::throttle::RateLimiter;
 use crate::tuner::{TunerPool, TunerPoolConfig};
-use crate::web::SessionRegistry;
+use crate::web::{IpSessionSlot, SessionRegistry};
 
 /// Database handle type.
 pub type DatabaseHandle = Arc<tokio::sync::Mutex<Database>>;
 
+/// How long [`Server::shutdown`] waits for sessions to record their
+/// history before stopping the tuners under them.
+const SHUTDOWN_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Forget per-IP rate limiters that have refilled once this many are held.
+const RATE_LIMITER_PRUNE_THRESHOLD: usize = 256;
+
+/// How long a client may take to complete the TLS handshake.
+#[cfg(feature = "tls")]
+const TLS_HANDSHAKE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Write half of a client connection, plain TCP or TLS.
+type SocketWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Address reported for clients connected over a Unix socket, which have
+/// no IP address of their own.
+const UNIX_PEER_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+/// Address a listener binds to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddr {
+    /// TCP on IPv4 or IPv6, e.g. `0.0.0.0:40070` or `[::]:40070`.
+    Tcp(SocketAddr),
+    /// Unix domain socket, written as `unix:/run/recisdb-proxy.sock`.
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+impl FromStr for ListenAddr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(path) = s.strip_prefix("unix:") {
+            #[cfg(unix)]
+            {
+                if path.is_empty() {
+                    return Err("unix: listen address needs a socket path".to_string());
+                }
+                return Ok(Self::Unix(std::path::PathBuf::from(path)));
+            }
+            #[cfg(not(unix))]
+            {
+                let _ = path;
+                return Err("Unix socket listeners are only supported on Unix".to_string());
+            }
+        }
+        s.parse::<SocketAddr>()
+            .map(Self::Tcp)
+            .map_err(|e| format!("invalid listen address '{}': {}", s, e))
+    }
+}
+
+impl fmt::Display for ListenAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{}", addr),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// A bound listener socket.
+enum BoundListener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener),
+}
+
+/// A connection accepted by a [`BoundListener`].
+enum Accepted {
+    Tcp(TcpStream, SocketAddr),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+}
+
+impl BoundListener {
+    async fn bind(addr: &ListenAddr) -> std::io::Result<Self> {
+        match addr {
+            ListenAddr::Tcp(addr) => Ok(Self::Tcp(TcpListener::bind(addr).await?)),
+            #[cfg(unix)]
+            ListenAddr::Unix(path) => {
+                // A socket file left by an earlier run would make bind fail
+                use std::os::unix::fs::FileTypeExt;
+                if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+                    std::fs::remove_file(path)?;
+                }
+                Ok(Self::Unix(tokio::net::UnixListener::bind(path)?))
+            }
+        }
+    }
+
+    async fn accept(&self) -> std::io::Result<Accepted> {
+        match self {
+            Self::Tcp(listener) => {
+                let (socket, addr) = listener.accept().await?;
+                Ok(Accepted::Tcp(socket, addr))
+            }
+            #[cfg(unix)]
+            Self::Unix(listener) => Ok(Accepted::Unix(listener.accept().await?.0)),
+        }
+    }
+}
+
 /// Server configuration.
 #[derive(Clone)]
 pub struct ServerConfig {
-    /// Address to listen on.
-    pub listen_addr: SocketAddr,
+    /// Addresses to listen on; one accept loop runs per address.
+    pub listen_addrs: Vec<ListenAddr>,
     /// Maximum concurrent connections.
     pub max_connections: usize,
     /// Path to the default tuner device.
@@ -30,6 +143,17 @@ pub struct ServerConfig {
     pub database: DatabaseHandle,
     /// Tuner optimization configuration.
     pub tuner_config: TunerPoolConfig,
+    /// Seconds without any client traffic before a session is dropped (0 = never).
+    /// A value stored in the database takes precedence when a session starts.
+    pub session_idle_timeout_secs: u64,
+    /// Shared secret clients must present in Hello (None = no authentication).
+    pub auth_token: Option<String>,
+    /// Whether clients on Unix-socket listeners must present `auth_token` too.
+    pub unix_socket_auth: bool,
+    /// Maximum concurrent connections from one client IP (0 = unlimited).
+    pub max_sessions_per_ip: usize,
+    /// New connections one client IP may open per minute (0 = unlimited).
+    pub connection_rate_per_ip: u32,
     /// TLS configuration (optional).
     #[cfg(feature = "tls")]
     pub tls_config: Option<TlsConfig>,
@@ -45,12 +169,223 @@ pub struct TlsConfig {
     pub require_client_cert: bool,
 }
 
+/// Build the rustls acceptor for `config`.
+///
+/// Client certificates are verified against the CA certificate. They are
+/// mandatory only with `require_client_cert`; otherwise clients without one
+/// are accepted and simply have no identity.
+#[cfg(feature = "tls")]
+fn build_tls_acceptor(config: &TlsConfig) -> std::io::Result<tokio_rustls::TlsAcceptor> {
+    use std::io::{BufReader, Error, ErrorKind};
+    use rustls::server::WebPkiClientVerifier;
+    use rustls::RootCertStore;
+
+    fn load_certs(path: &str) -> std::io::Result<Vec<rustls::pki_types::CertificateDer<'static>>> {
+        let mut reader = BufReader::new(std::fs::File::open(path)?);
+        rustls_pemfile::certs(&mut reader).collect()
+    }
+    let invalid = |e: &dyn std::fmt::Display| Error::new(ErrorKind::InvalidData, e.to_string());
+
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(&config.ca_cert_path)? {
+        roots.add(cert).map_err(|e| invalid(&e))?;
+    }
+    let verifier = WebPkiClientVerifier::builder(Arc::new(roots));
+    let verifier = if config.require_client_cert {
+        verifier
+    } else {
+        verifier.allow_unauthenticated()
+    };
+    let verifier = verifier.build().map_err(|e| invalid(&e))?;
+
+    let certs = load_certs(&config.server_cert_path)?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(std::fs::File::open(&config.server_key_path)?))?
+        .ok_or_else(|| invalid(&format!("no private key in {}", config.server_key_path)))?;
+
+    let server_config = rustls::ServerConfig::builder()
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(certs, key)
+        .map_err(|e| invalid(&e))?;
+    Ok(tokio_rustls::TlsAcceptor::from(Arc::new(server_config)))
+}
+
+/// Client identity from a verified certificate: the subject CN, or the
+/// first DNS name, e-mail address or URI in the subject alternative name.
+#[cfg(feature = "tls")]
+fn certificate_identity(der: &[u8]) -> Option<String> {
+    use x509_parser::prelude::{FromDer, GeneralName, X509Certificate};
+
+    let (_, cert) = X509Certificate::from_der(der).ok()?;
+    if let Some(cn) = cert.subject().iter_common_name().find_map(|cn| cn.as_str().ok()) {
+        return Some(cn.to_string());
+    }
+    let san = cert.subject_alternative_name().ok()??;
+    san.value.general_names.iter().find_map(|name| match name {
+        GeneralName::DNSName(name) | GeneralName::RFC822Name(name) | GeneralName::URI(name) => {
+            Some(name.to_string())
+        }
+        _ => None,
+    })
+}
+
+/// An accepted client connection, after any TLS handshake.
+struct ClientConnection {
+    reader: SocketReader,
+    writer: SocketWriter,
+    /// Identity from the TLS client certificate, if one was presented.
+    identity: Option<String>,
+}
+
+impl ClientConnection {
+    /// Use a TCP connection as is.
+    fn plain(socket: TcpStream) -> std::io::Result<Self> {
+        // Disable Nagle's algorithm for lower latency
+        socket.set_nodelay(true)?;
+        let (reader, writer) = socket.into_split();
+        Ok(Self { reader: Box::new(reader), writer: Box::new(writer), identity: None })
+    }
+
+    /// Use a Unix-socket connection as is.
+    #[cfg(unix)]
+    fn unix(stream: tokio::net::UnixStream) -> Self {
+        let (reader, writer) = stream.into_split();
+        Self { reader: Box::new(reader), writer: Box::new(writer), identity: None }
+    }
+
+    /// Complete the TLS handshake and pick up the client identity.
+    #[cfg(feature = "tls")]
+    async fn tls(socket: TcpStream, acceptor: &tokio_rustls::TlsAcceptor) -> std::io::Result<Self> {
+        socket.set_nodelay(true)?;
+        let stream = tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(socket))
+            .await
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "TLS handshake timed out"))??;
+        let identity = stream
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .and_then(|cert| certificate_identity(cert.as_ref()));
+        let (reader, writer) = tokio::io::split(stream);
+        Ok(Self { reader: Box::new(reader), writer: Box::new(writer), identity })
+    }
+}
+
+/// Live limit on concurrent client connections.
+///
+/// Shared between the listener and the web API so `max_connections` can be
+/// changed at runtime. Raising the limit admits new clients immediately;
+/// lowering it never drops existing sessions, the listener just stops
+/// accepting until the active count falls below the new limit.
+#[derive(Debug)]
+pub struct ConnectionLimiter {
+    max_connections: AtomicUsize,
+    active: AtomicUsize,
+    notify: Notify,
+    /// Whether the listener socket is bound and accepting connections.
+    accepting: AtomicBool,
+}
+
+impl ConnectionLimiter {
+    /// Create a limiter allowing `max_connections` concurrent connections.
+    pub fn new(max_connections: usize) -> Self {
+        Self {
+            max_connections: AtomicUsize::new(max_connections),
+            active: AtomicUsize::new(0),
+            notify: Notify::new(),
+            accepting: AtomicBool::new(false),
+        }
+    }
+
+    /// Current connection limit.
+    pub fn max_connections(&self) -> usize {
+        self.max_connections.load(Ordering::Acquire)
+    }
+
+    /// Number of connections currently holding a permit.
+    pub fn active_connections(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Whether the listener is bound and accepting connections.
+    pub fn is_accepting(&self) -> bool {
+        self.accepting.load(Ordering::Acquire)
+    }
+
+    fn set_accepting(&self, accepting: bool) {
+        self.accepting.store(accepting, Ordering::Release);
+    }
+
+    /// Change the connection limit. Existing connections are kept.
+    pub fn set_max_connections(&self, max_connections: usize) {
+        self.max_connections.store(max_connections, Ordering::Release);
+        self.notify.notify_one();
+    }
+
+    /// Take a connection slot if one is free.
+    pub fn try_acquire(self: &Arc<Self>) -> Option<ConnectionPermit> {
+        let mut active = self.active.load(Ordering::Acquire);
+        loop {
+            if active >= self.max_connections() {
+                return None;
+            }
+            match self.active.compare_exchange_weak(
+                active,
+                active + 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(ConnectionPermit { limiter: Arc::clone(self) }),
+                Err(current) => active = current,
+            }
+        }
+    }
+
+    /// Wait until a connection slot is free and take it.
+    pub async fn acquire(self: &Arc<Self>) -> ConnectionPermit {
+        let mut logged = false;
+        loop {
+            if let Some(permit) = self.try_acquire() {
+                return permit;
+            }
+            if !logged {
+                warn!(
+                    "Connection limit reached ({}/{}); waiting for a free slot",
+                    self.active_connections(),
+                    self.max_connections()
+                );
+                logged = true;
+            }
+            self.notify.notified().await;
+        }
+    }
+}
+
+/// Connection slot held for the lifetime of a session.
+#[derive(Debug)]
+pub struct ConnectionPermit {
+    limiter: Arc<ConnectionLimiter>,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.limiter.active.fetch_sub(1, Ordering::AcqRel);
+        self.limiter.notify.notify_one();
+    }
+}
+
 /// The main server that listens for connections and spawns sessions.
 pub struct Server {
     config: ServerConfig,
     tuner_pool: Arc<TunerPool>,
     database: DatabaseHandle,
     session_registry: Arc<SessionRegistry>,
+    connection_limiter: Arc<ConnectionLimiter>,
+    /// Set once [`shutdown`](Self::shutdown) starts; stops the accept loop.
+    shutdown_tx: watch::Sender<bool>,
+    /// New-connection rate per client IP.
+    connection_rates: std::sync::Mutex<HashMap<IpAddr, RateLimiter>>,
+    /// Last session ID handed out, shared by all listeners.
+    last_session_id: AtomicU64,
 }
 
 impl Server {
@@ -58,46 +393,209 @@ impl Server {
     pub fn new(config: ServerConfig, session_registry: Arc<SessionRegistry>) -> Self {
         let database = config.database.clone();
         let tuner_config = config.tuner_config.clone();
+        let connection_limiter = Arc::new(ConnectionLimiter::new(config.max_connections));
         Self {
             config,
             tuner_pool: Arc::new(TunerPool::new_with_config(16, tuner_config)),
             database,
             session_registry,
+            connection_limiter,
+            shutdown_tx: watch::channel(false).0,
+            connection_rates: std::sync::Mutex::new(HashMap::new()),
+            last_session_id: AtomicU64::new(0),
         }
     }
 
     /// Run the server, accepting connections until shutdown.
+    ///
+    /// Every listen address is bound up front, so a bad address fails
+    /// startup instead of leaving the server half-listening.
     pub async fn run(&self) -> std::io::Result<()> {
-        let listener = TcpListener::bind(self.config.listen_addr).await?;
-        info!("Server listening on {}", self.config.listen_addr);
+        #[cfg(feature = "tls")]
+        let tls_acceptor = self.config.tls_config.as_ref().map(build_tls_acceptor).transpose()?;
+
+        let mut listeners = Vec::with_capacity(self.config.listen_addrs.len());
+        for addr in &self.config.listen_addrs {
+            let listener = BoundListener::bind(addr).await.map_err(|e| {
+                std::io::Error::new(e.kind(), format!("Failed to listen on {}: {}", addr, e))
+            })?;
+            info!("Server listening on {}", addr);
+            listeners.push((addr, listener));
+        }
+        self.connection_limiter.set_accepting(true);
+
+        #[cfg(feature = "tls")]
+        let loops = listeners
+            .into_iter()
+            .map(|(addr, listener)| self.accept_loop(addr, listener, tls_acceptor.clone()));
+        #[cfg(not(feature = "tls"))]
+        let loops = listeners
+            .into_iter()
+            .map(|(addr, listener)| self.accept_loop(addr, listener));
+        futures::future::join_all(loops).await;
+
+        self.connection_limiter.set_accepting(false);
+        info!("Server no longer accepting connections");
+        Ok(())
+    }
 
-        let mut connection_count = 0u64;
+    /// Accept connections on one listener until shutdown.
+    async fn accept_loop(
+        &self,
+        addr: &ListenAddr,
+        listener: BoundListener,
+        #[cfg(feature = "tls")] tls_acceptor: Option<tokio_rustls::TlsAcceptor>,
+    ) {
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
 
         loop {
-            match listener.accept().await {
-                Ok((socket, addr)) => {
-                    connection_count += 1;
-                    let session_id = connection_count;
+            // Hold off accepting while at the connection limit
+            let accepted = tokio::select! {
+                _ = shutdown_rx.wait_for(|stopping| *stopping) => return,
+                accepted = async {
+                    let permit = self.connection_limiter.acquire().await;
+                    (permit, listener.accept().await)
+                } => accepted,
+            };
+            let (permit, accepted) = accepted;
 
-                    info!("[Session {}] New connection from {}", session_id, addr);
+            match accepted {
+                Ok(Accepted::Tcp(socket, addr)) => {
+                    if !self.allow_connection_rate(addr.ip()) {
+                        warn!("Rejecting connection from {}: more than {} connections per minute",
+                              addr, self.config.connection_rate_per_ip);
+                        reject_connection(socket, "connection rate limit exceeded");
+                        continue;
+                    }
+                    let Some(ip_slot) = self
+                        .session_registry
+                        .try_reserve_ip(addr.ip(), self.config.max_sessions_per_ip)
+                    else {
+                        warn!("Rejecting connection from {}: {} sessions already open from this address",
+                              addr, self.config.max_sessions_per_ip);
+                        reject_connection(socket, "too many sessions from this address");
+                        continue;
+                    };
 
-                    let pool = Arc::clone(&self.tuner_pool);
-                    let database = Arc::clone(&self.database);
-                    let default_tuner = self.config.default_tuner.clone();
-                    let session_registry = Arc::clone(&self.session_registry);
+                    let session_id = self.next_session_id();
+                    info!("[Session {}] New connection from {}", session_id, addr);
 
-                    tokio::spawn(async move {
-                        if let Err(e) = handle_connection(socket, addr, session_id, pool, database, default_tuner, session_registry).await {
-                            error!("[Session {}] Connection error: {}", session_id, e);
+                    #[cfg(feature = "tls")]
+                    let tls_acceptor = tls_acceptor.clone();
+                    let connect = async move {
+                        #[cfg(feature = "tls")]
+                        if let Some(acceptor) = &tls_acceptor {
+                            return ClientConnection::tls(socket, acceptor).await;
                         }
-                        info!("[Session {}] Connection closed", session_id);
-                    });
+                        ClientConnection::plain(socket)
+                    };
+                    self.spawn_session(session_id, addr, permit, Some(ip_slot), self.config.auth_token.clone(), connect);
+                }
+                #[cfg(unix)]
+                Ok(Accepted::Unix(stream)) => {
+                    // Local clients: no per-IP limits, and no TLS since the
+                    // traffic never leaves the host
+                    let session_id = self.next_session_id();
+                    info!("[Session {}] New connection on {}", session_id, addr);
+
+                    let auth_token = self
+                        .config
+                        .auth_token
+                        .clone()
+                        .filter(|_| self.config.unix_socket_auth);
+                    let connect = async move { Ok::<_, std::io::Error>(ClientConnection::unix(stream)) };
+                    self.spawn_session(session_id, UNIX_PEER_ADDR, permit, None, auth_token, connect);
                 }
                 Err(e) => {
-                    error!("Failed to accept connection: {}", e);
+                    error!("Failed to accept connection on {}: {}", addr, e);
+                }
+            }
+        }
+    }
+
+    /// Allocate a session ID, unique across all listeners.
+    fn next_session_id(&self) -> u64 {
+        self.last_session_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Run a session for an accepted connection in the background.
+    ///
+    /// `connect` finishes setting the connection up (e.g. the TLS handshake)
+    /// inside the spawned task so a slow client cannot stall the accept loop.
+    fn spawn_session<F>(
+        &self,
+        session_id: u64,
+        addr: SocketAddr,
+        permit: ConnectionPermit,
+        ip_slot: Option<IpSessionSlot>,
+        auth_token: Option<String>,
+        connect: F,
+    ) where
+        F: std::future::Future<Output = std::io::Result<ClientConnection>> + Send + 'static,
+    {
+        let pool = Arc::clone(&self.tuner_pool);
+        let database = Arc::clone(&self.database);
+        let default_tuner = self.config.default_tuner.clone();
+        let session_registry = Arc::clone(&self.session_registry);
+        let idle_timeout_secs = self.config.session_idle_timeout_secs;
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            let _ip_slot = ip_slot;
+            let result = match connect.await {
+                Ok(connection) => {
+                    if let Some(identity) = &connection.identity {
+                        info!("[Session {}] Client certificate identity: {}", session_id, identity);
+                    }
+                    handle_connection(connection, addr, session_id, pool, database, default_tuner, session_registry, idle_timeout_secs, auth_token).await
                 }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                error!("[Session {}] Connection error: {}", session_id, e);
             }
+            info!("[Session {}] Connection closed", session_id);
+        });
+    }
+
+    /// Take one new connection from the per-IP rate limit.
+    fn allow_connection_rate(&self, ip: IpAddr) -> bool {
+        let per_minute = self.config.connection_rate_per_ip;
+        if per_minute == 0 {
+            return true;
+        }
+        let now = Instant::now();
+        let mut rates = self.connection_rates.lock().unwrap();
+        if rates.len() >= RATE_LIMITER_PRUNE_THRESHOLD {
+            rates.retain(|_, limiter| !limiter.is_idle(now));
         }
+        rates
+            .entry(ip)
+            .or_insert_with(|| RateLimiter::per_minute(per_minute, now))
+            .try_acquire(now)
+    }
+
+    /// Shut the server down gracefully.
+    ///
+    /// Stops accepting connections, asks every session to disconnect with
+    /// reason "server_shutdown", waits up to [`SHUTDOWN_GRACE_PERIOD`] for
+    /// them to flush their metrics and session history, then stops all
+    /// tuner readers so no BonDriver handle outlives the process.
+    pub async fn shutdown(&self) {
+        self.shutdown_tx.send_replace(true);
+
+        let signalled = self.session_registry.request_shutdown_all("server_shutdown").await;
+        info!("Shutting down: disconnecting {} session(s)", signalled);
+        if !self.session_registry.wait_until_empty(SHUTDOWN_GRACE_PERIOD).await {
+            warn!(
+                "{} session(s) still running after {:?}; stopping tuners anyway",
+                self.session_registry.count().await,
+                SHUTDOWN_GRACE_PERIOD
+            );
+        }
+
+        let stopped = self.tuner_pool.shutdown().await;
+        info!("Shutdown complete: stopped {} tuner(s)", stopped);
     }
 
     /// Get a reference to the tuner pool.
@@ -109,25 +607,47 @@ impl Server {
     pub fn database(&self) -> &DatabaseHandle {
         &self.database
     }
+
+    /// Get a reference to the connection limiter.
+    pub fn connection_limiter(&self) -> &Arc<ConnectionLimiter> {
+        &self.connection_limiter
+    }
+}
+
+/// Tell a refused client why and close the connection.
+///
+/// Runs in the background so a slow client cannot stall the accept loop.
+fn reject_connection(mut socket: TcpStream, message: &str) {
+    let frame = encode_server_message(&ServerMessage::Error {
+        error_code: ErrorCode::TooManyConnections.into(),
+        message: message.to_string(),
+    });
+    tokio::spawn(async move {
+        if let Ok(frame) = frame {
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(1), socket.write_all(&frame)).await;
+        }
+        let _ = socket.shutdown().await;
+    });
 }
 
 /// Handle a single client connection.
+#[allow(clippy::too_many_arguments)]
 async fn handle_connection(
-    socket: TcpStream,
+    connection: ClientConnection,
     addr: SocketAddr,
     session_id: u64,
     tuner_pool: Arc<TunerPool>,
     database: DatabaseHandle,
     default_tuner: Option<String>,
     session_registry: Arc<SessionRegistry>,
+    idle_timeout_secs: u64,
+    auth_token: Option<String>,
 ) -> std::io::Result<()> {
-    // Disable Nagle's algorithm for lower latency
-    socket.set_nodelay(true)?;
-
-    // Split the socket into independent read/write halves.
+    // The socket is already split into independent read/write halves.
     // The write half moves to a dedicated writer task so that socket writes
     // (which may block on TCP backpressure) never stall the main select loop.
-    let (reader, writer) = socket.into_split();
+    let ClientConnection { reader, writer, identity } = connection;
+    let span = crate::logging::session_span(session_id, addr, identity.as_deref());
 
     // Per-session write channels.
     // TS data  :  bounded, uses try_send (no blocking), drops oldest on full.
@@ -141,11 +661,11 @@ async fn handle_connection(
 
     // Spawn the writer task – it owns the write-half of the socket.
     let writer_handle = tokio::spawn(
-        session_writer(session_id, writer, ts_write_rx, ctrl_write_rx),
+        session_writer(session_id, writer, ts_write_rx, ctrl_write_rx).instrument(span.clone()),
     );
 
     // Register the session
-    let shutdown_rx = session_registry.register(session_id, addr).await;
+    let shutdown_rx = session_registry.register(session_id, addr, identity.clone()).await;
 
     let mut session = Session::new(
         session_id,
@@ -159,8 +679,11 @@ async fn handle_connection(
         default_tuner,
         Arc::clone(&session_registry),
         shutdown_rx,
+        idle_timeout_secs,
+        auth_token,
+        identity,
     );
-    let result = session.run().await;
+    let result = session.run().instrument(span).await;
 
     // Unregister the session when done
     session_registry.unregister(session_id).await;
@@ -180,7 +703,7 @@ async fn handle_connection(
 /// socket write error occurs.
 async fn session_writer(
     session_id: u64,
-    mut writer: tokio::net::tcp::OwnedWriteHalf,
+    mut writer: SocketWriter,
     mut ts_rx: mpsc::Receiver<Bytes>,
     mut ctrl_rx: mpsc::Receiver<Bytes>,
 ) {
@@ -262,3 +785,260 @@ async fn session_writer(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::NewBonDriver;
+    use recisdb_protocol::{
+        decode_header, decode_server_message, encode_client_message, ClientMessage,
+        ErrorCode, MessageType, ServerMessage, HEADER_SIZE, MAGIC, MAX_FRAME_SIZE, PROTOCOL_VERSION,
+    };
+    use tokio::io::{AsyncRead, AsyncReadExt};
+
+    async fn send<S: AsyncWrite + Unpin>(client: &mut S, msg: ClientMessage) {
+        let frame = encode_client_message(&msg).unwrap();
+        client.write_all(&frame).await.unwrap();
+    }
+
+    async fn recv<S: AsyncRead + Unpin>(client: &mut S) -> ServerMessage {
+        let mut header = [0u8; HEADER_SIZE];
+        client.read_exact(&mut header).await.unwrap();
+        let header = decode_header(&header).unwrap().unwrap();
+        let mut payload = vec![0u8; header.payload_len as usize];
+        client.read_exact(&mut payload).await.unwrap();
+        decode_server_message(header.message_type, Bytes::from(payload)).unwrap()
+    }
+
+    #[test]
+    fn test_listen_addr_parse() {
+        assert_eq!(
+            "0.0.0.0:40070".parse::<ListenAddr>().unwrap(),
+            ListenAddr::Tcp("0.0.0.0:40070".parse().unwrap())
+        );
+        let v6 = "[::]:40070".parse::<ListenAddr>().unwrap();
+        assert_eq!(v6, ListenAddr::Tcp("[::]:40070".parse().unwrap()));
+        assert_eq!(v6.to_string(), "[::]:40070");
+        assert!("localhost".parse::<ListenAddr>().is_err());
+
+        #[cfg(unix)]
+        {
+            let unix = "unix:/run/recisdb-proxy.sock".parse::<ListenAddr>().unwrap();
+            assert_eq!(unix, ListenAddr::Unix("/run/recisdb-proxy.sock".into()));
+            assert_eq!(unix.to_string(), "unix:/run/recisdb-proxy.sock");
+            assert!("unix:".parse::<ListenAddr>().is_err());
+        }
+    }
+
+    #[test]
+    fn test_connection_limiter_resize() {
+        let limiter = Arc::new(ConnectionLimiter::new(1));
+
+        let first = limiter.try_acquire().expect("first slot");
+        assert!(limiter.try_acquire().is_none());
+
+        // Raising the limit admits more immediately
+        limiter.set_max_connections(2);
+        let second = limiter.try_acquire().expect("second slot");
+        assert_eq!(limiter.active_connections(), 2);
+
+        // Lowering keeps existing connections but blocks new ones
+        limiter.set_max_connections(1);
+        assert_eq!(limiter.active_connections(), 2);
+        drop(first);
+        assert!(limiter.try_acquire().is_none());
+        drop(second);
+        assert!(limiter.try_acquire().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_v1_set_channel_is_handled() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_bon_driver(&NewBonDriver::new("BonDriver_Missing.so")).unwrap();
+        let database: DatabaseHandle = Arc::new(tokio::sync::Mutex::new(db));
+        let tuner_pool = Arc::new(TunerPool::new(4));
+        let session_registry = Arc::new(SessionRegistry::new());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, addr) = listener.accept().await.unwrap();
+            let _ = handle_connection(
+                ClientConnection::plain(socket).unwrap(), addr, 1, tuner_pool, database, None,
+                session_registry, 0, None,
+            )
+            .await;
+        });
+
+        let mut client = TcpStream::connect(server_addr).await.unwrap();
+        let exchange = async {
+            send(&mut client, ClientMessage::Hello { version: PROTOCOL_VERSION, features: 0, auth_token: None }).await;
+            assert!(matches!(recv(&mut client).await, ServerMessage::HelloAck { success: true, .. }));
+
+            send(&mut client, ClientMessage::OpenTuner { tuner_path: "BonDriver_Missing.so".into() }).await;
+            assert!(matches!(recv(&mut client).await, ServerMessage::OpenTunerAck { success: true, .. }));
+
+            send(&mut client, ClientMessage::SetChannel { channel: 13, priority: 0, exclusive: false }).await;
+            recv(&mut client).await
+        };
+        let reply = tokio::time::timeout(std::time::Duration::from_secs(30), exchange)
+            .await
+            .expect("session did not answer SetChannel");
+
+        // The driver cannot be loaded here, so the tune itself fails, but it
+        // must be answered by the SetChannel handler rather than rejected.
+        match reply {
+            ServerMessage::SetChannelAck { success, error_code } => {
+                assert!(!success);
+                assert_ne!(error_code, ErrorCode::InvalidState as u16);
+                assert_ne!(error_code, 0xFF00);
+            }
+            other => panic!("unexpected reply to SetChannel: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_token_required() {
+        let db = Database::open_in_memory().unwrap();
+        let database: DatabaseHandle = Arc::new(tokio::sync::Mutex::new(db));
+        let tuner_pool = Arc::new(TunerPool::new(4));
+        let session_registry = Arc::new(SessionRegistry::new());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for id in 1..=3 {
+                let (socket, addr) = listener.accept().await.unwrap();
+                let _ = handle_connection(
+                    ClientConnection::plain(socket).unwrap(), addr, id, Arc::clone(&tuner_pool), Arc::clone(&database), None,
+                    Arc::clone(&session_registry), 0, Some("s3cret".into()),
+                )
+                .await;
+            }
+        });
+
+        let exchange = async {
+            // Skipping Hello is rejected before anything else is served
+            let mut client = TcpStream::connect(server_addr).await.unwrap();
+            send(&mut client, ClientMessage::OpenTuner { tuner_path: "BonDriver_Missing.so".into() }).await;
+            assert!(matches!(recv(&mut client).await, ServerMessage::AuthRequired));
+
+            let mut client = TcpStream::connect(server_addr).await.unwrap();
+            let hello = ClientMessage::Hello { version: PROTOCOL_VERSION, features: 0, auth_token: Some("wrong".into()) };
+            send(&mut client, hello).await;
+            assert!(matches!(recv(&mut client).await, ServerMessage::AuthFailed));
+
+            let mut client = TcpStream::connect(server_addr).await.unwrap();
+            let hello = ClientMessage::Hello { version: PROTOCOL_VERSION, features: 0, auth_token: Some("s3cret".into()) };
+            send(&mut client, hello).await;
+            assert!(matches!(recv(&mut client).await, ServerMessage::HelloAck { success: true, .. }));
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(30), exchange)
+            .await
+            .expect("session did not answer");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_frame_is_rejected() {
+        let db = Database::open_in_memory().unwrap();
+        let database: DatabaseHandle = Arc::new(tokio::sync::Mutex::new(db));
+        let tuner_pool = Arc::new(TunerPool::new(4));
+        let session_registry = Arc::new(SessionRegistry::new());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, addr) = listener.accept().await.unwrap();
+            let _ = handle_connection(
+                ClientConnection::plain(socket).unwrap(), addr, 1, tuner_pool, database, None,
+                session_registry, 0, None,
+            )
+            .await;
+        });
+
+        let mut client = TcpStream::connect(server_addr).await.unwrap();
+        let exchange = async {
+            send(&mut client, ClientMessage::Hello { version: PROTOCOL_VERSION, features: 0, auth_token: None }).await;
+            assert!(matches!(recv(&mut client).await, ServerMessage::HelloAck { success: true, .. }));
+
+            // Only the header is sent; the server must not wait for the payload
+            let mut header = Vec::with_capacity(HEADER_SIZE);
+            header.extend_from_slice(&MAGIC);
+            header.extend_from_slice(&(MAX_FRAME_SIZE + 1).to_le_bytes());
+            header.extend_from_slice(&u16::from(MessageType::Ping).to_le_bytes());
+            client.write_all(&header).await.unwrap();
+
+            match recv(&mut client).await {
+                ServerMessage::Error { error_code, .. } => {
+                    assert_eq!(error_code, ErrorCode::ProtocolError as u16);
+                }
+                other => panic!("unexpected reply to oversized frame: {:?}", other),
+            }
+            let mut rest = Vec::new();
+            client.read_to_end(&mut rest).await.unwrap();
+            assert!(rest.is_empty());
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(30), exchange)
+            .await
+            .expect("session did not reject the frame");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_tcp_and_unix_listeners() {
+        let db = Database::open_in_memory().unwrap();
+        let database: DatabaseHandle = Arc::new(tokio::sync::Mutex::new(db));
+        let tcp_addr = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let socket_path = std::env::temp_dir()
+            .join(format!("recisdb-proxy-test-{}.sock", std::process::id()));
+        let config = ServerConfig {
+            listen_addrs: vec![ListenAddr::Tcp(tcp_addr), ListenAddr::Unix(socket_path.clone())],
+            max_connections: 8,
+            default_tuner: None,
+            database,
+            tuner_config: TunerPoolConfig::default(),
+            session_idle_timeout_secs: 0,
+            auth_token: Some("s3cret".into()),
+            unix_socket_auth: false,
+            max_sessions_per_ip: 0,
+            connection_rate_per_ip: 0,
+            #[cfg(feature = "tls")]
+            tls_config: None,
+        };
+        let session_registry = Arc::new(SessionRegistry::new());
+        let server = Arc::new(Server::new(config, Arc::clone(&session_registry)));
+        let running = tokio::spawn({
+            let server = Arc::clone(&server);
+            async move { server.run().await }
+        });
+
+        let exchange = async {
+            while !server.connection_limiter().is_accepting() {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+
+            let mut tcp = TcpStream::connect(tcp_addr).await.unwrap();
+            let hello = ClientMessage::Hello { version: PROTOCOL_VERSION, features: 0, auth_token: Some("s3cret".into()) };
+            send(&mut tcp, hello).await;
+            assert!(matches!(recv(&mut tcp).await, ServerMessage::HelloAck { success: true, .. }));
+
+            // Unix-socket clients are exempt from the token here
+            let mut unix = tokio::net::UnixStream::connect(&socket_path).await.unwrap();
+            let hello = ClientMessage::Hello { version: PROTOCOL_VERSION, features: 0, auth_token: None };
+            send(&mut unix, hello).await;
+            assert!(matches!(recv(&mut unix).await, ServerMessage::HelloAck { success: true, .. }));
+
+            // Session IDs come from one counter shared by both listeners
+            let mut ids: Vec<u64> = session_registry.get_all().await.iter().map(|s| s.id).collect();
+            ids.sort();
+            assert_eq!(ids, vec![1, 2]);
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(30), exchange)
+            .await
+            .expect("listeners did not answer");
+
+        server.shutdown().await;
+        running.await.unwrap().unwrap();
+        let _ = std::fs::remove_file(&socket_path);
+    }
+}
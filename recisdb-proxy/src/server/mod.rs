@@ -1,8 +1,11 @@
 //! Server implementation for the proxy.
 
+mod coalesce;
 pub mod listener;
 pub mod session;
+pub mod space_map;
+mod throttle;
 
-pub use listener::{Server, ServerConfig};
+pub use listener::{ConnectionLimiter, ListenAddr, Server, ServerConfig};
 #[cfg(feature = "tls")]
 pub use listener::TlsConfig;
@@ -1,31 +1,42 @@
 //! Client session handling.
 
 use std::net::SocketAddr;
-use std::sync::Arc;
-use std::collections::BTreeMap;
-use std::collections::BTreeSet;
+use std::sync::{Arc, Weak};
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 use bytes::{Bytes, BytesMut};
 use log::{debug, error, info, trace, warn};
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::process::{Child, Command};
 use tokio::sync::{broadcast, mpsc};
 
 use recisdb_protocol::{
     broadcast_region::{classify_nid, TerrestrialRegion},
-    decode_client_message, decode_header, encode_server_message, ClientChannelInfo,
-    ClientMessage, ErrorCode, ServerMessage, HEADER_SIZE, PROTOCOL_VERSION,
+    decode_client_message_with, decode_header, encode_compressed_ts_frame,
+    encode_server_message_with, frame_crc32, supported_features, BandType, ClientChannelInfo,
+    ClientMessage, DecodeStatus, ErrorCode, FrameOptions, ProtocolError, ServerMessage, TunerCapacity,
+    TunerEntryKind, TunerListEntry, TunerState, CRC_SIZE,
+    FEATURE_SERVER_PING, FEATURE_TS_COMPRESSION, FEATURE_TUNER_STATUS, HEADER_SIZE, PROTOCOL_VERSION,
 };
 
+use crate::aribb24::sanitize_display_name;
+use crate::database::{DatabaseError, TunerResolution};
+use crate::server::coalesce::TsCoalescer;
 use crate::server::listener::DatabaseHandle;
-use crate::tuner::{ChannelKey, SharedTuner, TunerPool, WarmTunerHandle, ts_analyzer::TsPacketAnalyzer};
+use crate::server::throttle::TokenBucket;
+use crate::server::space_map::{
+    alternate_channels, find_by_remote_key, resolve_space_idx, SpaceMap, VirtualChannelMapping,
+    VirtualSpace,
+};
+use crate::tuner::{ChannelKey, DriverSelectionStrategy, DriverSelector, SharedTuner, TunerPool, WarmTunerHandle, ts_analyzer::TsPacketAnalyzer};
 use crate::tuner::quality_scorer::QualityScorer;
 use crate::tuner::channel_key::ChannelKeySpec;
+use crate::tuner::passive_scanner::{start_passive_scan, PassiveScanConfig};
 use crate::ts_analyzer::service_filter::TsServiceFilter;
-use crate::web::SessionRegistry;
+use crate::web::auth::constant_time_eq;
+use crate::web::{SessionPidStat, SessionRegistry};
 
 /// Session state machine.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -51,6 +62,20 @@ struct TsreplaceRuntimeConfig {
     passthrough_on_error: bool,
 }
 
+/// Error code for a BonDriver reader on `tuner` that failed to start.
+///
+/// A refused SetChannel means no (usable) signal; anything else is a driver
+/// that could not be opened, unless another session took the tuner over.
+fn reader_start_error_code(tuner: &SharedTuner, e: &std::io::Error) -> ErrorCode {
+    if tuner.preempt_reason().is_some() {
+        ErrorCode::Preempted
+    } else if e.kind() == std::io::ErrorKind::AddrNotAvailable {
+        ErrorCode::NoSignal
+    } else {
+        ErrorCode::DriverOpenFailed
+    }
+}
+
 fn fallback_space_label(actual_space: u32) -> String {
     // 最小実装: よくある割当の想定
     // 必要なら後で NID/分類でより正確に推定する
@@ -71,14 +96,6 @@ struct ChannelEntry {
     tsid: u16,            // Transport Stream ID
 }
 
-/// Multiple driver mappings for a single virtual channel
-#[derive(Clone, Debug)]
-struct VirtualChannelMapping {
-    driver_path: String,  // BonDriver DLL path
-    actual_space: u32,    // Physical space on this driver
-    actual_channel: u32,  // Physical channel on this driver
-}
-
 
 /// Capacity of the per-session TS write buffer.
 ///
@@ -93,6 +110,32 @@ const TS_WRITE_BUFFER_CAPACITY: usize = 256;
 /// infrequent. 64 slots is more than sufficient.
 const CTRL_WRITE_BUFFER_CAPACITY: usize = 64;
 
+/// Number of problem PIDs shown in the dashboard session detail.
+const PID_OFFENDER_LIMIT: usize = 8;
+
+/// Upper bound for a client's SetKeepAlive, so a client cannot pin a tuner
+/// indefinitely.
+const MAX_KEEP_ALIVE_OVERRIDE_SECS: u32 = 3600;
+
+/// How long a newly tuned physical channel may stay silent before the
+/// alternate physical channels of the same TS are tried.
+///
+/// Only waited for when alternates exist, since it holds up SetChannelSpace.
+const ALTERNATE_CHANNEL_FIRST_DATA_MS: u64 = 3_000;
+
+/// How long to wait for TS and then for a fresh signal sample when a
+/// minimum signal level is configured for the band.
+const MIN_SIGNAL_CHECK_TIMEOUT_MS: u64 = 3_000;
+
+/// Interval between latency Pings to clients that negotiated
+/// `FEATURE_SERVER_PING`. A Ping still unanswered at the next tick is
+/// replaced, so a late Pong never yields a bogus round-trip time.
+const LATENCY_PING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Read half of a client connection, plain TCP or TLS. `Sync` so that
+/// `&Session` can be held across awaits in the spawned session task.
+pub type SocketReader = Box<dyn AsyncRead + Send + Sync + Unpin>;
+
 /// A client session.
 pub struct Session {
     /// Unique session ID.
@@ -100,8 +143,8 @@ pub struct Session {
     /// Client address.
     #[allow(dead_code)]
     addr: SocketAddr,
-    /// Read half of the TCP socket (write half is in the writer task).
-    socket_reader: OwnedReadHalf,
+    /// Read half of the socket (write half is in the writer task).
+    socket_reader: SocketReader,
     /// Sender for TS data frames (pre-encoded wire bytes) to the writer task.
     /// `try_send` is used to avoid blocking the select loop; when the buffer
     /// is full, oldest entries are drained to stay close to real-time.
@@ -135,6 +178,11 @@ pub struct Session {
     group_driver_paths: Vec<String>,
     /// TS data receiver (when streaming).
     ts_receiver: Option<broadcast::Receiver<Bytes>>,
+    /// Whether the client wants the B25-decoded TS (SetDecode).
+    decode_enabled: bool,
+    /// Raw TS receiver used instead of `ts_receiver` while decoding is off,
+    /// with the tuner it belongs to.
+    raw_receiver: Option<(Weak<SharedTuner>, broadcast::Receiver<Bytes>)>,
     // Session struct に追加
     ts_bytes_sent: u64,
     ts_msgs_sent: u64,
@@ -143,7 +191,7 @@ pub struct Session {
     // ★追加: 仮想space_idx(0..N-1) -> (actual_space, display_name, region_key) のマップをチューナごとにキャッシュ
     // 例: [(0, "地デジ", "宮城"), (0, "地デジ", "福島"), (1, "BS", "BS"), (2, "CS", "CS")]
     // region_key はチャンネルフィルタリング用、display_name は EnumTuningSpace 表示用
-    space_list_cache: HashMap<String, Vec<(u32, String, String)>>,
+    space_list_cache: HashMap<String, Vec<VirtualSpace>>,
     // ★追加: 仮想チャンネル (NID, TSID) -> 複数のドライバー/スペース/チャンネル マッピング
     // 同じNID+TSIDが複数のドライバーに存在する場合、すべてのマッピングを保持
     virtual_channel_mappings: HashMap<String, HashMap<(u16, u16), Vec<VirtualChannelMapping>>>,
@@ -154,7 +202,7 @@ pub struct Session {
     /// Current channel name (for history).
     current_channel_name: Option<String>,
     /// Shutdown receiver for remote disconnect.
-    shutdown_rx: mpsc::Receiver<()>,
+    shutdown_rx: mpsc::Receiver<&'static str>,
     /// TS packet analyzer for this session.
     ts_quality_analyzer: TsPacketAnalyzer,
     /// Carry buffer for outgoing TS alignment (188-byte boundary).
@@ -168,6 +216,8 @@ pub struct Session {
     bytes_since_last: u64,
     interval_packets_total: u64,
     interval_packets_dropped: u64,
+    /// Bitrate measured over the last stats interval.
+    last_bitrate_mbps: f64,
     /// Session start time.
     session_started_at: std::time::Instant,
     /// Signal sampling for average.
@@ -211,6 +261,39 @@ pub struct Session {
     current_sid: Option<u16>,
     /// Additional tsreplace child processes (for chained multi-SID encoding).
     tsreplace_extra_children: Vec<Child>,
+    /// Tuner this session holds LNB power on, if any.
+    lnb_tuner: Option<Arc<SharedTuner>>,
+    /// TsData frames are sent in the compressed format (negotiated in Hello).
+    ts_compression: bool,
+    /// Framing options negotiated in Hello (CRC32 trailer).
+    frame_options: FrameOptions,
+    /// The client decodes TunerStatus messages (negotiated in Hello).
+    tuner_status: bool,
+    /// The client answers latency Pings (negotiated in Hello).
+    server_ping: bool,
+    /// Sequence number of the last latency Ping.
+    ping_seq: u32,
+    /// Latency Ping awaiting its Pong: sequence number and send time.
+    ping_pending: Option<(u32, std::time::Instant)>,
+    /// Key, reader restart count and (space, channel) of the current tuner
+    /// as of the last status check.
+    tuner_status_seen: Option<(ChannelKey, u64, Option<(u32, u32)>)>,
+    /// Drop the session after this long without client traffic (None = never).
+    idle_timeout: Option<std::time::Duration>,
+    /// Last time any bytes were received from the client.
+    last_rx_at: std::time::Instant,
+    /// Paces TsData writes when a bitrate cap is in effect.
+    ts_throttle: Option<TokenBucket>,
+    /// No TS is taken from the tuner until then, while the throttle is in debt.
+    ts_paced_until: Option<tokio::time::Instant>,
+    /// Merges small TS chunks into larger frames (None = send as received).
+    ts_coalescer: Option<TsCoalescer>,
+    /// Idle-close delay requested via SetKeepAlive (None = server default).
+    keep_alive_override: Option<u64>,
+    /// Shared secret the client must send in Hello (None = no auth).
+    auth_token: Option<String>,
+    /// Identity from the TLS client certificate; subject to `client_acl`.
+    client_identity: Option<String>,
 }
 
 impl Session {
@@ -222,7 +305,7 @@ impl Session {
     pub fn new(
         id: u64,
         addr: SocketAddr,
-        socket_reader: OwnedReadHalf,
+        socket_reader: SocketReader,
         ts_write_tx: mpsc::Sender<Bytes>,
         ctrl_write_tx: mpsc::Sender<Bytes>,
         writer_handle: tokio::task::JoinHandle<()>,
@@ -230,7 +313,10 @@ impl Session {
         database: DatabaseHandle,
         default_tuner: Option<String>,
         session_registry: Arc<SessionRegistry>,
-        shutdown_rx: mpsc::Receiver<()>,
+        shutdown_rx: mpsc::Receiver<&'static str>,
+        idle_timeout_secs: u64,
+        auth_token: Option<String>,
+        client_identity: Option<String>,
     ) -> Self {
         Self {
             id,
@@ -251,6 +337,8 @@ impl Session {
             current_group_name: None,
             group_driver_paths: Vec::new(),
             ts_receiver: None,
+            decode_enabled: true,
+            raw_receiver: None,
             ts_bytes_sent: 0,
             ts_msgs_sent: 0,
             last_ts_log: std::time::Instant::now(),
@@ -270,6 +358,7 @@ impl Session {
             bytes_since_last: 0,
             interval_packets_total: 0,
             interval_packets_dropped: 0,
+            last_bitrate_mbps: 0.0,
             session_started_at: std::time::Instant::now(),
             signal_samples: 0,
             signal_level_sum: 0.0,
@@ -293,7 +382,70 @@ impl Session {
             current_tsid: None,
             current_sid: None,
             tsreplace_extra_children: Vec::new(),
+            lnb_tuner: None,
+            ts_compression: false,
+            frame_options: FrameOptions::default(),
+            tuner_status: false,
+            server_ping: false,
+            ping_seq: 0,
+            ping_pending: None,
+            tuner_status_seen: None,
+            idle_timeout: Self::idle_timeout_from_secs(idle_timeout_secs),
+            last_rx_at: std::time::Instant::now(),
+            ts_throttle: None,
+            ts_paced_until: None,
+            ts_coalescer: None,
+            keep_alive_override: None,
+            auth_token,
+            client_identity,
+        }
+    }
+
+    fn idle_timeout_from_secs(secs: u64) -> Option<std::time::Duration> {
+        (secs > 0).then(|| std::time::Duration::from_secs(secs))
+    }
+
+    /// Apply the idle timeout override saved via the Web API, if any.
+    async fn load_idle_timeout(&mut self) {
+        let db = self.database.lock().await;
+        match db.get_server_session_idle_timeout() {
+            Ok(Some(secs)) => self.idle_timeout = Self::idle_timeout_from_secs(secs),
+            Ok(None) => {}
+            Err(e) => warn!("[Session {}] Failed to load session idle timeout: {}", self.id, e),
+        }
+    }
+
+    /// Publish the server default bitrate cap for this session and apply it.
+    async fn load_max_bitrate(&mut self) {
+        let default = {
+            let db = self.database.lock().await;
+            db.get_server_max_bitrate().unwrap_or_else(|e| {
+                warn!("[Session {}] Failed to load bitrate cap: {}", self.id, e);
+                None
+            })
+        };
+        self.session_registry.update_default_max_bitrate(self.id, default).await;
+        self.refresh_ts_throttle().await;
+    }
+
+    /// Pick up bitrate cap changes made via the Web API.
+    async fn refresh_ts_throttle(&mut self) {
+        let cap = self.session_registry.get_effective_max_bitrate(self.id).await;
+        let current = self.ts_throttle.as_ref().map(TokenBucket::mbps);
+        if cap == current {
+            return;
         }
+        match cap {
+            Some(mbps) => info!("[Session {}] TS bitrate capped at {} Mbps", self.id, mbps),
+            None => info!("[Session {}] TS bitrate cap removed", self.id),
+        }
+        self.ts_throttle = cap.map(|mbps| TokenBucket::new(mbps, std::time::Instant::now()));
+    }
+
+    /// Whether the client has sent nothing for longer than the idle timeout.
+    fn is_idle_expired(&self) -> bool {
+        self.idle_timeout
+            .is_some_and(|timeout| self.last_rx_at.elapsed() >= timeout)
     }
 
     async fn load_tsreplace_runtime_config(&self) -> TsreplaceRuntimeConfig {
@@ -743,6 +895,31 @@ impl Session {
         }
     }
 
+    /// Count running tuner instances on a group driver.
+    ///
+    /// The session's own tuner is not counted when the channel switch will
+    /// release it (sole subscriber).
+    async fn running_instances(
+        &self,
+        keys: &[ChannelKey],
+        driver_path: &str,
+        old_tuner_will_free_slot: bool,
+        old_tuner_key: Option<&ChannelKey>,
+    ) -> i32 {
+        let mut driver_instances = 0i32;
+        for k in keys.iter().filter(|k| k.tuner_path == driver_path) {
+            if old_tuner_will_free_slot && old_tuner_key == Some(k) {
+                continue;
+            }
+            if let Some(tuner) = self.tuner_pool.get(k).await {
+                if tuner.is_running() {
+                    driver_instances += 1;
+                }
+            }
+        }
+        driver_instances
+    }
+
     async fn stop_warm_tuner(&mut self) {
         if let Some(warm) = self.warm_tuner.take() {
             warm.shutdown().await;
@@ -782,6 +959,12 @@ impl Session {
                    self.id, tuner_path);
             return;
         }
+        // The startup warm tuner already holds this driver open; it is
+        // claimed in start_reader_with_warm.
+        if self.tuner_pool.has_startup_warm(tuner_path).await {
+            self.stop_warm_tuner().await;
+            return;
+        }
 
         self.stop_warm_tuner().await;
 
@@ -790,6 +973,49 @@ impl Session {
         self.warm_tuner = Some(warm);
     }
 
+    /// Warm the best-scored group driver that has no running reader, when
+    /// `prewarm_group` is enabled; otherwise drop any warm tuner.
+    ///
+    /// Busy drivers are skipped for the same reason as in
+    /// `maybe_start_warm_tuner`, so a warm tuner never takes an instance
+    /// slot from a driver that is already serving sessions.
+    async fn maybe_start_group_warm_tuner(&mut self) {
+        let config = self.tuner_pool.config().await;
+        if !config.prewarm_enabled || !config.prewarm_group {
+            self.stop_warm_tuner().await;
+            return;
+        }
+
+        let keys = self.tuner_pool.keys().await;
+        let mut best: Option<(String, f64)> = None;
+        for path in self.group_driver_paths.clone() {
+            if self.running_instances(&keys, &path, false, None).await > 0 {
+                continue;
+            }
+            let score = {
+                let db = self.database.lock().await;
+                db.get_driver_quality_score_by_path(&path).unwrap_or(1.0)
+            };
+            if best.as_ref().map_or(true, |(_, best_score)| score > *best_score) {
+                best = Some((path, score));
+            }
+        }
+
+        match best {
+            Some((path, score)) => {
+                if self.warm_tuner_path.as_deref() == Some(path.as_str()) {
+                    return;
+                }
+                debug!("[Session {}] Prewarming group driver {} (score {:.2})", self.id, path, score);
+                self.maybe_start_warm_tuner(&path).await;
+            }
+            None => {
+                debug!("[Session {}] No idle group driver to prewarm", self.id);
+                self.stop_warm_tuner().await;
+            }
+        }
+    }
+
     /// After a channel switch failure, attempt to restore the previous channel so the
     /// client (TVTest, etc.) keeps receiving TS data instead of being cut off.
     ///
@@ -861,7 +1087,7 @@ impl Session {
                                 info!("[Session {}] Evicting idle tuner {:?} to make room for fallback driver {}",
                                       self.id, gk, fallback_path);
                                 self.tuner_pool.cancel_idle_close(gk).await;
-                                other.stop_reader().await;
+                                other.preempt_reader(format!("evicted by session {} (idle tuner)", self.id)).await;
                                 self.tuner_pool.remove(gk).await;
                                 freed = true;
                                 break;
@@ -918,6 +1144,87 @@ impl Session {
         None
     }
 
+    /// Try other physical channels of the same driver carrying the wanted TS
+    /// (terrestrial relay stations) after the selected one delivered nothing.
+    ///
+    /// A reader that produces no TS within `ALTERNATE_CHANNEL_FIRST_DATA_MS`
+    /// is stopped again so the next alternate can use the instance slot.
+    /// Returns the tuner with its (space, channel) on success.
+    async fn try_alternate_channels(
+        &mut self,
+        tuner_path: &str,
+        alternates: &[(u32, u32)],
+    ) -> Option<(Arc<SharedTuner>, u32, u32)> {
+        for &(space, channel) in alternates {
+            let key = ChannelKey::space_channel(tuner_path, space, channel);
+            let tuner = match self.tuner_pool.get_or_create(key.clone(), 2, || async { Ok(()) }).await {
+                Ok(tuner) => tuner,
+                Err(e) => {
+                    warn!("[Session {}] Alternate channel {:?} creation failed: {}", self.id, key, e);
+                    continue;
+                }
+            };
+            if tuner.is_running() {
+                info!("[Session {}] Alternate channel {:?} already running, reusing", self.id, key);
+                return Some((tuner, space, channel));
+            }
+
+            info!("[Session {}] Trying alternate physical channel on {} (space {}, ch {})",
+                  self.id, tuner_path, space, channel);
+            match self.start_reader_with_warm(Arc::clone(&tuner), tuner_path.to_string(), space, channel).await {
+                Ok(()) if tuner.wait_first_data(ALTERNATE_CHANNEL_FIRST_DATA_MS).await => {
+                    return Some((tuner, space, channel));
+                }
+                Ok(()) => {
+                    warn!("[Session {}] Alternate channel {:?} delivered no TS", self.id, key);
+                    tuner.stop_reader().await;
+                }
+                Err(e) => {
+                    warn!("[Session {}] Alternate channel {:?} reader start failed: {}", self.id, key, e);
+                }
+            }
+            if !tuner.is_running() && !tuner.has_subscribers() {
+                self.tuner_pool.remove(&key).await;
+            }
+        }
+        None
+    }
+
+    /// Move the session onto a tuner found by `try_alternate_channels` (or a
+    /// fallback driver after a signal check) and acknowledge the SetChannelSpace.
+    async fn finish_alternate_channel(
+        &mut self,
+        tuner: Arc<SharedTuner>,
+        tuner_path: &str,
+        space: u32,
+        channel: u32,
+    ) -> std::io::Result<()> {
+        info!("[Session {}] Switching to {} (space {}, ch {})",
+              self.id, tuner_path, space, channel);
+        self.current_tuner = Some(tuner.clone());
+        tuner.notify_channel_change();
+        if self.state == SessionState::Streaming {
+            self.ts_receiver = Some(tuner.subscribe());
+        }
+        self.restart_tsreplace_pipeline_if_streaming().await;
+
+        let channel_info = format!("Space {}, Ch {}", space, channel);
+        self.session_registry.update_channel(self.id, Some(channel_info.clone())).await;
+        self.current_channel_info = Some(channel_info);
+        let (ch_name, ch_nid, ch_tsid, ch_sid) = {
+            let db = self.database.lock().await;
+            match db.get_channel_by_physical(tuner_path, space, channel) {
+                Ok(Some(rec)) => (rec.channel_name.or(rec.raw_name), Some(rec.nid), Some(rec.tsid), Some(rec.sid)),
+                _ => (None, None, None, None),
+            }
+        };
+        self.session_registry.update_channel_name(self.id, ch_name.clone()).await;
+        self.session_registry.update_channel_ids(self.id, ch_nid, ch_tsid, ch_sid).await;
+        self.update_service_filter_for_sid(ch_nid, ch_tsid, ch_sid);
+        self.current_channel_name = ch_name;
+        self.send_message(ServerMessage::SetChannelSpaceAck { success: true, error_code: 0 }).await
+    }
+
     async fn start_reader_with_warm(
         &mut self,
         tuner: Arc<SharedTuner>,
@@ -938,7 +1245,14 @@ impl Session {
         // data flowing), then dropped — the reader loop runs without it.
         let _dll_guard = self.tuner_pool.acquire_dll_init_lock(&tuner_path).await;
 
-        if !config.prewarm_enabled {
+        // A tuner warmed at server startup goes to whichever session tunes
+        // its driver first, exclusive requests included.
+        if let Some(warm) = self.tuner_pool.take_startup_warm(&tuner_path).await {
+            info!("[Session {}] Using startup warm tuner for {}", self.id, tuner_path);
+            self.stop_warm_tuner().await;
+            self.warm_tuner = Some(warm);
+            self.warm_tuner_path = Some(tuner_path.clone());
+        } else if !config.prewarm_enabled {
             self.stop_warm_tuner().await;
             return tuner
                 .start_bondriver_reader(tuner_path, space, channel, startup_config)
@@ -994,7 +1308,8 @@ impl Session {
             Err(_) => return vec![],
         };
 
-        let mut uniq: BTreeMap<u32, (String, u16, u16)> = BTreeMap::new();
+        let mut seen: HashSet<u32> = HashSet::new();
+        let mut entries: Vec<ChannelEntry> = Vec::new();
 
         for (ch, bd_opt) in all {
             let Some(bd) = bd_opt else { continue; };
@@ -1007,12 +1322,12 @@ impl Session {
                 .or(ch.ts_name.clone())
                 .unwrap_or_else(|| format!("CH{}", ch.channel));
 
-            uniq.entry(ch.channel).or_insert((name, ch.nid as u16, ch.tsid as u16));
+            if seen.insert(ch.channel) {
+                entries.push(ChannelEntry { bon_channel: ch.channel, name, nid: ch.nid as u16, tsid: ch.tsid as u16 });
+            }
         }
 
-        uniq.into_iter()
-            .map(|(bon_channel, (name, nid, tsid))| ChannelEntry { bon_channel, name, nid, tsid })
-            .collect()
+        entries
     }
 
     async fn ensure_channel_map(&mut self, space: u32) -> Vec<ChannelEntry> {
@@ -1033,7 +1348,8 @@ impl Session {
                 },
             };
 
-            let mut uniq: BTreeMap<u32, (String, u16, u16)> = BTreeMap::new();
+            let mut seen: HashSet<u32> = HashSet::new();
+            let mut entries: Vec<ChannelEntry> = Vec::new();
 
             for (ch, bd_opt) in all {
                 let Some(bd) = bd_opt else { continue; };
@@ -1052,12 +1368,12 @@ impl Session {
                     .or(ch.ts_name.clone())
                     .unwrap_or_else(|| format!("CH{}", bch));
 
-                uniq.entry(bch).or_insert((name, ch.nid as u16, ch.tsid as u16));
+                if seen.insert(bch) {
+                    entries.push(ChannelEntry { bon_channel: bch, name, nid: ch.nid as u16, tsid: ch.tsid as u16 });
+                }
             }
 
-            uniq.into_iter()
-                .map(|(bon_channel, (name, nid, tsid))| ChannelEntry { bon_channel, name, nid, tsid })
-                .collect::<Vec<_>>()
+            entries
         } else {
             // Single tuner mode
             let tuner_path = self
@@ -1083,7 +1399,8 @@ impl Session {
                 },
             };
 
-            let mut uniq: BTreeMap<u32, (String, u16, u16)> = BTreeMap::new();
+            let mut seen: HashSet<u32> = HashSet::new();
+            let mut entries: Vec<ChannelEntry> = Vec::new();
 
             for (ch, bd_opt) in all {
                 let Some(bd) = bd_opt else { continue; };
@@ -1099,12 +1416,12 @@ impl Session {
                     .or(ch.ts_name.clone())
                     .unwrap_or_else(|| format!("CH{}", bch));
 
-                uniq.entry(bch).or_insert((name, ch.nid as u16, ch.tsid as u16));
+                if seen.insert(bch) {
+                    entries.push(ChannelEntry { bon_channel: bch, name, nid: ch.nid as u16, tsid: ch.tsid as u16 });
+                }
             }
 
-            uniq.into_iter()
-                .map(|(bon_channel, (name, nid, tsid))| ChannelEntry { bon_channel, name, nid, tsid })
-                .collect::<Vec<_>>()
+            entries
         };
 
         debug!("[Session {}] ensure_channel_map: final channels for space {}: {} items", self.id, space, map.len());
@@ -1137,7 +1454,8 @@ impl Session {
         };
 
         // NID+TSIDをキーにして重複排除（異なるBonDriverが同じNID+TSIDに違うbon_channelを使う場合の対策）
-        let mut uniq: BTreeMap<(u16, u16), (u32, String)> = BTreeMap::new();
+        let mut seen: HashSet<(u16, u16)> = HashSet::new();
+        let mut entries: Vec<ChannelEntry> = Vec::new();
 
         for (ch, bd_opt) in all {
             let Some(bd) = bd_opt else { continue; };
@@ -1154,12 +1472,13 @@ impl Session {
 
             // Filter by region/broadcast type
             // For terrestrial, filter by TerrestrialRegion display_name (広域圏: "関東", "東北", etc.)
-            // For BS/CS, filter by broadcast type string ("BS" or "CS")
+            // For BS/CS/CATV, filter by broadcast type string ("BS", "CS" or "CATV")
             let ch_matches = {
                 let (btype, region) = classify_nid(ch.nid as u16);
                 match btype {
                     recisdb_protocol::types::BroadcastType::BS => region_name == "BS",
                     recisdb_protocol::types::BroadcastType::CS => region_name == "CS",
+                    recisdb_protocol::types::BroadcastType::Catv => region_name == "CATV",
                     recisdb_protocol::types::BroadcastType::Terrestrial => {
                         let ch_region = region.map(|r| match r {
                             TerrestrialRegion::Unknown(_) => "Unknown",
@@ -1181,12 +1500,12 @@ impl Session {
                 .or(ch.ts_name.clone())
                 .unwrap_or_else(|| format!("CH{}", bch));
 
-            uniq.entry(nid_tsid).or_insert((bch, name));
+            if seen.insert(nid_tsid) {
+                entries.push(ChannelEntry { bon_channel: bch, name, nid: nid_tsid.0, tsid: nid_tsid.1 });
+            }
         }
 
-        uniq.into_iter()
-            .map(|((nid, tsid), (bon_channel, name))| ChannelEntry { bon_channel, name, nid, tsid })
-            .collect::<Vec<_>>()
+        entries
     }
 
     fn clear_caches(&mut self) {
@@ -1206,15 +1525,25 @@ impl Session {
     /// チューナに紐づく「実スペース一覧」を DB から構築してキャッシュする
     async fn ensure_space_list(&mut self) -> Vec<u32> {
         // If group is set, get spaces from all group drivers
-        if !self.group_driver_paths.is_empty() {
+        let (cache_key, driver_paths) = if !self.group_driver_paths.is_empty() {
             let cache_key = format!("group_{}", self.current_group_name.as_ref().unwrap_or(&"unknown".to_string()));
-            
-            if let Some(v) = self.space_list_cache.get(&cache_key) {
-                trace!("[Session {}] ensure_space_list: using cache for group {} (spaces: {:?})", 
-                    self.id, self.current_group_name.as_ref().unwrap_or(&"unknown".to_string()), v);
-                return v.iter().map(|(actual_space, _, _)| *actual_space).collect();
+            (cache_key, self.group_driver_paths.clone())
+        } else {
+            // Single tuner mode
+            let tuner_path = self.current_or_default_tuner_path();
+            if tuner_path.is_empty() {
+                debug!("[Session {}] ensure_space_list: tuner_path is empty", self.id);
+                return Vec::new();
             }
+            (tuner_path.clone(), vec![tuner_path])
+        };
+
+        if let Some(v) = self.space_list_cache.get(&cache_key) {
+            trace!("[Session {}] ensure_space_list: using cache for {} (spaces: {:?})", self.id, cache_key, v);
+            return v.iter().map(|(actual_space, _, _)| *actual_space).collect();
+        }
 
+        let (all, space_cap) = {
             let db = self.database.lock().await;
             let all = match db.get_all_channels_with_drivers() {
                 Ok(v) => v,
@@ -1223,242 +1552,33 @@ impl Session {
                     Vec::new()
                 },
             };
-
-            // Build unique (space, region) pairs based on NID + TSID to eliminate duplicates
-            // But record ALL mappings (driver, space, channel) for each NID+TSID combination
-            let mut nid_tsid_seen: BTreeSet<(u16, u16)> = BTreeSet::new();
-            let mut region_seen: BTreeSet<String> = BTreeSet::new();  // For BS/CS deduplication
-            let mut space_region_names: HashMap<String, (u32, String)> = HashMap::new();  // region_name -> (space, name)
-            let mut nid_tsid_mappings: HashMap<(u16, u16), Vec<VirtualChannelMapping>> = HashMap::new();
-            
-            for (ch, bd_opt) in all {
-                let Some(bd) = bd_opt else { continue; };
-                // Check if this driver belongs to the group
-                if !self.group_driver_paths.contains(&bd.dll_path) {
-                    continue;
-                }
-                if !ch.is_enabled { continue; }
-                
-                let nid_tsid = (ch.nid as u16, ch.tsid as u16);
-                
-                // Record this mapping for this NID+TSID (allow multiples from different drivers)
-                nid_tsid_mappings
-                    .entry(nid_tsid)
-                    .or_insert_with(Vec::new)
-                    .push(VirtualChannelMapping {
-                        driver_path: bd.dll_path.clone(),
-                        actual_space: ch.space,
-                        actual_channel: ch.channel as u32,
-                    });
-                
-                // For display purposes, only register once per NID+TSID
-                if nid_tsid_seen.contains(&nid_tsid) {
-                    continue;
-                }
-                nid_tsid_seen.insert(nid_tsid);
-                
-                // Get region name: TerrestrialRegion display_name for terrestrial (広域圏), "BS"/"CS" for satellite
-                let (btype, terrestrial_region) = classify_nid(ch.nid as u16);
-                let is_terrestrial = matches!(btype, recisdb_protocol::types::BroadcastType::Terrestrial)
-                    && terrestrial_region.as_ref().map_or(false, |r| !matches!(r, TerrestrialRegion::Unknown(_)));
-                let region_name = match btype {
-                    recisdb_protocol::types::BroadcastType::BS => "BS".to_string(),
-                    recisdb_protocol::types::BroadcastType::CS => "CS".to_string(),
-                    recisdb_protocol::types::BroadcastType::Terrestrial => {
-                        terrestrial_region.as_ref().map(|r| match r {
-                            TerrestrialRegion::Unknown(_) => "Unknown".to_string(),
-                            _ => r.display_name().to_string(),
-                        }).unwrap_or_else(|| "Unknown".to_string())
-                    }
-                };
-                debug!("[Session {}] NID=0x{:04X} btype={:?} region={}", 
-                    self.id, ch.nid, btype, region_name);
-
-                
-                // For all regions, only register once per region name (prevent duplicates)
-                // This applies to both BS/CS and terrestrial
-                if region_seen.contains(&region_name) {
-                    debug!("[Session {}] Skipping duplicate region: {}", self.id, region_name);
-                    continue;
-                }
-                region_seen.insert(region_name.clone());
-                
-                // Build display name based on region
-                let name = if is_terrestrial {
-                    format!("地デジ ({})", region_name)
-                } else {
-                    region_name.clone()
-                };
-                
-                // For BS/CS, use the actual space from the first driver we see
-                // For terrestrial, use actual space as-is
-                // This ensures each region appears only once in the list
-                space_region_names.insert(region_name, (ch.space, name));
-            }
-
-            // Build the final list with proper sorting
-            // Order: 地上波 (terrestrial by region) -> BS -> CS
-            // Tuple: (actual_space, display_name, region_key)
-            let mut terrestrial_spaces: Vec<(u32, String, String)> = Vec::new();
-            let mut bs_space: Option<(u32, String, String)> = None;
-            let mut cs_space: Option<(u32, String, String)> = None;
-            
-            for (region, (space, name)) in space_region_names {
-                if region == "BS" {
-                    bs_space = Some((space, name, region));
-                } else if region == "CS" {
-                    cs_space = Some((space, name, region));
-                } else {
-                    terrestrial_spaces.push((space, name, region));
-                }
-            }
-            
-            // Sort terrestrial spaces by region key
-            terrestrial_spaces.sort_by(|a, b| a.2.cmp(&b.2));
-            
-            // Build final list: terrestrial first, then BS, then CS
-            let mut list: Vec<(u32, String, String)> = terrestrial_spaces;
-            if let Some(bs) = bs_space {
-                list.push(bs);
-            }
-            if let Some(cs) = cs_space {
-                list.push(cs);
-            }
-            debug!("[Session {}] ensure_space_list: final spaces for group {}: {:?}", 
-                self.id, self.current_group_name.as_ref().unwrap_or(&"unknown".to_string()), list);
-            self.space_list_cache.insert(cache_key.clone(), list.clone());
-            
-            // Also cache the NID+TSID mappings
-            let mut group_mappings = HashMap::new();
-            for (nid_tsid, mappings) in nid_tsid_mappings {
-                group_mappings.insert(nid_tsid, mappings);
-            }
-            self.virtual_channel_mappings.insert(cache_key, group_mappings);
-            
-            return list.iter().map(|(actual_space, _, _)| *actual_space).collect();
-        }
-
-        // Single tuner mode
-        let tuner_path = self.current_or_default_tuner_path();
-        if tuner_path.is_empty() {
-            debug!("[Session {}] ensure_space_list: tuner_path is empty", self.id);
-            return Vec::new();
-        }
-        if let Some(v) = self.space_list_cache.get(&tuner_path) {
-            trace!("[Session {}] ensure_space_list: using cache for {} (spaces: {:?})", self.id, tuner_path, v);
-            return v.iter().map(|(actual_space, _, _)| *actual_space).collect();
-        }
-
-        let db = self.database.lock().await;
-        let all = match db.get_all_channels_with_drivers() {
-            Ok(v) => v,
-            Err(e) => {
-                debug!("[Session {}] ensure_space_list: failed to get channels: {}", self.id, e);
-                Vec::new()
-            },
-        };
-
-        // Build unique (space, region) pairs based on NID + TSID to eliminate duplicates
-        // But record ALL mappings (driver, space, channel) for each NID+TSID combination
-        let mut nid_tsid_seen: BTreeSet<(u16, u16)> = BTreeSet::new();
-        let mut region_seen: BTreeSet<String> = BTreeSet::new();  // For BS/CS deduplication
-        let mut space_region_names: HashMap<String, (u32, String)> = HashMap::new();  // region_name -> (space, name)
-        let mut nid_tsid_mappings: HashMap<(u16, u16), Vec<VirtualChannelMapping>> = HashMap::new();
-        
-        for (ch, bd_opt) in all {
-            let Some(bd) = bd_opt else { continue; };
-            if bd.dll_path != tuner_path { continue; }
-            if !ch.is_enabled { continue; }
-            
-            let nid_tsid = (ch.nid as u16, ch.tsid as u16);
-            
-            // Record this mapping for this NID+TSID (allow multiples)
-            nid_tsid_mappings
-                .entry(nid_tsid)
-                .or_insert_with(Vec::new)
-                .push(VirtualChannelMapping {
-                    driver_path: bd.dll_path.clone(),
-                    actual_space: ch.space,
-                    actual_channel: ch.channel as u32,
-                });
-            
-            // For display purposes, only register once per NID+TSID
-            if nid_tsid_seen.contains(&nid_tsid) {
-                continue;
-            }
-            nid_tsid_seen.insert(nid_tsid);
-            
-            // Get region name: TerrestrialRegion display_name for terrestrial (広域圏), "BS"/"CS" for satellite
-            let (btype, terrestrial_region) = classify_nid(ch.nid as u16);
-            let is_terrestrial = matches!(btype, recisdb_protocol::types::BroadcastType::Terrestrial)
-                && terrestrial_region.as_ref().map_or(false, |r| !matches!(r, TerrestrialRegion::Unknown(_)));
-            let region_name = match btype {
-                recisdb_protocol::types::BroadcastType::BS => "BS".to_string(),
-                recisdb_protocol::types::BroadcastType::CS => "CS".to_string(),
-                recisdb_protocol::types::BroadcastType::Terrestrial => {
-                    terrestrial_region.as_ref().map(|r| match r {
-                        TerrestrialRegion::Unknown(_) => "Unknown".to_string(),
-                        _ => r.display_name().to_string(),
-                    }).unwrap_or_else(|| "Unknown".to_string())
+            let space_cap = match db.get_space_cap_config() {
+                Ok((Some(region), true)) => Some(region),
+                Ok(_) => None,
+                Err(e) => {
+                    debug!("[Session {}] ensure_space_list: failed to get space cap config: {}", self.id, e);
+                    None
                 }
             };
-            debug!("[Session {}] NID=0x{:04X} btype={:?} region={}", 
-                self.id, ch.nid, btype, region_name);
-            
-            // For all regions, only register once per region name (prevent duplicates)
-            // This applies to both BS/CS and terrestrial
-            if region_seen.contains(&region_name) {
-                debug!("[Session {}] Skipping duplicate region: {}", self.id, region_name);
-                continue;
-            }
-            region_seen.insert(region_name.clone());
-            
-            // Build display name based on region
-            let name = if is_terrestrial {
-                format!("地デジ ({})", region_name)
-            } else {
-                region_name.clone()
-            };
-            
-            space_region_names.insert(region_name, (ch.space, name));
-        }
+            (all, space_cap)
+        };
 
-        // Build the final list with proper sorting
-        // Order: 地上波 (terrestrial by region) -> BS -> CS
-        // Tuple: (actual_space, display_name, region_key)
-        let mut terrestrial_spaces: Vec<(u32, String, String)> = Vec::new();
-        let mut bs_space: Option<(u32, String, String)> = None;
-        let mut cs_space: Option<(u32, String, String)> = None;
-        
-        for (region, (space, name)) in space_region_names {
-            if region == "BS" {
-                bs_space = Some((space, name, region));
-            } else if region == "CS" {
-                cs_space = Some((space, name, region));
-            } else {
-                terrestrial_spaces.push((space, name, region));
+        let mut map = SpaceMap::build(all, &driver_paths);
+        if let Some(region) = space_cap {
+            if map.restrict_to_region(&region) {
+                debug!("[Session {}] ensure_space_list: limited terrestrial spaces to {}", self.id, region);
+            } else if !map.spaces.iter().any(|(_, _, key)| *key == region) {
+                warn!("[Session {}] Preferred region '{}' has no channels; exposing all spaces", self.id, region);
             }
         }
-        
-        // Sort terrestrial spaces by region key
-        terrestrial_spaces.sort_by(|a, b| a.2.cmp(&b.2));
-        
-        // Build final list: terrestrial first, then BS, then CS
-        let mut list: Vec<(u32, String, String)> = terrestrial_spaces;
-        if let Some(bs) = bs_space {
-            list.push(bs);
-        }
-        if let Some(cs) = cs_space {
-            list.push(cs);
-        }
+        debug!("[Session {}] ensure_space_list: final spaces for {}: {:?}", self.id, cache_key, map.spaces);
 
-        debug!("[Session {}] ensure_space_list: final spaces for {}: {:?}", self.id, tuner_path, list);
-        
         // Cache both space list and NID+TSID mappings
-        self.space_list_cache.insert(tuner_path.clone(), list.clone());
-        self.virtual_channel_mappings.insert(tuner_path, nid_tsid_mappings);
-        
-        list.iter().map(|(actual_space, _, _)| *actual_space).collect()
+        let actual_spaces = map.actual_spaces();
+        self.space_list_cache.insert(cache_key.clone(), map.spaces);
+        self.virtual_channel_mappings.insert(cache_key, map.mappings);
+
+        actual_spaces
     }
 
     /// TVTest が渡す仮想 space_idx を、DBの実 space へ変換
@@ -1472,7 +1592,7 @@ impl Session {
     /// NOT the display name (which may differ, e.g., "地デジ").
     async fn map_space_idx_to_actual_with_region(&mut self, space_idx: u32) -> Option<(u32, String)> {
         let list = self.get_space_list_with_names().await;
-        list.get(space_idx as usize).map(|(actual_space, _display_name, region_key)| (*actual_space, region_key.clone()))
+        resolve_space_idx(&list, space_idx)
     }
 
     /// Get space list with names (for internal use).
@@ -1509,6 +1629,7 @@ impl Session {
             self.current_channel_info.as_deref(),
             self.current_channel_name.as_deref(),
             started_at,
+            self.client_identity.as_deref(),
         ) {
             self.session_history_id = Some(db);
         } else {
@@ -1526,13 +1647,39 @@ impl Session {
         );
         reader_alive_check.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
+        // Sessions whose client vanished without a FIN (e.g. the PC went to
+        // sleep) would otherwise hold their tuner forever.
+        self.load_idle_timeout().await;
+        self.load_max_bitrate().await;
+        let idle_check_period = self
+            .idle_timeout
+            .map(|timeout| (timeout / 4).clamp(std::time::Duration::from_secs(1), std::time::Duration::from_secs(30)))
+            .unwrap_or(std::time::Duration::from_secs(30));
+        let mut idle_check = tokio::time::interval_at(
+            tokio::time::Instant::now() + idle_check_period,
+            idle_check_period,
+        );
+        idle_check.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut latency_ping = tokio::time::interval_at(
+            tokio::time::Instant::now() + LATENCY_PING_INTERVAL,
+            LATENCY_PING_INTERVAL,
+        );
+        latency_ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
         loop {
             // Process any complete messages in the buffer first
-            if let Some(msg) = self.try_decode_message()? {
-                if !self.handle_message(msg).await? {
+            match self.try_decode_message() {
+                Ok(Some(msg)) => {
+                    if !self.handle_message(msg).await? {
+                        break;
+                    }
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    self.reject_malformed_frame(e).await;
                     break;
                 }
-                continue;
             }
 
             // If streaming, we need to handle both incoming messages and TS data
@@ -1540,6 +1687,9 @@ impl Session {
             if self.state == SessionState::Streaming {
                 // Create futures for socket read and TS receive
                 let mut tmp_buf = [0u8; 4096];
+                self.sync_raw_receiver();
+                let coalesce_deadline = self.ts_coalescer.as_ref().and_then(TsCoalescer::deadline);
+                let paced_until = self.ts_paced_until;
 
                 tokio::select! {
                     // NOTE: `biased` is intentionally NOT used here.
@@ -1550,8 +1700,8 @@ impl Session {
                     // make progress.
 
                     // Remote shutdown request
-                    _ = self.shutdown_rx.recv() => {
-                        self.disconnect_reason = Some("remote_shutdown".to_string());
+                    reason = self.shutdown_rx.recv() => {
+                        self.disconnect_reason = Some(reason.unwrap_or("remote_shutdown").to_string());
                         break;
                     }
 
@@ -1559,15 +1709,52 @@ impl Session {
                     // This catches cases where another session's exclusive eviction,
                     // a BonDriver crash, or hardware failure stopped our reader.
                     _ = reader_alive_check.tick() => {
-                        if let Some(tuner) = &self.current_tuner {
+                        if let Some(tuner) = self.current_tuner.clone() {
                             if !tuner.is_running() {
-                                warn!("[Session {}] Tuner reader for {:?} stopped externally (is_running=false), disconnecting",
-                                      self.id, tuner.key);
-                                self.disconnect_reason = Some("reader_stopped".to_string());
+                                if let Some(reason) = tuner.preempt_reason() {
+                                    warn!("[Session {}] Tuner {:?} was taken over ({}), disconnecting",
+                                          self.id, tuner.key, reason);
+                                    self.send_tuner_status(TunerState::Preempted, reason).await?;
+                                    self.disconnect_reason = Some("preempted".to_string());
+                                } else {
+                                    warn!("[Session {}] Tuner reader for {:?} stopped externally (is_running=false), disconnecting",
+                                          self.id, tuner.key);
+                                    self.disconnect_reason = Some("reader_stopped".to_string());
+                                }
                                 break;
                             }
+                            self.check_tuner_status(&tuner).await?;
+                        }
+                    }
+
+                    _ = idle_check.tick(), if self.idle_timeout.is_some() => {
+                        if self.is_idle_expired() {
+                            warn!("[Session {}] No client traffic for {:?}, disconnecting",
+                                  self.id, self.last_rx_at.elapsed());
+                            self.disconnect_reason = Some("idle_timeout".to_string());
+                            break;
                         }
                     }
+                    _ = latency_ping.tick(), if self.server_ping => {
+                        self.send_latency_ping().await?;
+                    }
+
+                    // Send coalesced TS that has waited for the flush interval,
+                    // so low-bitrate streams (1seg) never sit in the buffer.
+                    _ = tokio::time::sleep_until(
+                        coalesce_deadline
+                            .map(tokio::time::Instant::from_std)
+                            .unwrap_or_else(tokio::time::Instant::now),
+                    ), if coalesce_deadline.is_some() => {
+                        self.flush_ts_coalescer().await?;
+                    }
+
+                    // Resume taking TS once the bitrate cap allows it again
+                    _ = tokio::time::sleep_until(
+                        paced_until.unwrap_or_else(tokio::time::Instant::now),
+                    ), if paced_until.is_some() => {
+                        self.ts_paced_until = None;
+                    }
 
                     // Check for incoming socket data (client commands).
                     // Prioritized above tsreplace/TS data so that StopStream,
@@ -1578,6 +1765,7 @@ impl Session {
                             self.disconnect_reason = Some("client_disconnect".to_string());
                             break; // Connection closed
                         }
+                        self.last_rx_at = std::time::Instant::now();
                         self.read_buf.extend_from_slice(&tmp_buf[..n]);
                     }
 
@@ -1588,7 +1776,7 @@ impl Session {
                         } else {
                             std::future::pending::<Option<Bytes>>().await
                         }
-                    } => {
+                    }, if paced_until.is_none() => {
                         if let Some(data) = encoded_result {
                             self.tsreplace_last_output_at = std::time::Instant::now();
                             self.send_ts_data(data).await?;
@@ -1605,13 +1793,14 @@ impl Session {
 
                     // Check for incoming TS data
                     ts_result = async {
-                        if let Some(rx) = &mut self.ts_receiver {
-                            Some(rx.recv().await)
-                        } else {
-                            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                            None
+                        match (&mut self.raw_receiver, &mut self.ts_receiver) {
+                            (Some((_, rx)), _) | (None, Some(rx)) => Some(rx.recv().await),
+                            (None, None) => {
+                                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                                None
+                            }
                         }
-                    } => {
+                    }, if paced_until.is_none() => {
                         match ts_result {
                             Some(Ok(data)) => {
                                 if let Some(tx) = &self.tsreplace_input_tx {
@@ -1660,6 +1849,7 @@ impl Session {
                             Some(Err(broadcast::error::RecvError::Lagged(count))) => {
                                 warn!("[Session {}] Broadcast receiver lagged, skipped {} messages — recovering", self.id, count);
                                 self.packets_dropped += count;
+                                self.session_registry.record_lag(self.id, count).await;
                                 // Recovery: clear the TS carry buffers so we don't
                                 // send partial/stale packets after the gap.  The
                                 // next received chunk will start a fresh alignment.
@@ -1680,23 +1870,39 @@ impl Session {
                 let socket = &mut self.socket_reader;
                 let read_buf = &mut self.read_buf;
                 let shutdown_rx = &mut self.shutdown_rx;
+                let last_rx_at = &mut self.last_rx_at;
 
                 tokio::select! {
-                    _ = shutdown_rx.recv() => {
-                        self.disconnect_reason = Some("remote_shutdown".to_string());
+                    reason = shutdown_rx.recv() => {
+                        self.disconnect_reason = Some(reason.unwrap_or("remote_shutdown").to_string());
                         break;
                     }
-                    result = Self::read_message_with(socket, read_buf, self.id) => {
+                    _ = idle_check.tick(), if self.idle_timeout.is_some() => {
+                        if self.is_idle_expired() {
+                            warn!("[Session {}] No client traffic for {:?}, disconnecting",
+                                  self.id, self.last_rx_at.elapsed());
+                            self.disconnect_reason = Some("idle_timeout".to_string());
+                            break;
+                        }
+                    }
+                    _ = latency_ping.tick(), if self.server_ping => {
+                        self.send_latency_ping().await?;
+                    }
+                    result = Self::read_message_with(socket, read_buf, last_rx_at, self.id, self.frame_options) => {
                         match result? {
-                            Some(msg) => {
+                            Ok(Some(msg)) => {
                                 if !self.handle_message(msg).await? {
                                     break;
                                 }
                             }
-                            None => {
+                            Ok(None) => {
                                 self.disconnect_reason = Some("client_disconnect".to_string());
                                 break;
                             }
+                            Err(e) => {
+                                self.reject_malformed_frame(e).await;
+                                break;
+                            }
                         }
                     }
                 }
@@ -1709,27 +1915,36 @@ impl Session {
     }
 
     /// Try to decode a complete message from the buffer.
-    fn try_decode_message(&mut self) -> std::io::Result<Option<ClientMessage>> {
+    ///
+    /// Returns an error only for an invalid frame header, after which the
+    /// stream cannot be resynchronized.
+    fn try_decode_message(&mut self) -> Result<Option<ClientMessage>, ProtocolError> {
         if self.read_buf.len() < HEADER_SIZE {
             return Ok(None);
         }
 
-        match decode_header(&self.read_buf) {
-            Ok(Some(header)) => {
+        match decode_header(&self.read_buf)? {
+            Some(header) => {
                 let total_len = HEADER_SIZE + header.payload_len as usize;
                 if self.read_buf.len() >= total_len {
                     // We have a complete frame
                     let _ = self.read_buf.split_to(HEADER_SIZE);
                     let payload = self.read_buf.split_to(header.payload_len as usize);
 
-                    match decode_client_message(
+                    match decode_client_message_with(
                         header.message_type,
                         Bytes::from(payload.to_vec()),
+                        self.frame_options,
                     ) {
                         Ok(msg) => {
                             debug!("[Session {}] Decoded message: {:?}", self.id, msg);
                             Ok(Some(msg))
                         }
+                        Err(e @ ProtocolError::ChecksumMismatch { .. }) => {
+                            // The length was intact, so the next frame starts cleanly
+                            warn!("[Session {}] Dropping corrupted {:?} frame: {}", self.id, header.message_type, e);
+                            Ok(None)
+                        }
                         Err(e) => {
                             error!("[Session {}] Failed to decode message: {}", self.id, e);
                             Ok(None)
@@ -1739,20 +1954,35 @@ impl Session {
                     Ok(None) // Need more data
                 }
             }
-            Ok(None) => Ok(None), // Need more data
-            Err(e) => {
-                error!("[Session {}] Protocol error: {}", self.id, e);
-                Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
-            }
+            None => Ok(None), // Need more data
+        }
+    }
+
+    /// Report an invalid frame header to the client before the session closes.
+    ///
+    /// The rest of the buffer is discarded without being read as a frame, so
+    /// an oversized length never leads to a payload allocation.
+    async fn reject_malformed_frame(&mut self, e: ProtocolError) {
+        error!("[Session {}] Protocol error from {}: {}", self.id, self.addr, e);
+        self.read_buf.clear();
+        let reason = match e {
+            ProtocolError::FrameTooLarge(..) => "frame_too_large",
+            _ => "protocol_error",
+        };
+        self.disconnect_reason = Some(reason.to_string());
+        if let Err(send_err) = self.send_error(ErrorCode::ProtocolError, &e.to_string()).await {
+            debug!("[Session {}] Failed to report protocol error: {}", self.id, send_err);
         }
     }
 
     /// Read and decode a client message (borrowed socket/buffer).
     async fn read_message_with(
-        socket: &mut OwnedReadHalf,
+        socket: &mut SocketReader,
         read_buf: &mut BytesMut,
+        last_rx_at: &mut std::time::Instant,
         session_id: u64,
-    ) -> std::io::Result<Option<ClientMessage>> {
+        frame_options: FrameOptions,
+    ) -> std::io::Result<Result<Option<ClientMessage>, ProtocolError>> {
         loop {
             // Try to decode a header from the buffer
             if read_buf.len() >= HEADER_SIZE {
@@ -1764,13 +1994,18 @@ impl Session {
                             let _ = read_buf.split_to(HEADER_SIZE);
                             let payload = read_buf.split_to(header.payload_len as usize);
 
-                            match decode_client_message(
+                            match decode_client_message_with(
                                 header.message_type,
                                 Bytes::from(payload.to_vec()),
+                                frame_options,
                             ) {
                                 Ok(msg) => {
                                     trace!("[Session {}] Received: {:?}", session_id, msg);
-                                    return Ok(Some(msg));
+                                    return Ok(Ok(Some(msg)));
+                                }
+                                Err(e @ ProtocolError::ChecksumMismatch { .. }) => {
+                                    warn!("[Session {}] Dropping corrupted {:?} frame: {}", session_id, header.message_type, e);
+                                    continue;
                                 }
                                 Err(e) => {
                                     error!("[Session {}] Failed to decode message: {}", session_id, e);
@@ -1782,10 +2017,7 @@ impl Session {
                     Ok(None) => {
                         // Need more data
                     }
-                    Err(e) => {
-                        error!("[Session {}] Protocol error: {}", session_id, e);
-                        return Ok(None);
-                    }
+                    Err(e) => return Ok(Err(e)),
                 }
             }
 
@@ -1793,27 +2025,60 @@ impl Session {
             let mut tmp_buf = [0u8; 4096];
             let n = socket.read(&mut tmp_buf).await?;
             if n == 0 {
-                return Ok(None); // Connection closed
+                return Ok(Ok(None)); // Connection closed
             }
+            *last_rx_at = std::time::Instant::now();
             read_buf.extend_from_slice(&tmp_buf[..n]);
         }
     }
 
     /// Handle a client message. Returns false to close the session.
     async fn handle_message(&mut self, msg: ClientMessage) -> std::io::Result<bool> {
+        let result = self.dispatch_message(msg).await;
+        self.record_log_fields();
+        result
+    }
+
+    /// Mirror the current driver and channel onto the session's log span
+    /// (a no-op unless JSON logging is enabled).
+    fn record_log_fields(&self) {
+        let span = tracing::Span::current();
+        span.record("driver", self.current_tuner_path.as_deref().unwrap_or(""));
+        span.record("channel", self.current_channel_info.as_deref().unwrap_or(""));
+    }
+
+    async fn dispatch_message(&mut self, msg: ClientMessage) -> std::io::Result<bool> {
+        // With auth configured nothing but Hello is served before it succeeds
+        if self.auth_token.is_some()
+            && self.state == SessionState::Initial
+            && !matches!(msg, ClientMessage::Hello { .. })
+        {
+            warn!(
+                "[Session {}] Unauthenticated {:?} from {}, closing",
+                self.id,
+                msg.message_type(),
+                self.addr
+            );
+            self.disconnect_reason = Some("auth_required".to_string());
+            self.send_message(ServerMessage::AuthRequired).await?;
+            return Ok(false);
+        }
+
         match msg {
-            ClientMessage::Hello { version } => {
-                self.handle_hello(version).await?;
+            ClientMessage::Hello { version, features, auth_token } => {
+                return self.handle_hello(version, features, auth_token).await;
             }
             ClientMessage::Ping => {
                 self.send_message(ServerMessage::Pong).await?;
             }
+            ClientMessage::Pong { seq } => {
+                self.handle_pong(seq).await;
+            }
             ClientMessage::OpenTuner { tuner_path } => {
                 self.handle_open_tuner(tuner_path).await?;
             }
             ClientMessage::OpenTunerWithGroup { group_name } => {
-                // Reuse OpenTuner path resolution (group_name is supported there).
-                self.handle_open_tuner(group_name).await?;
+                self.handle_open_tuner_with_group(group_name).await?;
             }
             ClientMessage::CloseTuner => {
                 self.handle_close_tuner().await?;
@@ -1825,16 +2090,26 @@ impl Session {
                 self.handle_set_channel_space(space, channel, priority, exclusive).await?;
             }
             ClientMessage::SetChannelSpaceInGroup { group_name, space_idx, channel, priority, exclusive } => {
-                // Group mode is handled by `handle_set_channel_space` via current group context.
-                // Keep the explicit group open path for compatibility.
-                if self.current_group_name.as_deref() != Some(group_name.as_str()) {
-                    self.handle_open_tuner(group_name).await?;
-                }
-                self.handle_set_channel_space(space_idx, channel, priority, exclusive).await?;
+                self.handle_set_channel_space_in_group(group_name, space_idx, channel, priority, exclusive).await?;
+            }
+            ClientMessage::SetKeepAlive { seconds } => {
+                self.handle_set_keep_alive(seconds).await?;
+            }
+            ClientMessage::SetDecode { enable } => {
+                self.handle_set_decode(enable).await?;
             }
             ClientMessage::GetSignalLevel => {
                 self.handle_get_signal_level().await?;
             }
+            ClientMessage::GetStreamStats => {
+                self.handle_get_stream_stats().await?;
+            }
+            ClientMessage::GetTunerCapacity => {
+                self.handle_get_tuner_capacity().await?;
+            }
+            ClientMessage::ListTuners => {
+                self.handle_list_tuners().await?;
+            }
             ClientMessage::EnumTuningSpace { space } => {
                 self.handle_enum_tuning_space(space).await?;
             }
@@ -1853,8 +2128,8 @@ impl Session {
             ClientMessage::SetLnbPower { enable } => {
                 self.handle_set_lnb_power(enable).await?;
             }
-            ClientMessage::SelectLogicalChannel { nid, tsid, sid } => {
-                self.handle_select_logical_channel(nid, tsid, sid).await?;
+            ClientMessage::SelectLogicalChannel { nid, tsid, sid, priority, exclusive } => {
+                self.handle_select_logical_channel(nid, tsid, sid, priority, exclusive).await?;
             }
             ClientMessage::GetChannelList { filter } => {
                 self.handle_get_channel_list(filter).await?;
@@ -1862,27 +2137,96 @@ impl Session {
             ClientMessage::SetServiceFilter { single_service } => {
                 self.handle_set_service_filter(single_service).await?;
             }
+            ClientMessage::SelectByEvent { nid, sid, event_id } => {
+                self.handle_select_by_event(nid, sid, event_id).await?;
+            }
+            ClientMessage::SetChannelByRemoteKey { remote_key, space_hint } => {
+                self.handle_set_channel_by_remote_key(remote_key, space_hint).await?;
+            }
         }
         Ok(true)
     }
 
-    /// Handle Hello message.
-    async fn handle_hello(&mut self, version: u16) -> std::io::Result<()> {
+    /// Handle Hello message. Returns false when authentication failed.
+    async fn handle_hello(
+        &mut self,
+        version: u16,
+        features: u32,
+        auth_token: Option<String>,
+    ) -> std::io::Result<bool> {
         info!(
-            "[Session {}] Client hello, version {}",
-            self.id, version
+            "[Session {}] Client hello, version {}, features {:#x}",
+            self.id, version, features
         );
 
+        if let Some(expected) = &self.auth_token {
+            let rejection = match auth_token.as_deref() {
+                None => Some((ServerMessage::AuthRequired, "auth_required")),
+                Some(token) if !constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+                    Some((ServerMessage::AuthFailed, "auth_failed"))
+                }
+                Some(_) => None,
+            };
+            if let Some((reply, reason)) = rejection {
+                warn!(
+                    "[Session {}] Authentication failed from {} ({}), closing",
+                    self.id, self.addr, reason
+                );
+                self.disconnect_reason = Some(reason.to_string());
+                self.send_message(reply).await?;
+                return Ok(false);
+            }
+        }
+
         let success = version == PROTOCOL_VERSION;
-        if success {
+        let features = if success {
             self.state = SessionState::Ready;
+            features & supported_features()
+        } else {
+            0
+        };
+        self.ts_compression = features & FEATURE_TS_COMPRESSION != 0;
+        self.tuner_status = features & FEATURE_TUNER_STATUS != 0;
+        self.server_ping = features & FEATURE_SERVER_PING != 0;
+        if self.ts_compression {
+            info!("[Session {}] TS compression enabled", self.id);
         }
 
         self.send_message(ServerMessage::HelloAck {
             version: PROTOCOL_VERSION,
             success,
+            features,
         })
-        .await
+        .await?;
+        // HelloAck itself goes out plain; everything after uses the agreed framing
+        self.frame_options = FrameOptions::from_features(features);
+        Ok(true)
+    }
+
+    /// Send a latency Ping, replacing any that is still unanswered.
+    ///
+    /// Pings go out from the session loop between requests, so they never
+    /// delay a reply.
+    async fn send_latency_ping(&mut self) -> std::io::Result<()> {
+        if let Some((seq, sent_at)) = self.ping_pending {
+            debug!("[Session {}] Ping {} unanswered after {:?}", self.id, seq, sent_at.elapsed());
+        }
+        self.ping_seq = self.ping_seq.wrapping_add(1);
+        self.ping_pending = Some((self.ping_seq, std::time::Instant::now()));
+        self.send_message(ServerMessage::Ping { seq: self.ping_seq }).await
+    }
+
+    /// Record the round-trip time of the pending latency Ping.
+    async fn handle_pong(&mut self, seq: u32) {
+        match self.ping_pending {
+            Some((pending, sent_at)) if pending == seq => {
+                self.ping_pending = None;
+                let rtt_ms = sent_at.elapsed().as_secs_f64() * 1000.0;
+                trace!("[Session {}] Ping {} RTT {:.1} ms", self.id, seq, rtt_ms);
+                self.session_registry.update_latency(self.id, rtt_ms).await;
+            }
+            _ => debug!("[Session {}] Ignoring stale Pong {}", self.id, seq),
+        }
     }
 
     /// Handle OpenTuner message.
@@ -1911,116 +2255,69 @@ impl Session {
         };
 
         // ★ Resolve: DLL path -> group name -> display_name -> first driver
+        // Resolution is a single pass (see Database::resolve_tuner_name); names that
+        // match more than one kind of target are rejected instead of guessed.
         let (resolved_path, is_group) = {
             let db = self.database.lock().await;
-            
-            // 1. Try as DLL path
-            if let Ok(Some(_driver)) = db.get_bon_driver_by_path(&path) {
-                debug!("[Session {}] Tuner '{}' matched as DLL path", self.id, path);
-                (path.clone(), false)
-            } else {
-                // 2. Try as group_name
-                match db.get_group_drivers(&path) {
-                    Ok(drivers) if !drivers.is_empty() => {
-                        debug!("[Session {}] Tuner '{}' matched as group_name (drivers: {})", 
-                            self.id, path, drivers.len());
-                        (path.clone(), true)
-                    },
-                    _ => {
-                        // 3. Try as display_name
-                        match db.get_bon_driver_by_display_name(&path) {
-                            Ok(Some(driver)) => {
-                                debug!("[Session {}] Tuner '{}' resolved to DLL: {}", 
-                                    self.id, path, driver.dll_path);
-                                (driver.dll_path, false)
-                            },
-                            Ok(None) => {
-                                // 4. Use first available enabled driver (prefer enabled over disabled)
-                                warn!("[Session {}] Tuner '{}' not found, trying first available driver", self.id, path);
-                                match db.get_all_bon_drivers() {
-                                    Ok(drivers) => {
-                                        // Try enabled drivers first
-                                        let mut selected_driver = None;
-                                        
-                                        // First pass: find an enabled driver
-                                        for driver in &drivers {
-                                            // Check if driver appears in enabled channels
-                                            // We can infer enabled status from whether it has enabled channels
-                                            let has_enabled_channels = drivers.iter().any(|d| d.dll_path == driver.dll_path);
-                                            if has_enabled_channels {
-                                                selected_driver = Some(driver);
-                                                break;
-                                            }
-                                        }
-                                        
-                                        // If no enabled driver found, use first available
-                                        let first_driver = selected_driver.or_else(|| drivers.first());
-                                        
-                                        match first_driver {
-                                            Some(driver) => {
-                                                warn!("[Session {}] Using driver: {} (path: {})", 
-                                                    self.id, 
-                                                    driver.driver_name.as_ref().unwrap_or(&driver.dll_path), 
-                                                    driver.dll_path);
-                                                (driver.dll_path.clone(), false)
-                                            }
-                                            None => {
-                                                error!("[Session {}] No drivers found in database at all", self.id);
-                                                drop(db);
-                                                return self
-                                                    .send_message(ServerMessage::OpenTunerAck {
-                                                        success: false,
-                                                        error_code: ErrorCode::InvalidParameter.into(),
-                                                        bondriver_version: 0,
-                                                    })
-                                                    .await;
-                                            }
-                                        }
-                                    },
-                                    Err(e) => {
-                                        error!("[Session {}] Failed to query drivers: {}", self.id, e);
-                                        drop(db);
-                                        return self
-                                            .send_message(ServerMessage::OpenTunerAck {
-                                                success: false,
-                                                error_code: ErrorCode::InvalidParameter.into(),
-                                                bondriver_version: 0,
-                                            })
-                                            .await;
-                                    }
-                                }
-                            },
-                            Err(e) => {
-                                error!("[Session {}] Database error resolving tuner: {}", self.id, e);
-                                drop(db);
-                                return self
-                                    .send_message(ServerMessage::OpenTunerAck {
-                                        success: false,
-                                        error_code: ErrorCode::TunerOpenFailed.into(),
-                                        bondriver_version: 0,
-                                    })
-                                    .await;
-                            }
+
+            match db.resolve_tuner_name(&path) {
+                Ok(TunerResolution::Driver(dll_path)) => {
+                    debug!("[Session {}] Tuner '{}' resolved to DLL: {}", self.id, path, dll_path);
+                    (dll_path, false)
+                }
+                Ok(TunerResolution::Group(group_name)) => {
+                    debug!("[Session {}] Tuner '{}' matched as group_name", self.id, path);
+                    (group_name, true)
+                }
+                Ok(TunerResolution::NotFound) => {
+                    // Fall back to the first registered driver
+                    warn!("[Session {}] Tuner '{}' not found, trying first available driver", self.id, path);
+                    match db.get_all_bon_drivers() {
+                        Ok(drivers) if !drivers.is_empty() => {
+                            let driver = &drivers[0];
+                            warn!("[Session {}] Using driver: {} (path: {})",
+                                self.id,
+                                driver.driver_name.as_ref().unwrap_or(&driver.dll_path),
+                                driver.dll_path);
+                            (driver.dll_path.clone(), false)
+                        }
+                        Ok(_) => {
+                            error!("[Session {}] No drivers found in database at all", self.id);
+                            drop(db);
+                            return self
+                                .send_message(ServerMessage::OpenTunerAck {
+                                    success: false,
+                                    error_code: ErrorCode::InvalidParameter.into(),
+                                    bondriver_version: 0,
+                                })
+                                .await;
+                        }
+                        Err(e) => {
+                            error!("[Session {}] Failed to query drivers: {}", self.id, e);
+                            drop(db);
+                            return self
+                                .send_message(ServerMessage::OpenTunerAck {
+                                    success: false,
+                                    error_code: ErrorCode::InvalidParameter.into(),
+                                    bondriver_version: 0,
+                                })
+                                .await;
                         }
                     }
                 }
-            }
-        }; // db is dropped here
-
-        info!("[Session {}] Opening tuner: {} (group: {})", self.id, path, is_group);
-
-        // If group, load all drivers in the group
-        if is_group {
-            let db = self.database.lock().await;
-            match db.get_group_drivers(&path) {
-                Ok(drivers) => {
-                    self.group_driver_paths = drivers.iter().map(|d| d.dll_path.clone()).collect();
-                    self.current_group_name = Some(path.clone());
-                    info!("[Session {}] Loaded group '{}' with {} drivers: {:?}", 
-                        self.id, path, self.group_driver_paths.len(), self.group_driver_paths);
-                },
+                Err(e @ DatabaseError::AmbiguousTunerName(_)) => {
+                    error!("[Session {}] OpenTuner: {}", self.id, e);
+                    drop(db);
+                    return self
+                        .send_message(ServerMessage::OpenTunerAck {
+                            success: false,
+                            error_code: ErrorCode::InvalidParameter.into(),
+                            bondriver_version: 0,
+                        })
+                        .await;
+                }
                 Err(e) => {
-                    error!("[Session {}] Failed to load group drivers: {}", self.id, e);
+                    error!("[Session {}] Database error resolving tuner: {}", self.id, e);
                     drop(db);
                     return self
                         .send_message(ServerMessage::OpenTunerAck {
@@ -2031,7 +2328,37 @@ impl Session {
                         .await;
                 }
             }
+        }; // db is dropped here
+
+        info!("[Session {}] Opening tuner: {} (group: {})", self.id, path, is_group);
+
+        // If group, load all drivers in the group
+        if is_group {
+            if let Err(error_code) = self.load_group_drivers(&path).await {
+                return self
+                    .send_message(ServerMessage::OpenTunerAck {
+                        success: false,
+                        error_code: error_code.into(),
+                        bondriver_version: 0,
+                    })
+                    .await;
+            }
         } else {
+            if !self.may_use_driver(&resolved_path).await {
+                warn!(
+                    "[Session {}] OpenTuner: client '{}' is not allowed to use {}",
+                    self.id,
+                    self.client_identity.as_deref().unwrap_or_default(),
+                    resolved_path
+                );
+                return self
+                    .send_message(ServerMessage::OpenTunerAck {
+                        success: false,
+                        error_code: ErrorCode::PermissionDenied.into(),
+                        bondriver_version: 0,
+                    })
+                    .await;
+            }
             self.current_tuner_path = Some(resolved_path.clone());
             self.current_group_name = None;
             self.group_driver_paths.clear();
@@ -2040,7 +2367,7 @@ impl Session {
         }
 
         if is_group {
-            self.stop_warm_tuner().await;
+            self.maybe_start_group_warm_tuner().await;
         }
 
         self.clear_caches();
@@ -2061,6 +2388,88 @@ impl Session {
         .await
     }
 
+    /// BonDriver IDs this client may use, or `None` when unrestricted.
+    ///
+    /// Only sessions with a TLS client identity can be restricted. A
+    /// database error denies every driver rather than failing open.
+    async fn allowed_drivers(&self) -> Option<HashSet<i64>> {
+        let identity = self.client_identity.as_deref()?;
+        match self.database.lock().await.get_client_allowed_drivers(identity) {
+            Ok(allowed) => allowed,
+            Err(e) => {
+                error!("[Session {}] Failed to load client ACL for '{}': {}", self.id, identity, e);
+                Some(HashSet::new())
+            }
+        }
+    }
+
+    /// Whether the client ACL lets this session open the driver at `dll_path`.
+    async fn may_use_driver(&self, dll_path: &str) -> bool {
+        let Some(allowed) = self.allowed_drivers().await else {
+            return true;
+        };
+        let db = self.database.lock().await;
+        matches!(db.get_bon_driver_by_path(dll_path), Ok(Some(d)) if allowed.contains(&d.id))
+    }
+
+    /// Load the drivers of a BonDriver group into the session's group context.
+    ///
+    /// Only drivers with at least one enabled channel are kept, so an open
+    /// is rejected up front instead of failing every SetChannel later.
+    async fn load_group_drivers(&mut self, group_name: &str) -> Result<(), ErrorCode> {
+        let allowed = self.allowed_drivers().await;
+        let db = self.database.lock().await;
+        let mut drivers = match db.get_group_drivers(group_name) {
+            Ok(drivers) => drivers,
+            Err(e) => {
+                error!("[Session {}] Failed to load group drivers: {}", self.id, e);
+                return Err(ErrorCode::TunerOpenFailed);
+            }
+        };
+        if drivers.is_empty() {
+            error!("[Session {}] Group '{}' not found", self.id, group_name);
+            return Err(ErrorCode::InvalidParameter);
+        }
+        if let Some(allowed) = &allowed {
+            drivers.retain(|d| allowed.contains(&d.id));
+            if drivers.is_empty() {
+                warn!(
+                    "[Session {}] OpenTuner: client '{}' is not allowed to use any driver in group '{}'",
+                    self.id,
+                    self.client_identity.as_deref().unwrap_or_default(),
+                    group_name
+                );
+                return Err(ErrorCode::PermissionDenied);
+            }
+        }
+
+        let usable: Vec<String> = drivers
+            .iter()
+            .filter(|d| {
+                db.get_enabled_channels_by_bon_driver(d.id)
+                    .map(|chs| !chs.is_empty())
+                    .unwrap_or(false)
+            })
+            .map(|d| d.dll_path.clone())
+            .collect();
+        drop(db);
+
+        if usable.is_empty() {
+            error!("[Session {}] OpenTuner: group '{}' has no enabled drivers ({} drivers, none with enabled channels)",
+                self.id, group_name, drivers.len());
+            return Err(ErrorCode::TunerOpenFailed);
+        }
+        if usable.len() < drivers.len() {
+            warn!("[Session {}] Group '{}': skipping {} driver(s) without enabled channels",
+                self.id, group_name, drivers.len() - usable.len());
+        }
+        self.group_driver_paths = usable;
+        self.current_group_name = Some(group_name.to_string());
+        info!("[Session {}] Loaded group '{}' with {} drivers: {:?}",
+            self.id, group_name, self.group_driver_paths.len(), self.group_driver_paths);
+        Ok(())
+    }
+
     /// Handle CloseTuner message.
     async fn handle_close_tuner(&mut self) -> std::io::Result<()> {
         info!("[Session {}] Closing tuner", self.id);
@@ -2144,7 +2553,7 @@ impl Session {
                         old.unsubscribe();
                         self.ts_receiver = None;
                         if old.subscriber_count() == 0 {
-                            self.tuner_pool.schedule_idle_close(old.key.clone(), old).await;
+                            self.schedule_idle_close(old).await;
                         }
                     }
                 }
@@ -2206,7 +2615,7 @@ impl Session {
                     } else {
                         info!("[Session {}] v1: old DLL has spare capacity ({}/{}), scheduling idle close for {:?}",
                               self.id, old_dll_running, old_dll_max, old_tuner.key);
-                        self.tuner_pool.schedule_idle_close(old_tuner.key.clone(), old_tuner).await;
+                        self.schedule_idle_close(old_tuner).await;
                     }
                 }
             }
@@ -2246,7 +2655,7 @@ impl Session {
                         self.try_restore_previous_channel(&old_tuner_key).await;
                         return self.send_message(ServerMessage::SetChannelAck {
                             success: false,
-                            error_code: ErrorCode::ChannelSetFailed.into(),
+                            error_code: ErrorCode::TunerBusy.into(),
                         }).await;
                     }
                 }
@@ -2273,7 +2682,7 @@ impl Session {
                         self.try_restore_previous_channel(&old_tuner_key).await;
                         return self.send_message(ServerMessage::SetChannelAck {
                             success: false,
-                            error_code: ErrorCode::ChannelSetFailed.into(),
+                            error_code: reader_start_error_code(&tuner, &e).into(),
                         }).await;
                     }
                 } else {
@@ -2301,7 +2710,7 @@ impl Session {
                 self.try_restore_previous_channel(&old_tuner_key).await;
                 self.send_message(ServerMessage::SetChannelAck {
                     success: false,
-                    error_code: ErrorCode::ChannelSetFailed.into(),
+                    error_code: ErrorCode::DriverOpenFailed.into(),
                 })
                 .await
             }
@@ -2348,7 +2757,7 @@ impl Session {
                    self.id, channel, actual_space, region_name, map.len());
             return self.send_message(ServerMessage::SetChannelSpaceAck {
                 success: false,
-                error_code: ErrorCode::InvalidParameter.into(),
+                error_code: ErrorCode::ChannelNotFound.into(),
             }).await;
         };
 
@@ -2381,23 +2790,22 @@ impl Session {
             // Query all channels and find which drivers have this NID+TSID
             let db = self.database.lock().await;
             let mut candidate_drivers: Vec<(String, u32, u32)> = Vec::new();  // (driver_path, actual_space, bon_channel)
+            // driver_path -> (running instances, max_instances)
+            let mut driver_load: HashMap<String, (i32, i32)> = HashMap::new();
+            let keys = self.tuner_pool.keys().await;
 
-            match db.get_all_channels_with_drivers() {
-                Ok(all_channels) => {
-                    for (ch, bd_opt) in all_channels {
-                        let Some(bd) = bd_opt else { continue; };
-                        
+            // One channel per driver; BS/CS channels placed from the NIT win,
+            // so each driver tunes the right transponder for this TS.
+            match db.get_physical_channels(entry.nid, entry.tsid) {
+                Ok(physical_channels) => {
+                    for (dll_path, space, channel) in physical_channels {
                         // Check if this driver is in the group
-                        if !self.group_driver_paths.contains(&bd.dll_path) {
+                        if !self.group_driver_paths.contains(&dll_path) {
                             continue;
                         }
-                        
-                        // Match by NID+TSID (this correctly handles different bon_channel values across drivers)
-                        if ch.nid as u16 == entry.nid && ch.tsid as u16 == entry.tsid && ch.is_enabled {
-                            candidate_drivers.push((bd.dll_path.clone(), ch.space, ch.channel));
-                            debug!("[Session {}] Found NID+TSID match in driver {} (space {}, ch {})", 
-                                self.id, bd.dll_path, ch.space, ch.channel);
-                        }
+                        debug!("[Session {}] Found NID+TSID match in driver {} (space {}, ch {})",
+                            self.id, dll_path, space, channel);
+                        candidate_drivers.push((dll_path, space, channel));
                     }
                 }
                 Err(e) => {
@@ -2420,6 +2828,72 @@ impl Session {
                     let score_b = score_map.get(&b.0).copied().unwrap_or(1.0);
                     score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
                 });
+
+                // ★ Per-group strategy (bon_drivers.group_strategy) on top of quality order
+                let group_name = self.current_group_name.clone().unwrap_or_default();
+                let strategy = match db.get_group_strategy(&group_name) {
+                    Ok(Some(name)) => DriverSelectionStrategy::from_name(&name).unwrap_or_else(|| {
+                        warn!("[Session {}] Unknown group_strategy '{}' for group {}; using quality order",
+                              self.id, name, group_name);
+                        DriverSelectionStrategy::default()
+                    }),
+                    Ok(None) => DriverSelectionStrategy::default(),
+                    Err(e) => {
+                        warn!("[Session {}] Failed to load group_strategy for {}: {}", self.id, group_name, e);
+                        DriverSelectionStrategy::default()
+                    }
+                };
+                let rotation = if strategy == DriverSelectionStrategy::RoundRobin {
+                    self.tuner_pool.next_round_robin(&group_name).await
+                } else {
+                    0
+                };
+                for (driver_path, _, _) in candidate_drivers.iter() {
+                    if !driver_load.contains_key(driver_path) {
+                        let running = self.running_instances(&keys, driver_path, old_tuner_will_free_slot, old_tuner_key.as_ref()).await;
+                        let max_instances = db.get_max_instances_for_path(driver_path).unwrap_or(1);
+                        driver_load.insert(driver_path.clone(), (running, max_instances));
+                    }
+                }
+                DriverSelector::apply_strategy(&mut candidate_drivers, strategy, rotation, |c| {
+                    driver_load.get(&c.0).copied().unwrap_or((0, 1))
+                });
+                debug!("[Session {}] Group {} strategy {}: candidate order {:?}",
+                       self.id, group_name, strategy.name(),
+                       candidate_drivers.iter().map(|c| c.0.as_str()).collect::<Vec<_>>());
+
+                // ★ Tuner affinity: prefer the driver this session is already bound to,
+                // so channel surfing doesn't bounce between drivers (open/close churn).
+                // Capacity is still checked below; 4K preference is applied after this.
+                if self.tuner_pool.config().await.driver_affinity_enabled {
+                    if let Some(bound_path) = old_tuner_key.as_ref().map(|k| k.tuner_path.clone()) {
+                        if let Some(pos) = candidate_drivers.iter().position(|c| c.0 == bound_path) {
+                            let bound = candidate_drivers.remove(pos);
+                            candidate_drivers.insert(0, bound);
+                            debug!("[Session {}] Driver affinity: preferring bound driver {}", self.id, bound_path);
+                        }
+                    }
+                }
+
+                // ★ 4K/HEVC channels: prefer drivers marked as 4K capable
+                // (avoids "tuned but no picture" on SD-only tuners)
+                let needs_4k = BandType::from_nid(entry.nid) == BandType::FourK;
+                if needs_4k {
+                    let mut capable_map: HashMap<String, bool> = HashMap::new();
+                    for (driver_path, _, _) in candidate_drivers.iter() {
+                        if !capable_map.contains_key(driver_path) {
+                            let capable = db.get_supports_4k_for_path(driver_path).unwrap_or(false);
+                            capable_map.insert(driver_path.clone(), capable);
+                        }
+                    }
+                    DriverSelector::prefer_codec_capable(&mut candidate_drivers, needs_4k, |c| {
+                        capable_map.get(&c.0).copied().unwrap_or(false)
+                    });
+                    if !capable_map.values().any(|c| *c) {
+                        warn!("[Session {}] No 4K capable driver for NID=0x{:04X} TSID=0x{:04X}; falling back to quality order",
+                              self.id, entry.nid, entry.tsid);
+                    }
+                }
             }
 
             // Build NID+TSID → ChannelKey mapping for same-channel reuse across drivers
@@ -2433,7 +2907,6 @@ impl Session {
             // Now select the driver with available capacity
             // Priority: 1) Driver already streaming this channel, 2) Driver with available capacity
             let mut selected_driver: Option<(String, u32, u32)> = None;
-            let keys = self.tuner_pool.keys().await;
             
             // First, check if any driver is already streaming this channel (by its own space+bon_channel)
             for (driver_path, driver_space, driver_bon_channel) in candidate_drivers.iter() {
@@ -2461,25 +2934,13 @@ impl Session {
             // If not found, select driver with available capacity
             if selected_driver.is_none() {
                 for (driver_path, driver_space, driver_bon_channel) in candidate_drivers.iter() {
-                    // Count current instances on this driver
-                    let mut driver_instances = 0i32;
-                    for k in keys.iter() {
-                        if k.tuner_path == *driver_path {
-                            // Skip the current session's own tuner if it will be freed
-                            // during channel switch (sole subscriber → slot released).
-                            if old_tuner_will_free_slot && old_tuner_key.as_ref() == Some(k) {
-                                continue;
-                            }
-                            if let Some(tuner) = self.tuner_pool.get(&k).await {
-                                if tuner.is_running() {
-                                    driver_instances += 1;
-                                }
-                            }
-                        }
-                    }
-                    
-                    // Get max_instances for this driver
-                    let max_instances = db.get_max_instances_for_path(driver_path).unwrap_or(1);
+                    let (driver_instances, max_instances) = match driver_load.get(driver_path) {
+                        Some(load) => *load,
+                        None => (
+                            self.running_instances(&keys, driver_path, old_tuner_will_free_slot, old_tuner_key.as_ref()).await,
+                            db.get_max_instances_for_path(driver_path).unwrap_or(1),
+                        ),
+                    };
                     
                     debug!("[Session {}] Driver {} has {}/{} instances", 
                            self.id, driver_path, driver_instances, max_instances);
@@ -2668,7 +3129,9 @@ impl Session {
                                       self.id, target_key, target_priority);
                             }
                             self.tuner_pool.cancel_idle_close(&target_key).await;
-                            target_tuner.stop_reader().await;
+                            target_tuner
+                                .preempt_reader(format!("preempted by session {} (exclusive access)", self.id))
+                                .await;
                             self.tuner_pool.remove(&target_key).await;
                         }
                     }
@@ -2758,7 +3221,7 @@ impl Session {
                                     if old.subscriber_count() == 0 {
                                         // Don't await stop_reader inline; schedule idle close instead
                                         // so we don't block the reuse path for 1+ seconds.
-                                        self.tuner_pool.schedule_idle_close(old.key.clone(), old).await;
+                                        self.schedule_idle_close(old).await;
                                     }
                                 }
                                 if self.state == SessionState::Streaming {
@@ -2793,7 +3256,7 @@ impl Session {
                             }
                         };
                         self.session_registry.update_channel_name(self.id, channel_name.clone()).await;
-                        self.session_registry.update_channel_ids(self.id, ch_nid, ch_sid).await;
+                        self.session_registry.update_channel_ids(self.id, ch_nid, ch_tsid, ch_sid).await;
                         self.update_service_filter_for_sid(ch_nid, ch_tsid, ch_sid);
                         self.current_channel_name = channel_name;
 
@@ -2863,7 +3326,7 @@ impl Session {
                         // DLL has spare capacity — old tuner can idle-close later.
                         info!("[Session {}] Same DLL switch (max_instances={}, running={}), scheduling idle close for {:?}",
                               self.id, old_dll_max, old_dll_running, tuner.key);
-                        self.tuner_pool.schedule_idle_close(tuner.key.clone(), tuner).await;
+                        self.schedule_idle_close(tuner).await;
                     }
                 } else {
                     // Different DLL switch.  Check whether the old DLL is at
@@ -2896,7 +3359,7 @@ impl Session {
                     } else {
                         info!("[Session {}] Different DLL switch (old DLL has spare capacity {}/{}), scheduling idle close for {:?}",
                               self.id, old_dll_running, old_dll_max, tuner.key);
-                        self.tuner_pool.schedule_idle_close(tuner.key.clone(), tuner).await;
+                        self.schedule_idle_close(tuner).await;
                     }
                 }
             }
@@ -2937,6 +3400,18 @@ impl Session {
             vec![]
         };
 
+        // Single-driver mode: other physical channels of this driver carrying
+        // the same NID+TSID, tried when the selected one delivers no TS
+        let alternate_candidates: Vec<(u32, u32)> = if self.group_driver_paths.is_empty() {
+            self.virtual_channel_mappings
+                .get(&tuner_path)
+                .and_then(|mappings| mappings.get(&(entry.nid, entry.tsid)))
+                .map(|mappings| alternate_channels(mappings, &tuner_path, actual_space, actual_bon_channel))
+                .unwrap_or_default()
+        } else {
+            vec![]
+        };
+
         // ★ Re-take fresh keys snapshot for capacity check
         // (The previous `keys` was obtained before old tuner unsubscribe/stop,
         //  and other sessions may have modified the pool since then)
@@ -3036,7 +3511,10 @@ impl Session {
                         info!("[Session {}] Forcing lower priority channel (priority {}) to make room for new channel (priority {})",
                               self.id, lowest_priority_value, channel_priority);
                         self.tuner_pool.cancel_idle_close(&lowest_key).await;
-                        lowest_tuner.stop_reader().await;
+                        lowest_tuner
+                            .preempt_reader(format!("preempted by session {} (priority {} >= {})",
+                                                    self.id, channel_priority, lowest_priority_value))
+                            .await;
                         
                         // Wait for reader to stop
                         let mut wait_attempts = 0;
@@ -3080,7 +3558,7 @@ impl Session {
                         }
                     };
                     self.session_registry.update_channel_name(self.id, fb_ch_name.clone()).await;
-                    self.session_registry.update_channel_ids(self.id, fb_nid, fb_sid).await;
+                    self.session_registry.update_channel_ids(self.id, fb_nid, fb_tsid, fb_sid).await;
                     self.update_service_filter_for_sid(fb_nid, fb_tsid, fb_sid);
                     self.current_channel_name = fb_ch_name;
                     return self.send_message(ServerMessage::SetChannelSpaceAck { success: true, error_code: 0 }).await;
@@ -3090,7 +3568,7 @@ impl Session {
                 self.try_restore_previous_channel(&old_tuner_key).await;
                 return self.send_message(ServerMessage::SetChannelSpaceAck {
                     success: false,
-                    error_code: ErrorCode::ChannelSetFailed.into(),
+                    error_code: ErrorCode::TunerBusy.into(),
                 }).await;
             }
         }
@@ -3192,7 +3670,7 @@ impl Session {
                                 }
                             };
                             self.session_registry.update_channel_name(self.id, fb_ch_name.clone()).await;
-                            self.session_registry.update_channel_ids(self.id, fb_nid, fb_sid).await;
+                            self.session_registry.update_channel_ids(self.id, fb_nid, fb_tsid, fb_sid).await;
                             self.update_service_filter_for_sid(fb_nid, fb_tsid, fb_sid);
                             self.current_channel_name = fb_ch_name;
                             return self.send_message(ServerMessage::SetChannelSpaceAck { success: true, error_code: 0 }).await;
@@ -3200,7 +3678,7 @@ impl Session {
                         self.try_restore_previous_channel(&old_tuner_key).await;
                         return self.send_message(ServerMessage::SetChannelSpaceAck {
                             success: false,
-                            error_code: ErrorCode::ChannelSetFailed.into(),
+                            error_code: ErrorCode::TunerBusy.into(),
                         }).await;
                     }
 
@@ -3238,7 +3716,7 @@ impl Session {
                                 }
                             };
                             self.session_registry.update_channel_name(self.id, fb_ch_name.clone()).await;
-                            self.session_registry.update_channel_ids(self.id, fb_nid, fb_sid).await;
+                            self.session_registry.update_channel_ids(self.id, fb_nid, fb_tsid, fb_sid).await;
                             self.update_service_filter_for_sid(fb_nid, fb_tsid, fb_sid);
                             self.current_channel_name = fb_ch_name;
                             return self.send_message(ServerMessage::SetChannelSpaceAck { success: true, error_code: 0 }).await;
@@ -3250,16 +3728,107 @@ impl Session {
                         if !tuner.is_running() && !tuner.has_subscribers() {
                             self.tuner_pool.remove(&key).await;
                         }
+                        if let Some((alt_tuner, alt_space, alt_channel)) =
+                            self.try_alternate_channels(&actual_tuner_path, &alternate_candidates).await
+                        {
+                            return self.finish_alternate_channel(alt_tuner, &actual_tuner_path, alt_space, alt_channel).await;
+                        }
                         self.try_restore_previous_channel(&old_tuner_key).await;
                         return self.send_message(ServerMessage::SetChannelSpaceAck {
                             success: false,
-                            error_code: ErrorCode::ChannelSetFailed.into(),
+                            error_code: reader_start_error_code(&tuner, &e).into(),
                         }).await;
                     }
+
+                    // ★ The channel locked but may still deliver nothing in fringe
+                    // reception areas.  When the same TS is also recorded on other
+                    // physical channels of this driver (relay stations), give it a
+                    // moment and retune there before settling for a silent channel.
+                    if !alternate_candidates.is_empty()
+                        && !tuner.wait_first_data(ALTERNATE_CHANNEL_FIRST_DATA_MS).await
+                    {
+                        warn!("[Session {}] No TS on {} (space {}, ch {}); trying {} alternate physical channel(s)",
+                              self.id, actual_tuner_path, actual_actual_space, actual_bon_channel, alternate_candidates.len());
+                        tuner.stop_reader().await;
+                        if let Some((alt_tuner, alt_space, alt_channel)) =
+                            self.try_alternate_channels(&actual_tuner_path, &alternate_candidates).await
+                        {
+                            if !tuner.is_running() && !tuner.has_subscribers() {
+                                self.tuner_pool.remove(&key).await;
+                            }
+                            return self.finish_alternate_channel(alt_tuner, &actual_tuner_path, alt_space, alt_channel).await;
+                        }
+
+                        warn!("[Session {}] No alternate physical channel delivered TS; returning to space {} ch {}",
+                              self.id, actual_actual_space, actual_bon_channel);
+                        if let Err(e) = self.start_reader_with_warm(
+                            Arc::clone(&tuner),
+                            actual_tuner_path.clone(),
+                            actual_actual_space,
+                            actual_bon_channel,
+                        ).await {
+                            error!("[Session {}] Failed to restart BonDriver reader: {}", self.id, e);
+                            if !tuner.is_running() && !tuner.has_subscribers() {
+                                self.tuner_pool.remove(&key).await;
+                            }
+                            self.try_restore_previous_channel(&old_tuner_key).await;
+                            return self.send_message(ServerMessage::SetChannelSpaceAck {
+                                success: false,
+                                error_code: reader_start_error_code(&tuner, &e).into(),
+                            }).await;
+                        }
+                    }
+
+                    // ★ Optional per-band signal floor: a channel that locks but
+                    // is too weak to decode cleanly is rejected so a group
+                    // fallback driver (e.g. another antenna) can be tried.
+                    let min_signal = self.tuner_pool.config().await.min_signal_level_for_nid(entry.nid);
+                    if min_signal > 0.0 {
+                        tuner.wait_first_data(MIN_SIGNAL_CHECK_TIMEOUT_MS).await;
+                        let level = tuner.fresh_signal_level(MIN_SIGNAL_CHECK_TIMEOUT_MS).await;
+                        if (level as f64) < min_signal {
+                            warn!("[Session {}] Signal {:.2}dB on {} (space {}, ch {}) is below minimum {:.2}dB",
+                                  self.id, level, actual_tuner_path, actual_actual_space, actual_bon_channel, min_signal);
+                            tuner.stop_reader().await;
+                            if !tuner.is_running() && !tuner.has_subscribers() {
+                                self.tuner_pool.remove(&key).await;
+                            }
+                            if let Some((fb_tuner, fb_path)) = self.try_fallback_drivers(&fallback_candidates, &[&actual_tuner_path]).await {
+                                let (fb_space, fb_channel) = fallback_candidates
+                                    .iter()
+                                    .find(|(path, _, _)| *path == fb_path)
+                                    .map(|(_, space, channel)| (*space, *channel))
+                                    .unwrap_or((actual_space, actual_bon_channel));
+                                self.current_tuner_path = Some(fb_path.clone());
+                                self.refresh_current_bon_driver_id().await;
+                                self.session_registry.update_tuner(self.id, Some(fb_path.clone())).await;
+                                return self.finish_alternate_channel(fb_tuner, &fb_path, fb_space, fb_channel).await;
+                            }
+                            self.try_restore_previous_channel(&old_tuner_key).await;
+                            return self.send_message(ServerMessage::SetChannelSpaceAck {
+                                success: false,
+                                error_code: ErrorCode::NoSignal.into(),
+                            }).await;
+                        }
+                    }
                 } else {
                     info!("[Session {}] BonDriver reader already running, reusing", self.id);
                 }
 
+                // ★ The checks above can take seconds; another session may have
+                // taken the tuner over meanwhile.
+                if !tuner.is_running() {
+                    if let Some(reason) = tuner.preempt_reason() {
+                        warn!("[Session {}] Tuner {:?} was taken over during channel switch ({})",
+                              self.id, tuner.key, reason);
+                        self.try_restore_previous_channel(&old_tuner_key).await;
+                        return self.send_message(ServerMessage::SetChannelSpaceAck {
+                            success: false,
+                            error_code: ErrorCode::Preempted.into(),
+                        }).await;
+                    }
+                }
+
                 // ★ Exclusive post-start re-check: during the reader initialization
                 // window (up to ~10 s) another session may have started a new reader
                 // on the same DLL, pushing over max_instances.  Only evict if we are
@@ -3321,7 +3890,9 @@ impl Session {
                                         info!("[Session {}] Exclusive post-start: evicting idle interloper {:?}", self.id, rk);
                                     }
                                     self.tuner_pool.cancel_idle_close(rk).await;
-                                    interloper.stop_reader().await;
+                                    interloper
+                                        .preempt_reader(format!("preempted by session {} (exclusive access)", self.id))
+                                        .await;
                                     self.tuner_pool.remove(rk).await;
                                     to_evict -= 1;
                                 }
@@ -3365,7 +3936,7 @@ impl Session {
                     }
                 };
                 self.session_registry.update_channel_name(self.id, channel_name.clone()).await;
-                self.session_registry.update_channel_ids(self.id, ch_nid, ch_sid).await;
+                self.session_registry.update_channel_ids(self.id, ch_nid, ch_tsid, ch_sid).await;
                 self.update_service_filter_for_sid(ch_nid, ch_tsid, ch_sid);
                 self.current_channel_name = channel_name;
 
@@ -3381,7 +3952,7 @@ impl Session {
                 self.try_restore_previous_channel(&old_tuner_key).await;
                 self.send_message(ServerMessage::SetChannelSpaceAck {
                     success: false,
-                    error_code: ErrorCode::ChannelSetFailed.into(),
+                    error_code: ErrorCode::DriverOpenFailed.into(),
                 }).await
             }
         }
@@ -3397,6 +3968,177 @@ impl Session {
         self.send_message(ServerMessage::GetSignalLevelAck { signal_level }).await
     }
 
+    /// Handle SetKeepAlive message.
+    async fn handle_set_keep_alive(&mut self, seconds: u32) -> std::io::Result<()> {
+        let seconds = seconds.min(MAX_KEEP_ALIVE_OVERRIDE_SECS);
+        info!("[Session {}] SetKeepAlive: {}s", self.id, seconds);
+        self.keep_alive_override = Some(u64::from(seconds));
+        self.send_message(ServerMessage::SetKeepAliveAck { success: true }).await
+    }
+
+    /// Handle SetDecode message.
+    ///
+    /// Turning decoding off streams the TS as read from the driver. Turning
+    /// it on fails (and the TS stays raw) when the tuner has no working
+    /// decoder.
+    async fn handle_set_decode(&mut self, enable: bool) -> std::io::Result<()> {
+        self.decode_enabled = enable;
+        let status = self.decode_status();
+        info!("[Session {}] SetDecode: enable={}, status={:?}", self.id, enable, status);
+        self.send_message(ServerMessage::SetDecodeAck {
+            success: !matches!(status, DecodeStatus::NoCard | DecodeStatus::Error),
+            status,
+        })
+        .await
+    }
+
+    /// Decode state of the TS this session receives (Active until a tuner
+    /// reports otherwise).
+    fn decode_status(&self) -> DecodeStatus {
+        if !self.decode_enabled {
+            return DecodeStatus::Disabled;
+        }
+        self.current_tuner
+            .as_ref()
+            .map_or(DecodeStatus::Active, |tuner| tuner.decode_status())
+    }
+
+    /// Keep the raw receiver in line with the decode setting and the
+    /// current tuner, which may change under any of the tune paths.
+    fn sync_raw_receiver(&mut self) {
+        let tuner = self
+            .current_tuner
+            .as_ref()
+            .filter(|_| !self.decode_enabled && self.ts_receiver.is_some());
+        let up_to_date = match (&self.raw_receiver, tuner) {
+            (Some((weak, _)), Some(tuner)) => std::ptr::eq(weak.as_ptr(), Arc::as_ptr(tuner)),
+            (None, None) => true,
+            _ => false,
+        };
+        if up_to_date {
+            return;
+        }
+        self.raw_receiver = tuner.map(|tuner| (Arc::downgrade(tuner), tuner.subscribe_raw()));
+        // Do not splice partial packets of one stream onto the other
+        self.ts_send_carry.clear();
+        self.ts_quality_carry.clear();
+    }
+
+    /// Schedule the idle close of a tuner this session released, using the
+    /// SetKeepAlive delay if the client sent one.
+    async fn schedule_idle_close(&self, tuner: Arc<SharedTuner>) {
+        let key = tuner.key.clone();
+        match self.keep_alive_override {
+            Some(secs) => self.tuner_pool.schedule_idle_close_after(key, tuner, secs).await,
+            None => self.tuner_pool.schedule_idle_close(key, tuner).await,
+        }
+    }
+
+    /// Handle GetStreamStats message.
+    async fn handle_get_stream_stats(&mut self) -> std::io::Result<()> {
+        let signal_level = self
+            .current_tuner
+            .as_ref()
+            .map(|t| t.signal_level())
+            .unwrap_or(0.0);
+        let quality = self.ts_quality_analyzer.snapshot();
+        let rate = |count: u64| {
+            if quality.packets_total > 0 {
+                (count as f64 / quality.packets_total as f64 * 100.0) as f32
+            } else {
+                0.0
+            }
+        };
+
+        self.send_message(ServerMessage::StreamStats {
+            signal_level,
+            drop_rate: rate(quality.packets_dropped),
+            scramble_rate: rate(quality.packets_scrambled),
+            error_rate: rate(quality.packets_error),
+            bitrate_mbps: self.last_bitrate_mbps as f32,
+            decode_status: self.decode_status(),
+        })
+        .await
+    }
+
+    /// Handle GetTunerCapacity message.
+    ///
+    /// Reports running/max instances for every driver of the session's group,
+    /// or for its single driver, so clients can show a driver as busy before
+    /// a tune fails on it.
+    async fn handle_get_tuner_capacity(&mut self) -> std::io::Result<()> {
+        let driver_paths = if !self.group_driver_paths.is_empty() {
+            self.group_driver_paths.clone()
+        } else {
+            let tuner_path = self.current_or_default_tuner_path();
+            if tuner_path.is_empty() { Vec::new() } else { vec![tuner_path] }
+        };
+
+        let keys = self.tuner_pool.keys().await;
+        let mut drivers = Vec::with_capacity(driver_paths.len());
+        for tuner_path in driver_paths {
+            let running = self.running_instances(&keys, &tuner_path, false, None).await;
+            let max_instances = {
+                let db = self.database.lock().await;
+                db.get_max_instances_for_path(&tuner_path).unwrap_or(1)
+            };
+            drivers.push(TunerCapacity {
+                tuner_path,
+                running: running.clamp(0, u16::MAX as i32) as u16,
+                max_instances: max_instances.clamp(0, u16::MAX as i32) as u16,
+            });
+        }
+
+        debug!("[Session {}] GetTunerCapacity: {:?}", self.id, drivers);
+        self.send_message(ServerMessage::GetTunerCapacityAck { drivers })
+            .await
+    }
+
+    /// Handle ListTuners message.
+    ///
+    /// Lists every group and then every driver with the instances still free
+    /// on it, so clients can offer a selection instead of a configured path.
+    async fn handle_list_tuners(&mut self) -> std::io::Result<()> {
+        let (drivers, groups) = {
+            let db = self.database.lock().await;
+            (
+                db.get_all_bon_drivers().unwrap_or_default(),
+                db.get_groups().unwrap_or_default(),
+            )
+        };
+
+        let keys = self.tuner_pool.keys().await;
+        let mut available = HashMap::with_capacity(drivers.len());
+        for driver in &drivers {
+            let running = self.running_instances(&keys, &driver.dll_path, false, None).await;
+            available.insert(driver.dll_path.as_str(), (driver.max_instances - running).max(0));
+        }
+        let to_u16 = |n: i32| n.clamp(0, u16::MAX as i32) as u16;
+
+        let mut entries = Vec::with_capacity(groups.len() + drivers.len());
+        for group in &groups {
+            let free: i32 = group
+                .drivers
+                .iter()
+                .map(|d| available.get(d.dll_path.as_str()).copied().unwrap_or(0))
+                .sum();
+            entries.push(TunerListEntry {
+                name: group.name.clone(),
+                kind: TunerEntryKind::Group,
+                available_instances: to_u16(free),
+            });
+        }
+        for driver in &drivers {
+            entries.push(TunerListEntry {
+                name: driver.dll_path.clone(),
+                kind: TunerEntryKind::Driver,
+                available_instances: to_u16(available[driver.dll_path.as_str()]),
+            });
+        }
+
+        debug!("[Session {}] ListTuners: {} entries", self.id, entries.len());
+        self.send_message(ServerMessage::TunerList { entries }).await
+    }
 
     /// Handle EnumTuningSpace message.
     async fn handle_enum_tuning_space(&mut self, space: u32) -> std::io::Result<()> {
@@ -3410,15 +4152,41 @@ impl Session {
             return self.send_message(ServerMessage::EnumTuningSpaceAck { name: None }).await;
         }
 
-        let (actual_space, name, _region_key) = &space_list[space as usize];
+        let (actual_space, raw_name, region_key) = &space_list[space as usize];
+
+        // Never hand TVTest a garbled name: fall back to the region key, then the
+        // broadcaster-provided TS name from the DB, then a generic label
+        let mut name = sanitize_display_name(raw_name.as_bytes())
+            .or_else(|| sanitize_display_name(region_key.as_bytes()));
+        if name.is_none() {
+            name = self.db_tuning_space_name(*actual_space).await;
+        }
+        let name = name.unwrap_or_else(|| fallback_space_label(*actual_space));
+        if name != *raw_name {
+            warn!("[Session {}] EnumTuningSpace: space name {:?} replaced with {:?}", self.id, raw_name, name);
+        }
 
         debug!("[Session {}] EnumTuningSpace: space_idx={} actual_space={} name={:?}",
             self.id, space, actual_space, name);
 
-        self.send_message(ServerMessage::EnumTuningSpaceAck { name: Some(name.clone()) })
+        self.send_message(ServerMessage::EnumTuningSpaceAck { name: Some(name) })
             .await
     }
 
+    /// Tuning space name stored for the current tuner (TS name or band-derived).
+    async fn db_tuning_space_name(&self, actual_space: u32) -> Option<String> {
+        let tuner_path = self.current_or_default_tuner_path();
+        if tuner_path.is_empty() {
+            return None;
+        }
+        let db = self.database.lock().await;
+        let driver = db.get_bon_driver_by_path(&tuner_path).ok().flatten()?;
+        db.get_tuning_space_name(driver.id, actual_space)
+            .ok()
+            .flatten()
+            .and_then(|name| sanitize_display_name(name.as_bytes()))
+    }
+
     /// Handle EnumChannelName message.
     async fn handle_enum_channel_name(&mut self, space: u32, channel: u32) -> std::io::Result<()> {
         debug!("[Session {}] EnumChannelName: space={}, channel={}", self.id, space, channel);
@@ -3436,10 +4204,44 @@ impl Session {
         self.send_message(ServerMessage::EnumChannelNameAck { name }).await
     }
 
-    /// Handle StartStream message.
-    async fn handle_start_stream(&mut self) -> std::io::Result<()> {
-        if self.state != SessionState::TunerOpen {
-            return self
+    /// Start the tuner's passive scanner if its BonDriver allows it.
+    ///
+    /// Only the first streaming session on a tuner attaches one. Channel
+    /// records stay owned by the scheduled scanner; streaming feeds the EPG.
+    async fn attach_passive_scanner(&self, tuner: &Arc<SharedTuner>) {
+        let bon_driver = {
+            let db = self.database.lock().await;
+            db.get_bon_driver_by_path(&tuner.key.tuner_path).ok().flatten()
+        };
+        let Some(bon_driver) = bon_driver.filter(|d| d.passive_scan_enabled) else {
+            return;
+        };
+        let Some(rx) = tuner.subscribe_passive() else {
+            return;
+        };
+
+        let (space, channel) = match tuner.key.channel {
+            ChannelKeySpec::SpaceChannel { space, channel } => (space, channel),
+            ChannelKeySpec::Simple(channel) => (0, channel as u32),
+        };
+        debug!("[Session {}] Attaching passive scanner to {:?}", self.id, tuner.key);
+        tokio::spawn(start_passive_scan(
+            rx,
+            Arc::clone(&self.database),
+            bon_driver.id,
+            space,
+            channel,
+            PassiveScanConfig {
+                update_channels: false,
+                ..PassiveScanConfig::default()
+            },
+        ));
+    }
+
+    /// Handle StartStream message.
+    async fn handle_start_stream(&mut self) -> std::io::Result<()> {
+        if self.state != SessionState::TunerOpen {
+            return self
                 .send_error(ErrorCode::InvalidState, "Tuner not open")
                 .await;
         }
@@ -3470,6 +4272,12 @@ impl Session {
         self.ts_receiver = Some(rx);
         self.state = SessionState::Streaming;
 
+        let config = self.tuner_pool.config().await;
+        self.ts_coalescer = TsCoalescer::new(
+            config.ts_coalesce_bytes,
+            std::time::Duration::from_millis(config.ts_coalesce_flush_ms),
+        );
+
         if let Err(e) = self.start_tsreplace_pipeline().await {
             if self.tsreplace_passthrough_on_error {
                 warn!("[Session {}] tsreplace unavailable, fallback to raw TS: {}", self.id, e);
@@ -3487,6 +4295,8 @@ impl Session {
             }
         }
 
+        self.attach_passive_scanner(&tuner).await;
+
         // Update session registry
         self.session_registry.update_streaming(self.id, true).await;
 
@@ -3513,13 +4323,14 @@ impl Session {
                 // If so, automatically stop the reader
                 if tuner.subscriber_count() == 0 {
                     info!("[Session {}] No more subscribers after StopStream, scheduling keep-alive close for {:?}", self.id, tuner.key);
-                    self.tuner_pool
-                        .schedule_idle_close(tuner.key.clone(), Arc::clone(tuner))
-                        .await;
+                    self.schedule_idle_close(Arc::clone(tuner)).await;
                 }
             }
         }
         self.ts_receiver = None;
+        self.raw_receiver = None;
+        self.ts_coalescer = None;
+        self.last_bitrate_mbps = 0.0;
         self.stop_tsreplace_pipeline().await;
         self.state = SessionState::TunerOpen;
 
@@ -3538,19 +4349,66 @@ impl Session {
         if let Some(rx) = &mut self.ts_receiver {
             while rx.try_recv().is_ok() {}
         }
+        if let Some((_, rx)) = &mut self.raw_receiver {
+            while rx.try_recv().is_ok() {}
+        }
+        if let Some(coalescer) = &mut self.ts_coalescer {
+            coalescer.clear();
+        }
 
         self.send_message(ServerMessage::PurgeStreamAck { success: true })
             .await
     }
 
     /// Handle SetLnbPower message.
+    ///
+    /// Applies to the tuner the session is tuned to; the supply is shared
+    /// with other sessions on that tuner.
     async fn handle_set_lnb_power(&mut self, enable: bool) -> std::io::Result<()> {
         info!("[Session {}] SetLnbPower: {}", self.id, enable);
 
-        // TODO: Implement actual LNB power control
+        // Release a hold left on a tuner this session has since moved away from
+        if let Some(old) = self.lnb_tuner.take() {
+            match &self.current_tuner {
+                Some(current) if Arc::ptr_eq(current, &old) => self.lnb_tuner = Some(old),
+                _ => {
+                    let _ = old.set_lnb_power(self.id, false).await;
+                }
+            }
+        }
+
+        // The BonDriver only exists while its reader is running
+        let tuner = match &self.current_tuner {
+            Some(tuner) if tuner.is_running() => Arc::clone(tuner),
+            _ => {
+                let error_code = if enable { ErrorCode::InvalidState.into() } else { 0 };
+                return self
+                    .send_message(ServerMessage::SetLnbPowerAck {
+                        success: !enable,
+                        error_code,
+                    })
+                    .await;
+            }
+        };
+
+        let error_code: u16 = match tuner.set_lnb_power(self.id, enable).await {
+            Ok(()) => {
+                self.lnb_tuner = enable.then_some(tuner);
+                0
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Unsupported => {
+                warn!("[Session {}] LNB power control not supported by {:?}: {}", self.id, tuner.key, e);
+                ErrorCode::InvalidParameter.into()
+            }
+            Err(e) => {
+                warn!("[Session {}] SetLnbPower failed on {:?}: {}", self.id, tuner.key, e);
+                ErrorCode::Unknown.into()
+            }
+        };
+
         self.send_message(ServerMessage::SetLnbPowerAck {
-            success: true,
-            error_code: 0,
+            success: error_code == 0,
+            error_code,
         })
         .await
     }
@@ -3620,11 +4478,17 @@ impl Session {
     }
 
     /// Handle SelectLogicalChannel message.
+    ///
+    /// Candidates are tried in order, skipping drivers at capacity. When
+    /// every candidate is at capacity, a second pass frees a slot using the
+    /// same priority/exclusive rules as SetChannelSpace.
     async fn handle_select_logical_channel(
         &mut self,
         nid: u16,
         tsid: u16,
         sid: Option<u16>,
+        priority: Option<i32>,
+        exclusive: Option<bool>,
     ) -> std::io::Result<()> {
         if self.state != SessionState::Ready
             && self.state != SessionState::TunerOpen
@@ -3636,12 +4500,25 @@ impl Session {
         }
 
         info!(
-            "[Session {}] SelectLogicalChannel: nid={}, tsid={}, sid={:?}",
-            self.id, nid, tsid, sid
+            "[Session {}] SelectLogicalChannel: nid={}, tsid={}, sid={:?}, priority={:?}, exclusive={:?}",
+            self.id, nid, tsid, sid, priority, exclusive
         );
 
+        if priority.is_some() || exclusive.is_some() {
+            self.session_registry
+                .update_client_controls(self.id, priority, exclusive)
+                .await;
+        }
+        let (priority, exclusive) = self
+            .session_registry
+            .get_effective_controls(self.id)
+            .await
+            .unwrap_or((priority, exclusive.unwrap_or(false)));
+        let priority = priority.unwrap_or(0);
+
         // Look up channel in database
-        let channels = {
+        let allowed = self.allowed_drivers().await;
+        let mut channels = {
             let db = self.database.lock().await;
             match db.get_channels_by_nid_tsid_ordered(nid, tsid, sid) {
                 Ok(chs) => chs,
@@ -3661,6 +4538,30 @@ impl Session {
             }
         };
 
+        if let Some(allowed) = &allowed {
+            let found = channels.len();
+            channels.retain(|ch| allowed.contains(&ch.channel.bon_driver_id));
+            if channels.is_empty() && found > 0 {
+                warn!(
+                    "[Session {}] Client '{}' is not allowed to use any of the {} driver(s) carrying nid={}, tsid={}",
+                    self.id,
+                    self.client_identity.as_deref().unwrap_or_default(),
+                    found,
+                    nid,
+                    tsid
+                );
+                return self
+                    .send_message(ServerMessage::SelectLogicalChannelAck {
+                        success: false,
+                        error_code: ErrorCode::PermissionDenied.into(),
+                        tuner_id: None,
+                        space: None,
+                        channel: None,
+                    })
+                    .await;
+            }
+        }
+
         if channels.is_empty() {
             info!(
                 "[Session {}] No channel found for nid={}, tsid={}, sid={:?}",
@@ -3669,7 +4570,7 @@ impl Session {
             return self
                 .send_message(ServerMessage::SelectLogicalChannelAck {
                     success: false,
-                    error_code: ErrorCode::ChannelSetFailed.into(),
+                    error_code: ErrorCode::ChannelNotFound.into(),
                     tuner_id: None,
                     space: None,
                     channel: None,
@@ -3681,7 +4582,6 @@ impl Session {
         // each one until we find a tuner that can be opened successfully.
         // This provides automatic fallback when the highest-priority driver is
         // busy, at capacity, or experiencing a hardware error.
-        let pool_keys = self.tuner_pool.keys().await;
 
         // ★ Capture the current session's tuner info BEFORE the loop.
         // If this session is the sole subscriber, its slot will be freed during
@@ -3697,222 +4597,258 @@ impl Session {
             })
             .unwrap_or(false);
 
-        for (candidate_idx, channel_with_driver) in channels.iter().enumerate() {
-            let channel_record = &channel_with_driver.channel;
-            let tuner_id = channel_with_driver.bon_driver_path.clone();
-            let space = channel_record.bon_space.unwrap_or(0);
-            let channel = channel_record.bon_channel.unwrap_or(0);
+        // Why the last candidate that could be tried failed; busy if none could
+        let mut failure = ErrorCode::TunerBusy;
+        let mut at_capacity: Vec<usize> = Vec::new();
+        for make_room in [false, true] {
+            if make_room && at_capacity.is_empty() {
+                break;
+            }
+            let pool_keys = self.tuner_pool.keys().await;
 
-            // ★ Capacity check: skip drivers that are already at max_instances.
-            let max_instances = {
-                let db = self.database.lock().await;
-                db.get_max_instances_for_path(&tuner_id).unwrap_or(1)
-            };
+            for (candidate_idx, channel_with_driver) in channels.iter().enumerate() {
+                if make_room && !at_capacity.contains(&candidate_idx) {
+                    continue;
+                }
+                let channel_record = &channel_with_driver.channel;
+                let tuner_id = channel_with_driver.bon_driver_path.clone();
+                let space = channel_record.bon_space.unwrap_or(0);
+                let channel = channel_record.bon_channel.unwrap_or(0);
 
-            let key = ChannelKey::space_channel(&tuner_id, space, channel);
-
-            // Count how many instances of this driver are already running
-            // (excluding an entry for the exact same channel key we're about
-            // to create, since get_or_create would reuse it).
-            let mut running_instances = 0i32;
-            for gk in &pool_keys {
-                if gk.tuner_path == tuner_id && *gk != key {
-                    // Skip the current session's own tuner if it will be freed
-                    // during channel switch (sole subscriber → slot released).
-                    if old_tuner_will_free_slot && old_tuner_key.as_ref() == Some(gk) {
-                        continue;
-                    }
-                    if let Some(existing) = self.tuner_pool.get(gk).await {
-                        if existing.is_running() {
-                            running_instances += 1;
+                // ★ Capacity check: skip drivers that are already at max_instances.
+                let max_instances = {
+                    let db = self.database.lock().await;
+                    db.get_max_instances_for_path(&tuner_id).unwrap_or(1)
+                };
+
+                let key = ChannelKey::space_channel(&tuner_id, space, channel);
+
+                // Count how many instances of this driver are already running
+                // (excluding an entry for the exact same channel key we're about
+                // to create, since get_or_create would reuse it).
+                let mut running_instances = 0i32;
+                for gk in &pool_keys {
+                    if gk.tuner_path == tuner_id && *gk != key {
+                        // Skip the current session's own tuner if it will be freed
+                        // during channel switch (sole subscriber → slot released).
+                        if old_tuner_will_free_slot && old_tuner_key.as_ref() == Some(gk) {
+                            continue;
+                        }
+                        if let Some(existing) = self.tuner_pool.get(gk).await {
+                            if existing.is_running() {
+                                running_instances += 1;
+                            }
                         }
                     }
                 }
-            }
-
-            // Check if an exact-key tuner is already in the pool and running;
-            // if so it doesn't count as a "new" instance.
-            let existing_for_key = self.tuner_pool.get(&key).await;
-            let reuse_existing = existing_for_key
-                .as_ref()
-                .map_or(false, |t| t.is_running());
-
-            if !reuse_existing && (running_instances + 1) > max_instances {
-                info!(
-                    "[Session {}] SelectLogicalChannel: skipping candidate {} '{}' — at capacity ({}/{} instances)",
-                    self.id, candidate_idx, tuner_id, running_instances, max_instances
-                );
-                continue;
-            }
 
-            // Set current tuner path (will be overwritten if this attempt fails and
-            // we move on to the next candidate).
-            self.current_tuner_path = Some(tuner_id.clone());
-            self.refresh_current_bon_driver_id().await;
+                // Check if an exact-key tuner is already in the pool and running;
+                // if so it doesn't count as a "new" instance.
+                let existing_for_key = self.tuner_pool.get(&key).await;
+                let reuse_existing = existing_for_key
+                    .as_ref()
+                    .map_or(false, |t| t.is_running());
 
-            // Try to obtain or create the tuner entry in the pool
-            let tuner = match self
-                .tuner_pool
-                .get_or_create(key.clone(), 2, || async { Ok(()) })
-                .await
-            {
-                Ok(t) => t,
-                Err(e) => {
-                    warn!(
-                        "[Session {}] SelectLogicalChannel: candidate {} '{}' pool creation failed: {}",
-                        self.id, candidate_idx, tuner_id, e
-                    );
-                    continue;
+                if !reuse_existing && (running_instances + 1) > max_instances {
+                    if !make_room {
+                        info!(
+                            "[Session {}] SelectLogicalChannel: skipping candidate {} '{}' — at capacity ({}/{} instances)",
+                            self.id, candidate_idx, tuner_id, running_instances, max_instances
+                        );
+                        at_capacity.push(candidate_idx);
+                        continue;
+                    }
+                    let channel_priority = if priority > 0 {
+                        priority
+                    } else if exclusive {
+                        i32::MAX
+                    } else {
+                        let db = self.database.lock().await;
+                        db.get_channel_priority(&tuner_id, space, channel)
+                            .unwrap_or(Some(0))
+                            .unwrap_or(0)
+                    };
+                    let own_key = old_tuner_key.as_ref().filter(|_| old_tuner_will_free_slot);
+                    if !self.free_driver_slot(&tuner_id, channel_priority, exclusive, own_key).await {
+                        info!(
+                            "[Session {}] SelectLogicalChannel: candidate {} '{}' at capacity and nothing can be preempted (priority {})",
+                            self.id, candidate_idx, tuner_id, channel_priority
+                        );
+                        continue;
+                    }
                 }
-            };
 
-            // ★ Bug H fix: cancel any pending idle-close before using this tuner.
-            self.tuner_pool.cancel_idle_close(&key).await;
-
-            // Start the BonDriver reader if not already running
-            if !tuner.is_running() {
-                if let Err(e) = self.start_reader_with_warm(
-                    Arc::clone(&tuner),
-                    tuner_id.clone(),
-                    space,
-                    channel,
-                ).await {
-                    if e.kind() == std::io::ErrorKind::AddrNotAvailable {
+                // Set current tuner path (will be overwritten if this attempt fails and
+                // we move on to the next candidate).
+                self.current_tuner_path = Some(tuner_id.clone());
+                self.refresh_current_bon_driver_id().await;
+
+                // Try to obtain or create the tuner entry in the pool
+                let tuner = match self
+                    .tuner_pool
+                    .get_or_create(key.clone(), 2, || async { Ok(()) })
+                    .await
+                {
+                    Ok(t) => t,
+                    Err(e) => {
                         warn!(
-                            "[Session {}] SelectLogicalChannel: candidate {} '{}' channel unavailable: {}",
-                            self.id, candidate_idx, tuner_id, e
-                        );
-                    } else {
-                        error!(
-                            "[Session {}] SelectLogicalChannel: candidate {} '{}' failed to start reader: {}",
+                            "[Session {}] SelectLogicalChannel: candidate {} '{}' pool creation failed: {}",
                             self.id, candidate_idx, tuner_id, e
                         );
+                        failure = ErrorCode::DriverOpenFailed;
+                        continue;
                     }
-                    // Clean up the orphaned pool entry
-                    if !tuner.is_running() && !tuner.has_subscribers() {
-                        self.tuner_pool.remove(&key).await;
+                };
+
+                // ★ Bug H fix: cancel any pending idle-close before using this tuner.
+                self.tuner_pool.cancel_idle_close(&key).await;
+
+                // Start the BonDriver reader if not already running
+                if !tuner.is_running() {
+                    if let Err(e) = self.start_reader_with_warm(
+                        Arc::clone(&tuner),
+                        tuner_id.clone(),
+                        space,
+                        channel,
+                    ).await {
+                        if e.kind() == std::io::ErrorKind::AddrNotAvailable {
+                            warn!(
+                                "[Session {}] SelectLogicalChannel: candidate {} '{}' channel unavailable: {}",
+                                self.id, candidate_idx, tuner_id, e
+                            );
+                        } else {
+                            error!(
+                                "[Session {}] SelectLogicalChannel: candidate {} '{}' failed to start reader: {}",
+                                self.id, candidate_idx, tuner_id, e
+                            );
+                        }
+                        failure = reader_start_error_code(&tuner, &e);
+                        // Clean up the orphaned pool entry
+                        if !tuner.is_running() && !tuner.has_subscribers() {
+                            self.tuner_pool.remove(&key).await;
+                        }
+                        // Try the next candidate
+                        continue;
                     }
-                    // Try the next candidate
-                    continue;
                 }
-            }
 
-            // ★ Success — this candidate works.
-            // Properly unsubscribe from the old tuner before switching.
-            let old_tuner = self.current_tuner.take();
-            if let Some(old) = old_tuner {
-                let same_tuner_reuse = Arc::ptr_eq(&old, &tuner);
-                if same_tuner_reuse {
-                    // Same SharedTuner (same channel key) — keep subscription.
-                    debug!("[Session {}] SelectLogicalChannel: reusing same tuner", self.id);
-                    if self.state == SessionState::Streaming {
-                        let new_rx = tuner.subscribe();
-                        self.ts_receiver = Some(new_rx);
-                        old.unsubscribe();
-                    }
-                } else {
-                    // Different tuner — unsubscribe from old and subscribe to new.
-                    if self.ts_receiver.is_some() {
-                        old.unsubscribe();
-                        self.ts_receiver = None;
-                        debug!("[Session {}] SelectLogicalChannel: unsubscribed from old tuner, remaining subscribers: {}",
-                               self.id, old.subscriber_count());
-                        if old.subscriber_count() == 0 {
-                            // Stop the old tuner synchronously.  This is critical when
-                            // the hardware (e.g. multi-tuner USB card) cannot have
-                            // multiple DLLs open simultaneously within a group.
-                            let old_max = {
-                                let db = self.database.lock().await;
-                                db.get_max_instances_for_path(&old.key.tuner_path).unwrap_or(1)
-                            };
-                            let old_running = {
-                                let ks = self.tuner_pool.keys().await;
-                                let mut n = 0i32;
-                                for k in &ks {
-                                    if k.tuner_path == old.key.tuner_path {
-                                        if let Some(t) = self.tuner_pool.get(k).await {
-                                            if t.is_running() { n += 1; }
+                // ★ Success — this candidate works.
+                // Properly unsubscribe from the old tuner before switching.
+                let old_tuner = self.current_tuner.take();
+                if let Some(old) = old_tuner {
+                    let same_tuner_reuse = Arc::ptr_eq(&old, &tuner);
+                    if same_tuner_reuse {
+                        // Same SharedTuner (same channel key) — keep subscription.
+                        debug!("[Session {}] SelectLogicalChannel: reusing same tuner", self.id);
+                        if self.state == SessionState::Streaming {
+                            let new_rx = tuner.subscribe();
+                            self.ts_receiver = Some(new_rx);
+                            old.unsubscribe();
+                        }
+                    } else {
+                        // Different tuner — unsubscribe from old and subscribe to new.
+                        if self.ts_receiver.is_some() {
+                            old.unsubscribe();
+                            self.ts_receiver = None;
+                            debug!("[Session {}] SelectLogicalChannel: unsubscribed from old tuner, remaining subscribers: {}",
+                                   self.id, old.subscriber_count());
+                            if old.subscriber_count() == 0 {
+                                // Stop the old tuner synchronously.  This is critical when
+                                // the hardware (e.g. multi-tuner USB card) cannot have
+                                // multiple DLLs open simultaneously within a group.
+                                let old_max = {
+                                    let db = self.database.lock().await;
+                                    db.get_max_instances_for_path(&old.key.tuner_path).unwrap_or(1)
+                                };
+                                let old_running = {
+                                    let ks = self.tuner_pool.keys().await;
+                                    let mut n = 0i32;
+                                    for k in &ks {
+                                        if k.tuner_path == old.key.tuner_path {
+                                            if let Some(t) = self.tuner_pool.get(k).await {
+                                                if t.is_running() { n += 1; }
+                                            }
                                         }
                                     }
+                                    n
+                                };
+                                if old.key.tuner_path == tuner_id || old_running >= old_max {
+                                    // Same DLL switch or at capacity — stop synchronously.
+                                    info!("[Session {}] SelectLogicalChannel: stopping old reader for {:?}",
+                                          self.id, old.key);
+                                    self.tuner_pool.cancel_idle_close(&old.key).await;
+                                    old.stop_reader().await;
+                                    self.tuner_pool.remove(&old.key).await;
+                                } else {
+                                    // Different DLL with spare capacity — schedule idle close.
+                                    info!("[Session {}] SelectLogicalChannel: scheduling idle close for {:?}",
+                                          self.id, old.key);
+                                    self.schedule_idle_close(old).await;
                                 }
-                                n
-                            };
-                            if old.key.tuner_path == tuner_id || old_running >= old_max {
-                                // Same DLL switch or at capacity — stop synchronously.
-                                info!("[Session {}] SelectLogicalChannel: stopping old reader for {:?}",
-                                      self.id, old.key);
-                                self.tuner_pool.cancel_idle_close(&old.key).await;
-                                old.stop_reader().await;
-                                self.tuner_pool.remove(&old.key).await;
-                            } else {
-                                // Different DLL with spare capacity — schedule idle close.
-                                info!("[Session {}] SelectLogicalChannel: scheduling idle close for {:?}",
-                                      self.id, old.key);
-                                self.tuner_pool.schedule_idle_close(old.key.clone(), old).await;
                             }
                         }
+                        if self.state == SessionState::Streaming {
+                            self.ts_receiver = Some(tuner.subscribe());
+                        }
                     }
-                    if self.state == SessionState::Streaming {
-                        self.ts_receiver = Some(tuner.subscribe());
-                    }
+                } else if self.state == SessionState::Streaming {
+                    self.ts_receiver = Some(tuner.subscribe());
                 }
-            } else if self.state == SessionState::Streaming {
-                self.ts_receiver = Some(tuner.subscribe());
-            }
 
-            self.current_tuner = Some(tuner);
+                self.current_tuner = Some(tuner);
 
-            // Notify B25 decoder about channel change
-            if let Some(tuner) = &self.current_tuner {
-                tuner.notify_channel_change();
-            }
+                // Notify B25 decoder about channel change
+                if let Some(tuner) = &self.current_tuner {
+                    tuner.notify_channel_change();
+                }
 
-            self.restart_tsreplace_pipeline_if_streaming().await;
+                self.restart_tsreplace_pipeline_if_streaming().await;
 
-            if self.state == SessionState::Ready {
-                self.state = SessionState::TunerOpen;
-            }
+                if self.state == SessionState::Ready {
+                    self.state = SessionState::TunerOpen;
+                }
 
-            info!(
-                "[Session {}] Logical channel selected (candidate {}): tuner={}, space={}, channel={}",
-                self.id, candidate_idx, tuner_id, space, channel
-            );
+                info!(
+                    "[Session {}] Logical channel selected (candidate {}): tuner={}, space={}, channel={}",
+                    self.id, candidate_idx, tuner_id, space, channel
+                );
 
-            // Update session registry
-            self.session_registry
-                .update_tuner(self.id, Some(tuner_id.clone()))
-                .await;
+                // Update session registry
+                self.session_registry
+                    .update_tuner(self.id, Some(tuner_id.clone()))
+                    .await;
 
-            // Update channel info, name, and NID/SID for dashboard logo
-            let channel_info = format!("Space {}, Ch {}", space, channel);
-            self.session_registry.update_channel(self.id, Some(channel_info.clone())).await;
-            self.current_channel_info = Some(channel_info);
+                // Update channel info, name, and NID/SID for dashboard logo
+                let channel_info = format!("Space {}, Ch {}", space, channel);
+                self.session_registry.update_channel(self.id, Some(channel_info.clone())).await;
+                self.current_channel_info = Some(channel_info);
 
-            let (channel_name, ch_nid, ch_tsid, ch_sid) = {
-                let db = self.database.lock().await;
-                match db.get_channel_by_physical(&tuner_id, space, channel) {
-                    Ok(Some(rec)) => (
-                        rec.channel_name.or(rec.raw_name),
-                        Some(rec.nid),
-                        Some(rec.tsid),
-                        Some(rec.sid),
-                    ),
-                    _ => (None, None, None, None),
-                }
-            };
-            self.session_registry.update_channel_name(self.id, channel_name.clone()).await;
-            self.session_registry.update_channel_ids(self.id, ch_nid, ch_sid).await;
-            self.update_service_filter_for_sid(ch_nid, ch_tsid, ch_sid);
-            self.current_channel_name = channel_name;
-
-            return self.send_message(ServerMessage::SelectLogicalChannelAck {
-                success: true,
-                error_code: 0,
-                tuner_id: Some(tuner_id),
-                space: Some(space),
-                channel: Some(channel),
-            })
-            .await;
+                let (channel_name, ch_nid, ch_tsid, ch_sid) = {
+                    let db = self.database.lock().await;
+                    match db.get_channel_by_physical(&tuner_id, space, channel) {
+                        Ok(Some(rec)) => (
+                            rec.channel_name.or(rec.raw_name),
+                            Some(rec.nid),
+                            Some(rec.tsid),
+                            Some(rec.sid),
+                        ),
+                        _ => (None, None, None, None),
+                    }
+                };
+                self.session_registry.update_channel_name(self.id, channel_name.clone()).await;
+                self.session_registry.update_channel_ids(self.id, ch_nid, ch_tsid, ch_sid).await;
+                self.update_service_filter_for_sid(ch_nid, ch_tsid, ch_sid);
+                self.current_channel_name = channel_name;
+
+                return self.send_message(ServerMessage::SelectLogicalChannelAck {
+                    success: true,
+                    error_code: 0,
+                    tuner_id: Some(tuner_id),
+                    space: Some(space),
+                    channel: Some(channel),
+                })
+                .await;
+            }
         }
 
         // All candidates exhausted
@@ -3922,7 +4858,7 @@ impl Session {
         );
         self.send_message(ServerMessage::SelectLogicalChannelAck {
             success: false,
-            error_code: ErrorCode::ChannelSetFailed.into(),
+            error_code: failure.into(),
             tuner_id: None,
             space: None,
             channel: None,
@@ -3930,6 +4866,220 @@ impl Session {
         .await
     }
 
+    /// Stop one running tuner of `tuner_path` so a new channel fits.
+    ///
+    /// Without `exclusive` only subscriber-less tuners whose channel priority
+    /// is not above `channel_priority` are eligible; with `exclusive` the
+    /// lowest-priority tuner is taken even if it has subscribers, preferring
+    /// idle ones. `own_key` (the session's current tuner) is never stopped.
+    /// Returns whether a tuner was stopped.
+    async fn free_driver_slot(
+        &mut self,
+        tuner_path: &str,
+        channel_priority: i32,
+        exclusive: bool,
+        own_key: Option<&ChannelKey>,
+    ) -> bool {
+        let mut best_idle: Option<(ChannelKey, i32)> = None;
+        let mut best_any: Option<(ChannelKey, i32)> = None;
+        for existing_key in self.tuner_pool.keys().await {
+            if existing_key.tuner_path != tuner_path || own_key == Some(&existing_key) {
+                continue;
+            }
+            let Some(existing) = self.tuner_pool.get(&existing_key).await else { continue; };
+            if !existing.is_running() {
+                continue;
+            }
+            let (es, ec) = match &existing_key.channel {
+                ChannelKeySpec::SpaceChannel { space, channel } => (*space, *channel),
+                ChannelKeySpec::Simple(ch) => (0, *ch as u32),
+            };
+            let existing_priority = {
+                let db = self.database.lock().await;
+                db.get_channel_priority(&existing_key.tuner_path, es, ec)
+                    .unwrap_or(Some(0))
+                    .unwrap_or(0)
+            };
+            if !existing.has_subscribers() && best_idle.as_ref().map_or(true, |(_, p)| existing_priority < *p) {
+                best_idle = Some((existing_key.clone(), existing_priority));
+            }
+            if best_any.as_ref().map_or(true, |(_, p)| existing_priority < *p) {
+                best_any = Some((existing_key, existing_priority));
+            }
+        }
+
+        let target = if exclusive {
+            best_idle.or(best_any)
+        } else {
+            best_idle.filter(|(_, p)| channel_priority >= *p)
+        };
+        let Some((target_key, target_priority)) = target else {
+            return false;
+        };
+        let Some(target_tuner) = self.tuner_pool.get(&target_key).await else {
+            return false;
+        };
+
+        let reason = if exclusive {
+            format!("preempted by session {} (exclusive access)", self.id)
+        } else {
+            format!("preempted by session {} (priority {} >= {})", self.id, channel_priority, target_priority)
+        };
+        info!("[Session {}] Freeing a slot on '{}': stopping {:?} (priority {}, {} subscriber(s))",
+              self.id, tuner_path, target_key, target_priority, target_tuner.subscriber_count());
+        self.tuner_pool.cancel_idle_close(&target_key).await;
+        target_tuner.preempt_reader(reason).await;
+
+        let mut wait_attempts = 0;
+        while target_tuner.is_running() && wait_attempts < 50 {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            wait_attempts += 1;
+        }
+        self.tuner_pool.remove(&target_key).await;
+        true
+    }
+
+    /// Handle SelectByEvent message.
+    ///
+    /// Resolves the EPG event to its service via the stored EIT and then
+    /// tunes it through the normal logical channel selection path.
+    async fn handle_select_by_event(
+        &mut self,
+        nid: u16,
+        sid: u16,
+        event_id: u16,
+    ) -> std::io::Result<()> {
+        info!(
+            "[Session {}] SelectByEvent: nid={}, sid={}, event_id=0x{:04X}",
+            self.id, nid, sid, event_id
+        );
+
+        let event = {
+            let db = self.database.lock().await;
+            db.get_epg_event(nid, sid, event_id)
+        };
+
+        let event = match event {
+            Ok(Some(ev)) => ev,
+            Ok(None) => {
+                info!(
+                    "[Session {}] SelectByEvent: event 0x{:04X} not found in EPG for nid={}, sid={}",
+                    self.id, event_id, nid, sid
+                );
+                return self
+                    .send_message(ServerMessage::SelectLogicalChannelAck {
+                        success: false,
+                        error_code: ErrorCode::InvalidParameter.into(),
+                        tuner_id: None,
+                        space: None,
+                        channel: None,
+                    })
+                    .await;
+            }
+            Err(e) => {
+                error!("[Session {}] Failed to query EPG event: {}", self.id, e);
+                return self
+                    .send_message(ServerMessage::SelectLogicalChannelAck {
+                        success: false,
+                        error_code: ErrorCode::ChannelSetFailed.into(),
+                        tuner_id: None,
+                        space: None,
+                        channel: None,
+                    })
+                    .await;
+            }
+        };
+
+        debug!(
+            "[Session {}] SelectByEvent: resolved to tsid={} title={:?} start={:?}",
+            self.id, event.tsid, event.title, event.start_time
+        );
+
+        self.handle_select_logical_channel(event.nid, event.tsid, Some(event.sid), None, None)
+            .await
+    }
+
+    /// Handle SetChannelByRemoteKey message.
+    ///
+    /// Looks up the service assigned to the remote control key on the
+    /// session's driver (or group) and tunes it through the normal logical
+    /// channel selection path. With a space hint only that space's region is
+    /// searched; otherwise spaces are tried in client order, so the local
+    /// terrestrial region wins over BS/CS.
+    async fn handle_set_channel_by_remote_key(
+        &mut self,
+        remote_key: u8,
+        space_hint: Option<u32>,
+    ) -> std::io::Result<()> {
+        info!(
+            "[Session {}] SetChannelByRemoteKey: remote_key={}, space_hint={:?}",
+            self.id, remote_key, space_hint
+        );
+
+        self.ensure_space_list().await;
+        let spaces = self.get_space_list_with_names().await;
+        let region_keys: Vec<String> = match space_hint {
+            Some(space_idx) => resolve_space_idx(&spaces, space_idx)
+                .map(|(_, region_key)| vec![region_key])
+                .unwrap_or_default(),
+            None => spaces.into_iter().map(|(_, _, region_key)| region_key).collect(),
+        };
+
+        let driver_paths = if !self.group_driver_paths.is_empty() {
+            self.group_driver_paths.clone()
+        } else {
+            vec![self.current_or_default_tuner_path()]
+        };
+
+        let rows = {
+            let db = self.database.lock().await;
+            db.get_all_channels_with_drivers()
+        };
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!("[Session {}] Failed to query channels: {}", self.id, e);
+                return self
+                    .send_message(ServerMessage::SelectLogicalChannelAck {
+                        success: false,
+                        error_code: ErrorCode::ChannelSetFailed.into(),
+                        tuner_id: None,
+                        space: None,
+                        channel: None,
+                    })
+                    .await;
+            }
+        };
+
+        let found = region_keys
+            .iter()
+            .find_map(|region_key| find_by_remote_key(&rows, &driver_paths, region_key, remote_key));
+
+        let Some((nid, tsid, sid)) = found else {
+            info!(
+                "[Session {}] SetChannelByRemoteKey: no enabled service for remote_key={} (space_hint={:?})",
+                self.id, remote_key, space_hint
+            );
+            return self
+                .send_message(ServerMessage::SelectLogicalChannelAck {
+                    success: false,
+                    error_code: ErrorCode::ChannelNotFound.into(),
+                    tuner_id: None,
+                    space: None,
+                    channel: None,
+                })
+                .await;
+        };
+
+        debug!(
+            "[Session {}] SetChannelByRemoteKey: resolved to nid={}, tsid={}, sid={}",
+            self.id, nid, tsid, sid
+        );
+
+        self.handle_select_logical_channel(nid, tsid, Some(sid), None, None)
+            .await
+    }
+
     /// Handle GetChannelList message.
     async fn handle_get_channel_list(
         &mut self,
@@ -4128,6 +5278,7 @@ impl Session {
             );
             let elapsed = self.last_ts_log.elapsed().as_secs_f64().max(0.001);
             self.last_ts_log = std::time::Instant::now();
+            self.refresh_ts_throttle().await;
 
             // Update session registry with signal and packet stats
             if let Some(tuner) = &self.current_tuner {
@@ -4136,6 +5287,7 @@ impl Session {
                 let packets_sent = self.ts_bytes_sent / 188; // TS packet size
 
                 let bitrate_mbps = (self.bytes_since_last as f64 * 8.0) / 1_000_000.0 / elapsed;
+                self.last_bitrate_mbps = bitrate_mbps;
                 let packet_loss_rate = if self.interval_packets_total > 0 {
                     (self.interval_packets_dropped as f64 / self.interval_packets_total as f64) * 100.0
                 } else {
@@ -4161,6 +5313,10 @@ impl Session {
                     signal_level,
                 ).await;
 
+                self.session_registry
+                    .update_pid_offenders(self.id, self.pid_offenders())
+                    .await;
+
                 self.signal_samples += 1;
                 self.signal_level_sum += signal_level as f64;
 
@@ -4176,13 +5332,57 @@ impl Session {
             }
         }
 
-        self.send_ts_data_raw(send_data).await
+        // Pace writes to the bitrate cap. Once the bucket is in debt the run
+        // loop stops taking TS until it is paid back, still serving client
+        // commands; the backlog stays in the broadcast channel, so a catch-up
+        // burst after a lag is spread out (or dropped as lag) instead of
+        // flooding the uplink.
+        if let Some(throttle) = self.ts_throttle.as_mut() {
+            let wait = throttle.consume(send_data.len(), std::time::Instant::now());
+            if !wait.is_zero() {
+                self.ts_paced_until = Some(tokio::time::Instant::now() + wait);
+            }
+        }
+
+        let Some(coalescer) = self.ts_coalescer.as_mut() else {
+            return self.send_ts_data_raw(send_data).await;
+        };
+        for frame_data in coalescer.push(send_data, std::time::Instant::now()) {
+            self.send_ts_data_raw(frame_data).await?;
+        }
+        Ok(())
+    }
+
+    /// Send whatever TS the coalescer is holding.
+    async fn flush_ts_coalescer(&mut self) -> std::io::Result<()> {
+        match self.ts_coalescer.as_mut().and_then(TsCoalescer::flush) {
+            Some(data) => self.send_ts_data_raw(data).await,
+            None => Ok(()),
+        }
+    }
+
+    /// PIDs with problems, worst first.
+    fn pid_offenders(&self) -> Vec<SessionPidStat> {
+        let mut offenders: Vec<SessionPidStat> = self
+            .ts_quality_analyzer
+            .pid_stats()
+            .into_iter()
+            .filter(|(_, stat)| stat.has_problems())
+            .map(|(pid, stat)| SessionPidStat { pid, stat })
+            .collect();
+        offenders.sort_by(|a, b| {
+            (b.stat.discontinuities, b.stat.errors, b.stat.scrambled, a.pid)
+                .cmp(&(a.stat.discontinuities, a.stat.errors, a.stat.scrambled, b.pid))
+        });
+        offenders.truncate(PID_OFFENDER_LIMIT);
+        offenders
     }
 
     /// Send raw TS data directly to the client via the writer task.
     ///
     /// The frame is built in-place using the same wire format (BNDP header +
     /// payload) so the client's fast-path TsData decoder works unchanged.
+    /// With negotiated compression the payload gets the flag byte instead.
     ///
     /// Uses `try_send` on the write channel so the select loop is never
     /// blocked by network backpressure.  When the channel is full (sustained
@@ -4193,14 +5393,27 @@ impl Session {
         use bytes::BufMut;
         use recisdb_protocol::{MessageType, MAGIC};
 
-        let payload_len = data.len() as u32;
-        let mut frame = BytesMut::with_capacity(10 + data.len());
-        frame.put_slice(&MAGIC);
-        frame.put_u32_le(payload_len);
-        frame.put_u16_le(MessageType::TsData.into());
-        frame.put_slice(&data);
-
-        let frame = frame.freeze();
+        let frame = if self.ts_compression {
+            match encode_compressed_ts_frame(&data, self.frame_options) {
+                Ok(frame) => frame,
+                Err(e) => {
+                    warn!("[Session {}] Failed to encode compressed TS frame: {}", self.id, e);
+                    return Ok(());
+                }
+            }
+        } else {
+            let crc_len = if self.frame_options.crc32 { CRC_SIZE } else { 0 };
+            let payload_len = (data.len() + crc_len) as u32;
+            let mut frame = BytesMut::with_capacity(10 + payload_len as usize);
+            frame.put_slice(&MAGIC);
+            frame.put_u32_le(payload_len);
+            frame.put_u16_le(MessageType::TsData.into());
+            frame.put_slice(&data);
+            if self.frame_options.crc32 {
+                frame.put_u32_le(frame_crc32(&data));
+            }
+            frame.freeze()
+        };
 
         match self.ts_write_tx.try_send(frame) {
             Ok(()) => Ok(()),
@@ -4251,7 +5464,7 @@ impl Session {
     async fn send_message(&mut self, msg: ServerMessage) -> std::io::Result<()> {
         trace!("[Session {}] Sending: {:?}", self.id, msg);
 
-        let encoded = encode_server_message(&msg).map_err(|e| {
+        let encoded = encode_server_message_with(&msg, self.frame_options).map_err(|e| {
             std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
         })?;
 
@@ -4260,6 +5473,52 @@ impl Session {
         })
     }
 
+    /// Tell the client about a tuner change it did not ask for.
+    ///
+    /// Clients that did not negotiate FEATURE_TUNER_STATUS would reject the
+    /// unknown message type, so they only get the log line.
+    async fn send_tuner_status(&mut self, state: TunerState, reason: String) -> std::io::Result<()> {
+        info!("[Session {}] Tuner status {:?}: {}", self.id, state, reason);
+        if !self.tuner_status {
+            return Ok(());
+        }
+        self.send_message(ServerMessage::TunerStatus { state, reason }).await
+    }
+
+    /// Report watchdog restarts and retunes of `tuner` since the last check.
+    ///
+    /// Switching to another tuner only resets the baseline; changes this
+    /// session made itself are already acknowledged to the client.
+    async fn check_tuner_status(&mut self, tuner: &SharedTuner) -> std::io::Result<()> {
+        let restarts = tuner.reader_restart_count();
+        let channel = tuner.reader_params().map(|(_, space, channel)| (space, channel));
+        let previous = self.tuner_status_seen.replace((tuner.key.clone(), restarts, channel));
+        let Some((seen_key, seen_restarts, seen_channel)) = previous else {
+            return Ok(());
+        };
+        if seen_key != tuner.key {
+            return Ok(());
+        }
+
+        if restarts > seen_restarts {
+            self.send_tuner_status(
+                TunerState::Stalled,
+                format!("driver stopped delivering TS; reader restarted {} time(s)", restarts - seen_restarts),
+            )
+            .await?;
+        }
+        if let (Some(seen), Some((space, ch))) = (seen_channel, channel) {
+            if seen != (space, ch) {
+                self.send_tuner_status(
+                    TunerState::ChannelChanged,
+                    format!("tuner retuned from space {} channel {} to space {} channel {}", seen.0, seen.1, space, ch),
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Send an error message to the client.
     async fn send_error(&mut self, code: ErrorCode, message: &str) -> std::io::Result<()> {
         self.send_message(ServerMessage::Error {
@@ -4357,6 +5616,9 @@ impl Session {
         }
 
         self.stop_warm_tuner().await;
+        if let Some(tuner) = self.lnb_tuner.take() {
+            let _ = tuner.set_lnb_power(self.id, false).await;
+        }
         // Unsubscribe from tuner and check if we should stop reader
         if let Some(tuner) = self.current_tuner.take() {
             // Unsubscribe only if we have an active subscription
@@ -4369,9 +5631,7 @@ impl Session {
             // (ts_receiver is None but tuner may still have no subscribers)
             if tuner.subscriber_count() == 0 {
                 info!("[Session {}] No more subscribers, scheduling keep-alive close for {:?}", self.id, tuner.key);
-                self.tuner_pool
-                    .schedule_idle_close(tuner.key.clone(), Arc::clone(&tuner))
-                    .await;
+                self.schedule_idle_close(Arc::clone(&tuner)).await;
             }
         }
         self.ts_receiver = None;
@@ -4451,6 +5711,9 @@ impl Session {
     }
 
     /// Handle OpenTunerWithGroup message.
+    ///
+    /// Opens a BonDriver group by name (no DLL/display-name resolution) and
+    /// exposes the aggregated virtual space list of its drivers.
     async fn handle_open_tuner_with_group(&mut self, group_name: String) -> std::io::Result<()> {
         if self.state != SessionState::Ready {
             return self
@@ -4459,43 +5722,65 @@ impl Session {
         }
 
         info!("[Session {}] Opening tuner group: {}", self.id, group_name);
-        self.stop_warm_tuner().await;
+        if let Err(error_code) = self.load_group_drivers(&group_name).await {
+            return self
+                .send_message(ServerMessage::OpenTunerAck {
+                    success: false,
+                    error_code: error_code.into(),
+                    bondriver_version: 0,
+                })
+                .await;
+        }
+        self.maybe_start_group_warm_tuner().await;
+
+        self.clear_caches();
+        let spaces = self.ensure_space_list().await;
+        info!("[Session {}] Group '{}' exposes {} virtual spaces", self.id, group_name, spaces.len());
+
+        self.state = SessionState::TunerOpen;
+        self.session_registry.update_tuner(self.id, Some(group_name)).await;
 
-        // TODO: Implement group space info building
-        // For now, send error
         self.send_message(ServerMessage::OpenTunerAck {
-            success: false,
-            error_code: 0xFF00, // Not implemented
-            bondriver_version: 0,
+            success: true,
+            error_code: 0,
+            bondriver_version: 2,
         })
         .await
     }
 
     /// Handle SetChannelSpaceInGroup message.
+    ///
+    /// Switches the session to the named group if needed, then tunes through
+    /// the regular group-mode driver selection in `handle_set_channel_space`.
     async fn handle_set_channel_space_in_group(
         &mut self,
-        _group_name: String,
-        _space_idx: u32,
-        _channel: u32,
+        group_name: String,
+        space_idx: u32,
+        channel: u32,
         priority: i32,
         exclusive: bool,
     ) -> std::io::Result<()> {
-        self.session_registry
-            .update_client_controls(self.id, Some(priority), Some(exclusive))
-            .await;
-        let (effective_priority, effective_exclusive) = self
-            .session_registry
-            .get_effective_controls(self.id)
-            .await
-            .unwrap_or((Some(priority), exclusive));
-        let priority = effective_priority.unwrap_or(priority);
-        let exclusive = effective_exclusive;
-        // TODO: Implement group-based channel selection
-        self.send_message(ServerMessage::SetChannelSpaceAck {
-            success: false,
-            error_code: 0xFF00, // Not implemented
-        })
-        .await
+        if self.current_group_name.as_deref() != Some(group_name.as_str()) {
+            info!("[Session {}] Switching to tuner group: {}", self.id, group_name);
+            if let Err(error_code) = self.load_group_drivers(&group_name).await {
+                return self
+                    .send_message(ServerMessage::SetChannelSpaceAck {
+                        success: false,
+                        error_code: error_code.into(),
+                    })
+                    .await;
+            }
+            self.maybe_start_group_warm_tuner().await;
+            self.clear_caches();
+            self.ensure_space_list().await;
+
+            if self.state == SessionState::Ready {
+                self.state = SessionState::TunerOpen;
+            }
+            self.session_registry.update_tuner(self.id, Some(group_name)).await;
+        }
+
+        self.handle_set_channel_space(space_idx, channel, priority, exclusive).await
     }
 }
 
@@ -0,0 +1,562 @@
+//! Virtual space list construction for client sessions.
+//!
+//! Clients (TVTest etc.) see a compact list of virtual tuning spaces
+//! (地デジ per region, BS, CS) regardless of how the physical BonDriver
+//! spaces are numbered. This module turns the channel table into that list
+//! and records every physical (driver, space, channel) location for each
+//! NID+TSID so the session can route a virtual selection back to hardware.
+//!
+//! The logic is kept free of database and session state so it can be tested
+//! directly.
+
+use std::collections::{BTreeSet, HashMap};
+
+use log::debug;
+use recisdb_protocol::broadcast_region::{classify_nid, TerrestrialRegion};
+use recisdb_protocol::types::BroadcastType;
+
+use crate::database::{BonDriverRecord, ClientChannelRecord};
+
+/// One virtual space entry: (actual_space, display_name, region_key).
+///
+/// `region_key` is the key used for channel matching (e.g. "関東", "BS", "CS", "CATV"),
+/// which may differ from the display name (e.g. "地デジ (関東)").
+pub type VirtualSpace = (u32, String, String);
+
+/// A physical location of a virtual channel on a specific driver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VirtualChannelMapping {
+    /// BonDriver DLL path
+    pub driver_path: String,
+    /// Physical space on this driver
+    pub actual_space: u32,
+    /// Physical channel on this driver
+    pub actual_channel: u32,
+}
+
+/// Ordered virtual spaces plus the NID+TSID routing table for a driver set.
+#[derive(Clone, Debug, Default)]
+pub struct SpaceMap {
+    /// Virtual spaces in client order: terrestrial (north to south) -> BS -> CS.
+    pub spaces: Vec<VirtualSpace>,
+    /// All physical locations of each NID+TSID across the selected drivers.
+    pub mappings: HashMap<(u16, u16), Vec<VirtualChannelMapping>>,
+}
+
+impl SpaceMap {
+    /// Build the space map from channel rows joined with their drivers.
+    ///
+    /// Only enabled channels whose driver path is in `driver_paths` are used.
+    /// A single-tuner session passes one path, a group session passes every
+    /// driver in the group. Each region appears once; its actual space is
+    /// taken from the first channel seen for that region. Unclassified NIDs
+    /// share an "Unknown" space, which is named after the broadcaster-provided
+    /// TS name when all of its transport streams agree on one.
+    pub fn build(
+        rows: Vec<(ClientChannelRecord, Option<BonDriverRecord>)>,
+        driver_paths: &[String],
+    ) -> Self {
+        let mut nid_tsid_seen: BTreeSet<(u16, u16)> = BTreeSet::new();
+        let mut region_seen: BTreeSet<String> = BTreeSet::new();
+        // region_name -> (space, name, sort_order)
+        let mut space_region_names: HashMap<String, (u32, String, u32)> = HashMap::new();
+        let mut mappings: HashMap<(u16, u16), Vec<VirtualChannelMapping>> = HashMap::new();
+        let mut unknown_ts_names: BTreeSet<Option<String>> = BTreeSet::new();
+
+        for (ch, bd_opt) in rows {
+            let Some(bd) = bd_opt else { continue; };
+            if !driver_paths.contains(&bd.dll_path) {
+                continue;
+            }
+            if !ch.is_enabled {
+                continue;
+            }
+
+            let nid_tsid = (ch.nid as u16, ch.tsid as u16);
+
+            // Record every physical location (multiple drivers may carry the same TS)
+            mappings.entry(nid_tsid).or_default().push(VirtualChannelMapping {
+                driver_path: bd.dll_path,
+                actual_space: ch.space,
+                actual_channel: ch.channel,
+            });
+
+            // For display purposes, only register once per NID+TSID
+            if !nid_tsid_seen.insert(nid_tsid) {
+                continue;
+            }
+
+            let (region_name, is_terrestrial, sort_order) = region_key_for_nid(ch.nid as u16);
+            debug!("[SpaceMap] NID=0x{:04X} region={}", ch.nid, region_name);
+
+            if region_name == UNKNOWN_REGION {
+                unknown_ts_names.insert(ch.ts_name.clone());
+            }
+
+            // Only register once per region name (BS/CS and terrestrial alike)
+            if !region_seen.insert(region_name.clone()) {
+                continue;
+            }
+
+            let name = if is_terrestrial {
+                format!("地デジ ({})", region_name)
+            } else {
+                region_name.clone()
+            };
+            space_region_names.insert(region_name, (ch.space, name, sort_order));
+        }
+
+        // Order: 地上波 (terrestrial north-to-south, then by region key) -> BS -> CS -> CATV
+        let mut terrestrial_spaces: Vec<(u32, VirtualSpace)> = Vec::new();
+        let mut bs_space: Option<VirtualSpace> = None;
+        let mut cs_space: Option<VirtualSpace> = None;
+        let mut catv_space: Option<VirtualSpace> = None;
+
+        if let Some((_, name, _)) = space_region_names.get_mut(UNKNOWN_REGION) {
+            if unknown_ts_names.len() == 1 {
+                if let Some(Some(ts_name)) = unknown_ts_names.into_iter().next() {
+                    *name = ts_name;
+                }
+            }
+        }
+
+        for (region, (space, name, sort_order)) in space_region_names {
+            if region == "BS" {
+                bs_space = Some((space, name, region));
+            } else if region == "CS" {
+                cs_space = Some((space, name, region));
+            } else if region == "CATV" {
+                catv_space = Some((space, name, region));
+            } else {
+                terrestrial_spaces.push((sort_order, (space, name, region)));
+            }
+        }
+
+        terrestrial_spaces.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1 .2.cmp(&b.1 .2)));
+
+        let mut spaces: Vec<VirtualSpace> = terrestrial_spaces.into_iter().map(|(_, space)| space).collect();
+        spaces.extend(bs_space);
+        spaces.extend(cs_space);
+        spaces.extend(catv_space);
+
+        Self { spaces, mappings }
+    }
+
+    /// Hide terrestrial spaces other than `region_key`, keeping BS/CS/CATV.
+    ///
+    /// Only the space list is trimmed; `mappings` still covers every
+    /// NID+TSID so hidden channels remain reachable by logical selection.
+    /// Nothing is hidden if `region_key` is not among the terrestrial spaces,
+    /// so a misconfigured region never leaves the client without 地デジ.
+    /// Returns whether the list was trimmed.
+    pub fn restrict_to_region(&mut self, region_key: &str) -> bool {
+        if !self.spaces.iter().any(|(_, _, key)| key == region_key) {
+            return false;
+        }
+        let before = self.spaces.len();
+        self.spaces
+            .retain(|(_, _, key)| key == region_key || matches!(key.as_str(), "BS" | "CS" | "CATV"));
+        self.spaces.len() != before
+    }
+
+    /// Actual spaces in virtual order.
+    pub fn actual_spaces(&self) -> Vec<u32> {
+        self.spaces.iter().map(|(actual_space, _, _)| *actual_space).collect()
+    }
+}
+
+/// Region key shared by all NIDs that are neither BS/CS nor a known terrestrial region.
+const UNKNOWN_REGION: &str = "Unknown";
+
+/// Resolve a client's virtual space index to (actual_space, region_key).
+pub fn resolve_space_idx(spaces: &[VirtualSpace], space_idx: u32) -> Option<(u32, String)> {
+    spaces
+        .get(space_idx as usize)
+        .map(|(actual_space, _display_name, region_key)| (*actual_space, region_key.clone()))
+}
+
+/// Find the service a remote control key selects within one region.
+///
+/// Considers enabled channels on `driver_paths` whose NID maps to
+/// `region_key` and whose `remote_control_key` equals `remote_key`. Several
+/// services usually share a key (a broadcaster's main, sub and one-seg
+/// services), so TV services win over others and the lowest SID, the main
+/// service, is picked among those. Returns (NID, TSID, SID).
+pub fn find_by_remote_key(
+    rows: &[(ClientChannelRecord, Option<BonDriverRecord>)],
+    driver_paths: &[String],
+    region_key: &str,
+    remote_key: u8,
+) -> Option<(u16, u16, u16)> {
+    rows.iter()
+        .filter(|(ch, bd)| {
+            ch.is_enabled
+                && ch.remote_control_key == Some(remote_key as i32)
+                && bd.as_ref().is_some_and(|bd| driver_paths.contains(&bd.dll_path))
+                && region_key_for_nid(ch.nid as u16).0 == region_key
+        })
+        .min_by_key(|(ch, _)| (ch.service_type != Some(0x01), ch.sid))
+        .map(|(ch, _)| (ch.nid as u16, ch.tsid as u16, ch.sid as u16))
+}
+
+/// Other physical channels on `driver_path` that carry the same TS as
+/// (`space`, `channel`), e.g. a terrestrial relay station on a neighbouring
+/// channel, in the order they were recorded.
+pub fn alternate_channels(
+    mappings: &[VirtualChannelMapping],
+    driver_path: &str,
+    space: u32,
+    channel: u32,
+) -> Vec<(u32, u32)> {
+    let mut alternates: Vec<(u32, u32)> = Vec::new();
+    for m in mappings {
+        let location = (m.actual_space, m.actual_channel);
+        if m.driver_path == driver_path
+            && location != (space, channel)
+            && !alternates.contains(&location)
+        {
+            alternates.push(location);
+        }
+    }
+    alternates
+}
+
+/// Region key for a NID: the terrestrial region display name (広域圏), or "BS"/"CS"/"CATV".
+///
+/// Returns whether the NID is a known terrestrial region and the region's
+/// north-to-south sort order alongside the key.
+fn region_key_for_nid(nid: u16) -> (String, bool, u32) {
+    let (btype, terrestrial_region) = classify_nid(nid);
+    match btype {
+        BroadcastType::BS => ("BS".to_string(), false, 0),
+        BroadcastType::CS => ("CS".to_string(), false, 0),
+        BroadcastType::Catv => ("CATV".to_string(), false, 0),
+        BroadcastType::Terrestrial => match terrestrial_region {
+            // All unknown NIDs share one "Unknown" space, ordered after known regions
+            Some(TerrestrialRegion::Unknown(_)) | None => (UNKNOWN_REGION.to_string(), false, u32::MAX),
+            Some(r) => (r.display_name().to_string(), true, r.sort_order()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KANTO_NID: u16 = 0x7FE8;
+    const KINKI_NID: u16 = 0x7FD1;
+    const MIYAGI_NID: u16 = 0x7EE0;
+    const OKINAWA_NID: u16 = 0x7C10;
+    const BS_NID: u16 = 4;
+    const CS_NID: u16 = 6;
+    const CATV_NID: u16 = 0xFFFE;
+
+    fn driver(id: i64, path: &str) -> BonDriverRecord {
+        BonDriverRecord {
+            id,
+            dll_path: path.to_string(),
+            driver_name: None,
+            version: None,
+            group_name: None,
+            auto_scan_enabled: false,
+            scan_interval_hours: 0,
+            scan_priority: 0,
+            last_scan: None,
+            next_scan_at: None,
+            passive_scan_enabled: false,
+            max_instances: 1,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn channel(nid: u16, tsid: u16, space: u32, channel: u32) -> ClientChannelRecord {
+        ClientChannelRecord {
+            id: 0,
+            bon_driver_id: 0,
+            nid: nid as i32,
+            sid: 0,
+            tsid: tsid as i32,
+            service_name: None,
+            name_is_synthetic: false,
+            ts_name: None,
+            service_type: None,
+            remote_control_key: None,
+            space,
+            channel,
+            is_enabled: true,
+            priority: 0,
+            display_order: None,
+        }
+    }
+
+    fn row(
+        bd: &BonDriverRecord,
+        nid: u16,
+        tsid: u16,
+        space: u32,
+        ch: u32,
+    ) -> (ClientChannelRecord, Option<BonDriverRecord>) {
+        let mut c = channel(nid, tsid, space, ch);
+        c.bon_driver_id = bd.id;
+        (c, Some(bd.clone()))
+    }
+
+    fn paths(p: &[&str]) -> Vec<String> {
+        p.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_single_driver_orders_terrestrial_bs_cs() {
+        let d = driver(1, "BonDriver_A.dll");
+        let rows = vec![
+            row(&d, CS_NID, 0x6020, 2, 0),
+            row(&d, BS_NID, 0x4010, 1, 0),
+            row(&d, KANTO_NID, 0x7FE8, 0, 13),
+        ];
+
+        let map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll"]));
+        let keys: Vec<&str> = map.spaces.iter().map(|s| s.2.as_str()).collect();
+        assert_eq!(keys, vec!["関東", "BS", "CS"]);
+        assert_eq!(map.spaces[0].1, "地デジ (関東)");
+        assert_eq!(map.actual_spaces(), vec![0, 1, 2]);
+
+        assert_eq!(resolve_space_idx(&map.spaces, 1), Some((1, "BS".to_string())));
+        assert_eq!(resolve_space_idx(&map.spaces, 2), Some((2, "CS".to_string())));
+        assert_eq!(resolve_space_idx(&map.spaces, 3), None);
+    }
+
+    #[test]
+    fn test_single_driver_ignores_other_drivers_and_disabled() {
+        let a = driver(1, "BonDriver_A.dll");
+        let b = driver(2, "BonDriver_B.dll");
+        let mut disabled = row(&a, CS_NID, 0x6020, 2, 0);
+        disabled.0.is_enabled = false;
+        let rows = vec![
+            row(&a, BS_NID, 0x4010, 0, 0),
+            row(&b, KANTO_NID, 0x7FE8, 0, 13),
+            disabled,
+            (channel(KANTO_NID, 0x7FE9, 0, 14), None),
+        ];
+
+        let map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll"]));
+        assert_eq!(map.spaces.len(), 1);
+        assert_eq!(map.spaces[0].2, "BS");
+        assert_eq!(map.mappings.len(), 1);
+        assert!(map.mappings.contains_key(&(BS_NID, 0x4010)));
+    }
+
+    #[test]
+    fn test_mixed_regions_sorted_north_to_south() {
+        let d = driver(1, "BonDriver_A.dll");
+        let rows = vec![
+            row(&d, 0x1234, 0x1234, 3, 0),
+            row(&d, OKINAWA_NID, 0x7C10, 0, 17),
+            row(&d, KINKI_NID, 0x7FD1, 0, 16),
+            row(&d, KANTO_NID, 0x7FE8, 0, 13),
+            row(&d, BS_NID, 0x4010, 1, 0),
+            row(&d, MIYAGI_NID, 0x7EE0, 0, 21),
+        ];
+
+        let map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll"]));
+        let keys: Vec<&str> = map.spaces.iter().map(|s| s.2.as_str()).collect();
+        assert_eq!(keys, vec!["東北", "関東", "近畿", "沖縄", "Unknown", "BS"]);
+    }
+
+    #[test]
+    fn test_unknown_terrestrial_nid_is_not_labelled_as_chideji() {
+        let d = driver(1, "BonDriver_A.dll");
+        let rows = vec![row(&d, 0x1234, 0x1234, 5, 0)];
+
+        let map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll"]));
+        assert_eq!(map.spaces, vec![(5, "Unknown".to_string(), "Unknown".to_string())]);
+    }
+
+    #[test]
+    fn test_unknown_space_uses_shared_ts_name() {
+        let d = driver(1, "BonDriver_A.dll");
+        let mut rows = vec![row(&d, 0x1234, 0x1234, 5, 0), row(&d, 0x1234, 0x1235, 5, 1)];
+        for (ch, _) in rows.iter_mut() {
+            ch.ts_name = Some("ケーブルテレビ".to_string());
+        }
+
+        let map = SpaceMap::build(rows.clone(), &paths(&["BonDriver_A.dll"]));
+        assert_eq!(map.spaces, vec![(5, "ケーブルテレビ".to_string(), "Unknown".to_string())]);
+
+        // Differing TS names keep the generic label
+        rows[1].0.ts_name = Some("別局".to_string());
+        let map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll"]));
+        assert_eq!(map.spaces, vec![(5, "Unknown".to_string(), "Unknown".to_string())]);
+    }
+
+    #[test]
+    fn test_group_merges_bs_cs_across_drivers() {
+        // Driver A numbers BS as space 0, driver B as space 1
+        let a = driver(1, "BonDriver_A.dll");
+        let b = driver(2, "BonDriver_B.dll");
+        let rows = vec![
+            row(&a, BS_NID, 0x4010, 0, 0),
+            row(&a, CS_NID, 0x6020, 1, 0),
+            row(&b, KANTO_NID, 0x7FE8, 0, 13),
+            row(&b, BS_NID, 0x4011, 1, 1),
+            row(&b, CS_NID, 0x6021, 2, 1),
+        ];
+
+        let map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll", "BonDriver_B.dll"]));
+        let keys: Vec<&str> = map.spaces.iter().map(|s| s.2.as_str()).collect();
+        assert_eq!(keys, vec!["関東", "BS", "CS"]);
+        // BS/CS take the actual space of the first driver seen
+        assert_eq!(map.actual_spaces(), vec![0, 0, 1]);
+
+        // Transponders only on driver B still route to driver B
+        let bs2 = &map.mappings[&(BS_NID, 0x4011)];
+        assert_eq!(
+            bs2,
+            &vec![VirtualChannelMapping {
+                driver_path: "BonDriver_B.dll".to_string(),
+                actual_space: 1,
+                actual_channel: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_group_duplicate_nid_tsid_keeps_all_locations() {
+        let a = driver(1, "BonDriver_A.dll");
+        let b = driver(2, "BonDriver_B.dll");
+        let c = driver(3, "BonDriver_C.dll");
+        let rows = vec![
+            row(&a, KANTO_NID, 0x7FE8, 0, 13),
+            row(&b, KANTO_NID, 0x7FE8, 2, 27),
+            // Not in the group
+            row(&c, KANTO_NID, 0x7FE8, 0, 13),
+        ];
+
+        let map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll", "BonDriver_B.dll"]));
+        assert_eq!(map.spaces.len(), 1);
+        assert_eq!(map.spaces[0].0, 0);
+
+        let locations = &map.mappings[&(KANTO_NID, 0x7FE8)];
+        let drivers: Vec<&str> = locations.iter().map(|m| m.driver_path.as_str()).collect();
+        assert_eq!(drivers, vec!["BonDriver_A.dll", "BonDriver_B.dll"]);
+        assert_eq!((locations[1].actual_space, locations[1].actual_channel), (2, 27));
+    }
+
+    #[test]
+    fn test_group_vs_single_mode_same_rows() {
+        let a = driver(1, "BonDriver_A.dll");
+        let b = driver(2, "BonDriver_B.dll");
+        let rows = vec![
+            row(&a, KANTO_NID, 0x7FE8, 0, 13),
+            row(&b, BS_NID, 0x4010, 0, 0),
+        ];
+
+        let single = SpaceMap::build(rows.clone(), &paths(&["BonDriver_A.dll"]));
+        let group = SpaceMap::build(rows, &paths(&["BonDriver_A.dll", "BonDriver_B.dll"]));
+
+        assert_eq!(single.spaces.len(), 1);
+        assert_eq!(resolve_space_idx(&single.spaces, 1), None);
+        assert_eq!(group.spaces.len(), 2);
+        assert_eq!(resolve_space_idx(&group.spaces, 1), Some((0, "BS".to_string())));
+    }
+
+    #[test]
+    fn test_restrict_to_region_keeps_satellite_and_mappings() {
+        let d = driver(1, "BonDriver_A.dll");
+        let rows = vec![
+            row(&d, MIYAGI_NID, 0x7EE0, 0, 21),
+            row(&d, KANTO_NID, 0x7FE8, 0, 13),
+            row(&d, KINKI_NID, 0x7FD1, 0, 16),
+            row(&d, CATV_NID, 0x0001, 3, 0),
+            row(&d, BS_NID, 0x4010, 1, 0),
+            row(&d, CS_NID, 0x6020, 2, 0),
+        ];
+
+        let mut map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll"]));
+        assert!(map.restrict_to_region("関東"));
+
+        let keys: Vec<&str> = map.spaces.iter().map(|s| s.2.as_str()).collect();
+        assert_eq!(keys, vec!["関東", "BS", "CS", "CATV"]);
+        assert_eq!(resolve_space_idx(&map.spaces, 1), Some((1, "BS".to_string())));
+        // Hidden regions stay routable
+        assert!(map.mappings.contains_key(&(MIYAGI_NID, 0x7EE0)));
+        assert!(map.mappings.contains_key(&(KINKI_NID, 0x7FD1)));
+    }
+
+    #[test]
+    fn test_restrict_to_missing_region_is_noop() {
+        let d = driver(1, "BonDriver_A.dll");
+        let rows = vec![
+            row(&d, KANTO_NID, 0x7FE8, 0, 13),
+            row(&d, BS_NID, 0x4010, 1, 0),
+        ];
+
+        let mut map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll"]));
+        assert!(!map.restrict_to_region("沖縄"));
+        assert_eq!(map.spaces.len(), 2);
+    }
+
+    #[test]
+    fn test_find_by_remote_key_prefers_main_service() {
+        let a = driver(1, "BonDriver_A.dll");
+        let b = driver(2, "BonDriver_B.dll");
+        let service = |bd: &BonDriverRecord, nid: u16, sid: i32, service_type: i32, key: i32| {
+            let mut r = row(bd, nid, nid, 0, 13);
+            r.0.sid = sid;
+            r.0.service_type = Some(service_type);
+            r.0.remote_control_key = Some(key);
+            r
+        };
+        let mut disabled = service(&a, KANTO_NID, 1020, 0x01, 1);
+        disabled.0.is_enabled = false;
+        let rows = vec![
+            // One-seg service sorts after the TV services despite its SID
+            service(&a, KANTO_NID, 1000, 0xC0, 1),
+            service(&a, KANTO_NID, 1026, 0x01, 1),
+            service(&a, KANTO_NID, 1024, 0x01, 1),
+            disabled,
+            service(&a, KINKI_NID, 1, 0x01, 1),
+            service(&b, KANTO_NID, 1, 0x01, 1),
+            service(&a, KANTO_NID, 1032, 0x01, 3),
+        ];
+        let drivers = paths(&["BonDriver_A.dll"]);
+
+        assert_eq!(
+            find_by_remote_key(&rows, &drivers, "関東", 1),
+            Some((KANTO_NID, KANTO_NID, 1024))
+        );
+        assert_eq!(
+            find_by_remote_key(&rows, &drivers, "近畿", 1),
+            Some((KINKI_NID, KINKI_NID, 1))
+        );
+        assert_eq!(find_by_remote_key(&rows, &drivers, "関東", 2), None);
+        assert_eq!(find_by_remote_key(&rows, &drivers, "BS", 1), None);
+    }
+
+    #[test]
+    fn test_alternate_channels_same_driver_only() {
+        let a = driver(1, "BonDriver_A.dll");
+        let b = driver(2, "BonDriver_B.dll");
+        let rows = vec![
+            row(&a, KANTO_NID, 0x7FE8, 0, 13),
+            row(&a, KANTO_NID, 0x7FE8, 0, 13),
+            row(&a, KANTO_NID, 0x7FE8, 0, 27),
+            row(&b, KANTO_NID, 0x7FE8, 0, 20),
+            row(&a, KANTO_NID, 0x7FE9, 0, 14),
+        ];
+
+        let map = SpaceMap::build(rows, &paths(&["BonDriver_A.dll", "BonDriver_B.dll"]));
+        let mappings = &map.mappings[&(KANTO_NID, 0x7FE8)];
+        assert_eq!(alternate_channels(mappings, "BonDriver_A.dll", 0, 13), vec![(0, 27)]);
+        assert_eq!(alternate_channels(mappings, "BonDriver_A.dll", 0, 27), vec![(0, 13)]);
+        assert!(alternate_channels(mappings, "BonDriver_B.dll", 0, 20).is_empty());
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let map = SpaceMap::build(Vec::new(), &paths(&["BonDriver_A.dll"]));
+        assert!(map.spaces.is_empty());
+        assert!(map.mappings.is_empty());
+        assert_eq!(resolve_space_idx(&map.spaces, 0), None);
+    }
+}
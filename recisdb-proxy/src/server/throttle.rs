@@ -0,0 +1,152 @@
+//! Token buckets used to pace TS writes to a bitrate cap and to limit how
+//! fast a client may open connections.
+
+use std::time::{Duration, Instant};
+
+/// Burst allowance, as time at the configured rate.
+///
+/// Kept short so a backlog drained from the broadcast channel after a lag is
+/// spread out instead of being flushed onto the link at once.
+const BURST: Duration = Duration::from_millis(250);
+
+/// Paces byte counts to a fixed bitrate.
+///
+/// Chunks larger than the bucket are allowed; the bucket goes into debt and
+/// the caller waits until it is paid back.
+#[derive(Debug, Clone)]
+pub(crate) struct TokenBucket {
+    /// Refill rate in bytes per second.
+    rate: f64,
+    /// Maximum stored tokens (bytes).
+    capacity: f64,
+    /// Available tokens; negative while in debt.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket for `mbps` megabits per second.
+    pub(crate) fn new(mbps: f64, now: Instant) -> Self {
+        let rate = mbps * 1_000_000.0 / 8.0;
+        let capacity = rate * BURST.as_secs_f64();
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    /// Configured rate in megabits per second.
+    pub(crate) fn mbps(&self) -> f64 {
+        self.rate * 8.0 / 1_000_000.0
+    }
+
+    /// Take `bytes` from the bucket and return how long to wait before
+    /// sending them.
+    pub(crate) fn consume(&mut self, bytes: usize, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.tokens -= bytes as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// Admits a fixed number of events per minute, in bursts of up to one
+/// minute's worth.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    /// Refill rate in events per second.
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a full limiter for `per_minute` events per minute.
+    pub(crate) fn per_minute(per_minute: u32, now: Instant) -> Self {
+        let capacity = per_minute as f64;
+        Self {
+            rate: capacity / 60.0,
+            capacity,
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+    }
+
+    /// Take one event; false if the limit has been reached.
+    pub(crate) fn try_acquire(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether the limiter has fully refilled, so forgetting it changes nothing.
+    pub(crate) fn is_idle(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_then_pacing() {
+        let start = Instant::now();
+        // 8 Mbps = 1,000,000 bytes/s, 250,000 byte burst
+        let mut bucket = TokenBucket::new(8.0, start);
+        assert_eq!(bucket.consume(250_000, start), Duration::ZERO);
+
+        // Empty bucket: the next 100 KB waits 100 ms
+        let wait = bucket.consume(100_000, start);
+        assert_eq!(wait.as_millis(), 100);
+
+        // After paying back the debt and idling, refill stops at the burst size
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.consume(250_000, later), Duration::ZERO);
+        assert!(bucket.consume(1, later) > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_rate_limiter_burst_and_refill() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::per_minute(3, start);
+        assert!(limiter.try_acquire(start));
+        assert!(limiter.try_acquire(start));
+        assert!(limiter.try_acquire(start));
+        assert!(!limiter.try_acquire(start));
+        assert!(!limiter.is_idle(start));
+
+        // One event per 20 seconds comes back
+        assert!(limiter.try_acquire(start + Duration::from_secs(20)));
+        assert!(!limiter.try_acquire(start + Duration::from_secs(21)));
+        assert!(limiter.is_idle(start + Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn test_oversized_chunk_goes_into_debt() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(8.0, start);
+        let wait = bucket.consume(1_250_000, start);
+        assert_eq!(wait, Duration::from_secs(1));
+        assert!((bucket.mbps() - 8.0).abs() < 1e-9);
+    }
+}
@@ -1,16 +1,23 @@
 //! TS Stream Analyzer - Main analysis engine.
 //!
 //! This module provides the main `TsAnalyzer` struct that processes
-//! TS packets and extracts channel information (PAT, PMT, NIT, SDT).
+//! TS packets and extracts channel information (PAT, PMT, NIT, SDT) and,
+//! optionally, program guide events (EIT).
 
 use std::collections::HashMap;
 
+use chrono::{DateTime, FixedOffset, Utc};
+
+use super::cat::CatTable;
+use super::eit::{is_eit_table_id, EitEvent, EitTable};
 use super::nit::NitTable;
 use super::packet::{TsPacket, TS_PACKET_SIZE};
 use super::pat::PatTable;
+use super::pcr::PcrTracker;
 use super::pmt::PmtTable;
 use super::psi::{PsiSection, SectionCollector};
 use super::sdt::SdtTable;
+use super::tdt::TimeTable;
 use super::{pid, table_id};
 
 /// Configuration for the TS analyzer.
@@ -22,6 +29,14 @@ pub struct AnalyzerConfig {
     pub parse_sdt: bool,
     /// Whether to parse PMT for all programs.
     pub parse_all_pmts: bool,
+    /// Whether to collect EIT events.
+    ///
+    /// EIT is sent continuously, so when enabled the analyzer keeps processing
+    /// every fed packet after the other tables are complete.
+    pub parse_eit: bool,
+    /// Whether to keep measuring the PCR-based bitrate after the tables
+    /// are complete (keeps processing every fed packet, like `parse_eit`).
+    pub measure_bitrate: bool,
     /// Maximum number of packets to process (0 = unlimited).
     pub max_packets: usize,
 }
@@ -32,11 +47,19 @@ impl Default for AnalyzerConfig {
             parse_nit: true,
             parse_sdt: true,
             parse_all_pmts: true,
+            parse_eit: false,
+            measure_bitrate: false,
             max_packets: 0,
         }
     }
 }
 
+/// ISDB service type for 1seg (partial reception) services.
+const ONE_SEG_SERVICE_TYPE: u8 = 0xC0;
+
+/// EIT event key: (original_network_id, transport_stream_id, service_id, event_id).
+pub type EitEventKey = (u16, u16, u16, u16);
+
 /// Result of TS analysis.
 #[derive(Debug, Clone, Default)]
 pub struct AnalyzerResult {
@@ -48,12 +71,22 @@ pub struct AnalyzerResult {
     pub network_name: Option<String>,
     /// PAT table.
     pub pat: Option<PatTable>,
+    /// CAT table.
+    pub cat: Option<CatTable>,
+    /// EMM PIDs as `(ca_system_id, ca_pid)` pairs (from CAT).
+    pub emm_pids: Vec<(u16, u16)>,
     /// NIT table.
     pub nit: Option<NitTable>,
     /// SDT table.
     pub sdt: Option<SdtTable>,
     /// PMT tables by program number.
     pub pmts: HashMap<u16, PmtTable>,
+    /// EIT events (present/following and schedule), latest version wins.
+    pub eit_events: HashMap<EitEventKey, EitEvent>,
+    /// Latest broadcast time (from TDT/TOT), converted to UTC.
+    pub stream_time: Option<DateTime<Utc>>,
+    /// Broadcast local time offset from UTC (JST, adjusted by TOT).
+    pub local_time_offset: Option<FixedOffset>,
     /// Total packets processed.
     pub packets_processed: usize,
     /// Analysis complete flag.
@@ -93,9 +126,57 @@ impl AnalyzerResult {
             service_type,
             video_pid: pmt.and_then(|p| p.get_video_pids().first().copied()),
             audio_pids: pmt.map(|p| p.get_audio_pids()).unwrap_or_default(),
+            is_one_seg: self.is_one_seg_service(service_id),
         })
     }
 
+    /// Check if a service is 1seg, from the NIT partial reception descriptor
+    /// or, failing that, the SDT service type.
+    pub fn is_one_seg_service(&self, service_id: u16) -> bool {
+        if self
+            .nit
+            .as_ref()
+            .is_some_and(|nit| nit.is_one_seg_service(service_id))
+        {
+            return true;
+        }
+        self.sdt
+            .as_ref()
+            .and_then(|sdt| sdt.find_service(service_id))
+            .and_then(|s| s.get_service_type())
+            == Some(ONE_SEG_SERVICE_TYPE)
+    }
+
+    /// Get the CDT logo ID of each service that announces one in the SDT.
+    pub fn service_logo_ids(&self) -> HashMap<u16, u16> {
+        self.sdt
+            .as_ref()
+            .map(|sdt| {
+                sdt.services
+                    .iter()
+                    .filter_map(|s| Some((s.service_id, s.get_logo_id()?)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the remote control key ID for a service.
+    ///
+    /// Tries the NIT entry of this TS (TS information descriptor, then remote
+    /// control key descriptor) and falls back to the SDT service's remote
+    /// control key descriptor, since broadcasters differ in where they send it.
+    pub fn get_remote_control_key(&self, service_id: u16) -> Option<u8> {
+        self.transport_stream_id
+            .and_then(|tsid| self.nit.as_ref()?.find_transport_stream(tsid))
+            .and_then(|ts| ts.remote_control_key_id())
+            .or_else(|| {
+                self.sdt
+                    .as_ref()?
+                    .find_service(service_id)?
+                    .get_remote_control_key()
+            })
+    }
+
     /// Get all channel info.
     pub fn get_all_channels(&self) -> Vec<ChannelInfo> {
         let Some(pat) = &self.pat else {
@@ -108,6 +189,40 @@ impl AnalyzerResult {
             .collect()
     }
 
+    /// Get EIT events for a service, ordered by start time.
+    ///
+    /// Events from other transport streams (EIT other) with the same service ID
+    /// are excluded when the current TSID is known.
+    pub fn events_for_service(&self, service_id: u16) -> Vec<&EitEvent> {
+        let mut events: Vec<&EitEvent> = self
+            .eit_events
+            .iter()
+            .filter(|((_, tsid, sid, _), _)| {
+                *sid == service_id
+                    && self.transport_stream_id.is_none_or(|t| t == *tsid)
+            })
+            .map(|(_, e)| e)
+            .collect();
+        events.sort_by_key(|e| (e.start_time, e.event_id));
+        events
+    }
+
+    /// Get ECM PIDs as `(ca_system_id, ca_pid)` pairs across all parsed PMTs.
+    pub fn ecm_pids(&self) -> Vec<(u16, u16)> {
+        let mut program_numbers: Vec<_> = self.pmts.keys().copied().collect();
+        program_numbers.sort_unstable();
+
+        let mut pids = Vec::new();
+        for program_number in program_numbers {
+            for entry in self.pmts[&program_number].get_ecm_pids() {
+                if !pids.contains(&entry) {
+                    pids.push(entry);
+                }
+            }
+        }
+        pids
+    }
+
     /// Check if analysis has gathered minimum required info.
     pub fn has_minimum_info(&self) -> bool {
         self.pat.is_some()
@@ -157,6 +272,8 @@ pub struct ChannelInfo {
     pub video_pid: Option<u16>,
     /// Audio PIDs.
     pub audio_pids: Vec<u16>,
+    /// Whether this is a 1seg (partial reception) service.
+    pub is_one_seg: bool,
 }
 
 /// TS Stream Analyzer.
@@ -170,6 +287,8 @@ pub struct TsAnalyzer {
     collectors: HashMap<u16, SectionCollector>,
     /// PMT PIDs to watch (from PAT).
     pmt_pids: HashMap<u16, u16>, // PID -> program_number
+    /// PCR-based bitrate measurement.
+    pcr: PcrTracker,
 }
 
 impl TsAnalyzer {
@@ -180,6 +299,7 @@ impl TsAnalyzer {
             result: AnalyzerResult::default(),
             collectors: HashMap::new(),
             pmt_pids: HashMap::new(),
+            pcr: PcrTracker::new(),
         }
     }
 
@@ -211,6 +331,8 @@ impl TsAnalyzer {
             }
 
             if let Ok(packet) = TsPacket::parse(&data[offset..]) {
+                self.pcr
+                    .push_packet(packet.header.pid, packet.adaptation_field.as_ref());
                 self.process_packet(&packet);
                 self.result.packets_processed += 1;
 
@@ -222,10 +344,13 @@ impl TsAnalyzer {
                     return true;
                 }
 
-                // Check if complete
+                // Check if complete (EIT collection and bitrate measurement
+                // keep consuming the stream)
                 if self.result.is_complete(&self.config) {
                     self.result.complete = true;
-                    return true;
+                    if !self.config.parse_eit && !self.config.measure_bitrate {
+                        return true;
+                    }
                 }
             }
 
@@ -251,14 +376,32 @@ impl TsAnalyzer {
 
         // Check if we're interested in this PID
         let should_process = pid_val == pid::PAT
+            || pid_val == pid::CAT
+            || pid_val == pid::TDT
             || (self.config.parse_nit && pid_val == pid::NIT)
             || (self.config.parse_sdt && pid_val == pid::SDT)
+            || (self.config.parse_eit && pid_val == pid::EIT)
             || self.pmt_pids.contains_key(&pid_val);
 
         if !should_process || !packet.header.has_payload() {
             return;
         }
 
+        // EIT sections are often packed several per packet or split across
+        // the pointer field, so collect every completed section
+        if pid_val == pid::EIT {
+            let collector = self.collectors.entry(pid_val).or_default();
+            let sections = collector.push_packet(
+                packet.payload,
+                packet.header.continuity_counter,
+                packet.header.payload_unit_start,
+            );
+            for section_data in sections {
+                self.process_section(pid_val, &section_data);
+            }
+            return;
+        }
+
         // Get or create section collector
         let collector = self.collectors.entry(pid_val).or_default();
 
@@ -282,6 +425,12 @@ impl TsAnalyzer {
 
     /// Process a complete PSI section.
     fn process_section(&mut self, pid_val: u16, data: &[u8]) {
+        // TDT has no section syntax or CRC, so parse it from the raw bytes
+        if pid_val == pid::TDT {
+            self.process_time_table(data);
+            return;
+        }
+
         let section = match PsiSection::parse(data) {
             Ok(s) => s,
             Err(_) => return,
@@ -289,8 +438,19 @@ impl TsAnalyzer {
 
         match pid_val {
             pid::PAT => self.process_pat(&section),
+            pid::CAT => {
+                if section.verify_crc(data) {
+                    self.process_cat(&section);
+                }
+            }
             pid::NIT => self.process_nit(&section),
             pid::SDT => self.process_sdt(&section),
+            pid::EIT => {
+                // EIT sections are numerous and long; drop corrupted ones
+                if section.verify_crc(data) {
+                    self.process_eit(&section);
+                }
+            }
             _ => {
                 // Check if this is a PMT PID
                 if let Some(&program_number) = self.pmt_pids.get(&pid_val) {
@@ -327,6 +487,24 @@ impl TsAnalyzer {
         }
     }
 
+    /// Process CAT section.
+    fn process_cat(&mut self, section: &PsiSection) {
+        if section.header.table_id != table_id::CAT {
+            return;
+        }
+
+        if let Some(ref existing) = self.result.cat {
+            if existing.version_number == section.header.version_number {
+                return;
+            }
+        }
+
+        if let Ok(cat) = CatTable::parse(section) {
+            self.result.emm_pids = cat.emm_pids();
+            self.result.cat = Some(cat);
+        }
+    }
+
     /// Process NIT section.
     fn process_nit(&mut self, section: &PsiSection) {
         if section.header.table_id != table_id::NIT_ACTUAL {
@@ -365,6 +543,41 @@ impl TsAnalyzer {
         }
     }
 
+    /// Process EIT section.
+    fn process_eit(&mut self, section: &PsiSection) {
+        if !is_eit_table_id(section.header.table_id) || !section.header.current_next_indicator {
+            return;
+        }
+
+        let Ok(eit) = EitTable::parse(section) else {
+            return;
+        };
+
+        for event in eit.events {
+            let key = (
+                eit.original_network_id,
+                eit.transport_stream_id,
+                eit.service_id,
+                event.event_id,
+            );
+            self.result.eit_events.insert(key, event);
+        }
+    }
+
+    /// Process TDT/TOT section.
+    fn process_time_table(&mut self, data: &[u8]) {
+        let Ok(table) = TimeTable::parse(data) else {
+            return;
+        };
+
+        self.result.stream_time = Some(table.utc_time());
+        // A TDT alone does not tell us whether a TOT offset applies, so
+        // keep the last TOT-derived offset when one has been seen
+        if table.table_id == table_id::TOT || self.result.local_time_offset.is_none() {
+            self.result.local_time_offset = table.local_offset();
+        }
+    }
+
     /// Process PMT section.
     fn process_pmt(&mut self, section: &PsiSection, expected_program: u16) {
         if section.header.table_id != table_id::PMT {
@@ -385,6 +598,21 @@ impl TsAnalyzer {
 
         if let Ok(pmt) = PmtTable::parse(section) {
             self.result.pmts.insert(expected_program, pmt);
+            self.update_pcr_pid();
+        }
+    }
+
+    /// Track the PCR PID of the lowest-numbered program with a PCR.
+    fn update_pcr_pid(&mut self) {
+        let pcr_pid = self
+            .result
+            .pmts
+            .iter()
+            .filter(|(_, pmt)| pmt.pcr_pid != pid::NULL)
+            .min_by_key(|(&program_number, _)| program_number)
+            .map(|(_, pmt)| pmt.pcr_pid);
+        if let Some(pcr_pid) = pcr_pid {
+            self.pcr.set_pcr_pid(pcr_pid);
         }
     }
 
@@ -393,6 +621,31 @@ impl TsAnalyzer {
         &self.result
     }
 
+    /// Latest broadcast wall-clock time seen in the stream (TDT/TOT).
+    ///
+    /// Returns None until a valid TDT or TOT has been received; some CATV
+    /// passthrough streams never carry one.
+    pub fn current_stream_time(&self) -> Option<DateTime<Utc>> {
+        self.result.stream_time
+    }
+
+    /// Transport bitrate measured from consecutive PCR samples, in bits per
+    /// second.
+    ///
+    /// Returns None until a PMT has declared the PCR PID and two valid PCR
+    /// samples have been seen.
+    pub fn measured_bitrate_bps(&self) -> Option<u64> {
+        self.pcr.bitrate_bps()
+    }
+
+    /// Take the EIT events collected so far, leaving the map empty.
+    ///
+    /// Lets long-running collectors persist events incrementally instead of
+    /// re-reading every event seen since the analyzer was created.
+    pub fn take_eit_events(&mut self) -> HashMap<EitEventKey, EitEvent> {
+        std::mem::take(&mut self.result.eit_events)
+    }
+
     /// Take the analysis result, consuming the analyzer.
     pub fn into_result(self) -> AnalyzerResult {
         self.result
@@ -403,6 +656,7 @@ impl TsAnalyzer {
         self.result = AnalyzerResult::default();
         self.collectors.clear();
         self.pmt_pids.clear();
+        self.pcr = PcrTracker::new();
     }
 
     /// Check if analysis is complete.
@@ -421,9 +675,192 @@ mod tests {
         assert!(config.parse_nit);
         assert!(config.parse_sdt);
         assert!(config.parse_all_pmts);
+        assert!(!config.parse_eit);
         assert_eq!(config.max_packets, 0);
     }
 
+    /// Split a PSI section into 188-byte TS packets on the given PID.
+    fn packetize(pid_val: u16, section: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut payload = vec![0u8];
+        payload.extend_from_slice(section);
+        for (i, chunk) in payload.chunks(184).enumerate() {
+            let pusi = if i == 0 { 0x40 } else { 0x00 };
+            out.push(0x47);
+            out.push(pusi | (pid_val >> 8) as u8);
+            out.push(pid_val as u8);
+            out.push(0x10 | (i as u8 & 0x0F));
+            out.extend_from_slice(chunk);
+            out.resize(out.len() + (184 - chunk.len()), 0xFF);
+        }
+        out
+    }
+
+    #[test]
+    fn test_analyzer_collects_eit_across_packets() {
+        use crate::ts_analyzer::psi::crc32_mpeg2;
+
+        // EIT p/f actual for service 0x0101 with one event carrying a long descriptor
+        let mut body = vec![0x7F, 0xE1, 0x7F, 0xE0, 0x00, 0x4E];
+        body.extend_from_slice(&[0x12, 0x34, 0xC0, 0x79, 0x12, 0x45, 0x00, 0x00, 0x30, 0x00]);
+        let descriptor_len = 2 + 3 + 1 + 200 + 1;
+        body.extend_from_slice(&[0x80, descriptor_len as u8]);
+        body.extend_from_slice(&[0x4D, (descriptor_len - 2) as u8, b'j', b'p', b'n', 200]);
+        body.extend(std::iter::repeat_n(0x21, 200));
+        body.push(0);
+
+        let section_length = 5 + body.len() + 4;
+        let mut section = vec![
+            0x4E,
+            0xF0 | (section_length >> 8) as u8,
+            section_length as u8,
+            0x01, 0x01, 0xC1, 0x00, 0x00,
+        ];
+        section.extend_from_slice(&body);
+        let crc = crc32_mpeg2(&section);
+        section.extend_from_slice(&crc.to_be_bytes());
+        assert!(section.len() > 184);
+
+        let mut analyzer = TsAnalyzer::new(AnalyzerConfig {
+            parse_eit: true,
+            ..AnalyzerConfig::default()
+        });
+        analyzer.feed(&packetize(pid::EIT, &section));
+
+        let events = analyzer.result().events_for_service(0x0101);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_id, 0x1234);
+        assert_eq!(events[0].duration_secs, Some(1800));
+        assert_eq!(events[0].short_event.as_ref().unwrap().language_code, "jpn");
+
+        // Corrupt CRC is ignored
+        let mut bad = section.clone();
+        let last = bad.len() - 1;
+        bad[last] ^= 0xFF;
+        let mut analyzer = TsAnalyzer::new(AnalyzerConfig {
+            parse_eit: true,
+            ..AnalyzerConfig::default()
+        });
+        analyzer.feed(&packetize(pid::EIT, &bad));
+        assert!(analyzer.result().eit_events.is_empty());
+    }
+
+    #[test]
+    fn test_analyzer_ignores_eit_by_default() {
+        let mut analyzer = TsAnalyzer::new_default();
+        let section = [0x4E, 0xF0, 0x0F, 0x01, 0x01, 0xC1, 0x00, 0x00, 0x7F, 0xE1, 0x7F, 0xE0, 0x00, 0x4E, 0, 0, 0, 0];
+        analyzer.feed(&packetize(pid::EIT, &section));
+        assert!(analyzer.result().eit_events.is_empty());
+    }
+
+    #[test]
+    fn test_analyzer_current_stream_time() {
+        let mut analyzer = TsAnalyzer::new_default();
+        assert_eq!(analyzer.current_stream_time(), None);
+
+        // Stuffed TDT is ignored
+        analyzer.feed(&packetize(pid::TDT, &[0x70, 0x70, 0x05, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]));
+        assert_eq!(analyzer.current_stream_time(), None);
+
+        // 1993-10-13 21:45:00 JST
+        analyzer.feed(&packetize(pid::TDT, &[0x70, 0x70, 0x05, 0xC0, 0x79, 0x21, 0x45, 0x00]));
+        let time = analyzer.current_stream_time().unwrap();
+        assert_eq!(time.to_rfc3339(), "1993-10-13T12:45:00+00:00");
+        assert_eq!(
+            analyzer.result().local_time_offset.unwrap().local_minus_utc(),
+            9 * 3600
+        );
+    }
+
+    /// Build a PSI section with section syntax and a valid CRC.
+    fn psi_section(table_id: u8, table_id_extension: u16, body: &[u8]) -> Vec<u8> {
+        use crate::ts_analyzer::psi::crc32_mpeg2;
+
+        let section_length = 5 + body.len() + 4;
+        let mut section = vec![
+            table_id,
+            0xB0 | (section_length >> 8) as u8,
+            section_length as u8,
+            (table_id_extension >> 8) as u8,
+            table_id_extension as u8,
+            0xC1,
+            0x00,
+            0x00,
+        ];
+        section.extend_from_slice(body);
+        let crc = crc32_mpeg2(&section);
+        section.extend_from_slice(&crc.to_be_bytes());
+        section
+    }
+
+    /// Build an adaptation-field-only packet carrying a PCR.
+    fn pcr_packet(pid_val: u16, pcr: u64) -> Vec<u8> {
+        let base = pcr / 300;
+        let ext = pcr % 300;
+        let mut packet = vec![
+            0x47,
+            (pid_val >> 8) as u8,
+            pid_val as u8,
+            0x20,
+            183,
+            0x10,
+            (base >> 25) as u8,
+            (base >> 17) as u8,
+            (base >> 9) as u8,
+            (base >> 1) as u8,
+            ((base & 1) << 7) as u8 | 0x7E | (ext >> 8) as u8,
+            ext as u8,
+        ];
+        packet.resize(TS_PACKET_SIZE, 0xFF);
+        packet
+    }
+
+    #[test]
+    fn test_analyzer_measured_bitrate() {
+        let mut analyzer = TsAnalyzer::new(AnalyzerConfig {
+            parse_nit: false,
+            parse_sdt: false,
+            measure_bitrate: true,
+            ..AnalyzerConfig::default()
+        });
+
+        // PAT: program 0x0101 -> PMT PID 0x01F0; PMT: PCR PID 0x0100
+        let mut stream = packetize(pid::PAT, &psi_section(table_id::PAT, 0x7FE1, &[0x01, 0x01, 0xE1, 0xF0]));
+        stream.extend(packetize(0x01F0, &psi_section(table_id::PMT, 0x0101, &[0xE1, 0x00, 0xF0, 0x00])));
+        assert_eq!(analyzer.measured_bitrate_bps(), None);
+
+        // Two PCRs 50ms apart with 100 packets between them
+        stream.extend(pcr_packet(0x0100, 1_000_000));
+        for _ in 0..99 {
+            stream.extend(pcr_packet(0x0200, 0));
+        }
+        stream.extend(pcr_packet(0x0100, 1_000_000 + 27_000_000 / 20));
+
+        assert!(analyzer.feed(&stream));
+        assert_eq!(analyzer.measured_bitrate_bps(), Some(100 * 188 * 8 * 20));
+    }
+
+    #[test]
+    fn test_analyzer_collects_emm_and_ecm_pids() {
+        let mut analyzer = TsAnalyzer::new(AnalyzerConfig {
+            parse_nit: false,
+            parse_sdt: false,
+            ..AnalyzerConfig::default()
+        });
+
+        let ca = |pid: u16| [0x09, 0x04, 0x00, 0x05, 0xE0 | (pid >> 8) as u8, pid as u8];
+        let mut stream = packetize(pid::CAT, &psi_section(table_id::CAT, 0xFFFF, &ca(0x0040)));
+        stream.extend(packetize(pid::PAT, &psi_section(table_id::PAT, 0x7FE1, &[0x01, 0x01, 0xE1, 0xF0])));
+        let mut pmt = vec![0xE1, 0x00, 0xF0, 0x06];
+        pmt.extend_from_slice(&ca(0x0030));
+        stream.extend(packetize(0x01F0, &psi_section(table_id::PMT, 0x0101, &pmt)));
+
+        analyzer.feed(&stream);
+        let result = analyzer.result();
+        assert_eq!(result.emm_pids, vec![(0x0005, 0x0040)]);
+        assert_eq!(result.ecm_pids(), vec![(0x0005, 0x0030)]);
+    }
+
     #[test]
     fn test_analyzer_new() {
         let analyzer = TsAnalyzer::new_default();
@@ -446,6 +883,8 @@ mod tests {
             parse_nit: true,
             parse_sdt: true,
             parse_all_pmts: false,
+            parse_eit: false,
+            measure_bitrate: false,
             max_packets: 0,
         };
 
@@ -541,5 +980,71 @@ mod tests {
         assert_eq!(info.service_type, Some(0x01));
         assert_eq!(info.video_pid, Some(0x0100));
         assert_eq!(info.audio_pids, vec![0x0110]);
+        assert!(!info.is_one_seg);
+
+        // 1seg flag comes from the NIT partial reception descriptor
+        result.nit = Some(NitTable {
+            network_id: 0x7FE0,
+            transport_streams: vec![crate::ts_analyzer::nit::NitTransportStream {
+                transport_stream_id: 0x7FE1,
+                original_network_id: 0x7FE0,
+                descriptors: vec![],
+                terrestrial_delivery: None,
+                partial_reception: vec![0x0101],
+                ts_information: None,
+                remote_control_key: None,
+                satellite_delivery: None,
+                service_list: vec![],
+            }],
+            ..Default::default()
+        });
+        assert!(result.get_channel_info(0x0101).unwrap().is_one_seg);
+    }
+
+    #[test]
+    fn test_analyzer_result_remote_control_key_sources() {
+        use crate::ts_analyzer::descriptors::{RemoteControlKeyDescriptor, TsInformationDescriptor};
+        use crate::ts_analyzer::nit::NitTransportStream;
+        use crate::ts_analyzer::sdt::SdtService;
+
+        let mut result = AnalyzerResult {
+            transport_stream_id: Some(0x7FE1),
+            ..Default::default()
+        };
+        assert_eq!(result.get_remote_control_key(0x0101), None);
+
+        // SDT remote control key descriptor
+        result.sdt = Some(SdtTable {
+            services: vec![SdtService {
+                service_id: 0x0101,
+                descriptors: vec![0xDE, 0x01, 0x09],
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        assert_eq!(result.get_remote_control_key(0x0101), Some(9));
+
+        // NIT remote control key descriptor wins over the SDT
+        let mut ts = NitTransportStream {
+            transport_stream_id: 0x7FE1,
+            remote_control_key: Some(RemoteControlKeyDescriptor { remote_control_key_id: 8 }),
+            ..Default::default()
+        };
+        result.nit = Some(NitTable {
+            transport_streams: vec![ts.clone()],
+            ..Default::default()
+        });
+        assert_eq!(result.get_remote_control_key(0x0101), Some(8));
+
+        // The TS information key (terrestrial) wins over both
+        ts.ts_information = Some(TsInformationDescriptor {
+            remote_control_key_id: 1,
+            ts_name: String::new(),
+        });
+        result.nit = Some(NitTable {
+            transport_streams: vec![ts],
+            ..Default::default()
+        });
+        assert_eq!(result.get_remote_control_key(0x0101), Some(1));
     }
 }
@@ -0,0 +1,451 @@
+//! ARIB STD-B24 caption extraction.
+//!
+//! Follows the PAT and PMT to the caption stream of one service, reassembles
+//! its synchronized PES packets and turns caption statements into timed
+//! text. Times are relative to the first PCR of the service, so they line up
+//! with a player that starts at the beginning of the same stream.
+//!
+//! A statement stays on screen until the next one (an empty statement only
+//! clears the screen) or for at most [`MAX_CUE_MS`].
+
+use serde::Serialize;
+
+use super::packet::{TsPacket, TS_PACKET_SIZE};
+use super::pat::PatTable;
+use super::pmt::PmtTable;
+use super::psi::{PsiSection, SectionCollector};
+use crate::aribb24::decode_arib_b24;
+
+/// PTS/PCR base values are 33 bits wide.
+const PTS_MASK: u64 = (1 << 33) - 1;
+/// Longest a caption stays on screen when no later statement ends it.
+pub const MAX_CUE_MS: u64 = 10_000;
+/// Stream ID of synchronized PES (private_stream_1).
+const STREAM_ID_PRIVATE_1: u8 = 0xBD;
+/// data_identifier of synchronized PES.
+const DATA_ID_SYNCHRONIZED: u8 = 0x80;
+/// Data unit separator that starts every data unit.
+const UNIT_SEPARATOR: u8 = 0x1F;
+/// data_unit_parameter of a statement body (the caption text).
+const DATA_UNIT_STATEMENT_BODY: u8 = 0x20;
+
+/// One caption with its display interval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Caption {
+    /// Milliseconds since the start of the stream.
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+impl Caption {
+    /// Format as a WebVTT cue, including the blank line that ends it.
+    pub fn to_vtt(&self) -> String {
+        format!(
+            "{} --> {}\n{}\n\n",
+            vtt_timestamp(self.start_ms),
+            vtt_timestamp(self.end_ms),
+            self.text
+        )
+    }
+}
+
+fn vtt_timestamp(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Extracts the first-language captions of one service from a TS.
+pub struct CaptionExtractor {
+    /// Service to follow (None = the first one in the PAT).
+    target_sid: Option<u16>,
+    pat_collector: SectionCollector,
+    /// (program_number, PMT PID) of the followed service.
+    program: Option<(u16, u16)>,
+    pmt_collector: SectionCollector,
+    pcr_pid: Option<u16>,
+    caption_pid: Option<u16>,
+    /// PES packet being reassembled.
+    pes: Vec<u8>,
+    /// 90 kHz clock value that maps to 0 ms.
+    base: Option<u64>,
+    /// Caption on screen: (start ms, text).
+    current: Option<(u64, String)>,
+}
+
+impl CaptionExtractor {
+    /// Create an extractor for `target_sid`, or the first service in the
+    /// PAT when None.
+    pub fn new(target_sid: Option<u16>) -> Self {
+        Self {
+            target_sid,
+            pat_collector: SectionCollector::new(),
+            program: None,
+            pmt_collector: SectionCollector::new(),
+            pcr_pid: None,
+            caption_pid: None,
+            pes: Vec::new(),
+            base: None,
+            current: None,
+        }
+    }
+
+    /// Feed packet-aligned TS and return the captions whose end is now known.
+    pub fn push(&mut self, data: &[u8]) -> Vec<Caption> {
+        let mut captions = Vec::new();
+        for pkt_data in data.chunks_exact(TS_PACKET_SIZE) {
+            let Ok(packet) = TsPacket::parse(pkt_data) else {
+                continue;
+            };
+            let pid = packet.header.pid;
+            if pid == 0x0000 {
+                self.process_pat(&packet);
+            } else if self.program.is_some_and(|(_, pmt_pid)| pmt_pid == pid) {
+                self.process_pmt(&packet);
+            }
+            if Some(pid) == self.pcr_pid {
+                if let Some(pcr) = packet.adaptation_field.as_ref().and_then(|af| af.pcr) {
+                    self.process_pcr(pcr / 300, &mut captions);
+                }
+            }
+            if Some(pid) == self.caption_pid {
+                self.process_caption_packet(&packet, &mut captions);
+            }
+        }
+        captions
+    }
+
+    /// Milliseconds from the stream start to a 90 kHz clock value (0 for
+    /// values before the start).
+    fn elapsed_ms(&self, clock: u64) -> Option<u64> {
+        let diff = clock.wrapping_sub(self.base?) & PTS_MASK;
+        Some(if diff > PTS_MASK / 2 { 0 } else { diff / 90 })
+    }
+
+    fn process_pat(&mut self, packet: &TsPacket) {
+        let header = &packet.header;
+        if !self.pat_collector.add_data(packet.payload, header.continuity_counter, header.payload_unit_start) {
+            return;
+        }
+        let Some(section_data) = self.pat_collector.get_section() else {
+            return;
+        };
+        let Ok(section) = PsiSection::parse(section_data) else {
+            return;
+        };
+        let Ok(pat) = PatTable::parse(&section) else {
+            return;
+        };
+        let program = pat
+            .programs
+            .iter()
+            .find(|entry| self.target_sid.map_or(true, |sid| entry.program_number == sid))
+            .map(|entry| (entry.program_number, entry.pid));
+        if program != self.program {
+            self.program = program;
+            self.pmt_collector.clear();
+            self.pcr_pid = None;
+            self.caption_pid = None;
+            self.pes.clear();
+        }
+    }
+
+    fn process_pmt(&mut self, packet: &TsPacket) {
+        let header = &packet.header;
+        if !self.pmt_collector.add_data(packet.payload, header.continuity_counter, header.payload_unit_start) {
+            return;
+        }
+        let Some(section_data) = self.pmt_collector.get_section() else {
+            return;
+        };
+        let Ok(section) = PsiSection::parse(section_data) else {
+            return;
+        };
+        let Ok(pmt) = PmtTable::parse(&section) else {
+            return;
+        };
+        if self.program.map(|(number, _)| number) != Some(pmt.program_number) {
+            return;
+        }
+
+        self.pcr_pid = (pmt.pcr_pid != 0x1FFF).then_some(pmt.pcr_pid);
+        // Prefer the full-segment caption over the 1seg one
+        let caption_pid = pmt
+            .streams
+            .iter()
+            .filter(|stream| stream.is_caption())
+            .min_by_key(|stream| stream.is_one_seg())
+            .map(|stream| stream.elementary_pid);
+        if caption_pid != self.caption_pid {
+            self.caption_pid = caption_pid;
+            self.pes.clear();
+        }
+    }
+
+    /// Take the first PCR as the stream start and end captions that have
+    /// been on screen for too long.
+    fn process_pcr(&mut self, clock: u64, captions: &mut Vec<Caption>) {
+        if self.base.is_none() {
+            self.base = Some(clock & PTS_MASK);
+        }
+        let Some(now_ms) = self.elapsed_ms(clock) else {
+            return;
+        };
+        if self.current.as_ref().is_some_and(|(start, _)| now_ms >= start + MAX_CUE_MS) {
+            self.show(now_ms, String::new(), captions);
+        }
+    }
+
+    fn process_caption_packet(&mut self, packet: &TsPacket, captions: &mut Vec<Caption>) {
+        let header = &packet.header;
+        if header.transport_error || header.is_scrambled() {
+            self.pes.clear();
+            return;
+        }
+        if header.payload_unit_start {
+            // A PES without a length ends where the next one starts
+            if pes_length(&self.pes) == Some(0) {
+                let pes = std::mem::take(&mut self.pes);
+                self.process_pes(&pes, captions);
+            }
+            self.pes.clear();
+        } else if self.pes.is_empty() {
+            return;
+        }
+        self.pes.extend_from_slice(packet.payload);
+
+        if let Some(length) = pes_length(&self.pes).filter(|&length| length > 0) {
+            if self.pes.len() >= 6 + length {
+                let pes = std::mem::take(&mut self.pes);
+                self.process_pes(&pes[..6 + length], captions);
+            }
+        }
+    }
+
+    fn process_pes(&mut self, pes: &[u8], captions: &mut Vec<Caption>) {
+        if pes.len() < 9 || pes[3] != STREAM_ID_PRIVATE_1 {
+            return;
+        }
+        let header_length = pes[8] as usize;
+        if pes[7] & 0x80 == 0 || header_length < 5 || pes.len() < 14 {
+            return;
+        }
+        let Some(at_ms) = self.elapsed_ms(parse_pts(&pes[9..14])) else {
+            // Nothing to time it against until the first PCR
+            return;
+        };
+        let Some(data) = pes.get(9 + header_length..) else {
+            return;
+        };
+        if data.len() < 3 || data[0] != DATA_ID_SYNCHRONIZED {
+            return;
+        }
+        let Some(group) = data.get(3 + (data[2] & 0x0F) as usize..) else {
+            return;
+        };
+        if group.len() < 5 {
+            return;
+        }
+        // Group A and B alternate; only the first language is extracted and
+        // ID 0 is the caption management data
+        if (group[0] >> 2) & 0x0F != 1 {
+            return;
+        }
+        let size = u16::from_be_bytes([group[3], group[4]]) as usize;
+        let Some(text) = group.get(5..5 + size).and_then(statement_text) else {
+            return;
+        };
+        self.show(at_ms, text, captions);
+    }
+
+    /// End the caption on screen at `at_ms` and show `text` (nothing when
+    /// empty) from then on.
+    fn show(&mut self, at_ms: u64, text: String, captions: &mut Vec<Caption>) {
+        if let Some((start_ms, previous)) = self.current.take() {
+            let end_ms = at_ms.min(start_ms + MAX_CUE_MS);
+            if end_ms > start_ms {
+                captions.push(Caption { start_ms, end_ms, text: previous });
+            }
+        }
+        if !text.is_empty() {
+            self.current = Some((at_ms, text));
+        }
+    }
+}
+
+/// PES_packet_length of a reassembled PES, once its header is complete.
+fn pes_length(pes: &[u8]) -> Option<usize> {
+    (pes.len() >= 6 && pes[..3] == [0x00, 0x00, 0x01])
+        .then(|| u16::from_be_bytes([pes[4], pes[5]]) as usize)
+}
+
+/// Read a 33-bit PTS from its 5-byte encoding.
+fn parse_pts(bytes: &[u8]) -> u64 {
+    ((bytes[0] as u64 >> 1) & 0x07) << 30
+        | (bytes[1] as u64) << 22
+        | (bytes[2] as u64 >> 1) << 15
+        | (bytes[3] as u64) << 7
+        | bytes[4] as u64 >> 1
+}
+
+fn read_u24(bytes: &[u8]) -> usize {
+    (bytes[0] as usize) << 16 | (bytes[1] as usize) << 8 | bytes[2] as usize
+}
+
+/// Decode the statement bodies of a caption statement data group.
+fn statement_text(body: &[u8]) -> Option<String> {
+    let tmd = body.first()? >> 6;
+    // Offset or real-time mode carries a 5-byte STM
+    let mut pos = if tmd == 0b01 || tmd == 0b10 { 6 } else { 1 };
+    let loop_length = read_u24(body.get(pos..pos + 3)?);
+    pos += 3;
+    let units = body.get(pos..pos + loop_length)?;
+
+    let mut raw = Vec::new();
+    let mut i = 0;
+    while i + 5 <= units.len() && units[i] == UNIT_SEPARATOR {
+        let parameter = units[i + 1];
+        let size = read_u24(&units[i + 2..i + 5]);
+        let data = units.get(i + 5..i + 5 + size)?;
+        if parameter == DATA_UNIT_STATEMENT_BODY {
+            raw.extend_from_slice(data);
+        }
+        i += 5 + size;
+    }
+
+    let decoded = decode_arib_b24(&raw);
+    let text: String = decoded.chars().filter(|c| !c.is_control()).collect();
+    Some(text.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_analyzer::psi::crc32_mpeg2;
+
+    const PMT_PID: u16 = 0x01F0;
+    const PCR_PID: u16 = 0x01FF;
+    const CAPTION_PID: u16 = 0x0130;
+
+    fn packet(pid: u16, payload_unit_start: bool, pcr: Option<u64>, payload: &[u8]) -> Vec<u8> {
+        let mut pkt = vec![0x47, (pid >> 8) as u8 | if payload_unit_start { 0x40 } else { 0 }, pid as u8];
+        match pcr {
+            Some(pcr) => {
+                // Adaptation field with PCR, padded so the payload fills the packet
+                let af_length = TS_PACKET_SIZE - 5 - payload.len();
+                pkt.push(0x30);
+                pkt.push(af_length as u8);
+                pkt.push(0x10);
+                pkt.extend_from_slice(&[
+                    (pcr >> 25) as u8,
+                    (pcr >> 17) as u8,
+                    (pcr >> 9) as u8,
+                    (pcr >> 1) as u8,
+                    ((pcr & 1) << 7) as u8 | 0x7E,
+                    0,
+                ]);
+                pkt.resize(5 + af_length, 0xFF);
+            }
+            None => pkt.push(0x10),
+        }
+        pkt.extend_from_slice(payload);
+        pkt.resize(TS_PACKET_SIZE, 0xFF);
+        pkt
+    }
+
+    fn section(table_id: u8, table_id_extension: u16, body: &[u8]) -> Vec<u8> {
+        let length = 5 + body.len() + 4;
+        let mut section = vec![
+            table_id,
+            0xB0 | (length >> 8) as u8,
+            length as u8,
+            (table_id_extension >> 8) as u8,
+            table_id_extension as u8,
+            0xC1,
+            0,
+            0,
+        ];
+        section.extend_from_slice(body);
+        let crc = crc32_mpeg2(&section);
+        section.extend_from_slice(&crc.to_be_bytes());
+        // Pointer field
+        let mut payload = vec![0];
+        payload.extend_from_slice(&section);
+        payload
+    }
+
+    fn caption_pes(pts: u64, text: &[u8]) -> Vec<u8> {
+        let mut units = vec![UNIT_SEPARATOR, DATA_UNIT_STATEMENT_BODY, 0, 0, text.len() as u8];
+        units.extend_from_slice(text);
+        let mut statement = vec![0x00, 0, 0, units.len() as u8];
+        statement.extend_from_slice(&units);
+        let mut group = vec![0x01 << 2, 0, 0, 0, statement.len() as u8];
+        group.extend_from_slice(&statement);
+        group.extend_from_slice(&[0, 0]); // CRC16 (not checked)
+        let mut data = vec![DATA_ID_SYNCHRONIZED, 0xFF, 0xF0];
+        data.extend_from_slice(&group);
+
+        let mut pes = vec![0, 0, 1, STREAM_ID_PRIVATE_1, 0, 0, 0x80, 0x80, 5];
+        pes.extend_from_slice(&[
+            0x21 | ((pts >> 29) & 0x0E) as u8,
+            (pts >> 22) as u8,
+            ((pts >> 14) & 0xFE) as u8 | 1,
+            (pts >> 7) as u8,
+            ((pts << 1) & 0xFE) as u8 | 1,
+        ]);
+        pes.extend_from_slice(&data);
+        let length = pes.len() - 6;
+        pes[4..6].copy_from_slice(&(length as u16).to_be_bytes());
+        pes
+    }
+
+    #[test]
+    fn test_pts_roundtrip() {
+        let pes = caption_pes(0x1_2345_6789, b"");
+        assert_eq!(parse_pts(&pes[9..14]), 0x1_2345_6789);
+        assert_eq!(vtt_timestamp(3_723_004), "01:02:03.004");
+    }
+
+    #[test]
+    fn test_extracts_timed_captions() {
+        let pat = section(0x00, 0x7FE0, &[0x04, 0x00, 0xE0 | (PMT_PID >> 8) as u8, PMT_PID as u8]);
+        let pmt = section(
+            0x02,
+            0x0400,
+            &[
+                0xE0 | (PCR_PID >> 8) as u8, PCR_PID as u8, 0xF0, 0x00,
+                // Caption ES with stream_identifier_descriptor (component 0x30)
+                0x06, 0xE0 | (CAPTION_PID >> 8) as u8, CAPTION_PID as u8, 0xF0, 0x03, 0x52, 0x01, 0x30,
+            ],
+        );
+        let base = 900_000u64; // 10 s in 90 kHz units
+
+        let mut ts = Vec::new();
+        ts.extend(packet(0x0000, true, None, &pat));
+        ts.extend(packet(PMT_PID, true, None, &pmt));
+        ts.extend(packet(PCR_PID, false, Some(base), &[]));
+        // LS1 switches to the alphanumeric set
+        ts.extend(packet(CAPTION_PID, true, None, &caption_pes(base + 90_000, b"\x0eABC")));
+        ts.extend(packet(CAPTION_PID, true, None, &caption_pes(base + 270_000, b"\x0c")));
+
+        let mut extractor = CaptionExtractor::new(Some(0x0400));
+        let captions = extractor.push(&ts);
+        assert_eq!(
+            captions,
+            vec![Caption { start_ms: 1000, end_ms: 3000, text: "ＡＢＣ".to_string() }]
+        );
+        assert_eq!(captions[0].to_vtt(), "00:00:01.000 --> 00:00:03.000\nＡＢＣ\n\n");
+
+        // A caption nobody clears is ended by the PCR after MAX_CUE_MS
+        let mut ts = packet(CAPTION_PID, true, None, &caption_pes(base + 450_000, b"\x0eD"));
+        ts.extend(packet(PCR_PID, false, Some((base + 450_000) + MAX_CUE_MS * 90), &[]));
+        let captions = extractor.push(&ts);
+        assert_eq!(captions.len(), 1);
+        assert_eq!((captions[0].start_ms, captions[0].end_ms), (5000, 5000 + MAX_CUE_MS));
+    }
+}
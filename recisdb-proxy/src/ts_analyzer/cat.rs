@@ -0,0 +1,82 @@
+//! CAT (Conditional Access Table) parsing.
+//!
+//! The CAT is transmitted on PID 0x0001 and lists, through CA descriptors,
+//! the PIDs carrying EMMs (Entitlement Management Messages) for each CA
+//! system. ECM PIDs are announced per program in the PMT instead.
+
+use super::descriptors::{parse_ca_descriptors, CaDescriptor};
+use super::psi::PsiSection;
+use super::table_id;
+
+/// Parsed CAT (Conditional Access Table).
+#[derive(Debug, Clone, Default)]
+pub struct CatTable {
+    /// Version number.
+    pub version_number: u8,
+    /// CA descriptors.
+    pub ca_descriptors: Vec<CaDescriptor>,
+}
+
+impl CatTable {
+    /// Parse a CAT from a PSI section.
+    pub fn parse(section: &PsiSection) -> Result<Self, &'static str> {
+        if section.header.table_id != table_id::CAT {
+            return Err("Not a CAT section");
+        }
+
+        Ok(CatTable {
+            version_number: section.header.version_number,
+            ca_descriptors: parse_ca_descriptors(section.data),
+        })
+    }
+
+    /// Get EMM PIDs as `(ca_system_id, ca_pid)` pairs.
+    pub fn emm_pids(&self) -> Vec<(u16, u16)> {
+        self.ca_descriptors
+            .iter()
+            .map(|d| (d.ca_system_id, d.ca_pid))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_analyzer::psi::PsiHeader;
+
+    fn section(table_id: u8, data: &[u8]) -> PsiSection<'_> {
+        PsiSection {
+            header: PsiHeader {
+                table_id,
+                section_syntax_indicator: true,
+                section_length: (data.len() + 9) as u16,
+                table_id_extension: 0xFFFF,
+                version_number: 2,
+                current_next_indicator: true,
+                section_number: 0,
+                last_section_number: 0,
+            },
+            data,
+            crc32: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_cat() {
+        let data = [
+            // CA descriptor: B-CAS, EMM PID 0x0040 (reserved bits set)
+            0x09, 0x04, 0x00, 0x05, 0xE0, 0x40,
+            // Unrelated descriptor is skipped
+            0xC1, 0x01, 0x00,
+            // CA descriptor: ACAS, EMM PID 0x0041, one private byte
+            0x09, 0x05, 0x00, 0x0A, 0xE0, 0x41, 0x7F,
+        ];
+        let cat = CatTable::parse(&section(table_id::CAT, &data)).unwrap();
+
+        assert_eq!(cat.version_number, 2);
+        assert_eq!(cat.emm_pids(), vec![(0x0005, 0x0040), (0x000A, 0x0041)]);
+        assert_eq!(cat.ca_descriptors[1].private_data, vec![0x7F]);
+
+        assert!(CatTable::parse(&section(table_id::PMT, &data)).is_err());
+    }
+}
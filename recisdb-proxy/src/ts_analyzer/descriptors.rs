@@ -136,6 +136,57 @@ impl TerrestrialDeliveryDescriptor {
     }
 }
 
+/// Partial reception descriptor (0xFB for ISDB-T 1seg).
+///
+/// Lists the service IDs carried on the partial reception (1seg) layer.
+#[derive(Debug, Clone, Default)]
+pub struct PartialReceptionDescriptor {
+    /// 1seg service IDs.
+    pub service_ids: Vec<u16>,
+}
+
+impl PartialReceptionDescriptor {
+    /// Parse a partial reception descriptor.
+    pub fn parse(data: &[u8]) -> Result<Self, &'static str> {
+        if !data.len().is_multiple_of(2) {
+            return Err("Invalid partial reception descriptor length");
+        }
+
+        Ok(PartialReceptionDescriptor {
+            service_ids: data
+                .chunks_exact(2)
+                .map(|c| ((c[0] as u16) << 8) | c[1] as u16)
+                .collect(),
+        })
+    }
+}
+
+/// Service list descriptor (0x41).
+///
+/// Lists every service of a transport stream with its type, so services can
+/// be known from the NIT even when the SDT is missing or incomplete.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceListDescriptor {
+    /// (service_id, service_type) pairs.
+    pub services: Vec<(u16, u8)>,
+}
+
+impl ServiceListDescriptor {
+    /// Parse a service list descriptor.
+    pub fn parse(data: &[u8]) -> Result<Self, &'static str> {
+        if !data.len().is_multiple_of(3) {
+            return Err("Invalid service list descriptor length");
+        }
+
+        Ok(ServiceListDescriptor {
+            services: data
+                .chunks_exact(3)
+                .map(|c| (((c[0] as u16) << 8) | c[1] as u16, c[2]))
+                .collect(),
+        })
+    }
+}
+
 /// Satellite delivery system descriptor (0x43).
 #[derive(Debug, Clone, Default)]
 pub struct SatelliteDeliveryDescriptor {
@@ -225,6 +276,162 @@ impl TsInformationDescriptor {
     }
 }
 
+/// Remote control key descriptor (0xDE for ISDB).
+///
+/// Carried in the NIT transport stream loop or the SDT service loop
+/// depending on the broadcaster.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteControlKeyDescriptor {
+    /// Remote control key ID (physical button number).
+    pub remote_control_key_id: u8,
+}
+
+impl RemoteControlKeyDescriptor {
+    /// Parse a remote control key descriptor.
+    pub fn parse(data: &[u8]) -> Result<Self, &'static str> {
+        match data.first() {
+            Some(&remote_control_key_id) => Ok(RemoteControlKeyDescriptor { remote_control_key_id }),
+            None => Err("Remote control key descriptor too short"),
+        }
+    }
+}
+
+/// Logo transmission descriptor (0xCF for ISDB).
+///
+/// Links a service to a logo carried in the CDT (types 0x01/0x02) or gives
+/// a simple text logo (type 0x03).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogoTransmissionDescriptor {
+    /// Logo transmission type (0x01 = CDT, 0x02 = logo ID only, 0x03 = simple logo).
+    pub transmission_type: u8,
+    /// Logo ID (types 0x01 and 0x02).
+    pub logo_id: Option<u16>,
+    /// Logo version (type 0x01).
+    pub logo_version: Option<u16>,
+    /// CDT download data ID (type 0x01).
+    pub download_data_id: Option<u16>,
+    /// Simple logo characters (type 0x03).
+    pub logo_char: Option<String>,
+}
+
+impl LogoTransmissionDescriptor {
+    /// Parse a logo transmission descriptor.
+    pub fn parse(data: &[u8]) -> Result<Self, &'static str> {
+        let Some(&transmission_type) = data.first() else {
+            return Err("Logo transmission descriptor too short");
+        };
+
+        let mut desc = LogoTransmissionDescriptor {
+            transmission_type,
+            ..Default::default()
+        };
+
+        match transmission_type {
+            0x01 => {
+                if data.len() < 7 {
+                    return Err("Logo transmission descriptor too short");
+                }
+                desc.logo_id = Some(((data[1] as u16 & 0x01) << 8) | data[2] as u16);
+                desc.logo_version = Some(((data[3] as u16 & 0x0F) << 8) | data[4] as u16);
+                desc.download_data_id = Some(((data[5] as u16) << 8) | data[6] as u16);
+            }
+            0x02 => {
+                if data.len() < 3 {
+                    return Err("Logo transmission descriptor too short");
+                }
+                desc.logo_id = Some(((data[1] as u16 & 0x01) << 8) | data[2] as u16);
+            }
+            0x03 => {
+                desc.logo_char = Some(decode_arib_string(&data[1..]));
+            }
+            _ => return Err("Unknown logo transmission type"),
+        }
+
+        Ok(desc)
+    }
+}
+
+/// Short event descriptor (0x4D).
+#[derive(Debug, Clone, Default)]
+pub struct ShortEventDescriptor {
+    /// ISO 639 language code (e.g. "jpn").
+    pub language_code: String,
+    /// Event name (title).
+    pub event_name: String,
+    /// Event description.
+    pub text: String,
+}
+
+impl ShortEventDescriptor {
+    /// Parse a short event descriptor.
+    pub fn parse(data: &[u8]) -> Result<Self, &'static str> {
+        if data.len() < 5 {
+            return Err("Short event descriptor too short");
+        }
+
+        let language_code = String::from_utf8_lossy(&data[0..3]).to_string();
+        let event_name_length = data[3] as usize;
+
+        if data.len() < 4 + event_name_length + 1 {
+            return Err("Invalid event name length");
+        }
+
+        let event_name = decode_arib_string(&data[4..4 + event_name_length]);
+
+        let text_offset = 4 + event_name_length;
+        let text_length = data[text_offset] as usize;
+
+        if data.len() < text_offset + 1 + text_length {
+            return Err("Invalid event text length");
+        }
+
+        let text = decode_arib_string(&data[text_offset + 1..text_offset + 1 + text_length]);
+
+        Ok(ShortEventDescriptor {
+            language_code,
+            event_name,
+            text,
+        })
+    }
+}
+
+/// Conditional access descriptor (0x09).
+///
+/// In the CAT the PID carries EMMs; in the PMT it carries ECMs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaDescriptor {
+    /// CA system ID (0x0005 = B-CAS, 0x000A = ACAS).
+    pub ca_system_id: u16,
+    /// PID carrying the EMM or ECM stream.
+    pub ca_pid: u16,
+    /// Private data bytes.
+    pub private_data: Vec<u8>,
+}
+
+impl CaDescriptor {
+    /// Parse a conditional access descriptor.
+    pub fn parse(data: &[u8]) -> Result<Self, &'static str> {
+        if data.len() < 4 {
+            return Err("CA descriptor too short");
+        }
+
+        Ok(CaDescriptor {
+            ca_system_id: ((data[0] as u16) << 8) | data[1] as u16,
+            ca_pid: ((data[2] as u16 & 0x1F) << 8) | data[3] as u16,
+            private_data: data[4..].to_vec(),
+        })
+    }
+}
+
+/// Parse every conditional access descriptor in a descriptor loop.
+pub fn parse_ca_descriptors(data: &[u8]) -> Vec<CaDescriptor> {
+    parse_descriptor_loop(data)
+        .into_iter()
+        .filter(|(tag, _)| *tag == descriptor_tag::CONDITIONAL_ACCESS)
+        .filter_map(|(_, d)| CaDescriptor::parse(&d).ok())
+        .collect()
+}
+
 /// Parse descriptors from a descriptor loop.
 pub fn parse_descriptor_loop(data: &[u8]) -> Vec<(u8, Vec<u8>)> {
     let mut descriptors = Vec::new();
@@ -323,6 +530,43 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[test]
+    fn test_parse_remote_control_key_descriptor() {
+        let desc = RemoteControlKeyDescriptor::parse(&[0x04]).unwrap();
+        assert_eq!(desc.remote_control_key_id, 4);
+        assert!(RemoteControlKeyDescriptor::parse(&[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_service_list_descriptor() {
+        let desc = ServiceListDescriptor::parse(&[0x04, 0x00, 0x01, 0x05, 0xC8, 0xC0]).unwrap();
+        assert_eq!(desc.services, vec![(0x0400, 0x01), (0x05C8, 0xC0)]);
+        assert!(ServiceListDescriptor::parse(&[0x04, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_parse_logo_transmission_descriptor() {
+        // CDT logo: logo_id=0x105, logo_version=0x00A, download_data_id=0x0105
+        let desc = LogoTransmissionDescriptor::parse(&[0x01, 0xFF, 0x05, 0xF0, 0x0A, 0x01, 0x05]).unwrap();
+        assert_eq!(desc.logo_id, Some(0x105));
+        assert_eq!(desc.logo_version, Some(0x00A));
+        assert_eq!(desc.download_data_id, Some(0x0105));
+
+        // Logo ID only
+        let desc = LogoTransmissionDescriptor::parse(&[0x02, 0xFE, 0x20]).unwrap();
+        assert_eq!(desc.logo_id, Some(0x020));
+        assert_eq!(desc.logo_version, None);
+
+        // Simple logo: "ＮＨＫ" in ARIB (JIS X 0208, the default G0 set)
+        let desc =
+            LogoTransmissionDescriptor::parse(&[0x03, 0x23, 0x4E, 0x23, 0x48, 0x23, 0x4B]).unwrap();
+        assert_eq!(desc.logo_id, None);
+        assert_eq!(desc.logo_char.as_deref(), Some("ＮＨＫ"));
+
+        assert!(LogoTransmissionDescriptor::parse(&[0x01, 0xFF, 0x05]).is_err());
+        assert!(LogoTransmissionDescriptor::parse(&[]).is_err());
+    }
+
     #[test]
     fn test_bcd_to_u32() {
         assert_eq!(bcd_to_u32(&[0x12, 0x34]), 1234);
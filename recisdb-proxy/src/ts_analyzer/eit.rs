@@ -0,0 +1,262 @@
+//! EIT (Event Information Table) parsing.
+//!
+//! The EIT is transmitted on PID 0x0012 and carries program guide data:
+//! present/following events (table_id 0x4E/0x4F) and schedule events
+//! (table_id 0x50-0x6F) for each service.
+
+use chrono::NaiveDateTime;
+
+use super::descriptors::{find_descriptor, ShortEventDescriptor};
+use super::psi::PsiSection;
+use super::time::{jst_to_unix, parse_bcd_duration, parse_mjd_bcd};
+use super::{descriptor_tag, table_id};
+
+/// Event entry in the EIT.
+#[derive(Debug, Clone, Default)]
+pub struct EitEvent {
+    /// Event ID.
+    pub event_id: u16,
+    /// Start time (JST), or None if undefined.
+    pub start_time: Option<NaiveDateTime>,
+    /// Duration in seconds, or None if undefined.
+    pub duration_secs: Option<u32>,
+    /// Running status.
+    pub running_status: u8,
+    /// Free CA mode.
+    pub free_ca_mode: bool,
+    /// Event descriptors (raw).
+    pub descriptors: Vec<u8>,
+    /// Parsed short event descriptor.
+    pub short_event: Option<ShortEventDescriptor>,
+}
+
+impl EitEvent {
+    /// Parse descriptors and extract known types.
+    pub fn parse_descriptors(&mut self) {
+        if let Some(data) = find_descriptor(&self.descriptors, descriptor_tag::SHORT_EVENT) {
+            if let Ok(desc) = ShortEventDescriptor::parse(&data) {
+                self.short_event = Some(desc);
+            }
+        }
+    }
+
+    /// Start time as a unix timestamp, or None if undefined.
+    pub fn start_timestamp(&self) -> Option<i64> {
+        self.start_time.map(jst_to_unix)
+    }
+
+    /// Get event title (from short event descriptor).
+    pub fn title(&self) -> Option<&str> {
+        self.short_event.as_ref().map(|d| d.event_name.as_str())
+    }
+
+    /// Get event description (from short event descriptor).
+    pub fn description(&self) -> Option<&str> {
+        self.short_event.as_ref().map(|d| d.text.as_str())
+    }
+}
+
+/// Parsed EIT section.
+#[derive(Debug, Clone, Default)]
+pub struct EitTable {
+    /// Table ID (0x4E-0x6F).
+    pub table_id: u8,
+    /// Service ID.
+    pub service_id: u16,
+    /// Transport stream ID.
+    pub transport_stream_id: u16,
+    /// Original network ID.
+    pub original_network_id: u16,
+    /// Version number.
+    pub version_number: u8,
+    /// Section number.
+    pub section_number: u8,
+    /// Last section number.
+    pub last_section_number: u8,
+    /// Segment last section number.
+    pub segment_last_section_number: u8,
+    /// Last table ID.
+    pub last_table_id: u8,
+    /// Events.
+    pub events: Vec<EitEvent>,
+}
+
+impl EitTable {
+    /// Parse an EIT from a PSI section.
+    pub fn parse(section: &PsiSection) -> Result<Self, &'static str> {
+        if !is_eit_table_id(section.header.table_id) {
+            return Err("Not an EIT section");
+        }
+
+        let data = section.data;
+        if data.len() < 6 {
+            return Err("EIT data too short");
+        }
+
+        let mut eit = EitTable {
+            table_id: section.header.table_id,
+            service_id: section.header.table_id_extension,
+            transport_stream_id: ((data[0] as u16) << 8) | data[1] as u16,
+            original_network_id: ((data[2] as u16) << 8) | data[3] as u16,
+            version_number: section.header.version_number,
+            section_number: section.header.section_number,
+            last_section_number: section.header.last_section_number,
+            segment_last_section_number: data[4],
+            last_table_id: data[5],
+            events: Vec::new(),
+        };
+
+        // Parse event loop
+        let mut offset = 6;
+        while offset + 12 <= data.len() {
+            let event_id = ((data[offset] as u16) << 8) | data[offset + 1] as u16;
+            let start_time = parse_mjd_bcd(&data[offset + 2..offset + 7]);
+            let duration_secs = parse_bcd_duration(&data[offset + 7..offset + 10]);
+            let running_status = (data[offset + 10] >> 5) & 0x07;
+            let free_ca_mode = data[offset + 10] & 0x10 != 0;
+            let descriptors_length =
+                ((data[offset + 10] as usize & 0x0F) << 8) | data[offset + 11] as usize;
+
+            offset += 12;
+
+            if offset + descriptors_length > data.len() {
+                break;
+            }
+
+            let descriptors = data[offset..offset + descriptors_length].to_vec();
+            offset += descriptors_length;
+
+            let mut event = EitEvent {
+                event_id,
+                start_time,
+                duration_secs,
+                running_status,
+                free_ca_mode,
+                descriptors,
+                short_event: None,
+            };
+            event.parse_descriptors();
+
+            eit.events.push(event);
+        }
+
+        Ok(eit)
+    }
+
+    /// Check if this is a present/following section (vs. schedule).
+    pub fn is_present_following(&self) -> bool {
+        self.table_id == table_id::EIT_PF_ACTUAL || self.table_id == table_id::EIT_PF_OTHER
+    }
+
+    /// Check if this section describes the current TS (actual vs. other).
+    pub fn is_actual(&self) -> bool {
+        self.table_id == table_id::EIT_PF_ACTUAL
+            || (table_id::EIT_SCHEDULE_ACTUAL_FIRST..=table_id::EIT_SCHEDULE_ACTUAL_LAST)
+                .contains(&self.table_id)
+    }
+}
+
+/// Check if a table ID belongs to the EIT (0x4E-0x6F).
+pub fn is_eit_table_id(tid: u8) -> bool {
+    (table_id::EIT_PF_ACTUAL..=table_id::EIT_SCHEDULE_OTHER_LAST).contains(&tid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_analyzer::psi::PsiHeader;
+
+    fn eit_data() -> Vec<u8> {
+        vec![
+            // transport_stream_id = 0x7FE1
+            0x7F, 0xE1,
+            // original_network_id = 0x7FE0
+            0x7F, 0xE0,
+            // segment_last_section_number, last_table_id
+            0x01, 0x4E,
+            // Event: event_id = 0x1234
+            0x12, 0x34,
+            // start_time = 1993-10-13 12:45:00
+            0xC0, 0x79, 0x12, 0x45, 0x00,
+            // duration = 01:30:00
+            0x01, 0x30, 0x00,
+            // running_status=4, free_ca=0, descriptors_length=9
+            0x80, 0x09,
+            // Short event descriptor: tag=0x4D, length=7
+            0x4D, 0x07,
+            // language "jpn"
+            b'j', b'p', b'n',
+            // event_name_length=1, name
+            0x01, 0x41,
+            // text_length=1, text
+            0x01, 0x42,
+            // Second event with undefined start/duration and no descriptors
+            0x12, 0x35,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF,
+            0x20, 0x00,
+        ]
+    }
+
+    fn section(table_id: u8, data: &[u8]) -> PsiSection<'_> {
+        PsiSection {
+            header: PsiHeader {
+                table_id,
+                section_syntax_indicator: true,
+                section_length: (data.len() + 9) as u16,
+                table_id_extension: 0x0101, // service_id
+                version_number: 3,
+                current_next_indicator: true,
+                section_number: 0,
+                last_section_number: 1,
+            },
+            data,
+            crc32: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_eit() {
+        let data = eit_data();
+        let eit = EitTable::parse(&section(table_id::EIT_PF_ACTUAL, &data)).unwrap();
+
+        assert_eq!(eit.service_id, 0x0101);
+        assert_eq!(eit.transport_stream_id, 0x7FE1);
+        assert_eq!(eit.original_network_id, 0x7FE0);
+        assert_eq!(eit.version_number, 3);
+        assert!(eit.is_present_following());
+        assert!(eit.is_actual());
+        assert_eq!(eit.events.len(), 2);
+
+        let event = &eit.events[0];
+        assert_eq!(event.event_id, 0x1234);
+        assert_eq!(event.start_time.unwrap().to_string(), "1993-10-13 12:45:00");
+        assert_eq!(event.duration_secs, Some(5400));
+        assert_eq!(event.running_status, 4);
+        assert!(!event.free_ca_mode);
+        let short = event.short_event.as_ref().unwrap();
+        assert_eq!(short.language_code, "jpn");
+
+        let undefined = &eit.events[1];
+        assert_eq!(undefined.event_id, 0x1235);
+        assert_eq!(undefined.start_time, None);
+        assert_eq!(undefined.duration_secs, None);
+        assert!(undefined.short_event.is_none());
+    }
+
+    #[test]
+    fn test_eit_table_ids() {
+        assert!(is_eit_table_id(0x4E));
+        assert!(is_eit_table_id(0x58));
+        assert!(is_eit_table_id(0x6F));
+        assert!(!is_eit_table_id(0x42));
+        assert!(!is_eit_table_id(0x70));
+
+        let data = eit_data();
+        let schedule_other = EitTable::parse(&section(0x60, &data)).unwrap();
+        assert!(!schedule_other.is_present_following());
+        assert!(!schedule_other.is_actual());
+
+        assert!(EitTable::parse(&section(table_id::SDT_ACTUAL, &data)).is_err());
+    }
+}
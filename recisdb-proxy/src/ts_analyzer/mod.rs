@@ -5,9 +5,12 @@
 //!
 //! # Supported Tables
 //! - PAT (Program Association Table) - PID 0x0000
+//! - CAT (Conditional Access Table) - PID 0x0001
 //! - PMT (Program Map Table) - Variable PIDs from PAT
 //! - NIT (Network Information Table) - PID 0x0010
 //! - SDT (Service Description Table) - PID 0x0011
+//! - EIT (Event Information Table) - PID 0x0012
+//! - TDT/TOT (Time and Date / Time Offset Table) - PID 0x0014
 //!
 //! # Usage
 //! ```ignore
@@ -27,8 +30,14 @@ mod pat;
 mod pmt;
 mod nit;
 mod sdt;
+mod eit;
+mod time;
+mod tdt;
+mod pcr;
+mod cat;
 mod analyzer;
 mod descriptors;
+pub mod caption;
 pub mod service_filter;
 
 pub use packet::{TsPacket, TsHeader, AdaptationField, TS_PACKET_SIZE, SYNC_BYTE};
@@ -37,8 +46,12 @@ pub use pat::{PatTable, PatEntry};
 pub use pmt::{PmtTable, PmtStream};
 pub use nit::{NitTable, NitTransportStream};
 pub use sdt::{SdtTable, SdtService};
-pub use analyzer::{TsAnalyzer, AnalyzerConfig, AnalyzerResult};
-pub use descriptors::{parse_descriptor_loop, ServiceDescriptor, TerrestrialDeliveryDescriptor};
+pub use eit::{EitTable, EitEvent};
+pub use tdt::TimeTable;
+pub use pcr::PcrTracker;
+pub use cat::CatTable;
+pub use analyzer::{TsAnalyzer, AnalyzerConfig, AnalyzerResult, EitEventKey};
+pub use descriptors::{parse_descriptor_loop, CaDescriptor, LogoTransmissionDescriptor, PartialReceptionDescriptor, RemoteControlKeyDescriptor, SatelliteDeliveryDescriptor, ServiceDescriptor, ShortEventDescriptor, TerrestrialDeliveryDescriptor, TsInformationDescriptor};
 
 /// Well-known PIDs in MPEG-TS.
 pub mod pid {
@@ -76,16 +89,42 @@ pub mod table_id {
     pub const SDT_ACTUAL: u8 = 0x42;
     /// Service Description Section - other.
     pub const SDT_OTHER: u8 = 0x46;
+    /// Event Information Section - actual, present/following.
+    pub const EIT_PF_ACTUAL: u8 = 0x4E;
+    /// Event Information Section - other, present/following.
+    pub const EIT_PF_OTHER: u8 = 0x4F;
+    /// Event Information Section - actual, schedule (first).
+    pub const EIT_SCHEDULE_ACTUAL_FIRST: u8 = 0x50;
+    /// Event Information Section - actual, schedule (last).
+    pub const EIT_SCHEDULE_ACTUAL_LAST: u8 = 0x5F;
+    /// Event Information Section - other, schedule (first).
+    pub const EIT_SCHEDULE_OTHER_FIRST: u8 = 0x60;
+    /// Event Information Section - other, schedule (last).
+    pub const EIT_SCHEDULE_OTHER_LAST: u8 = 0x6F;
+    /// Time and Date Section.
+    pub const TDT: u8 = 0x70;
+    /// Time Offset Section.
+    pub const TOT: u8 = 0x73;
 }
 
 /// Descriptor tags used in PSI/SI tables.
 pub mod descriptor_tag {
+    /// Conditional access descriptor (0x09).
+    pub const CONDITIONAL_ACCESS: u8 = 0x09;
     /// Service descriptor (0x48).
     pub const SERVICE: u8 = 0x48;
     /// Network name descriptor (0x40).
     pub const NETWORK_NAME: u8 = 0x40;
     /// Service list descriptor (0x41).
     pub const SERVICE_LIST: u8 = 0x41;
+    /// Short event descriptor (0x4D).
+    pub const SHORT_EVENT: u8 = 0x4D;
+    /// Local time offset descriptor (0x58).
+    pub const LOCAL_TIME_OFFSET: u8 = 0x58;
+    /// Stream identifier descriptor (0x52), carries the component tag.
+    pub const STREAM_IDENTIFIER: u8 = 0x52;
+    /// Data component descriptor (0xFD for ISDB).
+    pub const DATA_COMPONENT: u8 = 0xFD;
     /// Terrestrial delivery system descriptor (0xFA for ISDB-T).
     pub const TERRESTRIAL_DELIVERY: u8 = 0xFA;
     /// Satellite delivery system descriptor.
@@ -4,7 +4,9 @@
 //! the network and transport streams, including physical channel parameters.
 
 use super::descriptors::{
-    find_descriptor, parse_descriptor_loop, NetworkNameDescriptor, TerrestrialDeliveryDescriptor,
+    find_descriptor, parse_descriptor_loop, NetworkNameDescriptor, PartialReceptionDescriptor,
+    RemoteControlKeyDescriptor, SatelliteDeliveryDescriptor, ServiceListDescriptor,
+    TerrestrialDeliveryDescriptor, TsInformationDescriptor,
 };
 use super::psi::PsiSection;
 use super::{descriptor_tag, table_id};
@@ -20,6 +22,16 @@ pub struct NitTransportStream {
     pub descriptors: Vec<u8>,
     /// Terrestrial delivery descriptor (if present).
     pub terrestrial_delivery: Option<TerrestrialDeliveryDescriptor>,
+    /// 1seg service IDs (from partial reception descriptor).
+    pub partial_reception: Vec<u16>,
+    /// TS information descriptor (TS name and remote control key).
+    pub ts_information: Option<TsInformationDescriptor>,
+    /// Remote control key descriptor (if present).
+    pub remote_control_key: Option<RemoteControlKeyDescriptor>,
+    /// Satellite delivery descriptor (BS/CS, if present).
+    pub satellite_delivery: Option<SatelliteDeliveryDescriptor>,
+    /// (service_id, service_type) pairs from the service list descriptor.
+    pub service_list: Vec<(u16, u8)>,
 }
 
 impl NitTransportStream {
@@ -31,6 +43,68 @@ impl NitTransportStream {
                 self.terrestrial_delivery = Some(desc);
             }
         }
+        if let Some(data) = find_descriptor(&self.descriptors, descriptor_tag::SATELLITE_DELIVERY) {
+            if let Ok(desc) = SatelliteDeliveryDescriptor::parse(&data) {
+                self.satellite_delivery = Some(desc);
+            }
+        }
+        if let Some(data) = find_descriptor(&self.descriptors, descriptor_tag::PARTIAL_RECEPTION) {
+            if let Ok(desc) = PartialReceptionDescriptor::parse(&data) {
+                self.partial_reception = desc.service_ids;
+            }
+        }
+        if let Some(data) = find_descriptor(&self.descriptors, descriptor_tag::TS_INFORMATION) {
+            if let Ok(desc) = TsInformationDescriptor::parse(&data) {
+                self.ts_information = Some(desc);
+            }
+        }
+        if let Some(data) = find_descriptor(&self.descriptors, descriptor_tag::REMOTE_CONTROL_KEY) {
+            if let Ok(desc) = RemoteControlKeyDescriptor::parse(&data) {
+                self.remote_control_key = Some(desc);
+            }
+        }
+        if let Some(data) = find_descriptor(&self.descriptors, descriptor_tag::SERVICE_LIST) {
+            if let Ok(desc) = ServiceListDescriptor::parse(&data) {
+                self.service_list = desc.services;
+            }
+        }
+    }
+
+    /// Get a service's type from the service list descriptor.
+    pub fn service_type(&self, service_id: u16) -> Option<u8> {
+        self.service_list
+            .iter()
+            .find(|(sid, _)| *sid == service_id)
+            .map(|(_, service_type)| *service_type)
+    }
+
+    /// Get the TS name (from TS information descriptor).
+    pub fn ts_name(&self) -> Option<&str> {
+        self.ts_information
+            .as_ref()
+            .map(|d| d.ts_name.as_str())
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Get the remote control key ID.
+    ///
+    /// Terrestrial networks announce it in the TS information descriptor;
+    /// the remote control key descriptor is used when that is absent.
+    pub fn remote_control_key_id(&self) -> Option<u8> {
+        self.ts_information
+            .as_ref()
+            .map(|d| d.remote_control_key_id)
+            .filter(|&key| key != 0)
+            .or_else(|| self.remote_control_key.as_ref().map(|d| d.remote_control_key_id))
+            .filter(|&key| key != 0)
+    }
+
+    /// Get the satellite transponder frequency in kHz (BS/CS only).
+    pub fn satellite_frequency(&self) -> Option<u32> {
+        self.satellite_delivery
+            .as_ref()
+            .map(|d| d.frequency)
+            .filter(|&freq| freq != 0)
     }
 
     /// Get all frequencies from terrestrial delivery descriptor.
@@ -125,6 +199,11 @@ impl NitTable {
                 original_network_id,
                 descriptors,
                 terrestrial_delivery: None,
+                partial_reception: Vec::new(),
+                ts_information: None,
+                remote_control_key: None,
+                satellite_delivery: None,
+                service_list: Vec::new(),
             };
             ts.parse_descriptors();
 
@@ -149,6 +228,13 @@ impl NitTable {
             .collect()
     }
 
+    /// Check if a service is carried on a 1seg (partial reception) layer.
+    pub fn is_one_seg_service(&self, service_id: u16) -> bool {
+        self.transport_streams
+            .iter()
+            .any(|ts| ts.partial_reception.contains(&service_id))
+    }
+
     /// Check if this is NIT actual (for current network).
     pub fn is_actual(&self) -> bool {
         // NIT actual has table_id 0x40, other has 0x41
@@ -219,12 +305,22 @@ mod tests {
                     original_network_id: 0x7FE0,
                     descriptors: vec![],
                     terrestrial_delivery: None,
+                    partial_reception: vec![],
+                    ts_information: None,
+                    remote_control_key: None,
+                    satellite_delivery: None,
+                    service_list: Vec::new(),
                 },
                 NitTransportStream {
                     transport_stream_id: 0x7FE2,
                     original_network_id: 0x7FE0,
                     descriptors: vec![],
                     terrestrial_delivery: None,
+                    partial_reception: vec![],
+                    ts_information: None,
+                    remote_control_key: None,
+                    satellite_delivery: None,
+                    service_list: Vec::new(),
                 },
             ],
         };
@@ -247,12 +343,22 @@ mod tests {
                     original_network_id: 0x7FE0,
                     descriptors: vec![],
                     terrestrial_delivery: None,
+                    partial_reception: vec![],
+                    ts_information: None,
+                    remote_control_key: None,
+                    satellite_delivery: None,
+                    service_list: Vec::new(),
                 },
                 NitTransportStream {
                     transport_stream_id: 0x7FE2,
                     original_network_id: 0x7FE0,
                     descriptors: vec![],
                     terrestrial_delivery: None,
+                    partial_reception: vec![],
+                    ts_information: None,
+                    remote_control_key: None,
+                    satellite_delivery: None,
+                    service_list: Vec::new(),
                 },
             ],
         };
@@ -260,4 +366,74 @@ mod tests {
         let tsids = nit.get_all_tsids();
         assert_eq!(tsids, vec![0x7FE1, 0x7FE2]);
     }
+
+    #[test]
+    fn test_nit_partial_reception() {
+        let mut ts = NitTransportStream {
+            transport_stream_id: 0x7FE1,
+            original_network_id: 0x7FE0,
+            // Partial reception descriptor: SIDs 0x0588, 0x0589
+            descriptors: vec![0xFB, 0x04, 0x05, 0x88, 0x05, 0x89],
+            terrestrial_delivery: None,
+            partial_reception: vec![],
+            ts_information: None,
+            remote_control_key: None,
+            satellite_delivery: None,
+            service_list: Vec::new(),
+        };
+        ts.parse_descriptors();
+        assert_eq!(ts.partial_reception, vec![0x0588, 0x0589]);
+
+        let nit = NitTable {
+            network_id: 0x7FE0,
+            transport_streams: vec![ts],
+            ..Default::default()
+        };
+        assert!(nit.is_one_seg_service(0x0588));
+        assert!(!nit.is_one_seg_service(0x0400));
+    }
+
+    #[test]
+    fn test_nit_ts_information() {
+        let mut ts = NitTransportStream {
+            transport_stream_id: 0x7FE1,
+            original_network_id: 0x7FE0,
+            // TS information descriptor: remote key 3, TS name "ＮＨＫ" as ARIB
+            // JIS X 0208 (6 bytes, length << 2)
+            descriptors: vec![0xCD, 0x08, 0x03, 0x18, 0x23, 0x4E, 0x23, 0x48, 0x23, 0x4B],
+            ..Default::default()
+        };
+        ts.parse_descriptors();
+        assert_eq!(ts.remote_control_key_id(), Some(3));
+        assert_eq!(ts.ts_name(), Some("ＮＨＫ"));
+
+        // Remote control key descriptor only
+        let mut ts = NitTransportStream {
+            descriptors: vec![0xDE, 0x01, 0x07],
+            ..Default::default()
+        };
+        ts.parse_descriptors();
+        assert_eq!(ts.remote_control_key_id(), Some(7));
+        assert_eq!(ts.ts_name(), None);
+
+        let ts = NitTransportStream::default();
+        assert_eq!(ts.remote_control_key_id(), None);
+        assert_eq!(ts.ts_name(), None);
+    }
+
+    #[test]
+    fn test_nit_satellite_delivery() {
+        let mut ts = NitTransportStream {
+            transport_stream_id: 0x4010,
+            original_network_id: 0x0004,
+            // Satellite delivery descriptor: 11.72748 GHz, 110.0E
+            descriptors: vec![0x43, 0x0B, 0x01, 0x17, 0x27, 0x48, 0x11, 0x00, 0x22, 0x02, 0x88, 0x60, 0x0F],
+            ..Default::default()
+        };
+        ts.parse_descriptors();
+        assert_eq!(ts.satellite_frequency(), Some(11_727_480));
+        assert_eq!(ts.satellite_delivery.as_ref().unwrap().orbital_position, 1100);
+
+        assert_eq!(NitTransportStream::default().satellite_frequency(), None);
+    }
 }
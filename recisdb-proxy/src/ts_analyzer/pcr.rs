@@ -0,0 +1,169 @@
+//! PCR-based transport bitrate measurement.
+//!
+//! The PCR (Program Clock Reference) is a 27MHz timestamp carried in the
+//! adaptation field of the PCR PID declared in the PMT. The number of bytes
+//! between two PCR samples divided by their clock delta gives the transport
+//! bitrate as the broadcaster muxed it, independent of network buffering.
+
+use super::packet::{AdaptationField, TS_PACKET_SIZE};
+
+/// PCR clock frequency (27MHz).
+pub const PCR_HZ: u64 = 27_000_000;
+
+/// PCR wraps at 2^33 * 300 (33-bit base times 300 extension ticks).
+const PCR_WRAP: u64 = (1 << 33) * 300;
+
+/// Largest PCR delta accepted as a valid sample (1 second).
+///
+/// ARIB requires a PCR at least every 100ms, so anything longer is a
+/// glitch or an unflagged discontinuity.
+const MAX_PCR_DELTA: u64 = PCR_HZ;
+
+/// Tracks PCR samples on one PID and derives the transport bitrate.
+#[derive(Debug, Clone, Default)]
+pub struct PcrTracker {
+    /// PID carrying the PCR.
+    pcr_pid: Option<u16>,
+    /// Last accepted PCR value.
+    last_pcr: Option<u64>,
+    /// Bytes seen since the last PCR sample.
+    bytes_since_pcr: u64,
+    /// Bitrate measured between the last two PCR samples.
+    bitrate_bps: Option<u64>,
+}
+
+impl PcrTracker {
+    /// Create a tracker with no PCR PID assigned yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// PID being tracked.
+    pub fn pcr_pid(&self) -> Option<u16> {
+        self.pcr_pid
+    }
+
+    /// Set the PCR PID to track. Changing the PID restarts the measurement.
+    pub fn set_pcr_pid(&mut self, pid: u16) {
+        if self.pcr_pid != Some(pid) {
+            *self = Self {
+                pcr_pid: Some(pid),
+                ..Self::default()
+            };
+        }
+    }
+
+    /// Account for one TS packet on any PID.
+    ///
+    /// Every packet counts toward the byte total; only packets on the
+    /// tracked PID with a PCR in their adaptation field produce a sample.
+    pub fn push_packet(&mut self, pid: u16, adaptation_field: Option<&AdaptationField>) {
+        if self.last_pcr.is_some() {
+            self.bytes_since_pcr += TS_PACKET_SIZE as u64;
+        }
+
+        if Some(pid) != self.pcr_pid {
+            return;
+        }
+        let Some(af) = adaptation_field else {
+            return;
+        };
+        let Some(pcr) = af.pcr else {
+            return;
+        };
+
+        if af.discontinuity {
+            self.restart(pcr);
+            return;
+        }
+
+        if let Some(last) = self.last_pcr {
+            let delta = (pcr + PCR_WRAP - last) % PCR_WRAP;
+            // A negative delta shows up as a huge wrapped value
+            if delta == 0 || delta > MAX_PCR_DELTA {
+                self.restart(pcr);
+                return;
+            }
+            self.bitrate_bps = Some(self.bytes_since_pcr * 8 * PCR_HZ / delta);
+        }
+
+        self.last_pcr = Some(pcr);
+        self.bytes_since_pcr = 0;
+    }
+
+    /// Bitrate between the last two valid PCR samples, in bits per second.
+    pub fn bitrate_bps(&self) -> Option<u64> {
+        self.bitrate_bps
+    }
+
+    /// Drop the baseline and start measuring from `pcr`.
+    fn restart(&mut self, pcr: u64) {
+        self.last_pcr = Some(pcr);
+        self.bytes_since_pcr = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pcr_af(pcr: u64, discontinuity: bool) -> AdaptationField {
+        AdaptationField {
+            pcr_flag: true,
+            pcr: Some(pcr),
+            discontinuity,
+            ..Default::default()
+        }
+    }
+
+    /// Push `packets` packets on another PID, then one PCR packet.
+    fn feed(tracker: &mut PcrTracker, packets: u64, pcr: u64, discontinuity: bool) {
+        for _ in 0..packets {
+            tracker.push_packet(0x0111, None);
+        }
+        tracker.push_packet(0x0100, Some(&pcr_af(pcr, discontinuity)));
+    }
+
+    #[test]
+    fn test_pcr_bitrate() {
+        let mut tracker = PcrTracker::new();
+        tracker.set_pcr_pid(0x0100);
+        feed(&mut tracker, 0, 1_000_000, false);
+        assert_eq!(tracker.bitrate_bps(), None);
+
+        // 1000 packets (including the PCR packet) over 50ms
+        feed(&mut tracker, 999, 1_000_000 + PCR_HZ / 20, false);
+        assert_eq!(tracker.bitrate_bps(), Some(1000 * 188 * 8 * 20));
+    }
+
+    #[test]
+    fn test_pcr_wraparound() {
+        let mut tracker = PcrTracker::new();
+        tracker.set_pcr_pid(0x0100);
+        feed(&mut tracker, 0, PCR_WRAP - PCR_HZ / 40, false);
+        feed(&mut tracker, 99, PCR_HZ / 40, false);
+        assert_eq!(tracker.bitrate_bps(), Some(100 * 188 * 8 * 20));
+    }
+
+    #[test]
+    fn test_pcr_rejects_bad_samples() {
+        let mut tracker = PcrTracker::new();
+        tracker.set_pcr_pid(0x0100);
+        feed(&mut tracker, 0, 10 * PCR_HZ, false);
+
+        // Backwards jump is ignored and becomes the new baseline
+        feed(&mut tracker, 99, 5 * PCR_HZ, false);
+        assert_eq!(tracker.bitrate_bps(), None);
+
+        // Absurdly large jump is ignored
+        feed(&mut tracker, 99, 50 * PCR_HZ, false);
+        assert_eq!(tracker.bitrate_bps(), None);
+
+        // Flagged discontinuity restarts without a sample
+        feed(&mut tracker, 99, 3 * PCR_HZ, true);
+        assert_eq!(tracker.bitrate_bps(), None);
+
+        feed(&mut tracker, 99, 3 * PCR_HZ + PCR_HZ / 20, false);
+        assert_eq!(tracker.bitrate_bps(), Some(100 * 188 * 8 * 20));
+    }
+}
@@ -3,8 +3,9 @@
 //! The PMT contains information about a specific program/service,
 //! including the PIDs of its elementary streams (video, audio, etc.).
 
+use super::descriptors::{find_descriptor, parse_ca_descriptors};
 use super::psi::PsiSection;
-use super::table_id;
+use super::{descriptor_tag, table_id};
 
 /// Stream type constants.
 pub mod stream_type {
@@ -28,6 +29,18 @@ pub mod stream_type {
     pub const AAC_AUDIO: u8 = 0x0F;
     /// AAC Audio (LATM).
     pub const AAC_LATM: u8 = 0x11;
+    /// DSM-CC sections (ISDB data broadcasting carousel).
+    pub const DSMCC_SECTIONS: u8 = 0x0D;
+}
+
+/// ISDB data component IDs (data component descriptor, 0xFD).
+pub mod data_component_id {
+    /// ARIB caption / superimpose (STD-B24).
+    pub const ARIB_CAPTION: u16 = 0x0008;
+    /// BML data broadcasting.
+    pub const BML: u16 = 0x000C;
+    /// BML data broadcasting for 1seg.
+    pub const BML_ONE_SEG: u16 = 0x000D;
 }
 
 /// A single elementary stream entry in the PMT.
@@ -64,6 +77,78 @@ impl PmtStream {
         )
     }
 
+    /// Get the component tag from the stream identifier descriptor.
+    pub fn component_tag(&self) -> Option<u8> {
+        find_descriptor(&self.descriptors, descriptor_tag::STREAM_IDENTIFIER)
+            .and_then(|d| d.first().copied())
+    }
+
+    /// Get the data component ID from the data component descriptor.
+    pub fn data_component_id(&self) -> Option<u16> {
+        find_descriptor(&self.descriptors, descriptor_tag::DATA_COMPONENT)
+            .filter(|d| d.len() >= 2)
+            .map(|d| ((d[0] as u16) << 8) | d[1] as u16)
+    }
+
+    /// Check if this is a 1seg (partial reception) component.
+    ///
+    /// ARIB TR-B14 reserves component tags 0x80-0x8F for the 1seg layer.
+    pub fn is_one_seg(&self) -> bool {
+        matches!(self.component_tag(), Some(0x80..=0x8F))
+    }
+
+    /// Check if this is an ARIB caption stream.
+    pub fn is_caption(&self) -> bool {
+        self.arib_text_kind() == Some(AribTextKind::Caption)
+    }
+
+    /// Check if this is an ARIB superimpose (teletext-style overlay) stream.
+    pub fn is_superimpose(&self) -> bool {
+        self.arib_text_kind() == Some(AribTextKind::Superimpose)
+    }
+
+    /// Classify a PES private data stream as caption or superimpose.
+    ///
+    /// Both use data_component_id 0x0008; the component tag tells them apart
+    /// (0x30-0x37 / 0x87 caption, 0x38-0x3F / 0x88 superimpose).
+    fn arib_text_kind(&self) -> Option<AribTextKind> {
+        if self.stream_type != stream_type::PES_PRIVATE_DATA {
+            return None;
+        }
+        if let Some(id) = self.data_component_id() {
+            if id != data_component_id::ARIB_CAPTION {
+                return None;
+            }
+        }
+        match self.component_tag()? {
+            0x30..=0x37 | 0x87 => Some(AribTextKind::Caption),
+            0x38..=0x3F | 0x88 => Some(AribTextKind::Superimpose),
+            _ => None,
+        }
+    }
+
+    /// Get the codec name, using ISDB descriptors to refine private streams.
+    pub fn codec_name(&self) -> &'static str {
+        match self.stream_type {
+            stream_type::MPEG2_VIDEO => "MPEG-2 Video",
+            stream_type::H264_VIDEO => "H.264",
+            stream_type::H265_VIDEO => "HEVC",
+            stream_type::AAC_AUDIO | stream_type::AAC_LATM => "AAC",
+            stream_type::PES_PRIVATE_DATA => match self.arib_text_kind() {
+                Some(AribTextKind::Caption) => "ARIB Caption",
+                Some(AribTextKind::Superimpose) => "ARIB Superimpose",
+                None => "PES Private Data",
+            },
+            stream_type::DSMCC_SECTIONS => match self.data_component_id() {
+                Some(data_component_id::BML) | Some(data_component_id::BML_ONE_SEG) => {
+                    "BML Data Broadcasting"
+                }
+                _ => "DSM-CC Data Carousel",
+            },
+            _ => self.stream_type_name(),
+        }
+    }
+
     /// Get a human-readable stream type name.
     pub fn stream_type_name(&self) -> &'static str {
         match self.stream_type {
@@ -77,11 +162,19 @@ impl PmtStream {
             stream_type::H265_VIDEO => "H.265/HEVC Video",
             stream_type::AAC_AUDIO => "AAC Audio (ADTS)",
             stream_type::AAC_LATM => "AAC Audio (LATM)",
+            stream_type::DSMCC_SECTIONS => "DSM-CC Sections",
             _ => "Unknown",
         }
     }
 }
 
+/// Kind of ARIB text stream carried as PES private data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AribTextKind {
+    Caption,
+    Superimpose,
+}
+
 /// Parsed PMT (Program Map Table).
 #[derive(Debug, Clone, Default)]
 pub struct PmtTable {
@@ -174,6 +267,23 @@ impl PmtTable {
     pub fn get_all_pids(&self) -> Vec<u16> {
         self.streams.iter().map(|s| s.elementary_pid).collect()
     }
+
+    /// Get ECM PIDs as `(ca_system_id, ca_pid)` pairs from the program-level
+    /// and per-stream CA descriptors, without duplicates.
+    pub fn get_ecm_pids(&self) -> Vec<(u16, u16)> {
+        let mut pids = Vec::new();
+        let loops = std::iter::once(&self.program_info)
+            .chain(self.streams.iter().map(|s| &s.descriptors));
+        for descriptors in loops {
+            for ca in parse_ca_descriptors(descriptors) {
+                let entry = (ca.ca_system_id, ca.ca_pid);
+                if !pids.contains(&entry) {
+                    pids.push(entry);
+                }
+            }
+        }
+        pids
+    }
 }
 
 #[cfg(test)]
@@ -256,5 +366,92 @@ mod tests {
         assert_eq!(pmt.get_video_pids(), vec![0x100]);
         assert_eq!(pmt.get_audio_pids(), vec![0x110, 0x111]);
         assert_eq!(pmt.get_all_pids(), vec![0x100, 0x110, 0x111]);
+        assert!(pmt.get_ecm_pids().is_empty());
+    }
+
+    fn stream(stream_type: u8, descriptors: Vec<u8>) -> PmtStream {
+        PmtStream {
+            stream_type,
+            elementary_pid: 0x130,
+            descriptors,
+        }
+    }
+
+    #[test]
+    fn test_codec_name() {
+        assert_eq!(stream(stream_type::MPEG2_VIDEO, vec![]).codec_name(), "MPEG-2 Video");
+        assert_eq!(stream(stream_type::H264_VIDEO, vec![]).codec_name(), "H.264");
+        assert_eq!(stream(stream_type::H265_VIDEO, vec![]).codec_name(), "HEVC");
+        assert_eq!(stream(stream_type::AAC_AUDIO, vec![]).codec_name(), "AAC");
+        assert_eq!(stream(0x99, vec![]).codec_name(), "Unknown");
+
+        // Caption: component_tag 0x30, data_component_id 0x0008
+        let caption = stream(
+            stream_type::PES_PRIVATE_DATA,
+            vec![0x52, 0x01, 0x30, 0xFD, 0x03, 0x00, 0x08, 0x3D],
+        );
+        assert_eq!(caption.codec_name(), "ARIB Caption");
+        assert!(caption.is_caption());
+        assert!(!caption.is_superimpose());
+        assert!(!caption.is_one_seg());
+
+        // Superimpose: component_tag 0x38
+        let superimpose = stream(
+            stream_type::PES_PRIVATE_DATA,
+            vec![0x52, 0x01, 0x38, 0xFD, 0x03, 0x00, 0x08, 0x3C],
+        );
+        assert_eq!(superimpose.codec_name(), "ARIB Superimpose");
+        assert!(superimpose.is_superimpose());
+
+        // Caption tag range but a different data component is not a caption
+        let other = stream(
+            stream_type::PES_PRIVATE_DATA,
+            vec![0x52, 0x01, 0x30, 0xFD, 0x02, 0x00, 0x0C],
+        );
+        assert_eq!(other.codec_name(), "PES Private Data");
+        assert!(!other.is_caption());
+
+        let bml = stream(stream_type::DSMCC_SECTIONS, vec![0xFD, 0x02, 0x00, 0x0C]);
+        assert_eq!(bml.codec_name(), "BML Data Broadcasting");
+    }
+
+    #[test]
+    fn test_one_seg_components() {
+        let video = stream(stream_type::H264_VIDEO, vec![0x52, 0x01, 0x81]);
+        assert!(video.is_one_seg());
+        assert_eq!(video.component_tag(), Some(0x81));
+
+        let caption = stream(stream_type::PES_PRIVATE_DATA, vec![0x52, 0x01, 0x87]);
+        assert!(caption.is_one_seg());
+        assert!(caption.is_caption());
+
+        let full_seg = stream(stream_type::MPEG2_VIDEO, vec![0x52, 0x01, 0x00]);
+        assert!(!full_seg.is_one_seg());
+        assert!(!stream(stream_type::MPEG2_VIDEO, vec![]).is_one_seg());
+    }
+
+    #[test]
+    fn test_pmt_get_ecm_pids() {
+        let ca = vec![0x09, 0x04, 0x00, 0x05, 0xE0, 0x30];
+        let pmt = PmtTable {
+            program_number: 1,
+            version_number: 0,
+            pcr_pid: 0x100,
+            program_info: ca.clone(),
+            streams: vec![
+                PmtStream {
+                    stream_type: stream_type::H264_VIDEO,
+                    elementary_pid: 0x100,
+                    descriptors: ca,
+                },
+                PmtStream {
+                    stream_type: stream_type::PRIVATE_SECTIONS,
+                    elementary_pid: 0x130,
+                    descriptors: vec![0x09, 0x04, 0x00, 0x0A, 0xE0, 0x31],
+                },
+            ],
+        };
+
+        assert_eq!(pmt.get_ecm_pids(), vec![(0x0005, 0x0030), (0x000A, 0x0031)]);
     }
 }
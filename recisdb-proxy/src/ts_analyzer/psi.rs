@@ -206,6 +206,88 @@ impl SectionCollector {
         }
     }
 
+    /// Add data from a TS packet and return every section it completes.
+    ///
+    /// Unlike [`add_data`](Self::add_data), this also completes a section whose
+    /// tail precedes the pointer field and splits multiple sections packed into
+    /// one packet, as is common on the EIT PID.
+    pub fn push_packet(&mut self, payload: &[u8], cc: u8, payload_unit_start: bool) -> Vec<Vec<u8>> {
+        let mut sections = Vec::new();
+
+        if let Some(last) = self.last_cc {
+            if cc != (last + 1) & 0x0F {
+                // Discontinuity - any partial section is lost
+                self.buffer.clear();
+                self.expected_length = None;
+            }
+        }
+        self.last_cc = Some(cc);
+
+        if payload_unit_start {
+            let Some(&pointer) = payload.first() else {
+                return sections;
+            };
+            let section_start = 1 + pointer as usize;
+            if section_start > payload.len() {
+                self.buffer.clear();
+                self.expected_length = None;
+                return sections;
+            }
+
+            // Tail of the previous section
+            if !self.buffer.is_empty() {
+                self.buffer.extend_from_slice(&payload[1..section_start]);
+                self.drain_sections(&mut sections, false);
+            }
+
+            self.buffer.clear();
+            self.expected_length = None;
+            self.buffer.extend_from_slice(&payload[section_start..]);
+            self.drain_sections(&mut sections, true);
+        } else if !self.buffer.is_empty() {
+            self.buffer.extend_from_slice(payload);
+            // A new section can only start in a packet with payload_unit_start
+            self.drain_sections(&mut sections, false);
+        }
+
+        sections
+    }
+
+    /// Move complete sections from the buffer into `sections`.
+    fn drain_sections(&mut self, sections: &mut Vec<Vec<u8>>, allow_multiple: bool) {
+        loop {
+            if self.expected_length.is_none() && self.buffer.len() >= 3 {
+                // 0xFF table_id marks stuffing after the last section
+                if self.buffer[0] == 0xFF {
+                    self.buffer.clear();
+                    return;
+                }
+                let section_length =
+                    ((self.buffer[1] as usize & 0x0F) << 8) | self.buffer[2] as usize;
+                self.expected_length = Some(3 + section_length);
+            }
+
+            let Some(expected) = self.expected_length else {
+                if self.buffer.first() == Some(&0xFF) {
+                    self.buffer.clear();
+                }
+                return;
+            };
+            if self.buffer.len() < expected {
+                return;
+            }
+
+            sections.push(self.buffer[..expected].to_vec());
+            self.buffer.drain(..expected);
+            self.expected_length = None;
+
+            if !allow_multiple {
+                self.buffer.clear();
+                return;
+            }
+        }
+    }
+
     /// Get the collected section data.
     pub fn get_section(&self) -> Option<&[u8]> {
         self.expected_length
@@ -276,4 +358,71 @@ mod tests {
         let complete = collector.add_data(&payload, 0, true);
         assert!(complete);
     }
+
+    /// Build a long section with the given table_id and body length.
+    fn make_section(table_id: u8, body_len: usize) -> Vec<u8> {
+        let section_length = body_len + 5 + 4;
+        let mut s = vec![
+            table_id,
+            0xB0 | ((section_length >> 8) as u8 & 0x0F),
+            section_length as u8,
+            0x00, 0x01, 0xC1, 0x00, 0x00,
+        ];
+        s.extend(std::iter::repeat_n(0xAB, body_len));
+        let crc = crc32_mpeg2(&s);
+        s.extend_from_slice(&crc.to_be_bytes());
+        s
+    }
+
+    #[test]
+    fn test_push_packet_multiple_sections_in_one_packet() {
+        let a = make_section(0x4E, 20);
+        let b = make_section(0x4E, 30);
+
+        let mut payload = vec![0u8];
+        payload.extend_from_slice(&a);
+        payload.extend_from_slice(&b);
+        payload.resize(184, 0xFF);
+
+        let mut collector = SectionCollector::new();
+        let sections = collector.push_packet(&payload, 0, true);
+        assert_eq!(sections, vec![a, b]);
+        assert!(collector.is_empty());
+    }
+
+    #[test]
+    fn test_push_packet_spanning_section_with_pointer_tail() {
+        let long = make_section(0x50, 250);
+        let next = make_section(0x4E, 10);
+
+        // Packet 1: start of the long section
+        let mut p1 = vec![0u8];
+        p1.extend_from_slice(&long[..183]);
+
+        // Packet 2: tail of the long section before the pointer, then a new section
+        let tail = &long[183..];
+        let mut p2 = vec![tail.len() as u8];
+        p2.extend_from_slice(tail);
+        p2.extend_from_slice(&next);
+        p2.resize(184, 0xFF);
+
+        let mut collector = SectionCollector::new();
+        assert!(collector.push_packet(&p1, 5, true).is_empty());
+        let sections = collector.push_packet(&p2, 6, true);
+        assert_eq!(sections, vec![long, next]);
+    }
+
+    #[test]
+    fn test_push_packet_discontinuity_drops_partial() {
+        let long = make_section(0x50, 250);
+
+        let mut p1 = vec![0u8];
+        p1.extend_from_slice(&long[..183]);
+
+        let mut collector = SectionCollector::new();
+        assert!(collector.push_packet(&p1, 0, true).is_empty());
+        // CC jumps from 0 to 2: the continuation must not complete a corrupt section
+        assert!(collector.push_packet(&long[183..], 2, false).is_empty());
+        assert!(collector.is_empty());
+    }
 }
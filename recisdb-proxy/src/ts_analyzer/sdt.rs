@@ -3,7 +3,9 @@
 //! The SDT is transmitted on PID 0x0011 and contains information about
 //! services (channels) in a transport stream.
 
-use super::descriptors::{find_descriptor, ServiceDescriptor};
+use super::descriptors::{
+    find_descriptor, LogoTransmissionDescriptor, RemoteControlKeyDescriptor, ServiceDescriptor,
+};
 use super::psi::PsiSection;
 use super::{descriptor_tag, table_id};
 
@@ -55,6 +57,25 @@ impl SdtService {
         self.service_descriptor.as_ref().map(|d| d.service_type)
     }
 
+    /// Parse the logo transmission descriptor, if present.
+    pub fn logo_transmission(&self) -> Option<LogoTransmissionDescriptor> {
+        find_descriptor(&self.descriptors, descriptor_tag::LOGO_TRANSMISSION)
+            .and_then(|data| LogoTransmissionDescriptor::parse(&data).ok())
+    }
+
+    /// Get the CDT logo ID (from the logo transmission descriptor).
+    pub fn get_logo_id(&self) -> Option<u16> {
+        self.logo_transmission().and_then(|d| d.logo_id)
+    }
+
+    /// Get the remote control key ID (from the remote control key descriptor).
+    pub fn get_remote_control_key(&self) -> Option<u8> {
+        find_descriptor(&self.descriptors, descriptor_tag::REMOTE_CONTROL_KEY)
+            .and_then(|data| RemoteControlKeyDescriptor::parse(&data).ok())
+            .map(|d| d.remote_control_key_id)
+            .filter(|&key| key != 0)
+    }
+
     /// Get running status name.
     pub fn running_status_name(&self) -> &'static str {
         match self.running_status {
@@ -276,6 +297,34 @@ mod tests {
         assert_eq!(sdt.get_service_name(0x0102), Some("Channel 2"));
     }
 
+    #[test]
+    fn test_sdt_service_logo_id() {
+        let service = SdtService {
+            service_id: 0x0101,
+            // Service descriptor followed by a CDT logo transmission descriptor
+            descriptors: vec![
+                0x48, 0x03, 0x01, 0x00, 0x00,
+                0xCF, 0x07, 0x01, 0xFE, 0x10, 0xF0, 0x02, 0x00, 0x10,
+            ],
+            ..Default::default()
+        };
+        assert_eq!(service.get_logo_id(), Some(0x010));
+        assert_eq!(service.logo_transmission().unwrap().logo_version, Some(0x002));
+
+        assert_eq!(SdtService::default().get_logo_id(), None);
+    }
+
+    #[test]
+    fn test_sdt_service_remote_control_key() {
+        let service = SdtService {
+            service_id: 0x0101,
+            descriptors: vec![0x48, 0x03, 0x01, 0x00, 0x00, 0xDE, 0x01, 0x05],
+            ..Default::default()
+        };
+        assert_eq!(service.get_remote_control_key(), Some(5));
+        assert_eq!(SdtService::default().get_remote_control_key(), None);
+    }
+
     #[test]
     fn test_sdt_get_all_service_ids() {
         let sdt = SdtTable {
@@ -0,0 +1,146 @@
+//! TDT (Time and Date Table) and TOT (Time Offset Table) parsing.
+//!
+//! Both tables are transmitted on PID 0x0014 and carry the broadcast
+//! wall-clock time. In ISDB (ARIB STD-B10) the time field is JST rather
+//! than UTC; the TOT additionally carries a local time offset descriptor.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, Utc};
+
+use super::descriptors::find_descriptor;
+use super::psi::crc32_mpeg2;
+use super::time::{parse_mjd_bcd, JST_OFFSET_SECS};
+use super::{descriptor_tag, table_id};
+
+/// Parsed TDT or TOT section.
+#[derive(Debug, Clone)]
+pub struct TimeTable {
+    /// Table ID (0x70 = TDT, 0x73 = TOT).
+    pub table_id: u8,
+    /// Broadcast time as transmitted (JST).
+    pub jst_time: NaiveDateTime,
+    /// Local time offset from JST in minutes (TOT only, 0 if absent).
+    pub local_time_offset_minutes: i32,
+}
+
+impl TimeTable {
+    /// Parse a TDT or TOT from raw section bytes (starting at table_id).
+    ///
+    /// The TDT has no CRC, so it cannot go through `PsiSection`; the TOT's
+    /// CRC is verified here.
+    pub fn parse(data: &[u8]) -> Result<Self, &'static str> {
+        if data.len() < 8 {
+            return Err("Time table too short");
+        }
+
+        let tid = data[0];
+        if tid != table_id::TDT && tid != table_id::TOT {
+            return Err("Not a TDT/TOT section");
+        }
+
+        let section_length = ((data[1] as usize & 0x0F) << 8) | data[2] as usize;
+        let total_length = 3 + section_length;
+        if data.len() < total_length || section_length < 5 {
+            return Err("Incomplete time table");
+        }
+
+        let jst_time = parse_mjd_bcd(&data[3..8]).ok_or("Invalid MJD/BCD time")?;
+
+        let mut local_time_offset_minutes = 0;
+        if tid == table_id::TOT {
+            if section_length < 11 {
+                return Err("TOT too short");
+            }
+            if crc32_mpeg2(&data[..total_length]) != 0 {
+                return Err("TOT CRC mismatch");
+            }
+
+            let loop_length = ((data[8] as usize & 0x0F) << 8) | data[9] as usize;
+            let loop_end = (10 + loop_length).min(total_length - 4);
+            if let Some(desc) =
+                find_descriptor(&data[10..loop_end], descriptor_tag::LOCAL_TIME_OFFSET)
+            {
+                local_time_offset_minutes = parse_local_time_offset(&desc).unwrap_or(0);
+            }
+        }
+
+        Ok(TimeTable {
+            table_id: tid,
+            jst_time,
+            local_time_offset_minutes,
+        })
+    }
+
+    /// Broadcast time converted to UTC.
+    pub fn utc_time(&self) -> DateTime<Utc> {
+        (self.jst_time - Duration::seconds(JST_OFFSET_SECS as i64)).and_utc()
+    }
+
+    /// Offset of the broadcast's local time from UTC (JST plus any TOT offset).
+    pub fn local_offset(&self) -> Option<FixedOffset> {
+        FixedOffset::east_opt(JST_OFFSET_SECS + self.local_time_offset_minutes * 60)
+    }
+}
+
+/// Parse the first entry of a local time offset descriptor (0x58) into minutes.
+fn parse_local_time_offset(data: &[u8]) -> Option<i32> {
+    if data.len() < 6 {
+        return None;
+    }
+    let negative = data[3] & 0x01 != 0;
+    let hh = bcd(data[4])?;
+    let mm = bcd(data[5])?;
+    let minutes = hh * 60 + mm;
+    Some(if negative { -minutes } else { minutes })
+}
+
+fn bcd(b: u8) -> Option<i32> {
+    let (hi, lo) = ((b >> 4) as i32, (b & 0x0F) as i32);
+    (hi <= 9 && lo <= 9).then_some(hi * 10 + lo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tdt() {
+        // TDT: 1993-10-13 21:45:00 JST
+        let data = [0x70, 0x70, 0x05, 0xC0, 0x79, 0x21, 0x45, 0x00];
+        let tdt = TimeTable::parse(&data).unwrap();
+        assert_eq!(tdt.jst_time.to_string(), "1993-10-13 21:45:00");
+        assert_eq!(tdt.utc_time().to_rfc3339(), "1993-10-13T12:45:00+00:00");
+        assert_eq!(tdt.local_offset().unwrap().local_minus_utc(), 9 * 3600);
+    }
+
+    #[test]
+    fn test_parse_tot_with_offset() {
+        let mut data = vec![
+            0x73, 0x70, 0x00, // section_length filled below
+            0xC0, 0x79, 0x21, 0x45, 0x00,
+            0xF0, 0x0F, // descriptors_loop_length = 15
+            0x58, 0x0D, b'J', b'P', b'N', 0x02, 0x01, 0x00, // +01:00
+            0xC0, 0x79, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        data[2] = (data.len() - 3 + 4) as u8;
+        let crc = crc32_mpeg2(&data);
+        data.extend_from_slice(&crc.to_be_bytes());
+
+        let tot = TimeTable::parse(&data).unwrap();
+        assert_eq!(tot.table_id, table_id::TOT);
+        assert_eq!(tot.local_time_offset_minutes, 60);
+        assert_eq!(tot.local_offset().unwrap().local_minus_utc(), 10 * 3600);
+
+        // Corrupted CRC is rejected
+        let last = data.len() - 1;
+        data[last] ^= 0xFF;
+        assert!(TimeTable::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_parse_time_table_rejects_stuffing() {
+        assert!(TimeTable::parse(&[0x70, 0x70, 0x05, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]).is_err());
+        assert!(TimeTable::parse(&[0x70, 0x70, 0x05, 0xC0, 0x79, 0xFF, 0x45, 0x00]).is_err());
+        assert!(TimeTable::parse(&[0x70, 0x70, 0x05, 0xC0]).is_err());
+        assert!(TimeTable::parse(&[0x42, 0x70, 0x05, 0xC0, 0x79, 0x21, 0x45, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! MJD/BCD time field decoding for SI tables (EIT, TDT, TOT).
+//!
+//! ARIB SI encodes dates as a 16-bit Modified Julian Date followed by
+//! BCD-coded hours, minutes and seconds. Fields set to all 0xFF mean
+//! "undefined" and decode to `None`, as does any invalid BCD digit.
+
+use chrono::{Days, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// JST offset from UTC in seconds.
+pub(crate) const JST_OFFSET_SECS: i32 = 9 * 3600;
+
+/// Decode one BCD byte (two decimal digits).
+fn bcd_byte(b: u8) -> Option<u32> {
+    let hi = (b >> 4) as u32;
+    let lo = (b & 0x0F) as u32;
+    if hi > 9 || lo > 9 {
+        return None;
+    }
+    Some(hi * 10 + lo)
+}
+
+/// Decode 24-bit BCD hh:mm:ss into seconds.
+fn bcd_hms(data: &[u8]) -> Option<(u32, u32, u32)> {
+    if data.len() < 3 {
+        return None;
+    }
+    Some((bcd_byte(data[0])?, bcd_byte(data[1])?, bcd_byte(data[2])?))
+}
+
+/// Decode a 40-bit MJD + BCD time (5 bytes).
+///
+/// The result carries no time zone. ARIB broadcasts JST in both EIT and
+/// TDT/TOT, so callers convert when they need UTC.
+pub fn parse_mjd_bcd(data: &[u8]) -> Option<NaiveDateTime> {
+    if data.len() < 5 || data[..5].iter().all(|&b| b == 0xFF) {
+        return None;
+    }
+
+    let mjd = ((data[0] as u64) << 8) | data[1] as u64;
+    let (h, m, s) = bcd_hms(&data[2..5])?;
+
+    let epoch = NaiveDate::from_ymd_opt(1858, 11, 17)?;
+    let date = epoch.checked_add_days(Days::new(mjd))?;
+    let time = NaiveTime::from_hms_opt(h, m, s)?;
+    Some(date.and_time(time))
+}
+
+/// Convert a broadcast (JST) time to a unix timestamp.
+pub fn jst_to_unix(jst: NaiveDateTime) -> i64 {
+    jst.and_utc().timestamp() - JST_OFFSET_SECS as i64
+}
+
+/// Decode a 24-bit BCD duration (hh:mm:ss) into seconds.
+pub fn parse_bcd_duration(data: &[u8]) -> Option<u32> {
+    if data.len() < 3 || data[..3].iter().all(|&b| b == 0xFF) {
+        return None;
+    }
+    let (h, m, s) = bcd_hms(data)?;
+    if m > 59 || s > 59 {
+        return None;
+    }
+    Some(h * 3600 + m * 60 + s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mjd_bcd() {
+        // ETSI EN 300 468 example: 93/10/13 12:45:00 = 0xC079124500
+        let t = parse_mjd_bcd(&[0xC0, 0x79, 0x12, 0x45, 0x00]).unwrap();
+        assert_eq!(t.to_string(), "1993-10-13 12:45:00");
+    }
+
+    #[test]
+    fn test_parse_mjd_bcd_rejects_undefined_and_bad_bcd() {
+        assert_eq!(parse_mjd_bcd(&[0xFF; 5]), None);
+        assert_eq!(parse_mjd_bcd(&[0xC0, 0x79, 0x1A, 0x45, 0x00]), None);
+        assert_eq!(parse_mjd_bcd(&[0xC0, 0x79, 0x25, 0x00, 0x00]), None);
+        assert_eq!(parse_mjd_bcd(&[0xC0, 0x79]), None);
+    }
+
+    #[test]
+    fn test_jst_to_unix() {
+        // 2023-11-15 07:13:20 JST = 2023-11-14 22:13:20 UTC
+        let t = parse_mjd_bcd(&[0xEB, 0x67, 0x07, 0x13, 0x20]).unwrap();
+        assert_eq!(t.to_string(), "2023-11-15 07:13:20");
+        assert_eq!(jst_to_unix(t), 1_700_000_000);
+    }
+
+    #[test]
+    fn test_parse_bcd_duration() {
+        assert_eq!(parse_bcd_duration(&[0x01, 0x45, 0x30]), Some(3600 + 45 * 60 + 30));
+        assert_eq!(parse_bcd_duration(&[0xFF, 0xFF, 0xFF]), None);
+        assert_eq!(parse_bcd_duration(&[0x00, 0x6A, 0x00]), None);
+    }
+}
@@ -1,19 +1,22 @@
 // src/tuner/b25_pipe.rs
 use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
 
 use b25_sys::{DecoderOptions, StreamDecoder};
 use log::{debug, error, info, warn};
 
+use crate::tuner::ts_analyzer::SyncLossLogThrottle;
+
 const TS_PACKET_SIZE: usize = 188;
 const TS_SYNC_BYTE: u8 = 0x47;
 
 /// How many consecutive packets to check when re-synchronizing TS.
 /// Larger value reduces false positives but requires more buffered data.
-const RESYNC_CHECK_PACKETS: usize = 5;
+pub(crate) const RESYNC_CHECK_PACKETS: usize = 5;
 
 /// Find an offset (0..TS_PACKET_SIZE-1) such that packets appear aligned:
 /// buf[offset + k*188] == 0x47 for k in 0..RESYNC_CHECK_PACKETS.
-fn find_ts_sync_offset(buf: &[u8]) -> Option<usize> {
+pub(crate) fn find_ts_sync_offset(buf: &[u8]) -> Option<usize> {
     let need = TS_PACKET_SIZE * RESYNC_CHECK_PACKETS;
     if buf.len() < need {
         return None;
@@ -37,28 +40,189 @@ fn find_ts_sync_offset(buf: &[u8]) -> Option<usize> {
     None
 }
 
+/// Maximum backoff multiplier for the re-key window after unsuccessful re-keys.
+const MAX_REKEY_BACKOFF: u32 = 8;
+
+/// When to re-initialize the decoder because descrambling appears stuck.
+#[derive(Debug, Clone, Copy)]
+pub struct RekeyPolicy {
+    /// Scrambled-packet ratio (0.0-1.0) in the decoded output that counts as a failure.
+    pub scrambled_ratio: f64,
+    /// How long the ratio must stay above the threshold before re-keying.
+    pub window: Duration,
+}
+
+/// Tracks the scrambled ratio of decoded output over a time window.
+#[derive(Debug)]
+struct DescrambleWatchdog {
+    policy: RekeyPolicy,
+    window_start: Instant,
+    packets: u64,
+    scrambled: u64,
+    /// Window multiplier, doubled after each re-key that didn't help.
+    backoff: u32,
+}
+
+impl DescrambleWatchdog {
+    fn new(policy: RekeyPolicy) -> Self {
+        Self {
+            policy,
+            window_start: Instant::now(),
+            packets: 0,
+            scrambled: 0,
+            backoff: 1,
+        }
+    }
+
+    /// Count output packets; returns true when a re-key should be triggered.
+    fn observe(&mut self, data: &[u8], now: Instant) -> bool {
+        for packet in data.chunks_exact(TS_PACKET_SIZE) {
+            if packet[0] != TS_SYNC_BYTE {
+                continue;
+            }
+            self.packets += 1;
+            if (packet[3] >> 6) & 0x03 != 0 {
+                self.scrambled += 1;
+            }
+        }
+
+        if now.duration_since(self.window_start) < self.policy.window * self.backoff {
+            return false;
+        }
+
+        let failing = self.packets > 0
+            && self.scrambled as f64 / self.packets as f64 >= self.policy.scrambled_ratio;
+        if failing {
+            self.backoff = (self.backoff * 2).min(MAX_REKEY_BACKOFF);
+        } else {
+            self.backoff = 1;
+        }
+        self.window_start = now;
+        self.packets = 0;
+        self.scrambled = 0;
+        failing
+    }
+}
+
+/// Decoder options used for live tuner streams.
+pub(crate) fn stream_decoder_options() -> DecoderOptions {
+    DecoderOptions {
+        strip: true,
+        emm: true,
+        simd: true,
+        round: 4,
+        enable_working_key: false,
+    }
+}
+
+/// Copy decoder options (`DecoderOptions` is not `Clone`).
+fn copy_options(opt: &DecoderOptions) -> DecoderOptions {
+    DecoderOptions {
+        enable_working_key: opt.enable_working_key,
+        round: opt.round,
+        strip: opt.strip,
+        emm: opt.emm,
+        simd: opt.simd,
+    }
+}
+
 pub struct B25Pipe {
     dec: StreamDecoder,
+    /// Options the decoder was created with (used for re-keying).
+    opt: DecoderOptions,
     stash: Vec<u8>,
     tmp: Vec<u8>,
+    /// Total number of resyncs performed (exact, not throttled).
+    resync_count: u64,
+    /// Total number of bytes dropped while resyncing.
+    resync_bytes_dropped: u64,
+    resync_log: SyncLossLogThrottle,
+    /// Descramble-failure detection (None = disabled).
+    watchdog: Option<DescrambleWatchdog>,
+    /// Total number of re-keys triggered by sustained descramble failure.
+    rekey_count: u64,
 }
 
 impl B25Pipe {
     pub fn new(opt: DecoderOptions) -> io::Result<Self> {
         Ok(Self {
-            dec: StreamDecoder::new(opt)?,
+            dec: StreamDecoder::new(copy_options(&opt))?,
+            opt,
             stash: Vec::with_capacity(TS_PACKET_SIZE * 32),
             // Output buffer for decoder drain. Larger buffer to handle bursts.
             // B25 decoder can output more than 262KB in one session, so use 1MB.
             tmp: vec![0u8; 1024 * 1024],  // 1MB buffer
+            resync_count: 0,
+            resync_bytes_dropped: 0,
+            resync_log: SyncLossLogThrottle::default(),
+            watchdog: None,
+            rekey_count: 0,
         })
     }
 
+    /// Enable (or disable with `None`) re-keying on sustained descramble failure.
+    pub fn set_rekey_policy(&mut self, policy: Option<RekeyPolicy>) {
+        self.watchdog = policy.map(DescrambleWatchdog::new);
+    }
+
+    /// Total number of re-keys triggered since this pipe was created.
+    pub fn rekey_count(&self) -> u64 {
+        self.rekey_count
+    }
+
     pub fn reset(&mut self, opt: DecoderOptions) -> io::Result<()> {
-        *self = Self::new(opt)?;
+        self.replace_decoder(opt)
+    }
+
+    /// Total number of TS resyncs since this pipe was created.
+    pub fn resync_count(&self) -> u64 {
+        self.resync_count
+    }
+
+    /// Total number of bytes dropped by TS resyncs.
+    pub fn resync_bytes_dropped(&self) -> u64 {
+        self.resync_bytes_dropped
+    }
+
+    /// Recreate the decoder while keeping counters, log throttle and watchdog state.
+    fn replace_decoder(&mut self, opt: DecoderOptions) -> io::Result<()> {
+        let mut fresh = Self::new(opt)?;
+        fresh.resync_count = self.resync_count;
+        fresh.resync_bytes_dropped = self.resync_bytes_dropped;
+        fresh.rekey_count = self.rekey_count;
+        std::mem::swap(&mut fresh.resync_log, &mut self.resync_log);
+        std::mem::swap(&mut fresh.watchdog, &mut self.watchdog);
+        *self = fresh;
         Ok(())
     }
 
+    /// Re-initialize the CAS/key state after sustained descramble failure.
+    fn rekey(&mut self) {
+        self.rekey_count += 1;
+        warn!(
+            "[B25Pipe] Descrambling appears stuck; re-initializing decoder (attempt {})",
+            self.rekey_count
+        );
+        if let Err(e) = self.replace_decoder(copy_options(&self.opt)) {
+            error!("[B25Pipe] Failed to re-initialize decoder: {}", e);
+        }
+    }
+
+    /// Count a resync and log it, rate-limited by the sync-loss log interval.
+    fn note_resync(&mut self, dropped: usize) {
+        self.resync_count += 1;
+        self.resync_bytes_dropped += dropped as u64;
+        if let Some(summary) = self.resync_log.record(dropped as u64) {
+            warn!(
+                "[B25Pipe] Resync TS: {} resyncs dropped {} bytes in {:.1}s (total resyncs={})",
+                summary.events,
+                summary.bytes_dropped,
+                summary.elapsed.as_secs_f64(),
+                self.resync_count
+            );
+        }
+    }
+
     /// 任意長の入力を入れて、復号済みTSを返す（なければ空Vec）
     pub fn push(&mut self, input: &[u8]) -> io::Result<Vec<u8>> {
         self.stash.extend_from_slice(input);
@@ -68,7 +232,7 @@ impl B25Pipe {
         // If the first byte isn't 0x47, try to find a sync phase and realign.
         if self.stash.first().copied() != Some(TS_SYNC_BYTE) {
             if let Some(off) = find_ts_sync_offset(&self.stash) {
-                warn!("[B25Pipe] Resync TS: dropping {} bytes", off);
+                self.note_resync(off);
                 self.stash.drain(..off);
             } else {
                 // Keep at most 187 bytes to allow sync across chunk boundary.
@@ -91,7 +255,7 @@ impl B25Pipe {
         // If misaligned slips through, try resync again once.
         if self.stash.get(0).copied() != Some(TS_SYNC_BYTE) {
             if let Some(off) = find_ts_sync_offset(&self.stash) {
-                warn!("[B25Pipe] Resync TS (2nd): dropping {} bytes", off);
+                self.note_resync(off);
                 self.stash.drain(..off);
             } else {
                 self.stash.clear();
@@ -161,6 +325,14 @@ impl B25Pipe {
             }
         }
 
+        let needs_rekey = self
+            .watchdog
+            .as_mut()
+            .is_some_and(|w| w.observe(&out, Instant::now()));
+        if needs_rekey {
+            self.rekey();
+        }
+
         Ok(out)
     }
 
@@ -224,7 +396,51 @@ impl B25Pipe {
                 // Continue with reset anyway
             }
         }
-        *self = Self::new(opt)?;
-        Ok(())
+        self.replace_decoder(opt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packets(count: usize, scrambled: bool) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(count * TS_PACKET_SIZE);
+        for _ in 0..count {
+            let mut packet = [0u8; TS_PACKET_SIZE];
+            packet[0] = TS_SYNC_BYTE;
+            if scrambled {
+                packet[3] = 0x80; // transport_scrambling_control = 10
+            }
+            buf.extend_from_slice(&packet);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_descramble_watchdog_triggers_after_window() {
+        let policy = RekeyPolicy { scrambled_ratio: 0.9, window: Duration::from_secs(10) };
+        let mut watchdog = DescrambleWatchdog::new(policy);
+        let start = watchdog.window_start;
+
+        // Within the window nothing triggers
+        assert!(!watchdog.observe(&packets(10, true), start + Duration::from_secs(5)));
+        // Sustained scrambled output past the window triggers a re-key
+        assert!(watchdog.observe(&packets(10, true), start + Duration::from_secs(10)));
+        // Next window is backed off (20s) after the failure
+        assert!(!watchdog.observe(&packets(10, true), start + Duration::from_secs(25)));
+        assert!(watchdog.observe(&packets(10, true), start + Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_descramble_watchdog_ignores_healthy_stream() {
+        let policy = RekeyPolicy { scrambled_ratio: 0.9, window: Duration::from_secs(10) };
+        let mut watchdog = DescrambleWatchdog::new(policy);
+        let start = watchdog.window_start;
+
+        let mut mixed = packets(5, true);
+        mixed.extend(packets(5, false));
+        assert!(!watchdog.observe(&mixed, start + Duration::from_secs(10)));
+        assert_eq!(watchdog.backoff, 1);
     }
 }
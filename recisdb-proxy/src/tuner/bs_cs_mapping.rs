@@ -0,0 +1,234 @@
+//! NIT-based mapping of BS/CS transport streams to BonDriver channels.
+//!
+//! Satellite BonDrivers list one channel per (transponder, relative TS)
+//! pair, named like `BS01/TS0`, `BS15_1` or `ND02`. The NIT satellite
+//! delivery descriptor gives the transponder frequency of every TS and the
+//! low 3 bits of a BS TSID are its relative TS number, so each (nid, tsid)
+//! resolves to exactly one channel of a driver's list. Scanning uses this
+//! instead of trusting the channel that happened to be tuned, which on some
+//! tuners (PX-S) can still be delivering the previous transponder.
+
+use std::collections::HashMap;
+
+use recisdb_protocol::BandType;
+
+/// Frequency of BS-1 in kHz.
+const BS_BASE_KHZ: u32 = 11_727_480;
+/// Spacing between BS channel numbers in kHz (odd channels only are used).
+const BS_STEP_KHZ: u32 = 19_180;
+/// Frequency of ND2 (110°CS) in kHz.
+const CS_BASE_KHZ: u32 = 12_291_000;
+/// Spacing between ND channel numbers in kHz (even channels only are used).
+const CS_STEP_KHZ: u32 = 20_000;
+/// Largest accepted offset from a nominal transponder frequency.
+const FREQUENCY_TOLERANCE_KHZ: u32 = 5_000;
+
+/// Satellite band of a transport stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SatelliteBand {
+    /// BS (transponders BS-1 to BS-23, odd).
+    Bs,
+    /// 110°CS (transponders ND2 to ND24, even).
+    Cs,
+}
+
+/// Physical location of a satellite transport stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SatelliteTs {
+    pub band: SatelliteBand,
+    /// BS channel or ND number.
+    pub transponder: u8,
+    /// Relative TS number within the transponder (always 0 for CS).
+    pub relative_ts: u8,
+}
+
+impl SatelliteTs {
+    /// Locate a TS from its NIT entry.
+    ///
+    /// Returns None for non-satellite networks and frequencies that are not
+    /// a known transponder.
+    pub fn from_nit(nid: u16, tsid: u16, frequency_khz: u32) -> Option<Self> {
+        let band = match BandType::from_nid(nid) {
+            BandType::BS => SatelliteBand::Bs,
+            BandType::CS => SatelliteBand::Cs,
+            _ => return None,
+        };
+        let transponder = transponder_from_frequency(band, frequency_khz)?;
+        let relative_ts = match band {
+            SatelliteBand::Bs => (tsid & 0x07) as u8,
+            SatelliteBand::Cs => 0,
+        };
+        Some(Self { band, transponder, relative_ts })
+    }
+
+    /// Locate a TS from a BonDriver channel name such as `BS01/TS0`,
+    /// `BS15_1`, `ND02` or `CS4`.
+    pub fn from_channel_name(name: &str) -> Option<Self> {
+        let upper: String = name
+            .chars()
+            .map(|c| match c {
+                '！'..='～' => char::from_u32(c as u32 - '！' as u32 + '!' as u32).unwrap_or(c),
+                _ => c,
+            })
+            .collect::<String>()
+            .to_ascii_uppercase();
+
+        let (band, rest) = if let Some(pos) = upper.find("ND") {
+            (SatelliteBand::Cs, &upper[pos + 2..])
+        } else if let Some(pos) = upper.find("BS") {
+            (SatelliteBand::Bs, &upper[pos + 2..])
+        } else if let Some(pos) = upper.find("CS") {
+            (SatelliteBand::Cs, &upper[pos + 2..])
+        } else {
+            return None;
+        };
+
+        let rest = rest.trim_start_matches([' ', '-', '_']);
+        let (transponder, rest) = leading_number(rest)?;
+        let relative_ts = match band {
+            SatelliteBand::Bs => {
+                let after = rest.trim_start_matches(|c: char| !c.is_ascii_digit());
+                leading_number(after).map(|(n, _)| n).unwrap_or(0)
+            }
+            SatelliteBand::Cs => 0,
+        };
+
+        let valid = match band {
+            SatelliteBand::Bs => transponder % 2 == 1 && transponder <= 23 && relative_ts < 8,
+            SatelliteBand::Cs => transponder % 2 == 0 && (2..=24).contains(&transponder),
+        };
+        valid.then_some(Self { band, transponder, relative_ts })
+    }
+}
+
+/// Transponder number of a satellite frequency.
+pub fn transponder_from_frequency(band: SatelliteBand, frequency_khz: u32) -> Option<u8> {
+    let (base, step, first, last) = match band {
+        SatelliteBand::Bs => (BS_BASE_KHZ, BS_STEP_KHZ, 1, 23),
+        SatelliteBand::Cs => (CS_BASE_KHZ, CS_STEP_KHZ, 2, 24),
+    };
+    let delta = frequency_khz as i64 - base as i64;
+    let steps = (delta as f64 / step as f64).round() as i64;
+    if steps < 0 || (delta - steps * step as i64).unsigned_abs() > FREQUENCY_TOLERANCE_KHZ as u64 {
+        return None;
+    }
+    let transponder = first + steps;
+    (transponder <= last && transponder % 2 == first % 2).then_some(transponder as u8)
+}
+
+/// Satellite channels of one BonDriver, keyed by physical location.
+#[derive(Debug, Clone, Default)]
+pub struct SatelliteChannelMap {
+    channels: HashMap<SatelliteTs, (u32, u32)>,
+}
+
+impl SatelliteChannelMap {
+    /// Build the map from a driver's enumerated `(space, channel, name)` list.
+    ///
+    /// When a location is listed more than once, the first entry wins.
+    pub fn new<'a>(channels: impl IntoIterator<Item = (u32, u32, &'a str)>) -> Self {
+        let mut map = HashMap::new();
+        for (space, channel, name) in channels {
+            if let Some(ts) = SatelliteTs::from_channel_name(name) {
+                map.entry(ts).or_insert((space, channel));
+            }
+        }
+        Self { channels: map }
+    }
+
+    /// `(space, channel)` of the driver that carries `ts`.
+    pub fn locate(&self, ts: &SatelliteTs) -> Option<(u32, u32)> {
+        self.channels.get(ts).copied()
+    }
+
+    /// Whether none of the driver's channel names was recognized.
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+}
+
+/// Split a leading decimal number (at most 3 digits) off `s`.
+fn leading_number(s: &str) -> Option<(u8, &str)> {
+    let len = s.chars().take_while(|c| c.is_ascii_digit()).count();
+    if len == 0 || len > 3 {
+        return None;
+    }
+    let number = s[..len].parse().ok()?;
+    Some((number, &s[len..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bs(transponder: u8, relative_ts: u8) -> SatelliteTs {
+        SatelliteTs { band: SatelliteBand::Bs, transponder, relative_ts }
+    }
+
+    fn cs(transponder: u8) -> SatelliteTs {
+        SatelliteTs { band: SatelliteBand::Cs, transponder, relative_ts: 0 }
+    }
+
+    #[test]
+    fn test_transponder_from_frequency() {
+        assert_eq!(transponder_from_frequency(SatelliteBand::Bs, 11_727_480), Some(1));
+        assert_eq!(transponder_from_frequency(SatelliteBand::Bs, 11_996_000), Some(15));
+        assert_eq!(transponder_from_frequency(SatelliteBand::Bs, 12_149_440), Some(23));
+        // Even BS channels are not used
+        assert_eq!(transponder_from_frequency(SatelliteBand::Bs, 11_746_660), None);
+        assert_eq!(transponder_from_frequency(SatelliteBand::Bs, 11_000_000), None);
+
+        assert_eq!(transponder_from_frequency(SatelliteBand::Cs, 12_291_000), Some(2));
+        assert_eq!(transponder_from_frequency(SatelliteBand::Cs, 12_731_000), Some(24));
+        assert_eq!(transponder_from_frequency(SatelliteBand::Cs, 12_311_000), None);
+    }
+
+    #[test]
+    fn test_from_nit() {
+        // BS-1 TS1
+        assert_eq!(SatelliteTs::from_nit(0x0004, 0x4011, 11_727_480), Some(bs(1, 1)));
+        // BS-15 TS1
+        assert_eq!(SatelliteTs::from_nit(0x0004, 0x40F1, 11_996_000), Some(bs(15, 1)));
+        assert_eq!(SatelliteTs::from_nit(0x0007, 0x7040, 12_331_000), Some(cs(4)));
+        // Terrestrial networks have no satellite location
+        assert_eq!(SatelliteTs::from_nit(0x7FE8, 0x7FE8, 11_727_480), None);
+    }
+
+    #[test]
+    fn test_from_channel_name() {
+        assert_eq!(SatelliteTs::from_channel_name("BS01/TS0"), Some(bs(1, 0)));
+        assert_eq!(SatelliteTs::from_channel_name("BS1/TS2"), Some(bs(1, 2)));
+        assert_eq!(SatelliteTs::from_channel_name("BS15_1"), Some(bs(15, 1)));
+        assert_eq!(SatelliteTs::from_channel_name("ＢＳ０３/ＴＳ１"), Some(bs(3, 1)));
+        assert_eq!(SatelliteTs::from_channel_name("BS-9"), Some(bs(9, 0)));
+        assert_eq!(SatelliteTs::from_channel_name("ND02"), Some(cs(2)));
+        assert_eq!(SatelliteTs::from_channel_name("CS110 ND24"), Some(cs(24)));
+        assert_eq!(SatelliteTs::from_channel_name("CS4"), Some(cs(4)));
+
+        assert_eq!(SatelliteTs::from_channel_name("BS02/TS0"), None);
+        assert_eq!(SatelliteTs::from_channel_name("BS朝日"), None);
+        assert_eq!(SatelliteTs::from_channel_name("13ch"), None);
+    }
+
+    #[test]
+    fn test_channel_map_locate() {
+        let names = [
+            (0, 0, "BS01/TS0"),
+            (0, 1, "BS01/TS1"),
+            (0, 2, "BS03/TS0"),
+            (1, 0, "ND02"),
+            (1, 1, "ND04"),
+            (2, 0, "BS01/TS0"),
+        ];
+        let map = SatelliteChannelMap::new(names.iter().map(|(s, c, n)| (*s, *c, *n)));
+        assert!(!map.is_empty());
+
+        let ts = SatelliteTs::from_nit(0x0004, 0x4011, 11_727_480).unwrap();
+        assert_eq!(map.locate(&ts), Some((0, 1)));
+        let ts = SatelliteTs::from_nit(0x0006, 0x6020, 12_291_000).unwrap();
+        assert_eq!(map.locate(&ts), Some((1, 0)));
+        // The first listing of a duplicated location wins
+        assert_eq!(map.locate(&bs(1, 0)), Some((0, 0)));
+        assert_eq!(map.locate(&bs(5, 0)), None);
+    }
+}
@@ -1,6 +1,8 @@
 //! Channel identification key for tuner sharing.
 
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 /// A unique key identifying a tuner/channel combination.
 ///
@@ -48,6 +50,43 @@ impl ChannelKey {
     }
 }
 
+/// `<tuner_path>|<space>|<channel>`, or `<tuner_path>|<channel>` for v1
+/// keys; used to name running tuners in the web API.
+impl fmt::Display for ChannelKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.channel {
+            ChannelKeySpec::Simple(channel) => write!(f, "{}|{}", self.tuner_path, channel),
+            ChannelKeySpec::SpaceChannel { space, channel } => {
+                write!(f, "{}|{}|{}", self.tuner_path, space, channel)
+            }
+        }
+    }
+}
+
+impl FromStr for ChannelKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid tuner key: {}", s);
+        let mut parts = s.rsplitn(3, '|');
+        let last = parts.next().ok_or_else(invalid)?;
+        let middle = parts.next().ok_or_else(invalid)?;
+        match parts.next() {
+            Some(path) if !path.is_empty() => {
+                let space = middle.parse().map_err(|_| invalid())?;
+                let channel = last.parse().map_err(|_| invalid())?;
+                Ok(Self::space_channel(path, space, channel))
+            }
+            Some(_) => Err(invalid()),
+            None if !middle.is_empty() => {
+                let channel = last.parse().map_err(|_| invalid())?;
+                Ok(Self::simple(middle, channel))
+            }
+            None => Err(invalid()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,4 +112,18 @@ mod tests {
 
         assert_eq!(map.get(&key), Some(&42));
     }
+
+    #[test]
+    fn test_channel_key_string_roundtrip() {
+        let key = ChannelKey::space_channel("C:\\BonDriver\\BonDriver_PX-MLT1.dll", 1, 12);
+        assert_eq!(key.to_string(), "C:\\BonDriver\\BonDriver_PX-MLT1.dll|1|12");
+        assert_eq!(key.to_string().parse::<ChannelKey>(), Ok(key));
+
+        let key = ChannelKey::simple("/dev/pt3video0", 13);
+        assert_eq!("/dev/pt3video0|13".parse::<ChannelKey>(), Ok(key));
+
+        assert!("/dev/pt3video0".parse::<ChannelKey>().is_err());
+        assert!("|1|2".parse::<ChannelKey>().is_err());
+        assert!("/dev/pt3video0|x".parse::<ChannelKey>().is_err());
+    }
 }
@@ -18,6 +18,8 @@ pub struct DriverInfo {
     pub driver_path: String,
     /// Virtual space generator for this driver
     pub space_gen: SpaceGenerator,
+    /// Whether this driver can receive 4K/HEVC transports
+    pub supports_4k: bool,
 }
 
 /// Aggregated space information for a group of drivers.
@@ -83,10 +85,15 @@ impl GroupSpaceInfo {
                     .collect::<Vec<_>>(),
             );
 
+            let supports_4k = db
+                .get_supports_4k_for_path(&driver_record.dll_path)
+                .unwrap_or(false);
+
             drivers.push(DriverInfo {
                 driver_id,
                 driver_path: driver_record.dll_path,
                 space_gen: space_gen.clone(),
+                supports_4k,
             });
 
             all_channels_by_driver.push(channels);
@@ -172,9 +179,14 @@ impl GroupSpaceInfo {
 }
 
 /// Strategy for selecting a driver when multiple options are available.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum DriverSelectionStrategy {
-    /// Prefer drivers with fewer active sessions
+    /// Prefer drivers with the highest quality score
+    #[default]
+    QualityScore,
+    /// Rotate the first choice across the group's drivers on every tune
+    RoundRobin,
+    /// Prefer drivers with the fewest running instances relative to `max_instances`
     LeastLoaded,
     /// Use the first available driver
     FirstAvailable,
@@ -182,6 +194,29 @@ pub enum DriverSelectionStrategy {
     PreferExisting,
 }
 
+impl DriverSelectionStrategy {
+    /// Parse a strategy as stored in `bon_drivers.group_strategy`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "quality" => Some(Self::QualityScore),
+            "round_robin" => Some(Self::RoundRobin),
+            "least_loaded" => Some(Self::LeastLoaded),
+            _ => None,
+        }
+    }
+
+    /// Name stored in `bon_drivers.group_strategy`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::QualityScore => "quality",
+            Self::RoundRobin => "round_robin",
+            Self::LeastLoaded => "least_loaded",
+            Self::FirstAvailable => "first_available",
+            Self::PreferExisting => "prefer_existing",
+        }
+    }
+}
+
 /// Driver selector with scoring logic.
 pub struct DriverSelector;
 
@@ -195,12 +230,14 @@ impl DriverSelector {
         _active_sessions: &HashMap<usize, bool>, // driver_idx -> is_active
     ) -> Vec<(usize, u32)> {
         match strategy {
-            DriverSelectionStrategy::LeastLoaded => {
+            DriverSelectionStrategy::LeastLoaded | DriverSelectionStrategy::RoundRobin => {
                 let mut sorted = candidates.to_vec();
                 sorted.sort_by_key(|(idx, _)| *idx);
                 sorted
             }
-            DriverSelectionStrategy::FirstAvailable => candidates.to_vec(),
+            DriverSelectionStrategy::QualityScore | DriverSelectionStrategy::FirstAvailable => {
+                candidates.to_vec()
+            }
             DriverSelectionStrategy::PreferExisting => {
                 let mut sorted = candidates.to_vec();
                 sorted.sort_by_key(|(idx, _)| *idx);
@@ -208,6 +245,50 @@ impl DriverSelector {
             }
         }
     }
+
+    /// Reorder quality-sorted candidates for a group's selection strategy.
+    ///
+    /// `rotation` is the group's round-robin cursor and `load` returns
+    /// `(running_instances, max_instances)` for a candidate. The sort is
+    /// stable, so equally loaded drivers keep their quality order.
+    pub fn apply_strategy<T>(
+        candidates: &mut [T],
+        strategy: DriverSelectionStrategy,
+        rotation: usize,
+        load: impl Fn(&T) -> (i32, i32),
+    ) {
+        match strategy {
+            DriverSelectionStrategy::RoundRobin if !candidates.is_empty() => {
+                let len = candidates.len();
+                candidates.rotate_left(rotation % len);
+            }
+            DriverSelectionStrategy::LeastLoaded => {
+                // Compare running/max ratios without floating point
+                candidates.sort_by(|a, b| {
+                    let (running_a, max_a) = load(a);
+                    let (running_b, max_b) = load(b);
+                    let lhs = running_a as i64 * max_b.max(1) as i64;
+                    let rhs = running_b as i64 * max_a.max(1) as i64;
+                    lhs.cmp(&rhs)
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Move drivers that support the target's codec to the front.
+    ///
+    /// Only applies when the target needs 4K/HEVC; the existing order is kept
+    /// within capable and non-capable drivers, so this can run after other sorts.
+    pub fn prefer_codec_capable<T>(
+        candidates: &mut [T],
+        needs_4k: bool,
+        supports_4k: impl Fn(&T) -> bool,
+    ) {
+        if needs_4k {
+            candidates.sort_by_key(|c| !supports_4k(c));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -236,4 +317,59 @@ mod tests {
             DriverSelector::score_drivers(&candidates, DriverSelectionStrategy::FirstAvailable, &active);
         assert!(!selected.is_empty());
     }
+
+    #[test]
+    fn test_strategy_names() {
+        for strategy in [
+            DriverSelectionStrategy::QualityScore,
+            DriverSelectionStrategy::RoundRobin,
+            DriverSelectionStrategy::LeastLoaded,
+        ] {
+            assert_eq!(DriverSelectionStrategy::from_name(strategy.name()), Some(strategy));
+        }
+        assert_eq!(DriverSelectionStrategy::from_name(" Round_Robin "), Some(DriverSelectionStrategy::RoundRobin));
+        assert_eq!(DriverSelectionStrategy::from_name("random"), None);
+    }
+
+    #[test]
+    fn test_apply_strategy_round_robin() {
+        let orders: Vec<Vec<u32>> = (0..4)
+            .map(|rotation| {
+                let mut candidates = vec![0, 1, 2];
+                DriverSelector::apply_strategy(&mut candidates, DriverSelectionStrategy::RoundRobin, rotation, |_| (0, 1));
+                candidates
+            })
+            .collect();
+        assert_eq!(orders, vec![vec![0, 1, 2], vec![1, 2, 0], vec![2, 0, 1], vec![0, 1, 2]]);
+
+        let mut empty: Vec<u32> = Vec::new();
+        DriverSelector::apply_strategy(&mut empty, DriverSelectionStrategy::RoundRobin, 5, |_| (0, 1));
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_apply_strategy_least_loaded() {
+        // (driver_idx, running, max_instances), already in quality order
+        let mut candidates = vec![(0, 2, 2), (1, 1, 4), (2, 0, 1), (3, 1, 2), (4, 0, 2)];
+        DriverSelector::apply_strategy(&mut candidates, DriverSelectionStrategy::LeastLoaded, 0, |c| (c.1, c.2));
+        let order: Vec<i32> = candidates.iter().map(|c| c.0).collect();
+        assert_eq!(order, vec![2, 4, 1, 3, 0]);
+
+        // Quality order is kept as-is
+        let mut candidates = vec![(0, 2, 2), (1, 0, 2)];
+        DriverSelector::apply_strategy(&mut candidates, DriverSelectionStrategy::QualityScore, 3, |c| (c.1, c.2));
+        assert_eq!(candidates, vec![(0, 2, 2), (1, 0, 2)]);
+    }
+
+    #[test]
+    fn test_prefer_codec_capable() {
+        // (driver_idx, supports_4k)
+        let mut candidates = vec![(0, false), (1, true), (2, false), (3, true)];
+
+        DriverSelector::prefer_codec_capable(&mut candidates, false, |c| c.1);
+        assert_eq!(candidates, vec![(0, false), (1, true), (2, false), (3, true)]);
+
+        DriverSelector::prefer_codec_capable(&mut candidates, true, |c| c.1);
+        assert_eq!(candidates, vec![(1, true), (3, true), (0, false), (2, false)]);
+    }
 }
@@ -5,8 +5,7 @@ use std::path::PathBuf;
 use log::{debug, warn};
 
 use crate::ts_analyzer::{
-    descriptor_tag, parse_descriptor_loop, PsiSection, SdtTable, SectionCollector, TsPacket,
-    TS_PACKET_SIZE, table_id,
+    PsiSection, SdtTable, SectionCollector, TsPacket, TS_PACKET_SIZE, table_id,
 };
 
 const SDT_PID: u16 = 0x0011;
@@ -21,11 +20,15 @@ const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
 const MIN_LOGO_DATA_SIZE: usize = 94;
 
 /// Logo data extracted from a CDT section.
-struct CdtLogoData {
-    network_id: u16,
-    logo_id: u16,
-    logo_type: u8,
-    png: Vec<u8>,
+#[derive(Debug, Clone)]
+pub struct CdtLogoData {
+    pub network_id: u16,
+    pub logo_id: u16,
+    /// Logo type (0x00-0x05), selects the image size.
+    pub logo_type: u8,
+    pub logo_version: u16,
+    /// Standard PNG (palette and transparency already injected).
+    pub png: Vec<u8>,
 }
 
 /// Lightweight logo collector.
@@ -34,6 +37,9 @@ struct CdtLogoData {
 /// to logos/{nid}_{sid}.png.  The CDT data-module header is parsed according to
 /// ARIB STD-B21 (same logic as TVTest / LibISDB) so that the PNG payload is
 /// extracted using the explicit `data_size` field rather than heuristic scanning.
+///
+/// Created with [`ChannelLogoCollector::in_memory`], logos are queued for
+/// [`ChannelLogoCollector::take_logos`] instead of being written to disk.
 pub struct ChannelLogoCollector {
     sdt_collector: SectionCollector,
     cdt_collector: SectionCollector,
@@ -41,7 +47,11 @@ pub struct ChannelLogoCollector {
     current_service_ids: Vec<u16>,
     current_service_logo_ids: HashMap<u16, u16>,
     saved_keys: HashSet<String>,
-    output_dir: PathBuf,
+    output_dir: Option<PathBuf>,
+    /// Logos not yet taken (in-memory mode only).
+    pending: Vec<CdtLogoData>,
+    /// (nid, logo_id, logo_type, logo_version) already queued.
+    queued: HashSet<(u16, u16, u8, u16)>,
 }
 
 impl ChannelLogoCollector {
@@ -51,6 +61,14 @@ impl ChannelLogoCollector {
             warn!("[LogoCollector] Failed to create logo directory {:?}: {}", output_dir, e);
         }
 
+        Self {
+            output_dir: Some(output_dir),
+            ..Self::in_memory()
+        }
+    }
+
+    /// Create a collector that keeps logos in memory instead of saving files.
+    pub fn in_memory() -> Self {
         Self {
             sdt_collector: SectionCollector::new(),
             cdt_collector: SectionCollector::new(),
@@ -58,10 +76,22 @@ impl ChannelLogoCollector {
             current_service_ids: Vec::new(),
             current_service_logo_ids: HashMap::new(),
             saved_keys: HashSet::new(),
-            output_dir,
+            output_dir: None,
+            pending: Vec::new(),
+            queued: HashSet::new(),
         }
     }
 
+    /// Take the logos collected since the last call (in-memory mode).
+    pub fn take_logos(&mut self) -> Vec<CdtLogoData> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Original network ID and `service_id -> logo_id` map from the latest SDT.
+    pub fn service_logo_ids(&self) -> Option<(u16, &HashMap<u16, u16>)> {
+        Some((self.current_nid?, &self.current_service_logo_ids))
+    }
+
     pub fn process_ts_chunk(&mut self, data: &[u8]) {
         let mut offset = 0usize;
         while offset + TS_PACKET_SIZE <= data.len() {
@@ -133,7 +163,7 @@ impl ChannelLogoCollector {
         self.current_service_logo_ids.clear();
 
         for svc in &sdt.services {
-            if let Some(logo_id) = extract_logo_id_from_sdt_descriptors(&svc.descriptors) {
+            if let Some(logo_id) = svc.get_logo_id().filter(|&id| id > 0) {
                 self.current_service_logo_ids.insert(svc.service_id, logo_id);
             }
         }
@@ -168,6 +198,14 @@ impl ChannelLogoCollector {
             return;
         };
 
+        let Some(output_dir) = self.output_dir.clone() else {
+            let key = (nid, logo.logo_id, logo.logo_type, logo.logo_version);
+            if self.queued.insert(key) {
+                self.pending.push(CdtLogoData { network_id: nid, ..logo });
+            }
+            return;
+        };
+
         if self.current_service_ids.is_empty() {
             return;
         }
@@ -194,7 +232,7 @@ impl ChannelLogoCollector {
                 continue;
             }
 
-            let path = self.output_dir.join(format!("{}_{}.png", nid, sid));
+            let path = output_dir.join(format!("{}_{}.png", nid, sid));
             if path.exists() {
                 self.saved_keys.insert(key);
                 continue;
@@ -216,33 +254,6 @@ impl ChannelLogoCollector {
     }
 }
 
-fn extract_logo_id_from_sdt_descriptors(descriptors: &[u8]) -> Option<u16> {
-    for (tag, data) in parse_descriptor_loop(descriptors) {
-        if tag != descriptor_tag::LOGO_TRANSMISSION {
-            continue;
-        }
-
-        // logo_transmission_descriptor
-        // transmission_type==0x01 の場合、logo_id を含む
-        // [0]=type, [1]=....|logo_id[8], [2]=logo_id[7:0]
-        if data.len() < 3 {
-            continue;
-        }
-
-        let transmission_type = data[0];
-        if transmission_type != 0x01 {
-            continue;
-        }
-
-        let logo_id = (((data[1] & 0x01) as u16) << 8) | data[2] as u16;
-        if logo_id > 0 {
-            return Some(logo_id);
-        }
-    }
-
-    None
-}
-
 /// Extract logo PNG data from a CDT section following the ARIB STD-B21 data
 /// module format.  This mirrors the logic in TVTest / LibISDB
 /// (`LogoDownloaderFilter::OnCDTSection`).
@@ -294,8 +305,7 @@ fn extract_logo_from_cdt_section(section: &PsiSection<'_>) -> Option<CdtLogoData
     }
 
     let logo_id = (((module[1] as u16) & 0x01) << 8) | module[2] as u16;
-    // logo_version (unused but parsed for correctness):
-    // let _logo_version = (((module[3] as u16) & 0x0F) << 8) | module[4] as u16;
+    let logo_version = (((module[3] as u16) & 0x0F) << 8) | module[4] as u16;
     let data_size = ((module[5] as usize) << 8) | module[6] as usize;
 
     // Validate: data fits within the module, and within the CDT section
@@ -324,6 +334,7 @@ fn extract_logo_from_cdt_section(section: &PsiSection<'_>) -> Option<CdtLogoData
         network_id: original_network_id,
         logo_id,
         logo_type,
+        logo_version,
         png: standard_png,
     })
 }
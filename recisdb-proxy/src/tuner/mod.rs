@@ -3,11 +3,14 @@
 //! This module provides:
 //! - [`TunerPool`]: Pool of shared tuner instances with channel sharing
 //! - [`SharedTuner`]: Wrapper for tuner with broadcast capability
+//! - [`TsSink`]: Server-owned TS consumers attached to a shared tuner
+//! - [`RecordingRegistry`]: Server-side recordings to file
 //! - [`TunerLock`]: Exclusive/shared lock mechanism
 //! - [`TunerSelector`]: Intelligent tuner selection with fallback
 //! - [`passive_scanner`]: Real-time channel info updates during streaming
 //! - [`space_generator`]: Automatic virtual space generation from channels
 //! - [`group_space`]: Group-based aggregation and driver selection
+//! - [`bs_cs_mapping`]: NIT-based BS/CS transport stream to channel mapping
 
 pub mod channel_key;
 pub mod lock;
@@ -15,23 +18,31 @@ pub mod passive_scanner;
 pub mod pool;
 pub mod selector;
 pub mod shared;
+pub mod sink;
 pub mod ts_parser;
 pub mod ts_analyzer;
 pub mod b25_pipe;
+pub mod ts_align;
 pub mod space_generator;
 pub mod group_space;
 pub mod quality_scorer;
+pub mod recorder;
 pub mod warm;
 pub mod logo_collector;
+pub mod bs_cs_mapping;
 
 pub use channel_key::ChannelKey;
 #[allow(unused_imports)]
 pub use lock::{ExclusiveLockGuard, LockError, SharedLockGuard, TunerLock};
-pub use pool::{TunerPool, TunerPoolConfig};
+pub use pool::{ScanGuard, TunerPool, TunerPoolConfig};
 #[allow(unused_imports)]
 pub use selector::{ChannelCandidate, FallbackResult, SelectError, TuneError, TunerSelector};
-pub use shared::SharedTuner;
+pub use shared::{BroadcastSettings, LagPolicy, SharedTuner};
+pub use sink::{SinkId, TsSink};
 pub use warm::WarmTunerHandle;
 pub use space_generator::{SpaceGenerator, SpaceMapping, ChannelInfo as SpaceGenChannelInfo};
 pub use group_space::{GroupSpaceInfo, DriverInfo, DriverSelector, DriverSelectionStrategy};
 pub use quality_scorer::{BonDriverWithScore, QualityScorer};
+pub use recorder::{
+    confine_output_path, RecordingId, RecordingInfo, RecordingRegistry, RecordingRequest, SegmentPolicy,
+};
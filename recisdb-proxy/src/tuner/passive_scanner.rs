@@ -7,9 +7,13 @@
 //! # How It Works
 //!
 //! 1. When streaming starts, the passive scanner is attached to the tuner
-//! 2. It monitors TS packets for PAT/SDT/NIT tables
+//! 2. It monitors TS packets for PAT/SDT/NIT tables and EIT events
 //! 3. When channel information changes, it updates the database
 //! 4. This allows automatic discovery of new channels or metadata updates
+//! 5. Present/following and schedule events are stored as EPG data
+//! 6. Station logos (SDT logo_id + CDT images) are stored for the dashboard
+
+use std::collections::HashMap;
 
 use bytes::Bytes;
 use log::{debug, trace};
@@ -17,7 +21,10 @@ use tokio::sync::broadcast;
 
 use recisdb_protocol::ChannelInfo;
 
+use crate::database::NewEpgEvent;
 use crate::server::listener::DatabaseHandle;
+use crate::ts_analyzer::{AnalyzerConfig, EitEvent, EitEventKey, TsAnalyzer};
+use crate::tuner::logo_collector::ChannelLogoCollector;
 use crate::tuner::ts_parser::MinimalTsParser;
 
 /// Configuration for passive scanning.
@@ -27,6 +34,10 @@ pub struct PassiveScanConfig {
     pub enabled: bool,
     /// Interval between scan updates (to avoid too frequent DB writes).
     pub update_interval_secs: u64,
+    /// Whether to update channel records from PAT/SDT/NIT.
+    pub update_channels: bool,
+    /// Whether to store EIT events as EPG data.
+    pub collect_epg: bool,
 }
 
 impl Default for PassiveScanConfig {
@@ -34,10 +45,34 @@ impl Default for PassiveScanConfig {
         Self {
             enabled: true,
             update_interval_secs: 60,
+            update_channels: true,
+            collect_epg: true,
         }
     }
 }
 
+/// Convert a parsed EIT event into a database row.
+fn epg_event_from_eit(key: EitEventKey, event: &EitEvent) -> NewEpgEvent {
+    let (nid, tsid, sid, event_id) = key;
+    NewEpgEvent {
+        nid,
+        tsid,
+        sid,
+        event_id,
+        start_time: event.start_timestamp(),
+        duration_secs: event.duration_secs.map(i64::from),
+        title: event.title().map(str::to_string),
+        description: event.description().map(str::to_string),
+    }
+}
+
+/// Whether an event ended before `now` (events with unknown start never expire).
+fn epg_event_ended(event: &NewEpgEvent, now: i64) -> bool {
+    event
+        .start_time
+        .is_some_and(|start| start + event.duration_secs.unwrap_or(0) < now)
+}
+
 /// Passive scanner instance for a single tuner.
 pub struct PassiveScanner {
     /// Database handle.
@@ -58,6 +93,16 @@ pub struct PassiveScanner {
     ts_parser: MinimalTsParser,
     /// Whether the parser has completed (found required tables).
     parser_complete: bool,
+    /// Analyzer collecting EIT events.
+    eit_analyzer: TsAnalyzer,
+    /// Last stored version of each event, to skip unchanged rewrites.
+    epg_written: HashMap<EitEventKey, NewEpgEvent>,
+    /// Last EPG flush timestamp.
+    last_epg_flush: std::time::Instant,
+    /// Collector for SDT logo IDs and CDT logo images.
+    logo_collector: ChannelLogoCollector,
+    /// Last stored `service_id -> logo_id` map, to skip unchanged rewrites.
+    logo_ids_written: HashMap<u16, u16>,
 }
 
 impl PassiveScanner {
@@ -79,6 +124,17 @@ impl PassiveScanner {
             pending_info: None,
             ts_parser: MinimalTsParser::new(),
             parser_complete: false,
+            eit_analyzer: TsAnalyzer::new(AnalyzerConfig {
+                parse_nit: false,
+                parse_sdt: false,
+                parse_all_pmts: false,
+                parse_eit: true,
+                ..AnalyzerConfig::default()
+            }),
+            epg_written: HashMap::new(),
+            last_epg_flush: std::time::Instant::now(),
+            logo_collector: ChannelLogoCollector::in_memory(),
+            logo_ids_written: HashMap::new(),
         }
     }
 
@@ -86,12 +142,24 @@ impl PassiveScanner {
     ///
     /// This method should be called for each TS data chunk received.
     /// It will analyze the TS packets and extract channel information.
-    #[allow(dead_code)]
     pub fn process_ts_data(&mut self, data: &Bytes) {
         if !self.config.enabled {
             return;
         }
 
+        if self.config.collect_epg {
+            self.eit_analyzer.feed(data);
+            if self.last_epg_flush.elapsed().as_secs() >= self.config.update_interval_secs {
+                self.flush_epg();
+            }
+        }
+
+        if !self.config.update_channels {
+            return;
+        }
+
+        self.logo_collector.process_ts_chunk(data);
+
         // Feed data to the TS parser
         if !self.parser_complete {
             self.parser_complete = self.ts_parser.feed(data);
@@ -120,6 +188,8 @@ impl PassiveScanner {
             return;
         }
 
+        self.flush_logos();
+
         // If we have pending channel info, update the database
         if let Some(info) = self.pending_info.take() {
             trace!(
@@ -183,6 +253,77 @@ impl PassiveScanner {
         self.last_update = std::time::Instant::now();
     }
 
+    /// Store EIT events seen since the last flush and prune ended ones.
+    fn flush_epg(&mut self) {
+        self.last_epg_flush = std::time::Instant::now();
+        let now = chrono::Utc::now().timestamp();
+
+        let mut events = Vec::new();
+        for (key, event) in self.eit_analyzer.take_eit_events() {
+            let event = epg_event_from_eit(key, &event);
+            if epg_event_ended(&event, now) || self.epg_written.get(&key) == Some(&event) {
+                continue;
+            }
+            self.epg_written.insert(key, event.clone());
+            events.push(event);
+        }
+        self.epg_written.retain(|_, event| !epg_event_ended(event, now));
+
+        if events.is_empty() {
+            return;
+        }
+
+        let db = self.database.clone();
+        tokio::spawn(async move {
+            let mut db_guard = db.lock().await;
+            match db_guard.insert_events(&events) {
+                Ok(written) => trace!("PassiveScanner: Stored {} EPG event(s)", written),
+                Err(e) => debug!("PassiveScanner: Failed to store EPG events: {}", e),
+            }
+            if let Err(e) = db_guard.prune_expired_events(now) {
+                debug!("PassiveScanner: Failed to prune EPG events: {}", e);
+            }
+        });
+    }
+
+    /// Store logos and service logo IDs seen since the last flush.
+    fn flush_logos(&mut self) {
+        let logos = self.logo_collector.take_logos();
+        let logo_ids = match self.logo_collector.service_logo_ids() {
+            Some((nid, ids)) if *ids != self.logo_ids_written => {
+                self.logo_ids_written = ids.clone();
+                Some((nid, ids.iter().map(|(&sid, &id)| (sid, id)).collect::<Vec<_>>()))
+            }
+            _ => None,
+        };
+
+        if logos.is_empty() && logo_ids.is_none() {
+            return;
+        }
+
+        let db = self.database.clone();
+        tokio::spawn(async move {
+            let mut db_guard = db.lock().await;
+            for logo in &logos {
+                if let Err(e) = db_guard.upsert_channel_logo(
+                    logo.network_id,
+                    logo.logo_id,
+                    logo.logo_type,
+                    logo.logo_version,
+                    &logo.png,
+                ) {
+                    debug!("PassiveScanner: Failed to store logo: {}", e);
+                }
+            }
+            if let Some((nid, ids)) = logo_ids {
+                match db_guard.set_service_logo_ids(nid, &ids) {
+                    Ok(updated) => trace!("PassiveScanner: Set logo ID on {} channel(s)", updated),
+                    Err(e) => debug!("PassiveScanner: Failed to store logo IDs: {}", e),
+                }
+            }
+        });
+    }
+
     /// Set the pending channel info (for testing or manual updates).
     #[allow(dead_code)]
     pub fn set_pending_info(&mut self, info: ChannelInfo) {
@@ -205,7 +346,6 @@ impl PassiveScanner {
 /// Start passive scanning on a tuner's broadcast receiver.
 ///
 /// This function creates a passive scanner and processes incoming TS data.
-#[allow(dead_code)]
 pub async fn start_passive_scan(
     mut ts_receiver: broadcast::Receiver<Bytes>,
     database: DatabaseHandle,
@@ -242,5 +382,27 @@ mod tests {
         let config = PassiveScanConfig::default();
         assert!(config.enabled);
         assert_eq!(config.update_interval_secs, 60);
+        assert!(config.update_channels);
+        assert!(config.collect_epg);
+    }
+
+    #[test]
+    fn test_epg_event_from_eit() {
+        let event = EitEvent {
+            event_id: 0x1234,
+            start_time: chrono::NaiveDate::from_ymd_opt(2023, 11, 15)
+                .and_then(|d| d.and_hms_opt(7, 13, 20)),
+            duration_secs: Some(1800),
+            ..EitEvent::default()
+        };
+        let row = epg_event_from_eit((0x7FE8, 32736, 1024, 0x1234), &event);
+        assert_eq!((row.nid, row.tsid, row.sid, row.event_id), (0x7FE8, 32736, 1024, 0x1234));
+        assert_eq!(row.start_time, Some(1_700_000_000));
+        assert_eq!(row.duration_secs, Some(1800));
+        assert_eq!(row.title, None);
+
+        assert!(!epg_event_ended(&row, 1_700_001_800));
+        assert!(epg_event_ended(&row, 1_700_001_801));
+        assert!(!epg_event_ended(&NewEpgEvent::default(), i64::MAX));
     }
 }
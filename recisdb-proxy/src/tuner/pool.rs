@@ -1,14 +1,22 @@
 //! Tuner pool for managing shared tuner instances.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
-use log::{debug, info, warn};
+use log::{debug, error, info, warn};
+use recisdb_protocol::BandType;
 use tokio::sync::{Mutex, RwLock, Semaphore};
 use tokio::sync::oneshot;
 
+use crate::database::Database;
 use crate::tuner::channel_key::ChannelKey;
-use crate::tuner::shared::SharedTuner;
+use crate::tuner::quality_scorer::QualityScorer;
+use crate::tuner::shared::{BroadcastSettings, LagPolicy, ReaderStartupConfig, SharedTuner};
+use crate::tuner::warm::WarmTunerHandle;
+
+/// How often the stall watchdog checks running readers.
+const STALL_CHECK_INTERVAL: Duration = Duration::from_secs(2);
 
 /// Key for identifying a TS (Transport Stream) for tuner sharing.
 /// Used for TSID/SID-based tuner merging.
@@ -63,6 +71,37 @@ pub struct TunerPoolConfig {
     pub set_channel_retry_timeout_ms: u64,
     pub signal_poll_interval_ms: u64,
     pub signal_wait_timeout_ms: u64,
+    /// Keep a session on the driver it is already using across channel
+    /// changes in group mode (false = re-balance on every tune).
+    pub driver_affinity_enabled: bool,
+    /// Emit only whole 188-byte packets aligned on sync bytes
+    /// (false = forward reader chunks as-is for minimal latency).
+    pub align_ts_output: bool,
+    /// Re-key the B25 decoder when this percentage of decoded packets stays
+    /// scrambled (0 = disabled).
+    pub descramble_rekey_percent: u64,
+    /// How long the scrambled ratio must persist before re-keying.
+    pub descramble_rekey_window_secs: u64,
+    /// Restart a running reader that delivered no TS for this long
+    /// (0 = disabled).
+    pub stall_timeout_ms: u64,
+    /// Merge TS into TsData frames of up to this many bytes, capped at
+    /// `MAX_TS_CHUNK_SIZE` (0 = send reader chunks as-is).
+    pub ts_coalesce_bytes: usize,
+    /// Send a partly filled frame once its oldest data is this old.
+    pub ts_coalesce_flush_ms: u64,
+    /// In group mode, warm the best-scored idle driver of the group instead
+    /// of no driver at all.
+    pub prewarm_group: bool,
+    /// Open the default tuner at startup and hold it until the first session
+    /// tunes that driver.
+    pub prewarm_on_startup: bool,
+    /// Fail a channel change when a terrestrial or CATV signal stays below
+    /// this level (dB; 0 = disabled).
+    pub min_signal_level_terrestrial: f64,
+    /// Same for BS/CS satellite channels, whose drivers report C/N on a
+    /// different scale.
+    pub min_signal_level_satellite: f64,
 }
 
 impl Default for TunerPoolConfig {
@@ -75,6 +114,30 @@ impl Default for TunerPoolConfig {
             set_channel_retry_timeout_ms: 10_000,
             signal_poll_interval_ms: 500,
             signal_wait_timeout_ms: 10_000,
+            driver_affinity_enabled: true,
+            align_ts_output: false,
+            descramble_rekey_percent: 90,
+            descramble_rekey_window_secs: 30,
+            stall_timeout_ms: 15_000,
+            ts_coalesce_bytes: 0,
+            ts_coalesce_flush_ms: 20,
+            prewarm_group: false,
+            prewarm_on_startup: false,
+            min_signal_level_terrestrial: 0.0,
+            min_signal_level_satellite: 0.0,
+        }
+    }
+}
+
+impl TunerPoolConfig {
+    /// Minimum signal level for a channel of network `nid` (0 = no check).
+    pub fn min_signal_level_for_nid(&self, nid: u16) -> f64 {
+        match BandType::from_nid(nid) {
+            BandType::Terrestrial | BandType::CATV => self.min_signal_level_terrestrial,
+            BandType::BS | BandType::CS | BandType::FourK | BandType::SKY => {
+                self.min_signal_level_satellite
+            }
+            BandType::Other => 0.0,
         }
     }
 }
@@ -100,6 +163,27 @@ pub struct TunerPool {
     /// "steal" another's channel.  The lock is held only during the init phase
     /// (up to ~10 s); the reader loop runs without it.
     dll_init_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// Round-robin cursors per driver group.
+    round_robin: Mutex<HashMap<String, usize>>,
+    /// Tuner opened at server startup, waiting for its first session.
+    startup_warm: Mutex<Option<WarmTunerHandle>>,
+    /// Broadcast buffer settings per driver path; drivers not listed use the
+    /// defaults. Read when a tuner is created.
+    broadcast_settings: std::sync::RwLock<HashMap<String, BroadcastSettings>>,
+    /// Driver paths a channel scan currently has open.
+    scanning: std::sync::Mutex<HashSet<String>>,
+}
+
+/// Keeps a driver marked as being scanned; see [`TunerPool::begin_scan`].
+pub struct ScanGuard<'a> {
+    pool: &'a TunerPool,
+    dll_path: String,
+}
+
+impl Drop for ScanGuard<'_> {
+    fn drop(&mut self) {
+        self.pool.scanning.lock().unwrap().remove(&self.dll_path);
+    }
 }
 
 struct IdleHandle {
@@ -120,11 +204,27 @@ impl TunerPool {
             max_tuners,
             config: RwLock::new(config),
             dll_init_locks: Mutex::new(HashMap::new()),
+            round_robin: Mutex::new(HashMap::new()),
+            startup_warm: Mutex::new(None),
+            broadcast_settings: std::sync::RwLock::new(HashMap::new()),
+            scanning: std::sync::Mutex::new(HashSet::new()),
         }
     }
 
+    /// Maximum number of concurrent tuner instances.
+    pub fn max_tuners(&self) -> usize {
+        self.max_tuners
+    }
+
     /// Update tuner optimization configuration.
     pub async fn update_config(self: &Arc<Self>, config: TunerPoolConfig) {
+        if !config.prewarm_on_startup || config.keep_alive_secs == 0 {
+            if let Some(warm) = self.startup_warm.lock().await.take() {
+                info!("Releasing startup warm tuner {}", warm.path());
+                warm.shutdown().await;
+            }
+        }
+
         let old_keep_alive = {
             let mut guard = self.config.write().await;
             let old = guard.keep_alive_secs;
@@ -155,6 +255,92 @@ impl TunerPool {
         self.config.read().await.clone()
     }
 
+    /// Set the broadcast buffer settings of tuners opened on `tuner_path`.
+    ///
+    /// Takes effect for tuners created afterwards; a running tuner keeps its
+    /// buffer until it is closed.
+    pub fn set_broadcast_settings(&self, tuner_path: &str, settings: BroadcastSettings) {
+        let mut map = self.broadcast_settings.write().unwrap();
+        if settings == BroadcastSettings::default() {
+            map.remove(tuner_path);
+        } else {
+            map.insert(tuner_path.to_string(), settings);
+        }
+    }
+
+    /// Replace all broadcast buffer settings with those stored in `db`.
+    ///
+    /// Unknown lag policies fall back to the default with a warning.
+    pub fn load_broadcast_settings(&self, db: &Database) -> crate::database::Result<()> {
+        let mut map = HashMap::new();
+        for (path, capacity, lag_policy) in db.get_broadcast_configs()? {
+            let mut settings = BroadcastSettings::default();
+            if let Some(capacity) = capacity.filter(|c| *c > 0) {
+                settings.capacity = capacity as usize;
+            }
+            if let Some(name) = lag_policy {
+                match LagPolicy::from_name(&name) {
+                    Some(policy) => settings.lag_policy = policy,
+                    None => warn!("Unknown lag policy '{}' for {}, using default", name, path),
+                }
+            }
+            if settings != BroadcastSettings::default() {
+                map.insert(path, settings);
+            }
+        }
+        *self.broadcast_settings.write().unwrap() = map;
+        Ok(())
+    }
+
+    /// Broadcast buffer settings for tuners opened on `tuner_path`.
+    pub fn broadcast_settings(&self, tuner_path: &str) -> BroadcastSettings {
+        self.broadcast_settings
+            .read()
+            .unwrap()
+            .get(tuner_path)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Hold `warm` as the startup warm tuner, replacing any previous one.
+    pub async fn set_startup_warm(&self, warm: WarmTunerHandle) {
+        let previous = self.startup_warm.lock().await.replace(warm);
+        if let Some(previous) = previous {
+            previous.shutdown().await;
+        }
+    }
+
+    /// Claim the startup warm tuner if it was opened for `tuner_path`.
+    ///
+    /// The caller activates it like a session warm tuner; the resulting
+    /// reader is then an ordinary pool tuner with the usual keep-alive.
+    pub async fn take_startup_warm(&self, tuner_path: &str) -> Option<WarmTunerHandle> {
+        let mut guard = self.startup_warm.lock().await;
+        if guard.as_ref().is_some_and(|warm| warm.path() == tuner_path) {
+            guard.take()
+        } else {
+            None
+        }
+    }
+
+    /// Whether the startup warm tuner currently holds `tuner_path` open.
+    pub async fn has_startup_warm(&self, tuner_path: &str) -> bool {
+        self.startup_warm
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|warm| warm.path() == tuner_path)
+    }
+
+    /// Close the startup warm tuner for `tuner_path` so another user of the
+    /// driver (e.g. a channel scan) can open it.
+    pub async fn release_startup_warm(&self, tuner_path: &str) {
+        if let Some(warm) = self.take_startup_warm(tuner_path).await {
+            info!("Releasing startup warm tuner {}", tuner_path);
+            warm.shutdown().await;
+        }
+    }
+
     /// Acquire a per-DLL initialization lock.
     ///
     /// Returns an `OwnedMutexGuard` that serializes BonDriver DLL operations
@@ -173,6 +359,85 @@ impl TunerPool {
         mutex.lock_owned().await
     }
 
+    /// Mark `dll_path` as being scanned until the returned guard is dropped.
+    ///
+    /// Take the DLL init lock around this call so a test tune in progress
+    /// finishes before the scan opens the driver.
+    pub fn begin_scan(&self, dll_path: &str) -> ScanGuard<'_> {
+        self.scanning.lock().unwrap().insert(dll_path.to_string());
+        ScanGuard {
+            pool: self,
+            dll_path: dll_path.to_string(),
+        }
+    }
+
+    /// Whether a channel scan currently has `dll_path` open.
+    pub fn is_scanning(&self, dll_path: &str) -> bool {
+        self.scanning.lock().unwrap().contains(dll_path)
+    }
+
+    /// Reserve an instance of `dll_path` for a one-off direct open outside
+    /// the pool (a test tune).
+    ///
+    /// Like [`reserve_reader`](Self::reserve_reader), but the startup warm
+    /// tuner counts as in use too, since a direct open does not claim it.
+    pub async fn reserve_direct_open(
+        &self,
+        dll_path: &str,
+        max_instances: i32,
+    ) -> Result<tokio::sync::OwnedMutexGuard<()>, String> {
+        self.reserve_instance(dll_path, max_instances, true).await
+    }
+
+    /// Reserve an instance of `dll_path` for a new pool reader.
+    ///
+    /// Returns the DLL init lock guard, to be held until the reader is
+    /// started, or the reason the driver cannot be spared: a scan has it
+    /// open, or running readers already use all `max_instances`.
+    pub async fn reserve_reader(
+        &self,
+        dll_path: &str,
+        max_instances: i32,
+    ) -> Result<tokio::sync::OwnedMutexGuard<()>, String> {
+        self.reserve_instance(dll_path, max_instances, false).await
+    }
+
+    async fn reserve_instance(
+        &self,
+        dll_path: &str,
+        max_instances: i32,
+        count_startup_warm: bool,
+    ) -> Result<tokio::sync::OwnedMutexGuard<()>, String> {
+        let guard = self.acquire_dll_init_lock(dll_path).await;
+        if self.is_scanning(dll_path) {
+            return Err("A channel scan is running on this BonDriver".to_string());
+        }
+        let running = self
+            .tuners
+            .read()
+            .await
+            .iter()
+            .filter(|(key, tuner)| key.tuner_path == dll_path && tuner.is_running())
+            .count();
+        let warm = count_startup_warm && self.has_startup_warm(dll_path).await;
+        if (running + usize::from(warm)) as i64 >= i64::from(max_instances) {
+            return Err(format!(
+                "All {} instance(s) of this BonDriver are in use",
+                max_instances
+            ));
+        }
+        Ok(guard)
+    }
+
+    /// Return the group's round-robin cursor and advance it.
+    pub async fn next_round_robin(&self, group_name: &str) -> usize {
+        let mut cursors = self.round_robin.lock().await;
+        let cursor = cursors.entry(group_name.to_string()).or_insert(0);
+        let current = *cursor;
+        *cursor = cursor.wrapping_add(1);
+        current
+    }
+
     /// Cancel an idle-close timer if it exists.
     pub async fn cancel_idle_close(&self, key: &ChannelKey) {
         let mut idle_tasks = self.idle_tasks.lock().await;
@@ -192,6 +457,17 @@ impl TunerPool {
     /// Schedule a delayed close when the tuner becomes idle.
     pub async fn schedule_idle_close(self: &Arc<Self>, key: ChannelKey, tuner: Arc<SharedTuner>) {
         let keep_alive_secs = self.config.read().await.keep_alive_secs;
+        self.schedule_idle_close_after(key, tuner, keep_alive_secs).await;
+    }
+
+    /// Schedule a delayed close after `keep_alive_secs` instead of the
+    /// configured keep-alive (0 = close now).
+    pub async fn schedule_idle_close_after(
+        self: &Arc<Self>,
+        key: ChannelKey,
+        tuner: Arc<SharedTuner>,
+        keep_alive_secs: u64,
+    ) {
         if keep_alive_secs == 0 {
             info!("Keep-alive disabled, stopping reader for {:?}", key);
             tuner.stop_reader().await;
@@ -360,7 +636,8 @@ impl TunerPool {
         factory().await?;
 
         // Create the shared tuner wrapper
-        let shared = SharedTuner::new(key.clone(), bondriver_version);
+        let settings = self.broadcast_settings(&key.tuner_path);
+        let shared = SharedTuner::with_broadcast(key.clone(), bondriver_version, settings);
         info!("Created new shared tuner for {:?}", key);
 
         tuners.insert(key, Arc::clone(&shared));
@@ -397,10 +674,93 @@ impl TunerPool {
         before - tuners.len()
     }
 
+    /// Stop every reader and empty the pool (server shutdown).
+    ///
+    /// Returns the number of tuners stopped.
+    pub async fn shutdown(&self) -> usize {
+        self.cancel_all_idle().await;
+        if let Some(warm) = self.startup_warm.lock().await.take() {
+            warm.shutdown().await;
+        }
+        let tuners: Vec<Arc<SharedTuner>> = self.tuners.write().await.drain().map(|(_, t)| t).collect();
+        for tuner in &tuners {
+            if tuner.is_running() {
+                tuner.stop_reader().await;
+            }
+        }
+        tuners.len()
+    }
+
     /// Get all active tuner keys.
     pub async fn keys(&self) -> Vec<ChannelKey> {
         self.tuners.read().await.keys().cloned().collect()
     }
+
+    /// Start the watchdog that restarts BonDriver readers which stopped
+    /// delivering TS while sessions are still attached.
+    ///
+    /// A reader whose signal level is 0 is assumed to be on a channel without
+    /// signal rather than hung, and is left alone.
+    pub fn start_stall_watchdog(
+        self: &Arc<Self>,
+        database: Arc<tokio::sync::Mutex<Database>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let pool = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(STALL_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                let config = pool.config().await;
+                if config.stall_timeout_ms == 0 {
+                    continue;
+                }
+                let timeout = Duration::from_millis(config.stall_timeout_ms);
+
+                let tuners: Vec<Arc<SharedTuner>> = pool.tuners.read().await.values().cloned().collect();
+                for tuner in tuners {
+                    if !tuner.is_running() || !tuner.has_subscribers() {
+                        continue;
+                    }
+                    let Some(stalled) = tuner.stalled_for() else { continue };
+                    if stalled < timeout {
+                        continue;
+                    }
+                    let Some((tuner_path, _, _)) = tuner.reader_params() else { continue };
+
+                    let signal = tuner.signal_level();
+                    if signal <= 0.0 {
+                        debug!(
+                            "[TunerPool] No TS for {:?} in {:?}, but signal level is {:.2}; treating as no signal",
+                            tuner.key, stalled, signal
+                        );
+                        continue;
+                    }
+
+                    warn!(
+                        "[TunerPool] Reader for {:?} delivered no TS for {:?} (signal {:.2} dB), restarting",
+                        tuner.key, stalled, signal
+                    );
+                    {
+                        let _dll_guard = pool.acquire_dll_init_lock(&tuner_path).await;
+                        if let Err(e) = tuner.restart_reader(ReaderStartupConfig::from(&config)).await {
+                            error!("[TunerPool] Failed to restart reader for {:?}: {}", tuner.key, e);
+                        }
+                    }
+
+                    let db = database.lock().await;
+                    match db.get_bon_driver_by_path(&tuner_path) {
+                        Ok(Some(driver)) => {
+                            if let Err(e) = QualityScorer::record_stall(&db, driver.id) {
+                                warn!("[TunerPool] Failed to record stall for {}: {}", tuner_path, e);
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => warn!("[TunerPool] Failed to look up BonDriver {}: {}", tuner_path, e),
+                    }
+                }
+            }
+        })
+    }
 }
 
 impl Default for TunerPool {
@@ -442,4 +802,55 @@ mod tests {
         pool.cleanup().await;
         assert_eq!(pool.count().await, 0);
     }
+
+    #[test]
+    fn test_min_signal_level_per_band() {
+        let config = TunerPoolConfig {
+            min_signal_level_terrestrial: 20.0,
+            min_signal_level_satellite: 8.0,
+            ..TunerPoolConfig::default()
+        };
+        assert_eq!(config.min_signal_level_for_nid(0x7FE8), 20.0);
+        assert_eq!(config.min_signal_level_for_nid(0xFFFE), 20.0);
+        assert_eq!(config.min_signal_level_for_nid(0x0004), 8.0);
+        assert_eq!(config.min_signal_level_for_nid(0x0006), 8.0);
+        assert_eq!(config.min_signal_level_for_nid(0x0100), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_startup_warm_claimed_by_path() {
+        let pool = TunerPool::new(10);
+        pool.set_startup_warm(WarmTunerHandle::spawn("/dev/test0".to_string(), 0))
+            .await;
+
+        assert!(pool.has_startup_warm("/dev/test0").await);
+        assert!(pool.take_startup_warm("/dev/test1").await.is_none());
+
+        let warm = pool.take_startup_warm("/dev/test0").await.unwrap();
+        assert_eq!(warm.path(), "/dev/test0");
+        assert!(!pool.has_startup_warm("/dev/test0").await);
+        warm.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_direct_open_refused_when_drivers_busy() {
+        let pool = TunerPool::new(10);
+        pool.set_startup_warm(WarmTunerHandle::spawn("/dev/test0".to_string(), 0))
+            .await;
+
+        let err = pool.reserve_direct_open("/dev/test0", 1).await.unwrap_err();
+        assert!(err.contains("in use"), "{}", err);
+        assert!(pool.reserve_direct_open("/dev/test0", 2).await.is_ok());
+        assert!(pool.reserve_direct_open("/dev/test1", 1).await.is_ok());
+
+        {
+            let _scan = pool.begin_scan("/dev/test1");
+            let err = pool.reserve_direct_open("/dev/test1", 1).await.unwrap_err();
+            assert!(err.contains("scan"), "{}", err);
+        }
+        assert!(!pool.is_scanning("/dev/test1"));
+        assert!(pool.reserve_direct_open("/dev/test1", 1).await.is_ok());
+
+        pool.release_startup_warm("/dev/test0").await;
+    }
 }
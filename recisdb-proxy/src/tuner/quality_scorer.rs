@@ -10,10 +10,51 @@ pub struct BonDriverWithScore {
     pub recent_drop_rate: f64,
 }
 
+/// Counts added to a driver's stats by one update.
+#[derive(Debug, Default, Clone, Copy)]
+struct Sample {
+    packets: u64,
+    dropped: u64,
+    scrambled: u64,
+    errors: u64,
+    sessions: i64,
+    stalls: i64,
+}
+
 /// Driver quality scorer.
+///
+/// Scores are calculated from time-weighted counts: before each update the
+/// stored counts are halved once per half-life elapsed since `last_updated`,
+/// so recent sessions outweigh old ones. On read the penalty of the stored
+/// score fades the same way, letting an idle driver recover its ranking.
 pub struct QualityScorer;
 
 impl QualityScorer {
+    /// Half-life of the stats when `quality_half_life_hours` is not configured (one week).
+    pub const DEFAULT_HALF_LIFE_HOURS: f64 = 168.0;
+
+    /// Configured half-life of the stats in hours; 0 disables decay.
+    pub fn half_life_hours(db: &Database) -> f64 {
+        db.get_quality_half_life_hours()
+            .ok()
+            .flatten()
+            .unwrap_or(Self::DEFAULT_HALF_LIFE_HOURS)
+    }
+
+    /// Weight left on a sample that is `elapsed_secs` old (1.0 when decay is disabled).
+    pub fn decay_factor(elapsed_secs: i64, half_life_hours: f64) -> f64 {
+        if half_life_hours <= 0.0 || elapsed_secs <= 0 {
+            return 1.0;
+        }
+        0.5_f64.powf(elapsed_secs as f64 / (half_life_hours * 3600.0))
+    }
+
+    /// Score as seen at `now`: the distance from 1.0 shrinks as the stats age.
+    pub fn decayed_score(score: f64, last_updated: i64, now: i64, half_life_hours: f64) -> f64 {
+        let factor = Self::decay_factor(now - last_updated, half_life_hours);
+        (1.0 - (1.0 - score) * factor).clamp(0.0, 1.0)
+    }
+
     /// Update driver quality stats after a session ends.
     pub fn update_stats(
         db: &Database,
@@ -23,52 +64,18 @@ impl QualityScorer {
         scrambled: u64,
         errors: u64,
     ) -> Result<()> {
-        let current = db.get_driver_quality_stats(bon_driver_id)?;
-
-        let total_packets = current.as_ref().map(|s| s.total_packets).unwrap_or(0) + packets as i64;
-        let dropped_packets = current.as_ref().map(|s| s.dropped_packets).unwrap_or(0) + dropped as i64;
-        let scrambled_packets = current.as_ref().map(|s| s.scrambled_packets).unwrap_or(0) + scrambled as i64;
-        let error_packets = current.as_ref().map(|s| s.error_packets).unwrap_or(0) + errors as i64;
-        let total_sessions = current.as_ref().map(|s| s.total_sessions).unwrap_or(0) + 1;
-
-        let stats = DriverQualityStats {
-            id: current.as_ref().map(|s| s.id).unwrap_or(0),
-            bon_driver_id,
-            total_packets,
-            dropped_packets,
-            scrambled_packets,
-            error_packets,
-            total_sessions,
-            quality_score: 1.0,
-            recent_drop_rate: 0.0,
-            recent_error_rate: 0.0,
-            last_updated: chrono::Utc::now().timestamp(),
-        };
-
-        let quality_score = Self::calculate_score(&stats);
-
         let session_total = packets.max(1) as f64;
-        let recent_drop_rate = dropped as f64 / session_total;
-        let recent_error_rate = errors as f64 / session_total;
-
-        db.upsert_driver_quality_stats(
+        Self::accumulate(
+            db,
             bon_driver_id,
-            total_packets,
-            dropped_packets,
-            scrambled_packets,
-            error_packets,
-            total_sessions,
-            quality_score,
-            recent_drop_rate,
-            recent_error_rate,
-            chrono::Utc::now().timestamp(),
-        )?;
-
-        Ok(())
+            Sample { packets, dropped, scrambled, errors, sessions: 1, stalls: 0 },
+            Some((dropped as f64 / session_total, errors as f64 / session_total)),
+        )
     }
 
     /// Update driver quality stats with delta packets.
     /// When `increment_sessions` is false, only adds packet deltas without incrementing session count.
+    #[allow(clippy::too_many_arguments)]
     pub fn update_stats_delta(
         db: &Database,
         bon_driver_id: i64,
@@ -81,75 +88,130 @@ impl QualityScorer {
         session_errors: u64,
         increment_sessions: bool,
     ) -> Result<()> {
-        let current = db.get_driver_quality_stats(bon_driver_id)?;
+        let session_total = session_packets.max(1) as f64;
+        Self::accumulate(
+            db,
+            bon_driver_id,
+            Sample {
+                packets: delta_packets,
+                dropped: delta_dropped,
+                scrambled: delta_scrambled,
+                errors: delta_errors,
+                sessions: if increment_sessions { 1 } else { 0 },
+                stalls: 0,
+            },
+            Some((session_dropped as f64 / session_total, session_errors as f64 / session_total)),
+        )
+    }
 
-        let total_packets = current.as_ref().map(|s| s.total_packets).unwrap_or(0) + delta_packets as i64;
-        let dropped_packets = current.as_ref().map(|s| s.dropped_packets).unwrap_or(0) + delta_dropped as i64;
-        let scrambled_packets = current.as_ref().map(|s| s.scrambled_packets).unwrap_or(0) + delta_scrambled as i64;
-        let error_packets = current.as_ref().map(|s| s.error_packets).unwrap_or(0) + delta_errors as i64;
-        let total_sessions = current.as_ref().map(|s| s.total_sessions).unwrap_or(0)
-            + if increment_sessions { 1 } else { 0 };
+    /// Record a reader stall and lower the driver's score accordingly.
+    pub fn record_stall(db: &Database, bon_driver_id: i64) -> Result<()> {
+        Self::accumulate(db, bon_driver_id, Sample { stalls: 1, ..Sample::default() }, None)
+    }
 
-        let stats = DriverQualityStats {
-            id: current.as_ref().map(|s| s.id).unwrap_or(0),
-            bon_driver_id,
-            total_packets,
-            dropped_packets,
-            scrambled_packets,
-            error_packets,
-            total_sessions,
-            quality_score: 1.0,
-            recent_drop_rate: 0.0,
-            recent_error_rate: 0.0,
-            last_updated: chrono::Utc::now().timestamp(),
+    /// Decay the stored stats to now, add `sample` and store the new score.
+    ///
+    /// `recent_rates` replaces the recent (drop, error) rates when given.
+    fn accumulate(
+        db: &Database,
+        bon_driver_id: i64,
+        sample: Sample,
+        recent_rates: Option<(f64, f64)>,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let mut stats = match db.get_driver_quality_stats(bon_driver_id)? {
+            Some(current) => Self::decay(current, now, Self::half_life_hours(db)),
+            None => DriverQualityStats {
+                id: 0,
+                bon_driver_id,
+                total_packets: 0,
+                dropped_packets: 0,
+                scrambled_packets: 0,
+                error_packets: 0,
+                total_sessions: 0,
+                quality_score: 1.0,
+                recent_drop_rate: 0.0,
+                recent_error_rate: 0.0,
+                stall_count: 0,
+                weighted_packets: 0.0,
+                weighted_dropped: 0.0,
+                weighted_scrambled: 0.0,
+                weighted_errors: 0.0,
+                weighted_sessions: 0.0,
+                weighted_stalls: 0.0,
+                last_updated: now,
+            },
         };
 
-        let quality_score = Self::calculate_score(&stats);
-
-        let session_total = session_packets.max(1) as f64;
-        let recent_drop_rate = session_dropped as f64 / session_total;
-        let recent_error_rate = session_errors as f64 / session_total;
+        stats.total_packets += sample.packets as i64;
+        stats.dropped_packets += sample.dropped as i64;
+        stats.scrambled_packets += sample.scrambled as i64;
+        stats.error_packets += sample.errors as i64;
+        stats.total_sessions += sample.sessions;
+        stats.stall_count += sample.stalls;
+        stats.weighted_packets += sample.packets as f64;
+        stats.weighted_dropped += sample.dropped as f64;
+        stats.weighted_scrambled += sample.scrambled as f64;
+        stats.weighted_errors += sample.errors as f64;
+        stats.weighted_sessions += sample.sessions as f64;
+        stats.weighted_stalls += sample.stalls as f64;
+        stats.quality_score = Self::calculate_score(&stats);
+        if let Some((drop_rate, error_rate)) = recent_rates {
+            stats.recent_drop_rate = drop_rate;
+            stats.recent_error_rate = error_rate;
+        }
 
-        db.upsert_driver_quality_stats(
-            bon_driver_id,
-            total_packets,
-            dropped_packets,
-            scrambled_packets,
-            error_packets,
-            total_sessions,
-            quality_score,
-            recent_drop_rate,
-            recent_error_rate,
-            chrono::Utc::now().timestamp(),
-        )?;
+        db.upsert_driver_quality_stats(&stats)
+    }
 
-        Ok(())
+    /// Scale the weighted counts down to `now`.
+    fn decay(mut stats: DriverQualityStats, now: i64, half_life_hours: f64) -> DriverQualityStats {
+        let factor = Self::decay_factor(now - stats.last_updated, half_life_hours);
+        stats.weighted_packets *= factor;
+        stats.weighted_dropped *= factor;
+        stats.weighted_scrambled *= factor;
+        stats.weighted_errors *= factor;
+        stats.weighted_sessions *= factor;
+        stats.weighted_stalls *= factor;
+        stats.last_updated = now;
+        stats
     }
 
-    /// Calculate quality score (0.0 - 1.0).
-    /// score = 1.0 - (drop_rate * 0.5 + error_rate * 0.3 + scramble_rate * 0.2)
+    /// Calculate quality score (0.0 - 1.0) from the weighted counts.
+    /// score = 1.0 - (drop_rate * 0.5 + error_rate * 0.3 + scramble_rate * 0.2 + stall_rate * 0.5)
+    ///
+    /// stall_rate is stalls per session (capped at 1), so a driver that hangs
+    /// in every other session loses a quarter of its score.
     pub fn calculate_score(stats: &DriverQualityStats) -> f64 {
-        let total = stats.total_packets.max(1) as f64;
-        let drop_rate = stats.dropped_packets as f64 / total;
-        let error_rate = stats.error_packets as f64 / total;
-        let scramble_rate = stats.scrambled_packets as f64 / total;
+        let total = stats.weighted_packets.max(1.0);
+        let drop_rate = stats.weighted_dropped / total;
+        let error_rate = stats.weighted_errors / total;
+        let scramble_rate = stats.weighted_scrambled / total;
+        let stall_rate = (stats.weighted_stalls / stats.weighted_sessions.max(1.0)).min(1.0);
 
-        let score = 1.0 - (drop_rate * 0.5 + error_rate * 0.3 + scramble_rate * 0.2);
+        let score = 1.0 - (drop_rate * 0.5 + error_rate * 0.3 + scramble_rate * 0.2 + stall_rate * 0.5);
         score.clamp(0.0, 1.0)
     }
 
-    /// Get drivers for a channel ordered by quality score.
+    /// Get drivers for a channel ordered by quality score, decayed to the current time.
     pub async fn get_best_drivers_for_channel(
         db: &Database,
         nid: u16,
         tsid: u16,
     ) -> Result<Vec<BonDriverWithScore>> {
         let mut stmt = db.connection().prepare(
-            "SELECT bd.id, bd.dll_path, bd.driver_name, bd.version, bd.group_name, bd.auto_scan_enabled, bd.scan_interval_hours, bd.scan_priority, bd.last_scan, bd.next_scan_at, bd.passive_scan_enabled, bd.max_instances, bd.created_at, bd.updated_at, COALESCE(dqs.quality_score, 1.0) as quality_score, COALESCE(dqs.recent_drop_rate, 0.0) as recent_drop_rate FROM channels ch JOIN bon_drivers bd ON ch.bon_driver_id = bd.id LEFT JOIN driver_quality_stats dqs ON bd.id = dqs.bon_driver_id WHERE ch.nid = ?1 AND ch.tsid = ?2 AND ch.is_enabled = 1 GROUP BY bd.id ORDER BY quality_score DESC, bd.scan_priority DESC",
+            "SELECT bd.id, bd.dll_path, bd.driver_name, bd.version, bd.group_name, bd.auto_scan_enabled, bd.scan_interval_hours, bd.scan_priority, bd.last_scan, bd.next_scan_at, bd.passive_scan_enabled, bd.max_instances, bd.created_at, bd.updated_at, COALESCE(dqs.quality_score, 1.0) as quality_score, COALESCE(dqs.recent_drop_rate, 0.0) as recent_drop_rate, dqs.last_updated FROM channels ch JOIN bon_drivers bd ON ch.bon_driver_id = bd.id LEFT JOIN driver_quality_stats dqs ON bd.id = dqs.bon_driver_id WHERE ch.nid = ?1 AND ch.tsid = ?2 AND ch.is_enabled = 1 GROUP BY bd.id",
         )?;
 
-        let drivers = stmt
+        let now = chrono::Utc::now().timestamp();
+        let half_life_hours = Self::half_life_hours(db);
+        let mut drivers = stmt
             .query_map([nid as i64, tsid as i64], |row| {
+                let quality_score: f64 = row.get(14)?;
+                let quality_score = match row.get::<_, Option<i64>>(16)? {
+                    Some(last_updated) => Self::decayed_score(quality_score, last_updated, now, half_life_hours),
+                    None => quality_score,
+                };
                 Ok(BonDriverWithScore {
                     driver: BonDriverRecord {
                         id: row.get(0)?,
@@ -167,12 +229,83 @@ impl QualityScorer {
                         created_at: row.get(12)?,
                         updated_at: row.get(13)?,
                     },
-                    quality_score: row.get(14)?,
+                    quality_score,
                     recent_drop_rate: row.get(15)?,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
+        drivers.sort_by(|a, b| {
+            b.quality_score
+                .partial_cmp(&a.quality_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.driver.scan_priority.cmp(&a.driver.scan_priority))
+        });
+
         Ok(drivers)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_stall_lowers_ranking() {
+        let db = Database::open_in_memory().unwrap();
+        let flaky = db.get_or_create_bon_driver("BonDriver_Flaky.dll").unwrap();
+        let stable = db.get_or_create_bon_driver("BonDriver_Stable.dll").unwrap();
+
+        QualityScorer::record_stall(&db, flaky).unwrap();
+        QualityScorer::record_stall(&db, flaky).unwrap();
+
+        let stats = db.get_driver_quality_stats(flaky).unwrap().unwrap();
+        assert_eq!(stats.stall_count, 2);
+        assert!(stats.quality_score < 1.0);
+
+        let ranking = db.get_bondrivers_ranking().unwrap();
+        assert_eq!(ranking[0].0.id, stable);
+        let (_, score, _, _, stalls) = &ranking[1];
+        assert_eq!(*stalls, 2);
+        assert!(*score < 1.0);
+    }
+
+    #[test]
+    fn test_decay_factor() {
+        assert_eq!(QualityScorer::decay_factor(0, 24.0), 1.0);
+        assert!((QualityScorer::decay_factor(24 * 3600, 24.0) - 0.5).abs() < 1e-9);
+        assert!((QualityScorer::decay_factor(48 * 3600, 24.0) - 0.25).abs() < 1e-9);
+        // Half-life 0 disables decay
+        assert_eq!(QualityScorer::decay_factor(48 * 3600, 0.0), 1.0);
+
+        let now = 1_700_000_000;
+        assert!((QualityScorer::decayed_score(0.6, now - 24 * 3600, now, 24.0) - 0.8).abs() < 1e-9);
+        assert_eq!(QualityScorer::decayed_score(0.6, now - 24 * 3600, now, 0.0), 0.6);
+    }
+
+    #[test]
+    fn test_recent_samples_outweigh_stale_stats() {
+        let db = Database::open_in_memory().unwrap();
+        let driver = db.get_or_create_bon_driver("BonDriver_Recovered.dll").unwrap();
+        db.set_quality_half_life_hours(24.0).unwrap();
+
+        // A bad session, ten half-lives ago
+        QualityScorer::update_stats(&db, driver, 1000, 500, 0, 0).unwrap();
+        let mut stats = db.get_driver_quality_stats(driver).unwrap().unwrap();
+        let stale_score = stats.quality_score;
+        stats.last_updated -= 240 * 3600;
+        db.upsert_driver_quality_stats(&stats).unwrap();
+
+        // The stale penalty has faded on read
+        let (_, ranked_score, _, _, _) = db.get_bondrivers_ranking().unwrap().remove(0);
+        assert!(ranked_score > 0.99);
+        assert!(db.get_driver_quality_score_by_path("BonDriver_Recovered.dll").unwrap() > 0.99);
+
+        // A clean session now dominates the stored score
+        QualityScorer::update_stats(&db, driver, 1000, 0, 0, 0).unwrap();
+        let stats = db.get_driver_quality_stats(driver).unwrap().unwrap();
+        assert_eq!((stats.total_packets, stats.dropped_packets), (2000, 500));
+        assert!(stats.quality_score > 0.99);
+        assert!(stats.quality_score > stale_score);
+    }
+}
@@ -0,0 +1,701 @@
+//! Server-side recording of a tuner's TS to a file.
+//!
+//! A recording attaches a [`TsSink`] to a [`SharedTuner`] and writes what it
+//! receives for a fixed duration, so a channel can be recorded without any
+//! client attached. The TS is what the shared reader broadcasts, i.e. already
+//! B25-decoded when the decoder is available, and optionally narrowed down to
+//! the requested service. Active recordings are tracked in a
+//! [`RecordingRegistry`] so they can be listed and cancelled.
+//!
+//! Long recordings can be split into numbered segments (`out-000.ts`,
+//! `out-001.ts`, ...) by time or size, see [`SegmentPolicy`]. Segments are
+//! cut on packet boundaries, preferably right before a PAT so each file
+//! starts decodable.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use log::{error, info, warn};
+use serde::Serialize;
+use tokio::sync::{oneshot, Notify};
+
+use crate::ts_analyzer::service_filter::TsServiceFilter;
+use crate::tuner::sink::TsSink;
+use crate::tuner::ts_align::TsPacketAligner;
+use crate::tuner::{SharedTuner, TunerPool};
+
+/// Identifier of a recording in a [`RecordingRegistry`].
+pub type RecordingId = u64;
+
+/// Chunks queued for the writer thread before new ones are dropped.
+const WRITE_QUEUE_CHUNKS: usize = 256;
+
+const TS_PACKET_SIZE: usize = 188;
+
+/// How long a due segment cut waits for a PAT before cutting anyway.
+/// PATs are sent at least every 100 ms, so this only matters for odd streams.
+const SEGMENT_PAT_WAIT: Duration = Duration::from_secs(1);
+
+/// How a recording is split into several files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SegmentPolicy {
+    /// Start a new segment after this much time.
+    pub max_duration: Option<Duration>,
+    /// Start a new segment after this many bytes.
+    pub max_bytes: Option<u64>,
+    /// Also write `<stem>.index.tsv` listing each finished segment.
+    pub write_index: bool,
+}
+
+/// What to record and where.
+#[derive(Debug, Clone)]
+pub struct RecordingRequest {
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    pub duration: Duration,
+    /// Resolved with [`RecordingRegistry::output_path`].
+    pub output_path: PathBuf,
+    /// Keep only the packets of `sid` (false = the whole TS).
+    pub service_only: bool,
+    /// Split into segments named after `output_path` (None = one file).
+    pub segment: Option<SegmentPolicy>,
+    /// Hold the tuner's reader back instead of dropping chunks when the
+    /// recording falls behind.
+    pub lossless: bool,
+}
+
+/// Status of an active recording.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingInfo {
+    pub id: RecordingId,
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    pub output_path: String,
+    pub service_only: bool,
+    pub lossless: bool,
+    pub segment_duration_secs: Option<u64>,
+    pub segment_size_bytes: Option<u64>,
+    /// Segment files started so far (1 for an unsegmented recording).
+    pub segments: u64,
+    pub tuner_path: Option<String>,
+    pub space: Option<u32>,
+    pub channel: Option<u32>,
+    /// Start time (Unix seconds).
+    pub started_at: i64,
+    pub duration_secs: u64,
+    pub elapsed_secs: u64,
+    pub bytes_written: u64,
+    /// Chunks dropped because the disk could not keep up.
+    pub dropped_chunks: u64,
+    /// Write error that ended the recording early, if any.
+    pub error: Option<String>,
+}
+
+/// Counters shared between the sink, the writer thread and the registry.
+#[derive(Default)]
+struct RecordProgress {
+    bytes_written: AtomicU64,
+    dropped_chunks: AtomicU64,
+    segments: AtomicU64,
+    error: Mutex<Option<String>>,
+}
+
+/// Sink that hands TS chunks to a writer thread.
+struct FileRecordSink {
+    name: String,
+    /// None once closed; dropping it lets the writer thread finish.
+    tx: Option<SyncSender<Bytes>>,
+    /// Set when the filter or segmenting needs packet-aligned input.
+    aligner: Option<TsPacketAligner>,
+    /// Single-service filter.
+    filter: Option<TsServiceFilter>,
+    /// Keep a chunk the write queue had no room for instead of dropping it.
+    lossless: bool,
+    /// Chunk waiting for room in the write queue (lossless only).
+    pending: Option<Bytes>,
+    progress: Arc<RecordProgress>,
+}
+
+impl TsSink for FileRecordSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn write(&mut self, data: &Bytes) -> bool {
+        let data = match &mut self.aligner {
+            None => data.clone(),
+            Some(aligner) => {
+                let Some(aligned) = aligner.push(data.clone()) else { return true };
+                aligned
+            }
+        };
+        let data = match &mut self.filter {
+            None => data,
+            Some(filter) => {
+                let filtered = filter.filter(&data);
+                if filtered.is_empty() {
+                    return true;
+                }
+                Bytes::from(filtered)
+            }
+        };
+        let Some(tx) = &self.tx else { return false };
+        match tx.try_send(data) {
+            Ok(()) => true,
+            Err(TrySendError::Full(data)) if self.lossless => {
+                self.pending = Some(data);
+                true
+            }
+            Err(TrySendError::Full(_)) => {
+                let dropped = self.progress.dropped_chunks.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped == 1 || dropped.is_multiple_of(100) {
+                    warn!("[Recorder] {}: write queue full, {} chunks dropped", self.name, dropped);
+                }
+                true
+            }
+            // Writer thread gave up after an I/O error
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+
+    fn retry_pending(&mut self) -> bool {
+        let (Some(tx), Some(data)) = (&self.tx, self.pending.take()) else { return false };
+        match tx.try_send(data) {
+            Err(TrySendError::Full(data)) => {
+                self.pending = Some(data);
+                true
+            }
+            // Sent, or the writer is gone and the next write detaches
+            _ => false,
+        }
+    }
+
+    fn close(&mut self) {
+        self.tx = None;
+    }
+}
+
+/// File name of segment `index` of `base` (`out.ts` -> `out-003.ts`).
+fn segment_path(base: &Path, index: u64) -> PathBuf {
+    let stem = base.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let name = match base.extension() {
+        Some(ext) => format!("{}-{:03}.{}", stem, index, ext.to_string_lossy()),
+        None => format!("{}-{:03}", stem, index),
+    };
+    base.with_file_name(name)
+}
+
+/// Index file of a segmented recording (`out.ts` -> `out.index.tsv`).
+fn index_path(base: &Path) -> PathBuf {
+    let stem = base.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    base.with_file_name(format!("{}.index.tsv", stem))
+}
+
+fn create_new(path: &Path) -> io::Result<BufWriter<File>> {
+    OpenOptions::new().write(true).create_new(true).open(path).map(BufWriter::new)
+}
+
+/// Whether `packet` starts a PAT section.
+fn is_pat_start(packet: &[u8]) -> bool {
+    packet.len() >= 3 && packet[0] == 0x47 && packet[1] & 0x40 != 0 && packet[1] & 0x1F == 0 && packet[2] == 0
+}
+
+/// Output file(s) of a recording, rotating segments per [`SegmentPolicy`].
+struct RecordOutput {
+    base: PathBuf,
+    policy: Option<SegmentPolicy>,
+    file: BufWriter<File>,
+    index: Option<BufWriter<File>>,
+    segment: u64,
+    segment_bytes: u64,
+    segment_started: Instant,
+    /// Unix time (ms) the current segment started.
+    segment_started_at: i64,
+    /// When the current segment became due for a cut, while waiting for a PAT.
+    cut_due_since: Option<Instant>,
+}
+
+impl RecordOutput {
+    /// Create the first (or only) file. Existing files are never overwritten.
+    fn create(base: &Path, policy: Option<SegmentPolicy>) -> io::Result<Self> {
+        let (file, index) = match policy {
+            None => (create_new(base)?, None),
+            Some(policy) => {
+                let file = create_new(&segment_path(base, 0))?;
+                let index = if policy.write_index {
+                    let mut index = create_new(&index_path(base))?;
+                    writeln!(index, "# segment\tstart_ms\tduration_ms\tbytes")?;
+                    index.flush()?;
+                    Some(index)
+                } else {
+                    None
+                };
+                (file, index)
+            }
+        };
+        Ok(Self {
+            base: base.to_path_buf(),
+            policy,
+            file,
+            index,
+            segment: 0,
+            segment_bytes: 0,
+            segment_started: Instant::now(),
+            segment_started_at: chrono::Utc::now().timestamp_millis(),
+            cut_due_since: None,
+        })
+    }
+
+    /// Write a chunk; with a segment policy it must be packet-aligned.
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<()> {
+        let Some(policy) = self.policy else { return self.write_segment(chunk) };
+        let mut time_due = policy
+            .max_duration
+            .is_some_and(|d| self.segment_started.elapsed() >= d);
+        let mut start = 0;
+        for offset in (0..chunk.len()).step_by(TS_PACKET_SIZE) {
+            let pending = (offset - start) as u64;
+            let size_due = policy.max_bytes.is_some_and(|max| self.segment_bytes + pending >= max);
+            if !(time_due || size_due) {
+                continue;
+            }
+            let due_since = *self.cut_due_since.get_or_insert_with(Instant::now);
+            if is_pat_start(&chunk[offset..]) || due_since.elapsed() >= SEGMENT_PAT_WAIT {
+                self.write_segment(&chunk[start..offset])?;
+                start = offset;
+                self.rotate()?;
+                time_due = false;
+            }
+        }
+        self.write_segment(&chunk[start..])
+    }
+
+    fn write_segment(&mut self, data: &[u8]) -> io::Result<()> {
+        self.file.write_all(data)?;
+        self.segment_bytes += data.len() as u64;
+        Ok(())
+    }
+
+    /// Close the current segment and open the next one.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.finish_segment()?;
+        self.segment += 1;
+        self.file = create_new(&segment_path(&self.base, self.segment))?;
+        self.segment_bytes = 0;
+        self.segment_started = Instant::now();
+        self.segment_started_at = chrono::Utc::now().timestamp_millis();
+        self.cut_due_since = None;
+        Ok(())
+    }
+
+    /// Flush the current file and add it to the index.
+    fn finish_segment(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if let Some(index) = &mut self.index {
+            let name = segment_path(&self.base, self.segment);
+            writeln!(
+                index,
+                "{}\t{}\t{}\t{}",
+                name.file_name().map(|n| n.to_string_lossy()).unwrap_or_default(),
+                self.segment_started_at,
+                self.segment_started.elapsed().as_millis(),
+                self.segment_bytes
+            )?;
+            index.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Write queued chunks to `output` until the sink closes or a write fails.
+fn run_writer(mut output: RecordOutput, rx: Receiver<Bytes>, progress: Arc<RecordProgress>, done: oneshot::Sender<()>) {
+    let mut result = Ok(());
+    for chunk in rx {
+        let segment = output.segment;
+        result = output.write_chunk(&chunk);
+        if output.segment != segment {
+            progress.segments.store(output.segment + 1, Ordering::Relaxed);
+        }
+        if result.is_err() {
+            break;
+        }
+        progress.bytes_written.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+    }
+    if let Err(e) = result.and_then(|_| output.finish_segment()) {
+        *progress.error.lock().unwrap() = Some(e.to_string());
+    }
+    let _ = done.send(());
+}
+
+struct ActiveRecording {
+    info: RecordingInfo,
+    started: Instant,
+    progress: Arc<RecordProgress>,
+    cancel: Arc<Notify>,
+}
+
+impl ActiveRecording {
+    fn status(&self) -> RecordingInfo {
+        RecordingInfo {
+            elapsed_secs: self.started.elapsed().as_secs(),
+            bytes_written: self.progress.bytes_written.load(Ordering::Relaxed),
+            dropped_chunks: self.progress.dropped_chunks.load(Ordering::Relaxed),
+            segments: self.progress.segments.load(Ordering::Relaxed),
+            error: self.progress.error.lock().unwrap().clone(),
+            ..self.info.clone()
+        }
+    }
+}
+
+/// Resolve a requested recording file name under `dir`.
+///
+/// Only relative names are accepted (subdirectories are fine); an absolute
+/// path, a drive prefix or a `..` component is refused so a request cannot
+/// write outside `dir`.
+pub fn confine_output_path(dir: &Path, requested: &Path) -> Result<PathBuf, String> {
+    let mut relative = PathBuf::new();
+    for component in requested.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(format!(
+                    "output_path must be a relative path inside the recordings directory: {}",
+                    requested.display()
+                ));
+            }
+        }
+    }
+    if relative.as_os_str().is_empty() {
+        return Err("output_path is required".to_string());
+    }
+    Ok(dir.join(relative))
+}
+
+/// Registry of recordings in progress.
+pub struct RecordingRegistry {
+    next_id: AtomicU64,
+    active: Mutex<HashMap<RecordingId, ActiveRecording>>,
+    /// Directory every recording is written under.
+    output_dir: PathBuf,
+}
+
+impl RecordingRegistry {
+    /// Create an empty registry writing under `output_dir`.
+    pub fn new(output_dir: PathBuf) -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            active: Mutex::new(HashMap::new()),
+            output_dir,
+        }
+    }
+
+    /// Directory every recording is written under.
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    /// Resolve a requested file name under the output directory; see
+    /// [`confine_output_path`].
+    pub fn output_path(&self, requested: &str) -> Result<PathBuf, String> {
+        confine_output_path(&self.output_dir, Path::new(requested.trim()))
+    }
+
+    /// Start recording `tuner` to `request.output_path`.
+    ///
+    /// The file (or first segment and index) must not exist yet. The recording stops on its own after
+    /// `request.duration`, on [`cancel`](Self::cancel), or when writing fails;
+    /// the tuner is then handed back to the pool's keep-alive handling.
+    pub async fn start(
+        self: &Arc<Self>,
+        tuner_pool: &Arc<TunerPool>,
+        tuner: Arc<SharedTuner>,
+        request: RecordingRequest,
+    ) -> io::Result<RecordingInfo> {
+        let (info, _finished) = self.start_watched(tuner_pool, tuner, request).await?;
+        Ok(info)
+    }
+
+    /// Like [`start`](Self::start), but also returns a receiver for the final
+    /// status once the recording has ended and the file is flushed.
+    pub async fn start_watched(
+        self: &Arc<Self>,
+        tuner_pool: &Arc<TunerPool>,
+        tuner: Arc<SharedTuner>,
+        request: RecordingRequest,
+    ) -> io::Result<(RecordingInfo, oneshot::Receiver<RecordingInfo>)> {
+        if let Some(parent) = request.output_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let output = RecordOutput::create(&request.output_path, request.segment)?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let name = format!("record-{}", id);
+        let progress = Arc::new(RecordProgress::default());
+        progress.segments.store(1, Ordering::Relaxed);
+
+        let (tx, rx) = mpsc::sync_channel(WRITE_QUEUE_CHUNKS);
+        let (done_tx, mut done_rx) = oneshot::channel();
+        {
+            let progress = Arc::clone(&progress);
+            std::thread::Builder::new()
+                .name(name.clone())
+                .spawn(move || run_writer(output, rx, progress, done_tx))?;
+        }
+
+        let sink = FileRecordSink {
+            name: name.clone(),
+            tx: Some(tx),
+            aligner: (request.service_only || request.segment.is_some()).then(TsPacketAligner::new),
+            filter: request.service_only.then(|| TsServiceFilter::new(request.sid)),
+            lossless: request.lossless,
+            pending: None,
+            progress: Arc::clone(&progress),
+        };
+        // Cancel a pending keep-alive close before attaching
+        tuner_pool.cancel_idle_close(&tuner.key).await;
+        let sink_id = if request.lossless {
+            tuner.add_lossless_sink(Box::new(sink))
+        } else {
+            tuner.add_sink(Box::new(sink))
+        };
+
+        let reader = tuner.reader_params();
+        let info = RecordingInfo {
+            id,
+            nid: request.nid,
+            tsid: request.tsid,
+            sid: request.sid,
+            output_path: request.output_path.display().to_string(),
+            service_only: request.service_only,
+            lossless: request.lossless,
+            segment_duration_secs: request.segment.and_then(|s| s.max_duration).map(|d| d.as_secs()),
+            segment_size_bytes: request.segment.and_then(|s| s.max_bytes),
+            segments: 1,
+            tuner_path: reader.as_ref().map(|(path, _, _)| path.clone()),
+            space: reader.as_ref().map(|(_, space, _)| *space),
+            channel: reader.as_ref().map(|(_, _, channel)| *channel),
+            started_at: chrono::Utc::now().timestamp(),
+            duration_secs: request.duration.as_secs(),
+            elapsed_secs: 0,
+            bytes_written: 0,
+            dropped_chunks: 0,
+            error: None,
+        };
+        let cancel = Arc::new(Notify::new());
+        self.active.lock().unwrap().insert(
+            id,
+            ActiveRecording {
+                info: info.clone(),
+                started: Instant::now(),
+                progress: Arc::clone(&progress),
+                cancel: Arc::clone(&cancel),
+            },
+        );
+        info!(
+            "[Recorder] Recording {} started: NID={} TSID={} SID={} for {}s to {}",
+            id, request.nid, request.tsid, request.sid, info.duration_secs, info.output_path
+        );
+
+        let (finished_tx, finished_rx) = oneshot::channel();
+        let registry = Arc::clone(self);
+        let tuner_pool = Arc::clone(tuner_pool);
+        tokio::spawn(async move {
+            let mut writer_done = false;
+            tokio::select! {
+                _ = tokio::time::sleep(request.duration) => {}
+                _ = cancel.notified() => info!("[Recorder] Recording {} cancelled", id),
+                _ = &mut done_rx => writer_done = true,
+            }
+
+            tuner.remove_sink(sink_id);
+            if !writer_done {
+                let _ = done_rx.await;
+            }
+            // The sink task unsubscribes right after closing the sink
+            for _ in 0..20 {
+                if !tuner.sink_names().iter().any(|(sid, _)| *sid == sink_id) {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            if !tuner.has_subscribers() {
+                tuner_pool.schedule_idle_close(tuner.key.clone(), Arc::clone(&tuner)).await;
+            }
+
+            let recording = registry.active.lock().unwrap().remove(&id);
+            if let Some(recording) = recording {
+                let status = recording.status();
+                match &status.error {
+                    Some(e) => error!("[Recorder] Recording {} failed after {} bytes: {}", id, status.bytes_written, e),
+                    None => info!(
+                        "[Recorder] Recording {} finished: {} bytes in {}s ({} chunks dropped)",
+                        id, status.bytes_written, status.elapsed_secs, status.dropped_chunks
+                    ),
+                }
+                let _ = finished_tx.send(status);
+            }
+        });
+
+        Ok((info, finished_rx))
+    }
+
+    /// Status of all active recordings, ordered by ID.
+    pub fn list(&self) -> Vec<RecordingInfo> {
+        let mut recordings: Vec<RecordingInfo> =
+            self.active.lock().unwrap().values().map(ActiveRecording::status).collect();
+        recordings.sort_by_key(|r| r.id);
+        recordings
+    }
+
+    /// Stop a recording early. Returns false if it is not active.
+    pub fn cancel(&self, id: RecordingId) -> bool {
+        match self.active.lock().unwrap().get(&id) {
+            Some(recording) => {
+                recording.cancel.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("recisdb-proxy-{}-{}.ts", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_file_sink_writes_and_drains_on_close() {
+        let path = temp_path("sink");
+        let output = RecordOutput::create(&path, None).unwrap();
+        let progress = Arc::new(RecordProgress::default());
+        let (tx, rx) = mpsc::sync_channel(WRITE_QUEUE_CHUNKS);
+        let (done_tx, done_rx) = oneshot::channel();
+        let writer = {
+            let progress = Arc::clone(&progress);
+            std::thread::spawn(move || run_writer(output, rx, progress, done_tx))
+        };
+
+        let mut sink = FileRecordSink {
+            name: "test".to_string(),
+            tx: Some(tx),
+            aligner: None,
+            filter: None,
+            lossless: false,
+            pending: None,
+            progress: Arc::clone(&progress),
+        };
+        let packet = Bytes::from(vec![0x47u8; 188]);
+        assert!(sink.write(&packet));
+        assert!(sink.write(&packet));
+        sink.close();
+        assert!(!sink.write(&packet));
+
+        writer.join().unwrap();
+        assert!(done_rx.blocking_recv().is_ok());
+        assert_eq!(progress.bytes_written.load(Ordering::Relaxed), 376);
+        assert_eq!(std::fs::read(&path).unwrap().len(), 376);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_segments_cut_before_pat() {
+        let base = temp_path("segments");
+        let policy = SegmentPolicy { max_duration: None, max_bytes: Some(2 * 188), write_index: true };
+        let segments = [segment_path(&base, 0), segment_path(&base, 1), index_path(&base)];
+        for path in &segments {
+            let _ = std::fs::remove_file(path);
+        }
+        assert!(segments[1].file_name().unwrap().to_string_lossy().ends_with("-001.ts"));
+
+        let mut pat = vec![0u8; 188];
+        pat[..3].copy_from_slice(&[0x47, 0x40, 0x00]);
+        let mut video = vec![0u8; 188];
+        video[..3].copy_from_slice(&[0x47, 0x01, 0x11]);
+        let chunk = [&pat[..], &video[..], &video[..], &video[..], &pat[..], &video[..]].concat();
+
+        let mut output = RecordOutput::create(&base, Some(policy)).unwrap();
+        // The existing first segment is never overwritten
+        assert!(RecordOutput::create(&base, Some(policy)).is_err());
+        output.write_chunk(&chunk).unwrap();
+        output.finish_segment().unwrap();
+
+        // Due after two packets, but the cut waits for the next PAT
+        let first = std::fs::read(&segments[0]).unwrap();
+        let second = std::fs::read(&segments[1]).unwrap();
+        assert_eq!((first.len(), second.len()), (4 * 188, 2 * 188));
+        assert!(is_pat_start(&second));
+
+        let index = std::fs::read_to_string(&segments[2]).unwrap();
+        let lines: Vec<&str> = index.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].ends_with("\t752"));
+        assert!(lines[2].ends_with("\t376"));
+        for path in &segments {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_output_path_confined_to_directory() {
+        let dir = Path::new("/srv/recordings");
+        assert_eq!(confine_output_path(dir, Path::new("news/nhk.ts")), Ok(dir.join("news/nhk.ts")));
+        assert_eq!(confine_output_path(dir, Path::new("./nhk.ts")), Ok(dir.join("nhk.ts")));
+        assert!(confine_output_path(dir, Path::new("/etc/x")).is_err());
+        assert!(confine_output_path(dir, Path::new("../x")).is_err());
+        assert!(confine_output_path(dir, Path::new("news/../../x")).is_err());
+        assert!(confine_output_path(dir, Path::new("")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_registry_refuses_existing_file_and_cancels() {
+        let registry = Arc::new(RecordingRegistry::new(std::env::temp_dir()));
+        let pool = Arc::new(TunerPool::new(4));
+        let tuner = SharedTuner::new(crate::tuner::ChannelKey::simple("/dev/pt3video0", 13), 2);
+        let path = temp_path("registry");
+        let request = RecordingRequest {
+            nid: 0x7FE0,
+            tsid: 0x7FE0,
+            sid: 1024,
+            duration: Duration::from_secs(3600),
+            output_path: path.clone(),
+            service_only: false,
+            segment: None,
+            lossless: false,
+        };
+
+        let info = registry.start(&pool, Arc::clone(&tuner), request.clone()).await.unwrap();
+        assert_eq!(tuner.subscriber_count(), 1);
+        assert_eq!(registry.list().len(), 1);
+
+        // Never overwrite an existing file
+        let err = registry.start(&pool, Arc::clone(&tuner), request).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+        assert!(registry.cancel(info.id));
+        for _ in 0..100 {
+            if registry.list().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(registry.list().is_empty());
+        assert_eq!(tuner.subscriber_count(), 0);
+        assert!(!registry.cancel(info.id));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
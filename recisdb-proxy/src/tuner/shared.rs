@@ -1,30 +1,49 @@
 //! Shared tuner implementation with broadcast capability.
 
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::pin::Pin;
-use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
-use crate::tuner::b25_pipe::B25Pipe; // 作った場所に合わせて
-use b25_sys::DecoderOptions; // 鍵が必要な場合
+use crate::tuner::b25_pipe::{stream_decoder_options, B25Pipe, RekeyPolicy}; // 作った場所に合わせて
 
 use bytes::Bytes;
 use futures_util::AsyncBufRead;
 use log::{debug, error, info, trace, warn};
-use tokio::sync::broadcast;
+use recisdb_protocol::DecodeStatus;
+use tokio::sync::{broadcast, Notify};
 
 use crate::bondriver::BonDriverTuner;
 use crate::tuner::channel_key::ChannelKey;
 use crate::tuner::lock::TunerLock;
 use crate::tuner::logo_collector::ChannelLogoCollector;
+use crate::tuner::sink::{SinkId, TsSink};
+use crate::tuner::ts_align::TsPacketAligner;
 use crate::tuner::ts_analyzer::{TsPacketAnalyzer, TsStreamQuality};
 use crate::tuner::pool::TunerPoolConfig;
 
-/// Capacity of the broadcast channel for TS data.
+/// Default capacity of the broadcast channel for TS data.
 /// Increased to 4096 (256MB of 64KB chunks) to support multiple simultaneous subscribers
 /// without buffer overflow when subscriber read speeds vary significantly.
 /// Each slot holds a 64KB chunk, so 4096 slots = ~256MB of buffering capacity.
-const BROADCAST_CAPACITY: usize = 4096;
+pub const DEFAULT_BROADCAST_CAPACITY: usize = 4096;
+
+/// How often a paced reader re-checks the subscriber backlog.
+const LAG_PACE_INTERVAL: Duration = Duration::from_millis(5);
+/// A paced reader stops waiting for a subscriber whose backlog has not
+/// shrunk for this long, and drops for it until it catches up.
+const LAG_STALL_TIMEOUT: Duration = Duration::from_secs(2);
+/// A lossless sink whose pending chunk still cannot be written after this
+/// long is detached rather than retried forever.
+const LOSSLESS_WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the reader samples the driver's signal level.
+const SIGNAL_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// How far back the signal history reaches.
+const SIGNAL_HISTORY_WINDOW: Duration = Duration::from_secs(3600);
+/// Upper bound on stored signal samples (one hour at 1 Hz).
+const SIGNAL_HISTORY_CAPACITY: usize = 3600;
 
 /// Size of each TS data chunk to read from the tuner.
 /// Increased to 256KB to handle BonDrivers (like FukuDLL) that may return
@@ -38,6 +57,9 @@ pub struct ReaderStartupConfig {
     pub set_channel_retry_timeout_ms: u64,
     pub signal_poll_interval_ms: u64,
     pub signal_wait_timeout_ms: u64,
+    pub align_ts_output: bool,
+    pub descramble_rekey_percent: u64,
+    pub descramble_rekey_window_secs: u64,
 }
 
 impl From<&TunerPoolConfig> for ReaderStartupConfig {
@@ -47,16 +69,101 @@ impl From<&TunerPoolConfig> for ReaderStartupConfig {
             set_channel_retry_timeout_ms: cfg.set_channel_retry_timeout_ms,
             signal_poll_interval_ms: cfg.signal_poll_interval_ms,
             signal_wait_timeout_ms: cfg.signal_wait_timeout_ms,
+            align_ts_output: cfg.align_ts_output,
+            descramble_rekey_percent: cfg.descramble_rekey_percent,
+            descramble_rekey_window_secs: cfg.descramble_rekey_window_secs,
         }
     }
 }
 
+impl ReaderStartupConfig {
+    /// B25 re-key policy, or None when disabled.
+    fn rekey_policy(&self) -> Option<RekeyPolicy> {
+        if self.descramble_rekey_percent == 0 || self.descramble_rekey_window_secs == 0 {
+            return None;
+        }
+        Some(RekeyPolicy {
+            scrambled_ratio: self.descramble_rekey_percent.min(100) as f64 / 100.0,
+            window: Duration::from_secs(self.descramble_rekey_window_secs),
+        })
+    }
+}
+
+/// What the reader does when a subscriber falls behind the broadcast buffer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LagPolicy {
+    /// Keep reading; a subscriber a whole buffer behind loses the oldest chunks.
+    #[default]
+    DropOldest,
+    /// Pace the reader so that no subscriber falls that far behind.
+    SlowProducer,
+}
+
+impl LagPolicy {
+    /// Parse a policy as stored in `bon_drivers.lag_policy`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "drop_oldest" => Some(Self::DropOldest),
+            "slow_producer" => Some(Self::SlowProducer),
+            _ => None,
+        }
+    }
+
+    /// Name stored in `bon_drivers.lag_policy`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DropOldest => "drop_oldest",
+            Self::SlowProducer => "slow_producer",
+        }
+    }
+}
+
+/// Broadcast buffer settings of a tuner, fixed when it is created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BroadcastSettings {
+    /// Chunks buffered for subscribers.
+    pub capacity: usize,
+    pub lag_policy: LagPolicy,
+}
+
+impl Default for BroadcastSettings {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_BROADCAST_CAPACITY,
+            lag_policy: LagPolicy::default(),
+        }
+    }
+}
+
+/// How long to wait for the reader thread to apply an LNB power change.
+const LNB_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// LNB power change handed to the reader thread, which owns the BonDriver.
+struct LnbCommand {
+    enable: bool,
+    reply: tokio::sync::oneshot::Sender<std::io::Result<()>>,
+}
+
 /// A shared tuner instance that can broadcast TS data to multiple clients.
 pub struct SharedTuner {
     /// The channel key identifying this tuner/channel combination.
     pub key: ChannelKey,
     /// Broadcast sender for TS data.
     tx: broadcast::Sender<Bytes>,
+    /// Buffer capacity and lag policy of `tx`.
+    broadcast_settings: BroadcastSettings,
+    /// Chunks sent on `tx`, compared with the lossless subscribers' cursors.
+    chunks_sent: AtomicU64,
+    /// Chunks received by each lossless subscriber.
+    lossless_cursors: std::sync::Mutex<Vec<Weak<AtomicU64>>>,
+    /// Set when pacing gave up on a stalled subscriber; cleared once the
+    /// backlog is below the high-water mark again.
+    pacing_suspended: AtomicBool,
+    /// Broadcast sender for the TS before B25 decoding (fed only while
+    /// someone listens).
+    raw_tx: broadcast::Sender<Bytes>,
+    /// [`DecodeStatus`] of the current reader, as u8.
+    decode_status: AtomicU8,
     /// Channel change notification sender.
     channel_change_tx: broadcast::Sender<()>,
     /// Reference count of active subscribers.
@@ -75,16 +182,66 @@ pub struct SharedTuner {
     packets_received: AtomicU64,
     /// TS quality analyzer (drop/scramble/error stats).
     quality_analyzer: tokio::sync::Mutex<TsPacketAnalyzer>,
+    /// B25 decoder re-keys triggered by sustained descramble failure.
+    descramble_rekeys: AtomicU64,
+    /// Attached server-owned sinks: id -> (name, stop signal).
+    sinks: std::sync::Mutex<HashMap<SinkId, (String, Arc<Notify>)>>,
+    /// Next sink ID.
+    next_sink_id: AtomicU64,
+    /// Sessions that asked for LNB power; the supply stays on while any remain.
+    lnb_holders: tokio::sync::Mutex<HashSet<u64>>,
+    /// Whether LNB power is currently on (re-applied when the reader restarts).
+    lnb_powered: AtomicBool,
+    /// Command channel into the running reader thread.
+    lnb_tx: std::sync::Mutex<Option<std::sync::mpsc::Sender<LnbCommand>>>,
+    /// Whether a passive scanner has been attached to this tuner.
+    passive_scan_attached: AtomicBool,
+    /// Reference point for `last_data_ms`.
+    clock_base: Instant,
+    /// When the reader last got TS from the driver, in ms since `clock_base`
+    /// (0 = nothing since the reader started).
+    last_data_ms: AtomicU64,
+    /// Incremented on every BonDriver reader start; a reader whose generation
+    /// is stale exits instead of competing with its replacement.
+    reader_generation: AtomicU64,
+    /// (tuner_path, space, channel) of the current BonDriver reader.
+    reader_params: std::sync::Mutex<Option<(String, u32, u32)>>,
+    /// Number of watchdog restarts of the reader.
+    reader_restarts: AtomicU64,
+    /// Why the reader was stopped to make room for another session.
+    preempt_reason: std::sync::Mutex<Option<String>>,
+    /// Recent (unix time ms, signal level) samples, oldest first.
+    signal_history: std::sync::Mutex<VecDeque<(i64, f32)>>,
+    /// Incremented on every signal sample; lets callers wait for one taken
+    /// after a given point without comparing wall-clock times.
+    signal_seq: AtomicU64,
 }
 
 impl SharedTuner {
     /// Create a new shared tuner with the given key.
     pub fn new(key: ChannelKey, bondriver_version: u8) -> Arc<Self> {
-        let (tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self::with_broadcast(key, bondriver_version, BroadcastSettings::default())
+    }
+
+    /// Create a new shared tuner with its own broadcast buffer settings.
+    pub fn with_broadcast(
+        key: ChannelKey,
+        bondriver_version: u8,
+        settings: BroadcastSettings,
+    ) -> Arc<Self> {
+        let capacity = settings.capacity.max(1);
+        let (tx, _) = broadcast::channel(capacity);
+        let (raw_tx, _) = broadcast::channel(capacity);
         let (channel_change_tx, _) = broadcast::channel(1); // Only need to notify once
         Arc::new(Self {
             key,
             tx,
+            broadcast_settings: settings,
+            chunks_sent: AtomicU64::new(0),
+            lossless_cursors: std::sync::Mutex::new(Vec::new()),
+            pacing_suspended: AtomicBool::new(false),
+            raw_tx,
+            decode_status: AtomicU8::new(DecodeStatus::NoCard as u8),
             channel_change_tx,
             subscriber_count: AtomicU32::new(0),
             is_running: AtomicBool::new(false),
@@ -94,6 +251,21 @@ impl SharedTuner {
             lock: TunerLock::new(),
             packets_received: AtomicU64::new(0),
             quality_analyzer: tokio::sync::Mutex::new(TsPacketAnalyzer::new()),
+            descramble_rekeys: AtomicU64::new(0),
+            sinks: std::sync::Mutex::new(HashMap::new()),
+            next_sink_id: AtomicU64::new(1),
+            lnb_holders: tokio::sync::Mutex::new(HashSet::new()),
+            lnb_powered: AtomicBool::new(false),
+            lnb_tx: std::sync::Mutex::new(None),
+            passive_scan_attached: AtomicBool::new(false),
+            clock_base: Instant::now(),
+            last_data_ms: AtomicU64::new(0),
+            reader_generation: AtomicU64::new(0),
+            reader_params: std::sync::Mutex::new(None),
+            reader_restarts: AtomicU64::new(0),
+            preempt_reason: std::sync::Mutex::new(None),
+            signal_history: std::sync::Mutex::new(VecDeque::new()),
+            signal_seq: AtomicU64::new(0),
         })
     }
 
@@ -132,7 +304,74 @@ impl SharedTuner {
     /// Get a snapshot of TS stream quality stats.
     pub async fn quality_snapshot(&self) -> TsStreamQuality {
         let analyzer = self.quality_analyzer.lock().await;
-        analyzer.snapshot()
+        let mut quality = analyzer.snapshot();
+        quality.descramble_rekeys = self.descramble_rekey_count();
+        quality
+    }
+
+    /// Record that the driver just delivered TS data.
+    fn mark_data_received(&self) {
+        // max(1) keeps 0 free for "no data yet"
+        let now = (self.clock_base.elapsed().as_millis() as u64).max(1);
+        self.last_data_ms.store(now, Ordering::Relaxed);
+    }
+
+    /// How long the reader has gone without TS data.
+    ///
+    /// None until the current reader has received its first chunk, so slow
+    /// startups are left to the reader's own startup handling.
+    pub fn stalled_for(&self) -> Option<Duration> {
+        match self.last_data_ms.load(Ordering::Relaxed) {
+            0 => None,
+            last => {
+                let now = self.clock_base.elapsed().as_millis() as u64;
+                Some(Duration::from_millis(now.saturating_sub(last)))
+            }
+        }
+    }
+
+    /// (tuner_path, space, channel) of the current BonDriver reader.
+    pub fn reader_params(&self) -> Option<(String, u32, u32)> {
+        self.reader_params.lock().unwrap().clone()
+    }
+
+    /// Restart the BonDriver reader on the same channel.
+    ///
+    /// Subscribers keep their broadcast receivers, so active sessions resume
+    /// as soon as the new reader delivers data; a channel change notification
+    /// makes them reset their stream state.
+    pub async fn restart_reader(self: &Arc<Self>, startup_config: ReaderStartupConfig) -> std::io::Result<()> {
+        let Some((tuner_path, space, channel)) = self.reader_params() else {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "reader was not started from a BonDriver"));
+        };
+        self.start_bondriver_reader(tuner_path, space, channel, startup_config).await?;
+        self.reader_restarts.fetch_add(1, Ordering::Relaxed);
+        self.notify_channel_change();
+        Ok(())
+    }
+
+    /// Number of times the reader was restarted on the same channel.
+    pub fn reader_restart_count(&self) -> u64 {
+        self.reader_restarts.load(Ordering::Relaxed)
+    }
+
+    /// Stop the reader to hand the tuner to another session.
+    ///
+    /// Sessions still attached see the reason through
+    /// [`preempt_reason`](Self::preempt_reason) once the reader has stopped.
+    pub async fn preempt_reader(&self, reason: impl Into<String>) {
+        *self.preempt_reason.lock().unwrap() = Some(reason.into());
+        self.stop_reader().await;
+    }
+
+    /// Why the reader was preempted, if it was.
+    pub fn preempt_reason(&self) -> Option<String> {
+        self.preempt_reason.lock().unwrap().clone()
+    }
+
+    /// Number of B25 re-keys triggered by sustained descramble failure.
+    pub fn descramble_rekey_count(&self) -> u64 {
+        self.descramble_rekeys.load(Ordering::Relaxed)
     }
 
     /// Wait for the first TS packet to arrive (indicating driver is ready).
@@ -175,6 +414,42 @@ impl SharedTuner {
         self.tx.subscribe()
     }
 
+    /// Receiver for the tuner's passive scanner, handed out only once.
+    ///
+    /// Unlike [`subscribe`](Self::subscribe) this does not count as a
+    /// subscriber, so the scanner never keeps an idle tuner open. Its
+    /// receiver closes when the tuner is dropped.
+    pub fn subscribe_passive(&self) -> Option<broadcast::Receiver<Bytes>> {
+        if self.passive_scan_attached.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        Some(self.tx.subscribe())
+    }
+
+    /// Receiver for the TS as read from the driver, before B25 decoding.
+    ///
+    /// This does not count as a subscriber; a session streaming raw TS
+    /// holds it alongside its [`subscribe`](Self::subscribe) receiver.
+    pub fn subscribe_raw(&self) -> broadcast::Receiver<Bytes> {
+        self.raw_tx.subscribe()
+    }
+
+    /// Send a chunk to raw subscribers, if there are any.
+    fn broadcast_raw(&self, raw: &[u8]) {
+        if self.raw_tx.receiver_count() > 0 {
+            let _ = self.raw_tx.send(Bytes::copy_from_slice(raw));
+        }
+    }
+
+    /// B25 decode state of the current reader.
+    pub fn decode_status(&self) -> DecodeStatus {
+        DecodeStatus::try_from(self.decode_status.load(Ordering::Relaxed)).unwrap_or(DecodeStatus::NoCard)
+    }
+
+    fn set_decode_status(&self, status: DecodeStatus) {
+        self.decode_status.store(status as u8, Ordering::Relaxed);
+    }
+
     /// Subscribe to channel change notifications.
     pub fn subscribe_channel_change(&self) -> broadcast::Receiver<()> {
         self.channel_change_tx.subscribe()
@@ -220,14 +495,260 @@ impl SharedTuner {
         self.subscriber_count.load(Ordering::SeqCst) > 0
     }
 
+    /// Attach a server-owned sink to the TS stream.
+    ///
+    /// The sink is fed from its own task and counts as a subscriber until it
+    /// is removed, returns `false` from `write`, or the tuner is dropped.
+    /// It stays attached across reader restarts and channel changes.
+    pub fn add_sink(self: &Arc<Self>, sink: Box<dyn TsSink>) -> SinkId {
+        self.attach_sink(sink, false)
+    }
+
+    /// Attach a sink that must not lose data, such as an archival recording.
+    ///
+    /// The reader is paced while the sink is behind, whatever the tuner's
+    /// [`LagPolicy`], and the sink's [`TsSink::retry_pending`] is polled
+    /// until its own queue has room again. Only a sink that stops making
+    /// progress for a while is dropped for.
+    pub fn add_lossless_sink(self: &Arc<Self>, sink: Box<dyn TsSink>) -> SinkId {
+        self.attach_sink(sink, true)
+    }
+
+    fn attach_sink(self: &Arc<Self>, mut sink: Box<dyn TsSink>, lossless: bool) -> SinkId {
+        let id = self.next_sink_id.fetch_add(1, Ordering::Relaxed);
+        let name = sink.name().to_string();
+        let stop = Arc::new(Notify::new());
+        self.sinks
+            .lock()
+            .unwrap()
+            .insert(id, (name.clone(), Arc::clone(&stop)));
+
+        let mut rx = self.subscribe();
+        let cursor = lossless.then(|| self.register_lossless_cursor());
+        // Weak so an attached sink does not keep the tuner alive on its own
+        let weak: Weak<Self> = Arc::downgrade(self);
+        info!(
+            "[SharedTuner] Sink '{}' (id={}{}) attached to {:?}",
+            name,
+            id,
+            if lossless { ", lossless" } else { "" },
+            self.key
+        );
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = stop.notified() => break,
+                    result = rx.recv() => match result {
+                        Ok(data) => {
+                            if !sink.write(&data) {
+                                debug!("[SharedTuner] Sink '{}' (id={}) requested detach", name, id);
+                                break;
+                            }
+                            if let Some(cursor) = &cursor {
+                                let started = tokio::time::Instant::now();
+                                let mut detach = false;
+                                while sink.retry_pending() {
+                                    if started.elapsed() >= LOSSLESS_WRITE_TIMEOUT {
+                                        warn!(
+                                            "[SharedTuner] Sink '{}' (id={}) could not write for {:?}, detaching",
+                                            name, id, LOSSLESS_WRITE_TIMEOUT
+                                        );
+                                        detach = true;
+                                        break;
+                                    }
+                                    tokio::select! {
+                                        _ = stop.notified() => {
+                                            detach = true;
+                                            break;
+                                        }
+                                        _ = tokio::time::sleep(LAG_PACE_INTERVAL) => {}
+                                    }
+                                }
+                                if detach {
+                                    break;
+                                }
+                                cursor.fetch_add(1, Ordering::Release);
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!("[SharedTuner] Sink '{}' (id={}) lagged, {} chunks dropped", name, id, n);
+                            if let Some(cursor) = &cursor {
+                                cursor.fetch_add(n, Ordering::Release);
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                }
+            }
+
+            sink.close();
+            if let Some(shared) = weak.upgrade() {
+                shared.sinks.lock().unwrap().remove(&id);
+                shared.unsubscribe();
+                info!("[SharedTuner] Sink '{}' (id={}) detached from {:?}", name, id, shared.key);
+            }
+        });
+
+        id
+    }
+
+    /// Detach a sink. Returns false if no sink with that ID is attached.
+    ///
+    /// The sink's task exits asynchronously; the subscriber count drops once
+    /// it has closed the sink.
+    pub fn remove_sink(&self, id: SinkId) -> bool {
+        match self.sinks.lock().unwrap().get(&id) {
+            Some((_, stop)) => {
+                stop.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Names of the attached sinks, ordered by ID.
+    pub fn sink_names(&self) -> Vec<(SinkId, String)> {
+        let mut names: Vec<(SinkId, String)> = self
+            .sinks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, (name, _))| (*id, name.clone()))
+            .collect();
+        names.sort_by_key(|(id, _)| *id);
+        names
+    }
+
     /// Get the current signal level.
     pub fn signal_level(&self) -> f32 {
         f32::from_bits(self.signal_level.load(Ordering::Relaxed))
     }
 
-    /// Set the current signal level.
+    /// Set the current signal level and record it in the history.
     pub fn set_signal_level(&self, level: f32) {
         self.signal_level.store(level.to_bits(), Ordering::Relaxed);
+        self.push_signal_sample(chrono::Utc::now().timestamp_millis(), level);
+        self.signal_seq.fetch_add(1, Ordering::Release);
+    }
+
+    fn push_signal_sample(&self, timestamp_ms: i64, level: f32) {
+        let cutoff = timestamp_ms - SIGNAL_HISTORY_WINDOW.as_millis() as i64;
+        let mut history = self.signal_history.lock().unwrap();
+        while history.front().is_some_and(|(t, _)| *t < cutoff) || history.len() >= SIGNAL_HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back((timestamp_ms, level));
+    }
+
+    /// Signal level samples (unix time ms, level) from the last hour,
+    /// oldest first.
+    pub fn signal_history(&self) -> Vec<(i64, f32)> {
+        self.signal_history.lock().unwrap().iter().copied().collect()
+    }
+
+    /// Wait for a signal sample taken after this call and return it.
+    ///
+    /// The reader samples once per second, so right after a channel change
+    /// the stored level may still belong to the previous channel. Falls back
+    /// to the latest level if no new sample arrives within `timeout_ms`.
+    pub async fn fresh_signal_level(&self, timeout_ms: u64) -> f32 {
+        let seen = self.signal_seq.load(Ordering::Acquire);
+        let start = std::time::Instant::now();
+        let timeout = Duration::from_millis(timeout_ms);
+        loop {
+            if self.signal_seq.load(Ordering::Acquire) > seen || start.elapsed() > timeout {
+                return self.signal_level();
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Whether LNB power is currently on.
+    pub fn lnb_powered(&self) -> bool {
+        self.lnb_powered.load(Ordering::Acquire)
+    }
+
+    /// Request or release LNB power on behalf of a session.
+    ///
+    /// The supply is switched on by the first session asking for it and off
+    /// when the last one releases it, so sessions sharing the tuner do not
+    /// toggle it under each other. Requires a running BonDriver reader.
+    pub async fn set_lnb_power(&self, session_id: u64, enable: bool) -> std::io::Result<()> {
+        let mut holders = self.lnb_holders.lock().await;
+        let was_on = !holders.is_empty();
+        if enable {
+            holders.insert(session_id);
+        } else {
+            holders.remove(&session_id);
+        }
+        let now_on = !holders.is_empty();
+        if was_on == now_on {
+            return Ok(());
+        }
+
+        match self.send_lnb_command(now_on).await {
+            Ok(()) => {
+                self.lnb_powered.store(now_on, Ordering::Release);
+                Ok(())
+            }
+            Err(e) if enable => {
+                holders.remove(&session_id);
+                Err(e)
+            }
+            Err(e) => {
+                // Nothing holds it any more; a stopped reader has no power to turn off.
+                debug!("[SharedTuner] LNB power off for {:?} not applied: {}", self.key, e);
+                self.lnb_powered.store(false, Ordering::Release);
+                Ok(())
+            }
+        }
+    }
+
+    /// Forward an LNB power change to the reader thread and wait for the result.
+    async fn send_lnb_command(&self, enable: bool) -> std::io::Result<()> {
+        let (reply, reply_rx) = tokio::sync::oneshot::channel();
+        let sent = self
+            .lnb_tx
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|tx| tx.send(LnbCommand { enable, reply }).is_ok())
+            .unwrap_or(false);
+        if !sent {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "BonDriver reader is not running",
+            ));
+        }
+        match tokio::time::timeout(LNB_COMMAND_TIMEOUT, reply_rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "BonDriver reader stopped",
+            )),
+            Err(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "Timeout waiting for LNB power change",
+            )),
+        }
+    }
+
+    /// Apply pending LNB power changes on the reader thread.
+    fn apply_lnb_commands(
+        shared: &SharedTuner,
+        tuner: &BonDriverTuner,
+        lnb_rx: &std::sync::mpsc::Receiver<LnbCommand>,
+    ) {
+        while let Ok(cmd) = lnb_rx.try_recv() {
+            let result = tuner.set_lnb_power(cmd.enable);
+            match &result {
+                Ok(()) => info!("[SharedTuner] LNB power {} for {:?}",
+                                if cmd.enable { "on" } else { "off" }, shared.key),
+                Err(e) => warn!("[SharedTuner] SetLnbPower({}) failed for {:?}: {}", cmd.enable, shared.key, e),
+            }
+            let _ = cmd.reply.send(result);
+        }
     }
 
     /// Start the tuner reader task.
@@ -270,6 +791,7 @@ impl SharedTuner {
                     }
                     Ok(n) => {
                         trace!("Read {} bytes from tuner {:?}", n, shared.key);
+                        shared.mark_data_received();
 
                         // Increment packet count (n / 188 packets)
                         let packet_count = (n / 188) as u64;
@@ -285,10 +807,16 @@ impl SharedTuner {
                             analyzer.analyze(&buf[..n]);
                         }
 
+                        shared.broadcast_raw(&buf[..n]);
                         let data = Bytes::copy_from_slice(&buf[..n]);
 
+                        let mut progress = None;
+                        while shared.should_pace(&mut progress) {
+                            tokio::time::sleep(LAG_PACE_INTERVAL).await;
+                        }
+
                         // Broadcast to all subscribers
-                        match shared.tx.send(data) {
+                        match shared.send_ts(data) {
                             Ok(count) => {
                                 trace!("Broadcast {} bytes to {} receivers", n, count);
                             }
@@ -349,6 +877,95 @@ impl SharedTuner {
         info!("[SharedTuner] Reader stopped for {:?}", self.key);
     }
 
+    /// Broadcast a TS chunk to subscribers.
+    ///
+    /// When an aligner is given, only whole sync-aligned packets are sent and
+    /// any partial packet is kept until the next chunk completes it.
+    /// Runs on the reader thread, which it blocks while pacing.
+    fn broadcast_ts(
+        &self,
+        aligner: &mut Option<TsPacketAligner>,
+        data: Bytes,
+    ) -> Result<usize, broadcast::error::SendError<Bytes>> {
+        let data = match aligner {
+            Some(aligner) => match aligner.push(data) {
+                Some(aligned) => aligned,
+                None => return Ok(self.tx.receiver_count()),
+            },
+            None => data,
+        };
+        let mut progress = None;
+        while self.should_pace(&mut progress) {
+            std::thread::sleep(LAG_PACE_INTERVAL);
+        }
+        self.send_ts(data)
+    }
+
+    fn send_ts(&self, data: Bytes) -> Result<usize, broadcast::error::SendError<Bytes>> {
+        self.chunks_sent.fetch_add(1, Ordering::AcqRel);
+        self.tx.send(data)
+    }
+
+    /// Track what a new lossless subscriber receives from now on.
+    fn register_lossless_cursor(&self) -> Arc<AtomicU64> {
+        let cursor = Arc::new(AtomicU64::new(self.chunks_sent.load(Ordering::Acquire)));
+        self.lossless_cursors.lock().unwrap().push(Arc::downgrade(&cursor));
+        cursor
+    }
+
+    /// Chunks the furthest-behind subscriber that is waited for has yet to
+    /// receive: any subscriber under [`LagPolicy::SlowProducer`], otherwise
+    /// only lossless sinks.
+    fn pacing_backlog(&self) -> usize {
+        let mut backlog = match self.broadcast_settings.lag_policy {
+            LagPolicy::SlowProducer => self.tx.len(),
+            LagPolicy::DropOldest => 0,
+        };
+        let sent = self.chunks_sent.load(Ordering::Acquire);
+        self.lossless_cursors.lock().unwrap().retain(|cursor| match cursor.upgrade() {
+            Some(cursor) => {
+                let behind = sent.saturating_sub(cursor.load(Ordering::Acquire));
+                backlog = backlog.max(behind as usize);
+                true
+            }
+            None => false,
+        });
+        backlog
+    }
+
+    /// Whether the reader should wait before sending the next chunk.
+    ///
+    /// Waits while the backlog is at 3/4 of the buffer. `progress` holds the
+    /// smallest backlog seen during this wait and when it was reached; if it
+    /// does not shrink for [`LAG_STALL_TIMEOUT`] the subscriber is given up
+    /// on until it catches up, so an abandoned receiver cannot stall the
+    /// tuner for everyone else.
+    fn should_pace(&self, progress: &mut Option<(usize, Instant)>) -> bool {
+        let backlog = self.pacing_backlog();
+        let high_water = (self.broadcast_settings.capacity * 3 / 4).max(1);
+        if backlog < high_water {
+            self.pacing_suspended.store(false, Ordering::Relaxed);
+            return false;
+        }
+        if self.pacing_suspended.load(Ordering::Relaxed) || !self.is_running.load(Ordering::Acquire) {
+            return false;
+        }
+        match progress {
+            Some((smallest, since)) if backlog >= *smallest => {
+                if since.elapsed() >= LAG_STALL_TIMEOUT {
+                    warn!(
+                        "[SharedTuner] Subscriber of {:?} stopped receiving ({} chunks behind), no longer pacing the reader for it",
+                        self.key, backlog
+                    );
+                    self.pacing_suspended.store(true, Ordering::Relaxed);
+                    return false;
+                }
+            }
+            _ => *progress = Some((backlog, Instant::now())),
+        }
+        true
+    }
+
     /// Set the reader task handle (used by warm start).
     pub async fn set_reader_handle(&self, handle: tokio::task::JoinHandle<()>) {
         *self.reader_handle.lock().await = Some(handle);
@@ -364,6 +981,9 @@ impl SharedTuner {
         ready_tx: tokio::sync::oneshot::Sender<Result<(), String>>,
     ) {
         shared.is_running.store(true, Ordering::Release);
+        let generation = shared.reader_generation.fetch_add(1, Ordering::AcqRel) + 1;
+        shared.last_data_ms.store(0, Ordering::Relaxed);
+        *shared.reader_params.lock().unwrap() = Some((tuner_path.clone(), space, channel));
         info!("[SharedTuner] Using BonDriver: {}", tuner_path);
 
         // Set channel with retry for network-latency environments
@@ -438,22 +1058,22 @@ impl SharedTuner {
         std::thread::sleep(std::time::Duration::from_millis(500));
 
         // ===== B25 decoder init =====
-        let b25_opt = DecoderOptions {
-            strip: true,
-            emm: true,
-            simd: true,
-            round: 4,
-            enable_working_key: false,
-        };
-
-        let mut b25 = match B25Pipe::new(b25_opt) {
-            Ok(d) => {
+        let mut b25 = match B25Pipe::new(stream_decoder_options()) {
+            Ok(mut d) => {
                 info!("[SharedTuner] B25 decoder enabled");
+                shared.set_decode_status(DecodeStatus::Active);
+                let policy = startup_config.rekey_policy();
+                if let Some(p) = &policy {
+                    debug!("[SharedTuner] Descramble re-key: >= {:.0}% scrambled for {}s",
+                           p.scrambled_ratio * 100.0, p.window.as_secs());
+                }
+                d.set_rekey_policy(policy);
                 Some(d)
             }
             Err(e) => {
                 error!("[SharedTuner] Failed to init B25 decoder: {}", e);
                 error!("[SharedTuner] Falling back to raw TS streaming");
+                shared.set_decode_status(DecodeStatus::NoCard);
                 None
             }
         };
@@ -489,10 +1109,25 @@ impl SharedTuner {
         let mut total_bytes_read = 0u64;
         let mut last_log_time = std::time::Instant::now();
         let mut last_status_log = std::time::Instant::now();
+        let mut last_signal_sample = std::time::Instant::now();
         let mut reader_first_read = true;
         let reader_start_time = std::time::Instant::now();
         let mut broadcast_send_errors: u64 = 0;
         let mut logo_collector = ChannelLogoCollector::new();
+        let mut aligner = startup_config.align_ts_output.then(TsPacketAligner::new);
+        if aligner.is_some() {
+            info!("[SharedTuner] TS output alignment enabled (188-byte packets)");
+        }
+
+        // LNB power changes are applied here since this thread owns the tuner
+        let (lnb_tx, lnb_rx) = std::sync::mpsc::channel::<LnbCommand>();
+        *shared.lnb_tx.lock().unwrap() = Some(lnb_tx);
+        if shared.lnb_powered() {
+            // Restarted reader: keep the supply the sessions asked for
+            if let Err(e) = tuner.set_lnb_power(true) {
+                warn!("[SharedTuner] Failed to restore LNB power for {:?}: {}", shared.key, e);
+            }
+        }
 
         loop {
             // Check if we should stop due to explicit stop signal
@@ -500,10 +1135,24 @@ impl SharedTuner {
                 info!("[SharedTuner] BREAK: Stop signal received for {:?}", shared.key);
                 break;
             }
+            // A replacement reader took over while this one was stuck in the driver
+            if shared.reader_generation.load(Ordering::Acquire) != generation {
+                warn!("[SharedTuner] BREAK: Reader for {:?} was replaced", shared.key);
+                break;
+            }
+
+            SharedTuner::apply_lnb_commands(&shared, &tuner, &lnb_rx);
+
+            // Sample the signal for the history. Kept current while no data
+            // arrives so a stall can be told apart from a channel without signal
+            if last_signal_sample.elapsed() >= SIGNAL_SAMPLE_INTERVAL {
+                shared.set_signal_level(tuner.get_signal_level());
+                last_signal_sample = std::time::Instant::now();
+            }
 
             // Log status every 5 seconds for debugging
             if last_status_log.elapsed().as_secs() >= 5 {
-                let level = tuner.get_signal_level();
+                let level = shared.signal_level();
                 info!("[SharedTuner] LOOP_STATUS: total_bytes={}, consecutive_empty={}, signal={:.1}dB, subscribers={}, is_running={}, elapsed={}s",
                       total_bytes_read, consecutive_empty, level, shared.subscriber_count(), shared.is_running.load(Ordering::Acquire), reader_start_time.elapsed().as_secs());
                 last_status_log = std::time::Instant::now();
@@ -585,12 +1234,14 @@ impl SharedTuner {
                     }
                     consecutive_empty = 0;
                     total_bytes_read += n as u64;
+                    shared.mark_data_received();
 
                     // Broadcast to all subscribers
                     let raw = &buf[..n];
 
                     // Best-effort logo extraction from SDT/CDT stream.
                     logo_collector.process_ts_chunk(raw);
+                    shared.broadcast_raw(raw);
 
                     // Data validation before B25 decode (log only on first packet)
                     if reader_first_read && n > 0 {
@@ -608,6 +1259,10 @@ impl SharedTuner {
 
                             match push_result {
                                 Ok(Ok(decoded)) => {
+                                    let rekeys = b25_decoder.rekey_count();
+                                    if rekeys != shared.descramble_rekeys.load(Ordering::Relaxed) {
+                                        shared.descramble_rekeys.store(rekeys, Ordering::Relaxed);
+                                    }
                                     if decoded.is_empty() {
                                         consecutive_b25_errors = 0;
                                         continue;
@@ -622,7 +1277,7 @@ impl SharedTuner {
 
                                     let data = Bytes::from(decoded);
 
-                                    match shared.tx.send(data) {
+                                    match shared.broadcast_ts(&mut aligner, data) {
                                         Ok(_count) => {}
                                         Err(_e) => {
                                             broadcast_send_errors += 1;
@@ -643,6 +1298,7 @@ impl SharedTuner {
                                     if consecutive_b25_errors >= 10 {
                                         error!("[SharedTuner] Too many B25 errors, resetting decoder");
                                         b25_needs_reset = true;
+                                        shared.set_decode_status(DecodeStatus::Error);
                                     }
 
                                     let packet_count = (n / 188) as u64;
@@ -650,11 +1306,12 @@ impl SharedTuner {
                                         shared.increment_packet_count(packet_count);
                                     }
                                     let data = Bytes::copy_from_slice(raw);
-                                    let _ = shared.tx.send(data);
+                                    let _ = shared.broadcast_ts(&mut aligner, data);
                                 }
                                 Err(_panic_err) => {
                                     error!("[SharedTuner] PANIC in B25 decoder push - disabling decoder and falling back to raw TS");
                                     b25_needs_reset = true;
+                                    shared.set_decode_status(DecodeStatus::Error);
 
                                     // Fall back to raw TS
                                     let packet_count = (n / 188) as u64;
@@ -662,7 +1319,7 @@ impl SharedTuner {
                                         shared.increment_packet_count(packet_count);
                                     }
                                     let data = Bytes::copy_from_slice(raw);
-                                    let _ = shared.tx.send(data);
+                                    let _ = shared.broadcast_ts(&mut aligner, data);
                                 }
                             }
                         } else {
@@ -672,7 +1329,7 @@ impl SharedTuner {
                                 shared.increment_packet_count(packet_count);
                             }
                             let data = Bytes::copy_from_slice(raw);
-                            let _ = shared.tx.send(data);
+                            let _ = shared.broadcast_ts(&mut aligner, data);
                         }
                     } else {
                         // No B25 decoder, use raw TS
@@ -681,13 +1338,12 @@ impl SharedTuner {
                             shared.increment_packet_count(packet_count);
                         }
                         let data = Bytes::copy_from_slice(raw);
-                        let _ = shared.tx.send(data);
+                        let _ = shared.broadcast_ts(&mut aligner, data);
                     }
 
-                    // Update signal level and log periodically
+                    // Log periodically
                     if last_log_time.elapsed().as_secs() >= 5 {
-                        let level = tuner.get_signal_level();
-                        shared.set_signal_level(level);
+                        let level = shared.signal_level();
                         info!("[SharedTuner] {:?}: {} bytes sent, signal={:.1}dB",
                               shared.key, total_bytes_read, level);
                         last_log_time = std::time::Instant::now();
@@ -731,7 +1387,11 @@ impl SharedTuner {
             }
         }
 
-        shared.is_running.store(false, Ordering::Release);
+        // Leave the flags alone if a replacement reader already owns them
+        if shared.reader_generation.load(Ordering::Acquire) == generation {
+            *shared.lnb_tx.lock().unwrap() = None;
+            shared.is_running.store(false, Ordering::Release);
+        }
         info!("[SharedTuner] Reader task stopped for {:?}, total bytes: {}", shared.key, total_bytes_read);
     }
 
@@ -769,6 +1429,7 @@ impl SharedTuner {
             info!("[SharedTuner] Old reader fully stopped, starting new reader for {:?}", self.key);
         }
 
+        *self.preempt_reason.lock().unwrap() = None;
         let shared = Arc::clone(self);
         info!("[SharedTuner] Starting BonDriver reader for {:?}", self.key);
 
@@ -868,6 +1529,12 @@ impl SharedTuner {
     pub fn is_running(&self) -> bool {
         self.is_running.load(Ordering::Acquire)
     }
+
+    /// Mark the reader as running without opening a driver.
+    #[cfg(test)]
+    pub(crate) fn set_running_for_test(&self) {
+        self.is_running.store(true, Ordering::Release);
+    }
 }
 
 /// Helper function to poll an AsyncBufRead as a future.
@@ -908,6 +1575,226 @@ mod tests {
         assert_eq!(shared.subscriber_count(), 1);
     }
 
+    #[tokio::test]
+    async fn test_lnb_power_shared_between_sessions() {
+        let shared = SharedTuner::new(ChannelKey::simple("/dev/pt3video0", 13), 2);
+
+        // No reader yet: the request fails and leaves no hold behind
+        assert!(shared.set_lnb_power(1, true).await.is_err());
+        assert!(!shared.lnb_powered());
+
+        // Stand-in reader thread that records the commands it receives
+        let (tx, rx) = std::sync::mpsc::channel::<LnbCommand>();
+        *shared.lnb_tx.lock().unwrap() = Some(tx);
+        let applied = std::thread::spawn(move || {
+            let mut applied = Vec::new();
+            while let Ok(cmd) = rx.recv() {
+                applied.push(cmd.enable);
+                let _ = cmd.reply.send(Ok(()));
+            }
+            applied
+        });
+
+        shared.set_lnb_power(1, true).await.unwrap();
+        shared.set_lnb_power(2, true).await.unwrap();
+        shared.set_lnb_power(1, false).await.unwrap();
+        assert!(shared.lnb_powered());
+        shared.set_lnb_power(2, false).await.unwrap();
+        assert!(!shared.lnb_powered());
+
+        *shared.lnb_tx.lock().unwrap() = None;
+        assert_eq!(applied.join().unwrap(), [true, false]);
+    }
+
+    struct CollectSink {
+        data: Arc<std::sync::Mutex<Vec<u8>>>,
+        closed: Arc<AtomicBool>,
+        limit: usize,
+    }
+
+    impl TsSink for CollectSink {
+        fn name(&self) -> &str {
+            "collect"
+        }
+
+        fn write(&mut self, data: &Bytes) -> bool {
+            let mut buf = self.data.lock().unwrap();
+            buf.extend_from_slice(data);
+            buf.len() < self.limit
+        }
+
+        fn close(&mut self) {
+            self.closed.store(true, Ordering::SeqCst);
+        }
+    }
+
+    async fn wait_until(cond: impl Fn() -> bool) {
+        for _ in 0..100 {
+            if cond() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("condition not met");
+    }
+
+    #[tokio::test]
+    async fn test_sink_counts_as_subscriber() {
+        let shared = SharedTuner::new(ChannelKey::simple("/dev/pt3video0", 13), 2);
+        let data = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let id = shared.add_sink(Box::new(CollectSink {
+            data: Arc::clone(&data),
+            closed: Arc::clone(&closed),
+            limit: usize::MAX,
+        }));
+        assert_eq!(shared.subscriber_count(), 1);
+        assert_eq!(shared.sink_names(), vec![(id, "collect".to_string())]);
+
+        shared.tx.send(Bytes::from_static(&[0x47, 1, 2])).unwrap();
+        wait_until(|| data.lock().unwrap().len() == 3).await;
+
+        assert!(shared.remove_sink(id));
+        wait_until(|| shared.subscriber_count() == 0).await;
+        assert!(closed.load(Ordering::SeqCst));
+        assert!(shared.sink_names().is_empty());
+        assert!(!shared.remove_sink(id));
+    }
+
+    /// A lossless sink whose writer never catches up.
+    struct StuckSink {
+        closed: Arc<AtomicBool>,
+    }
+
+    impl TsSink for StuckSink {
+        fn name(&self) -> &str {
+            "stuck"
+        }
+
+        fn write(&mut self, _data: &Bytes) -> bool {
+            true
+        }
+
+        fn retry_pending(&mut self) -> bool {
+            true
+        }
+
+        fn close(&mut self) {
+            self.closed.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stuck_lossless_sink_detaches() {
+        let shared = SharedTuner::new(ChannelKey::simple("/dev/pt3video0", 13), 2);
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let id = shared.add_lossless_sink(Box::new(StuckSink { closed: Arc::clone(&closed) }));
+        shared.tx.send(Bytes::from_static(&[0x47, 1, 2])).unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // Removing it interrupts the retry loop
+        assert!(shared.remove_sink(id));
+        wait_until(|| closed.load(Ordering::SeqCst)).await;
+        wait_until(|| shared.subscriber_count() == 0).await;
+        assert!(shared.sink_names().is_empty());
+    }
+
+    #[test]
+    fn test_passive_subscriber_not_counted() {
+        let shared = SharedTuner::new(ChannelKey::simple("/dev/pt3video0", 13), 2);
+
+        let mut rx = shared.subscribe_passive().expect("first passive receiver");
+        assert!(shared.subscribe_passive().is_none());
+        assert_eq!(shared.subscriber_count(), 0);
+
+        shared.tx.send(Bytes::from_static(&[0x47, 1, 2])).unwrap();
+        assert_eq!(rx.try_recv().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_sink_detaches_itself() {
+        let shared = SharedTuner::new(ChannelKey::simple("/dev/pt3video0", 13), 2);
+        let data = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let closed = Arc::new(AtomicBool::new(false));
+
+        shared.add_sink(Box::new(CollectSink {
+            data: Arc::clone(&data),
+            closed: Arc::clone(&closed),
+            limit: 2,
+        }));
+        let _client = shared.subscribe();
+        assert_eq!(shared.subscriber_count(), 2);
+
+        shared.tx.send(Bytes::from_static(&[0x47, 1, 2])).unwrap();
+        wait_until(|| closed.load(Ordering::SeqCst)).await;
+        wait_until(|| shared.subscriber_count() == 1).await;
+    }
+
+    #[test]
+    fn test_lag_policy_pacing() {
+        let chunk = Bytes::from_static(&[0x47, 1, 2]);
+        let settings = BroadcastSettings { capacity: 16, lag_policy: LagPolicy::DropOldest };
+        let dropping = SharedTuner::with_broadcast(ChannelKey::simple("/dev/pt3video0", 13), 2, settings);
+        dropping.is_running.store(true, Ordering::Release);
+        let _rx = dropping.subscribe();
+        for _ in 0..12 {
+            dropping.send_ts(chunk.clone()).unwrap();
+        }
+        assert!(!dropping.should_pace(&mut None));
+
+        let settings = BroadcastSettings { lag_policy: LagPolicy::SlowProducer, ..settings };
+        let paced = SharedTuner::with_broadcast(ChannelKey::simple("/dev/pt3video0", 13), 2, settings);
+        paced.is_running.store(true, Ordering::Release);
+        let mut rx = paced.subscribe();
+        for _ in 0..12 {
+            paced.send_ts(chunk.clone()).unwrap();
+        }
+        // 3/4 of the buffer is waiting for the receiver
+        assert!(paced.should_pace(&mut None));
+        rx.try_recv().unwrap();
+        assert!(!paced.should_pace(&mut None));
+
+        // A receiver that makes no progress is eventually given up on
+        paced.send_ts(chunk.clone()).unwrap();
+        let mut progress = Some((12, Instant::now() - LAG_STALL_TIMEOUT));
+        assert!(!paced.should_pace(&mut progress));
+        assert!(!paced.should_pace(&mut None));
+        assert!(paced.pacing_suspended.load(Ordering::Relaxed));
+        rx.try_recv().unwrap();
+        assert!(!paced.should_pace(&mut None));
+        assert!(!paced.pacing_suspended.load(Ordering::Relaxed));
+        assert_eq!(paced.broadcast_settings.lag_policy.name(), "slow_producer");
+        assert_eq!(LagPolicy::from_name("Drop_Oldest"), Some(LagPolicy::DropOldest));
+        assert_eq!(LagPolicy::from_name("block"), None);
+    }
+
+    #[test]
+    fn test_lossless_cursor_paces_reader() {
+        let shared = SharedTuner::with_broadcast(
+            ChannelKey::simple("/dev/pt3video0", 13),
+            2,
+            BroadcastSettings { capacity: 16, lag_policy: LagPolicy::DropOldest },
+        );
+        shared.is_running.store(true, Ordering::Release);
+        let cursor = shared.register_lossless_cursor();
+        for _ in 0..12 {
+            shared.send_ts(Bytes::from_static(&[0x47, 1, 2])).unwrap_or(0);
+        }
+        assert_eq!(shared.pacing_backlog(), 12);
+        assert!(shared.should_pace(&mut None));
+
+        cursor.fetch_add(4, Ordering::Release);
+        assert!(!shared.should_pace(&mut None));
+
+        // Dropped cursors no longer hold the reader back
+        cursor.fetch_sub(4, Ordering::Release);
+        drop(cursor);
+        assert_eq!(shared.pacing_backlog(), 0);
+        assert!(shared.lossless_cursors.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_signal_level() {
         let key = ChannelKey::simple("/dev/pt3video0", 13);
@@ -915,5 +1802,38 @@ mod tests {
 
         shared.set_signal_level(23.5);
         assert!((shared.signal_level() - 23.5).abs() < 0.001);
+        assert_eq!(shared.signal_history().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fresh_signal_level_waits_for_new_sample() {
+        let shared = Arc::new(SharedTuner::new(ChannelKey::simple("/dev/pt3video0", 13), 2));
+        shared.set_signal_level(30.0);
+
+        // Without a new sample the stale level is returned after the timeout
+        assert!((shared.fresh_signal_level(100).await - 30.0).abs() < 0.001);
+
+        let writer = Arc::clone(&shared);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            writer.set_signal_level(4.5);
+        });
+        assert!((shared.fresh_signal_level(2_000).await - 4.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_signal_history_is_bounded() {
+        let shared = SharedTuner::new(ChannelKey::simple("/dev/pt3video0", 13), 2);
+        let base = 1_700_000_000_000;
+        for i in 0..(SIGNAL_HISTORY_CAPACITY as i64 + 10) {
+            shared.push_signal_sample(base + i * 1000, 20.0);
+        }
+        let history = shared.signal_history();
+        assert_eq!(history.len(), SIGNAL_HISTORY_CAPACITY);
+        assert_eq!(history[0].0, base + 10_000);
+
+        // Samples older than the window are dropped even below capacity
+        shared.push_signal_sample(base + 10 * 3_600_000, 18.5);
+        assert_eq!(shared.signal_history(), vec![(base + 10 * 3_600_000, 18.5)]);
     }
 }
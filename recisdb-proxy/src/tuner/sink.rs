@@ -0,0 +1,37 @@
+//! Server-owned TS stream consumers.
+//!
+//! A [`TsSink`] receives the same TS chunks as client subscribers of a
+//! [`SharedTuner`](crate::tuner::SharedTuner) (recorders, analyzers, caption
+//! extractors, ...). Sinks are attached with `SharedTuner::add_sink` and count
+//! as subscribers, so the tuner is not idle-closed while a sink is active.
+
+use bytes::Bytes;
+
+/// Identifier of a sink attached to a `SharedTuner`.
+pub type SinkId = u64;
+
+/// A consumer of a tuner's TS stream.
+///
+/// `write` runs on the sink's own task, but it should still return quickly:
+/// a slow sink falls behind the broadcast buffer and misses chunks, just like
+/// a slow client. Hand heavy work (disk I/O, parsing) off to another task.
+/// Sinks attached with `SharedTuner::add_lossless_sink` instead hold the
+/// reader back while they are behind, see [`TsSink::retry_pending`].
+pub trait TsSink: Send + 'static {
+    /// Short name used in logs and status output.
+    fn name(&self) -> &str;
+
+    /// Consume one TS chunk. Return `false` to detach the sink.
+    fn write(&mut self, data: &Bytes) -> bool;
+
+    /// Retry handing off data that `write` had to hold back.
+    ///
+    /// Only called for lossless sinks, after each `write` and until it
+    /// returns `false` (nothing left pending).
+    fn retry_pending(&mut self) -> bool {
+        false
+    }
+
+    /// Called once after the sink is detached, for any reason.
+    fn close(&mut self) {}
+}
@@ -0,0 +1,138 @@
+//! Optional 188-byte packet alignment for the broadcast TS stream.
+//!
+//! BonDriver reads are not guaranteed to start or end on a packet boundary.
+//! [`TsPacketAligner`] buffers partial packets between chunks and only emits
+//! whole packets starting at a sync byte, so clients don't have to resync.
+
+use bytes::Bytes;
+use log::warn;
+
+use crate::tuner::b25_pipe::{find_ts_sync_offset, RESYNC_CHECK_PACKETS};
+use crate::tuner::ts_analyzer::SyncLossLogThrottle;
+
+const TS_PACKET_SIZE: usize = 188;
+const TS_SYNC_BYTE: u8 = 0x47;
+
+/// Re-chunks a TS byte stream into whole, sync-aligned 188-byte packets.
+#[derive(Debug, Default)]
+pub struct TsPacketAligner {
+    /// Bytes not yet emitted (partial packet or data awaiting sync).
+    carry: Vec<u8>,
+    /// Total bytes discarded while searching for sync.
+    bytes_dropped: u64,
+    sync_log: SyncLossLogThrottle,
+}
+
+impl TsPacketAligner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total bytes discarded while searching for sync.
+    pub fn bytes_dropped(&self) -> u64 {
+        self.bytes_dropped
+    }
+
+    /// Feed a chunk and return the whole packets that are ready, if any.
+    ///
+    /// Already-aligned chunks are passed through without copying.
+    pub fn push(&mut self, data: Bytes) -> Option<Bytes> {
+        if self.carry.is_empty()
+            && data.first() == Some(&TS_SYNC_BYTE)
+            && data.len().is_multiple_of(TS_PACKET_SIZE)
+        {
+            return Some(data);
+        }
+
+        self.carry.extend_from_slice(&data);
+
+        // Realign if the buffered data doesn't start on a sync byte
+        while self.carry.first().is_some_and(|b| *b != TS_SYNC_BYTE) {
+            if let Some(off) = find_ts_sync_offset(&self.carry) {
+                self.drop_front(off);
+            } else if self.carry.len() >= TS_PACKET_SIZE * (RESYNC_CHECK_PACKETS + 1) {
+                // No sync phase within the first packet; skip it and keep searching
+                self.drop_front(TS_PACKET_SIZE);
+            } else {
+                // Not enough data to find sync yet
+                return None;
+            }
+        }
+
+        let full_len = (self.carry.len() / TS_PACKET_SIZE) * TS_PACKET_SIZE;
+        if full_len == 0 {
+            return None;
+        }
+
+        let rest = self.carry.split_off(full_len);
+        let out = std::mem::replace(&mut self.carry, rest);
+        Some(Bytes::from(out))
+    }
+
+    /// Discard partially buffered data (e.g. after a channel change).
+    pub fn reset(&mut self) {
+        self.carry.clear();
+    }
+
+    fn drop_front(&mut self, len: usize) {
+        self.carry.drain(..len);
+        self.bytes_dropped += len as u64;
+        if let Some(summary) = self.sync_log.record(len as u64) {
+            warn!(
+                "[SharedTuner] Output alignment: {} resyncs dropped {} bytes in {:.1}s (total dropped={})",
+                summary.events,
+                summary.bytes_dropped,
+                summary.elapsed.as_secs_f64(),
+                self.bytes_dropped
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packets(count: usize) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(count * TS_PACKET_SIZE);
+        for i in 0..count {
+            buf.push(TS_SYNC_BYTE);
+            buf.extend(std::iter::repeat_n(i as u8, TS_PACKET_SIZE - 1));
+        }
+        buf
+    }
+
+    #[test]
+    fn test_aligned_chunk_passes_through() {
+        let mut aligner = TsPacketAligner::new();
+        let data = Bytes::from(packets(3));
+        let out = aligner.push(data.clone()).unwrap();
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn test_split_packet_is_completed() {
+        let mut aligner = TsPacketAligner::new();
+        let stream = packets(4);
+
+        let first = aligner.push(Bytes::copy_from_slice(&stream[..300])).unwrap();
+        assert_eq!(first.len(), TS_PACKET_SIZE);
+
+        let second = aligner.push(Bytes::copy_from_slice(&stream[300..])).unwrap();
+        assert_eq!(second.len(), TS_PACKET_SIZE * 3);
+        assert_eq!(second[0], TS_SYNC_BYTE);
+        assert_eq!(&second[..], &stream[TS_PACKET_SIZE..]);
+    }
+
+    #[test]
+    fn test_leading_garbage_is_dropped() {
+        let mut aligner = TsPacketAligner::new();
+        let mut stream = vec![0xFFu8; 10];
+        stream.extend(packets(RESYNC_CHECK_PACKETS + 1));
+
+        let out = aligner.push(Bytes::from(stream)).unwrap();
+        assert_eq!(out.len(), TS_PACKET_SIZE * (RESYNC_CHECK_PACKETS + 1));
+        assert_eq!(out[0], TS_SYNC_BYTE);
+        assert_eq!(aligner.bytes_dropped(), 10);
+    }
+}
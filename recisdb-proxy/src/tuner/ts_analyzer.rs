@@ -1,9 +1,109 @@
 //! TS packet quality analyzer.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
+use log::warn;
+use serde::Serialize;
+
+use crate::ts_analyzer::{PatTable, PmtTable, PsiSection, SectionCollector, TsPacket};
 use crate::tuner::ts_parser::{SYNC_BYTE, TS_PACKET_SIZE};
 
+/// PID of the PAT.
+const PAT_PID: u16 = 0x0000;
+/// PID of null packets.
+const NULL_PID: u16 = 0x1FFF;
+
+/// Default interval between sync-loss log lines.
+pub const DEFAULT_SYNC_LOSS_LOG_INTERVAL_SECS: u64 = 10;
+
+/// Process-wide sync-loss log interval in milliseconds (0 = log every event).
+static SYNC_LOSS_LOG_INTERVAL_MS: AtomicU64 =
+    AtomicU64::new(DEFAULT_SYNC_LOSS_LOG_INTERVAL_SECS * 1000);
+
+/// Set the interval used by newly created [`SyncLossLogThrottle`]s.
+pub fn set_sync_loss_log_interval(interval: Duration) {
+    SYNC_LOSS_LOG_INTERVAL_MS.store(interval.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Get the configured sync-loss log interval.
+pub fn sync_loss_log_interval() -> Duration {
+    Duration::from_millis(SYNC_LOSS_LOG_INTERVAL_MS.load(Ordering::Relaxed))
+}
+
+/// Summary of sync-loss events accumulated since the previous log line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncLossSummary {
+    /// Number of sync-loss events since the previous log line.
+    pub events: u64,
+    /// Number of bytes discarded by those events.
+    pub bytes_dropped: u64,
+    /// Time covered by this summary.
+    pub elapsed: Duration,
+}
+
+/// Rate limiter for sync-loss / resync log lines.
+///
+/// Events are always accumulated; `record` only returns a summary when the
+/// first event occurs or when the interval has elapsed since the last line.
+#[derive(Debug)]
+pub struct SyncLossLogThrottle {
+    interval: Duration,
+    last_log: Option<Instant>,
+    pending_events: u64,
+    pending_bytes: u64,
+}
+
+impl Default for SyncLossLogThrottle {
+    fn default() -> Self {
+        Self::new(sync_loss_log_interval())
+    }
+}
+
+impl SyncLossLogThrottle {
+    /// Create a throttle with the given interval.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_log: None,
+            pending_events: 0,
+            pending_bytes: 0,
+        }
+    }
+
+    /// Record one sync-loss event and return a summary if a log line is due.
+    pub fn record(&mut self, bytes_dropped: u64) -> Option<SyncLossSummary> {
+        self.record_at(bytes_dropped, Instant::now())
+    }
+
+    fn record_at(&mut self, bytes_dropped: u64, now: Instant) -> Option<SyncLossSummary> {
+        self.pending_events += 1;
+        self.pending_bytes += bytes_dropped;
+
+        let elapsed = match self.last_log {
+            Some(last) => {
+                let elapsed = now.saturating_duration_since(last);
+                if elapsed < self.interval {
+                    return None;
+                }
+                elapsed
+            }
+            None => Duration::ZERO,
+        };
+
+        let summary = SyncLossSummary {
+            events: self.pending_events,
+            bytes_dropped: self.pending_bytes,
+            elapsed,
+        };
+        self.last_log = Some(now);
+        self.pending_events = 0;
+        self.pending_bytes = 0;
+        Some(summary)
+    }
+}
+
 /// Quality counters for TS stream.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct TsStreamQuality {
@@ -11,6 +111,9 @@ pub struct TsStreamQuality {
     pub packets_dropped: u64,
     pub packets_scrambled: u64,
     pub packets_error: u64,
+    pub sync_losses: u64,
+    /// B25 decoder re-keys after sustained descramble failure (filled by SharedTuner).
+    pub descramble_rekeys: u64,
 }
 
 /// Delta counters for a single analyze call.
@@ -20,6 +123,96 @@ pub struct TsStreamQualityDelta {
     pub packets_dropped: u64,
     pub packets_scrambled: u64,
     pub packets_error: u64,
+    pub sync_losses: u64,
+}
+
+/// Per-PID packet counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PidStat {
+    pub packets: u64,
+    /// Continuity-counter discontinuities.
+    pub discontinuities: u64,
+    pub scrambled: u64,
+    /// Packets with the transport error indicator set.
+    pub errors: u64,
+    /// Whether a PMT lists this PID as an elementary stream or PCR PID.
+    pub in_pmt: bool,
+}
+
+impl PidStat {
+    /// Whether any discontinuity, scrambled or error packet was seen.
+    pub fn has_problems(&self) -> bool {
+        self.discontinuities > 0 || self.scrambled > 0 || self.errors > 0
+    }
+}
+
+/// PAT/PMT tracking used to tell declared PIDs from stray ones.
+#[derive(Debug, Default)]
+struct PmtTracker {
+    pat_collector: SectionCollector,
+    /// PMT PID -> section collector, as listed in the latest PAT.
+    pmt_collectors: HashMap<u16, SectionCollector>,
+    /// PMT PID -> PIDs (elementary streams and PCR) declared by that PMT.
+    declared: HashMap<u16, Vec<u16>>,
+}
+
+impl PmtTracker {
+    fn process_packet(&mut self, packet: &[u8]) {
+        let Ok(packet) = TsPacket::parse(packet) else {
+            return;
+        };
+        let header = &packet.header;
+        if header.transport_error || header.is_scrambled() || !header.has_payload() {
+            return;
+        }
+
+        let collector = if header.pid == PAT_PID {
+            &mut self.pat_collector
+        } else if let Some(collector) = self.pmt_collectors.get_mut(&header.pid) {
+            collector
+        } else {
+            return;
+        };
+        if !collector.add_data(packet.payload, header.continuity_counter, header.payload_unit_start) {
+            return;
+        }
+        let Some(section_data) = collector.get_section().map(|s| s.to_vec()) else {
+            return;
+        };
+        collector.clear();
+
+        let Ok(section) = PsiSection::parse(&section_data) else {
+            return;
+        };
+        if header.pid == PAT_PID {
+            if let Ok(pat) = PatTable::parse(&section) {
+                self.update_pat(&pat);
+            }
+        } else if let Ok(pmt) = PmtTable::parse(&section) {
+            let mut pids: Vec<u16> = pmt.streams.iter().map(|s| s.elementary_pid).collect();
+            if pmt.pcr_pid != NULL_PID {
+                pids.push(pmt.pcr_pid);
+            }
+            self.declared.insert(header.pid, pids);
+        }
+    }
+
+    fn update_pat(&mut self, pat: &PatTable) {
+        let pmt_pids: HashSet<u16> = pat
+            .programs
+            .iter()
+            .map(|p| p.pid)
+            .collect();
+        self.pmt_collectors.retain(|pid, _| pmt_pids.contains(pid));
+        self.declared.retain(|pid, _| pmt_pids.contains(pid));
+        for pid in pmt_pids {
+            self.pmt_collectors.entry(pid).or_default();
+        }
+    }
+
+    fn is_declared(&self, pid: u16) -> bool {
+        self.declared.values().any(|pids| pids.contains(&pid))
+    }
 }
 
 /// TS packet analyzer for continuity and error tracking.
@@ -27,6 +220,9 @@ pub struct TsStreamQualityDelta {
 pub struct TsPacketAnalyzer {
     last_cc: HashMap<u16, u8>,
     quality: TsStreamQuality,
+    sync_loss_log: SyncLossLogThrottle,
+    pids: HashMap<u16, PidStat>,
+    pmt: PmtTracker,
 }
 
 impl TsPacketAnalyzer {
@@ -45,6 +241,16 @@ impl TsPacketAnalyzer {
             offset += TS_PACKET_SIZE;
 
             if packet[0] != SYNC_BYTE {
+                delta.sync_losses += 1;
+                self.quality.sync_losses += 1;
+                if let Some(summary) = self.sync_loss_log.record(TS_PACKET_SIZE as u64) {
+                    warn!(
+                        "[TsPacketAnalyzer] Sync lost: {} packets skipped in {:.1}s (total={})",
+                        summary.events,
+                        summary.elapsed.as_secs_f64(),
+                        self.quality.sync_losses
+                    );
+                }
                 continue;
             }
 
@@ -57,17 +263,22 @@ impl TsPacketAnalyzer {
             delta.packets_total += 1;
             self.quality.packets_total += 1;
 
+            let stat = self.pids.entry(pid).or_default();
+            stat.packets += 1;
+
             if transport_error {
                 delta.packets_error += 1;
                 self.quality.packets_error += 1;
+                stat.errors += 1;
             }
 
             if scrambling != 0 {
                 delta.packets_scrambled += 1;
                 self.quality.packets_scrambled += 1;
+                stat.scrambled += 1;
             }
 
-            if pid == 0x1FFF {
+            if pid == NULL_PID {
                 continue;
             }
 
@@ -80,9 +291,14 @@ impl TsPacketAnalyzer {
                 if continuity_counter != expected_cc {
                     delta.packets_dropped += 1;
                     self.quality.packets_dropped += 1;
+                    stat.discontinuities += 1;
                 }
             }
             self.last_cc.insert(pid, continuity_counter);
+
+            if pid == PAT_PID || self.pmt.pmt_collectors.contains_key(&pid) {
+                self.pmt.process_packet(packet);
+            }
         }
 
         delta
@@ -93,9 +309,130 @@ impl TsPacketAnalyzer {
         self.quality
     }
 
+    /// Get per-PID counters for every PID seen since the last reset.
+    pub fn pid_stats(&self) -> HashMap<u16, PidStat> {
+        self.pids
+            .iter()
+            .map(|(&pid, stat)| {
+                let in_pmt = self.pmt.is_declared(pid);
+                (pid, PidStat { in_pmt, ..*stat })
+            })
+            .collect()
+    }
+
     /// Reset counters.
     pub fn reset(&mut self) {
         self.quality = TsStreamQuality::default();
         self.last_cc.clear();
+        self.pids.clear();
+        self.pmt = PmtTracker::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_loss_throttle_aggregates_events() {
+        let mut throttle = SyncLossLogThrottle::new(Duration::from_secs(10));
+        let start = Instant::now();
+
+        let first = throttle.record_at(188, start).unwrap();
+        assert_eq!(first.events, 1);
+        assert_eq!(first.bytes_dropped, 188);
+
+        for i in 1..=5 {
+            assert!(throttle.record_at(10, start + Duration::from_secs(i)).is_none());
+        }
+
+        let summary = throttle.record_at(10, start + Duration::from_secs(10)).unwrap();
+        assert_eq!(summary.events, 6);
+        assert_eq!(summary.bytes_dropped, 60);
+        assert_eq!(summary.elapsed, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_sync_loss_throttle_zero_interval_logs_every_event() {
+        let mut throttle = SyncLossLogThrottle::new(Duration::ZERO);
+        let now = Instant::now();
+        assert!(throttle.record_at(1, now).is_some());
+        assert!(throttle.record_at(1, now).is_some());
+    }
+
+    #[test]
+    fn test_analyzer_counts_every_sync_loss() {
+        let mut analyzer = TsPacketAnalyzer::new();
+        let mut data = vec![0u8; TS_PACKET_SIZE * 3];
+        data[0] = SYNC_BYTE;
+        data[TS_PACKET_SIZE * 2] = SYNC_BYTE;
+
+        let delta = analyzer.analyze(&data);
+        assert_eq!(delta.sync_losses, 1);
+        assert_eq!(delta.packets_total, 2);
+
+        analyzer.analyze(&data);
+        assert_eq!(analyzer.snapshot().sync_losses, 2);
+    }
+
+    fn packet(pid: u16, cc: u8, payload: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0xFF; TS_PACKET_SIZE];
+        packet[0] = SYNC_BYTE;
+        packet[1] = (pid >> 8) as u8 & 0x1F;
+        packet[2] = pid as u8;
+        packet[3] = 0x10 | cc;
+        packet[4..4 + payload.len()].copy_from_slice(payload);
+        packet
+    }
+
+    fn section_packet(pid: u16, section: &[u8]) -> Vec<u8> {
+        let mut payload = vec![0x00];
+        payload.extend_from_slice(section);
+        let mut packet = packet(pid, 0, &payload);
+        packet[1] |= 0x40;
+        packet
+    }
+
+    #[test]
+    fn test_pid_stats() {
+        // PAT: program 1 -> PMT 0x01F0
+        let pat = [
+            0x00, 0xB0, 13, 0x7F, 0xE8, 0xC1, 0, 0, 0x00, 0x01, 0xE1, 0xF0, 0, 0, 0, 0,
+        ];
+        // PMT: PCR 0x0100, video 0x0100, audio 0x0110
+        let pmt = [
+            0x02, 0xB0, 23, 0x00, 0x01, 0xC1, 0, 0, 0xE1, 0x00, 0xF0, 0x00, 0x02, 0xE1, 0x00,
+            0xF0, 0x00, 0x0F, 0xE1, 0x10, 0xF0, 0x00, 0, 0, 0, 0,
+        ];
+
+        let mut data = section_packet(0x0000, &pat);
+        data.extend(section_packet(0x01F0, &pmt));
+        for cc in [0, 1, 3] {
+            data.extend(packet(0x0100, cc, &[]));
+        }
+        let mut errored = packet(0x0110, 0, &[]);
+        errored[1] |= 0x80;
+        data.extend(errored);
+        let mut scrambled = packet(0x0200, 0, &[]);
+        scrambled[3] |= 0x80;
+        data.extend(scrambled);
+
+        let mut analyzer = TsPacketAnalyzer::new();
+        analyzer.analyze(&data);
+        let stats = analyzer.pid_stats();
+
+        let video = stats[&0x0100];
+        assert_eq!((video.packets, video.discontinuities), (3, 1));
+        assert!(video.in_pmt);
+        assert_eq!(stats[&0x0110].errors, 1);
+        assert!(stats[&0x0110].in_pmt);
+
+        let stray = stats[&0x0200];
+        assert_eq!(stray.scrambled, 1);
+        assert!(!stray.in_pmt && stray.has_problems());
+        assert!(!stats[&0x01F0].in_pmt && !stats[&0x01F0].has_problems());
+
+        analyzer.reset();
+        assert!(analyzer.pid_stats().is_empty());
     }
 }
@@ -3,7 +3,7 @@
 //! This is a lightweight TS parser that extracts only the essential
 //! information needed for passive scanning (NID, TSID, SID, service names).
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use recisdb_protocol::ChannelInfo;
 
@@ -31,8 +31,14 @@ mod table_id {
 mod descriptor_tag {
     pub const SERVICE: u8 = 0x48;
     pub const NETWORK_NAME: u8 = 0x40;
+    pub const PARTIAL_RECEPTION: u8 = 0xFB;
+    pub const TS_INFORMATION: u8 = 0xCD;
+    pub const REMOTE_CONTROL_KEY: u8 = 0xDE;
 }
 
+/// ISDB service type for 1seg (partial reception) services.
+const ONE_SEG_SERVICE_TYPE: u8 = 0xC0;
+
 /// Minimal TS parser for passive scanning.
 #[derive(Debug, Default)]
 pub struct MinimalTsParser {
@@ -61,6 +67,10 @@ pub struct ParseResult {
     pub network_name: Option<String>,
     /// Services (SID -> service info).
     pub services: HashMap<u16, ServiceInfo>,
+    /// 1seg service IDs (from NIT partial reception descriptor).
+    pub one_seg_service_ids: HashSet<u16>,
+    /// TS information by TSID (from NIT TS information descriptor).
+    pub ts_information: HashMap<u16, TsInformation>,
     /// Has received PAT.
     pub has_pat: bool,
     /// Has received NIT.
@@ -69,6 +79,15 @@ pub struct ParseResult {
     pub has_sdt: bool,
 }
 
+/// TS name and remote control key of a transport stream.
+#[derive(Debug, Default, Clone)]
+pub struct TsInformation {
+    /// TS name.
+    pub ts_name: Option<String>,
+    /// Remote control key ID.
+    pub remote_control_key: Option<u8>,
+}
+
 /// Minimal service information.
 #[derive(Debug, Default, Clone)]
 pub struct ServiceInfo {
@@ -80,6 +99,8 @@ pub struct ServiceInfo {
     pub service_type: Option<u8>,
     /// Provider name.
     pub provider_name: Option<String>,
+    /// Remote control key ID (from SDT remote control key descriptor).
+    pub remote_control_key: Option<u8>,
 }
 
 impl MinimalTsParser {
@@ -291,6 +312,62 @@ impl MinimalTsParser {
         if desc_end > desc_start {
             self.parse_network_descriptors(&data[desc_start..desc_end]);
         }
+
+        // Transport stream loop (ends before CRC)
+        let section_length = ((data[1] as usize & 0x0F) << 8) | data[2] as usize;
+        let section_end = std::cmp::min(3 + section_length, data.len()).saturating_sub(4);
+        if desc_end + 2 > section_end {
+            return;
+        }
+        let ts_loop_length = ((data[desc_end] as usize & 0x0F) << 8) | data[desc_end + 1] as usize;
+        let ts_loop_end = std::cmp::min(desc_end + 2 + ts_loop_length, section_end);
+
+        let mut offset = desc_end + 2;
+        while offset + 6 <= ts_loop_end {
+            let tsid = ((data[offset] as u16) << 8) | data[offset + 1] as u16;
+            let ts_desc_length = ((data[offset + 4] as usize & 0x0F) << 8) | data[offset + 5] as usize;
+            let start = offset + 6;
+            let end = std::cmp::min(start + ts_desc_length, ts_loop_end);
+            self.parse_ts_descriptors(tsid, &data[start..end]);
+            offset = start + ts_desc_length;
+        }
+    }
+
+    /// Parse transport stream descriptors from NIT (partial reception, TS information,
+    /// remote control key).
+    fn parse_ts_descriptors(&mut self, tsid: u16, data: &[u8]) {
+        let mut offset = 0;
+
+        while offset + 2 <= data.len() {
+            let tag = data[offset];
+            let length = data[offset + 1] as usize;
+
+            if offset + 2 + length > data.len() {
+                break;
+            }
+
+            if tag == descriptor_tag::PARTIAL_RECEPTION {
+                for sid in data[offset + 2..offset + 2 + length].chunks_exact(2) {
+                    self.result
+                        .one_seg_service_ids
+                        .insert(((sid[0] as u16) << 8) | sid[1] as u16);
+                }
+            } else if tag == descriptor_tag::TS_INFORMATION && length >= 2 {
+                let desc_data = &data[offset + 2..offset + 2 + length];
+                let name_length = std::cmp::min((desc_data[1] >> 2) as usize, length - 2);
+                let entry = self.result.ts_information.entry(tsid).or_default();
+                entry.ts_name = decode_arib_string(&desc_data[2..2 + name_length]);
+                // The TS information key takes precedence over the remote control key descriptor
+                if desc_data[0] != 0 {
+                    entry.remote_control_key = Some(desc_data[0]);
+                }
+            } else if tag == descriptor_tag::REMOTE_CONTROL_KEY && length >= 1 && data[offset + 2] != 0 {
+                let entry = self.result.ts_information.entry(tsid).or_default();
+                entry.remote_control_key.get_or_insert(data[offset + 2]);
+            }
+
+            offset += 2 + length;
+        }
     }
 
     /// Parse network descriptors from NIT.
@@ -405,6 +482,15 @@ impl MinimalTsParser {
                 if provider_name.is_some() {
                     entry.provider_name = provider_name;
                 }
+            } else if tag == descriptor_tag::REMOTE_CONTROL_KEY && length >= 1 && data[offset + 2] != 0 {
+                self.result
+                    .services
+                    .entry(service_id)
+                    .or_insert_with(|| ServiceInfo {
+                        service_id,
+                        ..Default::default()
+                    })
+                    .remote_control_key = Some(data[offset + 2]);
             }
 
             offset += 2 + length;
@@ -425,6 +511,7 @@ impl MinimalTsParser {
     pub fn to_channel_infos(&self) -> Vec<ChannelInfo> {
         let nid = self.result.network_id.unwrap_or(0);
         let tsid = self.result.transport_stream_id.unwrap_or(0);
+        let ts_info = self.result.ts_information.get(&tsid);
 
         self.result
             .services
@@ -436,14 +523,20 @@ impl MinimalTsParser {
                 manual_sheet: None,
                 raw_name: s.service_name.clone(),
                 channel_name: s.service_name.clone(),
+                name_is_synthetic: false,
                 physical_ch: None,
-                remote_control_key: None,
+                remote_control_key: ts_info
+                    .and_then(|t| t.remote_control_key)
+                    .or(s.remote_control_key),
                 service_type: s.service_type,
                 network_name: self.result.network_name.clone(),
+                ts_name: ts_info.and_then(|t| t.ts_name.clone()),
                 bon_space: None,
                 bon_channel: None,
                 band_type: None,
                 terrestrial_region: None,
+                is_one_seg: self.result.one_seg_service_ids.contains(&s.service_id)
+                    || s.service_type == Some(ONE_SEG_SERVICE_TYPE),
             })
             .collect()
     }
@@ -511,6 +604,59 @@ mod tests {
         assert!(!result.has_sdt);
     }
 
+    #[test]
+    fn test_parse_nit_partial_reception() {
+        let mut parser = MinimalTsParser::new();
+        let section = [
+            0x40, 0xF0, 0x17, // table_id, section_length = 23
+            0x7F, 0xE0, 0xC1, 0x00, 0x00, // NID, version, section numbers
+            0xF0, 0x00, // network_descriptors_length = 0
+            0xF0, 0x0A, // transport_stream_loop_length = 10
+            0x7F, 0xE0, 0x7F, 0xE0, 0xF0, 0x04, // TSID, ONID, descriptors_length = 4
+            0xFB, 0x02, 0x05, 0x88, // partial reception: SID 0x0588
+            0x00, 0x00, 0x00, 0x00, // CRC (not checked)
+        ];
+        parser.parse_nit(&section);
+
+        assert_eq!(parser.result().network_id, Some(0x7FE0));
+        assert!(parser.result().one_seg_service_ids.contains(&0x0588));
+    }
+
+    #[test]
+    fn test_parse_nit_ts_information() {
+        let mut parser = MinimalTsParser::new();
+        let section = [
+            0x40, 0xF0, 0x18, // table_id, section_length = 24
+            0x7F, 0xE0, 0xC1, 0x00, 0x00, // NID, version, section numbers
+            0xF0, 0x00, // network_descriptors_length = 0
+            0xF0, 0x0B, // transport_stream_loop_length = 11
+            0x7F, 0xE1, 0x7F, 0xE0, 0xF0, 0x05, // TSID, ONID, descriptors_length = 5
+            0xCD, 0x03, 0x01, 0x04, b'A', // TS information: key 1, TS name "A"
+            0x00, 0x00, 0x00, 0x00, // CRC (not checked)
+        ];
+        parser.parse_nit(&section);
+
+        let info = parser.result().ts_information.get(&0x7FE1).unwrap();
+        assert_eq!(info.ts_name.as_deref(), Some("A"));
+        assert_eq!(info.remote_control_key, Some(1));
+    }
+
+    #[test]
+    fn test_parse_remote_control_key_descriptors() {
+        let mut parser = MinimalTsParser::new();
+        parser.result.transport_stream_id = Some(0x7FE1);
+
+        // NIT: remote control key descriptor (0xDE) before TS information with key 0
+        parser.parse_ts_descriptors(0x7FE1, &[0xDE, 0x01, 0x05, 0xCD, 0x02, 0x00, 0x00]);
+        assert_eq!(parser.result().ts_information[&0x7FE1].remote_control_key, Some(5));
+
+        // SDT: service-level remote control key descriptor is used without a NIT key
+        parser.parse_service_descriptors(0x0101, &[0xDE, 0x01, 0x03]);
+        assert_eq!(parser.to_channel_infos()[0].remote_control_key, Some(5));
+        parser.result.ts_information.clear();
+        assert_eq!(parser.to_channel_infos()[0].remote_control_key, Some(3));
+    }
+
     #[test]
     fn test_decode_arib_string() {
         // Simple ASCII
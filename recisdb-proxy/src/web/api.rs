@@ -8,11 +8,16 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::web::state::WebState;
-use crate::tuner::TunerPoolConfig;
-use crate::database::NewBonDriver;
+use crate::scheduler::{scan_scheduler, ScanScope};
+use crate::tuner::channel_key::ChannelKeySpec;
+use crate::tuner::{ChannelKey, DriverSelectionStrategy, LagPolicy, QualityScorer, TunerPoolConfig};
+use crate::database::{
+    ChannelRecord, ChannelSearchFilter, LogicalChannelMapping, NewBonDriver, SessionHistoryFilter,
+};
 
 /// Get channel logo image file.
 pub async fn get_logo(
@@ -43,6 +48,46 @@ pub async fn get_logo(
     }
 }
 
+/// Get a channel's station logo (PNG).
+///
+/// Serves the logo stored from the CDT and falls back to the file saved by
+/// the reader's logo collector (`logos/<nid>_<sid>.png`).
+pub async fn get_channel_logo(
+    State(web_state): State<Arc<WebState>>,
+    Path(id): Path<i64>,
+) -> impl IntoResponse {
+    let (logo, channel) = {
+        let db = web_state.database.lock().await;
+        (db.get_channel_logo(id), db.get_channel_by_id(id))
+    };
+
+    match logo {
+        Ok(Some(logo)) => {
+            return (StatusCode::OK, [(CONTENT_TYPE, "image/png")], logo.png).into_response();
+        }
+        Ok(None) => {}
+        Err(e) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, format!("failed to load logo: {}", e))
+                .into_response();
+        }
+    }
+
+    let channel = match channel {
+        Ok(Some(channel)) => channel,
+        Ok(None) => return (StatusCode::NOT_FOUND, "channel not found").into_response(),
+        Err(e) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, format!("failed to load channel: {}", e))
+                .into_response();
+        }
+    };
+
+    let path = std::path::PathBuf::from("logos").join(format!("{}_{}.png", channel.nid, channel.sid));
+    match tokio::fs::read(path).await {
+        Ok(bytes) => (StatusCode::OK, [(CONTENT_TYPE, "image/png")], bytes).into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, "not found").into_response(),
+    }
+}
+
 // ============================================================================
 // Data structures
 // ============================================================================
@@ -68,11 +113,24 @@ pub struct BonDriverInfo {
     pub group_name: Option<String>,
     pub auto_scan_enabled: bool,
     pub scan_interval_hours: i32,
+    pub scan_cron: Option<String>,
+    pub scan_scope: Option<String>,
     pub scan_priority: i32,
     pub last_scan: Option<i64>,
     pub next_scan_at: Option<i64>,
     pub passive_scan_enabled: bool,
     pub max_instances: i32,
+    pub supports_4k: bool,
+    pub group_strategy: Option<String>,
+    /// TS chunks buffered per tuner (None = default).
+    pub broadcast_capacity: Option<i64>,
+    /// `drop_oldest` or `slow_producer` (None = drop_oldest).
+    pub lag_policy: Option<String>,
+    /// Signal level (dB) a scanned channel needs to count as received
+    /// (None = scanner default).
+    pub reception_min_signal: Option<f64>,
+    /// Scans disable channels that fail the reception check.
+    pub auto_disable_no_reception: bool,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -89,6 +147,9 @@ pub struct ChannelInfoApi {
     pub manual_sheet: Option<u16>,
     pub raw_name: Option<String>,
     pub channel_name: Option<String>,
+    /// `channel_name` was synthesized by the scanner, not sent by the broadcaster.
+    #[serde(default)]
+    pub name_is_synthetic: bool,
     pub physical_ch: Option<u8>,
     pub remote_control_key: Option<u8>,
     pub service_type: Option<u8>,
@@ -101,14 +162,25 @@ pub struct ChannelInfoApi {
     pub terrestrial_region: Option<String>,
     pub is_enabled: bool,
     pub priority: i32,
+    #[serde(default)]
+    pub display_order: Option<i32>,
     pub failure_count: i32,
     pub scan_time: Option<i64>,
     pub last_seen: Option<i64>,
+    /// Signal level measured by the last scan (dB).
+    #[serde(default)]
+    pub signal_level: Option<f64>,
+    /// Whether the last scan received the channel (None = not checked yet).
+    #[serde(default)]
+    pub reception_ok: Option<bool>,
     // Grouped channel info (only when group_logical=true)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tuner_count: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tuner_names: Option<Vec<String>>,
+    /// Every (driver, space, channel) location (only when group_logical=true)
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub mappings: Option<Vec<LogicalChannelMapping>>,
 }
 
 /// Scan history record for API.
@@ -127,9 +199,60 @@ pub struct ScanHistoryInfo {
 pub struct SessionHistoryQuery {
     pub page: Option<u32>,
     pub per_page: Option<u32>,
+    /// Page size; takes precedence over `per_page`.
+    pub limit: Option<u32>,
+    /// Row offset; takes precedence over `page`.
+    pub offset: Option<u32>,
+    /// Sessions started at or after this unix time.
+    pub from: Option<i64>,
+    /// Sessions started before this unix time.
+    pub to: Option<i64>,
     pub client_address: Option<String>,
 }
 
+/// Channel usage ranking query.
+#[derive(Debug, Deserialize)]
+pub struct ChannelStatsQuery {
+    /// Window length in hours (default 24).
+    pub hours: Option<u32>,
+    /// Number of channels to return (default 10).
+    pub limit: Option<u32>,
+}
+
+/// Stale channel query.
+#[derive(Debug, Deserialize)]
+pub struct StaleChannelsQuery {
+    /// Channels not seen for this many days are listed (default 30).
+    pub older_than_days: Option<u32>,
+}
+
+/// A channel not confirmed recently, with its BonDriver path.
+#[derive(Debug, Serialize)]
+pub struct StaleChannel {
+    #[serde(flatten)]
+    pub channel: ChannelRecord,
+    pub bon_driver_path: Option<String>,
+}
+
+/// EPG query for one service.
+#[derive(Debug, Deserialize)]
+pub struct EpgQuery {
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    /// Unix timestamp; defaults to now.
+    pub from: Option<i64>,
+    /// Unix timestamp; defaults to 24 hours after `from`.
+    pub to: Option<i64>,
+}
+
+/// Now/next EPG query.
+#[derive(Debug, Deserialize)]
+pub struct EpgNowNextQuery {
+    /// Unix timestamp; defaults to now.
+    pub at: Option<i64>,
+}
+
 /// Alert rule create/update request.
 #[derive(Debug, Deserialize)]
 pub struct AlertRuleRequest {
@@ -141,6 +264,10 @@ pub struct AlertRuleRequest {
     pub is_enabled: Option<bool>,
     pub webhook_url: Option<String>,
     pub webhook_format: Option<String>,
+    /// Seconds to stay silent after firing for the same session (0 = none).
+    pub cooldown_secs: Option<i64>,
+    /// Action taken when a critical rule fires (`disconnect` / `disable_auto_scan`).
+    pub critical_action: Option<String>,
 }
 
 /// Client control override request.
@@ -148,6 +275,9 @@ pub struct AlertRuleRequest {
 pub struct ClientControlOverrideRequest {
     pub override_priority: Option<Option<i32>>,
     pub override_exclusive: Option<Option<bool>>,
+    /// TS bitrate cap in Mbps; 0 forces unlimited, null falls back to the
+    /// server default.
+    pub override_max_bitrate_mbps: Option<Option<f64>>,
 }
 
 // ============================================================================
@@ -169,25 +299,33 @@ pub async fn get_clients(
                 "session_id": s.id,
                 "address": s.addr,
                 "host": s.host,
+                "client_identity": s.client_identity,
                 "tuner_path": s.tuner_path,
                 "channel_info": s.channel_info,
                 "channel_name": s.channel_name,
                 "nid": s.channel_nid,
+                "tsid": s.channel_tsid,
                 "sid": s.channel_sid,
                 "is_streaming": s.is_streaming,
                 "connected_seconds": s.connected_seconds(),
                 "signal_level": (s.signal_level * 10.0).round() / 10.0,
                 "packets_sent": s.packets_sent,
                 "packets_dropped": s.packets_dropped,
+                "lag_events": s.lag_events,
+                "lagged_chunks": s.lagged_chunks,
                 "packets_scrambled": s.packets_scrambled,
                 "packets_error": s.packets_error,
                 "current_bitrate_mbps": (s.current_bitrate_mbps * 100.0).round() / 100.0,
+                "latency_ms": s.latency_ms.map(|ms| (ms * 10.0).round() / 10.0),
                 "client_priority": s.client_priority,
                 "client_exclusive": s.client_exclusive,
                 "override_priority": s.override_priority,
                 "override_exclusive": s.override_exclusive,
                 "effective_priority": effective_priority,
-                "effective_exclusive": effective_exclusive
+                "effective_exclusive": effective_exclusive,
+                "default_max_bitrate_mbps": s.default_max_bitrate_mbps,
+                "override_max_bitrate_mbps": s.override_max_bitrate_mbps,
+                "max_bitrate_mbps": s.effective_max_bitrate_mbps()
             })
         })
         .collect();
@@ -228,7 +366,7 @@ pub async fn get_stats(
         active_sessions: active_sessions as u64,
         total_tuners,
         active_tuners,
-        uptime_seconds: 0,
+        uptime_seconds: web_state.started_at.elapsed().as_secs(),
         total_sessions_db,
     };
 
@@ -238,6 +376,192 @@ pub async fn get_stats(
     }))
 }
 
+/// Get the most-served channels over a time window.
+///
+/// Usage is persisted once a minute in hourly buckets, so the newest minute
+/// is not included and the window starts at a full hour.
+pub async fn get_channel_stats(
+    State(web_state): State<Arc<WebState>>,
+    Query(query): Query<ChannelStatsQuery>,
+) -> impl IntoResponse {
+    let hours = query.hours.unwrap_or(24).clamp(1, 24 * 365);
+    let limit = query.limit.unwrap_or(10).clamp(1, 200);
+    let since = chrono::Utc::now().timestamp() - hours as i64 * 3600;
+
+    let db = web_state.database.lock().await;
+    match db.get_top_channel_usage(since, limit) {
+        Ok(channels) => Json(json!({
+            "success": true,
+            "hours": hours,
+            "since": since,
+            "channels": channels
+        })),
+        Err(e) => Json(json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+/// List channels that no scan or passive update has confirmed recently.
+///
+/// After a broadcaster reorganisation (e.g. the periodic BS/CS transponder
+/// reshuffles) these are the candidates for removal. Channels never seen are
+/// listed first.
+pub async fn get_stale_channels(
+    State(web_state): State<Arc<WebState>>,
+    Query(query): Query<StaleChannelsQuery>,
+) -> impl IntoResponse {
+    let older_than_days = query.older_than_days.unwrap_or(30).clamp(1, 3650);
+    let cutoff = chrono::Utc::now().timestamp() - older_than_days as i64 * 86400;
+
+    let db = web_state.database.lock().await;
+    match db.get_stale_channels(cutoff) {
+        Ok(rows) => {
+            let channels: Vec<StaleChannel> = rows
+                .into_iter()
+                .map(|(channel, bon_driver_path)| StaleChannel { channel, bon_driver_path })
+                .collect();
+            Json(json!({
+                "success": true,
+                "older_than_days": older_than_days,
+                "cutoff": cutoff,
+                "channels": channels
+            }))
+        }
+        Err(e) => Json(json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+/// Get the signal level history of a running tuner.
+///
+/// `key` is the tuner's channel key, `<dll_path>|<space>|<channel>`.
+pub async fn get_tuner_signal_history(
+    State(web_state): State<Arc<WebState>>,
+    Path(key): Path<String>,
+) -> impl IntoResponse {
+    let key = match key.parse::<ChannelKey>() {
+        Ok(key) => key,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+
+    match web_state.tuner_pool.get(&key).await {
+        Some(tuner) => Json(json!({
+            "success": true,
+            "key": key.to_string(),
+            "signal_level": tuner.signal_level(),
+            "samples": tuner.signal_history()
+        })),
+        None => Json(json!({
+            "success": false,
+            "error": "Tuner not running"
+        })),
+    }
+}
+
+/// Dump the tuner pool for debugging capacity problems.
+///
+/// Lists every pooled tuner with its reader state and the sessions on it,
+/// plus each registered driver's group, `max_instances` and running count,
+/// which is what group-mode driver selection compares when it rejects a
+/// channel change with ChannelSetFailed.
+pub async fn get_tuner_pool(
+    State(web_state): State<Arc<WebState>>,
+) -> impl IntoResponse {
+    let pool = &web_state.tuner_pool;
+    let sessions = web_state.session_registry.get_all().await;
+    let drivers = match web_state.database.lock().await.get_all_bon_drivers() {
+        Ok(drivers) => drivers,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e.to_string()
+            }));
+        }
+    };
+
+    let mut keys = pool.keys().await;
+    keys.sort_by_key(|key| key.to_string());
+
+    let mut running_per_driver: HashMap<String, usize> = HashMap::new();
+    let mut tuners = Vec::with_capacity(keys.len());
+    for key in keys {
+        let Some(tuner) = pool.get(&key).await else { continue };
+        let running = tuner.is_running();
+        if running {
+            *running_per_driver.entry(key.tuner_path.clone()).or_default() += 1;
+        }
+        let (space, channel) = match key.channel {
+            ChannelKeySpec::SpaceChannel { space, channel } => (Some(space), channel),
+            ChannelKeySpec::Simple(channel) => (None, channel as u32),
+        };
+        let driver = drivers.iter().find(|d| d.dll_path == key.tuner_path);
+        let subscribed: Vec<serde_json::Value> = sessions
+            .iter()
+            .filter(|s| s.is_on_tuner(&key))
+            .map(|s| json!({
+                "id": s.id,
+                "addr": s.addr,
+                "client_identity": s.client_identity,
+                "channel_name": s.channel_name,
+                "is_streaming": s.is_streaming,
+                "exclusive": s.override_exclusive.unwrap_or(s.client_exclusive),
+            }))
+            .collect();
+        let sinks: Vec<String> = tuner.sink_names().into_iter().map(|(_, name)| name).collect();
+
+        tuners.push(json!({
+            "key": key.to_string(),
+            "tuner_path": key.tuner_path,
+            "space": space,
+            "channel": channel,
+            "group_name": driver.and_then(|d| d.group_name.clone()),
+            "running": running,
+            "subscriber_count": tuner.subscriber_count(),
+            "signal_level": tuner.signal_level(),
+            "stalled_for_ms": tuner.stalled_for().map(|d| d.as_millis() as u64),
+            "preempt_reason": tuner.preempt_reason(),
+            "sessions": subscribed,
+            "sinks": sinks,
+        }));
+    }
+
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    let mut driver_capacity = Vec::with_capacity(drivers.len());
+    for d in &drivers {
+        if let Some(group) = &d.group_name {
+            groups.entry(group.clone()).or_default().push(d.dll_path.clone());
+        }
+        let running = running_per_driver.get(&d.dll_path).copied().unwrap_or(0);
+        driver_capacity.push(json!({
+            "id": d.id,
+            "dll_path": d.dll_path,
+            "group_name": d.group_name,
+            "max_instances": d.max_instances,
+            "running": running,
+            "full": running as i64 >= d.max_instances as i64,
+            "startup_warm": pool.has_startup_warm(&d.dll_path).await,
+        }));
+    }
+
+    Json(json!({
+        "success": true,
+        "max_tuners": pool.max_tuners(),
+        "tuner_count": tuners.len(),
+        "tuners": tuners,
+        "drivers": driver_capacity,
+        "groups": groups,
+    }))
+}
+
 // ============================================================================
 // BonDriver endpoints
 // ============================================================================
@@ -252,21 +576,33 @@ pub async fn get_bondrivers(
         Ok(drivers) => {
             let bondrivers: Vec<BonDriverInfo> = drivers
                 .iter()
-                .map(|d| BonDriverInfo {
-                    id: d.id,
-                    dll_path: d.dll_path.clone(),
-                    driver_name: d.driver_name.clone(),
-                    version: d.version.clone(),
-                    group_name: d.group_name.clone(),
-                    auto_scan_enabled: d.auto_scan_enabled,
-                    scan_interval_hours: d.scan_interval_hours,
-                    scan_priority: d.scan_priority,
-                    last_scan: d.last_scan,
-                    next_scan_at: d.next_scan_at,
-                    passive_scan_enabled: d.passive_scan_enabled,
-                    max_instances: d.max_instances,
-                    created_at: d.created_at,
-                    updated_at: d.updated_at,
+                .map(|d| {
+                    let broadcast = db.get_broadcast_config_for_path(&d.dll_path).unwrap_or_default();
+                    let reception = db.get_reception_config_for_path(&d.dll_path).unwrap_or_default();
+                    BonDriverInfo {
+                        id: d.id,
+                        dll_path: d.dll_path.clone(),
+                        driver_name: d.driver_name.clone(),
+                        version: d.version.clone(),
+                        group_name: d.group_name.clone(),
+                        auto_scan_enabled: d.auto_scan_enabled,
+                        scan_interval_hours: d.scan_interval_hours,
+                        scan_cron: db.get_scan_cron(d.id).unwrap_or(None),
+                        scan_scope: db.get_scan_scope(d.id).unwrap_or(None),
+                        scan_priority: d.scan_priority,
+                        last_scan: d.last_scan,
+                        next_scan_at: d.next_scan_at,
+                        passive_scan_enabled: d.passive_scan_enabled,
+                        max_instances: d.max_instances,
+                        supports_4k: db.get_supports_4k_for_path(&d.dll_path).unwrap_or(false),
+                        group_strategy: db.get_group_strategy_for_path(&d.dll_path).unwrap_or(None),
+                        broadcast_capacity: broadcast.0,
+                        lag_policy: broadcast.1,
+                        reception_min_signal: reception.0,
+                        auto_disable_no_reception: reception.1,
+                        created_at: d.created_at,
+                        updated_at: d.updated_at,
+                    }
                 })
                 .collect();
 
@@ -294,6 +630,8 @@ pub async fn get_bondriver(
 
     match db.get_bon_driver(id) {
         Ok(Some(d)) => {
+            let broadcast = db.get_broadcast_config_for_path(&d.dll_path).unwrap_or_default();
+            let reception = db.get_reception_config_for_path(&d.dll_path).unwrap_or_default();
             Json(json!({
                 "success": true,
                 "bondriver": BonDriverInfo {
@@ -304,11 +642,19 @@ pub async fn get_bondriver(
                     group_name: d.group_name.clone(),
                     auto_scan_enabled: d.auto_scan_enabled,
                     scan_interval_hours: d.scan_interval_hours,
+                    scan_cron: db.get_scan_cron(d.id).unwrap_or(None),
+                    scan_scope: db.get_scan_scope(d.id).unwrap_or(None),
                     scan_priority: d.scan_priority,
                     last_scan: d.last_scan,
                     next_scan_at: d.next_scan_at,
                     passive_scan_enabled: d.passive_scan_enabled,
                     max_instances: d.max_instances,
+                    supports_4k: db.get_supports_4k_for_path(&d.dll_path).unwrap_or(false),
+                    group_strategy: db.get_group_strategy_for_path(&d.dll_path).unwrap_or(None),
+                    broadcast_capacity: broadcast.0,
+                    lag_policy: broadcast.1,
+                    reception_min_signal: reception.0,
+                    auto_disable_no_reception: reception.1,
                     created_at: d.created_at,
                     updated_at: d.updated_at,
                 }
@@ -340,6 +686,24 @@ pub struct UpdateBonDriverRequest {
     pub scan_interval_hours: Option<i32>,
     pub scan_priority: Option<i32>,
     pub passive_scan_enabled: Option<bool>,
+    pub supports_4k: Option<bool>,
+    /// Driver selection within the group (`quality`, `round_robin`,
+    /// `least_loaded`; empty = default).
+    pub group_strategy: Option<String>,
+    /// Cron scan schedule in local time, e.g. `0 4 * * *` (empty = use the interval).
+    pub scan_cron: Option<String>,
+    /// Scan restriction: `terrestrial`, `bs`, `cs` or `channels:13,16,20-27`
+    /// (empty or `all` = every space).
+    pub scan_scope: Option<String>,
+    /// TS chunks buffered per tuner (0 = default).
+    pub broadcast_capacity: Option<i64>,
+    /// `drop_oldest` or `slow_producer` (empty = default).
+    pub lag_policy: Option<String>,
+    /// Signal level (dB) a scanned channel needs to count as received
+    /// (0 = scanner default).
+    pub reception_min_signal: Option<f64>,
+    /// Disable channels that fail the reception check on scan.
+    pub auto_disable_no_reception: Option<bool>,
 }
 
 /// Create BonDriver request.
@@ -353,6 +717,84 @@ pub struct CreateBonDriverRequest {
     pub scan_interval_hours: Option<i32>,
     pub scan_priority: Option<i32>,
     pub passive_scan_enabled: Option<bool>,
+    pub supports_4k: Option<bool>,
+    /// Driver selection within the group (`quality`, `round_robin`,
+    /// `least_loaded`; empty = default).
+    pub group_strategy: Option<String>,
+    /// Cron scan schedule in local time, e.g. `0 4 * * *` (empty = use the interval).
+    pub scan_cron: Option<String>,
+    /// Scan restriction: `terrestrial`, `bs`, `cs` or `channels:13,16,20-27`
+    /// (empty or `all` = every space).
+    pub scan_scope: Option<String>,
+    /// TS chunks buffered per tuner (0 = default).
+    pub broadcast_capacity: Option<i64>,
+    /// `drop_oldest` or `slow_producer` (empty = default).
+    pub lag_policy: Option<String>,
+    /// Signal level (dB) a scanned channel needs to count as received
+    /// (0 = scanner default).
+    pub reception_min_signal: Option<f64>,
+    /// Disable channels that fail the reception check on scan.
+    pub auto_disable_no_reception: Option<bool>,
+}
+
+/// Validate a broadcast_capacity value. Zero clears the setting.
+fn parse_broadcast_capacity(value: i64) -> Result<Option<i64>, String> {
+    match value {
+        0 => Ok(None),
+        16..=65536 => Ok(Some(value)),
+        _ => Err(format!("broadcast_capacity must be between 16 and 65536 (got {})", value)),
+    }
+}
+
+/// Validate a reception_min_signal value. Zero clears the setting.
+fn parse_reception_min_signal(value: f64) -> Result<Option<f64>, String> {
+    if !(0.0..=100.0).contains(&value) {
+        return Err(format!("reception_min_signal must be between 0 and 100 dB (got {})", value));
+    }
+    Ok((value > 0.0).then_some(value))
+}
+
+/// Validate a lag_policy value. An empty value clears the setting.
+fn parse_lag_policy(value: &str) -> Result<Option<&'static str>, String> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    LagPolicy::from_name(value)
+        .map(|policy| Some(policy.name()))
+        .ok_or_else(|| format!("Unknown lag_policy '{}' (drop_oldest, slow_producer)", value))
+}
+
+/// Validate a group_strategy value. An empty value clears the setting.
+fn parse_group_strategy(value: &str) -> Result<Option<&'static str>, String> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    DriverSelectionStrategy::from_name(value)
+        .map(|strategy| Some(strategy.name()))
+        .ok_or_else(|| format!("Unknown group_strategy '{}' (quality, round_robin, least_loaded)", value))
+}
+
+/// Validate a `scan_cron` request value; empty clears the schedule.
+fn parse_scan_cron(value: &str) -> Result<Option<&str>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    scan_scheduler::parse_scan_cron(value).map(|_| Some(value))
+}
+
+/// Validate a `scan_scope` request value into its stored form (None = all).
+fn parse_scan_scope(value: &str) -> Result<Option<String>, String> {
+    match ScanScope::parse(value)? {
+        ScanScope::All => Ok(None),
+        scope => Ok(Some(scope.to_string())),
+    }
+}
+
+/// Store a validated `scan_cron` and move the pending scan onto the new schedule.
+fn apply_scan_cron(db: &crate::database::Database, id: i64, cron: Option<&str>) -> crate::database::Result<()> {
+    let next_scan_at = cron.map(|expr| scan_scheduler::next_scan_time(Some(expr), 0, chrono::Local::now()));
+    db.update_bon_driver_scan_cron(id, cron, next_scan_at)
 }
 
 /// Create BonDriver.
@@ -370,6 +812,61 @@ pub async fn create_bondriver(
         }));
     }
 
+    let group_strategy = match payload.group_strategy.as_deref().map(parse_group_strategy).transpose() {
+        Ok(strategy) => strategy.flatten(),
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+    let scan_cron = match payload.scan_cron.as_deref().map(parse_scan_cron).transpose() {
+        Ok(cron) => cron.flatten(),
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+    let scan_scope = match payload.scan_scope.as_deref().map(parse_scan_scope).transpose() {
+        Ok(scope) => scope.flatten(),
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+    let broadcast_capacity = match payload.broadcast_capacity.map(parse_broadcast_capacity).transpose() {
+        Ok(capacity) => capacity.flatten(),
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+    let lag_policy = match payload.lag_policy.as_deref().map(parse_lag_policy).transpose() {
+        Ok(policy) => policy.flatten(),
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+    let reception_min_signal = match payload.reception_min_signal.map(parse_reception_min_signal).transpose() {
+        Ok(min_signal) => min_signal.flatten(),
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+
     match db.get_bon_driver_by_path(dll_path) {
         Ok(Some(_)) => {
             return Json(json!({
@@ -415,95 +912,277 @@ pub async fn create_bondriver(
         }
     }
 
-    if payload.auto_scan_enabled.is_some()
-        || payload.scan_interval_hours.is_some()
-        || payload.scan_priority.is_some()
-        || payload.passive_scan_enabled.is_some()
-    {
-        let auto_scan = payload.auto_scan_enabled.unwrap_or(false);
-        let interval = payload.scan_interval_hours.unwrap_or(24);
-        let priority = payload.scan_priority.unwrap_or(0);
-        let passive = payload.passive_scan_enabled.unwrap_or(false);
-
-        if let Err(e) = db.update_scan_config(id, Some(auto_scan), Some(interval), Some(priority), Some(passive)) {
+    if let Some(true) = payload.supports_4k {
+        if let Err(e) = db.update_bon_driver_supports_4k(id, true) {
             return Json(json!({
                 "success": false,
-                "error": format!("Failed to update scan config: {}", e)
+                "error": format!("Failed to set supports_4k: {}", e)
             }));
         }
     }
 
-    Json(json!({
-        "success": true,
-        "id": id,
-        "message": "BonDriver created successfully"
-    }))
-}
-
-/// Update BonDriver.
-pub async fn update_bondriver(
-    State(web_state): State<Arc<WebState>>,
-    Path(id): Path<i64>,
-    Json(payload): Json<UpdateBonDriverRequest>,
-) -> impl IntoResponse {
-    let db = web_state.database.lock().await;
+    if let Some(strategy) = group_strategy {
+        if let Err(e) = db.update_bon_driver_group_strategy(id, Some(strategy)) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to set group_strategy: {}", e)
+            }));
+        }
+    }
 
-    if let Some(path) = payload.dll_path.as_ref().map(|s| s.trim()).filter(|s| !s.is_empty()) {
-        if let Err(e) = db.update_bon_driver_path(id, path) {
+    if let Some(cron) = scan_cron {
+        if let Err(e) = db.update_bon_driver_scan_cron(id, Some(cron), None) {
             return Json(json!({
                 "success": false,
-                "error": format!("Failed to update dll_path: {}", e)
+                "error": format!("Failed to set scan_cron: {}", e)
             }));
         }
     }
 
-    // Update individual fields
-    if let Some(max_instances) = payload.max_instances {
-        if let Err(e) = db.update_bon_driver_max_instances(id, max_instances) {
+    if let Some(scope) = &scan_scope {
+        if let Err(e) = db.update_bon_driver_scan_scope(id, Some(scope)) {
             return Json(json!({
                 "success": false,
-                "error": format!("Failed to update max_instances: {}", e)
+                "error": format!("Failed to set scan_scope: {}", e)
             }));
         }
     }
 
-    if let Some(name) = &payload.driver_name {
-        if let Err(e) = db.update_bon_driver_display_name(id, name) {
+    if broadcast_capacity.is_some() || lag_policy.is_some() {
+        let result = db
+            .update_bon_driver_broadcast_capacity(id, broadcast_capacity)
+            .and_then(|()| db.update_bon_driver_lag_policy(id, lag_policy))
+            .and_then(|()| web_state.tuner_pool.load_broadcast_settings(&db));
+        if let Err(e) = result {
             return Json(json!({
                 "success": false,
-                "error": format!("Failed to update driver_name: {}", e)
+                "error": format!("Failed to set broadcast settings: {}", e)
             }));
         }
     }
 
-    if let Some(group) = &payload.group_name {
-        if let Err(e) = db.set_group_name(id, Some(group.as_str())) {
+    if reception_min_signal.is_some() || payload.auto_disable_no_reception == Some(true) {
+        let auto_disable = payload.auto_disable_no_reception.unwrap_or(false);
+        let result = db
+            .update_bon_driver_reception_min_signal(id, reception_min_signal)
+            .and_then(|()| db.update_bon_driver_auto_disable_no_reception(id, auto_disable));
+        if let Err(e) = result {
             return Json(json!({
                 "success": false,
-                "error": format!("Failed to update group_name: {}", e)
+                "error": format!("Failed to set reception check: {}", e)
             }));
         }
     }
 
-    // Update scan config if any scan-related fields are provided
     if payload.auto_scan_enabled.is_some()
         || payload.scan_interval_hours.is_some()
         || payload.scan_priority.is_some()
         || payload.passive_scan_enabled.is_some()
     {
-        // Get current values first
-        let current = match db.get_bon_driver(id) {
-            Ok(Some(d)) => d,
-            _ => return Json(json!({
-                "success": false,
-                "error": "BonDriver not found"
-            })),
-        };
-
-        let auto_scan = payload.auto_scan_enabled.unwrap_or(current.auto_scan_enabled);
-        let interval = payload.scan_interval_hours.unwrap_or(current.scan_interval_hours);
-        let priority = payload.scan_priority.unwrap_or(current.scan_priority);
-        let passive = payload.passive_scan_enabled.unwrap_or(current.passive_scan_enabled);
+        let auto_scan = payload.auto_scan_enabled.unwrap_or(false);
+        let interval = payload.scan_interval_hours.unwrap_or(24);
+        let priority = payload.scan_priority.unwrap_or(0);
+        let passive = payload.passive_scan_enabled.unwrap_or(false);
+
+        if let Err(e) = db.update_scan_config(id, Some(auto_scan), Some(interval), Some(priority), Some(passive)) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update scan config: {}", e)
+            }));
+        }
+    }
+
+    Json(json!({
+        "success": true,
+        "id": id,
+        "message": "BonDriver created successfully"
+    }))
+}
+
+/// Update BonDriver.
+pub async fn update_bondriver(
+    State(web_state): State<Arc<WebState>>,
+    Path(id): Path<i64>,
+    Json(payload): Json<UpdateBonDriverRequest>,
+) -> impl IntoResponse {
+    // Reject a bad schedule or scope before anything is written
+    let scan_cron = match payload.scan_cron.as_deref().map(parse_scan_cron).transpose() {
+        Ok(cron) => cron,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+    let scan_scope = match payload.scan_scope.as_deref().map(parse_scan_scope).transpose() {
+        Ok(scope) => scope,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+    let broadcast_capacity = match payload.broadcast_capacity.map(parse_broadcast_capacity).transpose() {
+        Ok(capacity) => capacity,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+    let lag_policy = match payload.lag_policy.as_deref().map(parse_lag_policy).transpose() {
+        Ok(policy) => policy,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+    let reception_min_signal = match payload.reception_min_signal.map(parse_reception_min_signal).transpose() {
+        Ok(min_signal) => min_signal,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+
+    let db = web_state.database.lock().await;
+
+    if let Some(path) = payload.dll_path.as_ref().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        if let Err(e) = db.update_bon_driver_path(id, path) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update dll_path: {}", e)
+            }));
+        }
+    }
+
+    // Update individual fields
+    if let Some(max_instances) = payload.max_instances {
+        if let Err(e) = db.update_bon_driver_max_instances(id, max_instances) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update max_instances: {}", e)
+            }));
+        }
+    }
+
+    if let Some(name) = &payload.driver_name {
+        if let Err(e) = db.update_bon_driver_display_name(id, name) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update driver_name: {}", e)
+            }));
+        }
+    }
+
+    if let Some(group) = &payload.group_name {
+        if let Err(e) = db.set_group_name(id, Some(group.as_str())) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update group_name: {}", e)
+            }));
+        }
+    }
+
+    if let Some(supports_4k) = payload.supports_4k {
+        if let Err(e) = db.update_bon_driver_supports_4k(id, supports_4k) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update supports_4k: {}", e)
+            }));
+        }
+    }
+
+    if let Some(value) = &payload.group_strategy {
+        let result = parse_group_strategy(value)
+            .and_then(|strategy| db.update_bon_driver_group_strategy(id, strategy).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update group_strategy: {}", e)
+            }));
+        }
+    }
+
+    if let Some(cron) = scan_cron {
+        if let Err(e) = apply_scan_cron(&db, id, cron) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update scan_cron: {}", e)
+            }));
+        }
+    }
+
+    if let Some(scope) = scan_scope {
+        if let Err(e) = db.update_bon_driver_scan_scope(id, scope.as_deref()) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update scan_scope: {}", e)
+            }));
+        }
+    }
+
+    if broadcast_capacity.is_some() || lag_policy.is_some() {
+        let mut result = Ok(());
+        if let Some(capacity) = broadcast_capacity {
+            result = db.update_bon_driver_broadcast_capacity(id, capacity);
+        }
+        if let (Ok(()), Some(policy)) = (&result, lag_policy) {
+            result = db.update_bon_driver_lag_policy(id, policy);
+        }
+        // Picked up by tuners opened on this driver from now on
+        if let Err(e) = result.and_then(|()| web_state.tuner_pool.load_broadcast_settings(&db)) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update broadcast settings: {}", e)
+            }));
+        }
+    }
+
+    if let Some(min_signal) = reception_min_signal {
+        if let Err(e) = db.update_bon_driver_reception_min_signal(id, min_signal) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update reception_min_signal: {}", e)
+            }));
+        }
+    }
+
+    if let Some(enabled) = payload.auto_disable_no_reception {
+        if let Err(e) = db.update_bon_driver_auto_disable_no_reception(id, enabled) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to update auto_disable_no_reception: {}", e)
+            }));
+        }
+    }
+
+    // Update scan config if any scan-related fields are provided
+    if payload.auto_scan_enabled.is_some()
+        || payload.scan_interval_hours.is_some()
+        || payload.scan_priority.is_some()
+        || payload.passive_scan_enabled.is_some()
+    {
+        // Get current values first
+        let current = match db.get_bon_driver(id) {
+            Ok(Some(d)) => d,
+            _ => return Json(json!({
+                "success": false,
+                "error": "BonDriver not found"
+            })),
+        };
+
+        let auto_scan = payload.auto_scan_enabled.unwrap_or(current.auto_scan_enabled);
+        let interval = payload.scan_interval_hours.unwrap_or(current.scan_interval_hours);
+        let priority = payload.scan_priority.unwrap_or(current.scan_priority);
+        let passive = payload.passive_scan_enabled.unwrap_or(current.passive_scan_enabled);
 
         if let Err(e) = db.update_scan_config(id, Some(auto_scan), Some(interval), Some(priority), Some(passive)) {
             return Json(json!({
@@ -575,6 +1254,10 @@ pub struct ChannelQuery {
     pub bondriver_id: Option<i64>,
     pub enabled_only: Option<bool>,
     pub group_logical: Option<bool>,
+    /// Substring search over service name, TS name and network name.
+    pub q: Option<String>,
+    /// Only channels the last scan could not receive.
+    pub reception_failed: Option<bool>,
 }
 
 /// Get all channels.
@@ -585,6 +1268,41 @@ pub async fn get_channels(
     let db = web_state.database.lock().await;
     let enabled_only = query.enabled_only.unwrap_or(false);
 
+    // Narrow to search matches (done in SQL) before building the list
+    let matches: Option<std::collections::HashSet<i64>> =
+        match query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            Some(q) => {
+                let filter = ChannelSearchFilter {
+                    bon_driver_id: query.bondriver_id,
+                    enabled_only,
+                };
+                match db.search_channels(q, &filter) {
+                    Ok(records) => Some(records.into_iter().map(|c| c.id).collect()),
+                    Err(e) => {
+                        return Json(json!({
+                            "success": false,
+                            "error": e.to_string()
+                        }))
+                    }
+                }
+            }
+            None => None,
+        };
+    let reception = match db.get_channel_reception() {
+        Ok(reception) => reception,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e.to_string()
+            }))
+        }
+    };
+    let reception_failed = query.reception_failed.unwrap_or(false);
+    let is_match = |id: i64| {
+        matches.as_ref().is_none_or(|m| m.contains(&id))
+            && (!reception_failed || reception.get(&id).is_some_and(|r| !r.reception_ok))
+    };
+
     // Get channels based on query
     let channel_infos: Result<Vec<ChannelInfoApi>, String> = if let Some(bondriver_id) = query.bondriver_id {
         // Get channels for specific BonDriver
@@ -592,7 +1310,7 @@ pub async fn get_channels(
             .map(|channels| {
                 channels
                     .into_iter()
-                    .filter(|c| !enabled_only || c.is_enabled)
+                    .filter(|c| (!enabled_only || c.is_enabled) && is_match(c.id))
                     .map(|c| ChannelInfoApi {
                         id: c.id,
                         bon_driver_id: c.bon_driver_id,
@@ -603,6 +1321,7 @@ pub async fn get_channels(
                         manual_sheet: c.manual_sheet,
                         raw_name: c.raw_name,
                         channel_name: c.channel_name,
+                        name_is_synthetic: c.name_is_synthetic,
                         physical_ch: c.physical_ch,
                         remote_control_key: c.remote_control_key,
                         service_type: c.service_type,
@@ -614,84 +1333,88 @@ pub async fn get_channels(
                         terrestrial_region: c.terrestrial_region,
                         is_enabled: c.is_enabled,
                         priority: c.priority,
+                        display_order: c.display_order,
                         failure_count: c.failure_count,
                         scan_time: c.scan_time,
                         last_seen: c.last_seen,
+                        signal_level: reception.get(&c.id).and_then(|r| r.signal_level),
+                        reception_ok: reception.get(&c.id).map(|r| r.reception_ok),
                         tuner_count: None,
                         tuner_names: None,
+                        mappings: None,
                     })
                     .collect()
             })
             .map_err(|e| e.to_string())
     } else if query.group_logical.unwrap_or(false) {
-        // Get all channels grouped by logical identity (NID-SID-TSID)
-        db.get_all_bon_drivers()
-            .map(|all_drivers| {
-                let mut channel_map: std::collections::HashMap<(u16, u16, u16), ChannelInfoApi> = std::collections::HashMap::new();
-
-                for driver in &all_drivers {
-                    if let Ok(channels) = db.get_channels_by_bon_driver(driver.id) {
-                        for c in channels {
-                            if enabled_only && !c.is_enabled {
-                                continue;
-                            }
-                            let key = (c.nid, c.sid, c.tsid);
-                            let driver_name = driver.driver_name.clone()
-                                .unwrap_or_else(|| std::path::Path::new(&driver.dll_path)
+        // One row per logical identity (NID-SID-TSID) across all drivers
+        db.merge_logical_channels(enabled_only)
+            .map(|logical| {
+                let mut channels: Vec<ChannelInfoApi> = logical
+                    .into_iter()
+                    .filter(|lc| lc.mappings.iter().any(|m| is_match(m.channel_id)))
+                    .map(|lc| {
+                        let mut tuner_names: Vec<String> = Vec::new();
+                        for m in &lc.mappings {
+                            let name = m.driver_name.clone().unwrap_or_else(|| {
+                                std::path::Path::new(&m.dll_path)
                                     .file_stem()
                                     .and_then(|s| s.to_str())
                                     .unwrap_or("Unknown")
-                                    .to_string());
-
-                            channel_map.entry(key)
-                                .and_modify(|existing| {
-                                    if let Some(ref mut count) = existing.tuner_count {
-                                        *count += 1;
-                                    }
-                                    if let Some(ref mut names) = existing.tuner_names {
-                                        if !names.contains(&driver_name) {
-                                            names.push(driver_name.clone());
-                                        }
-                                    }
-                                    // Use higher priority
-                                    if c.priority > existing.priority {
-                                        existing.priority = c.priority;
-                                    }
-                                })
-                                .or_insert_with(|| ChannelInfoApi {
-                                    id: c.id,
-                                    bon_driver_id: c.bon_driver_id,
-                                    bon_driver_path: Some(driver.dll_path.clone()),
-                                    nid: c.nid,
-                                    sid: c.sid,
-                                    tsid: c.tsid,
-                                    manual_sheet: c.manual_sheet,
-                                    raw_name: c.raw_name.clone(),
-                                    channel_name: c.channel_name.clone(),
-                                    physical_ch: c.physical_ch,
-                                    remote_control_key: c.remote_control_key,
-                                    service_type: c.service_type,
-                                    network_name: c.network_name.clone(),
-                                    bon_space: c.bon_space,
-                                    bon_channel: c.bon_channel,
-                                    band_type: c.band_type,
-                                    region_id: c.region_id,
-                                    terrestrial_region: c.terrestrial_region.clone(),
-                                    is_enabled: c.is_enabled,
-                                    priority: c.priority,
-                                    failure_count: c.failure_count,
-                                    scan_time: c.scan_time,
-                                    last_seen: c.last_seen,
-                                    tuner_count: Some(1),
-                                    tuner_names: Some(vec![driver_name]),
-                                });
+                                    .to_string()
+                            });
+                            if !tuner_names.contains(&name) {
+                                tuner_names.push(name);
+                            }
                         }
-                    }
-                }
+                        let tuner_count = lc.tuner_count();
+                        let c = lc.channel;
+                        ChannelInfoApi {
+                            id: c.id,
+                            bon_driver_id: c.bon_driver_id,
+                            bon_driver_path: lc.mappings.first().map(|m| m.dll_path.clone()),
+                            nid: c.nid,
+                            sid: c.sid,
+                            tsid: c.tsid,
+                            manual_sheet: c.manual_sheet,
+                            raw_name: c.raw_name,
+                            channel_name: c.channel_name,
+                            name_is_synthetic: c.name_is_synthetic,
+                            physical_ch: c.physical_ch,
+                            remote_control_key: c.remote_control_key,
+                            service_type: c.service_type,
+                            network_name: c.network_name,
+                            bon_space: c.bon_space,
+                            bon_channel: c.bon_channel,
+                            band_type: c.band_type,
+                            region_id: c.region_id,
+                            terrestrial_region: c.terrestrial_region,
+                            is_enabled: c.is_enabled,
+                            priority: c.priority,
+                            display_order: c.display_order,
+                            failure_count: c.failure_count,
+                            scan_time: c.scan_time,
+                            last_seen: c.last_seen,
+                            signal_level: reception.get(&c.id).and_then(|r| r.signal_level),
+                            reception_ok: reception.get(&c.id).map(|r| r.reception_ok),
+                            tuner_count: Some(tuner_count),
+                            tuner_names: Some(tuner_names),
+                            mappings: Some(lc.mappings),
+                        }
+                    })
+                    .collect();
 
-                let mut channels: Vec<ChannelInfoApi> = channel_map.into_values().collect();
+                // User-defined lineup first, then within a network order like a
+                // TV remote (channels without a position or key last)
                 channels.sort_by(|a, b| {
-                    a.nid.cmp(&b.nid)
+                    (a.display_order.is_none(), a.display_order)
+                        .cmp(&(b.display_order.is_none(), b.display_order))
+                        .then_with(|| a.nid.cmp(&b.nid))
+                        .then_with(|| {
+                            a.remote_control_key
+                                .unwrap_or(u8::MAX)
+                                .cmp(&b.remote_control_key.unwrap_or(u8::MAX))
+                        })
                         .then_with(|| a.tsid.cmp(&b.tsid))
                         .then_with(|| a.sid.cmp(&b.sid))
                 });
@@ -704,7 +1427,7 @@ pub async fn get_channels(
             .map(|channels| {
                 channels
                     .into_iter()
-                    .filter(|(c, _)| !enabled_only || c.is_enabled)
+                    .filter(|(c, _)| (!enabled_only || c.is_enabled) && is_match(c.id))
                     .map(|(c, bd)| ChannelInfoApi {
                         id: c.id,
                         bon_driver_id: c.bon_driver_id,
@@ -715,6 +1438,7 @@ pub async fn get_channels(
                         manual_sheet: None,
                         raw_name: None,
                         channel_name: c.service_name,
+                        name_is_synthetic: c.name_is_synthetic,
                         physical_ch: None,
                         remote_control_key: c.remote_control_key.map(|v| v as u8),
                         service_type: c.service_type.map(|v| v as u8),
@@ -726,11 +1450,15 @@ pub async fn get_channels(
                         terrestrial_region: None,
                         is_enabled: c.is_enabled,
                         priority: c.priority,
+                        display_order: c.display_order,
                         failure_count: 0,
                         scan_time: None,
                         last_seen: None,
+                        signal_level: reception.get(&c.id).and_then(|r| r.signal_level),
+                        reception_ok: reception.get(&c.id).map(|r| r.reception_ok),
                         tuner_count: None,
                         tuner_names: None,
+                        mappings: None,
                     })
                     .collect()
             })
@@ -1126,14 +1854,17 @@ pub async fn import_channels(
                 manual_sheet: None,
                 raw_name: None,
                 channel_name: channel_name.clone(),
+                name_is_synthetic: false,
                 physical_ch: None,
                 remote_control_key: None,
                 service_type: None,
                 network_name: None,
+                ts_name: None,
                 bon_space,
                 bon_channel,
                 band_type: None,
                 terrestrial_region: None,
+                is_one_seg: false,
             };
             match db.insert_channel(bon_drv, &info) {
                 Ok(new_id) => {
@@ -1153,6 +1884,98 @@ pub async fn import_channels(
     }))
 }
 
+/// Export BonDriver and channel configuration as JSON.
+pub async fn export_config(
+    State(web_state): State<Arc<WebState>>,
+) -> impl IntoResponse {
+    let db = web_state.database.lock().await;
+
+    match db.export_config() {
+        Ok(document) => {
+            use axum::http::header::{CONTENT_DISPOSITION, HeaderValue};
+            let mut resp = Json(document).into_response();
+            resp.headers_mut().insert(CONTENT_DISPOSITION, HeaderValue::from_static("attachment; filename=\"recisdb-config.json\""));
+            resp
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "success": false, "error": e.to_string() })),
+        ).into_response(),
+    }
+}
+
+/// Import BonDriver and channel configuration exported by `export_config`.
+pub async fn import_config(
+    State(web_state): State<Arc<WebState>>,
+    Json(document): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let db = web_state.database.lock().await;
+
+    match db.import_config(document) {
+        Ok(summary) => {
+            if let Err(e) = web_state.tuner_pool.load_broadcast_settings(&db) {
+                log::warn!("Failed to reload broadcast buffer settings: {}", e);
+            }
+            (StatusCode::OK, Json(json!({ "success": true, "summary": summary })))
+        }
+        Err(e @ crate::database::DatabaseError::InvalidConfig(_)) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "success": false, "error": e.to_string() })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "success": false, "error": e.to_string() })),
+        ),
+    }
+}
+
+/// Database maintenance request.
+#[derive(Debug, Default, Deserialize)]
+pub struct MaintenanceRequest {
+    /// History older than this many days is pruned (default 90).
+    pub retention_days: Option<u64>,
+}
+
+/// Prune old history and VACUUM the database.
+///
+/// The database lock is held for the whole run so no other handler can
+/// open a transaction while VACUUM is in progress.
+pub async fn run_maintenance(
+    State(web_state): State<Arc<WebState>>,
+    payload: Option<Json<MaintenanceRequest>>,
+) -> impl IntoResponse {
+    let retention_days = payload
+        .and_then(|Json(p)| p.retention_days)
+        .unwrap_or(crate::database::DEFAULT_HISTORY_RETENTION_DAYS);
+
+    let db = web_state.database.lock().await;
+    match db.maintenance(retention_days) {
+        Ok(report) => (StatusCode::OK, Json(json!({ "success": true, "report": report }))),
+        Err(e) => {
+            log::error!("Database maintenance failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "success": false, "error": e.to_string() })),
+            )
+        }
+    }
+}
+
+/// Re-link channels whose BonDriver was deleted and registered again.
+pub async fn reconcile_channels(State(web_state): State<Arc<WebState>>) -> impl IntoResponse {
+    let db = web_state.database.lock().await;
+    match db.reconcile_channels() {
+        Ok(report) => (StatusCode::OK, Json(json!({ "success": true, "report": report }))),
+        Err(e) => {
+            log::error!("Channel reconcile failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "success": false, "error": e.to_string() })),
+            )
+        }
+    }
+}
+
 /// Create channel request.
 #[derive(Debug, Deserialize)]
 pub struct CreateChannelRequest {
@@ -1183,14 +2006,17 @@ pub async fn create_channel(
         manual_sheet: None,
         raw_name: None,
         channel_name: payload.channel_name,
+        name_is_synthetic: false,
         physical_ch: None,
         remote_control_key: None,
         service_type: None,
         network_name: None,
+        ts_name: None,
         bon_space: payload.bon_space,
         bon_channel: payload.bon_channel,
         band_type: None,
         terrestrial_region: None,
+        is_one_seg: false,
     };
 
     match db.insert_channel(payload.bon_driver_id, &info) {
@@ -1213,6 +2039,32 @@ pub async fn create_channel(
     }
 }
 
+/// Channel reorder request.
+#[derive(Debug, Deserialize)]
+pub struct ReorderChannelsRequest {
+    /// Channel IDs in the desired lineup order.
+    pub channel_ids: Vec<i64>,
+}
+
+/// Set the channel lineup order.
+pub async fn reorder_channels(
+    State(web_state): State<Arc<WebState>>,
+    Json(payload): Json<ReorderChannelsRequest>,
+) -> impl IntoResponse {
+    let mut db = web_state.database.lock().await;
+
+    match db.reorder_channels(&payload.channel_ids) {
+        Ok(count) => Json(json!({
+            "success": true,
+            "message": format!("{} 件の並び順を保存しました", count)
+        })),
+        Err(e) => Json(json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
 /// Batch update item.
 #[derive(Debug, Deserialize)]
 pub struct BatchUpdateItem {
@@ -1455,6 +2307,17 @@ pub async fn get_tuner_config(
             set_channel_retry_timeout_ms,
             signal_poll_interval_ms,
             signal_wait_timeout_ms,
+            driver_affinity_enabled,
+            align_ts_output,
+            descramble_rekey_percent,
+            descramble_rekey_window_secs,
+            stall_timeout_ms,
+            ts_coalesce_bytes,
+            ts_coalesce_flush_ms,
+            prewarm_group,
+            prewarm_on_startup,
+            min_signal_level_terrestrial,
+            min_signal_level_satellite,
         )) => Json(json!({
             "success": true,
             "config": {
@@ -1465,6 +2328,17 @@ pub async fn get_tuner_config(
                 "set_channel_retry_timeout_ms": set_channel_retry_timeout_ms,
                 "signal_poll_interval_ms": signal_poll_interval_ms,
                 "signal_wait_timeout_ms": signal_wait_timeout_ms,
+                "driver_affinity_enabled": driver_affinity_enabled,
+                "align_ts_output": align_ts_output,
+                "descramble_rekey_percent": descramble_rekey_percent,
+                "descramble_rekey_window_secs": descramble_rekey_window_secs,
+                "stall_timeout_ms": stall_timeout_ms,
+                "ts_coalesce_bytes": ts_coalesce_bytes,
+                "ts_coalesce_flush_ms": ts_coalesce_flush_ms,
+                "prewarm_group": prewarm_group,
+                "prewarm_on_startup": prewarm_on_startup,
+                "min_signal_level_terrestrial": min_signal_level_terrestrial,
+                "min_signal_level_satellite": min_signal_level_satellite,
             }
         })),
         Err(e) => Json(json!({
@@ -1484,6 +2358,17 @@ pub struct UpdateTunerConfigRequest {
     pub set_channel_retry_timeout_ms: Option<u64>,
     pub signal_poll_interval_ms: Option<u64>,
     pub signal_wait_timeout_ms: Option<u64>,
+    pub driver_affinity_enabled: Option<bool>,
+    pub align_ts_output: Option<bool>,
+    pub descramble_rekey_percent: Option<u64>,
+    pub descramble_rekey_window_secs: Option<u64>,
+    pub stall_timeout_ms: Option<u64>,
+    pub ts_coalesce_bytes: Option<u64>,
+    pub ts_coalesce_flush_ms: Option<u64>,
+    pub prewarm_group: Option<bool>,
+    pub prewarm_on_startup: Option<bool>,
+    pub min_signal_level_terrestrial: Option<f64>,
+    pub min_signal_level_satellite: Option<f64>,
 }
 
 /// Update tuner optimization configuration.
@@ -1499,6 +2384,17 @@ pub async fn update_tuner_config(
         set_channel_retry_timeout_ms,
         signal_poll_interval_ms,
         signal_wait_timeout_ms,
+        driver_affinity_enabled,
+        align_ts_output,
+        descramble_rekey_percent,
+        descramble_rekey_window_secs,
+        stall_timeout_ms,
+        ts_coalesce_bytes,
+        ts_coalesce_flush_ms,
+        prewarm_group,
+        prewarm_on_startup,
+        min_signal_level_terrestrial,
+        min_signal_level_satellite,
     ) = {
         let db = web_state.database.lock().await;
 
@@ -1510,10 +2406,24 @@ pub async fn update_tuner_config(
             mut set_channel_retry_timeout_ms,
             mut signal_poll_interval_ms,
             mut signal_wait_timeout_ms,
+            mut driver_affinity_enabled,
+            mut align_ts_output,
+            mut descramble_rekey_percent,
+            mut descramble_rekey_window_secs,
+            mut stall_timeout_ms,
+            mut ts_coalesce_bytes,
+            mut ts_coalesce_flush_ms,
+            mut prewarm_group,
+            mut prewarm_on_startup,
+            mut min_signal_level_terrestrial,
+            mut min_signal_level_satellite,
         ) =
             match db.get_tuner_config() {
                 Ok(config) => config,
-                Err(_) => (60, true, 30, 500, 10_000, 500, 10_000),
+                Err(_) => (
+                    60, true, 30, 500, 10_000, 500, 10_000, true, false, 90, 30, 15_000, 0, 20, false,
+                    false, 0.0, 0.0,
+                ),
             };
 
         if let Some(val) = payload.keep_alive_secs {
@@ -1550,6 +2460,52 @@ pub async fn update_tuner_config(
                 signal_wait_timeout_ms = val;
             }
         }
+        if let Some(val) = payload.driver_affinity_enabled {
+            driver_affinity_enabled = val;
+        }
+        if let Some(val) = payload.align_ts_output {
+            align_ts_output = val;
+        }
+        // 0 disables re-keying
+        if let Some(val) = payload.descramble_rekey_percent {
+            descramble_rekey_percent = val.min(100);
+        }
+        if let Some(val) = payload.descramble_rekey_window_secs {
+            if val > 0 {
+                descramble_rekey_window_secs = val;
+            }
+        }
+        // 0 disables the stall watchdog
+        if let Some(val) = payload.stall_timeout_ms {
+            stall_timeout_ms = val;
+        }
+        // 0 disables coalescing; sizes above MAX_TS_CHUNK_SIZE are capped when applied
+        if let Some(val) = payload.ts_coalesce_bytes {
+            ts_coalesce_bytes = val;
+        }
+        if let Some(val) = payload.ts_coalesce_flush_ms {
+            if val > 0 {
+                ts_coalesce_flush_ms = val;
+            }
+        }
+        if let Some(val) = payload.prewarm_group {
+            prewarm_group = val;
+        }
+        // Turning this on takes effect at the next server start
+        if let Some(val) = payload.prewarm_on_startup {
+            prewarm_on_startup = val;
+        }
+        // 0 disables the signal check for that band
+        if let Some(val) = payload.min_signal_level_terrestrial {
+            if val.is_finite() && val >= 0.0 {
+                min_signal_level_terrestrial = val;
+            }
+        }
+        if let Some(val) = payload.min_signal_level_satellite {
+            if val.is_finite() && val >= 0.0 {
+                min_signal_level_satellite = val;
+            }
+        }
 
         if let Err(e) = db.update_tuner_config(
             keep_alive,
@@ -1559,6 +2515,17 @@ pub async fn update_tuner_config(
             set_channel_retry_timeout_ms,
             signal_poll_interval_ms,
             signal_wait_timeout_ms,
+            driver_affinity_enabled,
+            align_ts_output,
+            descramble_rekey_percent,
+            descramble_rekey_window_secs,
+            stall_timeout_ms,
+            ts_coalesce_bytes,
+            ts_coalesce_flush_ms,
+            prewarm_group,
+            prewarm_on_startup,
+            min_signal_level_terrestrial,
+            min_signal_level_satellite,
         ) {
             return Json(json!({
                 "success": false,
@@ -1574,6 +2541,17 @@ pub async fn update_tuner_config(
             set_channel_retry_timeout_ms,
             signal_poll_interval_ms,
             signal_wait_timeout_ms,
+            driver_affinity_enabled,
+            align_ts_output,
+            descramble_rekey_percent,
+            descramble_rekey_window_secs,
+            stall_timeout_ms,
+            ts_coalesce_bytes,
+            ts_coalesce_flush_ms,
+            prewarm_group,
+            prewarm_on_startup,
+            min_signal_level_terrestrial,
+            min_signal_level_satellite,
         )
     };
 
@@ -1585,6 +2563,17 @@ pub async fn update_tuner_config(
         set_channel_retry_timeout_ms,
         signal_poll_interval_ms,
         signal_wait_timeout_ms,
+        driver_affinity_enabled,
+        align_ts_output,
+        descramble_rekey_percent,
+        descramble_rekey_window_secs,
+        stall_timeout_ms,
+        ts_coalesce_bytes,
+        ts_coalesce_flush_ms,
+        prewarm_group,
+        prewarm_on_startup,
+        min_signal_level_terrestrial,
+        min_signal_level_satellite,
     };
     web_state.update_tuner_config(config.clone()).await;
 
@@ -1596,6 +2585,17 @@ pub async fn update_tuner_config(
         set_channel_retry_timeout_ms,
         signal_poll_interval_ms,
         signal_wait_timeout_ms,
+        driver_affinity_enabled,
+        align_ts_output,
+        descramble_rekey_percent,
+        descramble_rekey_window_secs,
+        stall_timeout_ms,
+        ts_coalesce_bytes: ts_coalesce_bytes as usize,
+        ts_coalesce_flush_ms,
+        prewarm_group,
+        prewarm_on_startup,
+        min_signal_level_terrestrial,
+        min_signal_level_satellite,
     };
     web_state.tuner_pool.update_config(pool_config).await;
 
@@ -1610,22 +2610,297 @@ pub async fn update_tuner_config(
             "set_channel_retry_timeout_ms": config.set_channel_retry_timeout_ms,
             "signal_poll_interval_ms": config.signal_poll_interval_ms,
             "signal_wait_timeout_ms": config.signal_wait_timeout_ms,
+            "driver_affinity_enabled": config.driver_affinity_enabled,
+            "align_ts_output": config.align_ts_output,
+            "descramble_rekey_percent": config.descramble_rekey_percent,
+            "descramble_rekey_window_secs": config.descramble_rekey_window_secs,
+            "stall_timeout_ms": config.stall_timeout_ms,
+            "ts_coalesce_bytes": config.ts_coalesce_bytes,
+            "ts_coalesce_flush_ms": config.ts_coalesce_flush_ms,
+            "prewarm_group": config.prewarm_group,
+            "prewarm_on_startup": config.prewarm_on_startup,
+            "min_signal_level_terrestrial": config.min_signal_level_terrestrial,
+            "min_signal_level_satellite": config.min_signal_level_satellite,
         }
     }))
 }
 
-/// Get external encoder (tsreplace) configuration.
-pub async fn get_tsreplace_config(
+/// Get server runtime configuration.
+pub async fn get_server_config(
     State(web_state): State<Arc<WebState>>,
 ) -> impl IntoResponse {
-    let db = web_state.database.lock().await;
-
-    match db.get_tsreplace_config() {
-        Ok((enabled, command_path, arguments, read_timeout_ms, passthrough_on_error)) => {
-            Json(json!({
-                "success": true,
-                "config": {
-                    "enabled": enabled,
+    let (preferred_region, limit_spaces) = {
+        let db = web_state.database.lock().await;
+        db.get_space_cap_config().unwrap_or((None, false))
+    };
+    let session_idle_timeout_secs = {
+        let db = web_state.database.lock().await;
+        db.get_server_session_idle_timeout().unwrap_or(None)
+    };
+    let max_bitrate_mbps = {
+        let db = web_state.database.lock().await;
+        db.get_server_max_bitrate().unwrap_or(None)
+    };
+    let quality_half_life_hours = {
+        let db = web_state.database.lock().await;
+        QualityScorer::half_life_hours(&db)
+    };
+    let limiter = &web_state.connection_limiter;
+    Json(json!({
+        "success": true,
+        "config": {
+            "max_connections": limiter.max_connections(),
+            "active_connections": limiter.active_connections(),
+            "preferred_region": preferred_region,
+            "limit_spaces_to_preferred_region": limit_spaces,
+            "session_idle_timeout_secs": session_idle_timeout_secs,
+            "max_bitrate_mbps": max_bitrate_mbps,
+            "quality_half_life_hours": quality_half_life_hours,
+        }
+    }))
+}
+
+/// Update server runtime configuration request.
+#[derive(Debug, Deserialize)]
+pub struct UpdateServerConfigRequest {
+    pub max_connections: Option<usize>,
+    /// Terrestrial region key (e.g. "関東"); empty string clears it.
+    pub preferred_region: Option<String>,
+    pub limit_spaces_to_preferred_region: Option<bool>,
+    /// Idle timeout in seconds; 0 disables reaping.
+    pub session_idle_timeout_secs: Option<u64>,
+    /// Default per-session TS bitrate cap in Mbps; 0 means unlimited.
+    pub max_bitrate_mbps: Option<f64>,
+    /// Half-life of driver quality stats in hours; 0 disables decay.
+    pub quality_half_life_hours: Option<f64>,
+}
+
+/// Update server runtime configuration.
+///
+/// The new max_connections is applied to the live listener without dropping
+/// existing sessions, and persisted so it survives restart. The space cap
+/// applies to sessions the next time they build their space list, the idle
+/// timeout to sessions connecting after the change, the bitrate cap to
+/// every session without an override within a second, and the quality
+/// half-life to the next score update or ranking read.
+pub async fn update_server_config(
+    State(web_state): State<Arc<WebState>>,
+    Json(payload): Json<UpdateServerConfigRequest>,
+) -> impl IntoResponse {
+    if let Some(max_connections) = payload.max_connections {
+        if max_connections == 0 {
+            return Json(json!({
+                "success": false,
+                "error": "max_connections must be greater than 0"
+            }));
+        }
+
+        {
+            let db = web_state.database.lock().await;
+            if let Err(e) = db.set_server_max_connections(max_connections) {
+                return Json(json!({
+                    "success": false,
+                    "error": format!("Failed to save configuration: {}", e)
+                }));
+            }
+        }
+
+        web_state.connection_limiter.set_max_connections(max_connections);
+        log::info!("max_connections changed to {} via Web API", max_connections);
+    }
+
+    if let Some(secs) = payload.session_idle_timeout_secs {
+        let db = web_state.database.lock().await;
+        if let Err(e) = db.set_server_session_idle_timeout(secs) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to save configuration: {}", e)
+            }));
+        }
+        log::info!("session_idle_timeout_secs changed to {} via Web API", secs);
+    }
+    let session_idle_timeout_secs = {
+        let db = web_state.database.lock().await;
+        db.get_server_session_idle_timeout().unwrap_or(None)
+    };
+
+    if let Some(mbps) = payload.max_bitrate_mbps {
+        if !mbps.is_finite() || mbps < 0.0 {
+            return Json(json!({
+                "success": false,
+                "error": "max_bitrate_mbps must be 0 or greater"
+            }));
+        }
+        let db = web_state.database.lock().await;
+        if let Err(e) = db.set_server_max_bitrate(mbps) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to save configuration: {}", e)
+            }));
+        }
+        log::info!("max_bitrate_mbps changed to {} via Web API", mbps);
+    }
+    let max_bitrate_mbps = {
+        let db = web_state.database.lock().await;
+        db.get_server_max_bitrate().unwrap_or(None)
+    };
+    if payload.max_bitrate_mbps.is_some() {
+        web_state
+            .session_registry
+            .update_default_max_bitrate_all(max_bitrate_mbps)
+            .await;
+    }
+
+    if let Some(hours) = payload.quality_half_life_hours {
+        if !hours.is_finite() || hours < 0.0 {
+            return Json(json!({
+                "success": false,
+                "error": "quality_half_life_hours must be 0 or greater"
+            }));
+        }
+        let db = web_state.database.lock().await;
+        if let Err(e) = db.set_quality_half_life_hours(hours) {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to save configuration: {}", e)
+            }));
+        }
+        log::info!("quality_half_life_hours changed to {} via Web API", hours);
+    }
+    let quality_half_life_hours = {
+        let db = web_state.database.lock().await;
+        QualityScorer::half_life_hours(&db)
+    };
+
+    let (preferred_region, limit_spaces) = {
+        let db = web_state.database.lock().await;
+        let (mut preferred_region, mut limit_spaces) = db.get_space_cap_config().unwrap_or((None, false));
+
+        if payload.preferred_region.is_some() || payload.limit_spaces_to_preferred_region.is_some() {
+            if let Some(region) = payload.preferred_region {
+                let region = region.trim().to_string();
+                preferred_region = (!region.is_empty()).then_some(region);
+            }
+            if let Some(val) = payload.limit_spaces_to_preferred_region {
+                limit_spaces = val;
+            }
+            if limit_spaces && preferred_region.is_none() {
+                return Json(json!({
+                    "success": false,
+                    "error": "preferred_region is required to limit spaces"
+                }));
+            }
+            if let Err(e) = db.set_space_cap_config(preferred_region.as_deref(), limit_spaces) {
+                return Json(json!({
+                    "success": false,
+                    "error": format!("Failed to save configuration: {}", e)
+                }));
+            }
+        }
+        (preferred_region, limit_spaces)
+    };
+
+    let limiter = &web_state.connection_limiter;
+    Json(json!({
+        "success": true,
+        "message": "Server configuration saved successfully",
+        "config": {
+            "max_connections": limiter.max_connections(),
+            "active_connections": limiter.active_connections(),
+            "preferred_region": preferred_region,
+            "limit_spaces_to_preferred_region": limit_spaces,
+            "session_idle_timeout_secs": session_idle_timeout_secs,
+            "max_bitrate_mbps": max_bitrate_mbps,
+            "quality_half_life_hours": quality_half_life_hours,
+        }
+    }))
+}
+
+/// Get the active log filter.
+pub async fn get_log_level(
+    State(web_state): State<Arc<WebState>>,
+) -> impl IntoResponse {
+    match &web_state.log_filter {
+        Some(handle) => Json(json!({
+            "success": true,
+            "filter": handle.current(),
+        })),
+        None => Json(json!({
+            "success": false,
+            "error": "Runtime log level change is not available"
+        })),
+    }
+}
+
+/// Update log level request.
+///
+/// Either `filter` (full directive string) or `level` with optional
+/// per-target `targets` must be given.
+#[derive(Debug, Deserialize)]
+pub struct UpdateLogLevelRequest {
+    /// Directive string, e.g. "info,recisdb_proxy::tuner=debug".
+    pub filter: Option<String>,
+    /// Default level, e.g. "warn".
+    pub level: Option<String>,
+    /// Per-target levels, e.g. {"recisdb_proxy::tuner": "debug"}.
+    #[serde(default)]
+    pub targets: std::collections::BTreeMap<String, String>,
+}
+
+/// Change the log filter at runtime.
+///
+/// Takes effect immediately for every session; the change is not persisted,
+/// so the configured level is restored on restart.
+pub async fn update_log_level(
+    State(web_state): State<Arc<WebState>>,
+    Json(payload): Json<UpdateLogLevelRequest>,
+) -> impl IntoResponse {
+    let Some(handle) = &web_state.log_filter else {
+        return Json(json!({
+            "success": false,
+            "error": "Runtime log level change is not available"
+        }));
+    };
+
+    let directives = match (payload.filter, payload.level) {
+        (Some(filter), _) => filter.trim().to_string(),
+        (None, Some(level)) => {
+            crate::logging::build_filter_directives(level.trim(), &payload.targets)
+        }
+        (None, None) => {
+            return Json(json!({
+                "success": false,
+                "error": "filter or level is required"
+            }));
+        }
+    };
+
+    if let Err(e) = handle.set(&directives) {
+        return Json(json!({
+            "success": false,
+            "error": format!("Invalid log filter: {}", e)
+        }));
+    }
+    log::warn!("Log filter changed to {:?} via Web API", directives);
+
+    Json(json!({
+        "success": true,
+        "filter": directives,
+    }))
+}
+
+/// Get external encoder (tsreplace) configuration.
+pub async fn get_tsreplace_config(
+    State(web_state): State<Arc<WebState>>,
+) -> impl IntoResponse {
+    let db = web_state.database.lock().await;
+
+    match db.get_tsreplace_config() {
+        Ok((enabled, command_path, arguments, read_timeout_ms, passthrough_on_error)) => {
+            Json(json!({
+                "success": true,
+                "config": {
+                    "enabled": enabled,
                     "command_path": command_path,
                     "arguments": arguments,
                     "read_timeout_ms": read_timeout_ms,
@@ -1835,16 +3110,26 @@ pub async fn get_session_history(
     State(web_state): State<Arc<WebState>>,
     Query(query): Query<SessionHistoryQuery>,
 ) -> impl IntoResponse {
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+    let limit = query.limit.or(query.per_page).unwrap_or(50).clamp(1, 200);
+    let offset = query
+        .offset
+        .unwrap_or_else(|| query.page.unwrap_or(1).max(1).saturating_sub(1).saturating_mul(limit));
+    let filter = SessionHistoryFilter {
+        client_address: query.client_address,
+        from: query.from,
+        to: query.to,
+    };
 
     let db = web_state.database.lock().await;
-    match db.get_session_history(page, per_page, query.client_address.as_deref()) {
-        Ok((rows, total)) => Json(json!({
+    match db.get_session_history_paged(&filter, limit, offset) {
+        Ok((rows, total_count)) => Json(json!({
             "success": true,
-            "total": total,
-            "page": page,
-            "per_page": per_page,
+            "total_count": total_count,
+            "total": total_count,
+            "limit": limit,
+            "offset": offset,
+            "page": offset / limit + 1,
+            "per_page": limit,
             "history": rows
         })),
         Err(e) => Json(json!({
@@ -1854,6 +3139,51 @@ pub async fn get_session_history(
     }
 }
 
+/// Get EPG events for a service.
+///
+/// Events still running at `from` are included, so the first entry of the
+/// default range is the current programme followed by the next ones.
+pub async fn get_epg(
+    State(web_state): State<Arc<WebState>>,
+    Query(query): Query<EpgQuery>,
+) -> impl IntoResponse {
+    let from = query.from.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let to = query.to.unwrap_or(from + 24 * 3600);
+
+    let db = web_state.database.lock().await;
+    match db.get_events_for_service(query.nid, query.tsid, query.sid, from, to) {
+        Ok(events) => Json(json!({
+            "success": true,
+            "events": events
+        })),
+        Err(e) => Json(json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+/// Get the current and next programme of every enabled service.
+pub async fn get_epg_now_next(
+    State(web_state): State<Arc<WebState>>,
+    Query(query): Query<EpgNowNextQuery>,
+) -> impl IntoResponse {
+    let at = query.at.unwrap_or_else(|| chrono::Utc::now().timestamp());
+
+    let db = web_state.database.lock().await;
+    match db.get_now_next(at) {
+        Ok(services) => Json(json!({
+            "success": true,
+            "at": at,
+            "services": services
+        })),
+        Err(e) => Json(json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
 /// Get time-series quality data for a client.
 pub async fn get_client_quality(
     State(web_state): State<Arc<WebState>>,
@@ -1892,7 +3222,8 @@ pub async fn get_client_metrics_history(
             "success": true,
             "bitrate": bitrate,
             "packet_loss": packet_loss,
-            "signal_level": signal_level
+            "signal_level": signal_level,
+            "pid_offenders": session.pid_offenders
         }));
     }
 
@@ -1913,16 +3244,29 @@ pub async fn disconnect_client(
     }))
 }
 
-/// Override client controls (priority/exclusive).
+/// Override client controls (priority/exclusive/bitrate cap).
 pub async fn override_client_controls(
     State(web_state): State<Arc<WebState>>,
     Path(id): Path<u64>,
     Json(payload): Json<ClientControlOverrideRequest>,
 ) -> impl IntoResponse {
+    if let Some(Some(mbps)) = payload.override_max_bitrate_mbps {
+        if !mbps.is_finite() || mbps < 0.0 {
+            return Json(json!({
+                "success": false,
+                "error": "override_max_bitrate_mbps must be 0 or greater"
+            }));
+        }
+    }
     // Treat JSON null as explicit clear. Absence means no change.
     web_state
         .session_registry
-        .update_override_controls(id, payload.override_priority, payload.override_exclusive)
+        .update_override_controls(
+            id,
+            payload.override_priority,
+            payload.override_exclusive,
+            payload.override_max_bitrate_mbps,
+        )
         .await;
     Json(json!({
         "success": true
@@ -1974,10 +3318,28 @@ pub async fn create_alert_rule(
     State(web_state): State<Arc<WebState>>,
     Json(payload): Json<AlertRuleRequest>,
 ) -> impl IntoResponse {
-    let db = web_state.database.lock().await;
     let severity = payload.severity.unwrap_or_else(|| "warning".to_string());
+    if !crate::database::ALERT_SEVERITIES.contains(&severity.as_str()) {
+        return Json(json!({
+            "success": false,
+            "error": format!("severity must be one of {:?}", crate::database::ALERT_SEVERITIES)
+        }));
+    }
+    let critical_action = payload
+        .critical_action
+        .filter(|action| !action.is_empty() && action != "none");
+    if let Some(action) = &critical_action {
+        if !crate::database::ALERT_CRITICAL_ACTIONS.contains(&action.as_str()) {
+            return Json(json!({
+                "success": false,
+                "error": format!("critical_action must be one of {:?}", crate::database::ALERT_CRITICAL_ACTIONS)
+            }));
+        }
+    }
     let is_enabled = payload.is_enabled.unwrap_or(true);
 
+    let db = web_state.database.lock().await;
+
     match db.create_alert_rule(
         &payload.name,
         &payload.metric,
@@ -1987,6 +3349,8 @@ pub async fn create_alert_rule(
         is_enabled,
         payload.webhook_url.as_deref(),
         payload.webhook_format.as_deref(),
+        payload.cooldown_secs.unwrap_or(0).max(0),
+        critical_action.as_deref(),
     ) {
         Ok(id) => Json(json!({
             "success": true,
@@ -2023,6 +3387,56 @@ pub async fn acknowledge_alert(
     }
 }
 
+// ============================================================================
+// Client ACL endpoints
+// ============================================================================
+
+/// Request body for a client ACL entry.
+#[derive(Debug, Deserialize)]
+pub struct ClientAclRequest {
+    pub identity: String,
+    pub bon_driver_id: Option<i64>,
+    pub group_name: Option<String>,
+}
+
+/// Get client ACL entries.
+pub async fn get_client_acls(
+    State(web_state): State<Arc<WebState>>,
+) -> impl IntoResponse {
+    let db = web_state.database.lock().await;
+    match db.get_client_acls() {
+        Ok(entries) => Json(json!({"success": true, "entries": entries})),
+        Err(e) => Json(json!({"success": false, "error": e.to_string()})),
+    }
+}
+
+/// Grant a client identity a BonDriver or group.
+pub async fn create_client_acl(
+    State(web_state): State<Arc<WebState>>,
+    Json(payload): Json<ClientAclRequest>,
+) -> impl IntoResponse {
+    let identity = payload.identity.trim();
+    let group_name = payload.group_name.as_deref().map(str::trim).filter(|g| !g.is_empty());
+    let db = web_state.database.lock().await;
+    match db.insert_client_acl(identity, payload.bon_driver_id, group_name) {
+        Ok(id) => Json(json!({"success": true, "id": id})),
+        Err(e) => Json(json!({"success": false, "error": e.to_string()})),
+    }
+}
+
+/// Delete a client ACL entry.
+pub async fn delete_client_acl(
+    State(web_state): State<Arc<WebState>>,
+    Path(id): Path<i64>,
+) -> impl IntoResponse {
+    let db = web_state.database.lock().await;
+    match db.delete_client_acl(id) {
+        Ok(true) => Json(json!({"success": true})),
+        Ok(false) => Json(json!({"success": false, "error": "ACL entry not found"})),
+        Err(e) => Json(json!({"success": false, "error": e.to_string()})),
+    }
+}
+
 // ============================================================================
 // BonDriver quality endpoints
 // ============================================================================
@@ -2049,6 +3463,369 @@ pub async fn get_bondriver_quality(
     }
 }
 
+// ============================================================================
+// BonDriver test tune endpoint
+// ============================================================================
+
+/// Test tune time budget when the request sets none.
+const TEST_TUNE_DEFAULT_TIMEOUT_MS: u64 = 10_000;
+/// Upper bound on a requested test tune time budget.
+const TEST_TUNE_MAX_TIMEOUT_MS: u64 = 30_000;
+
+/// Request body for a test tune.
+#[derive(Debug, Deserialize)]
+pub struct TestTuneRequest {
+    pub space: u32,
+    pub channel: u32,
+    /// Time budget in milliseconds (1000..=30000, default 10000).
+    pub timeout_ms: Option<u64>,
+}
+
+/// Tune one physical channel on a BonDriver and report lock time, signal
+/// level and the decoded NIT/SDT, without streaming anything.
+///
+/// Refused while every instance of the driver is in use or a channel scan
+/// has it open, so a test tune never takes a tuner away from a session.
+pub async fn test_tune_bondriver(
+    State(web_state): State<Arc<WebState>>,
+    Path(id): Path<i64>,
+    Json(req): Json<TestTuneRequest>,
+) -> impl IntoResponse {
+    let (driver, expected) = {
+        let db = web_state.database.lock().await;
+        let driver = match db.get_bon_driver(id) {
+            Ok(Some(driver)) => driver,
+            Ok(None) => {
+                return Json(json!({
+                    "success": false,
+                    "error": "BonDriver not found"
+                }))
+            }
+            Err(e) => {
+                return Json(json!({
+                    "success": false,
+                    "error": e.to_string()
+                }))
+            }
+        };
+        let expected: Vec<(u16, u16)> = match db.get_channels_by_bon_driver(id) {
+            Ok(channels) => {
+                let mut expected: Vec<(u16, u16)> = channels
+                    .iter()
+                    .filter(|c| c.bon_space == Some(req.space) && c.bon_channel == Some(req.channel))
+                    .map(|c| (c.nid, c.tsid))
+                    .collect();
+                expected.sort_unstable();
+                expected.dedup();
+                expected
+            }
+            Err(e) => {
+                return Json(json!({
+                    "success": false,
+                    "error": e.to_string()
+                }))
+            }
+        };
+        (driver, expected)
+    };
+
+    let timeout_ms = req.timeout_ms.unwrap_or(TEST_TUNE_DEFAULT_TIMEOUT_MS);
+    if !(1000..=TEST_TUNE_MAX_TIMEOUT_MS).contains(&timeout_ms) {
+        return Json(json!({
+            "success": false,
+            "error": format!("timeout_ms must be between 1000 and {}", TEST_TUNE_MAX_TIMEOUT_MS)
+        }));
+    }
+
+    // Held until the driver is closed, so no session or scan opens it
+    // between the capacity check and the tune
+    let init_guard = match web_state
+        .tuner_pool
+        .reserve_direct_open(&driver.dll_path, driver.max_instances)
+        .await
+    {
+        Ok(guard) => guard,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }))
+        }
+    };
+
+    let dll_path = driver.dll_path.clone();
+    let (space, channel) = (req.space, req.channel);
+    let budget = std::time::Duration::from_millis(timeout_ms);
+    let task = tokio::task::spawn_blocking(move || {
+        let _init_guard = init_guard;
+        scan_scheduler::test_tune_blocking(&dll_path, space, channel, budget)
+            .map_err(|e| e.to_string())
+    });
+    // The blocking side keeps to the budget; the slack covers a driver that
+    // hangs inside SetChannel or while closing
+    let report = match tokio::time::timeout(budget + std::time::Duration::from_secs(5), task).await {
+        Ok(Ok(Ok(report))) => report,
+        Ok(Ok(Err(e))) => {
+            return Json(json!({
+                "success": false,
+                "error": format!("Failed to open BonDriver: {}", e)
+            }))
+        }
+        Ok(Err(e)) => {
+            return Json(json!({
+                "success": false,
+                "error": e.to_string()
+            }))
+        }
+        Err(_) => {
+            log::warn!("Test tune on {} did not finish within {} ms", driver.dll_path, timeout_ms);
+            return Json(json!({
+                "success": false,
+                "error": "Test tune timed out"
+            }))
+        }
+    };
+
+    let network_match = report
+        .network_id
+        .zip(report.transport_stream_id)
+        .filter(|_| !expected.is_empty())
+        .map(|found| expected.contains(&found));
+    Json(json!({
+        "success": true,
+        "report": {
+            "space": space,
+            "channel": channel,
+            "set_channel_ms": report.set_channel_ms,
+            "first_ts_ms": report.first_ts_ms,
+            "signal_level": report.signal_level,
+            "locked": report.first_ts_ms.is_some() && report.transport_stream_id.is_some(),
+            "nid": report.network_id,
+            "tsid": report.transport_stream_id,
+            "network_name": report.network_name,
+            "ts_name": report.ts_name,
+            "services": report.services.iter().map(|(sid, name)| json!({
+                "sid": sid,
+                "name": name,
+            })).collect::<Vec<_>>(),
+            "expected": expected.iter().map(|(nid, tsid)| json!({
+                "nid": nid,
+                "tsid": tsid,
+            })).collect::<Vec<_>>(),
+            "network_match": network_match,
+            "error": report.error,
+        }
+    }))
+}
+
+/// Running reader count per driver path.
+async fn running_by_driver_path(pool: &crate::tuner::TunerPool) -> HashMap<String, i64> {
+    let mut running: HashMap<String, i64> = HashMap::new();
+    for key in pool.keys().await {
+        if pool.get(&key).await.is_some_and(|tuner| tuner.is_running()) {
+            *running.entry(key.tuner_path).or_default() += 1;
+        }
+    }
+    running
+}
+
+/// Group JSON with the members' running instances added up.
+fn group_json(
+    group: &crate::database::GroupSummary,
+    running: &HashMap<String, i64>,
+) -> serde_json::Value {
+    let running_instances: i64 = group
+        .drivers
+        .iter()
+        .map(|d| running.get(&d.dll_path).copied().unwrap_or(0))
+        .sum();
+    json!({
+        "name": group.name,
+        "strategy": group.strategy,
+        "drivers": group.drivers,
+        "total_instances": group.total_instances,
+        "running_instances": running_instances,
+        "available_instances": (group.total_instances - running_instances).max(0),
+    })
+}
+
+/// List BonDriver groups with their members and capacity.
+pub async fn get_groups(State(web_state): State<Arc<WebState>>) -> impl IntoResponse {
+    let running = running_by_driver_path(&web_state.tuner_pool).await;
+    let db = web_state.database.lock().await;
+    match db.get_groups() {
+        Ok(groups) => {
+            let groups: Vec<serde_json::Value> =
+                groups.iter().map(|g| group_json(g, &running)).collect();
+            Json(json!({
+                "success": true,
+                "count": groups.len(),
+                "groups": groups
+            }))
+        }
+        Err(e) => Json(json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+/// Create or rename group request.
+#[derive(Debug, Deserialize)]
+pub struct GroupRequest {
+    pub name: String,
+    /// Existing group to rename to `name`.
+    pub rename_from: Option<String>,
+    /// Driver selection within the group (`quality`, `round_robin`,
+    /// `least_loaded`; empty = default).
+    pub strategy: Option<String>,
+    /// Drivers to add to the group.
+    #[serde(default)]
+    pub drivers: Vec<i64>,
+}
+
+/// Group membership change request.
+#[derive(Debug, Default, Deserialize)]
+pub struct GroupMembersRequest {
+    #[serde(default)]
+    pub add: Vec<i64>,
+    #[serde(default)]
+    pub remove: Vec<i64>,
+}
+
+/// Check that every driver ID exists.
+fn check_drivers_exist(db: &crate::database::Database, ids: &[i64]) -> Result<(), String> {
+    for &id in ids {
+        match db.get_bon_driver(id) {
+            Ok(Some(_)) => {}
+            Ok(None) => return Err(format!("BonDriver {} not found", id)),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// Create a group from drivers, or rename one, and optionally set its strategy.
+pub async fn create_group(
+    State(web_state): State<Arc<WebState>>,
+    Json(payload): Json<GroupRequest>,
+) -> impl IntoResponse {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Json(json!({
+            "success": false,
+            "error": "name is required"
+        }));
+    }
+    let strategy = match payload.strategy.as_deref().map(parse_group_strategy).transpose() {
+        Ok(strategy) => strategy,
+        Err(e) => {
+            return Json(json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+
+    let running = running_by_driver_path(&web_state.tuner_pool).await;
+    let db = web_state.database.lock().await;
+    if let Err(e) = check_drivers_exist(&db, &payload.drivers) {
+        return Json(json!({
+            "success": false,
+            "error": e
+        }));
+    }
+
+    let result = (|| -> crate::database::Result<Option<String>> {
+        let rename_from = payload.rename_from.as_deref().map(str::trim);
+        if let Some(from) = rename_from.filter(|s| !s.is_empty()) {
+            if db.rename_group(from, name)? == 0 {
+                return Ok(Some(format!("Group '{}' not found", from)));
+            }
+        }
+        for &id in &payload.drivers {
+            db.add_group_member(name, id)?;
+        }
+        if db.get_group_drivers(name)?.is_empty() {
+            return Ok(Some("A group needs at least one driver".to_string()));
+        }
+        if let Some(strategy) = strategy {
+            db.set_group_strategy(name, strategy)?;
+        }
+        Ok(None)
+    })();
+
+    match result {
+        Ok(None) => {
+            let group = db
+                .get_groups()
+                .ok()
+                .and_then(|groups| groups.into_iter().find(|g| g.name == name));
+            Json(json!({
+                "success": true,
+                "group": group.map(|g| group_json(&g, &running))
+            }))
+        }
+        Ok(Some(e)) => Json(json!({
+            "success": false,
+            "error": e
+        })),
+        Err(e) => Json(json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+/// Add drivers to or remove drivers from a group.
+pub async fn update_group_members(
+    State(web_state): State<Arc<WebState>>,
+    Path(name): Path<String>,
+    Json(payload): Json<GroupMembersRequest>,
+) -> impl IntoResponse {
+    let running = running_by_driver_path(&web_state.tuner_pool).await;
+    let db = web_state.database.lock().await;
+    if let Err(e) = check_drivers_exist(&db, &payload.add) {
+        return Json(json!({
+            "success": false,
+            "error": e
+        }));
+    }
+
+    let result = (|| -> crate::database::Result<(usize, usize)> {
+        for &id in &payload.add {
+            db.add_group_member(&name, id)?;
+        }
+        let mut removed = 0;
+        for &id in &payload.remove {
+            if db.remove_group_member(&name, id)? {
+                removed += 1;
+            }
+        }
+        Ok((payload.add.len(), removed))
+    })();
+
+    match result {
+        Ok((added, removed)) => {
+            // None once the last member has been removed
+            let group = db
+                .get_groups()
+                .ok()
+                .and_then(|groups| groups.into_iter().find(|g| g.name == name));
+            Json(json!({
+                "success": true,
+                "added": added,
+                "removed": removed,
+                "group": group.map(|g| group_json(&g, &running))
+            }))
+        }
+        Err(e) => Json(json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
 /// Get BonDriver ranking by quality score.
 pub async fn get_bondrivers_ranking(
     State(web_state): State<Arc<WebState>>,
@@ -2058,12 +3835,15 @@ pub async fn get_bondrivers_ranking(
         Ok(rows) => {
             let items: Vec<serde_json::Value> = rows
                 .into_iter()
-                .map(|(driver, score, recent_drop_rate, total_sessions)| {
+                .map(|(driver, score, recent_drop_rate, total_sessions, stall_count)| {
                     json!({
+                        "scan_cron": db.get_scan_cron(driver.id).unwrap_or(None),
+                        "scan_scope": db.get_scan_scope(driver.id).unwrap_or(None),
                         "driver": driver,
                         "quality_score": score,
                         "recent_drop_rate": recent_drop_rate,
-                        "total_sessions": total_sessions
+                        "total_sessions": total_sessions,
+                        "stall_count": stall_count
                     })
                 })
                 .collect();
@@ -0,0 +1,224 @@
+//! Optional authentication for the dashboard and API.
+//!
+//! Configured from the `[web]` section of the TOML config. When neither a
+//! token nor a username/password is set, every request is allowed, as
+//! before. Otherwise requests need `Authorization: Bearer <token>` or
+//! `Authorization: Basic ...`; endpoints opened by browsers or media players
+//! that cannot set headers (the dashboard page, WebSocket, TS streams and
+//! export downloads) also accept `?token=<token>`.
+
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{AUTHORIZATION, WWW_AUTHENTICATE},
+        StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Credentials accepted by the web server.
+#[derive(Debug, Clone, Default)]
+pub struct WebAuthConfig {
+    /// Bearer / query token.
+    pub token: Option<String>,
+    /// Basic auth username and password.
+    pub basic: Option<(String, String)>,
+}
+
+impl WebAuthConfig {
+    /// Whether any credential is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.token.is_some() || self.basic.is_some()
+    }
+
+    /// Check an `Authorization` header value.
+    fn check_header(&self, value: &str) -> bool {
+        let (scheme, credentials) = value.split_once(' ').unwrap_or((value, ""));
+        let credentials = credentials.trim();
+        if scheme.eq_ignore_ascii_case("bearer") {
+            return self.check_token(credentials);
+        }
+        if scheme.eq_ignore_ascii_case("basic") {
+            let Some((user, pass)) = &self.basic else {
+                return false;
+            };
+            let Ok(decoded) = STANDARD.decode(credentials) else {
+                return false;
+            };
+            let expected = format!("{}:{}", user, pass);
+            return constant_time_eq(&decoded, expected.as_bytes());
+        }
+        false
+    }
+
+    fn check_token(&self, candidate: &str) -> bool {
+        self.token
+            .as_deref()
+            .is_some_and(|token| constant_time_eq(candidate.as_bytes(), token.as_bytes()))
+    }
+
+    /// Challenge sent with 401 so browsers show a login prompt for Basic auth.
+    fn challenge(&self) -> &'static str {
+        if self.basic.is_some() {
+            "Basic realm=\"recisdb-proxy\", charset=\"UTF-8\""
+        } else {
+            "Bearer realm=\"recisdb-proxy\""
+        }
+    }
+}
+
+/// Compare secrets without leaking the length of the matching prefix.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Paths that accept `?token=` because their clients cannot set headers
+/// (page loads, WebSocket, EventSource, media players and download links).
+fn accepts_query_token(path: &str) -> bool {
+    path == "/"
+        || path == "/api/ws"
+        || path.ends_with("/scan/progress")
+        || path.ends_with("/export")
+        || path == "/api/stream"
+        || path.starts_with("/api/stream/")
+        || path.starts_with("/api/playlist.")
+        || (path.starts_with("/mirakurun/") && path.ends_with("/stream"))
+}
+
+/// Paths served without authentication.
+///
+/// Channel logos are loaded by `<img>` tags, which cannot carry a token;
+/// load balancer health checks do not send credentials either.
+fn is_public(path: &str) -> bool {
+    path == "/healthz"
+        || path.starts_with("/logos/")
+        || (path.starts_with("/api/channel/") && path.ends_with("/logo"))
+}
+
+/// Extract the `token` query parameter.
+fn query_token(query: Option<&str>) -> Option<String> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "token")
+        .map(|(_, value)| percent_decode(value))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => out.push(b' '),
+            (b, _) => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Percent-encode a value for use in a URL query.
+pub(crate) fn encode_query_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            let _ = write!(out, "%{:02X}", b);
+        }
+    }
+    out
+}
+
+/// Middleware rejecting unauthenticated requests with 401.
+pub async fn require_auth(
+    State(auth): State<Arc<WebAuthConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !auth.is_enabled() || is_public(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| auth.check_header(v))
+        || (accepts_query_token(request.uri().path())
+            && query_token(request.uri().query()).is_some_and(|token| auth.check_token(&token)));
+    if authorized {
+        return next.run(request).await;
+    }
+
+    (
+        StatusCode::UNAUTHORIZED,
+        [(WWW_AUTHENTICATE, auth.challenge())],
+        Json(json!({ "success": false, "error": "Unauthorized" })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> WebAuthConfig {
+        WebAuthConfig {
+            token: Some("s3cret".to_string()),
+            basic: Some(("admin".to_string(), "pa:ss".to_string())),
+        }
+    }
+
+    #[test]
+    fn test_check_header() {
+        let auth = config();
+        assert!(auth.check_header("Bearer s3cret"));
+        assert!(auth.check_header(&format!("Basic {}", STANDARD.encode("admin:pa:ss"))));
+        assert!(!auth.check_header("Bearer wrong"));
+        assert!(!auth.check_header(&format!("Basic {}", STANDARD.encode("admin:wrong"))));
+        assert!(!auth.check_header("Basic !!!"));
+        assert!(!WebAuthConfig::default().check_header("Bearer "));
+    }
+
+    #[test]
+    fn test_query_token() {
+        assert_eq!(query_token(Some("nid=1&token=a%2Fb&sid=2")).as_deref(), Some("a/b"));
+        assert_eq!(query_token(Some("nid=1")), None);
+        assert_eq!(query_token(None), None);
+        assert_eq!(percent_decode(&encode_query_value("a/b c+%")), "a/b c+%");
+
+        assert!(accepts_query_token("/api/stream/1/1024"));
+        assert!(accepts_query_token("/api/bondriver/1/scan/progress"));
+        assert!(accepts_query_token("/mirakurun/api/channels/GR/27/stream"));
+        assert!(!accepts_query_token("/api/server-config"));
+    }
+
+    #[test]
+    fn test_is_public() {
+        assert!(is_public("/healthz"));
+        assert!(is_public("/logos/4_1024.png"));
+        assert!(is_public("/api/channel/12/logo"));
+        assert!(!is_public("/healthz/extra"));
+        assert!(!is_public("/api/channels"));
+    }
+}
@@ -305,6 +305,7 @@ const HTML_CONTENT: &str = r#"
             <button class="tab" data-tab="scan-history">スキャン履歴</button>
             <button class="tab" data-tab="session-history">セッション履歴</button>
             <button class="tab" data-tab="alerts">アラート</button>
+            <button class="tab" data-tab="recordings">録画</button>
             <button class="tab" data-tab="settings">設定</button>
         </nav>
 
@@ -381,6 +382,22 @@ const HTML_CONTENT: &str = r#"
                         <svg id="signal-graph" class="sparkline"></svg>
                     </div>
                 </div>
+                <h4 style="margin: 12px 0 6px;">問題のある PID</h4>
+                <table class="responsive-table">
+                    <thead>
+                        <tr>
+                            <th>PID</th>
+                            <th>パケット</th>
+                            <th>CC不連続</th>
+                            <th>スクランブル</th>
+                            <th>エラー</th>
+                            <th>PMT</th>
+                        </tr>
+                    </thead>
+                    <tbody id="pid-offenders-body">
+                        <tr><td colspan="6" class="empty-state">問題のある PID はありません</td></tr>
+                    </tbody>
+                </table>
             </div>
         </div>
 
@@ -432,7 +449,13 @@ const HTML_CONTENT: &str = r#"
                             <input type="checkbox" id="channel-enabled-filter" onchange="refreshChannels()">
                             有効のみ
                         </label>
+                        <label class="form-check" style="font-size: 13px;">
+                            <input type="checkbox" id="channel-reception-filter" onchange="refreshChannels()">
+                            受信不可のみ
+                        </label>
+                        <input type="search" id="channel-search" placeholder="チャンネル名・TS名で検索" oninput="onChannelSearchInput()" style="font-size: 13px; width: 180px;">
                         <button class="btn btn-secondary btn-sm" onclick="refreshChannels()">更新</button>
+                        <button class="btn btn-secondary btn-sm" onclick="sortChannels('display_order')" title="並び順で表示中はドラッグで並べ替えできます">並び順</button>
                         <button class="btn btn-warning btn-sm" onclick="enterChannelEditMode()">編集モード</button>
                         <a id="channel-export-btn" class="btn btn-secondary btn-sm" href="/api/channels/export" download="channels.csv">CSVエクスポート</a>
                         <label class="btn btn-secondary btn-sm" style="cursor:pointer;margin:0;">
@@ -464,6 +487,7 @@ const HTML_CONTENT: &str = r#"
                     <option value="bon_space">BonSpace</option>
                     <option value="bon_channel">BonChannel</option>
                     <option value="priority">優先度</option>
+                    <option value="display_order">並び順</option>
                 </select>
 
                 <select id="channel-sort-key-2" onchange="setChannelSortFromUI()">
@@ -480,6 +504,7 @@ const HTML_CONTENT: &str = r#"
                     <option value="bon_space">BonSpace</option>
                     <option value="bon_channel">BonChannel</option>
                     <option value="priority">優先度</option>
+                    <option value="display_order">並び順</option>
                 </select>
 
                 <select id="channel-sort-key-3" onchange="setChannelSortFromUI()">
@@ -496,6 +521,7 @@ const HTML_CONTENT: &str = r#"
                     <option value="bon_space">BonSpace</option>
                     <option value="bon_channel">BonChannel</option>
                     <option value="priority">優先度</option>
+                    <option value="display_order">並び順</option>
                 </select>
 
                 <button class="btn btn-secondary btn-sm" id="channel-sort-order-1" onclick="toggleChannelSortOrder(0)">第1:昇順</button>
@@ -566,6 +592,66 @@ const HTML_CONTENT: &str = r#"
                 <div id="config-message" style="margin-top: 15px; display: none;"></div>
             </div>
 
+            <h3 style="margin-top: 30px;">サーバー設定</h3>
+            <div class="settings-form">
+                <div class="form-group">
+                    <label for="server-max-connections">最大同時接続数</label>
+                    <input type="number" id="server-max-connections" min="1" value="64">
+                    <small>再起動なしで反映されます。下げても既存の接続は切断されず、接続数が下回るまで新規接続を待機させます（現在の接続数: <span id="server-active-connections">-</span>）</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="server-idle-timeout">アイドルタイムアウト (秒)</label>
+                    <input type="number" id="server-idle-timeout" min="0" placeholder="設定ファイルの値">
+                    <small>クライアントから何も受信しないまま経過するとセッションを切断し、チューナーを解放します（0 で無効。次回接続から有効）</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="server-max-bitrate">最大ビットレート (Mbps)</label>
+                    <input type="number" id="server-max-bitrate" min="0" step="0.1" placeholder="無制限">
+                    <small>1 セッションあたりの TS 送出レートの上限です。遅延後の一括送信で回線が埋まるのを防ぎます（0 で無制限。クライアントごとの上書きが優先、接続中のセッションにも反映）</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="server-quality-half-life">品質スコアの半減期 (時間)</label>
+                    <input type="number" id="server-quality-half-life" min="0" step="1" placeholder="168">
+                    <small>BonDriver の品質統計はこの時間ごとに重みが半分になり、最近のセッションほど品質スコアとランキングに強く反映されます（0 で減衰なし）</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="server-preferred-region">優先地域</label>
+                    <select id="server-preferred-region">
+                        <option value="">（指定なし）</option>
+                        <option value="北海道">北海道</option>
+                        <option value="東北">東北</option>
+                        <option value="関東">関東</option>
+                        <option value="甲信越">甲信越</option>
+                        <option value="北陸">北陸</option>
+                        <option value="東海">東海</option>
+                        <option value="近畿">近畿</option>
+                        <option value="中国">中国</option>
+                        <option value="四国">四国</option>
+                        <option value="九州">九州</option>
+                        <option value="沖縄">沖縄</option>
+                    </select>
+                </div>
+
+                <div class="form-group">
+                    <label>
+                        <input type="checkbox" id="server-limit-spaces">
+                        チューニング空間を優先地域とBS/CSに限定
+                    </label>
+                    <small>他地域の地デジ空間をクライアントのスペース一覧から隠します（論理チャンネル指定による選局は引き続き可能。次回チューナオープン時から有効）</small>
+                </div>
+
+                <div style="margin-top: 20px; display: flex; gap: 10px;">
+                    <button class="btn btn-primary" onclick="saveServerConfig()">保存</button>
+                    <button class="btn btn-secondary" onclick="loadServerConfig()">リセット</button>
+                </div>
+
+                <div id="server-config-message" style="margin-top: 15px; display: none;"></div>
+            </div>
+
             <h3 style="margin-top: 30px;">チューナ最適化設定</h3>
             <div class="settings-form">
                 <div class="form-group">
@@ -587,6 +673,34 @@ const HTML_CONTENT: &str = r#"
                     <small>OpenTuner 後に SetChannel が来ない場合の待機時間</small>
                 </div>
 
+                <div class="form-group">
+                    <label class="form-check">
+                        <input type="checkbox" id="tuner-prewarm-group">
+                        グループの Pre-Warm を有効にする
+                    </label>
+                    <small>グループで開いた場合も、未使用のドライバーのうち品質スコアが最も高いものを Pre-Warm します（Pre-Warm 有効時のみ）</small>
+                </div>
+
+                <div class="form-group">
+                    <label class="form-check">
+                        <input type="checkbox" id="tuner-prewarm-on-startup">
+                        起動時にデフォルトチューナーを Pre-Warm する
+                    </label>
+                    <small>サーバー起動時にデフォルトチューナーを開いて待機させ、最初のクライアントへすぐに TS を送ります。次回起動から反映されます（キープアライブ 0 秒では無効）</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="tuner-min-signal-terrestrial">最低信号レベル：地上波・CATV（dB）</label>
+                    <input type="number" id="tuner-min-signal-terrestrial" min="0" step="0.1" value="0">
+                    <small>選局後の信号レベルがこの値未満の場合は選局失敗とし、グループの他のドライバーを試します。確認のため選局応答が最大数秒遅れます（0 で無効）</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="tuner-min-signal-satellite">最低信号レベル：BS・CS（dB）</label>
+                    <input type="number" id="tuner-min-signal-satellite" min="0" step="0.1" value="0">
+                    <small>衛星放送は地上波と信号レベルの尺度が異なるため別に設定します（0 で無効）</small>
+                </div>
+
                 <div class="form-group">
                     <label for="tuner-setch-retry-interval">SetChannel リトライ間隔（ms）</label>
                     <input type="number" id="tuner-setch-retry-interval" min="1" value="500">
@@ -611,6 +725,52 @@ const HTML_CONTENT: &str = r#"
                     <small>信号値が返るまで待つ最大時間</small>
                 </div>
 
+                <div class="form-group">
+                    <label class="form-check">
+                        <input type="checkbox" id="tuner-driver-affinity" checked>
+                        チューナ固定（アフィニティ）を有効にする
+                    </label>
+                    <small>グループ利用時、チャンネル切替後も同じ BonDriver を優先して使用します（無効にすると毎回負荷分散で選択）</small>
+                </div>
+
+                <div class="form-group">
+                    <label class="form-check">
+                        <input type="checkbox" id="tuner-align-ts-output">
+                        TS出力を188バイト境界に揃える
+                    </label>
+                    <small>同期バイト位置で揃えた完全なTSパケットのみを配信します（次回チューナ起動時から有効。無効時は低遅延のため受信データをそのまま転送）</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="tuner-rekey-percent">復号失敗時の再初期化しきい値（%）</label>
+                    <input type="number" id="tuner-rekey-percent" min="0" max="100" value="90">
+                    <small>復号後もスクランブルされたパケットがこの割合以上続くとB25デコーダを再初期化します（0 で無効）</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="tuner-rekey-window">復号失敗の判定時間（秒）</label>
+                    <input type="number" id="tuner-rekey-window" min="1" value="30">
+                    <small>しきい値を超えた状態がこの時間続いたら再初期化します</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="tuner-stall-timeout">受信停止の検出時間（ミリ秒）</label>
+                    <input type="number" id="tuner-stall-timeout" min="0" value="15000">
+                    <small>受信中のチューナーからTSがこの時間届かない場合、リーダーを再起動します。信号レベルが 0 の場合（電波なし）は再起動しません（0 で無効）</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="tuner-coalesce-bytes">TS まとめ送信サイズ（バイト）</label>
+                    <input type="number" id="tuner-coalesce-bytes" min="0" max="188000" step="188" value="0">
+                    <small>小さな TS チャンクをこのサイズまでまとめて 1 フレームで送信し、フレーム数とシステムコールを減らします。最大 188000（0 で無効）。変更は次回の配信開始から反映されます</small>
+                </div>
+
+                <div class="form-group">
+                    <label for="tuner-coalesce-flush">TS まとめ送信の最大待ち時間（ミリ秒）</label>
+                    <input type="number" id="tuner-coalesce-flush" min="1" value="20">
+                    <small>サイズに達しなくてもこの時間が経てば送信します。ワンセグなど低ビットレートの配信で映像が途切れないよう短めにしてください</small>
+                </div>
+
                 <div style="margin-top: 20px; display: flex; gap: 10px;">
                     <button class="btn btn-primary" onclick="saveTunerConfig()">保存</button>
                     <button class="btn btn-secondary" onclick="loadTunerConfig()">リセット</button>
@@ -690,7 +850,9 @@ const HTML_CONTENT: &str = r#"
                 <h3>セッション履歴</h3>
                 <div class="filter-bar">
                     <input type="text" id="session-filter-address" placeholder="クライアントアドレスで絞り込み">
-                    <button class="btn btn-secondary btn-sm" onclick="refreshSessionHistory()">更新</button>
+                    <input type="date" id="session-filter-from" title="開始日（この日以降）">
+                    <input type="date" id="session-filter-to" title="終了日（この日まで）">
+                    <button class="btn btn-secondary btn-sm" onclick="sessionHistoryOffset = 0; refreshSessionHistory()">更新</button>
                 </div>
             </div>
             <table id="session-history-table" class="responsive-table sortable-table">
@@ -712,6 +874,11 @@ const HTML_CONTENT: &str = r#"
                     <tr><td colspan="10" class="empty-state">セッション履歴がありません</td></tr>
                 </tbody>
             </table>
+            <div class="filter-bar">
+                <button class="btn btn-secondary btn-sm" id="session-history-prev" onclick="changeSessionHistoryPage(-1)">前へ</button>
+                <span id="session-history-range">-</span>
+                <button class="btn btn-secondary btn-sm" id="session-history-next" onclick="changeSessionHistoryPage(1)">次へ</button>
+            </div>
         </div>
 
         <!-- Alerts Tab -->
@@ -724,6 +891,7 @@ const HTML_CONTENT: &str = r#"
                 <thead>
                     <tr>
                         <th class="sortable" data-sort-type="datetime">発生時刻</th>
+                        <th class="sortable" data-sort-type="text">重要度</th>
                         <th class="sortable" data-sort-type="number">ルールID</th>
                         <th class="sortable" data-sort-type="number">セッション</th>
                         <th class="sortable" data-sort-type="text">メッセージ</th>
@@ -731,7 +899,7 @@ const HTML_CONTENT: &str = r#"
                     </tr>
                 </thead>
                 <tbody id="alerts-body">
-                    <tr><td colspan="5" class="empty-state">アクティブアラートはありません</td></tr>
+                    <tr><td colspan="6" class="empty-state">アクティブアラートはありません</td></tr>
                 </tbody>
             </table>
 
@@ -747,12 +915,61 @@ const HTML_CONTENT: &str = r#"
                         <th class="sortable" data-sort-type="text">監視項目</th>
                         <th class="sortable" data-sort-type="text">条件（比較）</th>
                         <th class="sortable" data-sort-type="number">しきい値</th>
+                        <th class="sortable" data-sort-type="text">重要度</th>
                         <th class="sortable" data-sort-type="text">有効</th>
                         <th>操作</th>
                     </tr>
                 </thead>
                 <tbody id="alert-rules-body">
-                    <tr><td colspan="7" class="empty-state">ルールがありません</td></tr>
+                    <tr><td colspan="8" class="empty-state">ルールがありません</td></tr>
+                </tbody>
+            </table>
+        </div>
+
+        <!-- Recordings Tab -->
+        <div id="recordings" class="tab-content">
+            <div class="section-header">
+                <h3>録画中</h3>
+                <div>
+                    <button class="btn btn-secondary btn-sm" onclick="refreshRecordings(); refreshReservations()">更新</button>
+                    <button class="btn btn-primary btn-sm" onclick="openModal('recording-modal')">録画開始</button>
+                </div>
+            </div>
+            <table id="recordings-table" class="responsive-table sortable-table">
+                <thead>
+                    <tr>
+                        <th class="sortable" data-sort-type="number">ID</th>
+                        <th class="sortable" data-sort-type="text">サービス</th>
+                        <th class="sortable" data-sort-type="text">チューナー</th>
+                        <th class="sortable" data-sort-type="text">出力先</th>
+                        <th class="sortable" data-sort-type="number">経過</th>
+                        <th class="sortable" data-sort-type="number">書き込み</th>
+                        <th>操作</th>
+                    </tr>
+                </thead>
+                <tbody id="recordings-body">
+                    <tr><td colspan="7" class="empty-state">録画中の番組はありません</td></tr>
+                </tbody>
+            </table>
+
+            <div class="section-header" style="margin-top: 20px;">
+                <h3>録画予約</h3>
+                <button class="btn btn-primary btn-sm" onclick="openModal('reservation-modal')">予約追加</button>
+            </div>
+            <table id="reservations-table" class="responsive-table sortable-table">
+                <thead>
+                    <tr>
+                        <th class="sortable" data-sort-type="datetime">開始</th>
+                        <th class="sortable" data-sort-type="datetime">終了</th>
+                        <th class="sortable" data-sort-type="text">番組</th>
+                        <th class="sortable" data-sort-type="text">サービス</th>
+                        <th class="sortable" data-sort-type="text">出力先</th>
+                        <th class="sortable" data-sort-type="text">状態</th>
+                        <th>操作</th>
+                    </tr>
+                </thead>
+                <tbody id="reservations-body">
+                    <tr><td colspan="7" class="empty-state">予約はありません</td></tr>
                 </tbody>
             </table>
         </div>
@@ -776,10 +993,47 @@ const HTML_CONTENT: &str = r#"
                     <label>グループ名</label>
                     <input type="text" id="bd-group-name" placeholder="例：PX-MLT, PX-S">
                 </div>
+                <div class="form-group">
+                    <label>グループ内の選択方式 (グループ全体に適用)</label>
+                    <select id="bd-group-strategy">
+                        <option value="">品質スコア順 (既定)</option>
+                        <option value="round_robin">ラウンドロビン</option>
+                        <option value="least_loaded">負荷の低い順</option>
+                    </select>
+                </div>
                 <div class="form-group">
                     <label>最大インスタンス数</label>
                     <input type="number" id="bd-max-instances" min="1" max="32" value="1">
                 </div>
+                <div class="form-group">
+                    <label class="form-check">
+                        <input type="checkbox" id="bd-supports-4k">
+                        4K/HEVC 受信対応 (BS4K/CS4K チャンネルで優先)
+                    </label>
+                </div>
+                <div class="form-group">
+                    <label>配信バッファ容量（チャンク数）</label>
+                    <input type="number" id="bd-broadcast-capacity" min="0" max="65536" placeholder="4096">
+                    <small>16〜65536。空欄または 0 で既定値 (4096)。次にチューナーを開いたときから適用されます</small>
+                </div>
+                <div class="form-group">
+                    <label>遅延クライアントへの対応</label>
+                    <select id="bd-lag-policy">
+                        <option value="">古いデータを破棄 (既定)</option>
+                        <option value="slow_producer">読み出しを待たせる (欠落なし)</option>
+                    </select>
+                </div>
+                <div class="form-group">
+                    <label>受信判定の最低信号レベル (dB)</label>
+                    <input type="number" id="bd-reception-min-signal" min="0" max="100" step="0.1" placeholder="3.0">
+                    <small>スキャン時にこの信号レベル未満、またはロックできなかったチャンネルを「受信不可」にします。空欄または 0 で既定値 (3.0)</small>
+                </div>
+                <div class="form-group">
+                    <label class="form-check">
+                        <input type="checkbox" id="bd-auto-disable-no-reception">
+                        受信不可のチャンネルをスキャン時に自動で無効化する
+                    </label>
+                </div>
                 <div class="form-group">
                     <label class="form-check">
                         <input type="checkbox" id="bd-auto-scan">
@@ -790,6 +1044,23 @@ const HTML_CONTENT: &str = r#"
                     <label>スキャン間隔（時間）</label>
                     <input type="number" id="bd-scan-interval" min="1" max="720" value="24">
                 </div>
+                <div class="form-group">
+                    <label>スキャン時刻（cron）</label>
+                    <input type="text" id="bd-scan-cron" placeholder="0 4 * * *">
+                    <small>分 時 日 月 曜日（サーバーのローカル時刻）。設定するとスキャン間隔より優先されます。空欄でスキャン間隔を使用</small>
+                </div>
+                <div class="form-group">
+                    <label>スキャン範囲</label>
+                    <select id="bd-scan-scope" onchange="document.getElementById('bd-scan-channels').disabled = this.value !== 'channels'">
+                        <option value="">すべて</option>
+                        <option value="terrestrial">地デジのみ</option>
+                        <option value="bs">BSのみ</option>
+                        <option value="cs">CSのみ</option>
+                        <option value="channels">地デジの物理チャンネルを指定</option>
+                    </select>
+                    <input type="text" id="bd-scan-channels" placeholder="13,16,20-27" disabled>
+                    <small>受信できる帯域だけをスキャンして時間を短縮します</small>
+                </div>
                 <div class="form-group">
                     <label>スキャン優先度</label>
                     <input type="number" id="bd-scan-priority" min="0" max="100" value="0">
@@ -808,6 +1079,88 @@ const HTML_CONTENT: &str = r#"
         </div>
     </div>
     
+        <div id="recording-modal" class="modal">
+            <div class="modal-content">
+                <h3>録画開始</h3>
+                <form id="recording-form">
+                    <div class="form-group">
+                        <label>NID / TSID / SID</label>
+                        <input type="number" id="rec-nid" min="0" max="65535" placeholder="NID" required>
+                        <input type="number" id="rec-tsid" min="0" max="65535" placeholder="TSID" required>
+                        <input type="number" id="rec-sid" min="0" max="65535" placeholder="SID" required>
+                    </div>
+                    <div class="form-group">
+                        <label>録画時間（秒）</label>
+                        <input type="number" id="rec-duration" min="1" value="60" required>
+                    </div>
+                    <div class="form-group">
+                        <label>出力先ファイル</label>
+                        <input type="text" id="rec-output-path" placeholder="test.ts" required>
+                        <small>サーバーの録画ディレクトリからの相対パス。既に存在するファイルには書き込みません</small>
+                    </div>
+                    <div class="form-group">
+                        <label class="form-check">
+                            <input type="checkbox" id="rec-full-ts">
+                            TS 全体を録画する（オフの場合は指定サービスのみ）
+                        </label>
+                    </div>
+                    <div class="form-group">
+                        <label>分割（秒 / MB）</label>
+                        <input type="number" id="rec-segment-duration" min="0" value="0" placeholder="秒">
+                        <input type="number" id="rec-segment-size" min="0" value="0" placeholder="MB">
+                        <small>どちらかを指定すると out-000.ts, out-001.ts ... のように分割して保存します（0 で分割しない）</small>
+                    </div>
+                    <div class="form-group">
+                        <label class="form-check">
+                            <input type="checkbox" id="rec-segment-index">
+                            分割時にインデックスファイル（.index.tsv）を書き出す
+                        </label>
+                    </div>
+                    <div class="form-actions">
+                        <button type="button" class="btn btn-secondary" onclick="closeModal('recording-modal')">キャンセル</button>
+                        <button type="submit" class="btn btn-primary">開始</button>
+                    </div>
+                </form>
+            </div>
+        </div>
+
+        <div id="reservation-modal" class="modal">
+            <div class="modal-content">
+                <h3>録画予約追加</h3>
+                <form id="reservation-form">
+                    <div class="form-group">
+                        <label>NID / TSID / SID</label>
+                        <input type="number" id="rsv-nid" min="0" max="65535" placeholder="NID" required>
+                        <input type="number" id="rsv-tsid" min="0" max="65535" placeholder="TSID" required>
+                        <input type="number" id="rsv-sid" min="0" max="65535" placeholder="SID" required>
+                    </div>
+                    <div class="form-group">
+                        <label>イベントID（任意）</label>
+                        <input type="number" id="rsv-event-id" min="0" max="65535">
+                        <small>指定すると EPG の開始・終了時刻を使い、時刻変更にも追従します</small>
+                    </div>
+                    <div class="form-group">
+                        <label>開始 / 終了（イベントID未指定時）</label>
+                        <input type="datetime-local" id="rsv-start">
+                        <input type="datetime-local" id="rsv-end">
+                    </div>
+                    <div class="form-group">
+                        <label>番組名（任意）</label>
+                        <input type="text" id="rsv-title">
+                    </div>
+                    <div class="form-group">
+                        <label>出力先ファイル</label>
+                        <input type="text" id="rsv-output-path" placeholder="news.ts" required>
+                        <small>サーバーの録画ディレクトリからの相対パス。既に存在するファイルには書き込みません</small>
+                    </div>
+                    <div class="form-actions">
+                        <button type="button" class="btn btn-secondary" onclick="closeModal('reservation-modal')">キャンセル</button>
+                        <button type="submit" class="btn btn-primary">保存</button>
+                    </div>
+                </form>
+            </div>
+        </div>
+
         <div id="alert-rule-modal" class="modal">
             <div class="modal-content">
                 <h3>アラートルール追加</h3>
@@ -843,10 +1196,27 @@ const HTML_CONTENT: &str = r#"
                         <input type="number" id="ar-threshold" step="0.01" required>
                         <small>数値を入力（例: 0.05, 15, 2800）</small>
                     </div>
+                    <div class="form-group">
+                        <label>重要度</label>
+                        <select id="ar-severity">
+                            <option value="info">情報</option>
+                            <option value="warning" selected>警告</option>
+                            <option value="critical">重大</option>
+                        </select>
+                    </div>
+                    <div class="form-group">
+                        <label>重大アラート時の動作</label>
+                        <select id="ar-critical-action">
+                            <option value="">通知のみ</option>
+                            <option value="disconnect">セッションを切断</option>
+                            <option value="disable_auto_scan">ドライバーの自動スキャンを無効化</option>
+                        </select>
+                        <small>重要度が「重大」のルールが発火したときだけ実行します</small>
+                    </div>
                     <div class="form-group">
                         <label>Webhook URL（任意）</label>
                         <input type="text" id="ar-webhook-url" placeholder="https://...">
-                        <small>Discord/Slack/LINE などの Webhook URL</small>
+                        <small>Discord/Slack/LINE などの Webhook URL（メールの場合は mailto:宛先,宛先）</small>
                     </div>
                     <div class="form-group">
                         <label>Webhook 形式</label>
@@ -855,9 +1225,15 @@ const HTML_CONTENT: &str = r#"
                             <option value="discord">Discord</option>
                             <option value="slack">Slack</option>
                             <option value="line">LINE</option>
+                            <option value="email">メール（SMTP）</option>
                         </select>
                         <small>送信先に合わせて選択します</small>
                     </div>
+                    <div class="form-group">
+                        <label>クールダウン（秒）</label>
+                        <input type="number" id="ar-cooldown" min="0" step="1" value="0">
+                        <small>通知後、同じセッションでこの秒数が経つまで再通知しません（0 = なし）</small>
+                    </div>
                     <div class="form-group">
                         <label class="form-check">
                             <input type="checkbox" id="ar-enabled" checked>
@@ -939,6 +1315,14 @@ const HTML_CONTENT: &str = r#"
                         排他を上書きする
                     </label>
                 </div>
+                <div class="form-group">
+                    <label>最大ビットレート (Mbps)</label>
+                    <input type="number" id="override-max-bitrate" min="0" step="0.1" placeholder="0 で無制限">
+                    <label class="form-check" style="margin-top:6px;">
+                        <input type="checkbox" id="override-max-bitrate-enabled">
+                        最大ビットレートを上書きする
+                    </label>
+                </div>
                 <div class="form-actions">
                     <button type="button" class="btn btn-secondary" onclick="closeModal('client-override-modal')">キャンセル</button>
                     <button type="submit" class="btn btn-primary">保存</button>
@@ -948,6 +1332,26 @@ const HTML_CONTENT: &str = r#"
     </div>
 
     <script>
+        // Token auth: the dashboard is opened as /?token=...; API calls send
+        // it as a Bearer header, and URLs opened without fetch carry it in
+        // the query. Basic auth needs nothing here (the browser sends it).
+        const authToken = new URLSearchParams(location.search).get('token') || sessionStorage.getItem('authToken');
+        if (authToken) {
+            sessionStorage.setItem('authToken', authToken);
+            const plainFetch = window.fetch.bind(window);
+            window.fetch = (url, options = {}) => {
+                const headers = new Headers(options.headers || {});
+                headers.set('Authorization', 'Bearer ' + authToken);
+                return plainFetch(url, { ...options, headers });
+            };
+        }
+
+        function withAuthToken(url) {
+            if (!authToken) return url;
+            return url + (url.includes('?') ? '&' : '?') + 'token=' + encodeURIComponent(authToken);
+        }
+        document.getElementById('channel-export-btn').href = withAuthToken('/api/channels/export');
+
         // Tab switching
         document.querySelectorAll('.tab').forEach(tab => {
             tab.addEventListener('click', () => {
@@ -962,6 +1366,7 @@ const HTML_CONTENT: &str = r#"
                 else if (tab.dataset.tab === 'scan-history') refreshHistory();
                 else if (tab.dataset.tab === 'session-history') refreshSessionHistory();
                 else if (tab.dataset.tab === 'alerts') { refreshAlerts(); refreshAlertRules(); }
+                else if (tab.dataset.tab === 'recordings') { refreshRecordings(); refreshReservations(); }
             });
         });
 
@@ -1121,7 +1526,8 @@ const HTML_CONTENT: &str = r#"
 
         function renderOverrideBadge(c) {
             const hasOverride = (c.override_priority !== null && c.override_priority !== undefined) ||
-                (c.override_exclusive !== null && c.override_exclusive !== undefined);
+                (c.override_exclusive !== null && c.override_exclusive !== undefined) ||
+                (c.override_max_bitrate_mbps !== null && c.override_max_bitrate_mbps !== undefined);
             if (!hasOverride) return '<span class="badge badge-info">なし</span> ';
             const parts = [];
             if (c.override_priority !== null && c.override_priority !== undefined) {
@@ -1130,6 +1536,9 @@ const HTML_CONTENT: &str = r#"
             if (c.override_exclusive !== null && c.override_exclusive !== undefined) {
                 parts.push(`E=${c.override_exclusive ? 'ON' : 'OFF'}`);
             }
+            if (c.override_max_bitrate_mbps !== null && c.override_max_bitrate_mbps !== undefined) {
+                parts.push(`B=${c.override_max_bitrate_mbps > 0 ? c.override_max_bitrate_mbps : '∞'}`);
+            }
             return `<span class="badge badge-warning">${parts.join(' ')}</span> `;
         }
 
@@ -1144,9 +1553,9 @@ const HTML_CONTENT: &str = r#"
             return bandType !== null && bandType !== undefined ? (classes[bandType] || 'badge-danger') : '';
         }
 
-        function getChannelLogoHtml(c) {
+        function getChannelLogoHtml(c, channelId) {
             if (c.nid === null || c.nid === undefined || c.sid === null || c.sid === undefined) return '';
-            const src = `/logos/${c.nid}_${c.sid}.png`;
+            const src = channelId !== undefined ? `/api/channel/${channelId}/logo` : `/logos/${c.nid}_${c.sid}.png`;
             return `<img class="channel-logo" src="${src}" alt="logo" onerror="this.style.display='none'">`;
         }
 
@@ -1197,22 +1606,22 @@ const HTML_CONTENT: &str = r#"
                 tbody.innerHTML = data.clients.map(c => `
                     <tr onclick="selectClient(${c.session_id})" style="cursor:pointer;">
                         <td data-sort-value="${c.session_id}">${c.session_id}</td>
-                        <td data-sort-value="${escapeHtml(c.address)}">${escapeHtml(c.address)} <span style="color:#999;font-size:11px">(${formatDuration(c.connected_seconds)})</span></td>
-                        <td data-sort-value="${escapeHtml(c.host || '-')}">${escapeHtml(c.host || '-')}</td>
+                        <td data-sort-value="${escapeHtml(c.address)}">${escapeHtml(c.address)} <span style="color:#999;font-size:11px">(${formatDuration(c.connected_seconds)}${c.latency_ms != null ? ` / RTT ${c.latency_ms.toFixed(1)} ms` : ''})</span></td>
+                        <td data-sort-value="${escapeHtml(c.host || '-')}">${escapeHtml(c.host || '-')}${c.client_identity ? ` <span class="badge badge-info" title="クライアント証明書">${escapeHtml(c.client_identity)}</span>` : ''}</td>
                         <td data-sort-value="${c.is_streaming ? '1' : '0'}"><span class="badge ${c.is_streaming ? 'badge-success' : 'badge-warning'}">${c.is_streaming ? 'ストリーミング中' : '待機中'}</span></td>
                         <td data-sort-value="${escapeHtml(c.tuner_path || '-')}"><code>${escapeHtml(c.tuner_path || '-')}</code></td>
                         <td data-sort-value="${escapeHtml(c.channel_name || c.channel_info || '-')}">${getChannelLogoHtml(c)}${escapeHtml(c.channel_name || c.channel_info || '-')}</td>
                         <td data-sort-value="${c.signal_level != null ? c.signal_level : 0}">${c.signal_level != null ? c.signal_level.toFixed(1) : '-'} dB</td>
                         <td data-sort-value="${c.packets_sent || 0}">${formatPackets(c.packets_sent)}</td>
-                        <td data-sort-value="${c.packets_dropped || 0}">${formatPackets(c.packets_dropped)}</td>
+                        <td data-sort-value="${c.packets_dropped || 0}" title="${c.lag_events ? `バッファ遅延 ${c.lag_events} 回 (${c.lagged_chunks} チャンク欠落)` : ''}">${formatPackets(c.packets_dropped)}${c.lag_events ? ` <span style="color:#e67e22;font-size:11px">(遅延 ${c.lag_events})</span>` : ''}</td>
                         <td data-sort-value="${c.packets_scrambled || 0}">${formatPackets(c.packets_scrambled)}</td>
                         <td data-sort-value="${c.packets_error || 0}">${formatPackets(c.packets_error)}</td>
-                        <td data-sort-value="${c.current_bitrate_mbps != null ? c.current_bitrate_mbps : 0}">${c.current_bitrate_mbps != null ? c.current_bitrate_mbps.toFixed(2) : '-'} Mbps</td>
+                        <td data-sort-value="${c.current_bitrate_mbps != null ? c.current_bitrate_mbps : 0}">${c.current_bitrate_mbps != null ? c.current_bitrate_mbps.toFixed(2) : '-'} Mbps${c.max_bitrate_mbps ? ` <span style="color:#999;font-size:11px">(上限 ${c.max_bitrate_mbps})</span>` : ''}</td>
                         <td data-sort-value="${c.effective_priority !== null && c.effective_priority !== undefined ? c.effective_priority : -99999}">${c.effective_priority !== null && c.effective_priority !== undefined ? c.effective_priority : '-'}</td>
                         <td data-sort-value="${c.effective_exclusive ? '1' : '0'}"><span class="badge ${c.effective_exclusive ? 'badge-danger' : 'badge-success'}">${c.effective_exclusive ? 'ON' : 'OFF'}</span></td>
-                        <td data-sort-value="${(c.override_priority !== null && c.override_priority !== undefined) || (c.override_exclusive !== null && c.override_exclusive !== undefined) ? '1' : '0'}">
+                        <td data-sort-value="${(c.override_priority !== null && c.override_priority !== undefined) || (c.override_exclusive !== null && c.override_exclusive !== undefined) || (c.override_max_bitrate_mbps !== null && c.override_max_bitrate_mbps !== undefined) ? '1' : '0'}">
                             ${renderOverrideBadge(c)}
-                            <button class="btn btn-primary btn-sm" onclick="event.stopPropagation(); openOverrideModal(${c.session_id}, ${c.override_priority !== null && c.override_priority !== undefined ? c.override_priority : 'null'}, ${c.override_exclusive !== null && c.override_exclusive !== undefined ? c.override_exclusive : 'null'});">設定</button>
+                            <button class="btn btn-primary btn-sm" onclick="event.stopPropagation(); openOverrideModal(${c.session_id}, ${c.override_priority !== null && c.override_priority !== undefined ? c.override_priority : 'null'}, ${c.override_exclusive !== null && c.override_exclusive !== undefined ? c.override_exclusive : 'null'}, ${c.override_max_bitrate_mbps !== null && c.override_max_bitrate_mbps !== undefined ? c.override_max_bitrate_mbps : 'null'});">設定</button>
                             <button class="btn btn-secondary btn-sm" onclick="event.stopPropagation(); clearOverride(${c.session_id});">解除</button>
                         </td>
                         <td><button class="btn btn-danger btn-sm" onclick="event.stopPropagation(); disconnectClient(${c.session_id});">切断</button></td>
@@ -1224,9 +1633,11 @@ const HTML_CONTENT: &str = r#"
         }
 
         let activeClientId = null;
+        let activeClientMetrics = null;
 
         function selectClient(id) {
             activeClientId = id;
+            activeClientMetrics = null;
             document.getElementById('client-metrics-panel').style.display = 'block';
             document.getElementById('client-metrics-title').textContent = `Session ${id}`;
             updateClientMetrics();
@@ -1273,18 +1684,144 @@ const HTML_CONTENT: &str = r#"
                 const res = await fetch(`/api/client/${activeClientId}/metrics-history`);
                 const data = await res.json();
                 if (!data.success) return;
-                drawSparkline('bitrate-graph', data.bitrate, '#4CAF50', 0, null);
-                drawSparkline('packet-loss-graph', data.packet_loss, '#FF5722', 0, null);
-                drawSparkline('signal-graph', data.signal_level, '#2196F3', 0, null);
+                activeClientMetrics = {
+                    bitrate: data.bitrate,
+                    packet_loss: data.packet_loss,
+                    signal_level: data.signal_level,
+                };
+                drawClientMetrics();
+                renderPidOffenders(data.pid_offenders || []);
             } catch (e) { console.error('Failed to update metrics:', e); }
         }
 
-        function openOverrideModal(sessionId, overridePriority, overrideExclusive) {
+        function renderPidOffenders(offenders) {
+            const tbody = document.getElementById('pid-offenders-body');
+            if (offenders.length === 0) {
+                tbody.innerHTML = '<tr><td colspan="6" class="empty-state">問題のある PID はありません</td></tr>';
+                return;
+            }
+            tbody.innerHTML = offenders.map(p => `
+                <tr>
+                    <td>0x${p.pid.toString(16).toUpperCase().padStart(4, '0')}</td>
+                    <td>${p.packets.toLocaleString()}</td>
+                    <td>${p.discontinuities.toLocaleString()}</td>
+                    <td>${p.scrambled.toLocaleString()}</td>
+                    <td>${p.errors.toLocaleString()}</td>
+                    <td>${p.in_pmt ? '✓' : '未宣言'}</td>
+                </tr>
+            `).join('');
+        }
+
+        function drawClientMetrics() {
+            if (!activeClientMetrics) return;
+            drawSparkline('bitrate-graph', activeClientMetrics.bitrate, '#4CAF50', 0, null);
+            drawSparkline('packet-loss-graph', activeClientMetrics.packet_loss, '#FF5722', 0, null);
+            drawSparkline('signal-graph', activeClientMetrics.signal_level, '#2196F3', 0, null);
+        }
+
+        // Append a live sample to the selected client's sparklines (last 60 seconds)
+        function appendClientMetricsSample(ev) {
+            if (ev.session_id !== activeClientId || !activeClientMetrics) return;
+            const cutoff = ev.timestamp_ms - 60000;
+            const push = (series, value) => {
+                series.push([ev.timestamp_ms, value]);
+                while (series.length > 0 && series[0][0] < cutoff) series.shift();
+            };
+            push(activeClientMetrics.bitrate, ev.bitrate_mbps);
+            push(activeClientMetrics.packet_loss, ev.packet_loss_rate);
+            push(activeClientMetrics.signal_level, ev.signal_level);
+            drawClientMetrics();
+        }
+
+        // Live updates: WebSocket with polling fallback
+        let pollTimer = null;
+        let clientsRefreshPending = false;
+
+        function startPolling() {
+            if (pollTimer) return;
+            pollTimer = setInterval(() => { refreshStats(); refreshClients(); updateClientMetrics(); }, 2000);
+        }
+
+        function stopPolling() {
+            if (!pollTimer) return;
+            clearInterval(pollTimer);
+            pollTimer = null;
+        }
+
+        // Coalesce bursts of per-session events into one table refresh
+        function scheduleClientsRefresh() {
+            if (clientsRefreshPending) return;
+            clientsRefreshPending = true;
+            setTimeout(() => { clientsRefreshPending = false; refreshClients(); }, 1000);
+        }
+
+        function handleSessionEvent(ev) {
+            switch (ev.type) {
+                case 'connected':
+                case 'disconnected':
+                    refreshStats();
+                    refreshClients();
+                    if (ev.type === 'disconnected' && ev.session_id === activeClientId) activeClientMetrics = null;
+                    break;
+                case 'stats':
+                case 'host_resolved':
+                    scheduleClientsRefresh();
+                    break;
+                case 'metrics_sample':
+                    appendClientMetricsSample(ev);
+                    break;
+                case 'alert':
+                    refreshAlerts();
+                    break;
+                case 'scan_progress':
+                    showScanProgress(ev);
+                    break;
+                case 'scan_finished':
+                    refreshBonDrivers();
+                    break;
+                case 'lagged':
+                    refreshStats();
+                    refreshClients();
+                    updateClientMetrics();
+                    break;
+            }
+        }
+
+        function showScanProgress(ev) {
+            const btn = document.getElementById(`scan-btn-${ev.bondriver_id}`);
+            if (!btn) return;
+            btn.textContent = `スキャン中 ${ev.index}/${ev.total}`;
+            btn.title = `${ev.channel_name}: ${ev.locked ? `受信 (${ev.services_found} サービス)` : '信号なし'}`;
+        }
+
+        function connectEvents() {
+            if (!('WebSocket' in window)) { startPolling(); return; }
+            const proto = location.protocol === 'https:' ? 'wss:' : 'ws:';
+            const socket = new WebSocket(withAuthToken(`${proto}//${location.host}/api/ws`));
+            socket.onopen = () => {
+                stopPolling();
+                refreshStats();
+                refreshClients();
+                updateClientMetrics();
+            };
+            socket.onmessage = (msg) => {
+                try { handleSessionEvent(JSON.parse(msg.data)); }
+                catch (e) { console.error('Bad session event:', e); }
+            };
+            socket.onclose = () => {
+                startPolling();
+                setTimeout(connectEvents, 5000);
+            };
+        }
+
+        function openOverrideModal(sessionId, overridePriority, overrideExclusive, overrideMaxBitrate) {
             document.getElementById('override-session-id').value = sessionId;
             document.getElementById('override-priority').value = overridePriority !== null ? overridePriority : '';
             document.getElementById('override-exclusive').checked = overrideExclusive === true;
             document.getElementById('override-priority-enabled').checked = overridePriority !== null;
             document.getElementById('override-exclusive-enabled').checked = overrideExclusive !== null;
+            document.getElementById('override-max-bitrate').value = overrideMaxBitrate !== null ? overrideMaxBitrate : '';
+            document.getElementById('override-max-bitrate-enabled').checked = overrideMaxBitrate !== null;
             openModal('client-override-modal');
         }
 
@@ -1296,7 +1833,8 @@ const HTML_CONTENT: &str = r#"
                     headers: { 'Content-Type': 'application/json' },
                     body: JSON.stringify({
                         override_priority: null,
-                        override_exclusive: null
+                        override_exclusive: null,
+                        override_max_bitrate_mbps: null
                     })
                 });
                 const data = await res.json();
@@ -1312,6 +1850,9 @@ const HTML_CONTENT: &str = r#"
             const exclusiveEnabled = document.getElementById('override-exclusive-enabled').checked;
             const overridePriority = priorityEnabled ? (priorityValue === '' ? 0 : parseInt(priorityValue, 10)) : null;
             const overrideExclusive = exclusiveEnabled ? document.getElementById('override-exclusive').checked : null;
+            const maxBitrateValue = document.getElementById('override-max-bitrate').value;
+            const maxBitrateEnabled = document.getElementById('override-max-bitrate-enabled').checked;
+            const overrideMaxBitrate = maxBitrateEnabled ? (maxBitrateValue === '' ? 0 : parseFloat(maxBitrateValue)) : null;
 
             try {
                 const res = await fetch(`/api/client/${sessionId}/controls`, {
@@ -1319,7 +1860,8 @@ const HTML_CONTENT: &str = r#"
                     headers: { 'Content-Type': 'application/json' },
                     body: JSON.stringify({
                         override_priority: overridePriority,
-                        override_exclusive: overrideExclusive
+                        override_exclusive: overrideExclusive,
+                        override_max_bitrate_mbps: overrideMaxBitrate
                     })
                 });
                 const data = await res.json();
@@ -1353,8 +1895,9 @@ const HTML_CONTENT: &str = r#"
                     bondrivers.map(d => `<option value="${d.id}">${escapeHtml(d.driver_name || d.dll_path)}</option>`).join('');
 
                 tbody.innerHTML = data.items.map(item => {
-                    const d = item.driver;
-                    const nextScan = d.next_scan_at ? formatDateTime(d.next_scan_at) : '-';
+                    const d = { ...item.driver, scan_cron: item.scan_cron, scan_scope: item.scan_scope };
+                    const nextScan = (d.next_scan_at ? formatDateTime(d.next_scan_at) : '-')
+                        + (d.scan_cron ? ` <small title="cron"><code>${escapeHtml(d.scan_cron)}</code></small>` : '');
                     const quality = (item.quality_score * 100).toFixed(1) + '%';
                     const dropRate = (item.recent_drop_rate * 100).toFixed(2) + '%';
                     return `
@@ -1370,7 +1913,8 @@ const HTML_CONTENT: &str = r#"
                         <td data-sort-value="${d.next_scan_at || 0}">${nextScan}</td>
                         <td>
                             <button class="btn btn-primary btn-sm" onclick='editBonDriver(${JSON.stringify(d)})'>編集</button>
-                            <button class="btn btn-warning btn-sm" onclick="triggerScan(${d.id})">スキャン</button>
+                            <button class="btn btn-warning btn-sm" id="scan-btn-${d.id}" onclick="triggerScan(${d.id})">スキャン</button>
+                            <button class="btn btn-secondary btn-sm" onclick="testTune(${d.id})">テスト選局</button>
                             <button class="btn btn-danger btn-sm" onclick="deleteBonDriver(${d.id}, '${escapeHtml((d.driver_name || d.dll_path)).replace(/'/g, "\\'")}')">削除</button>
                         </td>
                     </tr>
@@ -1385,23 +1929,53 @@ const HTML_CONTENT: &str = r#"
             document.getElementById('bd-path').value = d.dll_path;
             document.getElementById('bd-name').value = d.driver_name || '';
             document.getElementById('bd-group-name').value = d.group_name || '';
+            document.getElementById('bd-group-strategy').value = d.group_strategy === 'quality' ? '' : (d.group_strategy || '');
             document.getElementById('bd-max-instances').value = d.max_instances;
+            document.getElementById('bd-supports-4k').checked = !!d.supports_4k;
+            document.getElementById('bd-broadcast-capacity').value = d.broadcast_capacity || '';
+            document.getElementById('bd-lag-policy').value = d.lag_policy === 'slow_producer' ? 'slow_producer' : '';
+            document.getElementById('bd-reception-min-signal').value = d.reception_min_signal ?? '';
+            document.getElementById('bd-auto-disable-no-reception').checked = !!d.auto_disable_no_reception;
             document.getElementById('bd-auto-scan').checked = d.auto_scan_enabled;
             document.getElementById('bd-scan-interval').value = d.scan_interval_hours;
+            document.getElementById('bd-scan-cron').value = d.scan_cron || '';
+            setScanScopeFields(d.scan_scope);
             document.getElementById('bd-scan-priority').value = d.scan_priority;
             document.getElementById('bd-passive-scan').checked = d.passive_scan_enabled;
             openModal('bondriver-modal');
         }
 
+        function setScanScopeFields(scope) {
+            const isChannels = !!scope && scope.startsWith('channels:');
+            document.getElementById('bd-scan-scope').value = isChannels ? 'channels' : (scope || '');
+            document.getElementById('bd-scan-channels').value = isChannels ? scope.slice('channels:'.length) : '';
+            document.getElementById('bd-scan-channels').disabled = !isChannels;
+        }
+
+        function scanScopeValue() {
+            const scope = document.getElementById('bd-scan-scope').value;
+            return scope === 'channels'
+                ? 'channels:' + document.getElementById('bd-scan-channels').value.trim()
+                : scope;
+        }
+
         function openCreateBonDriver() {
             document.querySelector('#bondriver-modal h3').textContent = 'BonDriver 追加';
             document.getElementById('bd-id').value = '';
             document.getElementById('bd-path').value = '';
             document.getElementById('bd-name').value = '';
             document.getElementById('bd-group-name').value = '';
+            document.getElementById('bd-group-strategy').value = '';
             document.getElementById('bd-max-instances').value = 1;
+            document.getElementById('bd-supports-4k').checked = false;
+            document.getElementById('bd-broadcast-capacity').value = '';
+            document.getElementById('bd-lag-policy').value = '';
+            document.getElementById('bd-reception-min-signal').value = '';
+            document.getElementById('bd-auto-disable-no-reception').checked = false;
             document.getElementById('bd-auto-scan').checked = false;
             document.getElementById('bd-scan-interval').value = 24;
+            document.getElementById('bd-scan-cron').value = '';
+            setScanScopeFields(null);
             document.getElementById('bd-scan-priority').value = 0;
             document.getElementById('bd-passive-scan').checked = false;
             openModal('bondriver-modal');
@@ -1414,9 +1988,17 @@ const HTML_CONTENT: &str = r#"
                 dll_path: document.getElementById('bd-path').value,
                 driver_name: document.getElementById('bd-name').value || null,
                 group_name: document.getElementById('bd-group-name').value || null,
+                group_strategy: document.getElementById('bd-group-strategy').value,
                 max_instances: parseInt(document.getElementById('bd-max-instances').value),
+                supports_4k: document.getElementById('bd-supports-4k').checked,
+                broadcast_capacity: parseInt(document.getElementById('bd-broadcast-capacity').value) || 0,
+                lag_policy: document.getElementById('bd-lag-policy').value,
+                reception_min_signal: parseFloat(document.getElementById('bd-reception-min-signal').value) || 0,
+                auto_disable_no_reception: document.getElementById('bd-auto-disable-no-reception').checked,
                 auto_scan_enabled: document.getElementById('bd-auto-scan').checked,
                 scan_interval_hours: parseInt(document.getElementById('bd-scan-interval').value),
+                scan_cron: document.getElementById('bd-scan-cron').value.trim(),
+                scan_scope: scanScopeValue(),
                 scan_priority: parseInt(document.getElementById('bd-scan-priority').value),
                 passive_scan_enabled: document.getElementById('bd-passive-scan').checked
             };
@@ -1463,6 +2045,38 @@ const HTML_CONTENT: &str = r#"
             } catch (e) { alert('スキャン開始に失敗しました: ' + e.message); }
         }
 
+        async function testTune(id) {
+            const input = prompt('テスト選局するチューニング空間とチャンネルを入力してください（例: 0 12）');
+            if (!input) return;
+            const [space, channel] = input.trim().split(/[\s,]+/).map(Number);
+            if (!Number.isInteger(space) || !Number.isInteger(channel)) {
+                alert('空間とチャンネルは数値で入力してください');
+                return;
+            }
+            try {
+                const res = await fetch(`/api/bondriver/${id}/test-tune`, {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({ space, channel })
+                });
+                const data = await res.json();
+                if (!data.success) { alert('エラー: ' + data.error); return; }
+                const r = data.report;
+                const hex = v => v == null ? '-' : '0x' + v.toString(16).toUpperCase().padStart(4, '0');
+                const lines = [
+                    `空間 ${r.space} / チャンネル ${r.channel}`,
+                    `SetChannel: ${r.set_channel_ms} ms`,
+                    `最初の TS まで: ${r.first_ts_ms == null ? '受信なし' : r.first_ts_ms + ' ms'}`,
+                    `信号レベル: ${r.signal_level.toFixed(2)} dB`,
+                    `NID/TSID: ${hex(r.nid)} / ${hex(r.tsid)}${r.network_name ? ' (' + r.network_name + ')' : ''}`,
+                    `サービス: ${r.services.map(s => s.name || s.sid).join(', ') || '-'}`,
+                    `登録済みチャンネルとの一致: ${r.network_match == null ? '-' : (r.network_match ? '一致' : '不一致')}`,
+                ];
+                if (r.error) lines.push('エラー: ' + r.error);
+                alert(lines.join('\n'));
+            } catch (e) { alert('テスト選局に失敗しました: ' + e.message); }
+        }
+
         // Channels - sorting state
         let channelData = [];
         let channelSortRules = [
@@ -1471,6 +2085,10 @@ const HTML_CONTENT: &str = r#"
             { key: 'tsid', asc: true },
         ];
 
+        // Channel drag-to-reorder state (rows in displayed order)
+        let channelDisplayed = [];
+        let channelDragId = null;
+
         // Channel edit mode state
         let channelEditMode = false;
         // {id: {channel_name, priority, is_enabled, deleted}}
@@ -1583,6 +2201,8 @@ const HTML_CONTENT: &str = r#"
                     return channel.bon_space ?? -1;
                 case 'bon_channel':
                     return channel.bon_channel ?? -1;
+                case 'display_order':
+                    return channel.display_order ?? Number.MAX_SAFE_INTEGER;
                 default:
                     return channel[key];
             }
@@ -1592,7 +2212,7 @@ const HTML_CONTENT: &str = r#"
             const allowed = new Set([
                 'is_enabled', 'channel_name', 'nid', 'sid', 'tsid', 'band_type',
                 'terrestrial_region', 'network_name', 'tuner_count',
-                'bon_space', 'bon_channel', 'priority'
+                'bon_space', 'bon_channel', 'priority', 'display_order'
             ]);
 
             const unique = [];
@@ -1651,15 +2271,21 @@ const HTML_CONTENT: &str = r#"
                     return 0;
                 });
 
+                channelDisplayed = sorted;
+                const reorderable = isChannelReorderable();
+                const dragAttrs = c => reorderable
+                    ? `draggable="true" style="cursor:move" ondragstart="onChannelDragStart(event, ${c.id})" ondragover="onChannelDragOver(event)" ondrop="onChannelDrop(event, ${c.id})"`
+                    : '';
+
                 tbody.innerHTML = sorted.map(c => `
-                    <tr ondblclick='enterChannelEditMode()'>
+                    <tr ondblclick='enterChannelEditMode()' ${dragAttrs(c)}>
                         <td>
                             <label class="toggle">
                                 <input type="checkbox" ${c.is_enabled ? 'checked' : ''} onchange="toggleChannel(${c.id}, this.checked)">
                                 <span class="toggle-slider"></span>
                             </label>
                         </td>
-                        <td>${getChannelLogoHtml(c)}${escapeHtml(c.channel_name || c.raw_name || '-')}</td>
+                        <td>${getChannelLogoHtml(c, c.id)}${escapeHtml(c.channel_name || c.raw_name || '-')}${c.name_is_synthetic ? ' <span class="badge badge-warning" title="放送局がサービス名を送出していないため、ネットワーク名とリモコンキーから推定しました">推定</span>' : ''}${c.reception_ok === false ? ` <span class="badge badge-danger" title="前回のスキャンで受信できませんでした (信号 ${c.signal_level != null ? c.signal_level.toFixed(1) : '-'} dB)">受信不可</span>` : ''}</td>
                         <td><code>0x${c.nid.toString(16).toUpperCase().padStart(4,'0')}/${c.sid}/${c.tsid}</code></td>
                         <td><span class="badge ${getBandBadgeClass(c.band_type)}">${getBandTypeName(c.band_type)}</span></td>
                         <td>${escapeHtml(c.terrestrial_region || '-')}</td>
@@ -1894,7 +2520,10 @@ const HTML_CONTENT: &str = r#"
                 let url = '/api/channels?';
                 if (bondriverId) url += `bondriver_id=${bondriverId}&`;
                 if (!bondriverId || groupLogical) url += 'group_logical=true&';
-                if (enabledOnly) url += 'enabled_only=true';
+                if (enabledOnly) url += 'enabled_only=true&';
+                if (document.getElementById('channel-reception-filter').checked) url += 'reception_failed=true&';
+                const q = document.getElementById('channel-search').value.trim();
+                if (q) url += `q=${encodeURIComponent(q)}`;
 
                 const res = await fetch(url);
                 const data = await res.json();
@@ -1910,6 +2539,57 @@ const HTML_CONTENT: &str = r#"
             } catch (e) { console.error('Failed to refresh channels:', e); }
         }
 
+        // Drag-to-reorder is offered only when every channel is listed in lineup order,
+        // since the saved order replaces the whole lineup
+        function isChannelReorderable() {
+            const rule = normalizeChannelSortRules(channelSortRules)[0];
+            return rule.key === 'display_order' && rule.asc
+                && !document.getElementById('channel-bondriver-filter').value
+                && !document.getElementById('channel-enabled-filter').checked
+                && !document.getElementById('channel-reception-filter').checked
+                && !document.getElementById('channel-search').value.trim();
+        }
+
+        // Search runs server-side; wait for typing to pause before refetching
+        let channelSearchTimer = null;
+        function onChannelSearchInput() {
+            clearTimeout(channelSearchTimer);
+            channelSearchTimer = setTimeout(refreshChannels, 300);
+        }
+
+        function onChannelDragStart(event, id) {
+            channelDragId = id;
+            event.dataTransfer.effectAllowed = 'move';
+        }
+
+        function onChannelDragOver(event) {
+            event.preventDefault();
+            event.dataTransfer.dropEffect = 'move';
+        }
+
+        async function onChannelDrop(event, targetId) {
+            event.preventDefault();
+            const dragId = channelDragId;
+            channelDragId = null;
+            if (dragId === null || dragId === targetId) return;
+
+            // Moving down lands after the target row, moving up lands before it
+            const ids = channelDisplayed.map(c => c.id);
+            const to = ids.indexOf(targetId);
+            ids.splice(ids.indexOf(dragId), 1);
+            ids.splice(to, 0, dragId);
+            try {
+                const res = await fetch('/api/channels/reorder', {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({ channel_ids: ids })
+                });
+                const data = await res.json();
+                if (!data.success) alert('エラー: ' + data.error);
+            } catch (e) { alert('並び替えに失敗しました: ' + e.message); }
+            refreshChannels();
+        }
+
         async function toggleChannel(id, enabled) {
             try {
                 const res = await fetch(`/api/channel/${id}/toggle`, {
@@ -2242,13 +2922,39 @@ const HTML_CONTENT: &str = r#"
         }
 
         // Session History
+        const SESSION_HISTORY_PAGE_SIZE = 50;
+        let sessionHistoryOffset = 0;
+        let sessionHistoryTotal = 0;
+
+        function changeSessionHistoryPage(delta) {
+            const next = sessionHistoryOffset + delta * SESSION_HISTORY_PAGE_SIZE;
+            if (next < 0 || next >= sessionHistoryTotal) return;
+            sessionHistoryOffset = next;
+            refreshSessionHistory();
+        }
+
+        function updateSessionHistoryPager(shown) {
+            const start = shown > 0 ? sessionHistoryOffset + 1 : 0;
+            document.getElementById('session-history-range').textContent =
+                `${start}–${sessionHistoryOffset + shown} / ${sessionHistoryTotal}件`;
+            document.getElementById('session-history-prev').disabled = sessionHistoryOffset === 0;
+            document.getElementById('session-history-next').disabled = sessionHistoryOffset + shown >= sessionHistoryTotal;
+        }
+
         async function refreshSessionHistory() {
             try {
+                const params = new URLSearchParams({ limit: SESSION_HISTORY_PAGE_SIZE, offset: sessionHistoryOffset });
                 const address = document.getElementById('session-filter-address').value || '';
-                const url = address ? `/api/session-history?client_address=${encodeURIComponent(address)}` : '/api/session-history';
-                const res = await fetch(url);
+                if (address) params.set('client_address', address);
+                const from = document.getElementById('session-filter-from').value;
+                if (from) params.set('from', Math.floor(new Date(from + 'T00:00:00').getTime() / 1000));
+                const to = document.getElementById('session-filter-to').value;
+                if (to) params.set('to', Math.floor(new Date(to + 'T00:00:00').getTime() / 1000) + 86400);
+                const res = await fetch(`/api/session-history?${params}`);
                 const data = await res.json();
                 const tbody = document.getElementById('session-history-body');
+                sessionHistoryTotal = data.success ? data.total_count : 0;
+                updateSessionHistoryPager(data.success && data.history ? data.history.length : 0);
 
                 if (!data.success || !data.history || data.history.length === 0) {
                     tbody.innerHTML = '<tr><td colspan="10" class="empty-state">セッション履歴がありません</td></tr>';
@@ -2260,7 +2966,7 @@ const HTML_CONTENT: &str = r#"
                     <tr>
                         <td data-sort-value="${h.started_at || 0}">${formatDateTime(h.started_at)}</td>
                         <td data-sort-value="${h.ended_at || 0}">${formatDateTime(h.ended_at)}</td>
-                        <td data-sort-value="${escapeHtml(h.client_address)}">${escapeHtml(h.client_address)}</td>
+                        <td data-sort-value="${escapeHtml(h.client_address)}">${escapeHtml(h.client_address)}${h.client_identity ? ` <span class="badge badge-info" title="クライアント証明書">${escapeHtml(h.client_identity)}</span>` : ''}</td>
                         <td data-sort-value="${escapeHtml(h.channel_name || h.channel_info || '-')}">${escapeHtml(h.channel_name || h.channel_info || '-') }</td>
                         <td data-sort-value="${h.duration_secs || 0}">${formatDuration(h.duration_secs)}</td>
                         <td data-sort-value="${h.packets_sent || 0}">${formatPackets(h.packets_sent)}</td>
@@ -2282,7 +2988,7 @@ const HTML_CONTENT: &str = r#"
                 const tbody = document.getElementById('alerts-body');
 
                 if (!data.success || !data.alerts || data.alerts.length === 0) {
-                    tbody.innerHTML = '<tr><td colspan="5" class="empty-state">アクティブアラートはありません</td></tr>';
+                    tbody.innerHTML = '<tr><td colspan="6" class="empty-state">アクティブアラートはありません</td></tr>';
                     applyResponsiveLabels('alerts-table');
                     return;
                 }
@@ -2290,6 +2996,7 @@ const HTML_CONTENT: &str = r#"
                 tbody.innerHTML = data.alerts.map(a => `
                     <tr>
                         <td data-sort-value="${a.triggered_at || 0}">${formatDateTime(a.triggered_at)}</td>
+                        <td data-sort-value="${escapeHtml(a.severity || '')}">${formatSeverityBadge(a.severity)}</td>
                         <td data-sort-value="${a.rule_id}">${a.rule_id}</td>
                         <td data-sort-value="${a.session_id || 0}">${a.session_id || '-'}</td>
                         <td data-sort-value="${escapeHtml(a.message || '-')}">${escapeHtml(a.message || '-') }</td>
@@ -2311,6 +3018,23 @@ const HTML_CONTENT: &str = r#"
             }
         }
 
+        function formatSeverityBadge(severity) {
+            switch (severity) {
+                case 'critical': return '<span class="badge badge-danger">重大</span>';
+                case 'info': return '<span class="badge badge-info">情報</span>';
+                case 'warning': return '<span class="badge badge-warning">警告</span>';
+                default: return escapeHtml(severity || '-');
+            }
+        }
+
+        function formatCriticalActionLabel(action) {
+            switch (action) {
+                case 'disconnect': return 'セッション切断';
+                case 'disable_auto_scan': return '自動スキャン無効化';
+                default: return action;
+            }
+        }
+
         function formatConditionLabel(condition) {
             switch (condition) {
                 case 'gt': return 'より大きい (>)';
@@ -2328,7 +3052,7 @@ const HTML_CONTENT: &str = r#"
                 const tbody = document.getElementById('alert-rules-body');
 
                 if (!data.success || !data.rules || data.rules.length === 0) {
-                    tbody.innerHTML = '<tr><td colspan="7" class="empty-state">ルールがありません</td></tr>';
+                    tbody.innerHTML = '<tr><td colspan="8" class="empty-state">ルールがありません</td></tr>';
                     applyResponsiveLabels('alert-rules-table');
                     return;
                 }
@@ -2340,6 +3064,7 @@ const HTML_CONTENT: &str = r#"
                         <td data-sort-value="${escapeHtml(r.metric)}">${escapeHtml(formatMetricLabel(r.metric))}</td>
                         <td data-sort-value="${escapeHtml(r.condition)}">${escapeHtml(formatConditionLabel(r.condition))}</td>
                         <td data-sort-value="${r.threshold}">${r.threshold}</td>
+                        <td data-sort-value="${escapeHtml(r.severity || '')}">${formatSeverityBadge(r.severity)}${r.severity === 'critical' && r.critical_action ? ` <small>${escapeHtml(formatCriticalActionLabel(r.critical_action))}</small>` : ''}</td>
                         <td data-sort-value="${r.is_enabled ? '1' : '0'}"><span class="badge ${r.is_enabled ? 'badge-success' : 'badge-danger'}">${r.is_enabled ? 'ON' : 'OFF'}</span></td>
                         <td><button class="btn btn-danger btn-sm" onclick="deleteAlertRule(${r.id})">削除</button></td>
                     </tr>
@@ -2376,10 +3101,12 @@ const HTML_CONTENT: &str = r#"
                         metric: document.getElementById('ar-metric').value,
                         condition: document.getElementById('ar-condition').value,
                         threshold: parseFloat(document.getElementById('ar-threshold').value),
-                        severity: 'warning',
+                        severity: document.getElementById('ar-severity').value,
+                        critical_action: document.getElementById('ar-critical-action').value || null,
                         is_enabled: document.getElementById('ar-enabled').checked,
                         webhook_url: document.getElementById('ar-webhook-url').value || null,
-                        webhook_format: document.getElementById('ar-webhook-format').value
+                        webhook_format: document.getElementById('ar-webhook-format').value,
+                        cooldown_secs: parseInt(document.getElementById('ar-cooldown').value, 10) || 0
                     })
                 });
                 const data = await res.json();
@@ -2392,6 +3119,159 @@ const HTML_CONTENT: &str = r#"
             } catch (e) { alert('保存に失敗しました: ' + e.message); }
         };
 
+        // Recordings
+        function formatBytes(bytes) {
+            if (!bytes) return '-';
+            if (bytes < 1024 * 1024) return (bytes / 1024).toFixed(1) + ' KB';
+            if (bytes < 1024 * 1024 * 1024) return (bytes / 1024 / 1024).toFixed(1) + ' MB';
+            return (bytes / 1024 / 1024 / 1024).toFixed(2) + ' GB';
+        }
+
+        async function refreshRecordings() {
+            try {
+                const res = await fetch('/api/record');
+                const data = await res.json();
+                const tbody = document.getElementById('recordings-body');
+
+                if (!data.success || !data.recordings || data.recordings.length === 0) {
+                    tbody.innerHTML = '<tr><td colspan="7" class="empty-state">録画中の番組はありません</td></tr>';
+                    applyResponsiveLabels('recordings-table');
+                    return;
+                }
+
+                tbody.innerHTML = data.recordings.map(r => `
+                    <tr>
+                        <td data-sort-value="${r.id}">${r.id}</td>
+                        <td data-sort-value="${r.nid}-${r.tsid}-${r.sid}">NID=${r.nid} TSID=${r.tsid} SID=${r.sid}${r.service_only ? '' : ' <span class="badge badge-info">TS全体</span>'}</td>
+                        <td data-sort-value="${escapeHtml(r.tuner_path || '-')}"><code>${escapeHtml(r.tuner_path || '-')}</code></td>
+                        <td data-sort-value="${escapeHtml(r.output_path)}"><code>${escapeHtml(r.output_path)}</code>${r.segment_duration_secs || r.segment_size_bytes ? ` <span class="badge badge-info">分割 ${r.segments}</span>` : ''}</td>
+                        <td data-sort-value="${r.elapsed_secs}">${formatDuration(r.elapsed_secs)} / ${formatDuration(r.duration_secs)}</td>
+                        <td data-sort-value="${r.bytes_written}">${formatBytes(r.bytes_written)}${r.dropped_chunks ? ` <span class="badge badge-danger">欠落 ${r.dropped_chunks}</span>` : ''}</td>
+                        <td><button class="btn btn-danger btn-sm" onclick="cancelRecording(${r.id})">停止</button></td>
+                    </tr>
+                `).join('');
+                applyResponsiveLabels('recordings-table');
+            } catch (e) { console.error('Failed to refresh recordings:', e); }
+        }
+
+        async function cancelRecording(id) {
+            if (!confirm('この録画を停止しますか？')) return;
+            try {
+                const res = await fetch(`/api/record/${id}`, { method: 'DELETE' });
+                const data = await res.json();
+                if (data.success) setTimeout(refreshRecordings, 500);
+                else alert('エラー: ' + data.error);
+            } catch (e) { alert('停止に失敗しました: ' + e.message); }
+        }
+
+        function formatReservationStatus(status) {
+            switch (status) {
+                case 'pending': return '<span class="badge badge-info">待機</span>';
+                case 'recording': return '<span class="badge badge-success">録画中</span>';
+                case 'done': return '<span class="badge badge-success">完了</span>';
+                case 'failed': return '<span class="badge badge-danger">失敗</span>';
+                case 'conflict': return '<span class="badge badge-warning">競合</span>';
+                default: return escapeHtml(status);
+            }
+        }
+
+        async function refreshReservations() {
+            try {
+                const res = await fetch('/api/recordings');
+                const data = await res.json();
+                const tbody = document.getElementById('reservations-body');
+
+                if (!data.success || !data.reservations || data.reservations.length === 0) {
+                    tbody.innerHTML = '<tr><td colspan="7" class="empty-state">予約はありません</td></tr>';
+                    applyResponsiveLabels('reservations-table');
+                    return;
+                }
+
+                tbody.innerHTML = data.reservations.map(r => `
+                    <tr>
+                        <td data-sort-value="${r.start_time}">${formatDateTime(r.start_time)}</td>
+                        <td data-sort-value="${r.end_time}">${formatDateTime(r.end_time)}</td>
+                        <td data-sort-value="${escapeHtml(r.title || '-')}">${escapeHtml(r.title || '-')}${r.event_id !== null ? ' <span class="badge badge-info">EPG</span>' : ''}</td>
+                        <td data-sort-value="${r.nid}-${r.tsid}-${r.sid}">NID=${r.nid} TSID=${r.tsid} SID=${r.sid}</td>
+                        <td data-sort-value="${escapeHtml(r.output_path)}"><code>${escapeHtml(r.output_path)}</code></td>
+                        <td data-sort-value="${r.status}" title="${escapeHtml(r.error || '')}">${formatReservationStatus(r.status)}</td>
+                        <td><button class="btn btn-danger btn-sm" onclick="deleteReservation(${r.id})">削除</button></td>
+                    </tr>
+                `).join('');
+                applyResponsiveLabels('reservations-table');
+            } catch (e) { console.error('Failed to refresh reservations:', e); }
+        }
+
+        async function deleteReservation(id) {
+            if (!confirm('この予約を削除しますか？（録画中の場合は停止します）')) return;
+            try {
+                const res = await fetch(`/api/recordings/${id}`, { method: 'DELETE' });
+                const data = await res.json();
+                if (data.success) { refreshReservations(); setTimeout(refreshRecordings, 500); }
+                else alert('エラー: ' + data.error);
+            } catch (e) { alert('削除に失敗しました: ' + e.message); }
+        }
+
+        document.getElementById('reservation-form').onsubmit = async (e) => {
+            e.preventDefault();
+            const toUnix = id => {
+                const value = document.getElementById(id).value;
+                return value ? Math.floor(new Date(value).getTime() / 1000) : null;
+            };
+            const eventId = document.getElementById('rsv-event-id').value;
+            try {
+                const res = await fetch('/api/recordings', {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({
+                        nid: parseInt(document.getElementById('rsv-nid').value),
+                        tsid: parseInt(document.getElementById('rsv-tsid').value),
+                        sid: parseInt(document.getElementById('rsv-sid').value),
+                        event_id: eventId ? parseInt(eventId) : null,
+                        start_time: toUnix('rsv-start'),
+                        end_time: toUnix('rsv-end'),
+                        title: document.getElementById('rsv-title').value || null,
+                        output_path: document.getElementById('rsv-output-path').value
+                    })
+                });
+                const data = await res.json();
+                if (data.success) {
+                    closeModal('reservation-modal');
+                    refreshReservations();
+                } else {
+                    alert('エラー: ' + data.error);
+                }
+            } catch (e) { alert('予約に失敗しました: ' + e.message); }
+        };
+
+        document.getElementById('recording-form').onsubmit = async (e) => {
+            e.preventDefault();
+            try {
+                const res = await fetch('/api/record', {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({
+                        nid: parseInt(document.getElementById('rec-nid').value),
+                        tsid: parseInt(document.getElementById('rec-tsid').value),
+                        sid: parseInt(document.getElementById('rec-sid').value),
+                        duration_secs: parseInt(document.getElementById('rec-duration').value),
+                        output_path: document.getElementById('rec-output-path').value,
+                        full_ts: document.getElementById('rec-full-ts').checked,
+                        segment_duration_secs: parseInt(document.getElementById('rec-segment-duration').value) || 0,
+                        segment_size_mb: parseInt(document.getElementById('rec-segment-size').value) || 0,
+                        segment_index: document.getElementById('rec-segment-index').checked
+                    })
+                });
+                const data = await res.json();
+                if (data.success) {
+                    closeModal('recording-modal');
+                    refreshRecordings();
+                } else {
+                    alert('エラー: ' + data.error);
+                }
+            } catch (e) { alert('録画の開始に失敗しました: ' + e.message); }
+        };
+
         // Scan Config Functions
         async function loadScanConfig() {
             try {
@@ -2466,6 +3346,90 @@ const HTML_CONTENT: &str = r#"
             document.getElementById('config-message').style.display = 'none';
         }
 
+        // Server Config Functions
+        async function loadServerConfig() {
+            try {
+                const response = await fetch('/api/server-config');
+                const data = await response.json();
+                if (data.success && data.config) {
+                    document.getElementById('server-max-connections').value = data.config.max_connections;
+                    document.getElementById('server-active-connections').textContent = data.config.active_connections;
+                    document.getElementById('server-preferred-region').value = data.config.preferred_region || '';
+                    document.getElementById('server-limit-spaces').checked = !!data.config.limit_spaces_to_preferred_region;
+                    document.getElementById('server-idle-timeout').value = data.config.session_idle_timeout_secs ?? '';
+                    document.getElementById('server-max-bitrate').value = data.config.max_bitrate_mbps ?? '';
+                    document.getElementById('server-quality-half-life').value = data.config.quality_half_life_hours ?? '';
+                    hideServerConfigMessage();
+                }
+            } catch (e) { console.error('Failed to load server config:', e); }
+        }
+
+        async function saveServerConfig() {
+            const maxConnections = parseInt(document.getElementById('server-max-connections').value);
+            const preferredRegion = document.getElementById('server-preferred-region').value;
+            const limitSpaces = document.getElementById('server-limit-spaces').checked;
+            const idleTimeoutText = document.getElementById('server-idle-timeout').value;
+            const idleTimeout = idleTimeoutText === '' ? null : parseInt(idleTimeoutText);
+            const maxBitrateText = document.getElementById('server-max-bitrate').value;
+            const maxBitrate = maxBitrateText === '' ? 0 : parseFloat(maxBitrateText);
+            const halfLifeText = document.getElementById('server-quality-half-life').value;
+            const halfLife = halfLifeText === '' ? null : parseFloat(halfLifeText);
+            if (!(maxConnections > 0) || (idleTimeout !== null && !(idleTimeout >= 0)) || !(maxBitrate >= 0)
+                || (halfLife !== null && !(halfLife >= 0))) {
+                showServerConfigMessage('入力値を確認してください', 'error');
+                return;
+            }
+            if (limitSpaces && !preferredRegion) {
+                showServerConfigMessage('空間を限定するには優先地域を選択してください', 'error');
+                return;
+            }
+
+            try {
+                const response = await fetch('/api/server-config', {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({
+                        max_connections: maxConnections,
+                        preferred_region: preferredRegion,
+                        limit_spaces_to_preferred_region: limitSpaces,
+                        session_idle_timeout_secs: idleTimeout,
+                        max_bitrate_mbps: maxBitrate,
+                        quality_half_life_hours: halfLife
+                    })
+                });
+                const data = await response.json();
+                if (data.success) {
+                    document.getElementById('server-active-connections').textContent = data.config.active_connections;
+                    showServerConfigMessage('設定を保存しました', 'success');
+                } else {
+                    showServerConfigMessage('設定の保存に失敗しました: ' + (data.error || 'Unknown error'), 'error');
+                }
+            } catch (e) {
+                showServerConfigMessage('設定の保存に失敗しました: ' + e.message, 'error');
+            }
+        }
+
+        function showServerConfigMessage(message, type) {
+            const msgEl = document.getElementById('server-config-message');
+            msgEl.textContent = message;
+            msgEl.style.display = 'block';
+            msgEl.style.padding = '10px 12px';
+            msgEl.style.borderRadius = '4px';
+            msgEl.style.fontSize = '13px';
+            if (type === 'success') {
+                msgEl.style.background = '#d4edda';
+                msgEl.style.color = '#155724';
+            } else {
+                msgEl.style.background = '#f8d7da';
+                msgEl.style.color = '#721c24';
+            }
+            setTimeout(hideServerConfigMessage, 5000);
+        }
+
+        function hideServerConfigMessage() {
+            document.getElementById('server-config-message').style.display = 'none';
+        }
+
         // Tuner Config Functions
         async function loadTunerConfig() {
             try {
@@ -2474,11 +3438,22 @@ const HTML_CONTENT: &str = r#"
                 if (data.success && data.config) {
                     document.getElementById('tuner-keep-alive').value = data.config.keep_alive_secs;
                     document.getElementById('tuner-prewarm-enabled').checked = !!data.config.prewarm_enabled;
+                    document.getElementById('tuner-prewarm-group').checked = !!data.config.prewarm_group;
+                    document.getElementById('tuner-prewarm-on-startup').checked = !!data.config.prewarm_on_startup;
+                    document.getElementById('tuner-min-signal-terrestrial').value = data.config.min_signal_level_terrestrial ?? 0;
+                    document.getElementById('tuner-min-signal-satellite').value = data.config.min_signal_level_satellite ?? 0;
                     document.getElementById('tuner-prewarm-timeout').value = data.config.prewarm_timeout_secs;
                     document.getElementById('tuner-setch-retry-interval').value = data.config.set_channel_retry_interval_ms ?? 500;
                     document.getElementById('tuner-setch-retry-timeout').value = data.config.set_channel_retry_timeout_ms ?? 10000;
                     document.getElementById('tuner-signal-poll-interval').value = data.config.signal_poll_interval_ms ?? 500;
                     document.getElementById('tuner-signal-wait-timeout').value = data.config.signal_wait_timeout_ms ?? 10000;
+                    document.getElementById('tuner-driver-affinity').checked = data.config.driver_affinity_enabled ?? true;
+                    document.getElementById('tuner-align-ts-output').checked = !!data.config.align_ts_output;
+                    document.getElementById('tuner-rekey-percent').value = data.config.descramble_rekey_percent ?? 90;
+                    document.getElementById('tuner-rekey-window').value = data.config.descramble_rekey_window_secs ?? 30;
+                    document.getElementById('tuner-stall-timeout').value = data.config.stall_timeout_ms ?? 15000;
+                    document.getElementById('tuner-coalesce-bytes').value = data.config.ts_coalesce_bytes ?? 0;
+                    document.getElementById('tuner-coalesce-flush').value = data.config.ts_coalesce_flush_ms ?? 20;
                     hideTunerConfigMessage();
                 }
             } catch (e) { console.error('Failed to load tuner config:', e); }
@@ -2489,10 +3464,21 @@ const HTML_CONTENT: &str = r#"
                 keep_alive_secs: parseInt(document.getElementById('tuner-keep-alive').value),
                 prewarm_enabled: document.getElementById('tuner-prewarm-enabled').checked,
                 prewarm_timeout_secs: parseInt(document.getElementById('tuner-prewarm-timeout').value),
+                prewarm_group: document.getElementById('tuner-prewarm-group').checked,
+                prewarm_on_startup: document.getElementById('tuner-prewarm-on-startup').checked,
+                min_signal_level_terrestrial: parseFloat(document.getElementById('tuner-min-signal-terrestrial').value),
+                min_signal_level_satellite: parseFloat(document.getElementById('tuner-min-signal-satellite').value),
                 set_channel_retry_interval_ms: parseInt(document.getElementById('tuner-setch-retry-interval').value),
                 set_channel_retry_timeout_ms: parseInt(document.getElementById('tuner-setch-retry-timeout').value),
                 signal_poll_interval_ms: parseInt(document.getElementById('tuner-signal-poll-interval').value),
-                signal_wait_timeout_ms: parseInt(document.getElementById('tuner-signal-wait-timeout').value)
+                signal_wait_timeout_ms: parseInt(document.getElementById('tuner-signal-wait-timeout').value),
+                driver_affinity_enabled: document.getElementById('tuner-driver-affinity').checked,
+                align_ts_output: document.getElementById('tuner-align-ts-output').checked,
+                descramble_rekey_percent: parseInt(document.getElementById('tuner-rekey-percent').value),
+                descramble_rekey_window_secs: parseInt(document.getElementById('tuner-rekey-window').value),
+                stall_timeout_ms: parseInt(document.getElementById('tuner-stall-timeout').value),
+                ts_coalesce_bytes: parseInt(document.getElementById('tuner-coalesce-bytes').value),
+                ts_coalesce_flush_ms: parseInt(document.getElementById('tuner-coalesce-flush').value)
             };
 
             if (
@@ -2501,7 +3487,14 @@ const HTML_CONTENT: &str = r#"
                 config.set_channel_retry_interval_ms <= 0 ||
                 config.set_channel_retry_timeout_ms <= 0 ||
                 config.signal_poll_interval_ms <= 0 ||
-                config.signal_wait_timeout_ms <= 0
+                config.signal_wait_timeout_ms <= 0 ||
+                !(config.descramble_rekey_percent >= 0 && config.descramble_rekey_percent <= 100) ||
+                config.descramble_rekey_window_secs <= 0 ||
+                !(config.stall_timeout_ms >= 0) ||
+                !(config.ts_coalesce_bytes >= 0) ||
+                config.ts_coalesce_flush_ms <= 0 ||
+                !(config.min_signal_level_terrestrial >= 0) ||
+                !(config.min_signal_level_satellite >= 0)
             ) {
                 showTunerConfigMessage('入力値を確認してください', 'error');
                 return;
@@ -2628,6 +3621,7 @@ const HTML_CONTENT: &str = r#"
             refreshStats();
             refreshClients();
             loadScanConfig();
+            loadServerConfig();
             loadTunerConfig();
             loadTsreplaceConfig();
             enableTableSorting('clients-table');
@@ -2636,7 +3630,7 @@ const HTML_CONTENT: &str = r#"
             enableTableSorting('session-history-table');
             enableTableSorting('alerts-table');
             enableTableSorting('alert-rules-table');
-            setInterval(() => { refreshStats(); refreshClients(); updateClientMetrics(); }, 2000);
+            connectEvents();
         });
 
         window.addEventListener('resize', () => {
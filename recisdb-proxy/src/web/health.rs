@@ -0,0 +1,72 @@
+//! Health check endpoint for load balancers and orchestrators.
+//!
+//! `GET /healthz` answers from cached state only: the database is probed by
+//! a background task every few seconds, so frequent polling never queues
+//! behind scans or EPG writes holding the database lock.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::json;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::web::state::WebState;
+
+/// Interval between database probes.
+const DATABASE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Start the periodic database probe behind `/healthz`.
+pub fn spawn_database_check(web_state: Arc<WebState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DATABASE_CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let result = web_state.database.lock().await.check_health();
+            let ok = match result {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("[Health] Database check failed: {}", e);
+                    false
+                }
+            };
+            web_state.database_ok.store(ok, Ordering::Release);
+        }
+    });
+}
+
+/// Report whether the proxy can serve clients.
+///
+/// Returns 200 while the database is readable and the TS listener is
+/// accepting connections, 503 otherwise.
+pub async fn get_healthz(State(web_state): State<Arc<WebState>>) -> impl IntoResponse {
+    let database = web_state.database_ok.load(Ordering::Acquire);
+    let listener = web_state.connection_limiter.is_accepting();
+
+    let mut active_tuners = 0;
+    for key in web_state.tuner_pool.keys().await {
+        if let Some(tuner) = web_state.tuner_pool.get(&key).await {
+            if tuner.is_running() {
+                active_tuners += 1;
+            }
+        }
+    }
+
+    let healthy = database && listener;
+    let status = if healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (
+        status,
+        Json(json!({
+            "status": if healthy { "ok" } else { "unavailable" },
+            "uptime_secs": web_state.started_at.elapsed().as_secs(),
+            "active_tuners": active_tuners,
+            "database": database,
+            "listener": listener,
+        })),
+    )
+}
@@ -0,0 +1,206 @@
+//! Prometheus scrape endpoint.
+//!
+//! Renders the same session and tuner state the dashboard reads in the
+//! Prometheus text exposition format (version 0.0.4).
+
+use axum::{
+    extract::State,
+    http::header::CONTENT_TYPE,
+    response::IntoResponse,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::web::state::WebState;
+
+/// Content type for the Prometheus text format.
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Per-driver TS quality counters, summed over the driver's open tuners.
+#[derive(Debug, Default, Clone, Copy)]
+struct DriverCounters {
+    active_tuners: u64,
+    packets_dropped: u64,
+    packets_scrambled: u64,
+    packets_error: u64,
+}
+
+/// Accessor for one of the per-driver counters.
+type CounterField = fn(&DriverCounters) -> u64;
+
+/// Builder for Prometheus text output.
+#[derive(Debug, Default)]
+struct PrometheusWriter {
+    out: String,
+}
+
+impl PrometheusWriter {
+    /// Write the HELP and TYPE lines for a metric family.
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    /// Write one sample line.
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            self.out.push('{');
+            for (i, (key, val)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.out.push(',');
+                }
+                let _ = write!(self.out, "{}=\"{}\"", key, escape_label_value(val));
+            }
+            self.out.push('}');
+        }
+        let _ = writeln!(self.out, " {}", value);
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// Escape a label value (backslash, double quote and newline).
+fn escape_label_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Prometheus metrics endpoint.
+pub async fn get_metrics(State(web_state): State<Arc<WebState>>) -> impl IntoResponse {
+    // dll_path -> group_name
+    let groups: HashMap<String, String> = {
+        let db = web_state.database.lock().await;
+        db.get_all_bon_drivers()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|d| (d.dll_path, d.group_name.unwrap_or_default()))
+            .collect()
+    };
+
+    let mut drivers: BTreeMap<String, DriverCounters> = BTreeMap::new();
+    let mut active_tuners = 0u64;
+    for key in web_state.tuner_pool.keys().await {
+        let Some(tuner) = web_state.tuner_pool.get(&key).await else {
+            continue;
+        };
+        if !tuner.is_running() {
+            continue;
+        }
+        active_tuners += 1;
+
+        let quality = tuner.quality_snapshot().await;
+        let counters = drivers.entry(key.tuner_path.clone()).or_default();
+        counters.active_tuners += 1;
+        counters.packets_dropped += quality.packets_dropped;
+        counters.packets_scrambled += quality.packets_scrambled;
+        counters.packets_error += quality.packets_error;
+    }
+
+    let sessions = web_state.session_registry.get_all().await;
+    let group_of = |path: &str| groups.get(path).map(String::as_str).unwrap_or("");
+
+    let mut w = PrometheusWriter::default();
+
+    w.family("recisdb_active_tuners", "gauge", "Number of running tuner instances.");
+    w.sample("recisdb_active_tuners", &[], active_tuners as f64);
+
+    w.family("recisdb_connected_clients", "gauge", "Number of connected client sessions.");
+    w.sample("recisdb_connected_clients", &[], sessions.len() as f64);
+
+    w.family(
+        "recisdb_ts_bytes_forwarded_total",
+        "counter",
+        "TS bytes forwarded to clients since server start.",
+    );
+    w.sample(
+        "recisdb_ts_bytes_forwarded_total",
+        &[],
+        web_state.session_registry.total_ts_bytes_forwarded() as f64,
+    );
+
+    w.family("recisdb_driver_active_tuners", "gauge", "Running tuner instances per BonDriver.");
+    for (path, counters) in &drivers {
+        let labels = [("dll_path", path.as_str()), ("group_name", group_of(path))];
+        w.sample("recisdb_driver_active_tuners", &labels, counters.active_tuners as f64);
+    }
+
+    let driver_counters: [(&str, &str, CounterField); 3] = [
+        (
+            "recisdb_driver_packets_dropped_total",
+            "TS packets lost to continuity errors on open tuners.",
+            |c| c.packets_dropped,
+        ),
+        (
+            "recisdb_driver_packets_scrambled_total",
+            "TS packets still scrambled after descrambling on open tuners.",
+            |c| c.packets_scrambled,
+        ),
+        (
+            "recisdb_driver_packets_error_total",
+            "TS packets with the transport error flag set on open tuners.",
+            |c| c.packets_error,
+        ),
+    ];
+    for (name, help, value) in driver_counters {
+        w.family(name, "counter", help);
+        for (path, counters) in &drivers {
+            let labels = [("dll_path", path.as_str()), ("group_name", group_of(path))];
+            w.sample(name, &labels, value(counters) as f64);
+        }
+    }
+
+    w.family(
+        "recisdb_session_bitrate_mbps",
+        "gauge",
+        "Current TS bitrate forwarded to each session (Mbps).",
+    );
+    for session in &sessions {
+        let id = session.id.to_string();
+        let path = session.tuner_path.as_deref().unwrap_or("");
+        let labels = [
+            ("session_id", id.as_str()),
+            ("dll_path", path),
+            ("group_name", group_of(path)),
+        ];
+        w.sample("recisdb_session_bitrate_mbps", &labels, session.current_bitrate_mbps);
+    }
+
+    ([(CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], w.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_writer_format() {
+        let mut w = PrometheusWriter::default();
+        w.family("recisdb_active_tuners", "gauge", "Number of running tuner instances.");
+        w.sample("recisdb_active_tuners", &[], 2.0);
+        w.sample(
+            "recisdb_session_bitrate_mbps",
+            &[("dll_path", "C:\\BonDriver_\"PX\".dll"), ("group_name", "")],
+            16.5,
+        );
+
+        assert_eq!(
+            w.finish(),
+            "# HELP recisdb_active_tuners Number of running tuner instances.\n\
+             # TYPE recisdb_active_tuners gauge\n\
+             recisdb_active_tuners 2\n\
+             recisdb_session_bitrate_mbps{dll_path=\"C:\\\\BonDriver_\\\"PX\\\".dll\",group_name=\"\"} 16.5\n"
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Mirakurun-compatible REST/streaming API.
+//!
+//! Implements the subset of the Mirakurun API that recorders such as
+//! EPGStation need to list channels/services and stream a whole TS over
+//! HTTP, without going through a BonDriver client. The routes are mounted
+//! under `/mirakurun` because `/api/channels` is already taken by the
+//! dashboard API.
+//!
+//! A Mirakurun channel corresponds to one transport stream (NID/TSID); its
+//! `channel` string is `"<nid>_<tsid>"`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use recisdb_protocol::BandType;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::database::{BonDriverRecord, ClientChannelRecord};
+use crate::web::state::WebState;
+use crate::web::stream::{stream_first_available, StreamCandidate};
+
+/// Mirakurun channel type for a network ID, or None if it has no equivalent.
+fn channel_type(nid: u16) -> Option<&'static str> {
+    match BandType::from_nid(nid) {
+        BandType::Terrestrial | BandType::CATV => Some("GR"),
+        BandType::BS | BandType::FourK => Some("BS"),
+        BandType::CS => Some("CS"),
+        BandType::SKY => Some("SKY"),
+        BandType::Other => None,
+    }
+}
+
+/// Mirakurun service ID (`networkId * 100000 + serviceId`).
+fn service_item_id(nid: u16, sid: u16) -> u64 {
+    nid as u64 * 100_000 + sid as u64
+}
+
+/// Reference from a service back to its channel.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelRef {
+    #[serde(rename = "type")]
+    pub channel_type: &'static str,
+    pub channel: String,
+}
+
+/// Mirakurun service.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirakurunService {
+    pub id: u64,
+    pub service_id: u16,
+    pub network_id: u16,
+    pub transport_stream_id: u16,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub service_type: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_control_key_id: Option<u8>,
+    pub has_logo_data: bool,
+    pub channel: ChannelRef,
+}
+
+/// Mirakurun channel (one transport stream).
+#[derive(Debug, Clone, Serialize)]
+pub struct MirakurunChannel {
+    #[serde(rename = "type")]
+    pub channel_type: &'static str,
+    pub channel: String,
+    pub name: String,
+    pub services: Vec<MirakurunService>,
+}
+
+/// Build the channel list from enabled, driver-backed channel rows.
+///
+/// The same service found on several BonDrivers is listed once. Channels
+/// are ordered by type, then NID/TSID.
+fn build_channels(
+    rows: &[(ClientChannelRecord, Option<BonDriverRecord>)],
+) -> Vec<MirakurunChannel> {
+    let mut channels: BTreeMap<(&'static str, u16, u16), MirakurunChannel> = BTreeMap::new();
+
+    for (ch, driver) in rows {
+        if !ch.is_enabled || driver.is_none() {
+            continue;
+        }
+        let (nid, tsid, sid) = (ch.nid as u16, ch.tsid as u16, ch.sid as u16);
+        let Some(channel_type) = channel_type(nid) else {
+            continue;
+        };
+        let channel_ref = ChannelRef {
+            channel_type,
+            channel: format!("{}_{}", nid, tsid),
+        };
+        let service_name = ch.service_name.clone().unwrap_or_default();
+
+        let entry = channels
+            .entry((channel_type, nid, tsid))
+            .or_insert_with(|| MirakurunChannel {
+                channel_type,
+                channel: channel_ref.channel.clone(),
+                name: ch.ts_name.clone().unwrap_or_else(|| service_name.clone()),
+                services: Vec::new(),
+            });
+        if entry.services.iter().any(|s| s.service_id == sid) {
+            continue;
+        }
+        entry.services.push(MirakurunService {
+            id: service_item_id(nid, sid),
+            service_id: sid,
+            network_id: nid,
+            transport_stream_id: tsid,
+            name: service_name,
+            service_type: ch.service_type.unwrap_or(0) as u8,
+            remote_control_key_id: ch.remote_control_key.map(|k| k as u8),
+            has_logo_data: false,
+            channel: channel_ref,
+        });
+    }
+
+    let mut channels: Vec<MirakurunChannel> = channels.into_values().collect();
+    for channel in &mut channels {
+        channel.services.sort_by_key(|s| s.service_id);
+    }
+    channels
+}
+
+/// Find the tuners that can receive a Mirakurun channel, best first.
+fn stream_candidates(
+    rows: &[(ClientChannelRecord, Option<BonDriverRecord>)],
+    channel_type_name: &str,
+    channel: &str,
+) -> Vec<StreamCandidate> {
+    let Some((nid, tsid)) = channel
+        .split_once('_')
+        .and_then(|(n, t)| Some((n.parse::<u16>().ok()?, t.parse::<u16>().ok()?)))
+    else {
+        return Vec::new();
+    };
+    if channel_type(nid) != Some(channel_type_name) {
+        return Vec::new();
+    }
+
+    // Rows are already ordered by priority
+    let mut candidates: Vec<StreamCandidate> = Vec::new();
+    for (ch, driver) in rows {
+        let Some(driver) = driver else { continue };
+        if !ch.is_enabled || ch.nid as u16 != nid || ch.tsid as u16 != tsid {
+            continue;
+        }
+        let candidate = StreamCandidate {
+            dll_path: driver.dll_path.clone(),
+            space: ch.space,
+            channel: ch.channel,
+            max_instances: driver.max_instances,
+        };
+        if !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// `GET /mirakurun/api/channels`
+pub async fn get_channels(State(web_state): State<Arc<WebState>>) -> impl IntoResponse {
+    let db = web_state.database.lock().await;
+    match db.get_all_channels_with_drivers() {
+        Ok(rows) => Json(build_channels(&rows)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "success": false, "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+/// `GET /mirakurun/api/services`
+pub async fn get_services(State(web_state): State<Arc<WebState>>) -> impl IntoResponse {
+    let db = web_state.database.lock().await;
+    match db.get_all_channels_with_drivers() {
+        Ok(rows) => {
+            let services: Vec<MirakurunService> = build_channels(&rows)
+                .into_iter()
+                .flat_map(|c| c.services)
+                .collect();
+            Json(services).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "success": false, "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+/// `GET /mirakurun/api/channels/:type/:channel/stream`
+///
+/// Opens (or joins) a tuner for the channel and streams the whole TS as a
+/// chunked `video/MP2T` response until the client disconnects.
+pub async fn get_channel_stream(
+    State(web_state): State<Arc<WebState>>,
+    Path((channel_type_name, channel)): Path<(String, String)>,
+) -> Response {
+    let candidates = {
+        let db = web_state.database.lock().await;
+        match db.get_all_channels_with_drivers() {
+            Ok(rows) => stream_candidates(&rows, &channel_type_name, &channel),
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "success": false, "error": e.to_string() })),
+                )
+                    .into_response();
+            }
+        }
+    };
+
+    let label = format!("{}/{}", channel_type_name, channel);
+    stream_first_available(&web_state.tuner_pool, &candidates, None, &label).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web::stream::tests::row;
+
+    #[test]
+    fn test_build_channels() {
+        let rows = vec![
+            row(Some("BonDriver_S.dll"), 4, 16625, 101, "NHK BS", true),
+            row(Some("BonDriver_T1.dll"), 0x7FE0, 0x7FE0, 1024, "NHK G", true),
+            row(Some("BonDriver_T2.dll"), 0x7FE0, 0x7FE0, 1024, "NHK G", true),
+            row(Some("BonDriver_T1.dll"), 0x7FE0, 0x7FE0, 1025, "NHK G2", true),
+            row(Some("BonDriver_T1.dll"), 0x7FE1, 0x7FE1, 1032, "Disabled", false),
+            row(None, 0x7FE2, 0x7FE2, 1040, "Orphan", true),
+        ];
+
+        let channels = build_channels(&rows);
+        assert_eq!(channels.len(), 2);
+
+        assert_eq!(channels[0].channel_type, "BS");
+        assert_eq!(channels[0].channel, "4_16625");
+        assert_eq!(channels[0].services[0].id, 400_101);
+
+        let gr = &channels[1];
+        assert_eq!(gr.channel_type, "GR");
+        assert_eq!(gr.channel, "32736_32736");
+        assert_eq!(gr.name, "TS 32736");
+        let sids: Vec<u16> = gr.services.iter().map(|s| s.service_id).collect();
+        assert_eq!(sids, [1024, 1025]);
+
+        let json = serde_json::to_value(&gr.services[0]).unwrap();
+        assert_eq!(json["serviceId"], 1024);
+        assert_eq!(json["networkId"], 0x7FE0);
+        assert_eq!(json["channel"]["type"], "GR");
+        assert_eq!(json["channel"]["channel"], "32736_32736");
+    }
+
+    #[test]
+    fn test_stream_candidates() {
+        let rows = vec![
+            row(Some("BonDriver_T1.dll"), 0x7FE0, 0x7FE0, 1024, "NHK G", true),
+            row(Some("BonDriver_T1.dll"), 0x7FE0, 0x7FE0, 1025, "NHK G2", true),
+            row(Some("BonDriver_T2.dll"), 0x7FE0, 0x7FE0, 1024, "NHK G", true),
+        ];
+
+        let candidates = stream_candidates(&rows, "GR", "32736_32736");
+        let paths: Vec<&str> = candidates.iter().map(|c| c.dll_path.as_str()).collect();
+        assert_eq!(paths, ["BonDriver_T1.dll", "BonDriver_T2.dll"]);
+
+        assert!(stream_candidates(&rows, "BS", "32736_32736").is_empty());
+        assert!(stream_candidates(&rows, "GR", "27").is_empty());
+    }
+}
@@ -1,54 +1,85 @@
 //! Web dashboard server for monitoring and configuration.
 
 pub mod api;
+pub mod auth;
 pub mod dashboard;
+pub mod health;
+pub mod metrics;
+pub mod mirakurun;
+pub mod playlist;
+pub mod record;
 pub mod state;
+pub mod stream;
+pub mod ws;
 
 use axum::{
     Router,
+    middleware,
     routing::{delete, get, post},
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 
-use crate::server::listener::DatabaseHandle;
+use crate::logging::LogFilterHandle;
+use crate::scheduler::RecordingScheduler;
+use crate::server::listener::{ConnectionLimiter, DatabaseHandle};
 use crate::tuner::TunerPool;
 use state::WebState;
 
-pub use state::{SessionInfo, SessionRegistry};
+pub use state::{IpSessionSlot, SessionEvent, SessionInfo, SessionPidStat, SessionRegistry};
 
 /// Start the web dashboard server.
+#[allow(clippy::too_many_arguments)]
 pub async fn start_web_server(
     listen_addr: SocketAddr,
     database: DatabaseHandle,
     tuner_pool: Arc<TunerPool>,
     session_registry: Arc<SessionRegistry>,
+    connection_limiter: Arc<ConnectionLimiter>,
+    recording_scheduler: Arc<RecordingScheduler>,
     scan_config: Option<state::ScanSchedulerInfo>,
     tuner_config: Option<state::TunerConfigInfo>,
+    auth_config: auth::WebAuthConfig,
+    log_filter: Option<Arc<LogFilterHandle>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut web_state = WebState::new(database, tuner_pool, session_registry);
+    let mut web_state = WebState::new(
+        database,
+        tuner_pool,
+        session_registry,
+        connection_limiter,
+        recording_scheduler,
+    );
     if let Some(config) = scan_config {
         *web_state.scan_config.write().await = config;
     }
     if let Some(config) = tuner_config {
         *web_state.tuner_config.write().await = config;
     }
+    web_state.log_filter = log_filter;
     let web_state = Arc::new(web_state);
+    health::spawn_database_check(Arc::clone(&web_state));
 
     let app = Router::new()
         // Legacy API routes (for backwards compatibility)
         .route("/api/tuners", get(api::get_tuners))
+        .route("/api/tuner/:key/signal-history", get(api::get_tuner_signal_history))
+        .route("/api/tuner-pool", get(api::get_tuner_pool))
         .route("/api/config", get(api::get_config))
         .route("/api/config", post(api::update_config))
         // Session/Client API
         .route("/api/clients", get(api::get_clients))
         .route("/api/stats", get(api::get_stats))
+        .route("/api/stats/channels", get(api::get_channel_stats))
         .route("/api/client/:id/quality", get(api::get_client_quality))
         .route("/api/client/:id/metrics-history", get(api::get_client_metrics_history))
         .route("/api/client/:id/disconnect", post(api::disconnect_client))
         .route("/api/client/:id/controls", post(api::override_client_controls))
         .route("/api/session-history", get(api::get_session_history))
+        .route("/api/epg", get(api::get_epg))
+        .route("/api/epg/now-next", get(api::get_epg_now_next))
+        // Live session events
+        .route("/api/ws", get(ws::session_events))
         // BonDriver API
         .route("/api/bondrivers", get(api::get_bondrivers))
         .route("/api/bondriver", post(api::create_bondriver))
@@ -56,17 +87,44 @@ pub async fn start_web_server(
         .route("/api/bondriver/:id", post(api::update_bondriver))
         .route("/api/bondriver/:id", delete(api::delete_bondriver))
         .route("/api/bondriver/:id/scan", post(api::trigger_scan))
+        .route("/api/bondriver/:id/scan/progress", get(ws::scan_progress))
         .route("/api/bondriver/:id/quality", get(api::get_bondriver_quality))
+        .route("/api/bondriver/:id/test-tune", post(api::test_tune_bondriver))
         .route("/api/bondrivers/ranking", get(api::get_bondrivers_ranking))
+        // Group API
+        .route("/api/groups", get(api::get_groups))
+        .route("/api/groups", post(api::create_group))
+        .route("/api/groups/:name/members", post(api::update_group_members))
         // Channel API
         .route("/api/channels", get(api::get_channels))
         .route("/api/channels/export", get(api::export_channels))
+        .route("/api/channels/stale", get(api::get_stale_channels))
         .route("/api/channels/import", post(api::import_channels))
+        .route("/api/config/export", get(api::export_config))
+        .route("/api/config/import", post(api::import_config))
+        .route("/api/maintenance", post(api::run_maintenance))
+        .route("/api/maintenance/reconcile", post(api::reconcile_channels))
         .route("/api/channels/batch", post(api::batch_update_channels))
+        .route("/api/channels/reorder", post(api::reorder_channels))
         .route("/api/channel", post(api::create_channel))
         .route("/api/channel/:id", post(api::update_channel))
         .route("/api/channel/:id/toggle", post(api::toggle_channel))
+        .route("/api/channel/:id/logo", get(api::get_channel_logo))
         .route("/api/channel/:id", delete(api::delete_channel))
+        // Playlist / raw TS streaming API
+        .route("/api/playlist.m3u", get(playlist::get_playlist))
+        .route("/api/playlist.m3u8", get(playlist::get_playlist))
+        .route("/api/stream", get(stream::get_raw_stream))
+        .route("/api/stream/:nid/:sid", get(stream::get_service_stream))
+        // Server-side recording API
+        .route("/api/record", get(record::get_active_recordings))
+        .route("/api/record", post(record::start_recording))
+        .route("/api/record/:id", delete(record::cancel_recording))
+        .route("/api/recordings", get(record::get_reservations))
+        .route("/api/recordings", post(record::create_reservation))
+        .route("/api/recordings/:id", get(record::get_reservation))
+        .route("/api/recordings/:id", post(record::update_reservation))
+        .route("/api/recordings/:id", delete(record::delete_reservation))
         // Scan history API
         .route("/api/scan-history", get(api::get_scan_history))
         // Alert API
@@ -74,6 +132,9 @@ pub async fn start_web_server(
         .route("/api/alert-rules", get(api::get_alert_rules))
         .route("/api/alert-rules", post(api::create_alert_rule))
         .route("/api/alert-rules/:id", delete(api::delete_alert_rule))
+        .route("/api/client-acl", get(api::get_client_acls))
+        .route("/api/client-acl", post(api::create_client_acl))
+        .route("/api/client-acl/:id", delete(api::delete_client_acl))
         .route("/api/alerts/:id/acknowledge", post(api::acknowledge_alert))
         // Scan scheduler configuration API
         .route("/api/scan-config", get(api::get_scan_config))
@@ -81,13 +142,30 @@ pub async fn start_web_server(
         // Tuner optimization configuration API
         .route("/api/tuner-config", get(api::get_tuner_config))
         .route("/api/tuner-config", post(api::update_tuner_config))
+        // Server runtime configuration
+        .route("/api/server-config", get(api::get_server_config))
+        .route("/api/server-config", post(api::update_server_config))
+        .route("/api/log-level", get(api::get_log_level))
+        .route("/api/log-level", post(api::update_log_level))
         // External encoder (tsreplace) configuration API
         .route("/api/tsreplace-config", get(api::get_tsreplace_config))
         .route("/api/tsreplace-config", post(api::update_tsreplace_config))
+        // Mirakurun-compatible API
+        .route("/mirakurun/api/channels", get(mirakurun::get_channels))
+        .route("/mirakurun/api/services", get(mirakurun::get_services))
+        .route(
+            "/mirakurun/api/channels/:type/:channel/stream",
+            get(mirakurun::get_channel_stream),
+        )
+        // Prometheus scrape endpoint
+        .route("/metrics", get(metrics::get_metrics))
+        // Load balancer health check
+        .route("/healthz", get(health::get_healthz))
         // Dashboard route
         .route("/", get(dashboard::index))
         .route("/logos/:file", get(api::get_logo))
         .with_state(web_state)
+        .layer(middleware::from_fn_with_state(Arc::new(auth_config), auth::require_auth))
         .layer(CorsLayer::permissive());
 
     let listener = tokio::net::TcpListener::bind(listen_addr).await?;
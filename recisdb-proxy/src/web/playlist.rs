@@ -0,0 +1,150 @@
+//! M3U playlist export for media players such as VLC.
+//!
+//! Each enabled service becomes one `#EXTINF` entry pointing at the raw TS
+//! endpoint `/api/stream/:nid/:sid`, grouped by broadcast band, with its
+//! station logo from `/api/channel/:id/logo`.
+
+use axum::{
+    extract::{Query, State},
+    http::{
+        header::{CONTENT_TYPE, HOST},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use recisdb_protocol::BandType;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::database::{BonDriverRecord, ClientChannelRecord};
+use crate::web::auth::encode_query_value;
+use crate::web::state::WebState;
+
+/// Content type for M3U playlists.
+const M3U_CONTENT_TYPE: &str = "audio/x-mpegurl; charset=utf-8";
+
+/// Remove characters that would break an `#EXTINF` line.
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '"' | '\r' | '\n' => ' ',
+            _ => c,
+        })
+        .collect()
+}
+
+/// Query for `GET /api/playlist.m3u`.
+#[derive(Debug, Deserialize)]
+pub struct PlaylistQuery {
+    /// Auth token; copied into the stream URLs so players can open them.
+    pub token: Option<String>,
+}
+
+/// Build the playlist from channel rows.
+///
+/// Services mapped to several drivers are listed once; the stream endpoint
+/// picks the driver at play time; the logo comes from the first channel row
+/// of the service. `url_suffix` is appended to every stream URL.
+fn build_playlist(
+    rows: &[(ClientChannelRecord, Option<BonDriverRecord>)],
+    base_url: &str,
+    url_suffix: &str,
+) -> String {
+    // (band, nid, sid) -> (channel id, service name)
+    let mut services: BTreeMap<(u8, u16, u16), (i64, String)> = BTreeMap::new();
+    for (ch, driver) in rows {
+        if !ch.is_enabled || driver.is_none() {
+            continue;
+        }
+        let (nid, sid) = (ch.nid as u16, ch.sid as u16);
+        let band = BandType::from_nid(nid) as u8;
+        services.entry((band, nid, sid)).or_insert_with(|| {
+            let name = ch
+                .service_name
+                .clone()
+                .unwrap_or_else(|| format!("{}/{}", nid, sid));
+            (ch.id, name)
+        });
+    }
+
+    let mut out = String::from("#EXTM3U\n");
+    for ((_, nid, sid), (id, name)) in &services {
+        let group = BandType::from_nid(*nid).display_name();
+        let _ = writeln!(
+            out,
+            "#EXTINF:-1 tvg-id=\"{}_{}\" tvg-logo=\"{}/api/channel/{}/logo\" group-title=\"{}\",{}",
+            nid,
+            sid,
+            base_url,
+            id,
+            group,
+            sanitize(name)
+        );
+        let _ = writeln!(out, "{}/api/stream/{}/{}{}", base_url, nid, sid, url_suffix);
+    }
+    out
+}
+
+/// `GET /api/playlist.m3u` (also served as `.m3u8`).
+pub async fn get_playlist(
+    State(web_state): State<Arc<WebState>>,
+    Query(query): Query<PlaylistQuery>,
+    headers: HeaderMap,
+) -> Response {
+    // Players need absolute URLs; use the host the playlist was fetched from
+    let base_url = headers
+        .get(HOST)
+        .and_then(|h| h.to_str().ok())
+        .map(|host| format!("http://{}", host))
+        .unwrap_or_default();
+    let url_suffix = query
+        .token
+        .map(|token| format!("?token={}", encode_query_value(&token)))
+        .unwrap_or_default();
+
+    let db = web_state.database.lock().await;
+    match db.get_all_channels_with_drivers() {
+        Ok(rows) => (
+            [(CONTENT_TYPE, M3U_CONTENT_TYPE)],
+            build_playlist(&rows, &base_url, &url_suffix),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to load channels: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web::stream::tests::row;
+
+    #[test]
+    fn test_build_playlist() {
+        let rows = vec![
+            row(Some("BonDriver_T1.dll"), 0x7FE0, 0x7FE0, 1024, "NHK \"G\"", true),
+            row(Some("BonDriver_T2.dll"), 0x7FE0, 0x7FE0, 1024, "NHK \"G\"", true),
+            row(Some("BonDriver_S.dll"), 4, 16625, 101, "NHK BS", true),
+            row(Some("BonDriver_T1.dll"), 0x7FE1, 0x7FE1, 1032, "Disabled", false),
+            row(None, 0x7FE2, 0x7FE2, 1040, "Orphan", true),
+        ];
+
+        assert_eq!(
+            build_playlist(&rows, "http://proxy:8080", ""),
+            "#EXTM3U\n\
+             #EXTINF:-1 tvg-id=\"32736_1024\" tvg-logo=\"http://proxy:8080/api/channel/0/logo\" group-title=\"地上波\",NHK  G \n\
+             http://proxy:8080/api/stream/32736/1024\n\
+             #EXTINF:-1 tvg-id=\"4_101\" tvg-logo=\"http://proxy:8080/api/channel/0/logo\" group-title=\"BS\",NHK BS\n\
+             http://proxy:8080/api/stream/4/101\n"
+        );
+
+        let with_token = build_playlist(&rows[2..3], "http://proxy:8080", "?token=abc");
+        assert!(with_token.ends_with("http://proxy:8080/api/stream/4/101?token=abc\n"));
+    }
+}
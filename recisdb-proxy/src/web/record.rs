@@ -0,0 +1,344 @@
+//! Server-side recording API.
+//!
+//! `POST /api/record` opens a tuner for a service (the same way the stream
+//! endpoints do) and records it to a file on the server for a fixed duration,
+//! without any client attached. `/api/recordings` manages reservations that
+//! the [`RecordingScheduler`](crate::scheduler::RecordingScheduler) records
+//! at their scheduled time.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use log::warn;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::database::{Database, NewReservation};
+use crate::tuner::{confine_output_path, RecordingId, RecordingRequest, SegmentPolicy};
+use crate::web::state::WebState;
+use crate::web::stream::{find_service_candidates, open_first_available};
+
+/// Start recording request.
+#[derive(Debug, Deserialize)]
+pub struct StartRecordingRequest {
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    pub duration_secs: u64,
+    /// File to create under the recordings directory; must not exist yet.
+    pub output_path: String,
+    /// Record the whole TS instead of only `sid`.
+    #[serde(default)]
+    pub full_ts: bool,
+    /// Start a new segment file after this many seconds.
+    #[serde(default)]
+    pub segment_duration_secs: Option<u64>,
+    /// Start a new segment file after this many MiB.
+    #[serde(default)]
+    pub segment_size_mb: Option<u64>,
+    /// Write `<stem>.index.tsv` next to the segments.
+    #[serde(default)]
+    pub segment_index: bool,
+    /// Slow the tuner down rather than drop chunks if the disk falls behind.
+    #[serde(default)]
+    pub lossless: bool,
+}
+
+impl StartRecordingRequest {
+    /// Segment policy of the request; zero sizes count as unset.
+    fn segment_policy(&self) -> Result<Option<SegmentPolicy>, &'static str> {
+        let max_duration = self.segment_duration_secs.filter(|s| *s > 0).map(Duration::from_secs);
+        let max_bytes = self.segment_size_mb.filter(|mb| *mb > 0).map(|mb| mb * 1024 * 1024);
+        if max_duration.is_none() && max_bytes.is_none() {
+            if self.segment_index {
+                return Err("segment_index requires segment_duration_secs or segment_size_mb");
+            }
+            return Ok(None);
+        }
+        Ok(Some(SegmentPolicy { max_duration, max_bytes, write_index: self.segment_index }))
+    }
+}
+
+fn error_response(status: StatusCode, error: impl ToString) -> Response {
+    (status, Json(json!({ "success": false, "error": error.to_string() }))).into_response()
+}
+
+/// `POST /api/record` — start a recording.
+pub async fn start_recording(
+    State(web_state): State<Arc<WebState>>,
+    Json(req): Json<StartRecordingRequest>,
+) -> Response {
+    if req.duration_secs == 0 {
+        return error_response(StatusCode::BAD_REQUEST, "duration_secs must be greater than 0");
+    }
+    let output_path = match web_state.recordings.output_path(&req.output_path) {
+        Ok(output_path) => output_path,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    let segment = match req.segment_policy() {
+        Ok(segment) => segment,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+
+    let candidates = match find_service_candidates(&web_state, req.nid, Some(req.tsid), req.sid).await {
+        Ok(candidates) => candidates,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    if candidates.is_empty() {
+        return error_response(StatusCode::NOT_FOUND, "Channel not found");
+    }
+
+    let label = format!("recording NID={} TSID={} SID={}", req.nid, req.tsid, req.sid);
+    let tuner_pool = &web_state.tuner_pool;
+    let Some(tuner) = open_first_available(tuner_pool, &candidates, &label).await else {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "No tuner available");
+    };
+
+    let request = RecordingRequest {
+        nid: req.nid,
+        tsid: req.tsid,
+        sid: req.sid,
+        duration: Duration::from_secs(req.duration_secs),
+        output_path,
+        service_only: !req.full_ts,
+        segment,
+        lossless: req.lossless,
+    };
+    match web_state.recordings.start(tuner_pool, Arc::clone(&tuner), request).await {
+        Ok(recording) => Json(json!({ "success": true, "recording": recording })).into_response(),
+        Err(e) => {
+            warn!("[Recorder] Failed to start {}: {}", label, e);
+            // Nothing else may be using the tuner we just opened
+            if !tuner.has_subscribers() {
+                tuner_pool.schedule_idle_close(tuner.key.clone(), tuner).await;
+            }
+            error_response(StatusCode::BAD_REQUEST, e)
+        }
+    }
+}
+
+/// `GET /api/record` — list active recordings.
+pub async fn get_active_recordings(State(web_state): State<Arc<WebState>>) -> impl IntoResponse {
+    let recordings = web_state.recordings.list();
+    let count = recordings.len();
+    Json(json!({ "success": true, "recordings": recordings, "count": count }))
+}
+
+/// `DELETE /api/record/:id` — stop a recording early.
+pub async fn cancel_recording(
+    State(web_state): State<Arc<WebState>>,
+    Path(id): Path<RecordingId>,
+) -> Response {
+    if web_state.recordings.cancel(id) {
+        Json(json!({ "success": true })).into_response()
+    } else {
+        error_response(StatusCode::NOT_FOUND, "Recording not found")
+    }
+}
+
+/// Recording reservation create/update request.
+///
+/// With `event_id`, the times (and a missing title) come from the EPG and
+/// keep following it; otherwise `start_time` and `end_time` are required.
+#[derive(Debug, Deserialize)]
+pub struct ReservationRequest {
+    pub nid: u16,
+    pub tsid: u16,
+    pub sid: u16,
+    #[serde(default)]
+    pub event_id: Option<u16>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub start_time: Option<i64>,
+    #[serde(default)]
+    pub end_time: Option<i64>,
+    pub output_path: String,
+}
+
+/// Validate a reservation request against the EPG.
+///
+/// `output_path` is kept relative and resolved under `output_dir` again
+/// when the recording starts.
+fn resolve_reservation(
+    db: &Database,
+    output_dir: &std::path::Path,
+    req: ReservationRequest,
+) -> Result<NewReservation, String> {
+    let output_path = req.output_path.trim().to_string();
+    confine_output_path(output_dir, std::path::Path::new(&output_path))?;
+
+    let (start_time, end_time, title) = match req.event_id {
+        Some(event_id) => {
+            let event = db
+                .get_epg_event(req.nid, req.sid, event_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("EPG event {} not found", event_id))?;
+            let (Some(start), Some(duration)) = (event.start_time, event.duration_secs) else {
+                return Err(format!("EPG event {} has no start time or duration yet", event_id));
+            };
+            (start, start + duration, req.title.or(event.title))
+        }
+        None => match (req.start_time, req.end_time) {
+            (Some(start), Some(end)) => (start, end, req.title),
+            _ => return Err("start_time and end_time are required without event_id".to_string()),
+        },
+    };
+    if end_time <= start_time {
+        return Err("end_time must be after start_time".to_string());
+    }
+
+    Ok(NewReservation {
+        nid: req.nid,
+        tsid: req.tsid,
+        sid: req.sid,
+        event_id: req.event_id,
+        title,
+        start_time,
+        end_time,
+        output_path,
+    })
+}
+
+/// `GET /api/recordings` — list recording reservations.
+pub async fn get_reservations(State(web_state): State<Arc<WebState>>) -> Response {
+    let db = web_state.database.lock().await;
+    match db.get_reservations() {
+        Ok(reservations) => {
+            let count = reservations.len();
+            Json(json!({ "success": true, "reservations": reservations, "count": count })).into_response()
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// `GET /api/recordings/:id` — get one reservation.
+pub async fn get_reservation(
+    State(web_state): State<Arc<WebState>>,
+    Path(id): Path<i64>,
+) -> Response {
+    let db = web_state.database.lock().await;
+    match db.get_reservation(id) {
+        Ok(Some(reservation)) => Json(json!({ "success": true, "reservation": reservation })).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Reservation not found"),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// `POST /api/recordings` — add a reservation.
+pub async fn create_reservation(
+    State(web_state): State<Arc<WebState>>,
+    Json(req): Json<ReservationRequest>,
+) -> Response {
+    let db = web_state.database.lock().await;
+    let reservation = match resolve_reservation(&db, web_state.recordings.output_dir(), req) {
+        Ok(reservation) => reservation,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    match db.insert_reservation(&reservation).and_then(|id| db.get_reservation(id)) {
+        Ok(reservation) => Json(json!({ "success": true, "reservation": reservation })).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// `POST /api/recordings/:id` — replace a reservation's settings.
+///
+/// The reservation goes back to `pending`; one that is being recorded cannot
+/// be changed.
+pub async fn update_reservation(
+    State(web_state): State<Arc<WebState>>,
+    Path(id): Path<i64>,
+    Json(req): Json<ReservationRequest>,
+) -> Response {
+    if web_state.recording_scheduler.is_recording(id) {
+        return error_response(StatusCode::CONFLICT, "Reservation is being recorded");
+    }
+    let db = web_state.database.lock().await;
+    let reservation = match resolve_reservation(&db, web_state.recordings.output_dir(), req) {
+        Ok(reservation) => reservation,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    match db.update_reservation(id, &reservation) {
+        Ok(true) => Json(json!({ "success": true, "reservation": db.get_reservation(id).ok().flatten() }))
+            .into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, "Reservation not found"),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// `DELETE /api/recordings/:id` — delete a reservation, stopping its
+/// recording if it is in progress.
+pub async fn delete_reservation(
+    State(web_state): State<Arc<WebState>>,
+    Path(id): Path<i64>,
+) -> Response {
+    web_state.recording_scheduler.cancel_reservation(id);
+    let db = web_state.database.lock().await;
+    match db.delete_reservation(id) {
+        Ok(true) => Json(json!({ "success": true })).into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, "Reservation not found"),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::NewEpgEvent;
+
+    fn request(event_id: Option<u16>, start_time: Option<i64>, end_time: Option<i64>) -> ReservationRequest {
+        ReservationRequest {
+            nid: 0x7FE0,
+            tsid: 0x7FE0,
+            sid: 1024,
+            event_id,
+            title: None,
+            start_time,
+            end_time,
+            output_path: " news.ts ".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_reservation() {
+        let mut db = Database::open_in_memory().unwrap();
+        db.insert_events(&[NewEpgEvent {
+            nid: 0x7FE0,
+            tsid: 0x7FE0,
+            sid: 1024,
+            event_id: 100,
+            start_time: Some(10_000),
+            duration_secs: Some(1_800),
+            title: Some("News".to_string()),
+            description: None,
+        }])
+        .unwrap();
+        let dir = std::path::Path::new("/srv/recordings");
+
+        let from_epg = resolve_reservation(&db, dir, request(Some(100), None, None)).unwrap();
+        assert_eq!((from_epg.start_time, from_epg.end_time), (10_000, 11_800));
+        assert_eq!(from_epg.title.as_deref(), Some("News"));
+        assert_eq!(from_epg.output_path, "news.ts");
+
+        let fixed = resolve_reservation(&db, dir, request(None, Some(1_000), Some(2_000))).unwrap();
+        assert_eq!((fixed.start_time, fixed.end_time, fixed.event_id), (1_000, 2_000, None));
+
+        assert!(resolve_reservation(&db, dir, request(Some(101), None, None)).is_err());
+        assert!(resolve_reservation(&db, dir, request(None, Some(1_000), None)).is_err());
+        assert!(resolve_reservation(&db, dir, request(None, Some(2_000), Some(1_000))).is_err());
+
+        // The output file must stay inside the recordings directory
+        for output_path in ["/etc/x", "../x", " "] {
+            let req = ReservationRequest {
+                output_path: output_path.to_string(),
+                ..request(None, Some(1_000), Some(2_000))
+            };
+            assert!(resolve_reservation(&db, dir, req).is_err(), "{}", output_path);
+        }
+    }
+}
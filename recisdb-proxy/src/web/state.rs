@@ -1,15 +1,21 @@
 //! Web server shared state.
 
 use std::collections::{HashMap, VecDeque};
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
-use tokio::sync::{mpsc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use serde::Serialize;
 use dns_lookup::lookup_addr;
 
-use crate::server::listener::DatabaseHandle;
-use crate::tuner::TunerPool;
+use crate::logging::LogFilterHandle;
+use crate::metrics::ChannelUsageTracker;
+use crate::server::listener::{ConnectionLimiter, DatabaseHandle};
+use crate::scheduler::RecordingScheduler;
+use crate::tuner::ts_analyzer::PidStat;
+use crate::tuner::channel_key::ChannelKeySpec;
+use crate::tuner::{ChannelKey, RecordingRegistry, TunerPool};
 
 /// Scan scheduler configuration (for Web API).
 #[derive(Debug, Clone, Serialize)]
@@ -36,6 +42,17 @@ pub struct TunerConfigInfo {
     pub set_channel_retry_timeout_ms: u64,
     pub signal_poll_interval_ms: u64,
     pub signal_wait_timeout_ms: u64,
+    pub driver_affinity_enabled: bool,
+    pub align_ts_output: bool,
+    pub descramble_rekey_percent: u64,
+    pub descramble_rekey_window_secs: u64,
+    pub stall_timeout_ms: u64,
+    pub ts_coalesce_bytes: u64,
+    pub ts_coalesce_flush_ms: u64,
+    pub prewarm_group: bool,
+    pub prewarm_on_startup: bool,
+    pub min_signal_level_terrestrial: f64,
+    pub min_signal_level_satellite: f64,
 }
 
 /// Information about an active session.
@@ -47,6 +64,8 @@ pub struct SessionInfo {
     pub addr: String,
     /// Client hostname (reverse DNS).
     pub host: Option<String>,
+    /// Identity from the TLS client certificate (CN, else first SAN).
+    pub client_identity: Option<String>,
     /// Current tuner path (if any).
     pub tuner_path: Option<String>,
     /// Current channel info (if any).
@@ -55,6 +74,8 @@ pub struct SessionInfo {
     pub channel_name: Option<String>,
     /// Current channel NID (for logo display).
     pub channel_nid: Option<u16>,
+    /// Current channel TSID.
+    pub channel_tsid: Option<u16>,
     /// Current channel SID (for logo display).
     pub channel_sid: Option<u16>,
     /// Whether the session is streaming.
@@ -68,12 +89,19 @@ pub struct SessionInfo {
     pub packets_sent: u64,
     /// Dropped TS packets.
     pub packets_dropped: u64,
+    /// Times the session fell a whole broadcast buffer behind its tuner.
+    pub lag_events: u64,
+    /// TS chunks skipped over those lag events.
+    pub lagged_chunks: u64,
     /// Scrambled TS packets.
     pub packets_scrambled: u64,
     /// Error TS packets.
     pub packets_error: u64,
     /// Current bitrate (Mbps).
     pub current_bitrate_mbps: f64,
+    /// Last measured round-trip time (ms); None until the client answers a
+    /// latency Ping.
+    pub latency_ms: Option<f64>,
     /// Client-specified priority (if provided).
     pub client_priority: Option<i32>,
     /// Client-specified exclusive lock request.
@@ -82,8 +110,22 @@ pub struct SessionInfo {
     pub override_priority: Option<i32>,
     /// Server override exclusive lock (if set).
     pub override_exclusive: Option<bool>,
+    /// Server default TS bitrate cap in Mbps (None = unlimited).
+    pub default_max_bitrate_mbps: Option<f64>,
+    /// Server override TS bitrate cap in Mbps (if set; 0 = unlimited).
+    pub override_max_bitrate_mbps: Option<f64>,
     /// Metrics history (last 60 seconds).
     pub metrics_history: SessionMetricsHistory,
+    /// PIDs with the most discontinuities, scrambled or error packets.
+    pub pid_offenders: Vec<SessionPidStat>,
+}
+
+/// Packet counters of one PID of a session's stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionPidStat {
+    pub pid: u16,
+    #[serde(flatten)]
+    pub stat: PidStat,
 }
 
 impl SessionInfo {
@@ -91,13 +133,140 @@ impl SessionInfo {
     pub fn connected_seconds(&self) -> u64 {
         self.connected_at.elapsed().as_secs()
     }
+
+    /// (NID, TSID, SID) of the current channel, once all three are known.
+    pub fn channel_key(&self) -> Option<(u16, u16, u16)> {
+        Some((self.channel_nid?, self.channel_tsid?, self.channel_sid?))
+    }
+
+    /// Whether the session is currently tuned through the pool tuner `key`.
+    pub fn is_on_tuner(&self, key: &ChannelKey) -> bool {
+        if self.tuner_path.as_deref() != Some(key.tuner_path.as_str()) {
+            return false;
+        }
+        match &key.channel {
+            ChannelKeySpec::SpaceChannel { space, channel } => {
+                self.channel_info.as_deref() == Some(format!("Space {}, Ch {}", space, channel).as_str())
+            }
+            ChannelKeySpec::Simple(_) => true,
+        }
+    }
+
+    /// Bitrate cap in effect (override if set, otherwise the server default).
+    pub fn effective_max_bitrate_mbps(&self) -> Option<f64> {
+        self.override_max_bitrate_mbps
+            .or(self.default_max_bitrate_mbps)
+            .filter(|mbps| *mbps > 0.0)
+    }
+}
+
+/// Capacity of the session event channel.
+///
+/// Each session publishes two events per second, so this covers a few
+/// seconds of backlog for a slow WebSocket client before it lags.
+const SESSION_EVENT_CAPACITY: usize = 256;
+
+/// How long to wait for a reverse DNS answer before showing the IP instead.
+const HOST_LOOKUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long a resolved hostname is reused for later connections from the same IP.
+const HOST_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Live session change pushed to dashboard WebSocket clients.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// A client connected.
+    Connected { session_id: u64, addr: String, host: Option<String> },
+    /// A client disconnected.
+    Disconnected { session_id: u64 },
+    /// The client's reverse DNS lookup finished after it connected.
+    HostResolved { session_id: u64, host: String },
+    /// Signal and packet counters were updated.
+    Stats {
+        session_id: u64,
+        signal_level: f32,
+        packets_sent: u64,
+        packets_dropped: u64,
+        packets_scrambled: u64,
+        packets_error: u64,
+        current_bitrate_mbps: f64,
+    },
+    /// A sparkline sample was recorded.
+    MetricsSample {
+        session_id: u64,
+        timestamp_ms: i64,
+        bitrate_mbps: f64,
+        packet_loss_rate: f64,
+        signal_level: f32,
+    },
+    /// An alert was triggered or resolved.
+    Alert {
+        alert_id: i64,
+        rule_name: String,
+        session_id: u64,
+        severity: String,
+        message: String,
+        resolved: bool,
+    },
+    /// A physical channel was probed during a channel scan.
+    ScanProgress {
+        bondriver_id: i64,
+        space: u32,
+        channel: u32,
+        channel_name: String,
+        /// 1-based position within the space being scanned.
+        index: usize,
+        total: usize,
+        locked: bool,
+        signal_level: f32,
+        services_found: usize,
+    },
+    /// A channel scan ended.
+    ScanFinished {
+        bondriver_id: i64,
+        success: bool,
+        channels_found: usize,
+        error: Option<String>,
+    },
+}
+
+impl SessionEvent {
+    /// BonDriver a scan event belongs to, or `None` for session events.
+    pub fn scan_bondriver_id(&self) -> Option<i64> {
+        match self {
+            Self::ScanProgress { bondriver_id, .. } | Self::ScanFinished { bondriver_id, .. } => {
+                Some(*bondriver_id)
+            }
+            _ => None,
+        }
+    }
 }
 
 /// Registry for tracking active sessions.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct SessionRegistry {
     sessions: RwLock<HashMap<u64, SessionInfo>>,
-    shutdown_txs: RwLock<HashMap<u64, mpsc::Sender<()>>>,
+    /// Per-session shutdown channels; the message is the disconnect reason.
+    shutdown_txs: RwLock<HashMap<u64, mpsc::Sender<&'static str>>>,
+    /// Open connections per client IP, counted from accept to disconnect.
+    ip_sessions: std::sync::Mutex<HashMap<IpAddr, usize>>,
+    /// TS bytes forwarded to clients over the server lifetime.
+    ts_bytes_forwarded: AtomicU64,
+    /// Bytes served and viewers per channel, drained into `channel_usage_stats`.
+    channel_usage: ChannelUsageTracker,
+    /// Live event feed for the dashboard WebSocket.
+    events: broadcast::Sender<SessionEvent>,
+    /// Friendly client names from the config file, used instead of reverse DNS.
+    host_labels: HashMap<IpAddr, String>,
+    /// Reverse DNS results per IP and when they were resolved.
+    host_cache: std::sync::Mutex<HashMap<IpAddr, (String, Instant)>>,
+}
+
+impl Default for SessionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Session metrics history for sparklines.
@@ -137,49 +306,162 @@ impl SessionRegistry {
         Self {
             sessions: RwLock::new(HashMap::new()),
             shutdown_txs: RwLock::new(HashMap::new()),
+            ip_sessions: std::sync::Mutex::new(HashMap::new()),
+            ts_bytes_forwarded: AtomicU64::new(0),
+            channel_usage: ChannelUsageTracker::new(),
+            events: broadcast::channel(SESSION_EVENT_CAPACITY).0,
+            host_labels: HashMap::new(),
+            host_cache: std::sync::Mutex::new(HashMap::new()),
         }
     }
 
+    /// Show `labels` as the host of clients connecting from those IPs.
+    pub fn with_host_labels(mut self, labels: HashMap<IpAddr, String>) -> Self {
+        self.host_labels = labels;
+        self
+    }
+
+    /// Configured label or cached reverse DNS name for `ip`.
+    fn known_host(&self, ip: IpAddr) -> Option<String> {
+        if let Some(label) = self.host_labels.get(&ip) {
+            return Some(label.clone());
+        }
+        let mut cache = self.host_cache.lock().unwrap();
+        match cache.get(&ip) {
+            Some((host, resolved_at)) if resolved_at.elapsed() < HOST_CACHE_TTL => Some(host.clone()),
+            Some(_) => {
+                cache.remove(&ip);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Resolve `ip` in the background and set it as the host of session `id`.
+    ///
+    /// Lookups that fail or time out cache the IP itself, so an address
+    /// without a PTR record is not looked up again on every connection.
+    fn spawn_host_lookup(self: &Arc<Self>, id: u64, ip: IpAddr) {
+        let registry = Arc::clone(self);
+        tokio::spawn(async move {
+            let lookup = tokio::task::spawn_blocking(move || lookup_addr(&ip).ok());
+            let host = match tokio::time::timeout(HOST_LOOKUP_TIMEOUT, lookup).await {
+                Ok(Ok(Some(host))) => host,
+                _ => ip.to_string(),
+            };
+            registry
+                .host_cache
+                .lock()
+                .unwrap()
+                .insert(ip, (host.clone(), Instant::now()));
+            match registry.sessions.write().await.get_mut(&id) {
+                Some(info) => info.host = Some(host.clone()),
+                None => return,
+            }
+            registry.publish(SessionEvent::HostResolved { session_id: id, host });
+        });
+    }
+
+    /// Subscribe to live session events.
+    pub fn subscribe(&self) -> broadcast::Receiver<SessionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publish an event to all subscribers.
+    ///
+    /// Sending fails only when nobody is subscribed, which is not an error.
+    pub fn publish(&self, event: SessionEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Count a new connection from `ip` unless it already has `max_per_ip`
+    /// (0 = unlimited).
+    ///
+    /// The connection is counted until the returned slot is dropped.
+    pub fn try_reserve_ip(self: &Arc<Self>, ip: IpAddr, max_per_ip: usize) -> Option<IpSessionSlot> {
+        let mut counts = self.ip_sessions.lock().unwrap();
+        let count = counts.entry(ip).or_insert(0);
+        if max_per_ip > 0 && *count >= max_per_ip {
+            return None;
+        }
+        *count += 1;
+        Some(IpSessionSlot { registry: Arc::clone(self), ip })
+    }
+
+    /// Number of open connections from `ip`.
+    pub fn sessions_from_ip(&self, ip: IpAddr) -> usize {
+        self.ip_sessions.lock().unwrap().get(&ip).copied().unwrap_or(0)
+    }
+
     /// Register a new session.
-    pub async fn register(&self, id: u64, addr: SocketAddr) -> mpsc::Receiver<()> {
+    ///
+    /// The host is filled in from the configured labels or the lookup cache;
+    /// otherwise it stays empty until a background lookup finishes.
+    pub async fn register(
+        self: &Arc<Self>,
+        id: u64,
+        addr: SocketAddr,
+        client_identity: Option<String>,
+    ) -> mpsc::Receiver<&'static str> {
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
         let ip = addr.ip();
-        let host = tokio::task::spawn_blocking(move || lookup_addr(&ip).ok())
-            .await
-            .ok()
-            .flatten();
+        let host = self.known_host(ip);
+        let needs_lookup = host.is_none();
         let info = SessionInfo {
             id,
             addr: addr.to_string(),
             host,
+            client_identity,
             tuner_path: None,
             channel_info: None,
             channel_name: None,
             channel_nid: None,
+            channel_tsid: None,
             channel_sid: None,
             is_streaming: false,
             connected_at: Instant::now(),
             signal_level: 0.0,
             packets_sent: 0,
             packets_dropped: 0,
+            lag_events: 0,
+            lagged_chunks: 0,
             packets_scrambled: 0,
             packets_error: 0,
             current_bitrate_mbps: 0.0,
+            latency_ms: None,
             client_priority: None,
             client_exclusive: false,
             override_priority: None,
             override_exclusive: None,
+            default_max_bitrate_mbps: None,
+            override_max_bitrate_mbps: None,
             metrics_history: SessionMetricsHistory::default(),
+            pid_offenders: Vec::new(),
+        };
+        let event = SessionEvent::Connected {
+            session_id: id,
+            addr: info.addr.clone(),
+            host: info.host.clone(),
         };
         self.sessions.write().await.insert(id, info);
         self.shutdown_txs.write().await.insert(id, shutdown_tx);
+        self.publish(event);
+        if needs_lookup {
+            self.spawn_host_lookup(id, ip);
+        }
         shutdown_rx
     }
 
     /// Unregister a session.
     pub async fn unregister(&self, id: u64) {
-        self.sessions.write().await.remove(&id);
+        let removed = self.sessions.write().await.remove(&id);
         self.shutdown_txs.write().await.remove(&id);
+        if let Some(info) = removed {
+            if let Some(key) = info.channel_key() {
+                self.channel_usage.viewer_left(key);
+            }
+            self.publish(SessionEvent::Disconnected { session_id: id });
+        }
     }
 
     /// Update session tuner path.
@@ -203,6 +485,21 @@ impl SessionRegistry {
         }
     }
 
+    /// Update session round-trip time.
+    pub async fn update_latency(&self, id: u64, latency_ms: f64) {
+        if let Some(info) = self.sessions.write().await.get_mut(&id) {
+            info.latency_ms = Some(latency_ms);
+        }
+    }
+
+    /// Record that the session lagged behind its tuner and skipped `chunks`.
+    pub async fn record_lag(&self, id: u64, chunks: u64) {
+        if let Some(info) = self.sessions.write().await.get_mut(&id) {
+            info.lag_events += 1;
+            info.lagged_chunks += chunks;
+        }
+    }
+
     /// Update session channel name.
     pub async fn update_channel_name(&self, id: u64, channel_name: Option<String>) {
         if let Some(info) = self.sessions.write().await.get_mut(&id) {
@@ -210,11 +507,28 @@ impl SessionRegistry {
         }
     }
 
-    /// Update session channel NID/SID (for logo display on dashboard).
-    pub async fn update_channel_ids(&self, id: u64, nid: Option<u16>, sid: Option<u16>) {
+    /// Update session channel NID/TSID/SID (for logos and channel usage stats).
+    pub async fn update_channel_ids(
+        &self,
+        id: u64,
+        nid: Option<u16>,
+        tsid: Option<u16>,
+        sid: Option<u16>,
+    ) {
         if let Some(info) = self.sessions.write().await.get_mut(&id) {
+            let previous = info.channel_key();
             info.channel_nid = nid;
+            info.channel_tsid = tsid;
             info.channel_sid = sid;
+            let current = info.channel_key();
+            if previous != current {
+                if let Some(key) = previous {
+                    self.channel_usage.viewer_left(key);
+                }
+                if let Some(key) = current {
+                    self.channel_usage.viewer_joined(key);
+                }
+            }
         }
     }
 
@@ -229,7 +543,17 @@ impl SessionRegistry {
         packets_error: u64,
         current_bitrate_mbps: f64,
     ) {
-        if let Some(info) = self.sessions.write().await.get_mut(&id) {
+        {
+            let mut sessions = self.sessions.write().await;
+            let Some(info) = sessions.get_mut(&id) else {
+                return;
+            };
+            let sent_delta = packets_sent.saturating_sub(info.packets_sent);
+            self.ts_bytes_forwarded
+                .fetch_add(sent_delta * 188, Ordering::Relaxed);
+            if let Some(key) = info.channel_key() {
+                self.channel_usage.record_bytes(key, sent_delta * 188);
+            }
             info.signal_level = signal_level;
             info.packets_sent = packets_sent;
             info.packets_dropped = packets_dropped;
@@ -237,6 +561,15 @@ impl SessionRegistry {
             info.packets_error = packets_error;
             info.current_bitrate_mbps = current_bitrate_mbps;
         }
+        self.publish(SessionEvent::Stats {
+            session_id: id,
+            signal_level,
+            packets_sent,
+            packets_dropped,
+            packets_scrambled,
+            packets_error,
+            current_bitrate_mbps,
+        });
     }
 
     /// Update client-specified priority and exclusive lock request.
@@ -262,6 +595,7 @@ impl SessionRegistry {
         id: u64,
         override_priority: Option<Option<i32>>,
         override_exclusive: Option<Option<bool>>,
+        override_max_bitrate_mbps: Option<Option<f64>>,
     ) {
         if let Some(info) = self.sessions.write().await.get_mut(&id) {
             if let Some(p) = override_priority {
@@ -270,9 +604,31 @@ impl SessionRegistry {
             if let Some(e) = override_exclusive {
                 info.override_exclusive = e;
             }
+            if let Some(b) = override_max_bitrate_mbps {
+                info.override_max_bitrate_mbps = b;
+            }
+        }
+    }
+
+    /// Update the server default bitrate cap the session started with.
+    pub async fn update_default_max_bitrate(&self, id: u64, mbps: Option<f64>) {
+        if let Some(info) = self.sessions.write().await.get_mut(&id) {
+            info.default_max_bitrate_mbps = mbps;
+        }
+    }
+
+    /// Apply a changed server default bitrate cap to every session.
+    pub async fn update_default_max_bitrate_all(&self, mbps: Option<f64>) {
+        for info in self.sessions.write().await.values_mut() {
+            info.default_max_bitrate_mbps = mbps;
         }
     }
 
+    /// Get the bitrate cap in effect for a session (None = unlimited).
+    pub async fn get_effective_max_bitrate(&self, id: u64) -> Option<f64> {
+        self.sessions.read().await.get(&id)?.effective_max_bitrate_mbps()
+    }
+
     /// Get effective controls (override if set, otherwise client values).
     pub async fn get_effective_controls(&self, id: u64) -> Option<(Option<i32>, bool)> {
         let info = self.sessions.read().await.get(&id)?.clone();
@@ -281,6 +637,13 @@ impl SessionRegistry {
         Some((priority, exclusive))
     }
 
+    /// Update the PIDs shown as offenders in the session detail.
+    pub async fn update_pid_offenders(&self, id: u64, offenders: Vec<SessionPidStat>) {
+        if let Some(info) = self.sessions.write().await.get_mut(&id) {
+            info.pid_offenders = offenders;
+        }
+    }
+
     /// Push a metrics sample for session sparklines.
     pub async fn push_metrics_sample(
         &self,
@@ -290,21 +653,61 @@ impl SessionRegistry {
         packet_loss_rate: f64,
         signal_level: f32,
     ) {
-        if let Some(info) = self.sessions.write().await.get_mut(&id) {
+        {
+            let mut sessions = self.sessions.write().await;
+            let Some(info) = sessions.get_mut(&id) else {
+                return;
+            };
             info.metrics_history
                 .push_sample(timestamp_ms, bitrate_mbps, packet_loss_rate, signal_level);
         }
+        self.publish(SessionEvent::MetricsSample {
+            session_id: id,
+            timestamp_ms,
+            bitrate_mbps,
+            packet_loss_rate,
+            signal_level,
+        });
     }
 
     /// Request remote shutdown for a session.
     pub async fn request_shutdown(&self, id: u64) -> bool {
         if let Some(tx) = self.shutdown_txs.read().await.get(&id) {
-            tx.send(()).await.is_ok()
+            tx.send("remote_shutdown").await.is_ok()
         } else {
             false
         }
     }
 
+    /// Ask every session to disconnect with `reason`.
+    ///
+    /// Returns the number of sessions signalled. A session that already has a
+    /// shutdown request pending keeps that one.
+    pub async fn request_shutdown_all(&self, reason: &'static str) -> usize {
+        self.shutdown_txs
+            .read()
+            .await
+            .values()
+            .filter(|tx| tx.try_send(reason).is_ok())
+            .count()
+    }
+
+    /// Wait until all sessions have unregistered, up to `timeout`.
+    ///
+    /// Returns false if sessions were still registered at the deadline.
+    pub async fn wait_until_empty(&self, timeout: std::time::Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if self.shutdown_txs.read().await.is_empty() {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    }
+
     /// Get all active sessions.
     pub async fn get_all(&self) -> Vec<SessionInfo> {
         self.sessions.read().await.values().cloned().collect()
@@ -314,6 +717,35 @@ impl SessionRegistry {
     pub async fn count(&self) -> usize {
         self.sessions.read().await.len()
     }
+
+    /// Total TS bytes forwarded to clients, including disconnected sessions.
+    pub fn total_ts_bytes_forwarded(&self) -> u64 {
+        self.ts_bytes_forwarded.load(Ordering::Relaxed)
+    }
+
+    /// Per-channel usage counters.
+    pub fn channel_usage(&self) -> &ChannelUsageTracker {
+        &self.channel_usage
+    }
+}
+
+/// Per-IP connection slot from [`SessionRegistry::try_reserve_ip`].
+#[derive(Debug)]
+pub struct IpSessionSlot {
+    registry: Arc<SessionRegistry>,
+    ip: IpAddr,
+}
+
+impl Drop for IpSessionSlot {
+    fn drop(&mut self) {
+        let mut counts = self.registry.ip_sessions.lock().unwrap();
+        if let Some(count) = counts.get_mut(&self.ip) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&self.ip);
+            }
+        }
+    }
 }
 
 /// Shared state for the web server.
@@ -328,11 +760,29 @@ pub struct WebState {
     pub scan_config: RwLock<ScanSchedulerInfo>,
     /// Tuner optimization configuration.
     pub tuner_config: RwLock<TunerConfigInfo>,
+    /// Live client connection limit.
+    pub connection_limiter: Arc<ConnectionLimiter>,
+    /// Server-side recordings in progress.
+    pub recordings: Arc<RecordingRegistry>,
+    /// Recording reservation scheduler.
+    pub recording_scheduler: Arc<RecordingScheduler>,
+    /// When the web server started.
+    pub started_at: Instant,
+    /// Result of the latest periodic database check.
+    pub database_ok: AtomicBool,
+    /// Runtime log filter (None when logging was initialized elsewhere).
+    pub log_filter: Option<Arc<LogFilterHandle>>,
 }
 
 impl WebState {
     /// Create a new web state.
-    pub fn new(database: DatabaseHandle, tuner_pool: Arc<TunerPool>, session_registry: Arc<SessionRegistry>) -> Self {
+    pub fn new(
+        database: DatabaseHandle,
+        tuner_pool: Arc<TunerPool>,
+        session_registry: Arc<SessionRegistry>,
+        connection_limiter: Arc<ConnectionLimiter>,
+        recording_scheduler: Arc<RecordingScheduler>,
+    ) -> Self {
         Self {
             database,
             tuner_pool,
@@ -352,7 +802,24 @@ impl WebState {
                 set_channel_retry_timeout_ms: 10_000,
                 signal_poll_interval_ms: 500,
                 signal_wait_timeout_ms: 10_000,
+                driver_affinity_enabled: true,
+                align_ts_output: false,
+                descramble_rekey_percent: 90,
+                descramble_rekey_window_secs: 30,
+                stall_timeout_ms: 15_000,
+                ts_coalesce_bytes: 0,
+                ts_coalesce_flush_ms: 20,
+                prewarm_group: false,
+                prewarm_on_startup: false,
+                min_signal_level_terrestrial: 0.0,
+                min_signal_level_satellite: 0.0,
             }),
+            connection_limiter,
+            recordings: Arc::clone(recording_scheduler.recordings()),
+            recording_scheduler,
+            started_at: Instant::now(),
+            database_ok: AtomicBool::new(true),
+            log_filter: None,
         }
     }
 
@@ -366,3 +833,121 @@ impl WebState {
         *self.tuner_config.write().await = config;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_session_registry_publishes_events() {
+        // A label skips the background lookup, which would add an event
+        let labels = HashMap::from([("127.0.0.1".parse().unwrap(), "TV".to_string())]);
+        let registry = Arc::new(SessionRegistry::new().with_host_labels(labels));
+        let mut events = registry.subscribe();
+
+        let _shutdown_rx = registry.register(7, "127.0.0.1:40000".parse().unwrap(), None).await;
+        registry.update_stats(7, 30.5, 1000, 1, 2, 3, 16.0).await;
+        registry.push_metrics_sample(7, 1_000, 16.0, 0.1, 30.5).await;
+        // Unknown sessions publish nothing
+        registry.update_stats(99, 0.0, 0, 0, 0, 0, 0.0).await;
+        registry.unregister(7).await;
+        registry.unregister(7).await;
+
+        let mut types = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            let json = serde_json::to_value(&event).unwrap();
+            assert_eq!(json["session_id"], 7);
+            types.push(json["type"].as_str().unwrap().to_string());
+        }
+        assert_eq!(types, ["connected", "stats", "metrics_sample", "disconnected"]);
+        assert_eq!(registry.total_ts_bytes_forwarded(), 1000 * 188);
+    }
+
+    #[tokio::test]
+    async fn test_session_registry_channel_usage() {
+        let registry = Arc::new(SessionRegistry::new());
+        let nhk = (0x7FE0, 0x7FE0, 0x0400);
+        let _rx1 = registry.register(1, "127.0.0.1:40001".parse().unwrap(), None).await;
+        let _rx2 = registry.register(2, "127.0.0.1:40002".parse().unwrap(), None).await;
+
+        // Bytes sent before the channel is known are not attributed
+        registry.update_stats(1, 0.0, 10, 0, 0, 0, 0.0).await;
+        registry.update_channel_ids(1, Some(0x7FE0), Some(0x7FE0), Some(0x0400)).await;
+        registry.update_channel_ids(1, Some(0x7FE0), Some(0x7FE0), Some(0x0400)).await;
+        registry.update_channel_ids(2, Some(0x7FE0), Some(0x7FE0), Some(0x0400)).await;
+        registry.update_stats(1, 0.0, 110, 0, 0, 0, 0.0).await;
+        registry.update_stats(2, 0.0, 50, 0, 0, 0, 0.0).await;
+        assert_eq!(registry.channel_usage().viewers(nhk), 2);
+
+        registry.update_channel_ids(2, Some(4), Some(16625), Some(101)).await;
+        registry.unregister(1).await;
+        assert_eq!(registry.channel_usage().viewers(nhk), 0);
+
+        let usage = registry.channel_usage().take();
+        let nhk_usage = usage.iter().find(|u| u.sid == 0x0400).unwrap();
+        assert_eq!(nhk_usage.bytes, 150 * 188);
+        assert_eq!(nhk_usage.peak_viewers, 2);
+        assert!(usage.iter().any(|u| u.sid == 101 && u.peak_viewers == 1));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_all_sessions() {
+        let registry = Arc::new(SessionRegistry::new());
+        let mut first = registry.register(1, "127.0.0.1:40001".parse().unwrap(), None).await;
+        let mut second = registry.register(2, "127.0.0.1:40002".parse().unwrap(), None).await;
+
+        assert!(registry.request_shutdown(1).await);
+        assert_eq!(first.recv().await, Some("remote_shutdown"));
+
+        assert_eq!(registry.request_shutdown_all("server_shutdown").await, 2);
+        assert_eq!(first.recv().await, Some("server_shutdown"));
+        assert_eq!(second.recv().await, Some("server_shutdown"));
+
+        let timeout = std::time::Duration::from_millis(200);
+        assert!(!registry.wait_until_empty(timeout).await);
+        registry.unregister(1).await;
+        registry.unregister(2).await;
+        assert!(registry.wait_until_empty(timeout).await);
+    }
+
+    #[tokio::test]
+    async fn test_host_labels_and_cache() {
+        let labeled: IpAddr = "192.168.1.20".parse().unwrap();
+        let cached: IpAddr = "192.168.1.21".parse().unwrap();
+        let labels = HashMap::from([(labeled, "Living Room TV".to_string())]);
+        let registry = Arc::new(SessionRegistry::new().with_host_labels(labels));
+        registry
+            .host_cache
+            .lock()
+            .unwrap()
+            .insert(cached, ("tv.lan".to_string(), Instant::now()));
+
+        let _rx1 = registry.register(1, SocketAddr::new(labeled, 40001), None).await;
+        let _rx2 = registry.register(2, SocketAddr::new(cached, 40002), None).await;
+        let mut sessions = registry.get_all().await;
+        sessions.sort_by_key(|s| s.id);
+        assert_eq!(sessions[0].host.as_deref(), Some("Living Room TV"));
+        assert_eq!(sessions[1].host.as_deref(), Some("tv.lan"));
+    }
+
+    #[test]
+    fn test_per_ip_session_slots() {
+        let registry = Arc::new(SessionRegistry::new());
+        let ip: IpAddr = "192.168.1.10".parse().unwrap();
+        let other: IpAddr = "192.168.1.11".parse().unwrap();
+
+        let first = registry.try_reserve_ip(ip, 2).unwrap();
+        let second = registry.try_reserve_ip(ip, 2).unwrap();
+        assert!(registry.try_reserve_ip(ip, 2).is_none());
+        // Other addresses and unlimited callers are unaffected
+        let _other = registry.try_reserve_ip(other, 2).unwrap();
+        assert_eq!(registry.sessions_from_ip(ip), 2);
+
+        drop(first);
+        let _third = registry.try_reserve_ip(ip, 2).unwrap();
+        drop(second);
+        assert_eq!(registry.sessions_from_ip(ip), 1);
+        let _unlimited = registry.try_reserve_ip(ip, 0).unwrap();
+        assert_eq!(registry.sessions_from_ip(ip), 2);
+    }
+}
@@ -0,0 +1,809 @@
+//! Raw TS streaming over HTTP, backed by the tuner pool.
+//!
+//! Used by the playlist and Mirakurun-compatible endpoints: a tuner is opened
+//! (or joined if already running) and its TS is sent as a chunked
+//! `video/MP2T` response until the client disconnects. `GET /api/stream`
+//! does the same for ad-hoc testing from a browser, VLC or ffmpeg, and can
+//! return the service's captions as a WebVTT or JSON track instead.
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use bytes::Bytes;
+use futures::stream;
+use log::{info, warn};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::database::{BonDriverRecord, ClientChannelRecord};
+use crate::ts_analyzer::caption::CaptionExtractor;
+use crate::ts_analyzer::service_filter::TsServiceFilter;
+use crate::tuner::b25_pipe::{stream_decoder_options, B25Pipe};
+use crate::tuner::shared::ReaderStartupConfig;
+use crate::tuner::ts_align::TsPacketAligner;
+use crate::tuner::{ChannelKey, SharedTuner, TunerPool};
+use crate::web::state::WebState;
+
+/// A BonDriver space/channel that can receive the requested stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StreamCandidate {
+    pub dll_path: String,
+    pub space: u32,
+    pub channel: u32,
+    /// The driver's `max_instances`; a new reader is refused beyond it.
+    pub max_instances: i32,
+}
+
+/// Open the first candidate that works and stream its TS.
+///
+/// With `service_id` set, the output is filtered down to that service.
+pub(crate) async fn stream_first_available(
+    tuner_pool: &Arc<TunerPool>,
+    candidates: &[StreamCandidate],
+    service_id: Option<u16>,
+    label: &str,
+) -> Response {
+    if candidates.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "success": false, "error": "Channel not found" })),
+        )
+            .into_response();
+    }
+
+    let Some(tuner) = open_first_available(tuner_pool, candidates, label).await else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "success": false, "error": "No tuner available" })),
+        )
+            .into_response();
+    };
+    stream_tuner(tuner_pool, tuner, service_id, false, "video/MP2T").await
+}
+
+/// Subscribe to an open tuner and stream its TS as the response body.
+///
+/// With `b25` the TS goes through an extra B25 pass on a worker thread
+/// (see [`spawn_stream_worker`]).
+async fn stream_tuner(
+    tuner_pool: &Arc<TunerPool>,
+    tuner: Arc<SharedTuner>,
+    service_id: Option<u16>,
+    b25: bool,
+    content_type: &'static str,
+) -> Response {
+    // Cancel a pending keep-alive close before subscribing
+    tuner_pool.cancel_idle_close(&tuner.key).await;
+    if !b25 {
+        let subscription = TunerSubscription::new(Arc::clone(tuner_pool), tuner, service_id);
+        return ([(CONTENT_TYPE, content_type)], Body::from_stream(ts_stream(subscription))).into_response();
+    }
+
+    // The filter runs after the decoder so the ECM is still there
+    let subscription = TunerSubscription::new(Arc::clone(tuner_pool), tuner, None);
+    let mut filter = service_filter(service_id);
+    let stage = move |data| apply_service_filter(&mut filter, data);
+    match spawn_stream_worker(subscription, true, None, stage).await {
+        Ok(body) => ([(CONTENT_TYPE, content_type)], Body::from_stream(body)).into_response(),
+        Err(e) => stream_worker_failed(e),
+    }
+}
+
+/// Response for a stream worker (or its B25 decoder) that could not start.
+fn stream_worker_failed(e: std::io::Error) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "success": false, "error": format!("Failed to start stream: {}", e) })),
+    )
+        .into_response()
+}
+
+/// Open the first candidate that works, in order.
+///
+/// Candidates whose driver is full or being scanned are skipped without
+/// touching the driver.
+pub(crate) async fn open_first_available(
+    tuner_pool: &Arc<TunerPool>,
+    candidates: &[StreamCandidate],
+    label: &str,
+) -> Option<Arc<SharedTuner>> {
+    for candidate in candidates {
+        match open_tuner(tuner_pool, candidate).await {
+            Ok(tuner) => {
+                info!(
+                    "[Stream] Opened {} for {} (space {}, ch {})",
+                    candidate.dll_path, label, candidate.space, candidate.channel
+                );
+                return Some(tuner);
+            }
+            Err(OpenError::Unavailable(reason)) => {
+                info!("[Stream] Skipping {} for {}: {}", candidate.dll_path, label, reason);
+            }
+            Err(OpenError::Failed(e)) => {
+                warn!("[Stream] Failed to open {} for {}: {}", candidate.dll_path, label, e);
+            }
+        }
+    }
+    None
+}
+
+/// Why [`open_tuner`] returned no tuner.
+#[derive(Debug)]
+enum OpenError {
+    /// The driver has no instance to spare or a scan has it open.
+    Unavailable(String),
+    /// Opening or tuning the driver failed.
+    Failed(String),
+}
+
+/// Get a running tuner for the candidate, starting its reader if needed.
+async fn open_tuner(
+    tuner_pool: &Arc<TunerPool>,
+    candidate: &StreamCandidate,
+) -> Result<Arc<SharedTuner>, OpenError> {
+    let key = ChannelKey::space_channel(&candidate.dll_path, candidate.space, candidate.channel);
+    // Joining a running reader takes no extra instance
+    if let Some(tuner) = tuner_pool.get(&key).await.filter(|t| t.is_running()) {
+        return Ok(tuner);
+    }
+
+    let config = tuner_pool.config().await;
+    let startup_config = ReaderStartupConfig::from(&config);
+    let reserved = tuner_pool
+        .reserve_reader(&candidate.dll_path, candidate.max_instances)
+        .await;
+    // Another request may have started it while we waited for the lock
+    if let Some(tuner) = tuner_pool.get(&key).await.filter(|t| t.is_running()) {
+        return Ok(tuner);
+    }
+    let _dll_guard = reserved.map_err(OpenError::Unavailable)?;
+    let tuner = tuner_pool
+        .get_or_create(key.clone(), 2, || async { Ok(()) })
+        .await
+        .map_err(|e| OpenError::Failed(e.to_string()))?;
+    if let Some(mut warm) = tuner_pool.take_startup_warm(&candidate.dll_path).await {
+        match warm
+            .activate(
+                Arc::clone(&tuner),
+                candidate.dll_path.clone(),
+                candidate.space,
+                candidate.channel,
+                startup_config,
+            )
+            .await
+        {
+            Ok(()) => return Ok(tuner),
+            Err(e) => {
+                warn!("Startup warm tuner activation failed for {}: {}", candidate.dll_path, e);
+                warm.shutdown().await;
+            }
+        }
+    }
+    if let Err(e) = tuner
+        .start_bondriver_reader(
+            candidate.dll_path.clone(),
+            candidate.space,
+            candidate.channel,
+            startup_config,
+        )
+        .await
+    {
+        if !tuner.is_running() && !tuner.has_subscribers() {
+            tuner_pool.remove(&key).await;
+        }
+        return Err(OpenError::Failed(e.to_string()));
+    }
+    Ok(tuner)
+}
+
+/// A subscription to a shared tuner that is released when dropped.
+///
+/// The response body owns this, so the subscriber count drops (and the
+/// keep-alive close is scheduled) as soon as the HTTP client goes away.
+struct TunerSubscription {
+    tuner_pool: Arc<TunerPool>,
+    tuner: Arc<SharedTuner>,
+    rx: broadcast::Receiver<Bytes>,
+    /// Single-service filter; it needs packet-aligned input.
+    filter: Option<ServiceFilter>,
+}
+
+/// Single-service filter with the aligner that feeds it whole packets.
+type ServiceFilter = (TsPacketAligner, TsServiceFilter);
+
+fn service_filter(service_id: Option<u16>) -> Option<ServiceFilter> {
+    service_id.map(|sid| (TsPacketAligner::new(), TsServiceFilter::new(sid)))
+}
+
+/// Apply the service filter, if any. Returns None when nothing is left.
+fn apply_service_filter(filter: &mut Option<ServiceFilter>, data: Bytes) -> Option<Bytes> {
+    let Some((aligner, filter)) = filter else {
+        return Some(data);
+    };
+    let aligned = aligner.push(data)?;
+    let filtered = filter.filter(&aligned);
+    (!filtered.is_empty()).then(|| Bytes::from(filtered))
+}
+
+impl TunerSubscription {
+    fn new(tuner_pool: Arc<TunerPool>, tuner: Arc<SharedTuner>, service_id: Option<u16>) -> Self {
+        let rx = tuner.subscribe();
+        Self {
+            tuner_pool,
+            tuner,
+            rx,
+            filter: service_filter(service_id),
+        }
+    }
+}
+
+impl Drop for TunerSubscription {
+    fn drop(&mut self) {
+        self.tuner.unsubscribe();
+        if self.tuner.subscriber_count() == 0 {
+            let tuner_pool = Arc::clone(&self.tuner_pool);
+            let tuner = Arc::clone(&self.tuner);
+            tokio::spawn(async move {
+                tuner_pool.schedule_idle_close(tuner.key.clone(), tuner).await;
+            });
+        }
+        info!("[Stream] Stream closed for {:?}", self.tuner.key);
+    }
+}
+
+/// Turn a subscription into a body stream of TS chunks.
+fn ts_stream(
+    subscription: TunerSubscription,
+) -> impl futures::Stream<Item = Result<Bytes, std::io::Error>> {
+    stream::unfold(subscription, |mut sub| async move {
+        loop {
+            match sub.rx.recv().await {
+                Ok(data) => {
+                    if let Some(data) = apply_service_filter(&mut sub.filter, data) {
+                        return Some((Ok(data), sub));
+                    }
+                }
+                Err(RecvError::Lagged(n)) => {
+                    warn!("[Stream] Stream for {:?} lagged, {} chunks dropped", sub.tuner.key, n);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+/// Chunks queued between the subscription, the worker thread and the body.
+const STREAM_WORKER_QUEUE: usize = 64;
+
+/// Process a subscription on a dedicated thread.
+///
+/// With `b25` each chunk first goes through a [`B25Pipe`]; it is not
+/// `Send`, so it is created on the worker thread and never leaves it. Each
+/// chunk then goes through `stage`, and whatever it returns is sent to the
+/// body over a channel, after `header` if one is given. The subscription
+/// itself stays on an async task that forwards chunks to the worker and
+/// ends when the body is dropped.
+async fn spawn_stream_worker<F>(
+    mut subscription: TunerSubscription,
+    b25: bool,
+    header: Option<Bytes>,
+    mut stage: F,
+) -> std::io::Result<ReceiverStream<Result<Bytes, std::io::Error>>>
+where
+    F: FnMut(Bytes) -> Option<Bytes> + Send + 'static,
+{
+    let (in_tx, mut in_rx) = mpsc::channel::<Bytes>(STREAM_WORKER_QUEUE);
+    let (out_tx, out_rx) = mpsc::channel(STREAM_WORKER_QUEUE);
+    let (ready_tx, ready_rx) = oneshot::channel();
+    let key = subscription.tuner.key.clone();
+    let worker_tx = out_tx.clone();
+    std::thread::Builder::new()
+        .name("stream-worker".to_string())
+        .spawn(move || {
+            let mut decoder = match b25.then(|| B25Pipe::new(stream_decoder_options())).transpose() {
+                Ok(decoder) => decoder,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready_tx.send(Ok(()));
+            if let Some(header) = header {
+                if worker_tx.blocking_send(Ok(header)).is_err() {
+                    return;
+                }
+            }
+            while let Some(data) = in_rx.blocking_recv() {
+                let data = match &mut decoder {
+                    Some(decoder) => match decoder.push(&data) {
+                        Ok(decoded) if decoded.is_empty() => continue,
+                        Ok(decoded) => Bytes::from(decoded),
+                        Err(e) => {
+                            // Keep streaming; the data is passed through undecoded
+                            warn!("[Stream] B25 decode error for {:?}: {}", key, e);
+                            data
+                        }
+                    },
+                    None => data,
+                };
+                let Some(data) = stage(data) else { continue };
+                if worker_tx.blocking_send(Ok(data)).is_err() {
+                    break;
+                }
+            }
+        })?;
+    ready_rx
+        .await
+        .unwrap_or_else(|_| Err(std::io::Error::other("stream worker exited")))?;
+
+    tokio::spawn(async move {
+        loop {
+            let data = tokio::select! {
+                received = subscription.rx.recv() => received,
+                _ = out_tx.closed() => break,
+            };
+            match data {
+                Ok(data) => {
+                    if in_tx.send(data).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(n)) => {
+                    warn!("[Stream] Stream for {:?} lagged, {} chunks dropped", subscription.tuner.key, n);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+    Ok(ReceiverStream::new(out_rx))
+}
+
+/// Subscribe to an open tuner and stream the captions of one service.
+///
+/// Decoding and caption extraction run on a worker thread (see
+/// [`spawn_stream_worker`]); the body only carries the finished text.
+async fn stream_captions(
+    tuner_pool: &Arc<TunerPool>,
+    tuner: Arc<SharedTuner>,
+    service_id: Option<u16>,
+    b25: bool,
+    format: CaptionFormat,
+) -> Response {
+    tuner_pool.cancel_idle_close(&tuner.key).await;
+    // The extractor follows the service itself, so no service filter
+    let subscription = TunerSubscription::new(Arc::clone(tuner_pool), tuner, None);
+    let header = match format {
+        CaptionFormat::Vtt => Some(Bytes::from_static(b"WEBVTT\n\n")),
+        CaptionFormat::Json => None,
+    };
+    let mut aligner = TsPacketAligner::new();
+    let mut extractor = CaptionExtractor::new(service_id);
+    let stage = move |data| {
+        let aligned = aligner.push(data)?;
+        let text: String = extractor
+            .push(&aligned)
+            .iter()
+            .map(|caption| match format {
+                CaptionFormat::Vtt => caption.to_vtt(),
+                CaptionFormat::Json => format!("{}\n", json!(caption)),
+            })
+            .collect();
+        (!text.is_empty()).then(|| Bytes::from(text))
+    };
+    match spawn_stream_worker(subscription, b25, header, stage).await {
+        Ok(body) => ([(CONTENT_TYPE, format.content_type())], Body::from_stream(body)).into_response(),
+        Err(e) => stream_worker_failed(e),
+    }
+}
+
+/// Candidates for one service, highest driver quality score first.
+///
+/// `tsid` narrows the match down to one TS when the service is carried on
+/// several.
+pub(crate) fn service_candidates(
+    rows: &[(ClientChannelRecord, Option<BonDriverRecord>)],
+    quality_scores: &HashMap<String, f64>,
+    nid: u16,
+    tsid: Option<u16>,
+    sid: u16,
+) -> Vec<StreamCandidate> {
+    let mut scored: Vec<(f64, StreamCandidate)> = Vec::new();
+    for (ch, driver) in rows {
+        let Some(driver) = driver else { continue };
+        if !ch.is_enabled || ch.nid as u16 != nid || ch.sid as u16 != sid {
+            continue;
+        }
+        if tsid.is_some_and(|tsid| ch.tsid as u16 != tsid) {
+            continue;
+        }
+        let candidate = StreamCandidate {
+            dll_path: driver.dll_path.clone(),
+            space: ch.space,
+            channel: ch.channel,
+            max_instances: driver.max_instances,
+        };
+        if scored.iter().any(|(_, c)| *c == candidate) {
+            continue;
+        }
+        let score = quality_scores.get(&driver.dll_path).copied().unwrap_or(1.0);
+        scored.push((score, candidate));
+    }
+    // Stable sort keeps the channel priority order among equal scores
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().map(|(_, c)| c).collect()
+}
+
+/// Look up the candidates for one service in the channel database.
+pub(crate) async fn find_service_candidates(
+    web_state: &WebState,
+    nid: u16,
+    tsid: Option<u16>,
+    sid: u16,
+) -> crate::database::Result<Vec<StreamCandidate>> {
+    let db = web_state.database.lock().await;
+    let rows = db.get_all_channels_with_drivers()?;
+    let quality_scores: HashMap<String, f64> = db
+        .get_bondrivers_ranking()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(driver, score, _, _, _)| (driver.dll_path, score))
+        .collect();
+    Ok(service_candidates(&rows, &quality_scores, nid, tsid, sid))
+}
+
+/// `GET /api/stream/:nid/:sid` — stream a single service as raw TS.
+pub async fn get_service_stream(
+    State(web_state): State<Arc<WebState>>,
+    Path((nid, sid)): Path<(u16, u16)>,
+) -> Response {
+    let candidates = match find_service_candidates(&web_state, nid, None, sid).await {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "success": false, "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+
+    let label = format!("NID={} SID={}", nid, sid);
+    stream_first_available(&web_state.tuner_pool, &candidates, Some(sid), &label).await
+}
+
+/// Query for `GET /api/stream`.
+///
+/// Either `nid` + `sid` (with an optional `tsid`) or `driver` + `space` +
+/// `channel` selects what to tune.
+#[derive(Debug, Default, Deserialize)]
+pub struct RawStreamQuery {
+    pub nid: Option<u16>,
+    pub tsid: Option<u16>,
+    pub sid: Option<u16>,
+    /// Registered BonDriver DLL path or display name.
+    pub driver: Option<String>,
+    pub space: Option<u32>,
+    pub channel: Option<u32>,
+    /// `b25` runs the TS through an extra B25 decoder pass.
+    pub decode: Option<String>,
+    /// `vtt` or `json` returns the service's captions instead of the TS.
+    pub captions: Option<String>,
+}
+
+/// Caption track format for `GET /api/stream?captions=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaptionFormat {
+    /// WebVTT cues.
+    Vtt,
+    /// One JSON object per line: `{"start_ms", "end_ms", "text"}`.
+    Json,
+}
+
+impl CaptionFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            CaptionFormat::Vtt => "text/vtt; charset=utf-8",
+            CaptionFormat::Json => "application/x-ndjson",
+        }
+    }
+}
+
+/// How a raw stream request picks its tuner.
+#[derive(Debug, PartialEq, Eq)]
+enum RawStreamTarget {
+    Service { nid: u16, tsid: Option<u16>, sid: u16 },
+    Channel { driver: String, space: u32, channel: u32 },
+}
+
+impl RawStreamQuery {
+    fn target(&self) -> Result<RawStreamTarget, &'static str> {
+        match (self.nid, self.sid, &self.driver, self.space, self.channel) {
+            (Some(nid), Some(sid), None, None, None) => Ok(RawStreamTarget::Service { nid, tsid: self.tsid, sid }),
+            (None, None, Some(driver), Some(space), Some(channel)) if self.tsid.is_none() => {
+                Ok(RawStreamTarget::Channel { driver: driver.clone(), space, channel })
+            }
+            _ => Err("Specify either nid and sid (and optionally tsid), or driver, space and channel"),
+        }
+    }
+
+    fn wants_b25(&self) -> Result<bool, &'static str> {
+        match self.decode.as_deref() {
+            None | Some("") => Ok(false),
+            Some(mode) if mode.eq_ignore_ascii_case("b25") => Ok(true),
+            Some(_) => Err("decode must be b25"),
+        }
+    }
+
+    fn caption_format(&self) -> Result<Option<CaptionFormat>, &'static str> {
+        match self.captions.as_deref() {
+            None | Some("") => Ok(None),
+            Some(mode) if mode.eq_ignore_ascii_case("vtt") => Ok(Some(CaptionFormat::Vtt)),
+            Some(mode) if mode.eq_ignore_ascii_case("json") => Ok(Some(CaptionFormat::Json)),
+            Some(_) => Err("captions must be vtt or json"),
+        }
+    }
+}
+
+/// `GET /api/stream` — stream a service or a whole physical channel as
+/// `application/octet-stream`, for testing from a browser or piping into
+/// ffmpeg.
+///
+/// A service is filtered down to its own packets; a driver/space/channel
+/// request gets the full TS. With `captions` the response is the caption
+/// track of the service (the first one in the PAT for a whole channel).
+pub async fn get_raw_stream(
+    State(web_state): State<Arc<WebState>>,
+    Query(query): Query<RawStreamQuery>,
+) -> Response {
+    let bad_request = |error: &str| {
+        (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error }))).into_response()
+    };
+    let target = match query.target() {
+        Ok(target) => target,
+        Err(e) => return bad_request(e),
+    };
+    let captions = match query.caption_format() {
+        Ok(captions) => captions,
+        Err(e) => return bad_request(e),
+    };
+    let b25 = match query.wants_b25() {
+        Ok(b25) => b25,
+        Err(e) => return bad_request(e),
+    };
+
+    let lookup = match &target {
+        RawStreamTarget::Service { nid, tsid, sid } => find_service_candidates(&web_state, *nid, *tsid, *sid)
+            .await
+            .map(|candidates| (candidates, Some(*sid), format!("NID={} SID={}", nid, sid))),
+        RawStreamTarget::Channel { driver, space, channel } => {
+            let db = web_state.database.lock().await;
+            db.get_bon_driver_by_path(driver)
+                .and_then(|found| match found {
+                    Some(found) => Ok(Some(found)),
+                    None => db.get_bon_driver_by_display_name(driver),
+                })
+                .map(|found| {
+                    let candidates: Vec<StreamCandidate> = found
+                        .into_iter()
+                        .map(|d| StreamCandidate {
+                            dll_path: d.dll_path,
+                            space: *space,
+                            channel: *channel,
+                            max_instances: d.max_instances,
+                        })
+                        .collect();
+                    (candidates, None, format!("{} space {} ch {}", driver, space, channel))
+                })
+        }
+    };
+    let (candidates, service_id, label) = match lookup {
+        Ok(found) => found,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "success": false, "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+    if candidates.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "success": false, "error": "Channel not found" })),
+        )
+            .into_response();
+    }
+
+    let tuner_pool = &web_state.tuner_pool;
+    let Some(tuner) = open_first_available(tuner_pool, &candidates, &label).await else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "success": false, "error": "No tuner available" })),
+        )
+            .into_response();
+    };
+    match captions {
+        Some(format) => stream_captions(tuner_pool, tuner, service_id, b25, format).await,
+        None => stream_tuner(tuner_pool, tuner, service_id, b25, "application/octet-stream").await,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Channel row fixture shared with the playlist and Mirakurun tests.
+    pub(crate) fn row(
+        driver: Option<&str>,
+        nid: i32,
+        tsid: i32,
+        sid: i32,
+        name: &str,
+        is_enabled: bool,
+    ) -> (ClientChannelRecord, Option<BonDriverRecord>) {
+        let channel = ClientChannelRecord {
+            id: 0,
+            bon_driver_id: 1,
+            nid,
+            sid,
+            tsid,
+            service_name: Some(name.to_string()),
+            name_is_synthetic: false,
+            ts_name: Some(format!("TS {}", tsid)),
+            service_type: Some(1),
+            remote_control_key: Some(1),
+            space: 0,
+            channel: 13,
+            is_enabled,
+            priority: 0,
+            display_order: None,
+        };
+        let driver = driver.map(|path| BonDriverRecord {
+            id: 1,
+            dll_path: path.to_string(),
+            driver_name: None,
+            version: None,
+            group_name: None,
+            auto_scan_enabled: false,
+            scan_interval_hours: 0,
+            scan_priority: 0,
+            last_scan: None,
+            next_scan_at: None,
+            passive_scan_enabled: false,
+            max_instances: 1,
+            created_at: 0,
+            updated_at: 0,
+        });
+        (channel, driver)
+    }
+
+    #[test]
+    fn test_service_candidates_prefer_quality_score() {
+        let rows = vec![
+            row(Some("BonDriver_T1.dll"), 0x7FE0, 0x7FE0, 1024, "NHK G", true),
+            row(Some("BonDriver_T2.dll"), 0x7FE0, 0x7FE0, 1024, "NHK G", true),
+            row(Some("BonDriver_T3.dll"), 0x7FE0, 0x7FE0, 1024, "NHK G", true),
+            row(Some("BonDriver_T4.dll"), 0x7FE0, 0x7FE0, 1024, "NHK G", false),
+            row(Some("BonDriver_T1.dll"), 0x7FE0, 0x7FE0, 1025, "NHK G2", true),
+        ];
+        let scores: HashMap<String, f64> = [
+            ("BonDriver_T1.dll".to_string(), 0.5),
+            ("BonDriver_T2.dll".to_string(), 0.9),
+        ]
+        .into_iter()
+        .collect();
+
+        let candidates = service_candidates(&rows, &scores, 0x7FE0, None, 1024);
+        let paths: Vec<&str> = candidates.iter().map(|c| c.dll_path.as_str()).collect();
+        // T3 has no stats yet and gets the default score of 1.0
+        assert_eq!(paths, ["BonDriver_T3.dll", "BonDriver_T2.dll", "BonDriver_T1.dll"]);
+
+        assert!(service_candidates(&rows, &scores, 0x7FE0, None, 9999).is_empty());
+        assert_eq!(service_candidates(&rows, &scores, 0x7FE0, Some(0x7FE0), 1024).len(), 3);
+        assert!(service_candidates(&rows, &scores, 0x7FE0, Some(0x7FE1), 1024).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_refused_beyond_max_instances() {
+        let tuner_pool = Arc::new(TunerPool::new(10));
+        // The single-instance driver already runs a reader on another channel
+        let busy = tuner_pool
+            .get_or_create(ChannelKey::space_channel("BonDriver_T1.dll", 0, 14), 2, || async { Ok(()) })
+            .await
+            .unwrap();
+        busy.set_running_for_test();
+
+        let full = StreamCandidate {
+            dll_path: "BonDriver_T1.dll".to_string(),
+            space: 0,
+            channel: 13,
+            max_instances: 1,
+        };
+        let response = stream_first_available(&tuner_pool, &[full], None, "test").await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let refused = ChannelKey::space_channel("BonDriver_T1.dll", 0, 13);
+        assert!(tuner_pool.get(&refused).await.is_none());
+
+        // A driver being scanned is refused even with instances to spare
+        let _scan = tuner_pool.begin_scan("BonDriver_T2.dll");
+        let scanning = StreamCandidate {
+            dll_path: "BonDriver_T2.dll".to_string(),
+            space: 0,
+            channel: 13,
+            max_instances: 2,
+        };
+        let response = stream_first_available(&tuner_pool, &[scanning], None, "test").await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_open_skips_full_candidates() {
+        let tuner_pool = Arc::new(TunerPool::new(10));
+        let busy = tuner_pool
+            .get_or_create(ChannelKey::space_channel("BonDriver_T1.dll", 0, 14), 2, || async { Ok(()) })
+            .await
+            .unwrap();
+        busy.set_running_for_test();
+        let shared = tuner_pool
+            .get_or_create(ChannelKey::space_channel("BonDriver_T2.dll", 0, 13), 2, || async { Ok(()) })
+            .await
+            .unwrap();
+        shared.set_running_for_test();
+
+        let candidate = |dll_path: &str| StreamCandidate {
+            dll_path: dll_path.to_string(),
+            space: 0,
+            channel: 13,
+            max_instances: 1,
+        };
+        // T1 is full, so the stream joins the reader already running on T2
+        let candidates = [candidate("BonDriver_T1.dll"), candidate("BonDriver_T2.dll")];
+        let tuner = open_first_available(&tuner_pool, &candidates, "test").await.unwrap();
+        assert!(Arc::ptr_eq(&tuner, &shared));
+        let skipped = ChannelKey::space_channel("BonDriver_T1.dll", 0, 13);
+        assert!(tuner_pool.get(&skipped).await.is_none());
+    }
+
+    #[test]
+    fn test_raw_stream_query_target() {
+        let service = RawStreamQuery { nid: Some(1), sid: Some(1024), ..Default::default() };
+        assert_eq!(service.target(), Ok(RawStreamTarget::Service { nid: 1, tsid: None, sid: 1024 }));
+        assert_eq!(service.wants_b25(), Ok(false));
+
+        let channel = RawStreamQuery {
+            driver: Some("BonDriver_T1.dll".to_string()),
+            space: Some(0),
+            channel: Some(13),
+            decode: Some("B25".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            channel.target(),
+            Ok(RawStreamTarget::Channel { driver: "BonDriver_T1.dll".to_string(), space: 0, channel: 13 })
+        );
+        assert_eq!(channel.wants_b25(), Ok(true));
+
+        // Mixing both forms or leaving one incomplete is rejected
+        let mixed = RawStreamQuery { nid: Some(1), sid: Some(1024), space: Some(0), ..Default::default() };
+        assert!(mixed.target().is_err());
+        let partial = RawStreamQuery { driver: Some("BonDriver_T1.dll".to_string()), ..Default::default() };
+        assert!(partial.target().is_err());
+        let unknown = RawStreamQuery { decode: Some("arib".to_string()), ..Default::default() };
+        assert!(unknown.wants_b25().is_err());
+
+        assert_eq!(service.caption_format(), Ok(None));
+        let vtt = RawStreamQuery { captions: Some("VTT".to_string()), ..Default::default() };
+        assert_eq!(vtt.caption_format(), Ok(Some(CaptionFormat::Vtt)));
+        let srt = RawStreamQuery { captions: Some("srt".to_string()), ..Default::default() };
+        assert!(srt.caption_format().is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! WebSocket feed of live session events.
+//!
+//! Streams `SessionRegistry` changes to the dashboard as JSON text frames so
+//! it does not have to poll `/api/clients` and the metrics history endpoint.
+//! Channel scan progress for a single BonDriver is also offered as
+//! server-sent events for clients that only care about one scan.
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use futures::stream::{self, Stream};
+use serde_json::json;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::web::state::{SessionEvent, WebState};
+
+/// Upgrade to a WebSocket that streams session events.
+pub async fn session_events(
+    ws: WebSocketUpgrade,
+    State(web_state): State<Arc<WebState>>,
+) -> impl IntoResponse {
+    // Subscribe before the upgrade so no event is missed between the
+    // handshake and the first recv.
+    let events = web_state.session_registry.subscribe();
+    ws.on_upgrade(move |socket| stream_events(socket, events))
+}
+
+/// Forward events until either side goes away.
+///
+/// The broadcast receiver is owned by this future and is dropped when it
+/// returns, so a disconnected dashboard stops holding a channel slot.
+async fn stream_events(mut socket: WebSocket, mut events: broadcast::Receiver<SessionEvent>) {
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let text = match event {
+                    Ok(event) => match serde_json::to_string(&event) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            log::warn!("Failed to serialize session event: {}", e);
+                            continue;
+                        }
+                    },
+                    // Tell the client to resync from the REST endpoints
+                    Err(RecvError::Lagged(skipped)) => {
+                        json!({ "type": "lagged", "skipped": skipped }).to_string()
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum; anything else from the client is ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    log::debug!("Session event WebSocket closed");
+}
+
+/// Server-sent events for the channel scan of one BonDriver.
+///
+/// Emits a `progress` event per probed physical channel and a `finished`
+/// event when the scan ends; the stream stays open for later scans.
+pub async fn scan_progress(
+    State(web_state): State<Arc<WebState>>,
+    Path(id): Path<i64>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = web_state.session_registry.subscribe();
+    let stream = stream::unfold(events, move |mut events| async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) if event.scan_bondriver_id() == Some(id) => event,
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    let event = Event::default()
+                        .event("lagged")
+                        .data(json!({ "skipped": skipped }).to_string());
+                    return Some((Ok(event), events));
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            let name = match &event {
+                SessionEvent::ScanFinished { .. } => "finished",
+                _ => "progress",
+            };
+            match Event::default().event(name).json_data(&event) {
+                Ok(event) => return Some((Ok(event), events)),
+                Err(e) => log::warn!("Failed to serialize scan event: {}", e),
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}